@@ -51,6 +51,19 @@ pub struct TypedArena<T> {
     _own: PhantomData<T>,
 }
 
+/// How much memory an arena has allocated, and how much of it is in use.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArenaStats {
+    /// Number of chunks allocated by the arena.
+    pub chunks: usize,
+    /// Total capacity of those chunks, in bytes.
+    pub allocated_bytes: usize,
+    /// Bytes handed out by the arena, including alignment padding.
+    pub used_bytes: usize,
+    /// Number of objects allocated in the arena, when the arena can tell.
+    pub objects: Option<usize>,
+}
+
 struct TypedArenaChunk<T> {
     /// The raw storage for the arena chunk.
     storage: RawVec<T>,
@@ -124,6 +137,32 @@ impl<T> TypedArena<T> {
 
         self.chunks.borrow().iter().any(|chunk| chunk.start() <= ptr && ptr < chunk.end())
     }
+
+    /// Returns how much memory this arena has allocated so far.
+    pub fn stats(&self) -> ArenaStats {
+        let chunks = self.chunks.borrow();
+        let objects = match chunks.split_last() {
+            Some((last_chunk, full_chunks)) => {
+                let last_entries = if mem::size_of::<T>() == 0 {
+                    // `ptr` is incremented by one byte for each zero-sized value.
+                    self.ptr.get() as usize - last_chunk.start() as usize
+                } else {
+                    (self.ptr.get() as usize - last_chunk.start() as usize) / mem::size_of::<T>()
+                };
+                full_chunks.iter().map(|chunk| chunk.entries).sum::<usize>() + last_entries
+            }
+            None => 0,
+        };
+        ArenaStats {
+            chunks: chunks.len(),
+            allocated_bytes: chunks.iter()
+                .map(|chunk| chunk.storage.capacity() * mem::size_of::<T>())
+                .sum(),
+            used_bytes: objects * mem::size_of::<T>(),
+            objects: Some(objects),
+        }
+    }
+
     /// Allocates an object in the `TypedArena`, returning a reference to it.
     #[inline]
     pub fn alloc(&self, object: T) -> &mut T {
@@ -345,6 +384,26 @@ impl DroplessArena {
         self.chunks.borrow().iter().any(|chunk| chunk.start() <= ptr && ptr < chunk.end())
     }
 
+    /// Returns how much memory this arena has allocated so far. The arena does not keep track
+    /// of individual allocations, so `objects` is always `None`.
+    pub fn stats(&self) -> ArenaStats {
+        let chunks = self.chunks.borrow();
+        // Full chunks record the number of bytes used in `entries` when the arena grows.
+        let used_bytes = match chunks.split_last() {
+            Some((last_chunk, full_chunks)) => {
+                full_chunks.iter().map(|chunk| chunk.entries).sum::<usize>() +
+                    (self.ptr.get() as usize - last_chunk.start() as usize)
+            }
+            None => 0,
+        };
+        ArenaStats {
+            chunks: chunks.len(),
+            allocated_bytes: chunks.iter().map(|chunk| chunk.storage.capacity()).sum(),
+            used_bytes,
+            objects: None,
+        }
+    }
+
     #[inline]
     fn align(&self, align: usize) {
         let final_address = ((self.ptr.get() as usize) + align - 1) & !(align - 1);
@@ -360,6 +419,7 @@ impl DroplessArena {
             let (chunk, mut new_capacity);
             if let Some(last_chunk) = chunks.last_mut() {
                 let used_bytes = self.ptr.get() as usize - last_chunk.start() as usize;
+                last_chunk.entries = used_bytes;
                 if last_chunk
                     .storage
                     .reserve_in_place(used_bytes, needed_bytes)
@@ -1,7 +1,8 @@
 extern crate test;
 use test::Bencher;
-use super::TypedArena;
+use super::{ArenaStats, DroplessArena, TypedArena};
 use std::cell::Cell;
+use std::mem;
 
 #[allow(dead_code)]
 #[derive(Debug, Eq, PartialEq)]
@@ -68,6 +69,34 @@ pub fn test_copy() {
     }
 }
 
+#[test]
+pub fn test_stats() {
+    let arena = TypedArena::default();
+    assert_eq!(arena.stats(), ArenaStats { objects: Some(0), ..ArenaStats::default() });
+    for _ in 0..100000 {
+        arena.alloc(Point { x: 1, y: 2, z: 3 });
+    }
+    let stats = arena.stats();
+    assert_eq!(stats.objects, Some(100000));
+    assert_eq!(stats.used_bytes, 100000 * mem::size_of::<Point>());
+    assert!(stats.chunks > 0);
+    assert!(stats.allocated_bytes >= stats.used_bytes);
+}
+
+#[test]
+pub fn test_dropless_stats() {
+    let arena = DroplessArena::default();
+    assert_eq!(arena.stats(), ArenaStats::default());
+    for _ in 0..100000 {
+        arena.alloc(1u8);
+    }
+    let stats = arena.stats();
+    assert_eq!(stats.objects, None);
+    assert_eq!(stats.used_bytes, 100000);
+    assert!(stats.chunks > 0);
+    assert!(stats.allocated_bytes >= stats.used_bytes);
+}
+
 #[bench]
 pub fn bench_copy(b: &mut Bencher) {
     let arena = TypedArena::default();
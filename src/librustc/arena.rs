@@ -1,4 +1,5 @@
 use arena::{TypedArena, DroplessArena};
+pub use arena::ArenaStats;
 use std::mem;
 use std::ptr;
 use std::slice;
@@ -176,10 +177,33 @@ macro_rules! impl_arena_allocatable {
     }
 }
 
+macro_rules! impl_arena_stats {
+    ([], [$($a:tt $name:ident: $ty:ty,)*], $tcx:lifetime) => {
+        impl<$tcx> Arena<$tcx> {
+            /// Returns the memory usage of each dedicated arena, followed by the shared arenas
+            /// used for types without destructors and for types with few allocations.
+            pub fn stats(&self) -> Vec<(&'static str, ArenaStats)> {
+                let mut stats = vec![];
+                $(
+                    let arena: Option<&TypedArena<$ty>> = which_arena_for_type!($a[&self.$name]);
+                    if let Some(arena) = arena {
+                        stats.push((stringify!($name), arena.stats()));
+                    }
+                )*
+                stats.push(("(dropless)", self.dropless.stats()));
+                stats.push(("(drop)", self.drop.stats()));
+                stats
+            }
+        }
+    }
+}
+
 arena_types!(declare_arena, [], 'tcx);
 
 arena_types!(impl_arena_allocatable, [], 'tcx);
 
+arena_types!(impl_arena_stats, [], 'tcx);
+
 pub trait ArenaAllocatable {}
 
 impl<T: Copy> ArenaAllocatable for T {}
@@ -267,6 +291,13 @@ struct DropArena {
 }
 
 impl DropArena {
+    fn stats(&self) -> ArenaStats {
+        ArenaStats {
+            objects: Some(self.destructors.borrow().len()),
+            ..self.arena.stats()
+        }
+    }
+
     #[inline]
     unsafe fn alloc<T>(&self, object: T) -> &mut T {
         let mem = self.arena.alloc_raw(
@@ -351,6 +351,48 @@ declare_lint! {
     "detects code samples in docs of private items not documented by rustdoc"
 }
 
+declare_lint! {
+    pub HIDDEN_TYPES_IN_SIGNATURES,
+    Warn,
+    "detects documented signatures which refer to private or `#[doc(hidden)]` types and traits"
+}
+
+declare_lint! {
+    pub BARE_URLS,
+    Allow,
+    "detects URLs in documentation which are not links"
+}
+
+declare_lint! {
+    pub UNLINKED_PATHS,
+    Allow,
+    "detects item paths in documentation which are neither links nor code"
+}
+
+declare_lint! {
+    pub INVALID_RUST_CODEBLOCKS,
+    Warn,
+    "detects Rust code blocks in documentation which are empty or do not parse"
+}
+
+declare_lint! {
+    pub INCONSISTENT_DOC_CFG,
+    Warn,
+    "detects `doc(cfg)` annotations which contradict `#[cfg]` or repeat what is already shown"
+}
+
+declare_lint! {
+    pub REDUNDANT_EXPLICIT_LINKS,
+    Warn,
+    "detects explicit link targets in documentation which the link text resolves to anyway"
+}
+
+declare_lint! {
+    pub BROKEN_LINK_FRAGMENTS,
+    Warn,
+    "detects links in documentation to anchors which do not exist on the linked page"
+}
+
 declare_lint! {
     pub WHERE_CLAUSES_OBJECT_SAFETY,
     Warn,
@@ -493,6 +535,13 @@ declare_lint_pass! {
         INTRA_DOC_LINK_RESOLUTION_FAILURE,
         MISSING_DOC_CODE_EXAMPLES,
         PRIVATE_DOC_TESTS,
+        HIDDEN_TYPES_IN_SIGNATURES,
+        BARE_URLS,
+        UNLINKED_PATHS,
+        INVALID_RUST_CODEBLOCKS,
+        INCONSISTENT_DOC_CFG,
+        REDUNDANT_EXPLICIT_LINKS,
+        BROKEN_LINK_FRAGMENTS,
         WHERE_CLAUSES_OBJECT_SAFETY,
         PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
         MACRO_USE_EXTERN_CRATE,
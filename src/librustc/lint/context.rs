@@ -266,6 +266,15 @@ impl LintStore {
         self.by_name.insert(name.into(), Removed(reason.into()));
     }
 
+    /// Makes the builtin `lint` available as `tool_name::name` too, for tools like rustdoc
+    /// whose lints are declared by the compiler.
+    pub fn register_tool_alias(&mut self, tool_name: &str, name: &str, lint: &'static Lint) {
+        let complete_name = format!("{}::{}", tool_name, name);
+        if self.by_name.insert(complete_name.clone(), Id(LintId::of(lint))).is_some() {
+            bug!("duplicate specification of lint {}", complete_name)
+        }
+    }
+
     pub fn find_lints(&self, mut lint_name: &str) -> Result<Vec<LintId>, FindLintError> {
         match self.by_name.get(lint_name) {
             Some(&Id(lint_id)) => Ok(vec![lint_id]),
@@ -32,6 +32,21 @@ pub enum AutoTraitResult<A> {
     NegativeImpl,
 }
 
+/// Why the conditions under which an auto trait is implemented for a type could not be found.
+///
+/// The compiler never has to answer that question for a type in general, only for concrete uses
+/// of it, so code the compiler accepts can still make it fail.
+#[derive(Debug)]
+pub enum AutoTraitError<'tcx> {
+    /// Selecting an obligation failed for another reason than the trait not being implemented,
+    /// e.g. it overflowed.
+    Selection(SelectionError<'tcx>),
+    /// A projection could not be resolved though it involves inference variables.
+    Ambiguous,
+    /// The bounds which were found do not imply the auto trait.
+    Unfulfilled(Vec<FulfillmentError<'tcx>>),
+}
+
 impl<A> AutoTraitResult<A> {
     fn is_auto(&self) -> bool {
         match *self {
@@ -80,7 +95,7 @@ impl<'tcx> AutoTraitFinder<'tcx> {
         orig_env: ty::ParamEnv<'tcx>,
         trait_did: DefId,
         auto_trait_callback: impl Fn(&InferCtxt<'_, 'tcx>, AutoTraitInfo<'tcx>) -> A,
-    ) -> AutoTraitResult<A> {
+    ) -> Result<AutoTraitResult<A>, AutoTraitError<'tcx>> {
         let tcx = self.tcx;
 
         let trait_ref = ty::TraitRef {
@@ -113,7 +128,7 @@ impl<'tcx> AutoTraitFinder<'tcx> {
 
         // If an explicit impl exists, it always takes priority over an auto impl
         if bail_out {
-            return AutoTraitResult::ExplicitImpl;
+            return Ok(AutoTraitResult::ExplicitImpl);
         }
 
         return tcx.infer_ctxt().enter(|mut infcx| {
@@ -160,9 +175,9 @@ impl<'tcx> AutoTraitFinder<'tcx> {
                 orig_env,
                 &mut fresh_preds,
                 false,
-            ) {
+            )? {
                 Some(e) => e,
-                None => return AutoTraitResult::NegativeImpl,
+                None => return Ok(AutoTraitResult::NegativeImpl),
             };
 
             let (full_env, full_user_env) = self.evaluate_predicates(
@@ -173,7 +188,7 @@ impl<'tcx> AutoTraitFinder<'tcx> {
                 user_env,
                 &mut fresh_preds,
                 true,
-            ).unwrap_or_else(|| {
+            )?.unwrap_or_else(|| {
                 panic!(
                     "Failed to fully process: {:?} {:?} {:?}",
                     ty, trait_did, orig_env
@@ -198,12 +213,13 @@ impl<'tcx> AutoTraitFinder<'tcx> {
                 trait_did,
                 ObligationCause::misc(DUMMY_SP, hir::DUMMY_HIR_ID),
             );
-            fulfill.select_all_or_error(&infcx).unwrap_or_else(|e| {
-                panic!(
-                    "Unable to fulfill trait {:?} for '{:?}': {:?}",
-                    trait_did, ty, e
-                )
-            });
+            if let Err(errors) = fulfill.select_all_or_error(&infcx) {
+                debug!(
+                    "find_auto_trait_generics({:?}): unable to fulfill with {:?}: {:?}",
+                    trait_ref, full_env, errors
+                );
+                return Err(AutoTraitError::Unfulfilled(errors));
+            }
 
             let body_id_map: FxHashMap<_, _> = infcx
                 .region_obligations
@@ -227,7 +243,7 @@ impl<'tcx> AutoTraitFinder<'tcx> {
                 vid_to_region,
             };
 
-            return AutoTraitResult::PositiveImpl(auto_trait_callback(&infcx, info));
+            return Ok(AutoTraitResult::PositiveImpl(auto_trait_callback(&infcx, info)));
         });
     }
 }
@@ -281,7 +297,7 @@ impl AutoTraitFinder<'tcx> {
         user_env: ty::ParamEnv<'tcx>,
         fresh_preds: &mut FxHashSet<ty::Predicate<'tcx>>,
         only_projections: bool,
-    ) -> Option<(ty::ParamEnv<'tcx>, ty::ParamEnv<'tcx>)> {
+    ) -> Result<Option<(ty::ParamEnv<'tcx>, ty::ParamEnv<'tcx>)>, AutoTraitError<'tcx>> {
         let tcx = infcx.tcx;
 
         let mut select = SelectionContext::with_negative(&infcx, true);
@@ -327,7 +343,7 @@ impl AutoTraitFinder<'tcx> {
                             {
                                 debug!("evaluate_nested_obligations: found explicit negative impl\
                                         {:?}, bailing out", impl_def_id);
-                                return None;
+                                return Ok(None);
                             }
                         },
                         _ => {}
@@ -343,8 +359,8 @@ impl AutoTraitFinder<'tcx> {
                         &mut predicates,
                         &mut select,
                         only_projections,
-                    ) {
-                        return None;
+                    )? {
+                        return Ok(None);
                     }
                 }
                 &Ok(None) => {}
@@ -364,10 +380,16 @@ impl AutoTraitFinder<'tcx> {
                             pred,
                             pred.skip_binder().trait_ref.substs
                         );
-                        return None;
+                        return Ok(None);
                     }
                 }
-                _ => panic!("Unexpected error for '{:?}': {:?}", ty, result),
+                &Err(ref e) => {
+                    debug!(
+                        "evaluate_nested_obligations: unexpected error for {:?} {:?}: {:?}",
+                        ty, pred, e
+                    );
+                    return Err(AutoTraitError::Selection(e.clone()));
+                }
             };
 
             computed_preds.extend(user_computed_preds.iter().cloned());
@@ -391,7 +413,7 @@ impl AutoTraitFinder<'tcx> {
             ty, trait_did, new_env, final_user_env
         );
 
-        return Some((new_env, final_user_env));
+        return Ok(Some((new_env, final_user_env)));
     }
 
     /// This method is designed to work around the following issue:
@@ -628,7 +650,7 @@ impl AutoTraitFinder<'tcx> {
         predicates: &mut VecDeque<ty::PolyTraitPredicate<'tcx>>,
         select: &mut SelectionContext<'_, 'tcx>,
         only_projections: bool,
-    ) -> bool {
+    ) -> Result<bool, AutoTraitError<'tcx>> {
         let dummy_cause = ObligationCause::misc(DUMMY_SP, hir::DUMMY_HIR_ID);
 
         for (obligation, mut predicate) in nested
@@ -746,7 +768,7 @@ impl AutoTraitFinder<'tcx> {
                                  '{:?}' '{:?}', bailing out",
                                 ty, e
                             );
-                            return false;
+                            return Ok(false);
                         }
                         Ok(Some(v)) => {
                             // We only care about sub-obligations
@@ -762,8 +784,8 @@ impl AutoTraitFinder<'tcx> {
                                     predicates,
                                     select,
                                     only_projections,
-                                ) {
-                                    return false;
+                                )? {
+                                    return Ok(false);
                                 }
                             }
                         }
@@ -776,7 +798,11 @@ impl AutoTraitFinder<'tcx> {
                             // subobligations or getting an error) when we started off with
                             // inference variables
                             if p.ty().skip_binder().has_infer_types() {
-                                panic!("Unexpected result when selecting {:?} {:?}", ty, obligation)
+                                debug!(
+                                    "evaluate_nested_obligations: no progress selecting {:?} {:?}",
+                                    ty, obligation
+                                );
+                                return Err(AutoTraitError::Ambiguous);
                             }
                         }
                     }
@@ -787,7 +813,7 @@ impl AutoTraitFinder<'tcx> {
                         .region_outlives_predicate(&dummy_cause, binder)
                         .is_err()
                     {
-                        return false;
+                        return Ok(false);
                     }
                 }
                 &ty::Predicate::TypeOutlives(ref binder) => {
@@ -815,7 +841,7 @@ impl AutoTraitFinder<'tcx> {
                 _ => panic!("Unexpected predicate {:?} {:?}", ty, predicate),
             };
         }
-        return true;
+        return Ok(true);
     }
 
     pub fn clean_pred(
@@ -539,6 +539,9 @@ declare_features! (
     /// Allows the use of `loop` and `while` in constants.
     (active, const_loop, "1.41.0", Some(52000), None),
 
+    /// Allows `#[doc(fragment = "name")]`.
+    (active, doc_fragment, "1.42.0", Some(67212), None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
@@ -86,8 +86,8 @@ pub fn parse<'a>(sess: &'a Session, input: &Input) -> PResult<'a, ast::Crate> {
         syntax::show_span::run(sess.diagnostic(), s, &krate);
     }
 
-    if sess.opts.debugging_opts.hir_stats {
-        hir_stats::print_ast_stats(&krate, "PRE EXPANSION AST STATS");
+    if sess.opts.debugging_opts.hir_stats || sess.opts.debugging_opts.hir_stats_compare {
+        hir_stats::collect_ast_stats(sess, &krate, "post-parse", "PRE EXPANSION AST STATS");
     }
 
     Ok(krate)
@@ -413,8 +413,8 @@ fn configure_and_expand_inner<'a>(
         println!("Post-expansion node count: {}", count_nodes(&krate));
     }
 
-    if sess.opts.debugging_opts.hir_stats {
-        hir_stats::print_ast_stats(&krate, "POST EXPANSION AST STATS");
+    if sess.opts.debugging_opts.hir_stats || sess.opts.debugging_opts.hir_stats_compare {
+        hir_stats::collect_ast_stats(sess, &krate, "post-expansion", "POST EXPANSION AST STATS");
     }
 
     if sess.opts.debugging_opts.ast_json {
@@ -458,8 +458,8 @@ pub fn lower_to_hir(
         let nt_to_tokenstream = rustc_parse::nt_to_tokenstream;
         let hir_crate = lower_crate(sess, &dep_graph, &krate, resolver, nt_to_tokenstream);
 
-        if sess.opts.debugging_opts.hir_stats {
-            hir_stats::print_hir_stats(&hir_crate);
+        if sess.opts.debugging_opts.hir_stats || sess.opts.debugging_opts.hir_stats_compare {
+            hir_stats::collect_hir_stats(sess, &hir_crate);
         }
 
         hir::map::Forest::new(hir_crate, &dep_graph)
@@ -905,6 +905,13 @@ fn analysis(tcx: TyCtxt<'_>, cnum: CrateNum) -> Result<()> {
         });
     });
 
+    if sess.opts.debugging_opts.hir_stats {
+        hir_stats::print_arena_stats(&tcx.arena.stats());
+    }
+    if sess.opts.debugging_opts.hir_stats_compare {
+        sess.code_stats.print_node_stats();
+    }
+
     Ok(())
 }
 
@@ -37,11 +37,18 @@ use rustc::lint;
 use rustc::lint::{EarlyContext, LateContext, LateLintPass, EarlyLintPass, LintPass, LintArray};
 use rustc::lint::builtin::{
     BARE_TRAIT_OBJECTS,
+    BARE_URLS,
+    BROKEN_LINK_FRAGMENTS,
     ELIDED_LIFETIMES_IN_PATHS,
     EXPLICIT_OUTLIVES_REQUIREMENTS,
+    HIDDEN_TYPES_IN_SIGNATURES,
+    INCONSISTENT_DOC_CFG,
     INTRA_DOC_LINK_RESOLUTION_FAILURE,
+    INVALID_RUST_CODEBLOCKS,
     MISSING_DOC_CODE_EXAMPLES,
     PRIVATE_DOC_TESTS,
+    REDUNDANT_EXPLICIT_LINKS,
+    UNLINKED_PATHS,
 };
 use rustc::hir;
 use rustc::hir::def_id::DefId;
@@ -284,7 +291,14 @@ fn register_builtins(store: &mut lint::LintStore, no_interleave_lints: bool) {
     add_lint_group!("rustdoc",
                     INTRA_DOC_LINK_RESOLUTION_FAILURE,
                     MISSING_DOC_CODE_EXAMPLES,
-                    PRIVATE_DOC_TESTS);
+                    PRIVATE_DOC_TESTS,
+                    HIDDEN_TYPES_IN_SIGNATURES,
+                    BARE_URLS,
+                    UNLINKED_PATHS,
+                    INVALID_RUST_CODEBLOCKS,
+                    INCONSISTENT_DOC_CFG,
+                    REDUNDANT_EXPLICIT_LINKS,
+                    BROKEN_LINK_FRAGMENTS);
 
     // Register renamed and removed lints.
     store.register_renamed("single_use_lifetime", "single_use_lifetimes");
@@ -1,12 +1,26 @@
 // The visitors in this module collect sizes and counts of the most important
 // pieces of AST and HIR. The resulting numbers are good approximations but not
 // completely accurate (some things might be counted twice, others missed).
-
+//
+// `hir_stats` and `ast_stats` return the numbers as a `CrateStats` for other
+// tools to consume; `-Z hir-stats` merely prints them with `print_stats`.
+//
+// For the AST, the size of the source text behind each node is recorded as well,
+// in bytes and in tokens. Nodes contain one another, so these totals overlap
+// between kinds: they are meant as a proxy for parsing and expansion costs, not
+// as a breakdown of the source.
+//
+// With `-Z hir-stats-compare`, the counts and sizes collected at each stage are
+// kept in the session and printed side by side once the crate has been analyzed.
+
+use rustc::arena::ArenaStats;
 use rustc::hir::{self, HirId};
 use rustc::hir::intravisit as hir_visit;
+use rustc::session::{NodeStats, Session};
 use rustc::util::common::to_readable_str;
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use syntax::ast::{self, NodeId, AttrId};
+use syntax::source_map::SourceMap;
 use syntax::visit as ast_visit;
 use syntax_pos::Span;
 
@@ -20,32 +34,181 @@ enum Id {
 struct NodeData {
     count: usize,
     size: usize,
+    /// Length of the source text of all recorded nodes, in bytes.
+    bytes: usize,
+    /// Number of tokens in the source text of all recorded nodes.
+    tokens: usize,
 }
 
 struct StatCollector<'k> {
     krate: Option<&'k hir::Crate>,
+    /// Only set when collecting AST stats, which measure the source of each node.
+    source_map: Option<&'k SourceMap>,
     data: FxHashMap<&'static str, NodeData>,
     seen: FxHashSet<Id>,
 }
 
-pub fn print_hir_stats(krate: &hir::Crate) {
+/// The stats of a single kind of node, as collected by `hir_stats` or `ast_stats`.
+#[derive(Clone, Debug)]
+pub struct NodeStat {
+    pub label: &'static str,
+    /// Number of nodes of this kind.
+    pub count: usize,
+    /// Size of a single node of this kind, in bytes.
+    pub size: usize,
+    /// Length of the source text of these nodes, in bytes. Only measured for the AST.
+    pub bytes: Option<usize>,
+    /// Number of tokens in the source text of these nodes. Only measured for the AST.
+    pub tokens: Option<usize>,
+}
+
+impl NodeStat {
+    /// Memory used by all the nodes of this kind.
+    pub fn accumulated_size(&self) -> usize {
+        self.count * self.size
+    }
+}
+
+/// The stats of every kind of node found in a crate, smallest accumulated size first.
+#[derive(Clone, Debug, Default)]
+pub struct CrateStats {
+    pub nodes: Vec<NodeStat>,
+}
+
+impl CrateStats {
+    /// Total number of nodes.
+    pub fn total_count(&self) -> usize {
+        self.nodes.iter().map(|node| node.count).sum()
+    }
+
+    /// Memory used by all the nodes.
+    pub fn total_size(&self) -> usize {
+        self.nodes.iter().map(NodeStat::accumulated_size).sum()
+    }
+
+    /// Whether the source text behind the nodes was measured.
+    pub fn has_source_stats(&self) -> bool {
+        self.nodes.iter().any(|node| node.bytes.is_some())
+    }
+
+    /// Looks up the stats of the nodes with the given label.
+    pub fn get(&self, label: &str) -> Option<&NodeStat> {
+        self.nodes.iter().find(|node| node.label == label)
+    }
+}
+
+/// Computes the stats of a lowered crate.
+pub fn hir_stats(krate: &hir::Crate) -> CrateStats {
     let mut collector = StatCollector {
         krate: Some(krate),
+        source_map: None,
         data: FxHashMap::default(),
         seen: FxHashSet::default(),
     };
     hir_visit::walk_crate(&mut collector, krate);
-    collector.print("HIR STATS");
+    collector.into_stats()
 }
 
-pub fn print_ast_stats(krate: &ast::Crate, title: &str) {
+/// Computes the stats of an AST crate. The source text behind each node is only measured when a
+/// `source_map` is given.
+pub fn ast_stats(krate: &ast::Crate, source_map: Option<&SourceMap>) -> CrateStats {
     let mut collector = StatCollector {
         krate: None,
+        source_map,
         data: FxHashMap::default(),
         seen: FxHashSet::default(),
     };
     ast_visit::walk_crate(&mut collector, krate);
-    collector.print(title);
+    collector.into_stats()
+}
+
+/// Collects the stats of the HIR right after lowering.
+pub fn collect_hir_stats(sess: &Session, krate: &hir::Crate) {
+    report(sess, &hir_stats(krate), "post-lowering", "HIR STATS");
+}
+
+/// Collects the stats of the AST at `stage`, printing them under `title`.
+pub fn collect_ast_stats(sess: &Session, krate: &ast::Crate, stage: &str, title: &str) {
+    report(sess, &ast_stats(krate, Some(sess.source_map())), stage, title);
+}
+
+fn report(sess: &Session, stats: &CrateStats, stage: &str, title: &str) {
+    if sess.opts.debugging_opts.hir_stats {
+        print_stats(stats, title);
+    }
+    if sess.opts.debugging_opts.hir_stats_compare {
+        let nodes = stats.nodes.iter().map(|node| {
+            (node.label, NodeStats { count: node.count, size: node.accumulated_size() })
+        }).collect();
+        sess.code_stats.record_node_stats(stage, nodes);
+    }
+}
+
+/// Prints `stats` as a table under `title`.
+pub fn print_stats(stats: &CrateStats, title: &str) {
+    let spans = stats.has_source_stats();
+    let rule = if spans { 92 } else { 64 };
+
+    println!("\n{}\n", title);
+
+    print!("{:<18}{:>18}{:>14}{:>14}",
+        "Name", "Accumulated Size", "Count", "Item Size");
+    if spans {
+        print!("{:>14}{:>14}", "Source Bytes", "Tokens");
+    }
+    println!("\n{:-<1$}", "", rule);
+
+    for node in &stats.nodes {
+        print!("{:<18}{:>18}{:>14}{:>14}",
+            node.label,
+            to_readable_str(node.accumulated_size()),
+            to_readable_str(node.count),
+            to_readable_str(node.size));
+        if spans {
+            print!("{:>14}{:>14}",
+                to_readable_str(node.bytes.unwrap_or(0)),
+                to_readable_str(node.tokens.unwrap_or(0)));
+        }
+        println!();
+    }
+    println!("{:-<1$}", "", rule);
+    println!("{:<18}{:>18}\n",
+            "Total",
+            to_readable_str(stats.total_size()));
+}
+
+/// Prints how much memory the arenas of the global context have allocated, and how much of it
+/// is in use, largest arenas first.
+pub fn print_arena_stats(stats: &[(&'static str, ArenaStats)]) {
+    let mut stats: Vec<_> = stats.iter().filter(|(_, s)| s.chunks > 0).collect();
+
+    stats.sort_by_key(|(_, s)| s.allocated_bytes);
+
+    let mut total_allocated = 0;
+    let mut total_used = 0;
+
+    println!("\nARENA STATS\n");
+
+    println!("{:<32}{:>16}{:>16}{:>10}{:>14}",
+        "Arena", "Allocated Bytes", "Used Bytes", "Chunks", "Objects");
+    println!("{:-<88}", "");
+
+    for (name, s) in stats {
+        println!("{:<32}{:>16}{:>16}{:>10}{:>14}",
+            name,
+            to_readable_str(s.allocated_bytes),
+            to_readable_str(s.used_bytes),
+            to_readable_str(s.chunks),
+            s.objects.map_or_else(|| "-".to_string(), to_readable_str));
+
+        total_allocated += s.allocated_bytes;
+        total_used += s.used_bytes;
+    }
+    println!("{:-<88}", "");
+    println!("{:<32}{:>16}{:>16}\n",
+            "Total",
+            to_readable_str(total_allocated),
+            to_readable_str(total_used));
 }
 
 impl<'k> StatCollector<'k> {
@@ -58,38 +221,49 @@ impl<'k> StatCollector<'k> {
         let entry = self.data.entry(label).or_insert(NodeData {
             count: 0,
             size: 0,
+            bytes: 0,
+            tokens: 0,
         });
 
         entry.count += 1;
         entry.size = std::mem::size_of_val(node);
     }
 
-    fn print(&self, title: &str) {
-        let mut stats: Vec<_> = self.data.iter().collect();
+    /// Like `record`, but also measures the source text covered by `span`.
+    fn record_with_span<T>(&mut self, label: &'static str, node: &T, span: Span) {
+        self.record(label, Id::None, node);
 
-        stats.sort_by_key(|&(_, ref d)| d.count * d.size);
+        let source_map = match self.source_map {
+            Some(source_map) if !span.is_dummy() => source_map,
+            _ => return,
+        };
+        let tokens = source_map.span_to_snippet(span).map(|snippet| {
+            rustc_lexer::tokenize(&snippet).filter(|token| match token.kind {
+                rustc_lexer::TokenKind::Whitespace |
+                rustc_lexer::TokenKind::LineComment |
+                rustc_lexer::TokenKind::BlockComment { .. } => false,
+                _ => true,
+            }).count()
+        }).unwrap_or(0);
 
-        let mut total_size = 0;
-
-        println!("\n{}\n", title);
+        let entry = self.data.get_mut(label).unwrap();
+        entry.bytes += (span.hi().0 - span.lo().0) as usize;
+        entry.tokens += tokens;
+    }
 
-        println!("{:<18}{:>18}{:>14}{:>14}",
-            "Name", "Accumulated Size", "Count", "Item Size");
-        println!("----------------------------------------------------------------");
+    fn into_stats(self) -> CrateStats {
+        let spans = self.source_map.is_some();
+        let mut nodes: Vec<_> = self.data.into_iter().map(|(label, data)| NodeStat {
+            label,
+            count: data.count,
+            size: data.size,
+            bytes: if spans { Some(data.bytes) } else { None },
+            tokens: if spans { Some(data.tokens) } else { None },
+        }).collect();
 
-        for (label, data) in stats {
-            println!("{:<18}{:>18}{:>14}{:>14}",
-                label,
-                to_readable_str(data.count * data.size),
-                to_readable_str(data.count),
-                to_readable_str(data.size));
+        nodes.sort_by_key(|node| node.accumulated_size());
 
-            total_size += data.count * data.size;
-        }
-        println!("----------------------------------------------------------------");
-        println!("{:<18}{:>18}\n",
-                "Total",
-                to_readable_str(total_size));
+        CrateStats { nodes }
     }
 }
 
@@ -256,52 +430,52 @@ impl<'v> hir_visit::Visitor<'v> for StatCollector<'v> {
 impl<'v> ast_visit::Visitor<'v> for StatCollector<'v> {
 
     fn visit_mod(&mut self, m: &'v ast::Mod, _s: Span, _a: &[ast::Attribute], _n: NodeId) {
-        self.record("Mod", Id::None, m);
+        self.record_with_span("Mod", m, m.inner);
         ast_visit::walk_mod(self, m)
     }
 
     fn visit_foreign_item(&mut self, i: &'v ast::ForeignItem) {
-        self.record("ForeignItem", Id::None, i);
+        self.record_with_span("ForeignItem", i, i.span);
         ast_visit::walk_foreign_item(self, i)
     }
 
     fn visit_item(&mut self, i: &'v ast::Item) {
-        self.record("Item", Id::None, i);
+        self.record_with_span("Item", i, i.span);
         ast_visit::walk_item(self, i)
     }
 
     fn visit_local(&mut self, l: &'v ast::Local) {
-        self.record("Local", Id::None, l);
+        self.record_with_span("Local", l, l.span);
         ast_visit::walk_local(self, l)
     }
 
     fn visit_block(&mut self, b: &'v ast::Block) {
-        self.record("Block", Id::None, b);
+        self.record_with_span("Block", b, b.span);
         ast_visit::walk_block(self, b)
     }
 
     fn visit_stmt(&mut self, s: &'v ast::Stmt) {
-        self.record("Stmt", Id::None, s);
+        self.record_with_span("Stmt", s, s.span);
         ast_visit::walk_stmt(self, s)
     }
 
     fn visit_arm(&mut self, a: &'v ast::Arm) {
-        self.record("Arm", Id::None, a);
+        self.record_with_span("Arm", a, a.span);
         ast_visit::walk_arm(self, a)
     }
 
     fn visit_pat(&mut self, p: &'v ast::Pat) {
-        self.record("Pat", Id::None, p);
+        self.record_with_span("Pat", p, p.span);
         ast_visit::walk_pat(self, p)
     }
 
     fn visit_expr(&mut self, ex: &'v ast::Expr) {
-        self.record("Expr", Id::None, ex);
+        self.record_with_span("Expr", ex, ex.span);
         ast_visit::walk_expr(self, ex)
     }
 
     fn visit_ty(&mut self, t: &'v ast::Ty) {
-        self.record("Ty", Id::None, t);
+        self.record_with_span("Ty", t, t.span);
         ast_visit::walk_ty(self, t)
     }
 
@@ -310,57 +484,57 @@ impl<'v> ast_visit::Visitor<'v> for StatCollector<'v> {
                 fd: &'v ast::FnDecl,
                 s: Span,
                 _: NodeId) {
-        self.record("FnDecl", Id::None, fd);
+        self.record_with_span("FnDecl", fd, s);
         ast_visit::walk_fn(self, fk, fd, s)
     }
 
     fn visit_trait_item(&mut self, ti: &'v ast::AssocItem) {
-        self.record("TraitItem", Id::None, ti);
+        self.record_with_span("TraitItem", ti, ti.span);
         ast_visit::walk_trait_item(self, ti)
     }
 
     fn visit_impl_item(&mut self, ii: &'v ast::AssocItem) {
-        self.record("ImplItem", Id::None, ii);
+        self.record_with_span("ImplItem", ii, ii.span);
         ast_visit::walk_impl_item(self, ii)
     }
 
     fn visit_param_bound(&mut self, bounds: &'v ast::GenericBound) {
-        self.record("GenericBound", Id::None, bounds);
+        self.record_with_span("GenericBound", bounds, bounds.span());
         ast_visit::walk_param_bound(self, bounds)
     }
 
     fn visit_struct_field(&mut self, s: &'v ast::StructField) {
-        self.record("StructField", Id::None, s);
+        self.record_with_span("StructField", s, s.span);
         ast_visit::walk_struct_field(self, s)
     }
 
     fn visit_variant(&mut self, v: &'v ast::Variant) {
-        self.record("Variant", Id::None, v);
+        self.record_with_span("Variant", v, v.span);
         ast_visit::walk_variant(self, v)
     }
 
     fn visit_lifetime(&mut self, lifetime: &'v ast::Lifetime) {
-        self.record("Lifetime", Id::None, lifetime);
+        self.record_with_span("Lifetime", lifetime, lifetime.ident.span);
         ast_visit::walk_lifetime(self, lifetime)
     }
 
     fn visit_mac(&mut self, mac: &'v ast::Mac) {
-        self.record("Mac", Id::None, mac);
+        self.record_with_span("Mac", mac, mac.span());
     }
 
     fn visit_path_segment(&mut self,
                           path_span: Span,
                           path_segment: &'v ast::PathSegment) {
-        self.record("PathSegment", Id::None, path_segment);
+        self.record_with_span("PathSegment", path_segment, path_segment.ident.span);
         ast_visit::walk_path_segment(self, path_span, path_segment)
     }
 
     fn visit_assoc_ty_constraint(&mut self, constraint: &'v ast::AssocTyConstraint) {
-        self.record("AssocTyConstraint", Id::None, constraint);
+        self.record_with_span("AssocTyConstraint", constraint, constraint.span);
         ast_visit::walk_assoc_ty_constraint(self, constraint)
     }
 
     fn visit_attribute(&mut self, attr: &'v ast::Attribute) {
-        self.record("Attribute", Id::None, attr);
+        self.record_with_span("Attribute", attr, attr.span);
     }
 }
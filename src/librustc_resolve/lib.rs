@@ -2820,6 +2820,33 @@ impl<'a> Resolver<'a> {
     // FIXME(Manishearth): intra-doc links won't get warned of epoch changes.
     pub fn resolve_str_path_error(
         &mut self, span: Span, path_str: &str, ns: Namespace, module_id: NodeId
+    ) -> Result<(ast::Path, Res), ()> {
+        let module = self.block_map.get(&module_id).copied().unwrap_or_else(|| {
+            let def_id = self.definitions.local_def_id(module_id);
+            self.module_map.get(&def_id).copied().unwrap_or(self.graph_root)
+        });
+        self.resolve_str_path_in(span, path_str, ns, module)
+    }
+
+    /// Like `resolve_str_path_error`, but resolves the path in the scope of a module which may
+    /// belong to another crate. Rustdoc uses this for the documentation it inlines from other
+    /// crates, whose paths are relative to the module they were written in.
+    pub fn resolve_str_path_in_module(
+        &mut self, span: Span, path_str: &str, ns: Namespace, module_id: DefId
+    ) -> Result<(ast::Path, Res), ()> {
+        // `crate` would otherwise refer to the crate being compiled.
+        let (module_id, path_str) = if path_str.starts_with("crate::") && !module_id.is_local() {
+            let root = DefId { krate: module_id.krate, index: CRATE_DEF_INDEX };
+            (root, format!("self::{}", &path_str["crate::".len()..]))
+        } else {
+            (module_id, path_str.to_string())
+        };
+        let module = self.get_module(module_id);
+        self.resolve_str_path_in(span, &path_str, ns, module)
+    }
+
+    fn resolve_str_path_in(
+        &mut self, span: Span, path_str: &str, ns: Namespace, module: Module<'a>
     ) -> Result<(ast::Path, Res), ()> {
         let path = if path_str.starts_with("::") {
             ast::Path {
@@ -2841,10 +2868,6 @@ impl<'a> Resolver<'a> {
                     .collect(),
             }
         };
-        let module = self.block_map.get(&module_id).copied().unwrap_or_else(|| {
-            let def_id = self.definitions.local_def_id(module_id);
-            self.module_map.get(&def_id).copied().unwrap_or(self.graph_root)
-        });
         let parent_scope = &ParentScope::module(module);
         let res = self.resolve_ast_path(&path, ns, parent_scope).map_err(|_| ())?;
         Ok((path, res))
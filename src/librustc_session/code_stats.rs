@@ -1,5 +1,5 @@
 use rustc_target::abi::{Align, Size};
-use rustc_data_structures::fx::{FxHashSet};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet, FxIndexMap};
 use std::cmp::{self, Ordering};
 use rustc_data_structures::sync::Lock;
 
@@ -45,9 +45,19 @@ pub struct TypeSizeInfo {
     pub variants: Vec<VariantInfo>,
 }
 
+/// How many nodes of one kind a crate has at some stage, and how much memory they take.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct NodeStats {
+    pub count: usize,
+    /// Accumulated size of the nodes, in bytes.
+    pub size: usize,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
+    /// Node stats of each stage recorded for `-Z hir-stats-compare`, in the order the stages ran.
+    node_stats: Lock<FxIndexMap<String, FxHashMap<&'static str, NodeStats>>>,
 }
 
 impl CodeStats {
@@ -177,4 +187,61 @@ impl CodeStats {
             }
         }
     }
+
+    /// Records the node stats of one compilation stage, replacing any previous record of the same
+    /// stage.
+    pub fn record_node_stats(&self, stage: &str, nodes: FxHashMap<&'static str, NodeStats>) {
+        self.node_stats.borrow_mut().insert(stage.to_string(), nodes);
+    }
+
+    /// Prints the node counts and sizes of every recorded stage side by side, largest kinds of
+    /// nodes first.
+    pub fn print_node_stats(&self) {
+        let node_stats = self.node_stats.borrow();
+        if node_stats.is_empty() {
+            return;
+        }
+
+        let mut labels: Vec<&'static str> = node_stats.values()
+            .flat_map(|nodes| nodes.keys().cloned())
+            .collect::<FxHashSet<_>>()
+            .into_iter()
+            .collect();
+        let peak_size = |label: &&'static str| {
+            node_stats.values().filter_map(|nodes| nodes.get(label)).map(|s| s.size).max()
+        };
+        labels.sort_by(|a, b| peak_size(b).cmp(&peak_size(a)).then(a.cmp(b)));
+
+        let width = 18 + 26 * node_stats.len();
+
+        println!("\nNODE STATS BY STAGE (count / accumulated size)\n");
+
+        print!("{:<18}", "Name");
+        for stage in node_stats.keys() {
+            print!("{:>26}", stage);
+        }
+        println!("\n{:-<1$}", "", width);
+
+        for label in &labels {
+            print!("{:<18}", label);
+            for nodes in node_stats.values() {
+                match nodes.get(label) {
+                    Some(s) => print!("{:>26}", format!("{} / {}", s.count, s.size)),
+                    None => print!("{:>26}", "-"),
+                }
+            }
+            println!();
+        }
+
+        println!("{:-<1$}", "", width);
+        print!("{:<18}", "Total");
+        for nodes in node_stats.values() {
+            let total = nodes.values().fold(NodeStats::default(), |total, s| NodeStats {
+                count: total.count + s.count,
+                size: total.size + s.size,
+            });
+            print!("{:>26}", format!("{} / {}", total.count, total.size));
+        }
+        println!("\n");
+    }
 }
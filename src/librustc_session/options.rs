@@ -839,7 +839,9 @@ options! {DebuggingOptions, DebuggingSetter, basic_debugging_options,
     query_stats: bool = (false, parse_bool, [UNTRACKED],
         "print some statistics about the query system"),
     hir_stats: bool = (false, parse_bool, [UNTRACKED],
-        "print some statistics about AST and HIR"),
+        "print some statistics about AST, HIR and arena memory usage"),
+    hir_stats_compare: bool = (false, parse_bool, [UNTRACKED],
+        "print a table comparing the AST and HIR statistics of each compilation stage"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata"),
     json_rendered: Option<String> = (None, parse_opt_string, [UNTRACKED],
@@ -1,4 +1,4 @@
-pub use crate::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo};
+pub use crate::code_stats::{DataTypeKind, SizeKind, FieldInfo, VariantInfo, NodeStats};
 use crate::code_stats::CodeStats;
 
 use crate::cgu_reuse_tracker::CguReuseTracker;
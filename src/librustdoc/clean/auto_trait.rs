@@ -1,11 +1,13 @@
+use arena::TypedArena;
 use rustc::hir;
-use rustc::traits::auto_trait::{self, AutoTraitResult};
+use rustc::traits::auto_trait::{self, AutoTraitError, AutoTraitResult};
 use rustc::ty::{self, Region, RegionVid, TypeFoldable};
 use rustc::util::nodemap::FxHashSet;
 
 use std::fmt::Debug;
 
 use super::*;
+use super::intern::{TypeInterner, TypeRef};
 
 #[derive(Eq, PartialEq, Hash, Copy, Clone, Debug)]
 enum RegionTarget<'tcx> {
@@ -20,36 +22,33 @@ struct RegionDeps<'tcx> {
 }
 
 pub struct AutoTraitFinder<'a, 'tcx> {
-    pub cx: &'a core::DocContext<'tcx>,
-    pub f: auto_trait::AutoTraitFinder<'tcx>,
+    pub cx: &'a mut core::DocContext<'tcx>,
 }
 
 impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
-    pub fn new(cx: &'a core::DocContext<'tcx>) -> Self {
-        let f = auto_trait::AutoTraitFinder::new(cx.tcx);
-
-        AutoTraitFinder { cx, f }
+    pub fn new(cx: &'a mut core::DocContext<'tcx>) -> Self {
+        AutoTraitFinder { cx }
     }
 
     // FIXME(eddyb) figure out a better way to pass information about
     // parametrization of `ty` than `param_env_def_id`.
     pub fn get_auto_trait_impls(
-        &self,
+        &mut self,
         ty: Ty<'tcx>,
         param_env_def_id: DefId,
     ) -> Vec<Item> {
+        let f = auto_trait::AutoTraitFinder::new(self.cx.tcx);
         let param_env = self.cx.tcx.param_env(param_env_def_id);
 
         debug!("get_auto_trait_impls({:?})", ty);
-        let auto_traits = self.cx.auto_traits.iter().cloned();
-        auto_traits.filter_map(|trait_def_id| {
+        let auto_traits = self.cx.auto_traits.clone();
+        auto_traits.into_iter().filter_map(|trait_def_id| {
             let trait_ref = ty::TraitRef {
                 def_id: trait_def_id,
                 substs: self.cx.tcx.mk_substs_trait(ty, &[]),
             };
             if !self.cx
                 .generated_synthetics
-                .borrow_mut()
                 .insert((ty, trait_def_id))
             {
                 debug!(
@@ -59,7 +58,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                 return None;
             }
 
-            let result = self.f.find_auto_trait_generics(
+            let result = f.find_auto_trait_generics(
                 ty,
                 param_env,
                 trait_def_id,
@@ -70,10 +69,10 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                         .params
                         .iter()
                         .filter_map(|param| match param.kind {
-                            ty::GenericParamDefKind::Lifetime => Some(param.name.to_string()),
+                            ty::GenericParamDefKind::Lifetime => Some(param.name),
                             _ => None,
                         })
-                        .map(|name| (name.clone(), Lifetime(name)))
+                        .map(|name| (name, Lifetime(name)))
                         .collect();
                     let lifetime_predicates =
                         self.handle_lifetimes(&region_data, &names_map);
@@ -95,6 +94,20 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                 },
             );
 
+            let result = match result {
+                Ok(result) => result,
+                Err(error) => {
+                    let why = match error {
+                        AutoTraitError::Selection(_) => "selecting its bounds failed",
+                        AutoTraitError::Ambiguous => "its bounds are ambiguous",
+                        AutoTraitError::Unfulfilled(_) => "its bounds could not be fulfilled",
+                    };
+                    let span = Some(self.cx.tcx.def_span(param_env_def_id));
+                    report_unsynthesizable(self.cx, span, ty, trait_def_id, why);
+                    return None;
+                }
+            };
+
             let polarity;
             let new_generics = match result {
                 AutoTraitResult::PositiveImpl(new_generics) => {
@@ -137,6 +150,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                 def_id: self.cx.next_def_id(param_env_def_id.krate),
                 stability: None,
                 deprecation: None,
+                canonical_path: None,
                 inner: ImplItem(Impl {
                     unsafety: hir::Unsafety::Normal,
                     generics: new_generics,
@@ -147,6 +161,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                     polarity,
                     synthetic: true,
                     blanket_impl: None,
+                    inherited: Default::default(),
                 }),
             })
         }).collect()
@@ -154,7 +169,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
 
     fn get_lifetime(
         &self, region: Region<'_>,
-        names_map: &FxHashMap<String, Lifetime>
+        names_map: &FxHashMap<Symbol, Lifetime>
     ) -> Lifetime {
         self.region_name(region)
             .map(|name| {
@@ -166,9 +181,9 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
             .clone()
     }
 
-    fn region_name(&self, region: Region<'_>) -> Option<String> {
+    fn region_name(&self, region: Region<'_>) -> Option<Symbol> {
         match region {
-            &ty::ReEarlyBound(r) => Some(r.name.to_string()),
+            &ty::ReEarlyBound(r) => Some(r.name),
             _ => None,
         }
     }
@@ -186,7 +201,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
     fn handle_lifetimes<'cx>(
         &self,
         regions: &RegionConstraintData<'cx>,
-        names_map: &FxHashMap<String, Lifetime>,
+        names_map: &FxHashMap<Symbol, Lifetime>,
     ) -> Vec<WherePredicate> {
         // Our goal is to 'flatten' the list of constraints by eliminating
         // all intermediate RegionVids. At the end, all constraints should
@@ -341,7 +356,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                         // to the 'for<>' section
                         &ty::ReLateBound(_, ty::BoundRegion::BrNamed(_, name)) => {
                             Some(GenericParamDef {
-                                name: name.to_string(),
+                                name,
                                 kind: GenericParamDefKind::Lifetime,
                             })
                         },
@@ -355,14 +370,16 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
 
     fn make_final_bounds(
         &self,
-        ty_to_bounds: FxHashMap<Type, FxHashSet<GenericBound>>,
-        ty_to_fn: FxHashMap<Type, (Option<PolyTrait>, Option<Type>)>,
+        types: &TypeInterner<'_>,
+        ty_to_bounds: FxHashMap<TypeRef, FxHashSet<GenericBound>>,
+        ty_to_fn: FxHashMap<TypeRef, (Option<PolyTrait>, Option<Type>)>,
         lifetime_to_bounds: FxHashMap<Lifetime, FxHashSet<GenericBound>>,
     ) -> Vec<WherePredicate> {
         ty_to_bounds
             .into_iter()
-            .flat_map(|(ty, mut bounds)| {
-                if let Some(data) = ty_to_fn.get(&ty) {
+            .flat_map(|(ty_ref, mut bounds)| {
+                let ty = types.get(ty_ref);
+                if let Some(data) = ty_to_fn.get(&ty_ref) {
                     let (poly_trait, output) =
                         (data.0.as_ref().expect("as_ref failed").clone(), data.1.as_ref().cloned());
                     let new_ty = match &poly_trait.trait_ {
@@ -431,7 +448,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                 self.sort_where_bounds(&mut bounds_vec);
 
                 Some(WherePredicate::BoundPredicate {
-                    ty,
+                    ty: ty.clone(),
                     bounds: bounds_vec,
                 })
             })
@@ -461,7 +478,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
     // K', we use the dedicated syntax 'T: Fn() -> K'
     // * We explcitly add a '?Sized' bound if we didn't find any 'Sized' predicates for a type
     fn param_env_to_generics(
-        &self,
+        &mut self,
         tcx: TyCtxt<'tcx>,
         param_env_def_id: DefId,
         param_env: ty::ParamEnv<'tcx>,
@@ -487,6 +504,11 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
 
         let orig_bounds: FxHashSet<_> =
             self.cx.tcx.param_env(param_env_def_id).caller_bounds.iter().collect();
+        let mut generic_params = (
+            tcx.generics_of(param_env_def_id),
+            tcx.explicit_predicates_of(param_env_def_id),
+        ).clean(self.cx).params;
+
         let clean_where_predicates = param_env
             .caller_bounds
             .iter()
@@ -499,19 +521,20 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
             .map(|p| {
                 let replaced = p.fold_with(&mut replacer);
                 (replaced.clone(), replaced.clean(self.cx))
-            });
-
-        let mut generic_params = (
-            tcx.generics_of(param_env_def_id),
-            tcx.explicit_predicates_of(param_env_def_id),
-        ).clean(self.cx).params;
+            })
+            .collect::<Vec<_>>();
 
+        // The types the predicates are grouped by are interned, rather than cloned into each of
+        // the maps below.
+        let arena = TypedArena::default();
+        let mut types = TypeInterner::new(&arena);
         let mut has_sized = FxHashSet::default();
         let mut ty_to_bounds: FxHashMap<_, FxHashSet<_>> = Default::default();
         let mut lifetime_to_bounds: FxHashMap<_, FxHashSet<_>> = Default::default();
-        let mut ty_to_traits: FxHashMap<Type, FxHashSet<Type>> = Default::default();
+        let mut ty_to_traits: FxHashMap<TypeRef, FxHashSet<TypeRef>> = Default::default();
 
-        let mut ty_to_fn: FxHashMap<Type, (Option<PolyTrait>, Option<Type>)> = Default::default();
+        let mut ty_to_fn: FxHashMap<TypeRef, (Option<PolyTrait>, Option<Type>)> =
+            Default::default();
 
         for (orig_p, p) in clean_where_predicates {
             if p.is_none() {
@@ -527,11 +550,13 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                     // of the type.
                     // Therefore, we make sure that we never add a ?Sized
                     // bound for projections
-                    match &ty {
-                        &Type::QPath { .. } => {
-                            has_sized.insert(ty.clone());
-                        }
-                        _ => {}
+                    let is_projection = match ty {
+                        Type::QPath { .. } => true,
+                        _ => false,
+                    };
+                    let ty = types.intern(ty);
+                    if is_projection {
+                        has_sized.insert(ty);
                     }
 
                     if bounds.is_empty() {
@@ -544,12 +569,12 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                     let mut b = bounds.pop().expect("bounds were empty");
 
                     if b.is_sized_bound(self.cx) {
-                        has_sized.insert(ty.clone());
+                        has_sized.insert(ty);
                     } else if !b.get_trait_type()
                         .and_then(|t| {
-                            ty_to_traits
-                                .get(&ty)
-                                .map(|bounds| bounds.contains(&strip_type(t.clone())))
+                            let bounds = ty_to_traits.get(&ty)?;
+                            let t = types.find(&strip_type(t))?;
+                            Some(bounds.contains(&t))
                         })
                         .unwrap_or(false)
                     {
@@ -575,16 +600,16 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
 
                         if is_fn {
                             ty_to_fn
-                                .entry(ty.clone())
+                                .entry(ty)
                                 .and_modify(|e| *e = (Some(poly_trait.clone()), e.1.clone()))
                                 .or_insert(((Some(poly_trait.clone())), None));
 
                             ty_to_bounds
-                                .entry(ty.clone())
+                                .entry(ty)
                                 .or_default();
                         } else {
                             ty_to_bounds
-                                .entry(ty.clone())
+                                .entry(ty)
                                 .or_default()
                                 .insert(b.clone());
                         }
@@ -603,7 +628,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                             ref self_type,
                             ref trait_,
                         } => {
-                            let ty = &*self_type;
+                            let ty = types.intern((**self_type).clone());
                             match **trait_ {
                                 Type::ResolvedPath {
                                     path: ref trait_path,
@@ -613,9 +638,9 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                                 } => {
                                     let mut new_trait_path = trait_path.clone();
 
-                                    if self.is_fn_ty(tcx, trait_) && left_name == FN_OUTPUT_NAME {
+                                    if self.is_fn_ty(tcx, trait_) && *left_name == sym::Output {
                                         ty_to_fn
-                                            .entry(*ty.clone())
+                                            .entry(ty)
                                             .and_modify(|e| *e = (e.0.clone(), Some(rhs.clone())))
                                             .or_insert((None, Some(rhs)));
                                         continue;
@@ -637,7 +662,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                                                 ..
                                             } => {
                                                 bindings.push(TypeBinding {
-                                                    name: left_name.clone(),
+                                                    name: *left_name,
                                                     kind: TypeBindingKind::Equality {
                                                         ty: rhs,
                                                     },
@@ -657,7 +682,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                                     }
 
                                     let bounds = ty_to_bounds
-                                        .entry(*ty.clone())
+                                        .entry(ty)
                                         .or_default();
 
                                     bounds.insert(GenericBound::TraitBound(
@@ -687,9 +712,9 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                                     // Avoid creating any new duplicate bounds later in the outer
                                     // loop
                                     ty_to_traits
-                                        .entry(*ty.clone())
+                                        .entry(ty)
                                         .or_default()
-                                        .insert(*trait_.clone());
+                                        .insert(types.intern((**trait_).clone()));
                                 }
                                 _ => panic!(
                                     "Unexpected trait {:?} for {:?}",
@@ -704,7 +729,8 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
             };
         }
 
-        let final_bounds = self.make_final_bounds(ty_to_bounds, ty_to_fn, lifetime_to_bounds);
+        let final_bounds =
+            self.make_final_bounds(&types, ty_to_bounds, ty_to_fn, lifetime_to_bounds);
 
         existing_predicates.extend(final_bounds);
 
@@ -714,7 +740,7 @@ impl<'a, 'tcx> AutoTraitFinder<'a, 'tcx> {
                     // We never want something like `impl<T=Foo>`.
                     default.take();
                     let generic_ty = Type::Generic(param.name.clone());
-                    if !has_sized.contains(&generic_ty) {
+                    if !types.find(&generic_ty).map_or(false, |ty| has_sized.contains(&ty)) {
                         bounds.insert(0, GenericBound::maybe_sized(self.cx));
                     }
                 }
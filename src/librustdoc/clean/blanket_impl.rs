@@ -9,18 +9,18 @@ use syntax_pos::DUMMY_SP;
 use super::*;
 
 pub struct BlanketImplFinder<'a, 'tcx> {
-    pub cx: &'a core::DocContext<'tcx>,
+    pub cx: &'a mut core::DocContext<'tcx>,
 }
 
 impl<'a, 'tcx> BlanketImplFinder<'a, 'tcx> {
-    pub fn new(cx: &'a core::DocContext<'tcx>) -> Self {
+    pub fn new(cx: &'a mut core::DocContext<'tcx>) -> Self {
         BlanketImplFinder { cx }
     }
 
     // FIXME(eddyb) figure out a better way to pass information about
     // parametrization of `ty` than `param_env_def_id`.
     pub fn get_blanket_impls(
-        &self,
+        &mut self,
         ty: Ty<'tcx>,
         param_env_def_id: DefId,
     ) -> Vec<Item> {
@@ -29,9 +29,8 @@ impl<'a, 'tcx> BlanketImplFinder<'a, 'tcx> {
         debug!("get_blanket_impls({:?})", ty);
         let mut impls = Vec::new();
         for &trait_def_id in self.cx.tcx.all_traits(LOCAL_CRATE).iter() {
-            if !self.cx.renderinfo.borrow().access_levels.is_public(trait_def_id) ||
+            if !self.cx.renderinfo.access_levels.is_public(trait_def_id) ||
                self.cx.generated_synthetics
-                      .borrow_mut()
                       .get(&(ty, trait_def_id))
                       .is_some() {
                 continue
@@ -43,7 +42,7 @@ impl<'a, 'tcx> BlanketImplFinder<'a, 'tcx> {
                 let may_apply = self.cx.tcx.infer_ctxt().enter(|infcx| {
                     match trait_ref.self_ty().kind {
                         ty::Param(_) => {},
-                        _ => return false,
+                        _ => return Ok(false),
                     }
 
                     let substs = infcx.fresh_substs_for_item(DUMMY_SP, param_env_def_id);
@@ -66,20 +65,27 @@ impl<'a, 'tcx> BlanketImplFinder<'a, 'tcx> {
                             "invoking predicate_may_hold: param_env={:?}, trait_ref={:?}, ty={:?}",
                              param_env, trait_ref, ty
                         );
-                        match infcx.evaluate_obligation(
+                        infcx.evaluate_obligation(
                             &traits::Obligation::new(
                                 cause,
                                 param_env,
                                 trait_ref.to_predicate(),
                             ),
-                        ) {
-                            Ok(eval_result) => eval_result.may_apply(),
-                            Err(traits::OverflowError) => true, // overflow doesn't mean yes *or* no
-                        }
+                        ).map(|eval_result| eval_result.may_apply())
                     } else {
-                        false
+                        Ok(false)
                     }
                 });
+                let may_apply = match may_apply {
+                    Ok(may_apply) => may_apply,
+                    // Overflow doesn't mean yes *or* no.
+                    Err(traits::OverflowError) => {
+                        let span = Some(self.cx.tcx.def_span(impl_def_id));
+                        report_unsynthesizable(self.cx, span, ty, trait_def_id,
+                                               "evaluating the bounds of the impl overflowed");
+                        return;
+                    }
+                };
                 debug!("get_blanket_impls: found applicable impl: {}\
                         for trait_ref={:?}, ty={:?}",
                         may_apply, trait_ref, ty);
@@ -87,8 +93,8 @@ impl<'a, 'tcx> BlanketImplFinder<'a, 'tcx> {
                     return;
                 }
 
-                self.cx.generated_synthetics.borrow_mut()
-                                            .insert((ty, trait_def_id));
+                self.cx.generated_synthetics.insert((ty, trait_def_id));
+                record_notable_trait(self.cx, trait_def_id);
                 let provided_trait_methods =
                     self.cx.tcx.provided_trait_methods(trait_def_id)
                                 .into_iter()
@@ -103,6 +109,7 @@ impl<'a, 'tcx> BlanketImplFinder<'a, 'tcx> {
                     def_id: self.cx.next_def_id(impl_def_id.krate),
                     stability: None,
                     deprecation: None,
+                    canonical_path: None,
                     inner: ImplItem(Impl {
                         unsafety: hir::Unsafety::Normal,
                         generics: (
@@ -120,6 +127,7 @@ impl<'a, 'tcx> BlanketImplFinder<'a, 'tcx> {
                         polarity: None,
                         synthetic: false,
                         blanket_impl: Some(trait_ref.self_ty().clean(self.cx)),
+                        inherited: Default::default(),
                     }),
                 });
             });
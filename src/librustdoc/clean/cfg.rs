@@ -7,6 +7,7 @@ use std::mem;
 use std::fmt::{self, Write};
 use std::ops;
 
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_feature::Features;
 use syntax::symbol::{Symbol, sym};
 use syntax::ast::{MetaItem, MetaItemKind, NestedMetaItem, LitKind};
@@ -19,6 +20,10 @@ use crate::html::escape::Escape;
 #[cfg(test)]
 mod tests;
 
+/// The display names given to configurations with `#![doc(cfg_name(...))]`, which are rendered
+/// instead of their default description.
+pub type CfgNames = FxHashMap<Cfg, String>;
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Cfg {
     /// Accepts all configurations.
@@ -43,7 +48,7 @@ pub struct InvalidCfgError {
 
 impl Cfg {
     /// Parses a `NestedMetaItem` into a `Cfg`.
-    fn parse_nested(nested_cfg: &NestedMetaItem) -> Result<Cfg, InvalidCfgError> {
+    pub fn parse_nested(nested_cfg: &NestedMetaItem) -> Result<Cfg, InvalidCfgError> {
         match nested_cfg {
             NestedMetaItem::MetaItem(ref cfg) => Cfg::parse(cfg),
             NestedMetaItem::Literal(ref lit) => Err(InvalidCfgError {
@@ -120,6 +125,136 @@ impl Cfg {
         }
     }
 
+    /// Returns an equivalent configuration that is easier to read: nested `all`s and `any`s are
+    /// flattened, duplicated and absorbed operands are dropped, and operands shared by every
+    /// branch are factored out, so that `any(all(unix, a), all(unix, b))` becomes
+    /// `all(unix, any(a, b))`.
+    pub fn simplify(&self) -> Cfg {
+        match *self {
+            Cfg::False | Cfg::True | Cfg::Cfg(..) => self.clone(),
+            Cfg::Not(ref child) => !child.simplify(),
+            Cfg::All(ref sub_cfgs) => simplify_operands(sub_cfgs, true),
+            Cfg::Any(ref sub_cfgs) => simplify_operands(sub_cfgs, false),
+        }
+    }
+
+    /// Removes the predicates listed in `#![doc(cfg_hide(...))]` from the configuration, so they
+    /// are not displayed. Returns `None` if nothing is left to display.
+    pub fn strip_hidden(&self, hidden: &FxHashSet<Cfg>) -> Option<Cfg> {
+        match *self {
+            Cfg::False | Cfg::True => Some(self.clone()),
+            Cfg::Cfg(..) => if hidden.contains(self) { None } else { Some(self.clone()) },
+            Cfg::Not(ref child) => child.strip_hidden(hidden).map(|child| !child),
+            Cfg::All(ref sub_cfgs) => {
+                let sub_cfgs = sub_cfgs.iter()
+                    .filter_map(|sub_cfg| sub_cfg.strip_hidden(hidden))
+                    .fold(Cfg::True, |acc, sub_cfg| acc & sub_cfg);
+                if sub_cfgs == Cfg::True { None } else { Some(sub_cfgs) }
+            },
+            Cfg::Any(ref sub_cfgs) => {
+                let sub_cfgs = sub_cfgs.iter()
+                    .filter_map(|sub_cfg| sub_cfg.strip_hidden(hidden))
+                    .fold(Cfg::False, |acc, sub_cfg| acc | sub_cfg);
+                if sub_cfgs == Cfg::False { None } else { Some(sub_cfgs) }
+            },
+        }
+    }
+
+    /// Whether any of the options named in `names` appears anywhere in the configuration.
+    pub fn mentions_any(&self, names: &[Symbol]) -> bool {
+        match *self {
+            Cfg::False | Cfg::True => false,
+            Cfg::Cfg(name, _) => names.contains(&name),
+            Cfg::Not(ref child) => child.mentions_any(names),
+            Cfg::All(ref sub_cfgs) | Cfg::Any(ref sub_cfgs) => {
+                sub_cfgs.iter().any(|sub_cfg| sub_cfg.mentions_any(names))
+            },
+        }
+    }
+
+    /// Returns the configurations which must all hold for this one to hold, i.e., the operands
+    /// of an `all(...)`.
+    pub fn conjuncts(&self) -> Vec<&Cfg> {
+        match *self {
+            Cfg::True => vec![],
+            Cfg::All(ref sub_cfgs) => sub_cfgs.iter().flat_map(Cfg::conjuncts).collect(),
+            _ => vec![self],
+        }
+    }
+
+    /// Returns the cargo features, i.e., the values of the `feature = "..."` options, appearing
+    /// anywhere in the configuration, without duplicates.
+    pub fn features(&self) -> Vec<Symbol> {
+        let mut features = vec![];
+        self.collect_features(&mut features);
+        features
+    }
+
+    fn collect_features(&self, features: &mut Vec<Symbol>) {
+        match *self {
+            Cfg::Cfg(name, Some(value)) if name == sym::feature => {
+                if !features.contains(&value) {
+                    features.push(value);
+                }
+            },
+            Cfg::False | Cfg::True | Cfg::Cfg(..) => {},
+            Cfg::Not(ref child) => child.collect_features(features),
+            Cfg::All(ref sub_cfgs) | Cfg::Any(ref sub_cfgs) => {
+                for sub_cfg in sub_cfgs {
+                    sub_cfg.collect_features(features);
+                }
+            },
+        }
+    }
+
+    /// Whether this configuration and `other` can never hold at the same time. This only knows
+    /// about negations and the options which take a single value on every target, like
+    /// `target_os`, so it may miss some exclusions.
+    pub fn excludes(&self, other: &Cfg) -> bool {
+        match (self, other) {
+            (&Cfg::False, _) | (_, &Cfg::False) => true,
+            (&Cfg::Not(ref child), cfg) | (cfg, &Cfg::Not(ref child)) if **child == *cfg => true,
+            (&Cfg::All(ref sub_cfgs), cfg) | (cfg, &Cfg::All(ref sub_cfgs)) => {
+                sub_cfgs.iter().any(|sub_cfg| sub_cfg.excludes(cfg))
+            },
+            (&Cfg::Any(ref sub_cfgs), cfg) | (cfg, &Cfg::Any(ref sub_cfgs)) => {
+                sub_cfgs.iter().all(|sub_cfg| sub_cfg.excludes(cfg))
+            },
+            (&Cfg::Cfg(..), &Cfg::Cfg(..)) => match (self.target_value(), other.target_value()) {
+                (Some((name, value)), Some((other_name, other_value))) => {
+                    name == other_name && value != other_value
+                },
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns the option and value of a configuration like `target_os = "linux"`, for the
+    /// options which take a single value on every target. `unix` and `windows` are shorthands
+    /// for `target_family`.
+    fn target_value(&self) -> Option<(Symbol, Symbol)> {
+        const SINGLE_VALUED: &[&str] = &[
+            "target_arch",
+            "target_endian",
+            "target_env",
+            "target_family",
+            "target_os",
+            "target_pointer_width",
+            "target_vendor",
+        ];
+
+        match *self {
+            Cfg::Cfg(name, None) if &*name.as_str() == "unix" || name == sym::windows => {
+                Some((Symbol::intern("target_family"), name))
+            },
+            Cfg::Cfg(name, Some(value)) if SINGLE_VALUED.contains(&&*name.as_str()) => {
+                Some((name, value))
+            },
+            _ => None,
+        }
+    }
+
     /// Whether the configuration consists of just `Cfg` or `Not`.
     fn is_simple(&self) -> bool {
         match *self {
@@ -137,9 +272,10 @@ impl Cfg {
     }
 
     /// Renders the configuration for human display, as a short HTML description.
-    pub(crate) fn render_short_html(&self) -> String {
-        let mut msg = Html(self, true).to_string();
-        if self.should_capitalize_first_letter() {
+    pub(crate) fn render_short_html(&self, names: &CfgNames) -> String {
+        let cfg = self.simplify();
+        let mut msg = Html(&cfg, true, names).to_string();
+        if cfg.should_capitalize_first_letter() {
             if let Some(i) = msg.find(|c: char| c.is_ascii_alphanumeric()) {
                 msg[i .. i+1].make_ascii_uppercase();
             }
@@ -148,15 +284,20 @@ impl Cfg {
     }
 
     /// Renders the configuration for long display, as a long HTML description.
-    pub(crate) fn render_long_html(&self) -> String {
-        let on = if self.should_use_with_in_description() {
+    pub(crate) fn render_long_html(&self, names: &CfgNames) -> String {
+        let cfg = self.simplify();
+        let on = if cfg.should_use_with_in_description() {
             "with"
         } else {
             "on"
         };
 
-        let mut msg = format!("This is supported {} <strong>{}</strong>", on, Html(self, false));
-        if self.should_append_only_to_description() {
+        let mut msg = format!(
+            "This is supported {} <strong>{}</strong>",
+            on,
+            Html(&cfg, false, names),
+        );
+        if cfg.should_append_only_to_description() {
             msg.push_str(" only");
         }
         msg.push('.');
@@ -195,6 +336,73 @@ impl Cfg {
     }
 }
 
+/// The operands of `cfg` when it is seen as an `all(...)`, or as an `any(...)` if `all` is false.
+fn operands(cfg: &Cfg, all: bool) -> Vec<Cfg> {
+    match *cfg {
+        Cfg::All(ref sub_cfgs) if all => sub_cfgs.clone(),
+        Cfg::Any(ref sub_cfgs) if !all => sub_cfgs.clone(),
+        _ => vec![cfg.clone()],
+    }
+}
+
+/// Builds an `all(...)` of `terms`, or an `any(...)` if `all` is false.
+fn combine(mut terms: Vec<Cfg>, all: bool) -> Cfg {
+    match terms.len() {
+        0 => if all { Cfg::True } else { Cfg::False },
+        1 => terms.pop().unwrap(),
+        _ => if all { Cfg::All(terms) } else { Cfg::Any(terms) },
+    }
+}
+
+/// Simplifies an `all(sub_cfgs)`, or an `any(sub_cfgs)` if `all` is false. The comments below
+/// describe the `any` case, the `all` case being its dual.
+fn simplify_operands(sub_cfgs: &[Cfg], all: bool) -> Cfg {
+    let (identity, absorbing) = if all { (Cfg::True, Cfg::False) } else { (Cfg::False, Cfg::True) };
+
+    // Flattening and deduplication: `any(a, any(a, b))` is `any(a, b)`.
+    let mut terms: Vec<Cfg> = vec![];
+    for sub_cfg in sub_cfgs.iter().map(Cfg::simplify) {
+        if sub_cfg == absorbing {
+            return absorbing;
+        }
+        for term in operands(&sub_cfg, all) {
+            if term != identity && !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+    }
+
+    // Absorption: `any(a, all(a, b))` is `a`, since `all(a, b)` is only true when `a` is.
+    let inner: Vec<_> = terms.iter().map(|term| operands(term, !all)).collect();
+    let absorbed = |i: usize| inner.iter().enumerate().any(|(j, other)| {
+        j != i && other.iter().all(|o| inner[i].contains(o)) &&
+            (other.len() < inner[i].len() || j < i)
+    });
+    let kept: Vec<usize> = (0..terms.len()).filter(|&i| !absorbed(i)).collect();
+    if kept.len() < 2 {
+        return combine(kept.into_iter().map(|i| terms[i].clone()).collect(), all);
+    }
+
+    // Factoring: `any(all(a, b), all(a, c))` is `all(a, any(b, c))`. None of the remaining
+    // operands is made of the shared part only, or it would have absorbed the others.
+    let common: Vec<Cfg> = inner[kept[0]].iter()
+        .filter(|o| kept.iter().all(|&i| inner[i].contains(o)))
+        .cloned()
+        .collect();
+    if common.is_empty() {
+        return combine(kept.into_iter().map(|i| terms[i].clone()).collect(), all);
+    }
+    let rests: Vec<Cfg> = kept.iter().map(|&i| {
+        combine(inner[i].iter().filter(|o| !common.contains(o)).cloned().collect(), !all)
+    }).collect();
+    let rest = simplify_operands(&rests, all);
+    let mut factored = common;
+    if rest != absorbing {
+        factored.extend(operands(&rest, !all));
+    }
+    combine(factored, !all)
+}
+
 impl ops::Not for Cfg {
     type Output = Cfg;
     fn not(self) -> Cfg {
@@ -216,11 +424,23 @@ impl ops::BitAndAssign for Cfg {
             (&mut Cfg::False, _) | (_, Cfg::True) => {},
             (s, Cfg::False) => *s = Cfg::False,
             (s @ &mut Cfg::True, b) => *s = b,
-            (&mut Cfg::All(ref mut a), Cfg::All(ref mut b)) => a.append(b),
-            (&mut Cfg::All(ref mut a), ref mut b) => a.push(mem::replace(b, Cfg::True)),
+            (&mut Cfg::All(ref mut a), Cfg::All(b)) => {
+                for c in b {
+                    if !a.contains(&c) {
+                        a.push(c);
+                    }
+                }
+            },
+            (&mut Cfg::All(ref mut a), ref mut b) => {
+                if !a.contains(b) {
+                    a.push(mem::replace(b, Cfg::True));
+                }
+            },
             (s, Cfg::All(mut a)) => {
                 let b = mem::replace(s, Cfg::True);
-                a.push(b);
+                if !a.contains(&b) {
+                    a.push(b);
+                }
                 *s = Cfg::All(a);
             },
             (s, b) => {
@@ -272,8 +492,8 @@ impl ops::BitOr for Cfg {
 }
 
 /// Pretty-print wrapper for a `Cfg`. Also indicates whether the "short-form" rendering should be
-/// used.
-struct Html<'a>(&'a Cfg, bool);
+/// used, and holds the display names registered by the crate.
+struct Html<'a>(&'a Cfg, bool, &'a CfgNames);
 
 fn write_with_opt_paren<T: fmt::Display>(
     fmt: &mut fmt::Formatter<'_>,
@@ -293,6 +513,9 @@ fn write_with_opt_paren<T: fmt::Display>(
 
 impl<'a> fmt::Display for Html<'a> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(name) = self.2.get(self.0) {
+            return write!(fmt, "{}", Escape(name));
+        }
         match *self.0 {
             Cfg::Not(ref child) => match **child {
                 Cfg::Any(ref sub_cfgs) => {
@@ -303,12 +526,13 @@ impl<'a> fmt::Display for Html<'a> {
                     };
                     for (i, sub_cfg) in sub_cfgs.iter().enumerate() {
                         fmt.write_str(if i == 0 { "neither " } else { separator })?;
-                        write_with_opt_paren(fmt, !sub_cfg.is_all(), Html(sub_cfg, self.1))?;
+                        let html = Html(sub_cfg, self.1, self.2);
+                        write_with_opt_paren(fmt, !sub_cfg.is_all(), html)?;
                     }
                     Ok(())
                 }
-                ref simple @ Cfg::Cfg(..) => write!(fmt, "non-{}", Html(simple, self.1)),
-                ref c => write!(fmt, "not ({})", Html(c, self.1)),
+                ref simple @ Cfg::Cfg(..) => write!(fmt, "non-{}", Html(simple, self.1, self.2)),
+                ref c => write!(fmt, "not ({})", Html(c, self.1, self.2)),
             },
 
             Cfg::Any(ref sub_cfgs) => {
@@ -321,7 +545,7 @@ impl<'a> fmt::Display for Html<'a> {
                     if i != 0 {
                         fmt.write_str(separator)?;
                     }
-                    write_with_opt_paren(fmt, !sub_cfg.is_all(), Html(sub_cfg, self.1))?;
+                    write_with_opt_paren(fmt, !sub_cfg.is_all(), Html(sub_cfg, self.1, self.2))?;
                 }
                 Ok(())
             },
@@ -331,7 +555,7 @@ impl<'a> fmt::Display for Html<'a> {
                     if i != 0 {
                         fmt.write_str(" and ")?;
                     }
-                    write_with_opt_paren(fmt, !sub_cfg.is_simple(), Html(sub_cfg, self.1))?;
+                    write_with_opt_paren(fmt, !sub_cfg.is_simple(), Html(sub_cfg, self.1, self.2))?;
                 }
                 Ok(())
             },
@@ -117,6 +117,20 @@ fn test_cfg_and() {
             word_cfg("a") & word_cfg("b") & word_cfg("c"),
             Cfg::All(vec![word_cfg("a"), word_cfg("b"), word_cfg("c")])
         );
+
+        assert_eq!(
+            word_cfg("a") & word_cfg("b") & word_cfg("a"),
+            Cfg::All(vec![word_cfg("a"), word_cfg("b")])
+        );
+
+        assert_eq!(
+            word_cfg("a") & Cfg::All(vec![word_cfg("a"), word_cfg("b")]),
+            Cfg::All(vec![word_cfg("a"), word_cfg("b")])
+        );
+
+        let mut z = Cfg::All(vec![word_cfg("a"), word_cfg("b")]);
+        z &= Cfg::All(vec![word_cfg("b"), word_cfg("c")]);
+        assert_eq!(z, Cfg::All(vec![word_cfg("a"), word_cfg("b"), word_cfg("c")]));
     })
 }
 
@@ -245,81 +259,271 @@ fn test_parse_err() {
     })
 }
 
+#[test]
+fn test_mentions_any() {
+    with_default_globals(|| {
+        let hidden = [Symbol::intern("test"), Symbol::intern("doc")];
+
+        assert!(!Cfg::True.mentions_any(&hidden));
+        assert!(!word_cfg("unix").mentions_any(&hidden));
+        assert!(word_cfg("test").mentions_any(&hidden));
+        assert!((!word_cfg("test")).mentions_any(&hidden));
+        assert!((word_cfg("unix") | word_cfg("doc")).mentions_any(&hidden));
+        assert!(!(word_cfg("unix") & name_value_cfg("target_os", "test")).mentions_any(&hidden));
+    })
+}
+
+#[test]
+fn test_conjuncts() {
+    with_default_globals(|| {
+        assert!(Cfg::True.conjuncts().is_empty());
+        assert_eq!(word_cfg("unix").conjuncts(), vec![&word_cfg("unix")]);
+
+        let cfg = Cfg::All(vec![
+            word_cfg("a"),
+            Cfg::All(vec![word_cfg("b"), word_cfg("c")]),
+            Cfg::Any(vec![word_cfg("d"), word_cfg("e")]),
+        ]);
+        assert_eq!(cfg.conjuncts(), vec![
+            &word_cfg("a"),
+            &word_cfg("b"),
+            &word_cfg("c"),
+            &Cfg::Any(vec![word_cfg("d"), word_cfg("e")]),
+        ]);
+    })
+}
+
+#[test]
+fn test_features() {
+    with_default_globals(|| {
+        assert!(Cfg::True.features().is_empty());
+        assert!(word_cfg("feature").features().is_empty());
+        assert!(name_value_cfg("target_os", "serde").features().is_empty());
+        assert_eq!(name_value_cfg("feature", "serde").features(), vec![Symbol::intern("serde")]);
+
+        let cfg = Cfg::All(vec![
+            name_value_cfg("feature", "std"),
+            Cfg::Any(vec![word_cfg("unix"), !name_value_cfg("feature", "alloc")]),
+            name_value_cfg("feature", "std"),
+        ]);
+        assert_eq!(cfg.features(), vec![Symbol::intern("std"), Symbol::intern("alloc")]);
+    })
+}
+
+#[test]
+fn test_excludes() {
+    with_default_globals(|| {
+        assert!(word_cfg("a").excludes(&!word_cfg("a")));
+        assert!((!word_cfg("a")).excludes(&word_cfg("a")));
+        assert!(!word_cfg("a").excludes(&word_cfg("b")));
+        assert!(!word_cfg("a").excludes(&!word_cfg("b")));
+
+        assert!(word_cfg("unix").excludes(&word_cfg("windows")));
+        assert!(word_cfg("windows").excludes(&name_value_cfg("target_family", "unix")));
+        assert!(name_value_cfg("target_os", "linux").excludes(
+            &name_value_cfg("target_os", "macos")
+        ));
+        assert!(!name_value_cfg("target_os", "linux").excludes(
+            &name_value_cfg("target_arch", "x86_64")
+        ));
+        // a crate can be built with several features enabled at once
+        assert!(!name_value_cfg("feature", "a").excludes(&name_value_cfg("feature", "b")));
+
+        let any = Cfg::Any(vec![
+            name_value_cfg("target_os", "linux"),
+            name_value_cfg("target_os", "macos"),
+        ]);
+        assert!(!any.excludes(&word_cfg("windows")));
+        assert!(any.excludes(&name_value_cfg("target_os", "windows")));
+        assert!(!any.excludes(&name_value_cfg("target_os", "macos")));
+
+        let all = word_cfg("unix") & name_value_cfg("target_arch", "x86_64");
+        assert!(all.excludes(&name_value_cfg("target_arch", "arm")));
+        assert!(name_value_cfg("target_arch", "arm").excludes(&all));
+        assert!(!all.excludes(&name_value_cfg("target_os", "linux")));
+    })
+}
+
+#[test]
+fn test_simplify() {
+    with_default_globals(|| {
+        let a = name_value_cfg("feature", "a");
+        let b = name_value_cfg("feature", "b");
+        let unix = word_cfg("unix");
+
+        // flattening and deduplication
+        assert_eq!(
+            Cfg::Any(vec![unix.clone(), Cfg::Any(vec![a.clone(), unix.clone()])]).simplify(),
+            Cfg::Any(vec![unix.clone(), a.clone()])
+        );
+        assert_eq!(Cfg::All(vec![a.clone(), a.clone()]).simplify(), a);
+        assert_eq!(Cfg::All(vec![a.clone(), Cfg::False]).simplify(), Cfg::False);
+        assert_eq!(Cfg::Any(vec![a.clone(), Cfg::False]).simplify(), a);
+
+        // absorption
+        assert_eq!((unix.clone() | (unix.clone() & a.clone())).simplify(), unix);
+        assert_eq!((unix.clone() & (a.clone() | unix.clone())).simplify(), unix);
+        assert_eq!(
+            Cfg::Any(vec![
+                Cfg::All(vec![unix.clone(), a.clone()]),
+                Cfg::All(vec![a.clone(), unix.clone()]),
+            ]).simplify(),
+            unix.clone() & a.clone()
+        );
+
+        // factoring
+        assert_eq!(
+            ((unix.clone() & a.clone()) | (unix.clone() & b.clone())).simplify(),
+            unix.clone() & (a.clone() | b.clone())
+        );
+        assert_eq!(
+            ((a.clone() | unix.clone()) & (b.clone() | unix.clone())).simplify(),
+            unix.clone() | (a.clone() & b.clone())
+        );
+
+        // nothing to do
+        let cfg = (word_cfg("debug_assertions") | word_cfg("windows")) & unix.clone();
+        assert_eq!(cfg.simplify(), cfg);
+        assert_eq!((!(unix.clone() & a.clone())).simplify(), !(unix & a));
+    })
+}
+
+#[test]
+fn test_render_simplified() {
+    with_default_globals(|| {
+        assert_eq!(
+            (
+                (word_cfg("unix") & name_value_cfg("feature", "a")) |
+                (word_cfg("unix") & name_value_cfg("feature", "b"))
+            ).render_short_html(&CfgNames::default()),
+            "Unix and (<code>feature=\"a\"</code> or <code>feature=\"b\"</code>)"
+        );
+    })
+}
+
+#[test]
+fn test_render_names() {
+    with_default_globals(|| {
+        let mut names = CfgNames::default();
+        names.insert(name_value_cfg("feature", "tls"), "TLS support".to_string());
+        names.insert(word_cfg("unix") | word_cfg("windows"), "desktop <OS>".to_string());
+
+        assert_eq!(name_value_cfg("feature", "tls").render_short_html(&names), "TLS support");
+        assert_eq!(
+            (word_cfg("unix") & name_value_cfg("feature", "tls")).render_short_html(&names),
+            "Unix and TLS support"
+        );
+        assert_eq!(
+            (word_cfg("unix") | word_cfg("windows")).render_long_html(&names),
+            "This is supported on <strong>desktop &lt;OS&gt;</strong> only."
+        );
+        assert_eq!(
+            name_value_cfg("feature", "std").render_short_html(&names),
+            "<code>feature=\"std\"</code>"
+        );
+    })
+}
+
+#[test]
+fn test_strip_hidden() {
+    with_default_globals(|| {
+        let hidden = vec![name_value_cfg("feature", "std"), word_cfg("test")]
+            .into_iter()
+            .collect::<FxHashSet<_>>();
+
+        assert_eq!(word_cfg("unix").strip_hidden(&hidden), Some(word_cfg("unix")));
+        assert_eq!(name_value_cfg("feature", "std").strip_hidden(&hidden), None);
+        assert_eq!((!word_cfg("test")).strip_hidden(&hidden), None);
+        assert_eq!(
+            (word_cfg("unix") & name_value_cfg("feature", "std")).strip_hidden(&hidden),
+            Some(word_cfg("unix"))
+        );
+        assert_eq!(
+            (word_cfg("unix") | word_cfg("windows") | word_cfg("test")).strip_hidden(&hidden),
+            Some(word_cfg("unix") | word_cfg("windows"))
+        );
+        assert_eq!(
+            (name_value_cfg("feature", "std") & !word_cfg("test")).strip_hidden(&hidden),
+            None
+        );
+    })
+}
+
 #[test]
 fn test_render_short_html() {
     with_default_globals(|| {
         assert_eq!(
-            word_cfg("unix").render_short_html(),
+            word_cfg("unix").render_short_html(&CfgNames::default()),
             "Unix"
         );
         assert_eq!(
-            name_value_cfg("target_os", "macos").render_short_html(),
+            name_value_cfg("target_os", "macos").render_short_html(&CfgNames::default()),
             "macOS"
         );
         assert_eq!(
-            name_value_cfg("target_pointer_width", "16").render_short_html(),
+            name_value_cfg("target_pointer_width", "16").render_short_html(&CfgNames::default()),
             "16-bit"
         );
         assert_eq!(
-            name_value_cfg("target_endian", "little").render_short_html(),
+            name_value_cfg("target_endian", "little").render_short_html(&CfgNames::default()),
             "Little-endian"
         );
         assert_eq!(
-            (!word_cfg("windows")).render_short_html(),
+            (!word_cfg("windows")).render_short_html(&CfgNames::default()),
             "Non-Windows"
         );
         assert_eq!(
-            (word_cfg("unix") & word_cfg("windows")).render_short_html(),
+            (word_cfg("unix") & word_cfg("windows")).render_short_html(&CfgNames::default()),
             "Unix and Windows"
         );
         assert_eq!(
-            (word_cfg("unix") | word_cfg("windows")).render_short_html(),
+            (word_cfg("unix") | word_cfg("windows")).render_short_html(&CfgNames::default()),
             "Unix or Windows"
         );
         assert_eq!(
             (
                 word_cfg("unix") & word_cfg("windows") & word_cfg("debug_assertions")
-            ).render_short_html(),
+            ).render_short_html(&CfgNames::default()),
             "Unix and Windows and debug-assertions enabled"
         );
         assert_eq!(
             (
                 word_cfg("unix") | word_cfg("windows") | word_cfg("debug_assertions")
-            ).render_short_html(),
+            ).render_short_html(&CfgNames::default()),
             "Unix or Windows or debug-assertions enabled"
         );
         assert_eq!(
             (
                 !(word_cfg("unix") | word_cfg("windows") | word_cfg("debug_assertions"))
-            ).render_short_html(),
+            ).render_short_html(&CfgNames::default()),
             "Neither Unix nor Windows nor debug-assertions enabled"
         );
         assert_eq!(
             (
                 (word_cfg("unix") & name_value_cfg("target_arch", "x86_64")) |
                 (word_cfg("windows") & name_value_cfg("target_pointer_width", "64"))
-            ).render_short_html(),
+            ).render_short_html(&CfgNames::default()),
             "Unix and x86-64, or Windows and 64-bit"
         );
         assert_eq!(
-            (!(word_cfg("unix") & word_cfg("windows"))).render_short_html(),
+            (!(word_cfg("unix") & word_cfg("windows"))).render_short_html(&CfgNames::default()),
             "Not (Unix and Windows)"
         );
         assert_eq!(
             (
                 (word_cfg("debug_assertions") | word_cfg("windows")) & word_cfg("unix")
-            ).render_short_html(),
+            ).render_short_html(&CfgNames::default()),
             "(Debug-assertions enabled or Windows) and Unix"
         );
         assert_eq!(
-            name_value_cfg("target_feature", "sse2").render_short_html(),
+            name_value_cfg("target_feature", "sse2").render_short_html(&CfgNames::default()),
             "<code>sse2</code>"
         );
         assert_eq!(
             (
                 name_value_cfg("target_arch", "x86_64") &
                 name_value_cfg("target_feature", "sse2")
-            ).render_short_html(),
+            ).render_short_html(&CfgNames::default()),
             "x86-64 and <code>sse2</code>"
         );
     })
@@ -329,51 +533,51 @@ fn test_render_short_html() {
 fn test_render_long_html() {
     with_default_globals(|| {
         assert_eq!(
-            word_cfg("unix").render_long_html(),
+            word_cfg("unix").render_long_html(&CfgNames::default()),
             "This is supported on <strong>Unix</strong> only."
         );
         assert_eq!(
-            name_value_cfg("target_os", "macos").render_long_html(),
+            name_value_cfg("target_os", "macos").render_long_html(&CfgNames::default()),
             "This is supported on <strong>macOS</strong> only."
         );
         assert_eq!(
-            name_value_cfg("target_pointer_width", "16").render_long_html(),
+            name_value_cfg("target_pointer_width", "16").render_long_html(&CfgNames::default()),
             "This is supported on <strong>16-bit</strong> only."
         );
         assert_eq!(
-            name_value_cfg("target_endian", "little").render_long_html(),
+            name_value_cfg("target_endian", "little").render_long_html(&CfgNames::default()),
             "This is supported on <strong>little-endian</strong> only."
         );
         assert_eq!(
-            (!word_cfg("windows")).render_long_html(),
+            (!word_cfg("windows")).render_long_html(&CfgNames::default()),
             "This is supported on <strong>non-Windows</strong> only."
         );
         assert_eq!(
-            (word_cfg("unix") & word_cfg("windows")).render_long_html(),
+            (word_cfg("unix") & word_cfg("windows")).render_long_html(&CfgNames::default()),
             "This is supported on <strong>Unix and Windows</strong> only."
         );
         assert_eq!(
-            (word_cfg("unix") | word_cfg("windows")).render_long_html(),
+            (word_cfg("unix") | word_cfg("windows")).render_long_html(&CfgNames::default()),
             "This is supported on <strong>Unix or Windows</strong> only."
         );
         assert_eq!(
             (
                 word_cfg("unix") & word_cfg("windows") & word_cfg("debug_assertions")
-            ).render_long_html(),
+            ).render_long_html(&CfgNames::default()),
             "This is supported on <strong>Unix and Windows and debug-assertions enabled\
                 </strong> only."
         );
         assert_eq!(
             (
                 word_cfg("unix") | word_cfg("windows") | word_cfg("debug_assertions")
-            ).render_long_html(),
+            ).render_long_html(&CfgNames::default()),
             "This is supported on <strong>Unix or Windows or debug-assertions enabled\
                 </strong> only."
         );
         assert_eq!(
             (
                 !(word_cfg("unix") | word_cfg("windows") | word_cfg("debug_assertions"))
-            ).render_long_html(),
+            ).render_long_html(&CfgNames::default()),
             "This is supported on <strong>neither Unix nor Windows nor debug-assertions \
                 enabled</strong>."
         );
@@ -381,30 +585,30 @@ fn test_render_long_html() {
             (
                 (word_cfg("unix") & name_value_cfg("target_arch", "x86_64")) |
                 (word_cfg("windows") & name_value_cfg("target_pointer_width", "64"))
-            ).render_long_html(),
+            ).render_long_html(&CfgNames::default()),
             "This is supported on <strong>Unix and x86-64, or Windows and 64-bit</strong> \
                 only."
         );
         assert_eq!(
-            (!(word_cfg("unix") & word_cfg("windows"))).render_long_html(),
+            (!(word_cfg("unix") & word_cfg("windows"))).render_long_html(&CfgNames::default()),
             "This is supported on <strong>not (Unix and Windows)</strong>."
         );
         assert_eq!(
             (
                 (word_cfg("debug_assertions") | word_cfg("windows")) & word_cfg("unix")
-            ).render_long_html(),
+            ).render_long_html(&CfgNames::default()),
             "This is supported on <strong>(debug-assertions enabled or Windows) and Unix\
             </strong> only."
         );
         assert_eq!(
-            name_value_cfg("target_feature", "sse2").render_long_html(),
+            name_value_cfg("target_feature", "sse2").render_long_html(&CfgNames::default()),
             "This is supported with <strong>target feature <code>sse2</code></strong> only."
         );
         assert_eq!(
             (
                 name_value_cfg("target_arch", "x86_64") &
                 name_value_cfg("target_feature", "sse2")
-            ).render_long_html(),
+            ).render_long_html(&CfgNames::default()),
             "This is supported on <strong>x86-64 and target feature \
             <code>sse2</code></strong> only."
         );
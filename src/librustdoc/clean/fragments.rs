@@ -0,0 +1,261 @@
+//! Named pieces of documentation declared with `#[doc(fragment = "name")]`, reused in other docs
+//! through `{{include:name}}`.
+//!
+//! The fragments are collected from the HIR before cleaning starts, so that the references can be
+//! resolved while the attributes are cleaned whatever order the items are cleaned in. Tools
+//! calling `build` without running the passes thus see the resolved text as well.
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::def_id::LOCAL_CRATE;
+use rustc::session::Session;
+use rustc::ty::TyCtxt;
+use rustc::util::nodemap::FxHashMap;
+use syntax::ast;
+use syntax::symbol::{sym, Symbol};
+use syntax_pos::Span;
+
+use crate::clean::{Attributes, AttributesExt, DocFragment};
+use crate::html::escape::Escape;
+use crate::passes::span_of_attrs;
+
+const INCLUDE_START: &str = "{{include:";
+const INCLUDE_END: &str = "}}";
+
+/// The fragments declared in the crate, along with the ones expanded so far.
+#[derive(Default)]
+pub struct DocFragments {
+    defs: FxHashMap<String, FragmentDef>,
+    /// Fully expanded fragment texts, including their provenance notes.
+    expanded: FxHashMap<String, String>,
+}
+
+/// A named piece of documentation declared with `#[doc(fragment = "name")]`.
+struct FragmentDef {
+    /// The unindented documentation of the item carrying the attribute.
+    text: String,
+    /// The path of the item carrying the attribute, used for the provenance note.
+    source: String,
+    span: Span,
+}
+
+crate fn collect_fragments(tcx: TyCtxt<'_>) -> DocFragments {
+    let krate = tcx.hir().krate();
+    let mut collector = FragmentCollector { tcx, path: vec![], fragments: Default::default() };
+    collector.record(tcx.crate_name(LOCAL_CRATE), &krate.attrs, krate.span);
+    intravisit::walk_crate(&mut collector, krate);
+    collector.fragments
+}
+
+struct FragmentCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The module path leading to the item currently being visited.
+    path: Vec<String>,
+    fragments: DocFragments,
+}
+
+impl<'tcx> FragmentCollector<'tcx> {
+    fn record(&mut self, item_name: Symbol, attrs: &[ast::Attribute], item_span: Span) {
+        let mut names = attrs.lists(sym::doc).filter(|a| a.check_name(sym::fragment)).peekable();
+        if names.peek().is_none() {
+            return;
+        }
+
+        let mut docs = Attributes::from_ast(self.tcx.sess.diagnostic(), attrs);
+        docs.unindent_doc_comments();
+        let span = span_of_attrs(&docs).unwrap_or(item_span);
+
+        for attr in names {
+            let name = match attr.value_str() {
+                Some(name) => name.to_string(),
+                None => {
+                    self.tcx.sess.struct_span_err(
+                        attr.span(),
+                        "`#[doc(fragment)]` must be given a name: `#[doc(fragment = \"name\")]`",
+                    ).emit();
+                    continue;
+                }
+            };
+
+            if let Some(previous) = self.fragments.defs.get(&name) {
+                self.tcx.sess.struct_span_err(
+                    span,
+                    &format!("doc fragment `{}` is defined multiple times", name),
+                )
+                .span_note(previous.span, "previous definition here")
+                .emit();
+                continue;
+            }
+
+            let source = self.path.iter()
+                .cloned()
+                .chain(Some(item_name.to_string()))
+                .collect::<Vec<_>>()
+                .join("::");
+            self.fragments.defs.insert(name, FragmentDef {
+                text: docs.collapsed_doc_value().unwrap_or_default(),
+                source,
+                span,
+            });
+        }
+    }
+}
+
+impl<'tcx> intravisit::Visitor<'tcx> for FragmentCollector<'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+        intravisit::NestedVisitorMap::All(&self.tcx.hir())
+    }
+
+    fn visit_item(&mut self, item: &'tcx hir::Item) {
+        self.record(item.ident.name, &item.attrs, item.span);
+        if let hir::ItemKind::Mod(..) = item.kind {
+            self.path.push(item.ident.to_string());
+            intravisit::walk_item(self, item);
+            self.path.pop();
+        } else {
+            intravisit::walk_item(self, item);
+        }
+    }
+
+    fn visit_trait_item(&mut self, item: &'tcx hir::TraitItem) {
+        self.record(item.ident.name, &item.attrs, item.span);
+        intravisit::walk_trait_item(self, item);
+    }
+
+    fn visit_impl_item(&mut self, item: &'tcx hir::ImplItem) {
+        self.record(item.ident.name, &item.attrs, item.span);
+        intravisit::walk_impl_item(self, item);
+    }
+
+    fn visit_foreign_item(&mut self, item: &'tcx hir::ForeignItem) {
+        self.record(item.ident.name, &item.attrs, item.span);
+        intravisit::walk_foreign_item(self, item);
+    }
+
+    fn visit_struct_field(&mut self, field: &'tcx hir::StructField) {
+        self.record(field.ident.name, &field.attrs, field.span);
+        intravisit::walk_struct_field(self, field);
+    }
+
+    fn visit_variant(
+        &mut self,
+        variant: &'tcx hir::Variant,
+        generics: &'tcx hir::Generics,
+        item_id: hir::HirId,
+    ) {
+        self.record(variant.ident.name, &variant.attrs, variant.span);
+        intravisit::walk_variant(self, variant, generics, item_id);
+    }
+
+    fn visit_macro_def(&mut self, macro_def: &'tcx hir::MacroDef) {
+        self.record(macro_def.name, &macro_def.attrs, macro_def.span);
+        intravisit::walk_macro_def(self, macro_def);
+    }
+}
+
+impl DocFragments {
+    /// Replaces every `{{include:name}}` in the docs of `attrs` with the expanded fragment.
+    /// References that cannot be resolved are reported and left untouched.
+    crate fn resolve(&mut self, sess: &Session, attrs: &mut Attributes) {
+        if self.defs.is_empty() {
+            return;
+        }
+
+        let span = match span_of_attrs(attrs) {
+            Some(span) => span,
+            None => return,
+        };
+        for fragment in &mut attrs.doc_strings {
+            let doc_string = match fragment {
+                DocFragment::SugaredDoc(_, _, ref mut doc_string)
+                | DocFragment::RawDoc(_, _, ref mut doc_string)
+                | DocFragment::Include(_, _, _, ref mut doc_string) => doc_string,
+            };
+            if doc_string.contains(INCLUDE_START) {
+                *doc_string = self.substitute(sess, doc_string, span, &mut vec![]);
+            }
+        }
+    }
+
+    fn substitute(
+        &mut self,
+        sess: &Session,
+        text: &str,
+        span: Span,
+        stack: &mut Vec<String>,
+    ) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find(INCLUDE_START) {
+            let after = &rest[start + INCLUDE_START.len()..];
+            let end = match after.find(INCLUDE_END) {
+                Some(end) => end,
+                None => break,
+            };
+            let name = after[..end].trim();
+            let reference_len = INCLUDE_START.len() + end + INCLUDE_END.len();
+
+            out.push_str(&rest[..start]);
+            match self.expand(sess, name, span, stack) {
+                Some(expanded) => out.push_str(&expanded),
+                None => out.push_str(&rest[start..start + reference_len]),
+            }
+            rest = &after[end + INCLUDE_END.len()..];
+        }
+
+        out.push_str(rest);
+        out
+    }
+
+    fn expand(
+        &mut self,
+        sess: &Session,
+        name: &str,
+        span: Span,
+        stack: &mut Vec<String>,
+    ) -> Option<String> {
+        if let Some(text) = self.expanded.get(name) {
+            return Some(text.clone());
+        }
+
+        if let Some(pos) = stack.iter().position(|n| n == name) {
+            let cycle = stack[pos..].iter()
+                .map(|n| n.as_str())
+                .chain(Some(name))
+                .map(|n| format!("`{}`", n))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            sess.struct_span_err(
+                span,
+                &format!("cycle detected while including doc fragment `{}`", name),
+            )
+            .note(&format!("the fragments include each other: {}", cycle))
+            .emit();
+            return None;
+        }
+
+        let (text, source, def_span) = match self.defs.get(name) {
+            Some(def) => (def.text.clone(), def.source.clone(), def.span),
+            None => {
+                sess.struct_span_err(span, &format!("cannot find doc fragment `{}`", name))
+                    .help("fragments are declared with `#[doc(fragment = \"name\")]`")
+                    .emit();
+                return None;
+            }
+        };
+
+        stack.push(name.to_owned());
+        let text = self.substitute(sess, &text, def_span, stack);
+        stack.pop();
+
+        let expanded = format!(
+            "{}\n\n<div class=\"doc-fragment-source\">Shared documentation from the \
+             <code>{}</code> fragment of <code>{}</code>.</div>\n",
+            text.trim_end(),
+            Escape(name),
+            Escape(&source),
+        );
+        self.expanded.insert(name.to_owned(), expanded.clone());
+        Some(expanded)
+    }
+}
@@ -3,23 +3,25 @@
 use std::iter::once;
 
 use syntax::ast;
-use syntax::symbol::sym;
+use syntax::attr;
+use syntax::symbol::{kw, sym};
 use syntax_pos::hygiene::MacroKind;
 use syntax_pos::Span;
 
 use rustc::hir;
 use rustc::hir::def::{Res, DefKind, CtorKind};
-use rustc::hir::def_id::DefId;
+use rustc::hir::def_id::{CRATE_DEF_INDEX, DefId, LOCAL_CRATE};
 use rustc_metadata::creader::LoadedMacro;
-use rustc::ty;
-use rustc::util::nodemap::FxHashSet;
+use rustc::ty::{self, DefIdTree};
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
 
 use crate::core::DocContext;
 use crate::doctree;
 use crate::clean::{
     self,
+    AttributesExt,
     GetDefId,
-    ToSource,
+    NestedAttributesExt,
     TypeKind
 };
 
@@ -40,7 +42,7 @@ type Attrs<'hir> = rustc::ty::Attributes<'hir>;
 /// The returned value is `None` if the definition could not be inlined,
 /// and `Some` of a vector of items if it was successfully expanded.
 pub fn try_inline(
-    cx: &DocContext<'_>,
+    cx: &mut DocContext<'_>,
     res: Res,
     name: ast::Name,
     attrs: Option<Attrs<'_>>,
@@ -60,7 +62,9 @@ pub fn try_inline(
         Res::Def(DefKind::Trait, did) => {
             record_extern_fqn(cx, did, clean::TypeKind::Trait);
             ret.extend(build_impls(cx, did, attrs));
-            clean::TraitItem(build_external_trait(cx, did))
+            let mut trait_ = build_external_trait(cx, did);
+            fill_in_default_impls(cx, &mut trait_);
+            clean::TraitItem(trait_)
         }
         Res::Def(DefKind::Fn, did) => {
             record_extern_fqn(cx, did, clean::TypeKind::Function);
@@ -98,7 +102,7 @@ pub fn try_inline(
         Res::Def(DefKind::Ctor(..), _) | Res::SelfCtor(..) => return Some(Vec::new()),
         Res::Def(DefKind::Mod, did) => {
             record_extern_fqn(cx, did, clean::TypeKind::Module);
-            clean::ModuleItem(build_module(cx, did, visited))
+            clean::ModuleItem(build_module(cx, did, visited, false))
         }
         Res::Def(DefKind::Static, did) => {
             record_extern_fqn(cx, did, clean::TypeKind::Static);
@@ -123,23 +127,24 @@ pub fn try_inline(
     };
 
     let target_attrs = load_attrs(cx, did);
-    let attrs = merge_attrs(cx, target_attrs,  attrs_clone);
+    let attrs = merge_attrs(cx, did, target_attrs,  attrs_clone);
 
-    cx.renderinfo.borrow_mut().inlined.insert(did);
+    cx.renderinfo.inlined.insert(did);
     ret.push(clean::Item {
         source: cx.tcx.def_span(did).clean(cx),
-        name: Some(name.clean(cx)),
+        name: Some(name),
         attrs,
         inner,
         visibility: clean::Public,
         stability: cx.tcx.lookup_stability(did).clean(cx),
         deprecation: cx.tcx.lookup_deprecation(did).clean(cx),
+        canonical_path: None,
         def_id: did,
     });
     Some(ret)
 }
 
-pub fn try_inline_glob(cx: &DocContext<'_>, res: Res, visited: &mut FxHashSet<DefId>)
+pub fn try_inline_glob(cx: &mut DocContext<'_>, res: Res, visited: &mut FxHashSet<DefId>)
     -> Option<Vec<clean::Item>>
 {
     if res == Res::Err { return None }
@@ -148,7 +153,7 @@ pub fn try_inline_glob(cx: &DocContext<'_>, res: Res, visited: &mut FxHashSet<De
 
     match res {
         Res::Def(DefKind::Mod, did) => {
-            let m = build_module(cx, did, visited);
+            let m = build_module(cx, did, visited, true);
             Some(m.items)
         }
         // glob imports on things like enums aren't inlined even for local exports, so just bail
@@ -164,7 +169,14 @@ pub fn load_attrs<'hir>(cx: &DocContext<'hir>, did: DefId) -> Attrs<'hir> {
 ///
 /// These names are used later on by HTML rendering to generate things like
 /// source links back to the original item.
-pub fn record_extern_fqn(cx: &DocContext<'_>, did: DefId, kind: clean::TypeKind) {
+///
+/// This is called every time a path is cleaned, so the name of each `DefId` is only computed the
+/// first time it is recorded.
+pub fn record_extern_fqn(cx: &mut DocContext<'_>, did: DefId, kind: clean::TypeKind) {
+    if cx.extern_fqns.contains_key(&did) {
+        return;
+    }
+
     let crate_name = cx.tcx.crate_name(did.krate).to_string();
 
     let relative = cx.tcx.def_path(did).data.into_iter().filter_map(|elem| {
@@ -178,25 +190,47 @@ pub fn record_extern_fqn(cx: &DocContext<'_>, did: DefId, kind: clean::TypeKind)
     });
     let fqn = if let clean::TypeKind::Macro = kind {
         vec![crate_name, relative.last().expect("relative was empty")]
+    } else if let Some(path) = visible_path(cx, did) {
+        // Items re-exported from a private module are documented where they are re-exported.
+        path
     } else {
         once(crate_name).chain(relative).collect()
     };
 
-    if did.is_local() {
-        cx.renderinfo.borrow_mut().exact_paths.insert(did, fqn);
-    } else {
-        cx.renderinfo.borrow_mut().external_paths.insert(did, (fqn, kind));
+    cx.extern_fqns.entry(did).or_insert((fqn, kind));
+}
+
+/// Returns the public path of the external item `did`, going through the re-exports of the
+/// private modules it is defined in, if it can be reached from the root of its crate.
+fn visible_path(cx: &DocContext<'_>, did: DefId) -> Option<Vec<String>> {
+    let visible_parents = cx.tcx.visible_parent_map(LOCAL_CRATE);
+    let mut path = vec![];
+    let mut current = did;
+    while current.index != CRATE_DEF_INDEX {
+        let parent = *visible_parents.get(&current)?;
+        if parent.krate != did.krate {
+            return None;
+        }
+        // The re-export may rename the item.
+        let name = cx.tcx.item_children(parent).iter()
+            .find(|child| child.res.opt_def_id() == Some(current))?
+            .ident;
+        path.push(name.to_string());
+        current = parent;
     }
+    path.push(cx.tcx.crate_name(did.krate).to_string());
+    path.reverse();
+    Some(path)
 }
 
-pub fn build_external_trait(cx: &DocContext<'_>, did: DefId) -> clean::Trait {
+pub fn build_external_trait(cx: &mut DocContext<'_>, did: DefId) -> clean::Trait {
     let auto_trait = cx.tcx.trait_def(did).has_auto_impl;
     let trait_items = cx.tcx.associated_items(did).map(|item| item.clean(cx)).collect();
     let predicates = cx.tcx.predicates_of(did);
     let generics = (cx.tcx.generics_of(did), predicates).clean(cx);
     let generics = filter_non_trait_generics(did, generics);
     let (generics, supertrait_bounds) = separate_supertrait_bounds(generics);
-    let is_spotlight = load_attrs(cx, did).clean(cx).has_doc_flag(sym::spotlight);
+    let is_notable = clean::utils::record_notable_trait(cx, did);
     let is_auto = cx.tcx.trait_is_auto(did);
     clean::Trait {
         auto: auto_trait,
@@ -204,12 +238,12 @@ pub fn build_external_trait(cx: &DocContext<'_>, did: DefId) -> clean::Trait {
         generics,
         items: trait_items,
         bounds: supertrait_bounds,
-        is_spotlight,
+        is_notable,
         is_auto,
     }
 }
 
-fn build_external_function(cx: &DocContext<'_>, did: DefId) -> clean::Function {
+fn build_external_function(cx: &mut DocContext<'_>, did: DefId) -> clean::Function {
     let sig = cx.tcx.fn_sig(did);
 
     let constness = if cx.tcx.is_min_const_fn(did) {
@@ -219,7 +253,7 @@ fn build_external_function(cx: &DocContext<'_>, did: DefId) -> clean::Function {
     };
     let asyncness =  cx.tcx.asyncness(did);
     let predicates = cx.tcx.predicates_of(did);
-    let (generics, decl) = clean::enter_impl_trait(cx, || {
+    let (generics, decl) = clean::enter_impl_trait(cx, |cx| {
         ((cx.tcx.generics_of(did), predicates).clean(cx), (did, sig).clean(cx))
     });
     let (all_types, ret_types) = clean::get_all_types(&generics, &decl, cx);
@@ -237,17 +271,18 @@ fn build_external_function(cx: &DocContext<'_>, did: DefId) -> clean::Function {
     }
 }
 
-fn build_enum(cx: &DocContext<'_>, did: DefId) -> clean::Enum {
+fn build_enum(cx: &mut DocContext<'_>, did: DefId) -> clean::Enum {
     let predicates = cx.tcx.explicit_predicates_of(did);
 
     clean::Enum {
         generics: (cx.tcx.generics_of(did), predicates).clean(cx),
         variants_stripped: false,
         variants: cx.tcx.adt_def(did).variants.clean(cx),
+        layout: clean::utils::type_layout(cx, did),
     }
 }
 
-fn build_struct(cx: &DocContext<'_>, did: DefId) -> clean::Struct {
+fn build_struct(cx: &mut DocContext<'_>, did: DefId) -> clean::Struct {
     let predicates = cx.tcx.explicit_predicates_of(did);
     let variant = cx.tcx.adt_def(did).non_enum_variant();
 
@@ -260,10 +295,11 @@ fn build_struct(cx: &DocContext<'_>, did: DefId) -> clean::Struct {
         generics: (cx.tcx.generics_of(did), predicates).clean(cx),
         fields: variant.fields.clean(cx),
         fields_stripped: false,
+        layout: clean::utils::type_layout(cx, did),
     }
 }
 
-fn build_union(cx: &DocContext<'_>, did: DefId) -> clean::Union {
+fn build_union(cx: &mut DocContext<'_>, did: DefId) -> clean::Union {
     let predicates = cx.tcx.explicit_predicates_of(did);
     let variant = cx.tcx.adt_def(did).non_enum_variant();
 
@@ -272,10 +308,11 @@ fn build_union(cx: &DocContext<'_>, did: DefId) -> clean::Union {
         generics: (cx.tcx.generics_of(did), predicates).clean(cx),
         fields: variant.fields.clean(cx),
         fields_stripped: false,
+        layout: clean::utils::type_layout(cx, did),
     }
 }
 
-fn build_type_alias(cx: &DocContext<'_>, did: DefId) -> clean::Typedef {
+fn build_type_alias(cx: &mut DocContext<'_>, did: DefId) -> clean::Typedef {
     let predicates = cx.tcx.explicit_predicates_of(did);
 
     clean::Typedef {
@@ -284,7 +321,9 @@ fn build_type_alias(cx: &DocContext<'_>, did: DefId) -> clean::Typedef {
     }
 }
 
-pub fn build_impls(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>) -> Vec<clean::Item> {
+pub fn build_impls(cx: &mut DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>)
+    -> Vec<clean::Item>
+{
     let tcx = cx.tcx;
     let mut impls = Vec::new();
 
@@ -295,7 +334,107 @@ pub fn build_impls(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>) ->
     impls
 }
 
-fn merge_attrs(cx: &DocContext<'_>, attrs: Attrs<'_>, other_attrs: Option<Attrs<'_>>
+/// The trait impls of every dependency, indexed by the item they are written for and by the trait
+/// they implement.
+///
+/// Cleaning an impl is expensive and most of the impls of a large dependency tree concern types
+/// that never show up in the documentation, so `collect-trait-impls` only asks for the impls of the
+/// items it knows are documented.
+#[derive(Default)]
+pub struct ExternalImplIndex {
+    by_item: FxHashMap<DefId, Vec<DefId>>,
+    /// Impls that can't be attributed to a single item, like impls on primitives or blanket impls.
+    /// These are always inlined.
+    unkeyed: Vec<DefId>,
+}
+
+impl ExternalImplIndex {
+    fn new(cx: &DocContext<'_>) -> ExternalImplIndex {
+        let mut index = ExternalImplIndex::default();
+
+        for &cnum in cx.tcx.crates().iter() {
+            for &did in cx.tcx.all_trait_implementations(cnum).iter() {
+                match impl_self_item(cx.tcx.type_of(did)) {
+                    Some(self_did) => {
+                        index.by_item.entry(self_did).or_default().push(did);
+                        if let Some(trait_ref) = cx.tcx.impl_trait_ref(did) {
+                            index.by_item.entry(trait_ref.def_id).or_default().push(did);
+                        }
+                    }
+                    None => index.unkeyed.push(did),
+                }
+            }
+        }
+
+        index
+    }
+}
+
+/// Returns the item an impl on `ty` is attached to in the documentation, mirroring what
+/// `clean::Type::def_id` returns for the cleaned type.
+fn impl_self_item(ty: ty::Ty<'_>) -> Option<DefId> {
+    match ty.kind {
+        ty::Adt(def, _) => Some(def.did),
+        ty::Foreign(did) => Some(did),
+        ty::Dynamic(ref preds, _) => preds.principal_def_id(),
+        ty::Ref(_, ty, _) => impl_self_item(ty),
+        _ => None,
+    }
+}
+
+/// The index of the trait impls of every dependency, built the first time it's needed.
+fn external_impl_index<'a>(cx: &'a mut DocContext<'_>) -> &'a mut ExternalImplIndex {
+    if cx.external_impls.is_none() {
+        cx.external_impls = Some(ExternalImplIndex::new(cx));
+    }
+    cx.external_impls.as_mut().unwrap()
+}
+
+/// Inlines the external trait impls written for, or implementing, the item `did`.
+///
+/// Each impl is only ever inlined once, no matter how many times it's requested.
+pub fn build_external_impls_for(cx: &mut DocContext<'_>, did: DefId, ret: &mut Vec<clean::Item>) {
+    let impls = external_impl_index(cx).by_item.remove(&did).unwrap_or_default();
+
+    for impl_did in impls {
+        build_impl(cx, impl_did, None, ret);
+    }
+}
+
+/// Inlines the external `Deref` impl written for the item `did`, if any. Inlining it also inlines
+/// the inherent impls of its target and follows the target's own `Deref` impl, and so on.
+pub fn build_external_deref_impl(cx: &mut DocContext<'_>, did: DefId, ret: &mut Vec<clean::Item>) {
+    let deref_trait = match cx.tcx.lang_items().deref_trait() {
+        Some(deref_trait) => deref_trait,
+        None => return,
+    };
+    let tcx = cx.tcx;
+    let index = external_impl_index(cx);
+    let deref_impl = index.by_item.get(&did).and_then(|impls| {
+        impls.iter().cloned().find(|&impl_did| {
+            tcx.impl_trait_ref(impl_did).map_or(false, |trait_ref| {
+                trait_ref.def_id == deref_trait
+                    && impl_self_item(trait_ref.self_ty()) == Some(did)
+            })
+        })
+    });
+
+    // `build_impl` skips impls that were already inlined, which also ends `Deref` cycles.
+    if let Some(impl_did) = deref_impl {
+        build_impl(cx, impl_did, None, ret);
+    }
+}
+
+/// Inlines the external trait impls that can't be attributed to a single item.
+pub fn build_unkeyed_external_impls(cx: &mut DocContext<'_>, ret: &mut Vec<clean::Item>) {
+    let impls = std::mem::take(&mut external_impl_index(cx).unkeyed);
+
+    for impl_did in impls {
+        build_impl(cx, impl_did, None, ret);
+    }
+}
+
+fn merge_attrs(cx: &mut DocContext<'_>, did: DefId, attrs: Attrs<'_>, other_attrs: Option<Attrs<'_>>
 ) -> clean::Attributes {
     let mut merged_attrs: Vec<ast::Attribute> = Vec::with_capacity(attrs.len());
     // If we have additional attributes (from a re-export),
@@ -306,17 +445,46 @@ fn merge_attrs(cx: &DocContext<'_>, attrs: Attrs<'_>, other_attrs: Option<Attrs<
         merged_attrs.extend(a.iter().cloned());
     }
     merged_attrs.extend(attrs.to_vec());
-    merged_attrs.clean(cx)
+    let mut cleaned = merged_attrs.clean(cx);
+    if !did.is_local() {
+        let inner_docs = attrs.iter()
+            .find(|attr| attr.check_name(sym::doc))
+            .map_or(true, |attr| attr.style == ast::AttrStyle::Inner);
+        cleaned.scope = Some(doc_scope(cx, did, inner_docs));
+    }
+    cleaned
 }
 
-pub fn build_impl(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
+/// Returns the module whose scope the documentation of `did` was written in: the module itself
+/// for the inner documentation of a module, and the one containing the item otherwise.
+fn doc_scope(cx: &DocContext<'_>, did: DefId, inner_docs: bool) -> DefId {
+    let mut scope = if inner_docs { did } else { cx.tcx.parent(did).unwrap_or(did) };
+    while cx.tcx.def_kind(scope) != Some(DefKind::Mod) {
+        match cx.tcx.parent(scope) {
+            Some(parent) => scope = parent,
+            None => break,
+        }
+    }
+    scope
+}
+
+/// Removes `#[doc(hidden)]` from an inlined item, so that it is documented at the path of the
+/// re-export that inlined it instead of being stripped.
+pub fn unhide(item: &mut clean::Item) {
+    item.attrs.other_attrs.retain(|attr| {
+        !attr.check_name(sym::doc) ||
+            !attr.meta_item_list().map_or(false, |l| attr::list_contains_name(&l, sym::hidden))
+    });
+}
+
+pub fn build_impl(cx: &mut DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
                   ret: &mut Vec<clean::Item>
 ) {
-    if !cx.renderinfo.borrow_mut().inlined.insert(did) {
+    if !cx.renderinfo.inlined.insert(did) {
         return
     }
 
-    let attrs = merge_attrs(cx, load_attrs(cx, did), attrs);
+    let attrs = merge_attrs(cx, did, load_attrs(cx, did), attrs);
 
 
     let tcx = cx.tcx;
@@ -326,7 +494,7 @@ pub fn build_impl(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
     // reachable in rustdoc generated documentation
     if !did.is_local() {
         if let Some(traitref) = associated_trait {
-            if !cx.renderinfo.borrow().access_levels.is_public(traitref.def_id) {
+            if !cx.renderinfo.access_levels.is_public(traitref.def_id) {
                 return
             }
         }
@@ -347,14 +515,14 @@ pub fn build_impl(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
     // reachable in rustdoc generated documentation
     if !did.is_local() {
         if let Some(did) = for_.def_id() {
-            if !cx.renderinfo.borrow().access_levels.is_public(did) {
+            if !cx.renderinfo.access_levels.is_public(did) {
                 return
             }
         }
     }
 
     let predicates = tcx.explicit_predicates_of(did);
-    let (trait_items, generics) = if let Some(hir_id) = tcx.hir().as_local_hir_id(did) {
+    let (mut trait_items, generics) = if let Some(hir_id) = tcx.hir().as_local_hir_id(did) {
         match tcx.hir().expect_item(hir_id).kind {
             hir::ItemKind::Impl(.., ref gen, _, _, ref item_ids) => {
                 (
@@ -375,7 +543,7 @@ pub fn build_impl(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
                     None
                 }
             }).collect::<Vec<_>>(),
-            clean::enter_impl_trait(cx, || {
+            clean::enter_impl_trait(cx, |cx| {
                 (tcx.generics_of(did), predicates).clean(cx)
             }),
         )
@@ -392,6 +560,7 @@ pub fn build_impl(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
     }
     if let Some(trait_did) = trait_.def_id() {
         record_extern_trait(cx, trait_did);
+        clean::utils::record_notable_trait(cx, trait_did);
     }
 
     let provided = trait_.def_id().map(|did| {
@@ -403,6 +572,10 @@ pub fn build_impl(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
 
     debug!("build_impl: impl {:?} for {:?}", trait_.def_id(), for_.def_id());
 
+    let deprecation = tcx.lookup_deprecation(did).clean(cx);
+    let inherited = clean::ImplAttrs::new(&attrs, deprecation.as_ref());
+    inherited.apply(&mut trait_items);
+
     ret.push(clean::Item {
         inner: clean::ImplItem(clean::Impl {
             unsafety: hir::Unsafety::Normal,
@@ -414,31 +587,38 @@ pub fn build_impl(cx: &DocContext<'_>, did: DefId, attrs: Option<Attrs<'_>>,
             polarity: Some(polarity.clean(cx)),
             synthetic: false,
             blanket_impl: None,
+            inherited,
         }),
         source: tcx.def_span(did).clean(cx),
         name: None,
         attrs,
         visibility: clean::Inherited,
         stability: tcx.lookup_stability(did).clean(cx),
-        deprecation: tcx.lookup_deprecation(did).clean(cx),
+        deprecation,
+        canonical_path: None,
         def_id: did,
     });
 }
 
+/// Builds the contents of the external module `did`.
+///
+/// When `glob` is set the module is being inlined through a glob import, in which case its items
+/// can opt out of inlining with their own `#[doc(no_inline)]` or `#[doc(hidden)]` attributes.
 fn build_module(
-    cx: &DocContext<'_>,
+    cx: &mut DocContext<'_>,
     did: DefId,
-    visited: &mut FxHashSet<DefId>
+    visited: &mut FxHashSet<DefId>,
+    glob: bool,
 ) -> clean::Module {
     let mut items = Vec::new();
-    fill_in(cx, did, &mut items, visited);
+    fill_in(cx, did, &mut items, visited, glob);
     return clean::Module {
         items,
         is_crate: false,
     };
 
-    fn fill_in(cx: &DocContext<'_>, did: DefId, items: &mut Vec<clean::Item>,
-               visited: &mut FxHashSet<DefId>) {
+    fn fill_in(cx: &mut DocContext<'_>, did: DefId, items: &mut Vec<clean::Item>,
+               visited: &mut FxHashSet<DefId>, glob: bool) {
         // If we're re-exporting a re-export it may actually re-export something in
         // two namespaces, so the target may be listed twice. Make sure we only
         // visit each node at most once.
@@ -446,6 +626,18 @@ fn build_module(
             let def_id = item.res.def_id();
             if item.vis == ty::Visibility::Public {
                 if did == def_id || !visited.insert(def_id) { continue }
+                if glob {
+                    // There is no `use` statement of their own to carry the attribute, so items
+                    // reached through a glob decide for themselves whether to be inlined.
+                    let attrs = load_attrs(cx, def_id);
+                    if attrs.lists(sym::doc).has_word(sym::hidden) {
+                        continue;
+                    }
+                    if attrs.lists(sym::doc).has_word(sym::no_inline) {
+                        items.push(build_reexport(cx, item.res, item.ident.name));
+                        continue;
+                    }
+                }
                 if let Some(i) = try_inline(cx, item.res, item.ident.name, None, visited) {
                     items.extend(i)
                 }
@@ -454,6 +646,70 @@ fn build_module(
     }
 }
 
+/// Builds a `pub use` item pointing at the item `res`, for items reached through a glob import
+/// that asked not to be inlined.
+pub fn build_reexport(cx: &mut DocContext<'_>, res: Res, name: ast::Name) -> clean::Item {
+    let did = res.def_id();
+    let crate_name = cx.tcx.crate_name(did.krate);
+    let segments = once(crate_name)
+        .chain(cx.tcx.def_path(did).data.into_iter().map(|elem| elem.data.as_symbol()))
+        // extern blocks have an empty name
+        .filter(|&name| name != kw::Invalid)
+        .map(|name| clean::PathSegment {
+            name,
+            args: clean::GenericArgs::AngleBracketed {
+                args: vec![],
+                bindings: vec![],
+            },
+        })
+        .collect();
+    let path = clean::Path {
+        global: false,
+        res,
+        segments,
+    };
+
+    clean::Item {
+        name: None,
+        attrs: Default::default(),
+        source: cx.tcx.def_span(did).clean(cx),
+        def_id: cx.tcx.hir().local_def_id_from_node_id(ast::CRATE_NODE_ID),
+        visibility: clean::Public,
+        stability: None,
+        deprecation: None,
+        canonical_path: None,
+        inner: clean::ImportItem(clean::Import::Simple(
+            name,
+            clean::utils::resolve_use_source(cx, path),
+        )),
+    }
+}
+
+/// Fills in the source code of the default implementations of the provided methods of `trait_`.
+///
+/// Only the pages of inlined traits show these, so they are not looked up when the external traits
+/// of the impls of the crate are cleaned.
+fn fill_in_default_impls(cx: &DocContext<'_>, trait_: &mut clean::Trait) {
+    for item in &mut trait_.items {
+        if let clean::MethodItem(ref mut m) = item.inner {
+            m.default_impl = default_method_source(cx, item.def_id);
+        }
+    }
+}
+
+/// Returns the source code of the default implementation of the trait method `did`.
+///
+/// The body of an external method is only known through its MIR, and its text is only available
+/// if the sources of its crate can be found.
+fn default_method_source(cx: &DocContext<'_>, did: DefId) -> Option<String> {
+    let span = match cx.tcx.hir().span_if_local(did) {
+        Some(span) => span,
+        None if cx.tcx.is_mir_available(did) => cx.tcx.optimized_mir(did).span,
+        None => return None,
+    };
+    clean::utils::span_to_dedented_snippet(cx, span)
+}
+
 pub fn print_inlined_const(cx: &DocContext<'_>, did: DefId) -> String {
     if let Some(node_id) = cx.tcx.hir().as_local_hir_id(did) {
         cx.tcx.hir().hir_to_pretty_string(node_id)
@@ -462,47 +718,45 @@ pub fn print_inlined_const(cx: &DocContext<'_>, did: DefId) -> String {
     }
 }
 
-fn build_const(cx: &DocContext<'_>, did: DefId) -> clean::Constant {
+fn build_const(cx: &mut DocContext<'_>, did: DefId) -> clean::Constant {
+    let expr = print_inlined_const(cx, did);
     clean::Constant {
         type_: cx.tcx.type_of(did).clean(cx),
-        expr: print_inlined_const(cx, did)
+        value: clean::utils::print_evaluated_const(cx, did, &expr),
+        expr,
     }
 }
 
-fn build_static(cx: &DocContext<'_>, did: DefId, mutable: bool) -> clean::Static {
+fn build_static(cx: &mut DocContext<'_>, did: DefId, mutable: bool) -> clean::Static {
     clean::Static {
         type_: cx.tcx.type_of(did).clean(cx),
         mutability: if mutable {clean::Mutable} else {clean::Immutable},
         expr: "\n\n\n".to_string(), // trigger the "[definition]" links
+        value: None,
     }
 }
 
-fn build_macro(cx: &DocContext<'_>, did: DefId, name: ast::Name) -> clean::ItemEnum {
+fn build_macro(cx: &mut DocContext<'_>, did: DefId, name: ast::Name) -> clean::ItemEnum {
     let imported_from = cx.tcx.original_crate_name(did.krate);
-    match cx.enter_resolver(|r| r.cstore().load_macro_untracked(did, cx.sess())) {
+    match cx.cstore().load_macro_untracked(did, cx.sess()) {
         LoadedMacro::MacroDef(def, _) => {
-            let matchers: hir::HirVec<Span> = if let ast::ItemKind::MacroDef(ref def) = def.kind {
+            let (matchers, legacy) = if let ast::ItemKind::MacroDef(ref def) = def.kind {
                 let tts: Vec<_> = def.body.inner_tokens().into_trees().collect();
-                tts.chunks(4).map(|arm| arm[0].span()).collect()
+                let matchers: Vec<Span> = tts.chunks(4).map(|arm| arm[0].span()).collect();
+                (matchers, def.legacy)
             } else {
                 unreachable!()
             };
 
-            let source = format!("macro_rules! {} {{\n{}}}",
-                                 name.clean(cx),
-                                 matchers.iter().map(|span| {
-                                     format!("    {} => {{ ... }};\n", span.to_src(cx))
-                                 }).collect::<String>());
-
             clean::MacroItem(clean::Macro {
-                source,
-                imported_from: Some(imported_from).clean(cx),
+                source: clean::utils::display_macro_source(cx, &name.as_str(), &matchers, legacy),
+                imported_from: Some(imported_from),
             })
         }
         LoadedMacro::ProcMacro(ext) => {
             clean::ProcMacroItem(clean::ProcMacro {
                 kind: ext.macro_kind(),
-                helpers: ext.helper_attrs.clean(cx),
+                helpers: ext.helper_attrs.clone(),
             })
         }
     }
@@ -520,9 +774,9 @@ fn filter_non_trait_generics(trait_did: DefId, mut g: clean::Generics) -> clean:
     for pred in &mut g.where_predicates {
         match *pred {
             clean::WherePredicate::BoundPredicate {
-                ty: clean::Generic(ref s),
+                ty: clean::Generic(s),
                 ref mut bounds
-            } if *s == "Self" => {
+            } if s == kw::SelfUpper => {
                 bounds.retain(|bound| {
                     match *bound {
                         clean::GenericBound::TraitBound(clean::PolyTrait {
@@ -541,11 +795,11 @@ fn filter_non_trait_generics(trait_did: DefId, mut g: clean::Generics) -> clean:
         match *pred {
             clean::WherePredicate::BoundPredicate {
                 ty: clean::QPath {
-                    self_type: box clean::Generic(ref s),
+                    self_type: box clean::Generic(s),
                     trait_: box clean::ResolvedPath { did, .. },
-                    name: ref _name,
+                    name: _,
                 }, ref bounds
-            } => !(*s == "Self" && did == trait_did) && !bounds.is_empty(),
+            } => !(s == kw::SelfUpper && did == trait_did) && !bounds.is_empty(),
             _ => true,
         }
     });
@@ -561,9 +815,9 @@ fn separate_supertrait_bounds(mut g: clean::Generics)
     g.where_predicates.retain(|pred| {
         match *pred {
             clean::WherePredicate::BoundPredicate {
-                ty: clean::Generic(ref s),
+                ty: clean::Generic(s),
                 ref bounds
-            } if *s == "Self" => {
+            } if s == kw::SelfUpper => {
                 ty_bounds.extend(bounds.iter().cloned());
                 false
             }
@@ -573,24 +827,17 @@ fn separate_supertrait_bounds(mut g: clean::Generics)
     (g, ty_bounds)
 }
 
-pub fn record_extern_trait(cx: &DocContext<'_>, did: DefId) {
+pub fn record_extern_trait(cx: &mut DocContext<'_>, did: DefId) {
     if did.is_local() {
         return;
     }
 
-    {
-        if cx.external_traits.borrow().contains_key(&did) ||
-            cx.active_extern_traits.borrow().contains(&did)
-        {
-            return;
-        }
+    if !cx.recorded_extern_traits.insert(did) {
+        return;
     }
 
-    cx.active_extern_traits.borrow_mut().insert(did);
-
     debug!("record_extern_trait: {:?}", did);
     let trait_ = build_external_trait(cx, did);
 
-    cx.external_traits.borrow_mut().insert(did, trait_);
-    cx.active_extern_traits.borrow_mut().remove(&did);
+    cx.external_traits.insert(did, trait_);
 }
@@ -0,0 +1,61 @@
+//! Interning of cleaned types.
+//!
+//! `clean::Type` trees are deep and boxed, and the same types show up over and over again in the
+//! bounds of the impls synthesized for auto traits. Grouping those bounds by type interns the
+//! types here instead of cloning and hashing the whole tree every time: a type is moved into an
+//! arena once, and is then referred to by a `TypeRef`, which is `Copy` and compared and hashed as
+//! an integer.
+//!
+//! The interner only lives as long as the grouping. The items of the cleaned crate keep owning
+//! their `Type` trees, so interning does not lower the memory taken by the cleaned crate.
+
+use arena::TypedArena;
+use rustc::util::nodemap::FxHashMap;
+
+use crate::clean::Type;
+
+#[cfg(test)]
+mod tests;
+
+/// A handle to a type interned in a `TypeInterner`. Two handles of the same interner are equal if
+/// and only if their types are, but handles of different interners must not be mixed.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct TypeRef(u32);
+
+pub struct TypeInterner<'a> {
+    arena: &'a TypedArena<Type>,
+    refs: FxHashMap<&'a Type, TypeRef>,
+    types: Vec<&'a Type>,
+}
+
+impl<'a> TypeInterner<'a> {
+    /// Creates an interner which moves the types into `arena`.
+    pub fn new(arena: &'a TypedArena<Type>) -> Self {
+        TypeInterner {
+            arena,
+            refs: Default::default(),
+            types: Vec::new(),
+        }
+    }
+
+    pub fn intern(&mut self, ty: Type) -> TypeRef {
+        if let Some(&ty_ref) = self.refs.get(&ty) {
+            return ty_ref;
+        }
+
+        let ty_ref = TypeRef(self.types.len() as u32);
+        let ty: &'a Type = self.arena.alloc(ty);
+        self.types.push(ty);
+        self.refs.insert(ty, ty_ref);
+        ty_ref
+    }
+
+    /// Returns the handle of `ty` if it was already interned, without cloning it otherwise.
+    pub fn find(&self, ty: &Type) -> Option<TypeRef> {
+        self.refs.get(ty).copied()
+    }
+
+    pub fn get(&self, ty_ref: TypeRef) -> &'a Type {
+        self.types[ty_ref.0 as usize]
+    }
+}
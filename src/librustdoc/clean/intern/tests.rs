@@ -0,0 +1,32 @@
+use super::*;
+
+use arena::TypedArena;
+
+use crate::clean::{PrimitiveType, Type};
+
+fn slice_of(ty: Type) -> Type {
+    Type::Slice(Box::new(ty))
+}
+
+#[test]
+fn equal_types_share_a_handle() {
+    let arena = TypedArena::default();
+    let mut types = TypeInterner::new(&arena);
+    let a = types.intern(slice_of(Type::Primitive(PrimitiveType::Str)));
+    let b = types.intern(Type::Primitive(PrimitiveType::U8));
+    let c = types.intern(slice_of(Type::Primitive(PrimitiveType::Str)));
+    assert_eq!(a, c);
+    assert_ne!(a, b);
+    assert_eq!(*types.get(b), Type::Primitive(PrimitiveType::U8));
+}
+
+#[test]
+fn find_does_not_intern() {
+    let arena = TypedArena::default();
+    let mut types = TypeInterner::new(&arena);
+    let never = Type::Never;
+    assert_eq!(types.find(&never), None);
+    let t = types.intern(never.clone());
+    assert_eq!(types.find(&never), Some(t));
+    assert_eq!(types.find(&slice_of(never)), None);
+}
@@ -0,0 +1,525 @@
+//! Merging of the crates documented for several targets, see `--merge-target`.
+//!
+//! The crate is cleaned once per target. Items are matched between targets by their path and
+//! kind, and items that are missing on some targets are noted as only available on the others.
+//!
+//! Each target is compiled separately, so the `DefId`s of local items do not match between them.
+//! Local items found only on other targets are therefore given new `DefId`s which cannot clash
+//! with the ones of the primary target. The types in their signatures still refer to the local
+//! definitions of the target they come from, and the impls of such items are not merged.
+//!
+//! The crates a target depends on are numbered in the order they are loaded, which differs
+//! between targets depending on different crates. The `CrateNum`s of the other targets are
+//! therefore mapped to the ones of the primary target by crate name and disambiguator, and the
+//! crates only the other targets depend on are given new ones.
+
+use std::mem;
+use std::sync::Arc;
+
+use rustc::hir::def::Res;
+use rustc::hir::def_id::{CrateNum, DefId, DefIndex};
+use rustc::session::CrateDisambiguator;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+use rustc_target::spec::{Target, TargetTriple};
+use syntax::symbol::Symbol;
+
+use crate::clean::{
+    Crate, FnDecl, FunctionRetTy, GenericArg, GenericArgs, GenericBound, GenericParamDef,
+    GenericParamDefKind, Generics, Import, Item, ItemEnum, Path, Type, TypeBindingKind,
+    VariantKind, Visibility, WherePredicate,
+};
+use crate::clean::cfg::Cfg;
+use crate::core::RenderInfo;
+use crate::fold::DocFolder;
+use crate::html::item_type::ItemType;
+
+#[cfg(test)]
+mod tests;
+
+/// Local items found only on the other targets get `DefId`s from this index on.
+const MERGED_DEF_INDEX_START: u32 = 0x8000_0000;
+
+/// Identifies a crate across the compiler sessions of the different targets.
+type CrateKey = (Symbol, CrateDisambiguator);
+
+type TargetKey = fn(&Target) -> &str;
+
+fn target_os(target: &Target) -> &str { &target.target_os }
+fn target_arch(target: &Target) -> &str { &target.arch }
+fn target_env(target: &Target) -> &str { &target.target_env }
+
+/// Describes each of `targets` with the first of `target_os`, `target_arch` and `target_env`
+/// that tells all of them apart, or with all three if none of them does.
+pub fn target_cfgs(targets: &[TargetTriple]) -> Vec<Cfg> {
+    let keys: [(&str, TargetKey); 3] = [
+        ("target_os", target_os),
+        ("target_arch", target_arch),
+        ("target_env", target_env),
+    ];
+    let cfg = |target: &Target, &(name, value): &(&str, TargetKey)| {
+        Cfg::Cfg(Symbol::intern(name), Some(Symbol::intern(value(target))))
+    };
+
+    let specs = match targets.iter().map(|t| Target::search(t).ok()).collect::<Option<Vec<_>>>() {
+        Some(specs) => specs,
+        // Custom targets we cannot load can only be described by their name.
+        None => return targets.iter().map(|t| {
+            Cfg::Cfg(Symbol::intern("target"), Some(Symbol::intern(t.triple())))
+        }).collect(),
+    };
+
+    for key in &keys {
+        let values = specs.iter().map(|spec| (key.1)(spec)).collect::<FxHashSet<_>>();
+        if values.len() == specs.len() {
+            return specs.iter().map(|spec| cfg(spec, key)).collect();
+        }
+    }
+    specs.iter().map(|spec| {
+        keys.iter()
+            .filter(|key| !(key.1)(spec).is_empty())
+            .fold(Cfg::True, |acc, key| acc & cfg(spec, key))
+    }).collect()
+}
+
+/// Merges the crates documented for other targets into `krate`, the one documented for the
+/// primary target. `cfgs` describes the primary target first, then each of `others`.
+pub fn merge_targets(
+    mut krate: Crate,
+    renderinfo: &mut RenderInfo,
+    others: Vec<(Crate, RenderInfo)>,
+    cfgs: &[Cfg],
+) -> Crate {
+    // The local items of the primary target, including the impls rustdoc synthesizes with fake
+    // `DefId`s past the real ones, must be numbered below the items merged from the others.
+    let mut last_index = LastLocalIndex(0);
+    krate.module = krate.module.take().and_then(|module| last_index.fold_item(module));
+    let last_path_index = renderinfo.external_paths.keys()
+        .chain(renderinfo.exact_paths.keys())
+        .filter(|did| did.is_local())
+        .map(|did| did.index.as_u32())
+        .max()
+        .unwrap_or(0);
+    assert!(last_index.0.max(last_path_index) < MERGED_DEF_INDEX_START,
+            "the local `DefIndex`es of the primary target reach the ones of the merged items");
+
+    let mut cnums: FxHashMap<CrateKey, CrateNum> = krate.externs.iter()
+        .map(|&(cnum, ref external)| ((external.name, external.disambiguator), cnum))
+        .collect();
+    let mut next_cnum = krate.externs.iter().map(|&(cnum, _)| cnum.as_u32() + 1).max().unwrap_or(1);
+
+    let mut other_modules = Vec::with_capacity(others.len());
+    for (other, other_info) in others {
+        let mut remap = RemapCrates { cnums: FxHashMap::default() };
+        let mut new_externs = vec![];
+        for (cnum, external) in other.externs {
+            let key = (external.name, external.disambiguator);
+            let new_cnum = match cnums.get(&key) {
+                Some(&new_cnum) => new_cnum,
+                None => {
+                    // A crate only the other targets depend on.
+                    let new_cnum = CrateNum::from_u32(next_cnum);
+                    next_cnum += 1;
+                    cnums.insert(key, new_cnum);
+                    new_externs.push((new_cnum, external));
+                    new_cnum
+                }
+            };
+            remap.cnums.insert(cnum, new_cnum);
+        }
+        for (cnum, mut external) in new_externs {
+            for (did, ..) in &mut external.primitives {
+                *did = remap.def_id(*did);
+            }
+            for (did, ..) in &mut external.keywords {
+                *did = remap.def_id(*did);
+            }
+            krate.externs.push((cnum, external));
+        }
+
+        for (did, path) in other_info.external_paths {
+            renderinfo.external_paths.entry(remap.def_id(did)).or_insert(path);
+        }
+        for (did, path) in other_info.exact_paths {
+            renderinfo.exact_paths.entry(remap.def_id(did)).or_insert(path);
+        }
+        other_modules.push(other.module.and_then(|module| remap.fold_item(module)));
+    }
+
+    let mut merger = Merger { cfgs, next_index: MERGED_DEF_INDEX_START };
+    if let Some(module) = krate.module.take() {
+        krate.module = Some(merger.merge_children(module, other_modules));
+    }
+    krate
+}
+
+struct Merger<'a> {
+    cfgs: &'a [Cfg],
+    next_index: u32,
+}
+
+fn key(item: &Item) -> Option<(Symbol, ItemType)> {
+    item.name.map(|name| (name, ItemType::from(item)))
+}
+
+/// Removes the item with the given key from `items`.
+fn take(items: &mut Vec<Item>, item_key: &(Symbol, ItemType)) -> Option<Item> {
+    let pos = items.iter().position(|item| key(item).as_ref() == Some(item_key))?;
+    Some(items.remove(pos))
+}
+
+impl<'a> Merger<'a> {
+    /// Merges into `items` the items found at the same path on each of the other targets, given
+    /// in `others`.
+    fn merge(&mut self, items: Vec<Item>, mut others: Vec<Vec<Item>>) -> Vec<Item> {
+        let mut merged = Vec::with_capacity(items.len());
+
+        for item in items {
+            let item_key = match key(&item) {
+                Some(item_key) => item_key,
+                None => {
+                    merged.push(item);
+                    continue;
+                }
+            };
+            let matches = others.iter_mut().map(|o| take(o, &item_key)).collect::<Vec<_>>();
+            let mut present = vec![true];
+            present.extend(matches.iter().map(Option::is_some));
+            let item = self.merge_children(item, matches);
+            merged.push(self.restrict(item, &present));
+        }
+
+        // Whatever is left only exists on some of the other targets. Every item is added once,
+        // from the first target it is found on. Unnamed items, like impls, are left out.
+        for i in 0..others.len() {
+            while !others[i].is_empty() {
+                let item = others[i].remove(0);
+                let item_key = match key(&item) {
+                    Some(item_key) => item_key,
+                    None => continue,
+                };
+                let mut matches = vec![None; i + 1];
+                matches.extend(others[i + 1..].iter_mut().map(|o| take(o, &item_key)));
+                let mut present = vec![false];
+                present.extend(matches.iter().enumerate().map(|(j, m)| j == i || m.is_some()));
+                let item = self.merge_children(item, matches);
+                let item = self.restrict(item, &present);
+                merged.extend(Renumber { next_index: &mut self.next_index }.fold_item(item));
+            }
+        }
+
+        merged
+    }
+
+    /// If `item` is a module, merges into it the children of the modules found at the same path
+    /// on the other targets, given in `matches`.
+    fn merge_children(&mut self, mut item: Item, matches: Vec<Option<Item>>) -> Item {
+        if matches.iter().all(Option::is_none) {
+            return item;
+        }
+        if let ItemEnum::ModuleItem(ref mut m) = item.inner {
+            let others = matches.into_iter().map(|other| {
+                match other.map(|other| other.inner) {
+                    Some(ItemEnum::ModuleItem(other)) => other.items,
+                    _ => vec![],
+                }
+            }).collect();
+            m.items = self.merge(mem::take(&mut m.items), others);
+        }
+        item
+    }
+
+    /// Notes that `item` and its children are only available on the targets set in `present`.
+    fn restrict(&self, item: Item, present: &[bool]) -> Item {
+        if present.iter().all(|&p| p) {
+            return item;
+        }
+        let cfg = self.cfgs.iter()
+            .zip(present)
+            .filter(|&(_, &p)| p)
+            .fold(Cfg::False, |acc, (cfg, _)| acc | cfg.clone());
+        AddCfg { cfg: &cfg }.fold_item(item).unwrap()
+    }
+}
+
+/// Adds a cfg to an item and all of its children, like `propagate-doc-cfg` would have.
+struct AddCfg<'a> {
+    cfg: &'a Cfg,
+}
+
+impl<'a> DocFolder for AddCfg<'a> {
+    fn fold_item(&mut self, mut item: Item) -> Option<Item> {
+        item.attrs.cfg = Some(Arc::new(match item.attrs.cfg.take() {
+            Some(cfg) => Cfg::clone(&cfg) & self.cfg.clone(),
+            None => self.cfg.clone(),
+        }));
+        self.fold_item_recur(item)
+    }
+}
+
+/// Finds the largest `DefIndex` of the local items of a crate.
+struct LastLocalIndex(u32);
+
+impl DocFolder for LastLocalIndex {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        if item.def_id.is_local() {
+            self.0 = self.0.max(item.def_id.index.as_u32());
+        }
+        self.fold_item_recur(item)
+    }
+}
+
+/// Gives new `DefId`s to the local items of another target.
+struct Renumber<'a> {
+    next_index: &'a mut u32,
+}
+
+impl<'a> DocFolder for Renumber<'a> {
+    fn fold_item(&mut self, mut item: Item) -> Option<Item> {
+        if item.def_id.is_local() {
+            item.def_id = DefId {
+                krate: item.def_id.krate,
+                index: DefIndex::from_u32(*self.next_index),
+            };
+            *self.next_index += 1;
+        }
+        self.fold_item_recur(item)
+    }
+}
+
+/// Moves the `DefId`s of the items of other crates, as seen by another target, to the
+/// `CrateNum`s of the primary target. Local `DefId`s are left as they are.
+struct RemapCrates {
+    cnums: FxHashMap<CrateNum, CrateNum>,
+}
+
+impl RemapCrates {
+    fn def_id(&self, did: DefId) -> DefId {
+        match self.cnums.get(&did.krate) {
+            Some(&krate) => DefId { krate, index: did.index },
+            None => did,
+        }
+    }
+
+    fn res(&self, res: Res) -> Res {
+        match res {
+            Res::Def(kind, did) => Res::Def(kind, self.def_id(did)),
+            Res::SelfTy(trait_, impl_) => {
+                Res::SelfTy(trait_.map(|did| self.def_id(did)), impl_.map(|did| self.def_id(did)))
+            }
+            Res::SelfCtor(did) => Res::SelfCtor(self.def_id(did)),
+            res => res,
+        }
+    }
+
+    fn path(&self, path: &mut Path) {
+        path.res = self.res(path.res);
+        for segment in &mut path.segments {
+            self.generic_args(&mut segment.args);
+        }
+    }
+
+    fn generic_args(&self, args: &mut GenericArgs) {
+        match args {
+            GenericArgs::AngleBracketed { args, bindings } => {
+                for arg in args {
+                    match arg {
+                        GenericArg::Lifetime(_) => {}
+                        GenericArg::Type(ty) => self.type_(ty),
+                        GenericArg::Const(constant) => self.type_(&mut constant.type_),
+                    }
+                }
+                for binding in bindings {
+                    match &mut binding.kind {
+                        TypeBindingKind::Equality { ty } => self.type_(ty),
+                        TypeBindingKind::Constraint { bounds } => self.bounds(bounds),
+                    }
+                }
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                for ty in inputs.iter_mut().chain(output) {
+                    self.type_(ty);
+                }
+            }
+        }
+    }
+
+    fn type_(&self, ty: &mut Type) {
+        match ty {
+            Type::ResolvedPath { path, param_names, did, .. } => {
+                *did = self.def_id(*did);
+                self.path(path);
+                for bound in param_names.iter_mut().flatten() {
+                    self.bound(bound);
+                }
+            }
+            Type::BareFunction(function) => {
+                self.generic_params(&mut function.generic_params);
+                self.fn_decl(&mut function.decl);
+            }
+            Type::Tuple(types) => self.types(types),
+            Type::Slice(ty) |
+            Type::Array(ty, _) |
+            Type::RawPointer(_, ty) |
+            Type::BorrowedRef { type_: ty, .. } => self.type_(ty),
+            Type::QPath { self_type, trait_, .. } => {
+                self.type_(self_type);
+                self.type_(trait_);
+            }
+            Type::ImplTrait(bounds) => self.bounds(bounds),
+            Type::Generic(_) | Type::Primitive(_) | Type::Never | Type::Infer => {}
+        }
+    }
+
+    fn types(&self, types: &mut [Type]) {
+        for ty in types {
+            self.type_(ty);
+        }
+    }
+
+    fn bound(&self, bound: &mut GenericBound) {
+        if let GenericBound::TraitBound(poly_trait, _) = bound {
+            self.type_(&mut poly_trait.trait_);
+            self.generic_params(&mut poly_trait.generic_params);
+        }
+    }
+
+    fn bounds(&self, bounds: &mut [GenericBound]) {
+        for bound in bounds {
+            self.bound(bound);
+        }
+    }
+
+    fn generic_params(&self, params: &mut [GenericParamDef]) {
+        for param in params {
+            match &mut param.kind {
+                GenericParamDefKind::Lifetime => {}
+                GenericParamDefKind::Type { did, bounds, default, .. } => {
+                    *did = self.def_id(*did);
+                    self.bounds(bounds);
+                    if let Some(default) = default {
+                        self.type_(default);
+                    }
+                }
+                GenericParamDefKind::Const { did, ty } => {
+                    *did = self.def_id(*did);
+                    self.type_(ty);
+                }
+            }
+        }
+    }
+
+    fn generics(&self, generics: &mut Generics) {
+        self.generic_params(&mut generics.params);
+        for predicate in &mut generics.where_predicates {
+            match predicate {
+                WherePredicate::BoundPredicate { ty, bounds } => {
+                    self.type_(ty);
+                    self.bounds(bounds);
+                }
+                WherePredicate::RegionPredicate { bounds, .. } => self.bounds(bounds),
+                WherePredicate::EqPredicate { lhs, rhs } => {
+                    self.type_(lhs);
+                    self.type_(rhs);
+                }
+            }
+        }
+    }
+
+    fn fn_decl(&self, decl: &mut FnDecl) {
+        for argument in &mut decl.inputs.values {
+            self.type_(&mut argument.type_);
+        }
+        if let FunctionRetTy::Return(ty) = &mut decl.output {
+            self.type_(ty);
+        }
+    }
+
+    /// Remaps what `inner` refers to, except for the items it contains, which are folded.
+    fn inner(&self, inner: &mut ItemEnum) {
+        match inner {
+            ItemEnum::ImportItem(Import::Simple(_, source)) |
+            ItemEnum::ImportItem(Import::Glob(source)) => {
+                source.did = source.did.map(|did| self.def_id(did));
+                self.path(&mut source.path);
+            }
+            ItemEnum::StructItem(s) => self.generics(&mut s.generics),
+            ItemEnum::UnionItem(u) => self.generics(&mut u.generics),
+            ItemEnum::EnumItem(e) => self.generics(&mut e.generics),
+            ItemEnum::FunctionItem(f) | ItemEnum::ForeignFunctionItem(f) => {
+                self.generics(&mut f.generics);
+                self.fn_decl(&mut f.decl);
+                self.types(&mut f.all_types);
+                self.types(&mut f.ret_types);
+            }
+            ItemEnum::TypedefItem(t, _) => {
+                self.type_(&mut t.type_);
+                self.generics(&mut t.generics);
+            }
+            ItemEnum::OpaqueTyItem(t, _) => {
+                self.bounds(&mut t.bounds);
+                self.generics(&mut t.generics);
+            }
+            ItemEnum::StaticItem(s) | ItemEnum::ForeignStaticItem(s) => self.type_(&mut s.type_),
+            ItemEnum::ConstantItem(c) => self.type_(&mut c.type_),
+            ItemEnum::TraitItem(t) => {
+                self.generics(&mut t.generics);
+                self.bounds(&mut t.bounds);
+            }
+            ItemEnum::TraitAliasItem(t) => {
+                self.generics(&mut t.generics);
+                self.bounds(&mut t.bounds);
+            }
+            ItemEnum::ImplItem(i) => {
+                self.generics(&mut i.generics);
+                self.type_(&mut i.for_);
+                for ty in i.trait_.iter_mut().chain(&mut i.blanket_impl) {
+                    self.type_(ty);
+                }
+            }
+            ItemEnum::TyMethodItem(m) => {
+                self.generics(&mut m.generics);
+                self.fn_decl(&mut m.decl);
+                self.types(&mut m.all_types);
+                self.types(&mut m.ret_types);
+            }
+            ItemEnum::MethodItem(m) => {
+                self.generics(&mut m.generics);
+                self.fn_decl(&mut m.decl);
+                self.types(&mut m.all_types);
+                self.types(&mut m.ret_types);
+            }
+            ItemEnum::StructFieldItem(ty) | ItemEnum::AssocConstItem(ty, _) => self.type_(ty),
+            ItemEnum::VariantItem(v) => {
+                if let VariantKind::Tuple(types) = &mut v.kind {
+                    self.types(types);
+                }
+            }
+            ItemEnum::AssocTypeItem(bounds, default) => {
+                self.bounds(bounds);
+                if let Some(default) = default {
+                    self.type_(default);
+                }
+            }
+            ItemEnum::StrippedItem(inner) => self.inner(inner),
+            ItemEnum::ExternCrateItem(..) |
+            ItemEnum::ModuleItem(_) |
+            ItemEnum::ForeignTypeItem |
+            ItemEnum::MacroItem(_) |
+            ItemEnum::ProcMacroItem(_) |
+            ItemEnum::PrimitiveItem(_) |
+            ItemEnum::KeywordItem(_) => {}
+        }
+    }
+}
+
+impl DocFolder for RemapCrates {
+    fn fold_item(&mut self, mut item: Item) -> Option<Item> {
+        item.def_id = self.def_id(item.def_id);
+        if let Visibility::Restricted(did, path) = &mut item.visibility {
+            *did = self.def_id(*did);
+            self.path(path);
+        }
+        self.inner(&mut item.inner);
+        self.fold_item_recur(item)
+    }
+}
@@ -0,0 +1,181 @@
+use super::*;
+
+use rustc::hir;
+use rustc::hir::def::DefKind;
+use rustc::hir::def_id::{CRATE_DEF_INDEX, LOCAL_CRATE};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_target::spec::abi::Abi;
+use syntax::with_default_globals;
+use syntax_pos::FileName;
+
+use crate::clean::{
+    self, Argument, Arguments, ExternalCrate, Function, Module, PathSegment, TypeKind,
+};
+
+fn def_id(krate: u32, index: u32) -> DefId {
+    DefId { krate: CrateNum::from_u32(krate), index: DefIndex::from_u32(index) }
+}
+
+fn external_crate(name: &str, disambiguator: u64) -> ExternalCrate {
+    ExternalCrate {
+        name: Symbol::intern(name),
+        disambiguator: Fingerprint::from_smaller_hash(disambiguator).into(),
+        version: None,
+        src: FileName::Anon(0),
+        attrs: Default::default(),
+        primitives: vec![],
+        keywords: vec![],
+    }
+}
+
+fn item(name: &str, index: u32, inner: ItemEnum) -> Item {
+    Item {
+        source: clean::Span::empty(),
+        name: Some(Symbol::intern(name)),
+        attrs: Default::default(),
+        inner,
+        visibility: clean::Public,
+        def_id: DefId { krate: LOCAL_CRATE, index: DefIndex::from_u32(index) },
+        stability: None,
+        deprecation: None,
+        canonical_path: None,
+    }
+}
+
+/// A function taking the struct `did` of another crate.
+fn function(name: &str, index: u32, did: DefId, struct_name: &str) -> Item {
+    let arg = Type::ResolvedPath {
+        path: Path {
+            global: false,
+            res: Res::Def(DefKind::Struct, did),
+            segments: vec![PathSegment {
+                name: Symbol::intern(struct_name),
+                args: GenericArgs::AngleBracketed { args: vec![], bindings: vec![] },
+            }],
+        },
+        param_names: None,
+        did,
+        is_generic: false,
+    };
+    item(name, index, ItemEnum::FunctionItem(Function {
+        decl: FnDecl {
+            inputs: Arguments {
+                values: vec![Argument { type_: arg, name: Symbol::intern("arg") }],
+            },
+            output: FunctionRetTy::DefaultReturn,
+            c_variadic: false,
+            attrs: Default::default(),
+        },
+        generics: Default::default(),
+        header: hir::FnHeader {
+            unsafety: hir::Unsafety::Normal,
+            constness: hir::Constness::NotConst,
+            asyncness: hir::IsAsync::NotAsync,
+            abi: Abi::Rust,
+        },
+        all_types: vec![],
+        ret_types: vec![],
+    }))
+}
+
+fn krate(externs: Vec<(CrateNum, ExternalCrate)>, items: Vec<Item>) -> Crate {
+    Crate {
+        name: Symbol::intern("foo"),
+        version: None,
+        src: FileName::Anon(0),
+        module: Some(Item {
+            def_id: DefId { krate: LOCAL_CRATE, index: CRATE_DEF_INDEX },
+            ..item("foo", 0, ItemEnum::ModuleItem(Module { items, is_crate: true }))
+        }),
+        externs,
+        primitives: vec![],
+        external_traits: Default::default(),
+        masked_crates: Default::default(),
+        reported_masked_crates: Default::default(),
+        cfg_names: Default::default(),
+        collapsed: false,
+    }
+}
+
+fn cfgs() -> Vec<Cfg> {
+    ["linux", "windows"].iter().map(|os| {
+        Cfg::Cfg(Symbol::intern("target_os"), Some(Symbol::intern(os)))
+    }).collect()
+}
+
+/// The type of the argument of the function `name` of the crate.
+fn argument_did(krate: &Crate, name: &str) -> DefId {
+    let items = match krate.module.as_ref().unwrap().inner {
+        ItemEnum::ModuleItem(ref m) => &m.items,
+        _ => panic!("expected a module"),
+    };
+    let function = items.iter().find(|i| i.name == Some(Symbol::intern(name))).unwrap();
+    match function.inner {
+        ItemEnum::FunctionItem(ref f) => match f.decl.inputs.values[0].type_ {
+            Type::ResolvedPath { did, ref path, .. } => {
+                assert_eq!(path.res, Res::Def(DefKind::Struct, did));
+                did
+            }
+            _ => panic!("expected a path"),
+        },
+        _ => panic!("expected a function"),
+    }
+}
+
+#[test]
+fn test_other_dependencies_renumbered() {
+    with_default_globals(|| {
+        // The primary target depends on `libc`, the other one on `winapi`, then `libc`.
+        let libc = def_id(1, 7);
+        let primary = krate(
+            vec![(CrateNum::from_u32(1), external_crate("libc", 1))],
+            vec![function("stat", 3, libc, "stat")],
+        );
+        let mut renderinfo = RenderInfo::default();
+        renderinfo.external_paths.insert(libc, (vec!["libc".into(), "stat".into()],
+                                                TypeKind::Struct));
+
+        let handle = def_id(1, 7);
+        let other_libc = def_id(2, 7);
+        let other = krate(
+            vec![
+                (CrateNum::from_u32(1), external_crate("winapi", 2)),
+                (CrateNum::from_u32(2), external_crate("libc", 1)),
+            ],
+            vec![
+                function("stat", 3, other_libc, "stat"),
+                function("close_handle", 4, handle, "HANDLE"),
+            ],
+        );
+        let mut other_info = RenderInfo::default();
+        other_info.external_paths.insert(handle, (vec!["winapi".into(), "HANDLE".into()],
+                                                  TypeKind::Struct));
+        other_info.external_paths.insert(other_libc, (vec!["libc".into(), "stat".into()],
+                                                      TypeKind::Struct));
+
+        let merged = merge_targets(primary, &mut renderinfo, vec![(other, other_info)], &cfgs());
+
+        // `libc` keeps its number, `winapi` gets the next one.
+        let names = merged.externs.iter()
+            .map(|(cnum, external)| (cnum.as_u32(), external.name.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(names, [(1, "libc".to_string()), (2, "winapi".to_string())]);
+
+        assert_eq!(argument_did(&merged, "stat"), def_id(1, 7));
+        assert_eq!(argument_did(&merged, "close_handle"), def_id(2, 7));
+        assert_eq!(renderinfo.external_paths.len(), 2);
+        assert_eq!(renderinfo.external_paths[&def_id(1, 7)].0, ["libc", "stat"]);
+        assert_eq!(renderinfo.external_paths[&def_id(2, 7)].0, ["winapi", "HANDLE"]);
+    })
+}
+
+#[test]
+#[should_panic(expected = "reach the ones of the merged items")]
+fn test_local_index_collision() {
+    with_default_globals(|| {
+        let primary = krate(vec![], vec![
+            item("S", MERGED_DEF_INDEX_START, ItemEnum::ForeignTypeItem),
+        ]);
+        merge_targets(primary, &mut RenderInfo::default(), vec![], &cfgs());
+    })
+}
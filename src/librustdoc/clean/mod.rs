@@ -2,7 +2,10 @@
 //! that clean them.
 
 pub mod inline;
+pub mod intern;
+pub mod merge;
 pub mod cfg;
+pub mod fragments;
 pub mod utils;
 mod auto_trait;
 mod blanket_impl;
@@ -26,7 +29,7 @@ use rustc::ty::fold::TypeFolder;
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use syntax::ast::{self, Ident};
 use syntax::attr;
-use syntax_pos::symbol::{kw, sym};
+use syntax_pos::symbol::{Symbol, kw, sym};
 use syntax_pos::hygiene::MacroKind;
 use syntax_pos::{self, Pos};
 
@@ -42,7 +45,7 @@ use crate::doctree;
 
 use utils::*;
 
-pub use utils::{get_auto_trait_and_blanket_impls, krate, register_res};
+pub use utils::{get_auto_trait_and_blanket_impls, krate, record_canonical_paths, register_res};
 
 pub use self::types::*;
 pub use self::types::Type::*;
@@ -52,58 +55,57 @@ pub use self::types::SelfTy::*;
 pub use self::types::FunctionRetTy::*;
 pub use self::types::Visibility::{Public, Inherited};
 
-const FN_OUTPUT_NAME: &'static str = "Output";
-
 pub trait Clean<T> {
-    fn clean(&self, cx: &DocContext<'_>) -> T;
+    fn clean(&self, cx: &mut DocContext<'_>) -> T;
 }
 
 impl<T: Clean<U>, U> Clean<Vec<U>> for [T] {
-    fn clean(&self, cx: &DocContext<'_>) -> Vec<U> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Vec<U> {
         self.iter().map(|x| x.clean(cx)).collect()
     }
 }
 
 impl<T: Clean<U>, U, V: Idx> Clean<IndexVec<V, U>> for IndexVec<V, T> {
-    fn clean(&self, cx: &DocContext<'_>) -> IndexVec<V, U> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> IndexVec<V, U> {
         self.iter().map(|x| x.clean(cx)).collect()
     }
 }
 
 impl<T: Clean<U>, U> Clean<U> for P<T> {
-    fn clean(&self, cx: &DocContext<'_>) -> U {
+    fn clean(&self, cx: &mut DocContext<'_>) -> U {
         (**self).clean(cx)
     }
 }
 
 impl<T: Clean<U>, U> Clean<U> for Rc<T> {
-    fn clean(&self, cx: &DocContext<'_>) -> U {
+    fn clean(&self, cx: &mut DocContext<'_>) -> U {
         (**self).clean(cx)
     }
 }
 
 impl<T: Clean<U>, U> Clean<Option<U>> for Option<T> {
-    fn clean(&self, cx: &DocContext<'_>) -> Option<U> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Option<U> {
         self.as_ref().map(|v| v.clean(cx))
     }
 }
 
 impl<T, U> Clean<U> for ty::Binder<T> where T: Clean<U> {
-    fn clean(&self, cx: &DocContext<'_>) -> U {
+    fn clean(&self, cx: &mut DocContext<'_>) -> U {
         self.skip_binder().clean(cx)
     }
 }
 
 impl<T: Clean<U>, U> Clean<Vec<U>> for P<[T]> {
-    fn clean(&self, cx: &DocContext<'_>) -> Vec<U> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Vec<U> {
         self.iter().map(|x| x.clean(cx)).collect()
     }
 }
 
 impl Clean<ExternalCrate> for CrateNum {
-    fn clean(&self, cx: &DocContext<'_>) -> ExternalCrate {
+    fn clean(&self, cx: &mut DocContext<'_>) -> ExternalCrate {
+        let tcx = cx.tcx;
         let root = DefId { krate: *self, index: CRATE_DEF_INDEX };
-        let krate_span = cx.tcx.def_span(root);
+        let krate_span = tcx.def_span(root);
         let krate_src = cx.sess().source_map().span_to_filename(krate_span);
 
         // Collect all inner modules which are tagged as implementations of
@@ -123,9 +125,9 @@ impl Clean<ExternalCrate> for CrateNum {
         // Also note that this does not attempt to deal with modules tagged
         // duplicately for the same primitive. This is handled later on when
         // rendering by delegating everything to a hash map.
-        let as_primitive = |res: Res| {
+        let mut as_primitive = |res: Res| {
             if let Res::Def(DefKind::Mod, def_id) = res {
-                let attrs = cx.tcx.get_attrs(def_id).clean(cx);
+                let attrs = tcx.get_attrs(def_id).clean(cx);
                 let mut prim = None;
                 for attr in attrs.lists(sym::doc) {
                     if let Some(v) = attr.value_str() {
@@ -143,39 +145,39 @@ impl Clean<ExternalCrate> for CrateNum {
             None
         };
         let primitives = if root.is_local() {
-            cx.tcx.hir().krate().module.item_ids.iter().filter_map(|&id| {
-                let item = cx.tcx.hir().expect_item(id.id);
+            tcx.hir().krate().module.item_ids.iter().filter_map(|&id| {
+                let item = tcx.hir().expect_item(id.id);
                 match item.kind {
                     hir::ItemKind::Mod(_) => {
                         as_primitive(Res::Def(
                             DefKind::Mod,
-                            cx.tcx.hir().local_def_id(id.id),
+                            tcx.hir().local_def_id(id.id),
                         ))
                     }
                     hir::ItemKind::Use(ref path, hir::UseKind::Single)
                     if item.vis.node.is_pub() => {
                         as_primitive(path.res).map(|(_, prim, attrs)| {
                             // Pretend the primitive is local.
-                            (cx.tcx.hir().local_def_id(id.id), prim, attrs)
+                            (tcx.hir().local_def_id(id.id), prim, attrs)
                         })
                     }
                     _ => None
                 }
             }).collect()
         } else {
-            cx.tcx.item_children(root).iter().map(|item| item.res)
+            tcx.item_children(root).iter().map(|item| item.res)
               .filter_map(as_primitive).collect()
         };
 
-        let as_keyword = |res: Res| {
+        let mut as_keyword = |res: Res| {
             if let Res::Def(DefKind::Mod, def_id) = res {
-                let attrs = cx.tcx.get_attrs(def_id).clean(cx);
+                let attrs = tcx.get_attrs(def_id).clean(cx);
                 let mut keyword = None;
                 for attr in attrs.lists(sym::doc) {
                     if let Some(v) = attr.value_str() {
                         if attr.check_name(sym::keyword) {
                             if v.is_doc_keyword() {
-                                keyword = Some(v.to_string());
+                                keyword = Some(v);
                                 break;
                             }
                             // FIXME: should warn on unknown keywords?
@@ -187,33 +189,42 @@ impl Clean<ExternalCrate> for CrateNum {
             None
         };
         let keywords = if root.is_local() {
-            cx.tcx.hir().krate().module.item_ids.iter().filter_map(|&id| {
-                let item = cx.tcx.hir().expect_item(id.id);
+            tcx.hir().krate().module.item_ids.iter().filter_map(|&id| {
+                let item = tcx.hir().expect_item(id.id);
                 match item.kind {
                     hir::ItemKind::Mod(_) => {
                         as_keyword(Res::Def(
                             DefKind::Mod,
-                            cx.tcx.hir().local_def_id(id.id),
+                            tcx.hir().local_def_id(id.id),
                         ))
                     }
                     hir::ItemKind::Use(ref path, hir::UseKind::Single)
                     if item.vis.node.is_pub() => {
                         as_keyword(path.res).map(|(_, prim, attrs)| {
-                            (cx.tcx.hir().local_def_id(id.id), prim, attrs)
+                            (tcx.hir().local_def_id(id.id), prim, attrs)
                         })
                     }
                     _ => None
                 }
             }).collect()
         } else {
-            cx.tcx.item_children(root).iter().map(|item| item.res)
+            tcx.item_children(root).iter().map(|item| item.res)
               .filter_map(as_keyword).collect()
         };
 
+        let name = cx.tcx.crate_name(*self);
+        let attrs = cx.tcx.get_attrs(root).clean(cx);
+        let version = if root.is_local() {
+            None
+        } else {
+            crate_version(&name.as_str(), &krate_src, &attrs)
+        };
         ExternalCrate {
-            name: cx.tcx.crate_name(*self).to_string(),
+            name,
+            disambiguator: cx.tcx.crate_disambiguator(*self),
+            version,
             src: krate_src,
-            attrs: cx.tcx.get_attrs(root).clean(cx),
+            attrs,
             primitives,
             keywords,
         }
@@ -221,12 +232,8 @@ impl Clean<ExternalCrate> for CrateNum {
 }
 
 impl Clean<Item> for doctree::Module<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
-        let name = if self.name.is_some() {
-            self.name.expect("No name provided").clean(cx)
-        } else {
-            String::new()
-        };
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
+        let name = self.name.unwrap_or(kw::Invalid);
 
         // maintain a stack of mod ids, for doc comment path resolution
         // but we also need to resolve the module's own docs based on whether its docs were written
@@ -236,6 +243,7 @@ impl Clean<Item> for doctree::Module<'_> {
         let mut items: Vec<Item> = vec![];
         items.extend(self.extern_crates.iter().flat_map(|x| x.clean(cx)));
         items.extend(self.imports.iter().flat_map(|x| x.clean(cx)));
+        items.extend(self.glob_reexports.iter().map(|x| inline::build_reexport(cx, x.res, x.name)));
         items.extend(self.structs.iter().map(|x| x.clean(cx)));
         items.extend(self.unions.iter().map(|x| x.clean(cx)));
         items.extend(self.enums.iter().map(|x| x.clean(cx)));
@@ -274,6 +282,7 @@ impl Clean<Item> for doctree::Module<'_> {
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             def_id: cx.tcx.hir().local_def_id(self.id),
             inner: ModuleItem(Module {
                is_crate: self.is_crate,
@@ -284,13 +293,16 @@ impl Clean<Item> for doctree::Module<'_> {
 }
 
 impl Clean<Attributes> for [ast::Attribute] {
-    fn clean(&self, cx: &DocContext<'_>) -> Attributes {
-        Attributes::from_ast(cx.sess().diagnostic(), self)
+    fn clean(&self, cx: &mut DocContext<'_>) -> Attributes {
+        let mut attrs = Attributes::from_ast(cx.sess().diagnostic(), self);
+        let sess = cx.tcx.sess;
+        cx.doc_fragments.resolve(sess, &mut attrs);
+        attrs
     }
 }
 
 impl Clean<GenericBound> for hir::GenericBound {
-    fn clean(&self, cx: &DocContext<'_>) -> GenericBound {
+    fn clean(&self, cx: &mut DocContext<'_>) -> GenericBound {
         match *self {
             hir::GenericBound::Outlives(lt) => GenericBound::Outlives(lt.clean(cx)),
             hir::GenericBound::Trait(ref t, modifier) => {
@@ -301,7 +313,7 @@ impl Clean<GenericBound> for hir::GenericBound {
 }
 
 impl<'a, 'tcx> Clean<GenericBound> for (&'a ty::TraitRef<'tcx>, Vec<TypeBinding>) {
-    fn clean(&self, cx: &DocContext<'_>) -> GenericBound {
+    fn clean(&self, cx: &mut DocContext<'_>) -> GenericBound {
         let (trait_ref, ref bounds) = *self;
         inline::record_extern_fqn(cx, trait_ref.def_id, TypeKind::Trait);
         let path = external_path(cx, cx.tcx.item_name(trait_ref.def_id),
@@ -345,13 +357,13 @@ impl<'a, 'tcx> Clean<GenericBound> for (&'a ty::TraitRef<'tcx>, Vec<TypeBinding>
 }
 
 impl<'tcx> Clean<GenericBound> for ty::TraitRef<'tcx> {
-    fn clean(&self, cx: &DocContext<'_>) -> GenericBound {
+    fn clean(&self, cx: &mut DocContext<'_>) -> GenericBound {
         (self, vec![]).clean(cx)
     }
 }
 
 impl<'tcx> Clean<Option<Vec<GenericBound>>> for InternalSubsts<'tcx> {
-    fn clean(&self, cx: &DocContext<'_>) -> Option<Vec<GenericBound>> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Option<Vec<GenericBound>> {
         let mut v = Vec::new();
         v.extend(self.regions().filter_map(|r| r.clean(cx)).map(GenericBound::Outlives));
         v.extend(self.types().map(|t| GenericBound::TraitBound(PolyTrait {
@@ -363,26 +375,26 @@ impl<'tcx> Clean<Option<Vec<GenericBound>>> for InternalSubsts<'tcx> {
 }
 
 impl Clean<Lifetime> for hir::Lifetime {
-    fn clean(&self, cx: &DocContext<'_>) -> Lifetime {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Lifetime {
         if self.hir_id != hir::DUMMY_HIR_ID {
             let def = cx.tcx.named_region(self.hir_id);
             match def {
                 Some(rl::Region::EarlyBound(_, node_id, _)) |
                 Some(rl::Region::LateBound(_, node_id, _)) |
                 Some(rl::Region::Free(_, node_id)) => {
-                    if let Some(lt) = cx.lt_substs.borrow().get(&node_id).cloned() {
+                    if let Some(lt) = cx.lt_substs.get(&node_id).cloned() {
                         return lt;
                     }
                 }
                 _ => {}
             }
         }
-        Lifetime(self.name.ident().to_string())
+        Lifetime(self.name.ident().name)
     }
 }
 
 impl Clean<Lifetime> for hir::GenericParam {
-    fn clean(&self, _: &DocContext<'_>) -> Lifetime {
+    fn clean(&self, _: &mut DocContext<'_>) -> Lifetime {
         match self.kind {
             hir::GenericParamKind::Lifetime { .. } => {
                 if self.bounds.len() > 0 {
@@ -395,9 +407,9 @@ impl Clean<Lifetime> for hir::GenericParam {
                     for bound in bounds {
                         s.push_str(&format!(" + {}", bound.name.ident()));
                     }
-                    Lifetime(s)
+                    Lifetime(Symbol::intern(&s))
                 } else {
-                    Lifetime(self.name.ident().to_string())
+                    Lifetime(self.name.ident().name)
                 }
             }
             _ => panic!(),
@@ -406,26 +418,27 @@ impl Clean<Lifetime> for hir::GenericParam {
 }
 
 impl Clean<Constant> for hir::ConstArg {
-    fn clean(&self, cx: &DocContext<'_>) -> Constant {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Constant {
         Constant {
             type_: cx.tcx.type_of(cx.tcx.hir().body_owner_def_id(self.value.body)).clean(cx),
             expr: print_const_expr(cx, self.value.body),
+            value: None,
         }
     }
 }
 
 impl Clean<Lifetime> for ty::GenericParamDef {
-    fn clean(&self, _cx: &DocContext<'_>) -> Lifetime {
-        Lifetime(self.name.to_string())
+    fn clean(&self, _cx: &mut DocContext<'_>) -> Lifetime {
+        Lifetime(self.name)
     }
 }
 
 impl Clean<Option<Lifetime>> for ty::RegionKind {
-    fn clean(&self, cx: &DocContext<'_>) -> Option<Lifetime> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Option<Lifetime> {
         match *self {
             ty::ReStatic => Some(Lifetime::statik()),
-            ty::ReLateBound(_, ty::BrNamed(_, name)) => Some(Lifetime(name.to_string())),
-            ty::ReEarlyBound(ref data) => Some(Lifetime(data.name.clean(cx))),
+            ty::ReLateBound(_, ty::BrNamed(_, name)) => Some(Lifetime(name)),
+            ty::ReEarlyBound(ref data) => Some(Lifetime(data.name)),
 
             ty::ReLateBound(..) |
             ty::ReFree(..) |
@@ -443,7 +456,7 @@ impl Clean<Option<Lifetime>> for ty::RegionKind {
 }
 
 impl Clean<WherePredicate> for hir::WherePredicate {
-    fn clean(&self, cx: &DocContext<'_>) -> WherePredicate {
+    fn clean(&self, cx: &mut DocContext<'_>) -> WherePredicate {
         match *self {
             hir::WherePredicate::BoundPredicate(ref wbp) => {
                 WherePredicate::BoundPredicate {
@@ -470,26 +483,30 @@ impl Clean<WherePredicate> for hir::WherePredicate {
 }
 
 impl<'a> Clean<Option<WherePredicate>> for ty::Predicate<'a> {
-    fn clean(&self, cx: &DocContext<'_>) -> Option<WherePredicate> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Option<WherePredicate> {
         use rustc::ty::Predicate;
 
         match *self {
             Predicate::Trait(ref pred) => Some(pred.clean(cx)),
-            Predicate::Subtype(ref pred) => Some(pred.clean(cx)),
             Predicate::RegionOutlives(ref pred) => pred.clean(cx),
             Predicate::TypeOutlives(ref pred) => pred.clean(cx),
             Predicate::Projection(ref pred) => Some(pred.clean(cx)),
 
+            // These predicates are internal to the compiler and cannot be written.
+            Predicate::Subtype(..) |
             Predicate::WellFormed(..) |
             Predicate::ObjectSafe(..) |
             Predicate::ClosureKind(..) |
-            Predicate::ConstEvaluatable(..) => panic!("not user writable"),
+            Predicate::ConstEvaluatable(..) => {
+                report_uncleanable(cx, None, &format!("the predicate `{}`", self));
+                None
+            }
         }
     }
 }
 
 impl<'a> Clean<WherePredicate> for ty::TraitPredicate<'a> {
-    fn clean(&self, cx: &DocContext<'_>) -> WherePredicate {
+    fn clean(&self, cx: &mut DocContext<'_>) -> WherePredicate {
         WherePredicate::BoundPredicate {
             ty: self.trait_ref.self_ty().clean(cx),
             bounds: vec![self.trait_ref.clean(cx)]
@@ -497,17 +514,10 @@ impl<'a> Clean<WherePredicate> for ty::TraitPredicate<'a> {
     }
 }
 
-impl<'tcx> Clean<WherePredicate> for ty::SubtypePredicate<'tcx> {
-    fn clean(&self, _cx: &DocContext<'_>) -> WherePredicate {
-        panic!("subtype predicates are an internal rustc artifact \
-                and should not be seen by rustdoc")
-    }
-}
-
 impl<'tcx> Clean<Option<WherePredicate>> for
     ty::OutlivesPredicate<ty::Region<'tcx>,ty::Region<'tcx>> {
 
-    fn clean(&self, cx: &DocContext<'_>) -> Option<WherePredicate> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Option<WherePredicate> {
         let ty::OutlivesPredicate(ref a, ref b) = *self;
 
         match (a, b) {
@@ -517,15 +527,21 @@ impl<'tcx> Clean<Option<WherePredicate>> for
             _ => {}
         }
 
-        Some(WherePredicate::RegionPredicate {
-            lifetime: a.clean(cx).expect("failed to clean lifetime"),
-            bounds: vec![GenericBound::Outlives(b.clean(cx).expect("failed to clean bounds"))]
-        })
+        match (a.clean(cx), b.clean(cx)) {
+            (Some(lifetime), Some(bound)) => Some(WherePredicate::RegionPredicate {
+                lifetime,
+                bounds: vec![GenericBound::Outlives(bound)]
+            }),
+            _ => {
+                report_uncleanable(cx, None, &format!("the predicate `{}: {}`", a, b));
+                None
+            }
+        }
     }
 }
 
 impl<'tcx> Clean<Option<WherePredicate>> for ty::OutlivesPredicate<Ty<'tcx>, ty::Region<'tcx>> {
-    fn clean(&self, cx: &DocContext<'_>) -> Option<WherePredicate> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Option<WherePredicate> {
         let ty::OutlivesPredicate(ref ty, ref lt) = *self;
 
         match lt {
@@ -533,15 +549,22 @@ impl<'tcx> Clean<Option<WherePredicate>> for ty::OutlivesPredicate<Ty<'tcx>, ty:
             _ => {}
         }
 
+        let bound = match lt.clean(cx) {
+            Some(bound) => bound,
+            None => {
+                report_uncleanable(cx, None, &format!("the predicate `{}: {}`", ty, lt));
+                return None;
+            }
+        };
         Some(WherePredicate::BoundPredicate {
             ty: ty.clean(cx),
-            bounds: vec![GenericBound::Outlives(lt.clean(cx).expect("failed to clean lifetimes"))]
+            bounds: vec![GenericBound::Outlives(bound)]
         })
     }
 }
 
 impl<'tcx> Clean<WherePredicate> for ty::ProjectionPredicate<'tcx> {
-    fn clean(&self, cx: &DocContext<'_>) -> WherePredicate {
+    fn clean(&self, cx: &mut DocContext<'_>) -> WherePredicate {
         WherePredicate::EqPredicate {
             lhs: self.projection_ty.clean(cx),
             rhs: self.ty.clean(cx)
@@ -550,14 +573,17 @@ impl<'tcx> Clean<WherePredicate> for ty::ProjectionPredicate<'tcx> {
 }
 
 impl<'tcx> Clean<Type> for ty::ProjectionTy<'tcx> {
-    fn clean(&self, cx: &DocContext<'_>) -> Type {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Type {
         let lifted = self.lift_to_tcx(cx.tcx).unwrap();
         let trait_ = match lifted.trait_ref(cx.tcx).clean(cx) {
             GenericBound::TraitBound(t, _) => t.trait_,
-            GenericBound::Outlives(_) => panic!("cleaning a trait got a lifetime"),
+            GenericBound::Outlives(_) => {
+                report_uncleanable(cx, None, &format!("the projection `{:?}`", lifted));
+                return Infer;
+            }
         };
         Type::QPath {
-            name: cx.tcx.associated_item(self.item_def_id).ident.name.clean(cx),
+            name: cx.tcx.associated_item(self.item_def_id).ident.name,
             self_type: box self.self_ty().clean(cx),
             trait_: box trait_
         }
@@ -565,10 +591,10 @@ impl<'tcx> Clean<Type> for ty::ProjectionTy<'tcx> {
 }
 
 impl Clean<GenericParamDef> for ty::GenericParamDef {
-    fn clean(&self, cx: &DocContext<'_>) -> GenericParamDef {
+    fn clean(&self, cx: &mut DocContext<'_>) -> GenericParamDef {
         let (name, kind) = match self.kind {
             ty::GenericParamDefKind::Lifetime => {
-                (self.name.to_string(), GenericParamDefKind::Lifetime)
+                (self.name, GenericParamDefKind::Lifetime)
             }
             ty::GenericParamDefKind::Type { has_default, synthetic, .. } => {
                 let default = if has_default {
@@ -576,7 +602,7 @@ impl Clean<GenericParamDef> for ty::GenericParamDef {
                 } else {
                     None
                 };
-                (self.name.clean(cx), GenericParamDefKind::Type {
+                (self.name, GenericParamDefKind::Type {
                     did: self.def_id,
                     bounds: vec![], // These are filled in from the where-clauses.
                     default,
@@ -584,7 +610,7 @@ impl Clean<GenericParamDef> for ty::GenericParamDef {
                 })
             }
             ty::GenericParamDefKind::Const { .. } => {
-                (self.name.clean(cx), GenericParamDefKind::Const {
+                (self.name, GenericParamDefKind::Const {
                     did: self.def_id,
                     ty: cx.tcx.type_of(self.def_id).clean(cx),
                 })
@@ -599,7 +625,7 @@ impl Clean<GenericParamDef> for ty::GenericParamDef {
 }
 
 impl Clean<GenericParamDef> for hir::GenericParam {
-    fn clean(&self, cx: &DocContext<'_>) -> GenericParamDef {
+    fn clean(&self, cx: &mut DocContext<'_>) -> GenericParamDef {
         let (name, kind) = match self.kind {
             hir::GenericParamKind::Lifetime { .. } => {
                 let name = if self.bounds.len() > 0 {
@@ -612,14 +638,14 @@ impl Clean<GenericParamDef> for hir::GenericParam {
                     for bound in bounds {
                         s.push_str(&format!(" + {}", bound.name.ident()));
                     }
-                    s
+                    Symbol::intern(&s)
                 } else {
-                    self.name.ident().to_string()
+                    self.name.ident().name
                 };
                 (name, GenericParamDefKind::Lifetime)
             }
             hir::GenericParamKind::Type { ref default, synthetic } => {
-                (self.name.ident().name.clean(cx), GenericParamDefKind::Type {
+                (self.name.ident().name, GenericParamDefKind::Type {
                     did: cx.tcx.hir().local_def_id(self.hir_id),
                     bounds: self.bounds.clean(cx),
                     default: default.clean(cx),
@@ -627,7 +653,7 @@ impl Clean<GenericParamDef> for hir::GenericParam {
                 })
             }
             hir::GenericParamKind::Const { ref ty } => {
-                (self.name.ident().name.clean(cx), GenericParamDefKind::Const {
+                (self.name.ident().name, GenericParamDefKind::Const {
                     did: cx.tcx.hir().local_def_id(self.hir_id),
                     ty: ty.clean(cx),
                 })
@@ -642,7 +668,7 @@ impl Clean<GenericParamDef> for hir::GenericParam {
 }
 
 impl Clean<Generics> for hir::Generics {
-    fn clean(&self, cx: &DocContext<'_>) -> Generics {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Generics {
         // Synthetic type-parameters are inserted after normal ones.
         // In order for normal parameters to be able to refer to synthetic ones,
         // scans them first.
@@ -662,7 +688,7 @@ impl Clean<Generics> for hir::Generics {
                 match param.kind {
                     GenericParamDefKind::Lifetime => unreachable!(),
                     GenericParamDefKind::Type { did, ref bounds, .. } => {
-                        cx.impl_trait_bounds.borrow_mut().insert(did.into(), bounds.clone());
+                        cx.impl_trait_bounds.insert(did.into(), bounds.clone());
                     }
                     GenericParamDefKind::Const { .. } => unreachable!(),
                 }
@@ -711,7 +737,7 @@ impl Clean<Generics> for hir::Generics {
 }
 
 impl<'a, 'tcx> Clean<Generics> for (&'a ty::Generics, ty::GenericPredicates<'tcx>) {
-    fn clean(&self, cx: &DocContext<'_>) -> Generics {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Generics {
         use self::WherePredicate as WP;
         use std::collections::BTreeMap;
 
@@ -743,7 +769,7 @@ impl<'a, 'tcx> Clean<Generics> for (&'a ty::Generics, ty::GenericPredicates<'tcx
 
         // param index -> [(DefId of trait, associated type name, type)]
         let mut impl_trait_proj =
-            FxHashMap::<u32, Vec<(DefId, String, Ty<'tcx>)>>::default();
+            FxHashMap::<u32, Vec<(DefId, Symbol, Ty<'tcx>)>>::default();
 
         let where_predicates = preds.predicates.iter()
             .flat_map(|(p, _)| {
@@ -787,7 +813,7 @@ impl<'a, 'tcx> Clean<Generics> for (&'a ty::Generics, ty::GenericPredicates<'tcx
                             impl_trait_proj
                                 .entry(param_idx)
                                 .or_default()
-                                .push((trait_did, name.to_string(), rhs));
+                                .push((trait_did, name, rhs));
                         }
 
                         return None;
@@ -809,12 +835,13 @@ impl<'a, 'tcx> Clean<Generics> for (&'a ty::Generics, ty::GenericPredicates<'tcx
             if let crate::core::ImplTraitParam::ParamIndex(idx) = param {
                 if let Some(proj) = impl_trait_proj.remove(&idx) {
                     for (trait_did, name, rhs) in proj {
+                        let rhs = rhs.clean(cx);
                         simplify::merge_bounds(
                             cx,
                             &mut bounds,
                             trait_did,
-                            &name,
-                            &rhs.clean(cx),
+                            name,
+                            &rhs,
                         );
                     }
                 }
@@ -822,7 +849,7 @@ impl<'a, 'tcx> Clean<Generics> for (&'a ty::Generics, ty::GenericPredicates<'tcx
                 unreachable!();
             }
 
-            cx.impl_trait_bounds.borrow_mut().insert(param, bounds);
+            cx.impl_trait_bounds.insert(param, bounds);
         }
 
         // Now that `cx.impl_trait_bounds` is populated, we can process
@@ -885,8 +912,8 @@ impl<'a, 'tcx> Clean<Generics> for (&'a ty::Generics, ty::GenericPredicates<'tcx
 
 impl<'a> Clean<Method> for (&'a hir::FnSig, &'a hir::Generics, hir::BodyId,
                             Option<hir::Defaultness>) {
-    fn clean(&self, cx: &DocContext<'_>) -> Method {
-        let (generics, decl) = enter_impl_trait(cx, || {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Method {
+        let (generics, decl) = enter_impl_trait(cx, |cx| {
             (self.1.clean(cx), (&*self.0.decl, self.2).clean(cx))
         });
         let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
@@ -897,13 +924,14 @@ impl<'a> Clean<Method> for (&'a hir::FnSig, &'a hir::Generics, hir::BodyId,
             defaultness: self.3,
             all_types,
             ret_types,
+            default_impl: None,
         }
     }
 }
 
 impl Clean<Item> for doctree::Function<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
-        let (generics, decl) = enter_impl_trait(cx, || {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
+        let (generics, decl) = enter_impl_trait(cx, |cx| {
             (self.generics.clean(cx), (self.decl, self.body).clean(cx))
         });
 
@@ -915,12 +943,13 @@ impl Clean<Item> for doctree::Function<'_> {
         };
         let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             def_id: did,
             inner: FunctionItem(Function {
                 decl,
@@ -934,13 +963,12 @@ impl Clean<Item> for doctree::Function<'_> {
 }
 
 impl<'a> Clean<Arguments> for (&'a [hir::Ty], &'a [ast::Ident]) {
-    fn clean(&self, cx: &DocContext<'_>) -> Arguments {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Arguments {
         Arguments {
             values: self.0.iter().enumerate().map(|(i, ty)| {
-                let mut name = self.1.get(i).map(|ident| ident.to_string())
-                                            .unwrap_or(String::new());
-                if name.is_empty() {
-                    name = "_".to_string();
+                let mut name = self.1.get(i).map(|ident| ident.name).unwrap_or(kw::Invalid);
+                if name == kw::Invalid {
+                    name = kw::Underscore;
                 }
                 Argument {
                     name,
@@ -952,13 +980,13 @@ impl<'a> Clean<Arguments> for (&'a [hir::Ty], &'a [ast::Ident]) {
 }
 
 impl<'a> Clean<Arguments> for (&'a [hir::Ty], hir::BodyId) {
-    fn clean(&self, cx: &DocContext<'_>) -> Arguments {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Arguments {
         let body = cx.tcx.hir().body(self.1);
 
         Arguments {
             values: self.0.iter().enumerate().map(|(i, ty)| {
                 Argument {
-                    name: name_from_pat(&body.params[i].pat),
+                    name: Symbol::intern(&name_from_pat(&body.params[i].pat)),
                     type_: ty.clean(cx),
                 }
             }).collect()
@@ -969,7 +997,7 @@ impl<'a> Clean<Arguments> for (&'a [hir::Ty], hir::BodyId) {
 impl<'a, A: Copy> Clean<FnDecl> for (&'a hir::FnDecl, A)
     where (&'a [hir::Ty], A): Clean<Arguments>
 {
-    fn clean(&self, cx: &DocContext<'_>) -> FnDecl {
+    fn clean(&self, cx: &mut DocContext<'_>) -> FnDecl {
         FnDecl {
             inputs: (&self.0.inputs[..], self.1).clean(cx),
             output: self.0.output.clean(cx),
@@ -980,7 +1008,7 @@ impl<'a, A: Copy> Clean<FnDecl> for (&'a hir::FnDecl, A)
 }
 
 impl<'tcx> Clean<FnDecl> for (DefId, ty::PolyFnSig<'tcx>) {
-    fn clean(&self, cx: &DocContext<'_>) -> FnDecl {
+    fn clean(&self, cx: &mut DocContext<'_>) -> FnDecl {
         let (did, sig) = *self;
         let mut names = if cx.tcx.hir().as_local_hir_id(did).is_some() {
             vec![].into_iter()
@@ -996,7 +1024,7 @@ impl<'tcx> Clean<FnDecl> for (DefId, ty::PolyFnSig<'tcx>) {
                 values: sig.skip_binder().inputs().iter().map(|t| {
                     Argument {
                         type_: t.clean(cx),
-                        name: names.next().map_or(String::new(), |name| name.to_string()),
+                        name: names.next().unwrap_or(kw::Invalid),
                     }
                 }).collect(),
             },
@@ -1005,7 +1033,7 @@ impl<'tcx> Clean<FnDecl> for (DefId, ty::PolyFnSig<'tcx>) {
 }
 
 impl Clean<FunctionRetTy> for hir::FunctionRetTy {
-    fn clean(&self, cx: &DocContext<'_>) -> FunctionRetTy {
+    fn clean(&self, cx: &mut DocContext<'_>) -> FunctionRetTy {
         match *self {
             hir::Return(ref typ) => Return(typ.clean(cx)),
             hir::DefaultReturn(..) => DefaultReturn,
@@ -1014,24 +1042,26 @@ impl Clean<FunctionRetTy> for hir::FunctionRetTy {
 }
 
 impl Clean<Item> for doctree::Trait<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let attrs = self.attrs.clean(cx);
-        let is_spotlight = attrs.has_doc_flag(sym::spotlight);
+        let def_id = cx.tcx.hir().local_def_id(self.id);
+        let is_notable = record_notable_trait(cx, def_id);
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs,
             source: self.whence.clean(cx),
-            def_id: cx.tcx.hir().local_def_id(self.id),
+            def_id,
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: TraitItem(Trait {
                 auto: self.is_auto.clean(cx),
                 unsafety: self.unsafety,
                 items: self.items.iter().map(|ti| ti.clean(cx)).collect(),
                 generics: self.generics.clean(cx),
                 bounds: self.bounds.clean(cx),
-                is_spotlight,
+                is_notable,
                 is_auto: self.is_auto.clean(cx),
             }),
         }
@@ -1039,16 +1069,17 @@ impl Clean<Item> for doctree::Trait<'_> {
 }
 
 impl Clean<Item> for doctree::TraitAlias<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let attrs = self.attrs.clean(cx);
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs,
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(self.id),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: TraitAliasItem(TraitAlias {
                 generics: self.generics.clean(cx),
                 bounds: self.bounds.clean(cx),
@@ -1058,7 +1089,7 @@ impl Clean<Item> for doctree::TraitAlias<'_> {
 }
 
 impl Clean<bool> for hir::IsAuto {
-    fn clean(&self, _: &DocContext<'_>) -> bool {
+    fn clean(&self, _: &mut DocContext<'_>) -> bool {
         match *self {
             hir::IsAuto::Yes => true,
             hir::IsAuto::No => false,
@@ -1067,13 +1098,14 @@ impl Clean<bool> for hir::IsAuto {
 }
 
 impl Clean<Type> for hir::TraitRef {
-    fn clean(&self, cx: &DocContext<'_>) -> Type {
-        resolve_type(cx, self.path.clean(cx), self.hir_ref_id)
+    fn clean(&self, cx: &mut DocContext<'_>) -> Type {
+        let path = self.path.clean(cx);
+        resolve_type(cx, path, self.hir_ref_id)
     }
 }
 
 impl Clean<PolyTrait> for hir::PolyTraitRef {
-    fn clean(&self, cx: &DocContext<'_>) -> PolyTrait {
+    fn clean(&self, cx: &mut DocContext<'_>) -> PolyTrait {
         PolyTrait {
             trait_: self.trait_ref.clean(cx),
             generic_params: self.bound_generic_params.clean(cx)
@@ -1082,17 +1114,19 @@ impl Clean<PolyTrait> for hir::PolyTraitRef {
 }
 
 impl Clean<Item> for hir::TraitItem {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let inner = match self.kind {
             hir::TraitItemKind::Const(ref ty, default) => {
                 AssocConstItem(ty.clean(cx),
                                     default.map(|e| print_const_expr(cx, e)))
             }
             hir::TraitItemKind::Method(ref sig, hir::TraitMethod::Provided(body)) => {
-                MethodItem((sig, &self.generics, body, None).clean(cx))
+                let mut m: Method = (sig, &self.generics, body, None).clean(cx);
+                m.default_impl = span_to_dedented_snippet(cx, self.span);
+                MethodItem(m)
             }
             hir::TraitItemKind::Method(ref sig, hir::TraitMethod::Required(ref names)) => {
-                let (generics, decl) = enter_impl_trait(cx, || {
+                let (generics, decl) = enter_impl_trait(cx, |cx| {
                     (self.generics.clean(cx), (&*sig.decl, &names[..]).clean(cx))
                 });
                 let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
@@ -1110,20 +1144,21 @@ impl Clean<Item> for hir::TraitItem {
         };
         let local_did = cx.tcx.hir().local_def_id(self.hir_id);
         Item {
-            name: Some(self.ident.name.clean(cx)),
+            name: Some(self.ident.name),
             attrs: self.attrs.clean(cx),
             source: self.span.clean(cx),
             def_id: local_did,
             visibility: Visibility::Inherited,
             stability: get_stability(cx, local_did),
             deprecation: get_deprecation(cx, local_did),
+            canonical_path: None,
             inner,
         }
     }
 }
 
 impl Clean<Item> for hir::ImplItem {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let inner = match self.kind {
             hir::ImplItemKind::Const(ref ty, expr) => {
                 AssocConstItem(ty.clean(cx),
@@ -1143,20 +1178,21 @@ impl Clean<Item> for hir::ImplItem {
         };
         let local_did = cx.tcx.hir().local_def_id(self.hir_id);
         Item {
-            name: Some(self.ident.name.clean(cx)),
+            name: Some(self.ident.name),
             source: self.span.clean(cx),
             attrs: self.attrs.clean(cx),
             def_id: local_did,
             visibility: self.vis.clean(cx),
             stability: get_stability(cx, local_did),
             deprecation: get_deprecation(cx, local_did),
+            canonical_path: None,
             inner,
         }
     }
 }
 
 impl Clean<Item> for ty::AssocItem {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let inner = match self.kind {
             ty::AssocKind::Const => {
                 let ty = cx.tcx.type_of(self.def_id);
@@ -1182,12 +1218,12 @@ impl Clean<Item> for ty::AssocItem {
                     };
                     let self_arg_ty = *sig.input(0).skip_binder();
                     if self_arg_ty == self_ty {
-                        decl.inputs.values[0].type_ = Generic(String::from("Self"));
+                        decl.inputs.values[0].type_ = Generic(kw::SelfUpper);
                     } else if let ty::Ref(_, ty, _) = self_arg_ty.kind {
                         if ty == self_ty {
                             match decl.inputs.values[0].type_ {
                                 BorrowedRef{ref mut type_, ..} => {
-                                    **type_ = Generic(String::from("Self"))
+                                    **type_ = Generic(kw::SelfUpper)
                                 }
                                 _ => unreachable!(),
                             }
@@ -1223,6 +1259,8 @@ impl Clean<Item> for ty::AssocItem {
                         defaultness,
                         all_types,
                         ret_types,
+                        // Filled in by `inline::try_inline` for the traits which get a page.
+                        default_impl: None,
                     })
                 } else {
                     TyMethodItem(TyMethod {
@@ -1240,7 +1278,7 @@ impl Clean<Item> for ty::AssocItem {
                 }
             }
             ty::AssocKind::Type => {
-                let my_name = self.ident.name.clean(cx);
+                let my_name = self.ident.name;
 
                 if let ty::TraitContainer(did) = self.container {
                     // When loading a cross-crate associated type, the bounds for this type
@@ -1263,7 +1301,7 @@ impl Clean<Item> for ty::AssocItem {
                             _ => return None,
                         }
                         match **self_type {
-                            Generic(ref s) if *s == "Self" => {}
+                            Generic(s) if s == kw::SelfUpper => {}
                             _ => return None,
                         }
                         Some(bounds)
@@ -1295,7 +1333,17 @@ impl Clean<Item> for ty::AssocItem {
                     }, true)
                 }
             }
-            ty::AssocKind::OpaqueTy => unimplemented!(),
+            ty::AssocKind::OpaqueTy => {
+                report_uncleanable(cx, Some(cx.tcx.def_span(self.def_id)),
+                                   "the bounds of associated opaque types");
+                TypedefItem(Typedef {
+                    type_: Infer,
+                    generics: Generics {
+                        params: Vec::new(),
+                        where_predicates: Vec::new(),
+                    },
+                }, true)
+            }
         };
 
         let visibility = match self.container {
@@ -1304,10 +1352,11 @@ impl Clean<Item> for ty::AssocItem {
         };
 
         Item {
-            name: Some(self.ident.name.clean(cx)),
+            name: Some(self.ident.name),
             visibility,
             stability: get_stability(cx, self.def_id),
             deprecation: get_deprecation(cx, self.def_id),
+            canonical_path: None,
             def_id: self.def_id,
             attrs: inline::load_attrs(cx, self.def_id).clean(cx),
             source: cx.tcx.def_span(self.def_id).clean(cx),
@@ -1317,7 +1366,7 @@ impl Clean<Item> for ty::AssocItem {
 }
 
 impl Clean<Type> for hir::Ty {
-    fn clean(&self, cx: &DocContext<'_>) -> Type {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Type {
         use rustc::hir::*;
 
         match self.kind {
@@ -1341,9 +1390,12 @@ impl Clean<Type> for hir::Ty {
                     instance: ty::Instance::new(def_id, substs),
                     promoted: None
                 };
-                let length = match cx.tcx.const_eval(param_env.and(cid)) {
-                    Ok(length) => print_const(cx, length),
-                    Err(_) => cx.sess()
+                let length = eval_const(cx, param_env, cid, "evaluate the length of this array",
+                                        "it is shown as it is written");
+                let length = match length {
+                    Some(length) => print_const(cx, length),
+                    // The length is shown as written when it cannot be evaluated.
+                    _ => cx.sess()
                                 .source_map()
                                 .span_to_snippet(cx.tcx.def_span(def_id))
                                 .unwrap_or_else(|_| "_".to_string()),
@@ -1356,15 +1408,16 @@ impl Clean<Type> for hir::Ty {
                 if let hir::ItemKind::OpaqueTy(ref ty) = item.kind {
                     ImplTrait(ty.bounds.clean(cx))
                 } else {
-                    unreachable!()
+                    report_uncleanable(cx, Some(self.span), "this opaque type");
+                    Infer
                 }
             }
             TyKind::Path(hir::QPath::Resolved(None, ref path)) => {
                 if let Res::Def(DefKind::TyParam, did) = path.res {
-                    if let Some(new_ty) = cx.ty_substs.borrow().get(&did).cloned() {
+                    if let Some(new_ty) = cx.ty_substs.get(&did).cloned() {
                         return new_ty;
                     }
-                    if let Some(bounds) = cx.impl_trait_bounds.borrow_mut().remove(&did.into()) {
+                    if let Some(bounds) = cx.impl_trait_bounds.remove(&did.into()) {
                         return ImplTrait(bounds);
                     }
                 }
@@ -1373,7 +1426,7 @@ impl Clean<Type> for hir::Ty {
                 if let Res::Def(DefKind::TyAlias, def_id) = path.res {
                     // Substitute private type aliases
                     if let Some(hir_id) = cx.tcx.hir().as_local_hir_id(def_id) {
-                        if !cx.renderinfo.borrow().access_levels.is_exported(def_id) {
+                        if !cx.renderinfo.access_levels.is_exported(def_id) {
                             alias = Some(&cx.tcx.hir().expect_item(hir_id).kind);
                         }
                     }
@@ -1461,9 +1514,10 @@ impl Clean<Type> for hir::Ty {
                             }
                         }
                     }
-                    return cx.enter_alias(ty_substs, lt_substs, ct_substs, || ty.clean(cx));
+                    return cx.enter_alias(ty_substs, lt_substs, ct_substs, |cx| ty.clean(cx));
                 }
-                resolve_type(cx, path.clean(cx), self.hir_id)
+                let path = path.clean(cx);
+                resolve_type(cx, path, self.hir_id)
             }
             TyKind::Path(hir::QPath::Resolved(Some(ref qself), ref p)) => {
                 let segments = if p.is_global() { &p.segments[1..] } else { &p.segments };
@@ -1477,7 +1531,7 @@ impl Clean<Type> for hir::Ty {
                     segments: trait_segments.clean(cx),
                 };
                 Type::QPath {
-                    name: p.segments.last().expect("segments were empty").ident.name.clean(cx),
+                    name: p.segments.last().expect("segments were empty").ident.name,
                     self_type: box qself.clean(cx),
                     trait_: box resolve_type(cx, trait_path, self.hir_id)
                 }
@@ -1492,11 +1546,11 @@ impl Clean<Type> for hir::Ty {
                     span: self.span,
                     res,
                     segments: vec![].into(),
-                };
+                }.clean(cx);
                 Type::QPath {
-                    name: segment.ident.name.clean(cx),
+                    name: segment.ident.name,
                     self_type: box qself.clean(cx),
-                    trait_: box resolve_type(cx, trait_path.clean(cx), self.hir_id)
+                    trait_: box resolve_type(cx, trait_path, self.hir_id)
                 }
             }
             TyKind::TraitObject(ref bounds, ref lifetime) => {
@@ -1516,13 +1570,16 @@ impl Clean<Type> for hir::Ty {
             }
             TyKind::BareFn(ref barefn) => BareFunction(box barefn.clean(cx)),
             TyKind::Infer | TyKind::Err => Infer,
-            TyKind::Typeof(..) => panic!("unimplemented type {:?}", self.kind),
+            TyKind::Typeof(..) => {
+                report_uncleanable(cx, Some(self.span), "`typeof` types");
+                Infer
+            }
         }
     }
 }
 
 impl<'tcx> Clean<Type> for Ty<'tcx> {
-    fn clean(&self, cx: &DocContext<'_>) -> Type {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Type {
         debug!("cleaning type: {:?}", self);
         match self.kind {
             ty::Never => Never,
@@ -1541,7 +1598,9 @@ impl<'tcx> Clean<Type> for Ty<'tcx> {
                         instance: ty::Instance::new(def_id, substs),
                         promoted: None
                     };
-                    if let Ok(new_n) = cx.tcx.const_eval(param_env.and(cid)) {
+                    if let Some(new_n) = eval_const(cx, param_env, cid,
+                                                    "evaluate the length of this array",
+                                                    "it is shown as it is written") {
                         n = new_n;
                     }
                 };
@@ -1598,9 +1657,13 @@ impl<'tcx> Clean<Type> for Ty<'tcx> {
                 // might want to implement "native" support for marker-trait-only
                 // trait objects.
                 let mut dids = obj.principal_def_id().into_iter().chain(obj.auto_traits());
-                let did = dids.next().unwrap_or_else(|| {
-                    panic!("found trait object `{:?}` with no traits?", self)
-                });
+                let did = match dids.next() {
+                    Some(did) => did,
+                    None => {
+                        report_uncleanable(cx, None, &format!("the trait object `{}`", self));
+                        return Infer;
+                    }
+                };
                 let substs = match obj.principal() {
                     Some(principal) => principal.skip_binder().substs,
                     // marker traits have no substs.
@@ -1631,7 +1694,7 @@ impl<'tcx> Clean<Type> for Ty<'tcx> {
                 let mut bindings = vec![];
                 for pb in obj.projection_bounds() {
                     bindings.push(TypeBinding {
-                        name: cx.tcx.associated_item(pb.item_def_id()).ident.name.clean(cx),
+                        name: cx.tcx.associated_item(pb.item_def_id()).ident.name,
                         kind: TypeBindingKind::Equality {
                             ty: pb.skip_binder().ty.clean(cx)
                         },
@@ -1654,10 +1717,10 @@ impl<'tcx> Clean<Type> for Ty<'tcx> {
             ty::Projection(ref data) => data.clean(cx),
 
             ty::Param(ref p) => {
-                if let Some(bounds) = cx.impl_trait_bounds.borrow_mut().remove(&p.index.into()) {
+                if let Some(bounds) = cx.impl_trait_bounds.remove(&p.index.into()) {
                     ImplTrait(bounds)
                 } else {
-                    Generic(p.name.to_string())
+                    Generic(p.name)
                 }
             }
 
@@ -1695,7 +1758,7 @@ impl<'tcx> Clean<Type> for Ty<'tcx> {
                             if proj.projection_ty.trait_ref(cx.tcx) == *trait_ref.skip_binder() {
                                 Some(TypeBinding {
                                     name: cx.tcx.associated_item(proj.projection_ty.item_def_id)
-                                                .ident.name.clean(cx),
+                                                .ident.name,
                                     kind: TypeBindingKind::Equality {
                                         ty: proj.ty.clean(cx),
                                     },
@@ -1719,36 +1782,44 @@ impl<'tcx> Clean<Type> for Ty<'tcx> {
 
             ty::Closure(..) | ty::Generator(..) => Tuple(vec![]), // FIXME(pcwalton)
 
-            ty::Bound(..) => panic!("Bound"),
-            ty::Placeholder(..) => panic!("Placeholder"),
-            ty::UnnormalizedProjection(..) => panic!("UnnormalizedProjection"),
-            ty::GeneratorWitness(..) => panic!("GeneratorWitness"),
-            ty::Infer(..) => panic!("Infer"),
-            ty::Error => panic!("Error"),
+            // These types only exist while the compiler type-checks the crate, and should not
+            // show up in the types of the items.
+            ty::Bound(..) |
+            ty::Placeholder(..) |
+            ty::UnnormalizedProjection(..) |
+            ty::GeneratorWitness(..) |
+            ty::Infer(..) => {
+                report_uncleanable(cx, None, &format!("the type `{:?}`", self));
+                Infer
+            }
+            // The error was reported already.
+            ty::Error => Infer,
         }
     }
 }
 
 impl<'tcx> Clean<Constant> for ty::Const<'tcx> {
-    fn clean(&self, cx: &DocContext<'_>) -> Constant {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Constant {
         Constant {
             type_: self.ty.clean(cx),
             expr: format!("{}", self),
+            value: None,
         }
     }
 }
 
 impl Clean<Item> for hir::StructField {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let local_did = cx.tcx.hir().local_def_id(self.hir_id);
 
         Item {
-            name: Some(self.ident.name).clean(cx),
+            name: Some(self.ident.name),
             attrs: self.attrs.clean(cx),
             source: self.span.clean(cx),
             visibility: self.vis.clean(cx),
             stability: get_stability(cx, local_did),
             deprecation: get_deprecation(cx, local_did),
+            canonical_path: None,
             def_id: local_did,
             inner: StructFieldItem(self.ty.clean(cx)),
         }
@@ -1756,14 +1827,15 @@ impl Clean<Item> for hir::StructField {
 }
 
 impl Clean<Item> for ty::FieldDef {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.ident.name).clean(cx),
+            name: Some(self.ident.name),
             attrs: cx.tcx.get_attrs(self.did).clean(cx),
             source: cx.tcx.def_span(self.did).clean(cx),
             visibility: self.vis.clean(cx),
             stability: get_stability(cx, self.did),
             deprecation: get_deprecation(cx, self.did),
+            canonical_path: None,
             def_id: self.did,
             inner: StructFieldItem(cx.tcx.type_of(self.did).clean(cx)),
         }
@@ -1771,7 +1843,7 @@ impl Clean<Item> for ty::FieldDef {
 }
 
 impl Clean<Visibility> for hir::Visibility {
-    fn clean(&self, cx: &DocContext<'_>) -> Visibility {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Visibility {
         match self.node {
             hir::VisibilityKind::Public => Visibility::Public,
             hir::VisibilityKind::Inherited => Visibility::Inherited,
@@ -1786,53 +1858,57 @@ impl Clean<Visibility> for hir::Visibility {
 }
 
 impl Clean<Visibility> for ty::Visibility {
-    fn clean(&self, _: &DocContext<'_>) -> Visibility {
+    fn clean(&self, _: &mut DocContext<'_>) -> Visibility {
         if *self == ty::Visibility::Public { Public } else { Inherited }
     }
 }
 
 impl Clean<Item> for doctree::Struct<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(self.id),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: StructItem(Struct {
                 struct_type: self.struct_type,
                 generics: self.generics.clean(cx),
                 fields: self.fields.clean(cx),
                 fields_stripped: false,
+                layout: type_layout(cx, cx.tcx.hir().local_def_id(self.id)),
             }),
         }
     }
 }
 
 impl Clean<Item> for doctree::Union<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(self.id),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: UnionItem(Union {
                 struct_type: self.struct_type,
                 generics: self.generics.clean(cx),
                 fields: self.fields.clean(cx),
                 fields_stripped: false,
+                layout: type_layout(cx, cx.tcx.hir().local_def_id(self.id)),
             }),
         }
     }
 }
 
 impl Clean<VariantStruct> for ::rustc::hir::VariantData {
-    fn clean(&self, cx: &DocContext<'_>) -> VariantStruct {
+    fn clean(&self, cx: &mut DocContext<'_>) -> VariantStruct {
         VariantStruct {
             struct_type: doctree::struct_type_from_def(self),
             fields: self.fields().iter().map(|x| x.clean(cx)).collect(),
@@ -1842,33 +1918,36 @@ impl Clean<VariantStruct> for ::rustc::hir::VariantData {
 }
 
 impl Clean<Item> for doctree::Enum<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(self.id),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: EnumItem(Enum {
                 variants: self.variants.iter().map(|v| v.clean(cx)).collect(),
                 generics: self.generics.clean(cx),
                 variants_stripped: false,
+                layout: type_layout(cx, cx.tcx.hir().local_def_id(self.id)),
             }),
         }
     }
 }
 
 impl Clean<Item> for doctree::Variant<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             visibility: Inherited,
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             def_id: cx.tcx.hir().local_def_id(self.id),
             inner: VariantItem(Variant {
                 kind: self.def.clean(cx),
@@ -1878,7 +1957,7 @@ impl Clean<Item> for doctree::Variant<'_> {
 }
 
 impl Clean<Item> for ty::VariantDef {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let kind = match self.ctor_kind {
             CtorKind::Const => VariantKind::CLike,
             CtorKind::Fn => {
@@ -1893,12 +1972,13 @@ impl Clean<Item> for ty::VariantDef {
                     fields: self.fields.iter().map(|field| {
                         Item {
                             source: cx.tcx.def_span(field.did).clean(cx),
-                            name: Some(field.ident.name.clean(cx)),
+                            name: Some(field.ident.name),
                             attrs: cx.tcx.get_attrs(field.did).clean(cx),
                             visibility: field.vis.clean(cx),
                             def_id: field.did,
                             stability: get_stability(cx, field.did),
                             deprecation: get_deprecation(cx, field.did),
+                            canonical_path: None,
                             inner: StructFieldItem(cx.tcx.type_of(field.did).clean(cx))
                         }
                     }).collect()
@@ -1906,7 +1986,7 @@ impl Clean<Item> for ty::VariantDef {
             }
         };
         Item {
-            name: Some(self.ident.clean(cx)),
+            name: Some(self.ident.name),
             attrs: inline::load_attrs(cx, self.def_id).clean(cx),
             source: cx.tcx.def_span(self.def_id).clean(cx),
             visibility: Inherited,
@@ -1914,12 +1994,13 @@ impl Clean<Item> for ty::VariantDef {
             inner: VariantItem(Variant { kind }),
             stability: get_stability(cx, self.def_id),
             deprecation: get_deprecation(cx, self.def_id),
+            canonical_path: None,
         }
     }
 }
 
 impl Clean<VariantKind> for hir::VariantData {
-    fn clean(&self, cx: &DocContext<'_>) -> VariantKind {
+    fn clean(&self, cx: &mut DocContext<'_>) -> VariantKind {
         match self {
             hir::VariantData::Struct(..) => VariantKind::Struct(self.clean(cx)),
             hir::VariantData::Tuple(..) =>
@@ -1930,7 +2011,7 @@ impl Clean<VariantKind> for hir::VariantData {
 }
 
 impl Clean<Span> for syntax_pos::Span {
-    fn clean(&self, cx: &DocContext<'_>) -> Span {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Span {
         if self.is_dummy() {
             return Span::empty();
         }
@@ -1951,7 +2032,7 @@ impl Clean<Span> for syntax_pos::Span {
 }
 
 impl Clean<Path> for hir::Path {
-    fn clean(&self, cx: &DocContext<'_>) -> Path {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Path {
         Path {
             global: self.is_global(),
             res: self.res,
@@ -1961,7 +2042,7 @@ impl Clean<Path> for hir::Path {
 }
 
 impl Clean<GenericArgs> for hir::GenericArgs {
-    fn clean(&self, cx: &DocContext<'_>) -> GenericArgs {
+    fn clean(&self, cx: &mut DocContext<'_>) -> GenericArgs {
         if self.parenthesized {
             let output = self.bindings[0].ty().clean(cx);
             GenericArgs::Parenthesized {
@@ -1989,38 +2070,25 @@ impl Clean<GenericArgs> for hir::GenericArgs {
 }
 
 impl Clean<PathSegment> for hir::PathSegment {
-    fn clean(&self, cx: &DocContext<'_>) -> PathSegment {
+    fn clean(&self, cx: &mut DocContext<'_>) -> PathSegment {
         PathSegment {
-            name: self.ident.name.clean(cx),
+            name: self.ident.name,
             args: self.generic_args().clean(cx),
         }
     }
 }
 
-impl Clean<String> for Ident {
-    #[inline]
-    fn clean(&self, cx: &DocContext<'_>) -> String {
-        self.name.clean(cx)
-    }
-}
-
-impl Clean<String> for ast::Name {
-    #[inline]
-    fn clean(&self, _: &DocContext<'_>) -> String {
-        self.to_string()
-    }
-}
-
 impl Clean<Item> for doctree::Typedef<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(self.id),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: TypedefItem(Typedef {
                 type_: self.ty.clean(cx),
                 generics: self.gen.clean(cx),
@@ -2030,15 +2098,16 @@ impl Clean<Item> for doctree::Typedef<'_> {
 }
 
 impl Clean<Item> for doctree::OpaqueTy<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(self.id),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: OpaqueTyItem(OpaqueTy {
                 bounds: self.opaque_ty.bounds.clean(cx),
                 generics: self.opaque_ty.generics.clean(cx),
@@ -2048,8 +2117,8 @@ impl Clean<Item> for doctree::OpaqueTy<'_> {
 }
 
 impl Clean<BareFunctionDecl> for hir::BareFnTy {
-    fn clean(&self, cx: &DocContext<'_>) -> BareFunctionDecl {
-        let (generic_params, decl) = enter_impl_trait(cx, || {
+    fn clean(&self, cx: &mut DocContext<'_>) -> BareFunctionDecl {
+        let (generic_params, decl) = enter_impl_trait(cx, |cx| {
             (self.generic_params.clean(cx), (&*self.decl, &self.param_names[..]).clean(cx))
         });
         BareFunctionDecl {
@@ -2062,45 +2131,59 @@ impl Clean<BareFunctionDecl> for hir::BareFnTy {
 }
 
 impl Clean<Item> for doctree::Static<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
-        debug!("cleaning static {}: {:?}", self.name.clean(cx), self);
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
+        debug!("cleaning static {}: {:?}", self.name, self);
+        let def_id = cx.tcx.hir().local_def_id(self.id);
+        let mutability = self.mutability.clean(cx);
+        let expr = print_const_expr(cx, self.expr);
+        // The value of a `static mut` is only its initial one.
+        let value = match mutability {
+            Mutable => None,
+            Immutable => print_evaluated_const(cx, def_id, &expr),
+        };
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
-            def_id: cx.tcx.hir().local_def_id(self.id),
+            def_id,
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: StaticItem(Static {
                 type_: self.type_.clean(cx),
-                mutability: self.mutability.clean(cx),
-                expr: print_const_expr(cx, self.expr),
+                mutability,
+                expr,
+                value,
             }),
         }
     }
 }
 
 impl Clean<Item> for doctree::Constant<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
+        let def_id = cx.tcx.hir().local_def_id(self.id);
+        let expr = print_const_expr(cx, self.expr);
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
-            def_id: cx.tcx.hir().local_def_id(self.id),
+            def_id,
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner: ConstantItem(Constant {
                 type_: self.type_.clean(cx),
-                expr: print_const_expr(cx, self.expr),
+                value: print_evaluated_const(cx, def_id, &expr),
+                expr,
             }),
         }
     }
 }
 
 impl Clean<Mutability> for hir::Mutability {
-    fn clean(&self, _: &DocContext<'_>) -> Mutability {
+    fn clean(&self, _: &mut DocContext<'_>) -> Mutability {
         match self {
             &hir::Mutability::Mut => Mutable,
             &hir::Mutability::Not => Immutable,
@@ -2109,7 +2192,7 @@ impl Clean<Mutability> for hir::Mutability {
 }
 
 impl Clean<ImplPolarity> for ty::ImplPolarity {
-    fn clean(&self, _: &DocContext<'_>) -> ImplPolarity {
+    fn clean(&self, _: &mut DocContext<'_>) -> ImplPolarity {
         match self {
             &ty::ImplPolarity::Positive |
             // FIXME: do we want to do something else here?
@@ -2120,17 +2203,24 @@ impl Clean<ImplPolarity> for ty::ImplPolarity {
 }
 
 impl Clean<Vec<Item>> for doctree::Impl<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Vec<Item> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Vec<Item> {
         let mut ret = Vec::new();
         let trait_ = self.trait_.clean(cx);
-        let items = self.items.iter().map(|ii| ii.clean(cx)).collect::<Vec<_>>();
+        let mut items = self.items.iter().map(|ii| ii.clean(cx)).collect::<Vec<_>>();
         let def_id = cx.tcx.hir().local_def_id(self.id);
+        let attrs = self.attrs.clean(cx);
+        let deprecation = cx.deprecation(self.id).clean(cx);
+        let inherited = ImplAttrs::new(&attrs, deprecation.as_ref());
+        inherited.apply(&mut items);
 
         // If this impl block is an implementation of the Deref trait, then we
         // need to try inlining the target's inherent impl blocks as well.
         if trait_.def_id() == cx.tcx.lang_items().deref_trait() {
             build_deref_target_impls(cx, &items, &mut ret);
         }
+        if let Some(trait_did) = trait_.def_id() {
+            record_notable_trait(cx, trait_did);
+        }
 
         let provided = trait_.def_id().map(|did| {
             cx.tcx.provided_trait_methods(did)
@@ -2141,12 +2231,13 @@ impl Clean<Vec<Item>> for doctree::Impl<'_> {
 
         ret.push(Item {
             name: None,
-            attrs: self.attrs.clean(cx),
+            attrs,
             source: self.whence.clean(cx),
             def_id,
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
-            deprecation: cx.deprecation(self.id).clean(cx),
+            deprecation,
+            canonical_path: None,
             inner: ImplItem(Impl {
                 unsafety: self.unsafety,
                 generics: self.generics.clean(cx),
@@ -2157,6 +2248,7 @@ impl Clean<Vec<Item>> for doctree::Impl<'_> {
                 polarity: Some(cx.tcx.impl_polarity(def_id).clean(cx)),
                 synthetic: false,
                 blanket_impl: None,
+                inherited,
             })
         });
         ret
@@ -2164,7 +2256,7 @@ impl Clean<Vec<Item>> for doctree::Impl<'_> {
 }
 
 impl Clean<Vec<Item>> for doctree::ExternCrate<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Vec<Item> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Vec<Item> {
 
         let please_inline = self.vis.node.is_pub() && self.attrs.iter().any(|a| {
             a.check_name(sym::doc) && match a.meta_item_list() {
@@ -2201,13 +2293,14 @@ impl Clean<Vec<Item>> for doctree::ExternCrate<'_> {
             visibility: self.vis.clean(cx),
             stability: None,
             deprecation: None,
-            inner: ExternCrateItem(self.name.clean(cx), self.path.clone())
+            canonical_path: None,
+            inner: ExternCrateItem(self.name, self.path.clone())
         }]
     }
 }
 
 impl Clean<Vec<Item>> for doctree::Import<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Vec<Item> {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Vec<Item> {
         // We consider inlining the documentation of `pub use` statements, but we
         // forcefully don't inline if this is not public or if the
         // #[doc(no_inline)] attribute is present.
@@ -2248,15 +2341,21 @@ impl Clean<Vec<Item>> for doctree::Import<'_> {
             }
             if !denied {
                 let mut visited = FxHashSet::default();
-                if let Some(items) = inline::try_inline(
+                if let Some(mut items) = inline::try_inline(
                     cx, path.res, name,
                     Some(rustc::ty::Attributes::Borrowed(self.attrs)),
                     &mut visited
                 ) {
+                    if cx.inline_hidden_reexports {
+                        let did = path.res.def_id();
+                        for item in items.iter_mut().filter(|item| item.def_id == did) {
+                            inline::unhide(item);
+                        }
+                    }
                     return items;
                 }
             }
-            Import::Simple(name.clean(cx), resolve_use_source(cx, path))
+            Import::Simple(name, resolve_use_source(cx, path))
         };
 
         vec![Item {
@@ -2267,17 +2366,18 @@ impl Clean<Vec<Item>> for doctree::Import<'_> {
             visibility: self.vis.clean(cx),
             stability: None,
             deprecation: None,
+            canonical_path: None,
             inner: ImportItem(inner)
         }]
     }
 }
 
 impl Clean<Item> for doctree::ForeignItem<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         let inner = match self.kind {
             hir::ForeignItemKind::Fn(ref decl, ref names, ref generics) => {
                 let abi = cx.tcx.hir().get_foreign_abi(self.id);
-                let (generics, decl) = enter_impl_trait(cx, || {
+                let (generics, decl) = enter_impl_trait(cx, |cx| {
                     (generics.clean(cx), (&**decl, &names[..]).clean(cx))
                 });
                 let (all_types, ret_types) = get_all_types(&generics, &decl, cx);
@@ -2299,6 +2399,7 @@ impl Clean<Item> for doctree::ForeignItem<'_> {
                     type_: ty.clean(cx),
                     mutability: mutbl.clean(cx),
                     expr: String::new(),
+                    value: None,
                 })
             }
             hir::ForeignItemKind::Type => {
@@ -2307,61 +2408,60 @@ impl Clean<Item> for doctree::ForeignItem<'_> {
         };
 
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             def_id: cx.tcx.hir().local_def_id(self.id),
             visibility: self.vis.clean(cx),
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             inner,
         }
     }
 }
 
 impl Clean<Item> for doctree::Macro<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
-        let name = self.name.clean(cx);
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
+        let name = self.name;
         Item {
-            name: Some(name.clone()),
+            name: Some(name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             visibility: Public,
             stability: cx.stability(self.hid).clean(cx),
             deprecation: cx.deprecation(self.hid).clean(cx),
+            canonical_path: None,
             def_id: self.def_id,
             inner: MacroItem(Macro {
-                source: format!("macro_rules! {} {{\n{}}}",
-                                name,
-                                self.matchers.iter().map(|span| {
-                                    format!("    {} => {{ ... }};\n", span.to_src(cx))
-                                }).collect::<String>()),
-                imported_from: self.imported_from.clean(cx),
+                source: display_macro_source(cx, &name.as_str(), &self.matchers, self.legacy),
+                imported_from: self.imported_from,
             }),
         }
     }
 }
 
 impl Clean<Item> for doctree::ProcMacro<'_> {
-    fn clean(&self, cx: &DocContext<'_>) -> Item {
+    fn clean(&self, cx: &mut DocContext<'_>) -> Item {
         Item {
-            name: Some(self.name.clean(cx)),
+            name: Some(self.name),
             attrs: self.attrs.clean(cx),
             source: self.whence.clean(cx),
             visibility: Public,
             stability: cx.stability(self.id).clean(cx),
             deprecation: cx.deprecation(self.id).clean(cx),
+            canonical_path: None,
             def_id: cx.tcx.hir().local_def_id(self.id),
             inner: ProcMacroItem(ProcMacro {
                 kind: self.kind,
-                helpers: self.helpers.clean(cx),
+                helpers: self.helpers.clone(),
             }),
         }
     }
 }
 
 impl Clean<Stability> for attr::Stability {
-    fn clean(&self, _: &DocContext<'_>) -> Stability {
+    fn clean(&self, _: &mut DocContext<'_>) -> Stability {
         Stability {
             level: stability::StabilityLevel::from_attr_level(&self.level),
             feature: Some(self.feature.to_string()).filter(|f| !f.is_empty()),
@@ -2382,19 +2482,20 @@ impl Clean<Stability> for attr::Stability {
             issue: match self.level {
                 attr::Unstable {issue, ..} => issue,
                 _ => None,
-            }
+            },
+            inherited_from: None,
         }
     }
 }
 
 impl<'a> Clean<Stability> for &'a attr::Stability {
-    fn clean(&self, dc: &DocContext<'_>) -> Stability {
+    fn clean(&self, dc: &mut DocContext<'_>) -> Stability {
         (**self).clean(dc)
     }
 }
 
 impl Clean<Deprecation> for attr::Deprecation {
-    fn clean(&self, _: &DocContext<'_>) -> Deprecation {
+    fn clean(&self, _: &mut DocContext<'_>) -> Deprecation {
         Deprecation {
             since: self.since.map(|s| s.to_string()).filter(|s| !s.is_empty()),
             note: self.note.map(|n| n.to_string()).filter(|n| !n.is_empty()),
@@ -2403,16 +2504,16 @@ impl Clean<Deprecation> for attr::Deprecation {
 }
 
 impl Clean<TypeBinding> for hir::TypeBinding {
-    fn clean(&self, cx: &DocContext<'_>) -> TypeBinding {
+    fn clean(&self, cx: &mut DocContext<'_>) -> TypeBinding {
         TypeBinding {
-            name: self.ident.name.clean(cx),
+            name: self.ident.name,
             kind: self.kind.clean(cx),
         }
     }
 }
 
 impl Clean<TypeBindingKind> for hir::TypeBindingKind {
-    fn clean(&self, cx: &DocContext<'_>) -> TypeBindingKind {
+    fn clean(&self, cx: &mut DocContext<'_>) -> TypeBindingKind {
         match *self {
             hir::TypeBindingKind::Equality { ref ty } =>
                 TypeBindingKind::Equality {
@@ -12,10 +12,11 @@
 //! bounds by special casing scenarios such as these. Fun!
 
 use std::mem;
-use std::collections::BTreeMap;
 
 use rustc::hir::def_id::DefId;
 use rustc::ty;
+use rustc::util::nodemap::FxHashMap;
+use syntax_pos::symbol::Symbol;
 
 use crate::clean::GenericArgs as PP;
 use crate::clean::WherePredicate as WP;
@@ -24,7 +25,7 @@ use crate::core::DocContext;
 
 pub fn where_clauses(cx: &DocContext<'_>, clauses: Vec<WP>) -> Vec<WP> {
     // First, partition the where clause into its separate components
-    let mut params: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    let mut params: FxHashMap<_, Vec<_>> = FxHashMap::default();
     let mut lifetimes = Vec::new();
     let mut equalities = Vec::new();
     let mut tybounds = Vec::new();
@@ -70,6 +71,9 @@ pub fn where_clauses(cx: &DocContext<'_>, clauses: Vec<WP>) -> Vec<WP> {
     clauses.extend(lifetimes.into_iter().map(|(lt, bounds)| {
         WP::RegionPredicate { lifetime: lt, bounds }
     }));
+    // The parameters are listed by name.
+    let mut params = params.into_iter().collect::<Vec<_>>();
+    params.sort_by_key(|&(k, _)| k.as_str());
     clauses.extend(params.into_iter().map(|(k, v)| {
         WP::BoundPredicate {
             ty: clean::Generic(k),
@@ -89,7 +93,7 @@ pub fn merge_bounds(
     cx: &clean::DocContext<'_>,
     bounds: &mut Vec<clean::GenericBound>,
     trait_did: DefId,
-    name: &str,
+    name: Symbol,
     rhs: &clean::Type,
 ) -> bool {
     !bounds.iter_mut().any(|b| {
@@ -111,7 +115,7 @@ pub fn merge_bounds(
         match last.args {
             PP::AngleBracketed { ref mut bindings, .. } => {
                 bindings.push(clean::TypeBinding {
-                    name: name.to_string(),
+                    name,
                     kind: clean::TypeBindingKind::Equality {
                         ty: rhs.clone(),
                     },
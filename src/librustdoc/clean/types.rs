@@ -4,7 +4,6 @@ use std::default::Default;
 use std::{slice, vec};
 use std::num::NonZeroU32;
 use std::iter::FromIterator;
-use std::rc::Rc;
 use std::cell::RefCell;
 use std::sync::Arc;
 
@@ -13,20 +12,22 @@ use rustc::middle::stability;
 use rustc::hir;
 use rustc::hir::def::Res;
 use rustc::hir::def_id::{CrateNum, DefId};
+use rustc::session::CrateDisambiguator;
 use rustc::ty::layout::VariantIdx;
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_index::vec::IndexVec;
 use rustc_target::spec::abi::Abi;
 use syntax::ast::{self, Attribute, AttrStyle, AttrKind, Ident};
 use syntax::attr;
+use syntax::print::pprust;
 use syntax::util::comments;
 use syntax::source_map::DUMMY_SP;
 use syntax_pos::hygiene::MacroKind;
-use syntax_pos::symbol::{Symbol, sym};
+use syntax_pos::symbol::{Symbol, SymbolStr, kw, sym};
 use syntax_pos::{self, FileName};
 
 use crate::core::DocContext;
-use crate::clean::cfg::Cfg;
+use crate::clean::cfg::{Cfg, CfgNames};
 use crate::clean::inline;
 use crate::clean::external_path;
 use crate::clean::types::Type::{QPath, ResolvedPath};
@@ -43,7 +44,7 @@ thread_local!(pub static MAX_DEF_ID: RefCell<FxHashMap<CrateNum, DefId>> = Defau
 
 #[derive(Clone, Debug)]
 pub struct Crate {
-    pub name: String,
+    pub name: Symbol,
     pub version: Option<String>,
     pub src: FileName,
     pub module: Option<Item>,
@@ -51,18 +52,28 @@ pub struct Crate {
     pub primitives: Vec<(DefId, PrimitiveType, Attributes)>,
     // These are later on moved into `CACHEKEY`, leaving the map empty.
     // Only here so that they can be filtered through the rustdoc passes.
-    pub external_traits: Rc<RefCell<FxHashMap<DefId, Trait>>>,
+    pub external_traits: FxHashMap<DefId, Trait>,
     pub masked_crates: FxHashSet<CrateNum>,
+    /// The masked crates named in `#![doc(masked(...))]`, which get a report of the impls they
+    /// mask.
+    pub reported_masked_crates: FxHashSet<CrateNum>,
+    /// The display names given to configurations with `#![doc(cfg_name(...))]`.
+    pub cfg_names: CfgNames,
     pub collapsed: bool,
 }
 
 #[derive(Clone, Debug)]
 pub struct ExternalCrate {
-    pub name: String,
+    pub name: Symbol,
+    /// Tells the crate apart from the other crates of the same name, in a way that, unlike its
+    /// `CrateNum`, does not depend on the compiler session. See `merge::merge_targets`.
+    pub disambiguator: CrateDisambiguator,
+    /// The version of the crate, if it can be told from its metadata.
+    pub version: Option<String>,
     pub src: FileName,
     pub attrs: Attributes,
     pub primitives: Vec<(DefId, PrimitiveType, Attributes)>,
-    pub keywords: Vec<(DefId, String, Attributes)>,
+    pub keywords: Vec<(DefId, Symbol, Attributes)>,
 }
 
 /// Anything with a source location and set of attributes and, optionally, a
@@ -73,13 +84,17 @@ pub struct Item {
     /// Stringified span
     pub source: Span,
     /// Not everything has a name. E.g., impls
-    pub name: Option<String>,
+    pub name: Option<Symbol>,
     pub attrs: Attributes,
     pub inner: ItemEnum,
     pub visibility: Visibility,
     pub def_id: DefId,
     pub stability: Option<Stability>,
     pub deprecation: Option<Deprecation>,
+    /// The public path the item is documented at, for the items getting a page of their own. It
+    /// differs from the path the item is defined at when the item is re-exported, e.g., from a
+    /// private module. Filled in once the crate is cleaned, see `utils::record_canonical_paths`.
+    pub canonical_path: Option<Vec<String>>,
 }
 
 impl fmt::Debug for Item {
@@ -97,6 +112,7 @@ impl fmt::Debug for Item {
             .field("def_id", def_id)
             .field("stability", &self.stability)
             .field("deprecation", &self.deprecation)
+            .field("canonical_path", &self.canonical_path)
             .finish()
     }
 }
@@ -242,7 +258,7 @@ impl Item {
 
 #[derive(Clone, Debug)]
 pub enum ItemEnum {
-    ExternCrateItem(String, Option<String>),
+    ExternCrateItem(Symbol, Option<String>),
     ImportItem(Import),
     StructItem(Struct),
     UnionItem(Union),
@@ -276,7 +292,7 @@ pub enum ItemEnum {
     AssocTypeItem(Vec<GenericBound>, Option<Type>),
     /// An item that has been stripped by a rustdoc pass
     StrippedItem(Box<ItemEnum>),
-    KeywordItem(String),
+    KeywordItem(Symbol),
 }
 
 impl ItemEnum {
@@ -418,10 +434,84 @@ pub struct Attributes {
     pub doc_strings: Vec<DocFragment>,
     pub other_attrs: Vec<ast::Attribute>,
     pub cfg: Option<Arc<Cfg>>,
+    /// The plain `#[cfg(...)]` attributes of the item, which are only merged into `cfg` when the
+    /// crate opts in with `#![doc(auto_cfg)]`.
+    pub auto_cfg: Option<Arc<Cfg>>,
     pub span: Option<syntax_pos::Span>,
     /// map from Rust paths to resolved defs and potential URL fragments
     pub links: Vec<(String, Option<DefId>, Option<String>)>,
     pub inner_docs: bool,
+    /// The module the documentation was written in, when it was inlined from another crate.
+    /// Its links are relative to that module rather than to the one it is displayed in.
+    pub scope: Option<DefId>,
+    /// The attributes shown in the declaration of the item, like `repr(C)`, see
+    /// `DISPLAYED_ATTRIBUTES`.
+    pub displayed: Vec<String>,
+    /// The other names the item can be searched by, given with `#[doc(alias = "...")]`.
+    pub aliases: Vec<DocAlias>,
+}
+
+/// A name an item can be searched by, given with `#[doc(alias = "...")]`, or with
+/// `#[doc(alias("...", priority = "low"))]` for a name the search should rank below the names of
+/// the items.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocAlias {
+    pub name: Symbol,
+    /// Whether the items found by this name come after all the items found by their own name.
+    pub low_priority: bool,
+}
+
+/// The attributes that change how an item can be used from other crates or other languages, and
+/// so are shown in its declaration. Any other attribute is an implementation detail.
+const DISPLAYED_ATTRIBUTES: &[Symbol] = &[
+    sym::export_name,
+    sym::link_section,
+    sym::must_use,
+    sym::no_mangle,
+    sym::repr,
+    sym::non_exhaustive,
+];
+
+/// Prints `attr` the way it's written in the source, without the surrounding `#[]`.
+fn display_attribute(attr: &ast::MetaItem) -> Option<String> {
+    let path = pprust::path_to_string(&attr.path);
+
+    if attr.is_word() {
+        Some(path)
+    } else if let Some(v) = attr.value_str() {
+        Some(format!("{} = {:?}", path, v))
+    } else if let Some(values) = attr.meta_item_list() {
+        let display: Vec<_> = values.iter().filter_map(|attr| {
+            attr.meta_item().and_then(|mi| display_attribute(mi))
+        }).collect();
+
+        if display.len() > 0 {
+            Some(format!("{}({})", path, display.join(", ")))
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+/// Reads the `alias` entry of a `doc` attribute, written as `alias = "name"` or as
+/// `alias("name", priority = "low")`.
+fn parse_alias(alias: &ast::NestedMetaItem) -> Option<DocAlias> {
+    if let Some(name) = alias.value_str() {
+        let name = Symbol::intern(&name.as_str().replace("\"", ""));
+        return Some(DocAlias { name, low_priority: false });
+    }
+    let mut name = None;
+    let mut low_priority = false;
+    for nested in alias.meta_item_list()? {
+        if let Some(&ast::Lit { kind: ast::LitKind::Str(value, _), .. }) = nested.literal() {
+            name = Some(Symbol::intern(&value.as_str().replace("\"", "")));
+        } else if nested.check_name(sym::priority) {
+            low_priority = nested.value_str().map_or(false, |p| &*p.as_str() == "low");
+        }
+    }
+    Some(DocAlias { name: name?, low_priority })
 }
 
 impl Attributes {
@@ -588,18 +678,50 @@ impl Attributes {
             }
         }
 
+        // collect the plain #[cfg(...)] attributes, leaving out the ones that only describe how
+        // the crate is being built rather than where the item is available
+        let mut auto_cfg = Cfg::True;
+        for attr in attrs.iter().filter(|a| a.check_name(sym::cfg)) {
+            let cfg_mi = attr.meta_item_list()
+                .filter(|list| list.len() == 1)
+                .and_then(|list| list[0].meta_item().cloned());
+            if let Some(Ok(new_cfg)) = cfg_mi.as_ref().map(Cfg::parse) {
+                if !new_cfg.mentions_any(&[sym::test, sym::doc, sym::doctest]) {
+                    auto_cfg &= new_cfg;
+                }
+            }
+        }
+
         let inner_docs = attrs.iter()
                               .filter(|a| a.check_name(sym::doc))
                               .next()
                               .map_or(true, |a| a.style == AttrStyle::Inner);
 
+        let displayed = attrs.iter()
+            .filter(|attr| DISPLAYED_ATTRIBUTES.contains(&attr.name_or_empty()))
+            .filter_map(|attr| display_attribute(&attr.meta()?))
+            .collect();
+
+        let mut aliases: Vec<DocAlias> = Vec::new();
+        for alias in attrs.lists(sym::doc)
+                          .filter(|a| a.check_name(sym::alias))
+                          .filter_map(|a| parse_alias(&a)) {
+            if alias.name != kw::Invalid && !aliases.iter().any(|a| a.name == alias.name) {
+                aliases.push(alias);
+            }
+        }
+
         Attributes {
             doc_strings,
             other_attrs,
             cfg: if cfg == Cfg::True { None } else { Some(Arc::new(cfg)) },
+            auto_cfg: if auto_cfg == Cfg::True { None } else { Some(Arc::new(auto_cfg)) },
             span: sp,
             links: vec![],
             inner_docs,
+            scope: None,
+            displayed,
+            aliases,
         }
     }
 
@@ -702,7 +824,7 @@ pub enum GenericBound {
 }
 
 impl GenericBound {
-    pub fn maybe_sized(cx: &DocContext<'_>) -> GenericBound {
+    pub fn maybe_sized(cx: &mut DocContext<'_>) -> GenericBound {
         let did = cx.tcx.require_lang_item(lang_items::SizedTraitLangItem, None);
         let empty = cx.tcx.intern_substs(&[]);
         let path = external_path(cx, cx.tcx.item_name(did),
@@ -746,17 +868,15 @@ impl GenericBound {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
-pub struct Lifetime(pub String);
+pub struct Lifetime(pub Symbol);
 
 impl Lifetime {
-    pub fn get_ref<'a>(&'a self) -> &'a str {
-        let Lifetime(ref s) = *self;
-        let s: &'a str = s;
-        s
+    pub fn get_ref(&self) -> SymbolStr {
+        self.0.as_str()
     }
 
     pub fn statik() -> Lifetime {
-        Lifetime("'static".to_string())
+        Lifetime(kw::StaticLifetime)
     }
 }
 
@@ -814,7 +934,7 @@ impl GenericParamDefKind {
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct GenericParamDef {
-    pub name: String,
+    pub name: Symbol,
     pub kind: GenericParamDefKind,
 }
 
@@ -858,6 +978,9 @@ pub struct Method {
     pub defaultness: Option<hir::Defaultness>,
     pub all_types: Vec<Type>,
     pub ret_types: Vec<Type>,
+    /// For provided trait methods, the source code of the default implementation, if available.
+    /// It is only looked up for local traits and the external traits which are inlined.
+    pub default_impl: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -924,7 +1047,7 @@ pub struct Arguments {
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct Argument {
     pub type_: Type,
-    pub name: String,
+    pub name: Symbol,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -936,7 +1059,7 @@ pub enum SelfTy {
 
 impl Argument {
     pub fn to_self(&self) -> Option<SelfTy> {
-        if self.name != "self" {
+        if self.name != kw::SelfLower {
             return None;
         }
         if self.type_.is_self_type() {
@@ -973,7 +1096,7 @@ pub struct Trait {
     pub items: Vec<Item>,
     pub generics: Generics,
     pub bounds: Vec<GenericBound>,
-    pub is_spotlight: bool,
+    pub is_notable: bool,
     pub is_auto: bool,
 }
 
@@ -1005,7 +1128,7 @@ pub enum Type {
     },
     /// For parameterized types, so the consumer of the JSON don't go
     /// looking for types which don't exist anywhere.
-    Generic(String),
+    Generic(Symbol),
     /// Primitives are the fixed-size numeric types (plus int/usize/float), char,
     /// arrays, slices, and tuples.
     Primitive(PrimitiveType),
@@ -1024,7 +1147,7 @@ pub enum Type {
 
     // `<Type as Trait>::Name`
     QPath {
-        name: String,
+        name: Symbol,
         self_type: Box<Type>,
         trait_: Box<Type>
     },
@@ -1110,7 +1233,7 @@ impl Type {
 
     pub fn is_self_type(&self) -> bool {
         match *self {
-            Generic(ref name) => name == "Self",
+            Generic(name) => name == kw::SelfUpper,
             _ => false
         }
     }
@@ -1155,9 +1278,9 @@ impl Type {
         }
     }
 
-    pub fn projection(&self) -> Option<(&Type, DefId, &str)> {
-        let (self_, trait_, name) = match self {
-            QPath { ref self_type, ref trait_, ref name } => {
+    pub fn projection(&self) -> Option<(&Type, DefId, Symbol)> {
+        let (self_, trait_, name) = match *self {
+            QPath { ref self_type, ref trait_, name } => {
                 (self_type, trait_, name)
             }
             _ => return None,
@@ -1312,6 +1435,7 @@ pub struct Struct {
     pub generics: Generics,
     pub fields: Vec<Item>,
     pub fields_stripped: bool,
+    pub layout: Option<TypeLayout>,
 }
 
 #[derive(Clone, Debug)]
@@ -1320,6 +1444,7 @@ pub struct Union {
     pub generics: Generics,
     pub fields: Vec<Item>,
     pub fields_stripped: bool,
+    pub layout: Option<TypeLayout>,
 }
 
 /// This is a more limited form of the standard Struct, different in that
@@ -1337,6 +1462,25 @@ pub struct Enum {
     pub variants: IndexVec<VariantIdx, Item>,
     pub generics: Generics,
     pub variants_stripped: bool,
+    pub layout: Option<TypeLayout>,
+}
+
+/// The layout of a struct, enum or union, computed with `--show-type-layout`.
+#[derive(Clone, Debug)]
+pub enum TypeLayout {
+    Known {
+        size: u64,
+        align: u64,
+        /// The offset of each field of a struct or union, by name, in declaration order.
+        fields: Vec<(String, u64)>,
+        /// The size of each variant of an enum, by name, in declaration order.
+        variants: Vec<(String, u64)>,
+        /// How many invalid values of the type an enum containing it can use for its
+        /// discriminant, without growing.
+        niches: u128,
+    },
+    /// The layout depends on generic parameters, or could not be computed at all.
+    Unknown,
 }
 
 #[derive(Clone, Debug)]
@@ -1384,8 +1528,8 @@ pub struct Path {
 }
 
 impl Path {
-    pub fn last_name(&self) -> &str {
-        self.segments.last().expect("segments were empty").name.as_str()
+    pub fn last_name(&self) -> Symbol {
+        self.segments.last().expect("segments were empty").name
     }
 }
 
@@ -1410,7 +1554,7 @@ pub enum GenericArgs {
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct PathSegment {
-    pub name: String,
+    pub name: Symbol,
     pub args: GenericArgs,
 }
 
@@ -1442,12 +1586,16 @@ pub struct Static {
     /// desire to represent expressions (that'd basically be all of the AST,
     /// which is huge!). So, have a string.
     pub expr: String,
+    /// The value `expr` evaluates to, when it's a primitive worth showing, see
+    /// `utils::print_evaluated_const`.
+    pub value: Option<String>,
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct Constant {
     pub type_: Type,
     pub expr: String,
+    pub value: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy, Hash)]
@@ -1473,12 +1621,45 @@ pub struct Impl {
     pub polarity: Option<ImplPolarity>,
     pub synthetic: bool,
     pub blanket_impl: Option<Type>,
+    /// The attributes of the impl block which its associated items are subject to as well.
+    pub inherited: ImplAttrs,
+}
+
+/// The attributes of an impl block which also apply to each of its associated items.
+#[derive(Clone, Debug, Default)]
+pub struct ImplAttrs {
+    /// Whether the impl is `#[doc(hidden)]`. Its associated items are stripped along with it.
+    pub hidden: bool,
+    /// The `#[doc(cfg(...))]` of the impl, which `propagate-doc-cfg` adds to the one of each
+    /// associated item.
+    pub cfg: Option<Arc<Cfg>>,
+    /// The deprecation of the impl, which associated items without one of their own inherit.
+    pub deprecation: Option<Deprecation>,
+}
+
+impl ImplAttrs {
+    pub fn new(attrs: &Attributes, deprecation: Option<&Deprecation>) -> ImplAttrs {
+        ImplAttrs {
+            hidden: attrs.lists(sym::doc).has_word(sym::hidden),
+            cfg: attrs.cfg.clone(),
+            deprecation: deprecation.cloned(),
+        }
+    }
+
+    /// Makes the associated items of the impl inherit its deprecation.
+    pub fn apply(&self, items: &mut [Item]) {
+        for item in items {
+            if item.deprecation.is_none() {
+                item.deprecation = self.deprecation.clone();
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum Import {
     // use source as str;
-    Simple(String, ImportSource),
+    Simple(Symbol, ImportSource),
     // use source::*;
     Glob(ImportSource)
 }
@@ -1492,13 +1673,13 @@ pub struct ImportSource {
 #[derive(Clone, Debug)]
 pub struct Macro {
     pub source: String,
-    pub imported_from: Option<String>,
+    pub imported_from: Option<Symbol>,
 }
 
 #[derive(Clone, Debug)]
 pub struct ProcMacro {
     pub kind: MacroKind,
-    pub helpers: Vec<String>,
+    pub helpers: Vec<Symbol>,
 }
 
 #[derive(Clone, Debug)]
@@ -1509,6 +1690,9 @@ pub struct Stability {
     pub deprecation: Option<Deprecation>,
     pub unstable_reason: Option<String>,
     pub issue: Option<NonZeroU32>,
+    /// The name of the trait this stability was taken from, if the item did not have a
+    /// stability of its own, or if it was more stable than its trait.
+    pub inherited_from: Option<String>,
 }
 
 #[derive(Clone, Debug)]
@@ -1521,7 +1705,7 @@ pub struct Deprecation {
 /// `A: Send + Sync` in `Foo<A: Send + Sync>`).
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub struct TypeBinding {
-    pub name: String,
+    pub name: Symbol,
     pub kind: TypeBindingKind,
 }
 
@@ -1,19 +1,24 @@
 use crate::core::DocContext;
+use crate::clean;
 use crate::clean::{
-    Clean, Crate, Deprecation, ExternalCrate, FnDecl, FunctionRetTy, Generic, GenericArg,
-    GenericArgs, Generics, GenericBound, GetDefId, ImportSource, Item, ItemEnum, MacroKind, Path,
-    PathSegment, Primitive, PrimitiveType, ResolvedPath, Span, Stability, Type, TypeBinding,
-    TypeKind, Visibility, WherePredicate, inline,
+    AttributesExt, Clean, Crate, Deprecation, ExternalCrate, FnDecl, FunctionRetTy, Generic,
+    GenericArg, GenericArgs, Generics, GenericBound, GetDefId, Import, ImportSource, Item,
+    ItemEnum, MacroKind, Path, PathSegment, Primitive, PrimitiveType, ResolvedPath, Span,
+    Stability, Type, TypeBinding, TypeKind, TypeLayout, Visibility, WherePredicate, inline,
 };
 use crate::clean::blanket_impl::BlanketImplFinder;
+use crate::clean::cfg::{Cfg, CfgNames};
 use crate::clean::auto_trait::AutoTraitFinder;
 
 use rustc::hir;
 use rustc::hir::def::{DefKind, Res};
-use rustc::hir::def_id::{DefId, LOCAL_CRATE};
+use rustc::hir::def_id::{CRATE_DEF_INDEX, DefId, LOCAL_CRATE};
+use rustc::mir::interpret::{ConstValue, ErrorHandled, GlobalId, read_target_uint, sign_extend};
 use rustc::ty::{self, DefIdTree, Ty};
-use rustc::ty::subst::{SubstsRef, GenericArgKind};
-use rustc::util::nodemap::FxHashSet;
+use rustc::ty::layout::Variants;
+use rustc::ty::subst::{InternalSubsts, SubstsRef, GenericArgKind};
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+use syntax::ast::{self, LitKind};
 use syntax_pos;
 use syntax_pos::symbol::{Symbol, kw, sym};
 
@@ -25,7 +30,7 @@ pub fn krate(mut cx: &mut DocContext<'_>) -> Crate {
     let krate = cx.tcx.hir().krate();
     let module = crate::visit_ast::RustdocVisitor::new(&mut cx).visit(krate);
 
-    let mut r = cx.renderinfo.get_mut();
+    let r = &mut cx.renderinfo;
     r.deref_trait_did = cx.tcx.lang_items().deref_trait();
     r.deref_mut_trait_did = cx.tcx.lang_items().deref_mut_trait();
     r.owned_box_did = cx.tcx.lang_items().owned_box();
@@ -54,11 +59,78 @@ pub fn krate(mut cx: &mut DocContext<'_>) -> Crate {
                 {
                     masked_crates.insert(it.def_id.krate);
                 }
+                // Facade crates can also mask a crate they re-export with `pub use`.
+                if let ItemEnum::ImportItem(Import::Simple(_, ref source)) = it.inner {
+                    if let Some(did) = source.did {
+                        if !did.is_local() && did.index == CRATE_DEF_INDEX &&
+                            it.attrs.has_doc_flag(sym::masked)
+                        {
+                            masked_crates.insert(did.krate);
+                        }
+                    }
+                }
             }
         }
         _ => unreachable!(),
     }
 
+    // Any dependency can also be masked by its name from the crate root, in which case the impls
+    // it masks are reported.
+    let mut reported_masked_crates = FxHashSet::default();
+    for attr in module.attrs.lists(sym::doc).filter(|a| a.check_name(sym::masked)) {
+        for nested in attr.meta_item_list().unwrap_or(&[]) {
+            let name = match nested.ident() {
+                Some(ident) if nested.is_word() => ident.name,
+                _ => {
+                    cx.sess().span_err(nested.span(), "expected a crate name");
+                    continue;
+                }
+            };
+            match cx.tcx.crates().iter().find(|&&cnum| cx.tcx.crate_name(cnum) == name) {
+                Some(&cnum) => {
+                    masked_crates.insert(cnum);
+                    reported_masked_crates.insert(cnum);
+                }
+                None => {
+                    cx.sess().span_err(
+                        nested.span(),
+                        &format!("unknown crate `{}` in `#![doc(masked(...))]`", name),
+                    );
+                }
+            }
+        }
+    }
+
+    // Configurations can be given a display name for their portability notes with
+    // `#![doc(cfg_name(feature = "tls", "TLS support"))]`.
+    let mut cfg_names = CfgNames::default();
+    for attr in module.attrs.lists(sym::doc).filter(|a| a.check_name(sym::cfg_name)) {
+        let (cfg, name) = match attr.meta_item_list() {
+            Some([cfg, name]) => (cfg, name),
+            _ => {
+                cx.sess().span_err(
+                    attr.span(),
+                    "expected a configuration and its display name, as in \
+                     `cfg_name(feature = \"tls\", \"TLS support\")`",
+                );
+                continue;
+            }
+        };
+        let cfg = match Cfg::parse_nested(cfg) {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                cx.sess().span_err(e.span, e.msg);
+                continue;
+            }
+        };
+        match name.literal().map(|lit| &lit.kind) {
+            Some(&LitKind::Str(name, _)) => {
+                cfg_names.insert(cfg, name.to_string());
+            }
+            _ => cx.sess().span_err(name.span(), "expected a string literal"),
+        }
+    }
+
     let ExternalCrate { name, src, primitives, keywords, .. } = LOCAL_CRATE.clean(cx);
     {
         let m = match module.inner {
@@ -68,11 +140,12 @@ pub fn krate(mut cx: &mut DocContext<'_>) -> Crate {
         m.items.extend(primitives.iter().map(|&(def_id, prim, ref attrs)| {
             Item {
                 source: Span::empty(),
-                name: Some(prim.to_url_str().to_string()),
+                name: Some(Symbol::intern(prim.to_url_str())),
                 attrs: attrs.clone(),
                 visibility: Visibility::Public,
                 stability: get_stability(cx, def_id),
                 deprecation: get_deprecation(cx, def_id),
+                canonical_path: None,
                 def_id,
                 inner: ItemEnum::PrimitiveItem(prim),
             }
@@ -80,11 +153,12 @@ pub fn krate(mut cx: &mut DocContext<'_>) -> Crate {
         m.items.extend(keywords.into_iter().map(|(def_id, kw, attrs)| {
             Item {
                 source: Span::empty(),
-                name: Some(kw.clone()),
+                name: Some(kw),
                 attrs,
                 visibility: Visibility::Public,
                 stability: get_stability(cx, def_id),
                 deprecation: get_deprecation(cx, def_id),
+                canonical_path: None,
                 def_id,
                 inner: ItemEnum::KeywordItem(kw),
             }
@@ -98,23 +172,149 @@ pub fn krate(mut cx: &mut DocContext<'_>) -> Crate {
         module: Some(module),
         externs,
         primitives,
-        external_traits: cx.external_traits.clone(),
+        external_traits: mem::take(&mut cx.external_traits),
         masked_crates,
+        reported_masked_crates,
+        cfg_names,
         collapsed: false,
     }
 }
 
+/// Gives the items of `krate` getting a page of their own their `canonical_path`.
+///
+/// An item inlined through several re-exports is found at several locations of the crate. Its
+/// canonical path is the one it is defined at, `exact_paths`, when that is one of the locations,
+/// e.g. when the item is not re-exported from a private module, and otherwise the shortest one,
+/// favoring the first one found on a tie. The other locations only get a short page pointing there.
+pub fn record_canonical_paths(krate: &mut Crate, exact_paths: &FxHashMap<DefId, Vec<String>>) {
+    fn has_page(item: &Item) -> bool {
+        match item.inner {
+            ItemEnum::StructItem(..) | ItemEnum::EnumItem(..) |
+            ItemEnum::TypedefItem(..) | ItemEnum::TraitItem(..) |
+            ItemEnum::FunctionItem(..) | ItemEnum::ForeignFunctionItem(..) |
+            ItemEnum::ForeignStaticItem(..) | ItemEnum::ConstantItem(..) |
+            ItemEnum::StaticItem(..) | ItemEnum::UnionItem(..) |
+            ItemEnum::ForeignTypeItem | ItemEnum::MacroItem(..) |
+            ItemEnum::ProcMacroItem(..) => true,
+            _ => false,
+        }
+    }
+
+    fn collect(
+        item: &Item,
+        stack: &mut Vec<String>,
+        locations: &mut FxHashMap<DefId, Vec<Vec<String>>>,
+    ) {
+        let name = match item.name {
+            Some(name) if name != kw::Invalid => name.to_string(),
+            _ => return,
+        };
+        if let ItemEnum::ModuleItem(ref m) = item.inner {
+            stack.push(name);
+            for item in &m.items {
+                collect(item, stack, locations);
+            }
+            stack.pop();
+        } else if has_page(item) {
+            let mut path = stack.clone();
+            path.push(name);
+            locations.entry(item.def_id).or_default().push(path);
+        }
+    }
+
+    fn record(item: &mut Item, canonical_paths: &FxHashMap<DefId, Vec<String>>) {
+        if let ItemEnum::ModuleItem(ref mut m) = item.inner {
+            for item in &mut m.items {
+                record(item, canonical_paths);
+            }
+        } else if has_page(item) {
+            item.canonical_path = canonical_paths.get(&item.def_id).cloned();
+        }
+    }
+
+    // The crate root has no name of its own.
+    let module = match krate.module {
+        Some(Item { inner: ItemEnum::ModuleItem(ref mut m), .. }) => m,
+        _ => return,
+    };
+    let mut locations = FxHashMap::default();
+    let mut stack = vec![krate.name.to_string()];
+    for item in &module.items {
+        collect(item, &mut stack, &mut locations);
+    }
+    let canonical_paths = locations.into_iter()
+        .map(|(did, paths)| {
+            let canonical = match exact_paths.get(&did) {
+                Some(exact) if paths.contains(exact) => exact.clone(),
+                _ => paths.into_iter().min_by_key(|path| path.len()).unwrap(),
+            };
+            (did, canonical)
+        })
+        .collect();
+    for item in &mut module.items {
+        record(item, &canonical_paths);
+    }
+}
+
 // extract the stability index for a node from tcx, if possible
-pub fn get_stability(cx: &DocContext<'_>, def_id: DefId) -> Option<Stability> {
+pub fn get_stability(cx: &mut DocContext<'_>, def_id: DefId) -> Option<Stability> {
     cx.tcx.lookup_stability(def_id).clean(cx)
 }
 
-pub fn get_deprecation(cx: &DocContext<'_>, def_id: DefId) -> Option<Deprecation> {
+pub fn get_deprecation(cx: &mut DocContext<'_>, def_id: DefId) -> Option<Deprecation> {
     cx.tcx.lookup_deprecation(def_id).clean(cx)
 }
 
+/// Returns the version of the external crate `name`, telling it from its `html_root_url` if that
+/// ends with a version, like the `https://docs.rs/serde/1.0.104` of `serde`, or from the directory
+/// of its sources, like the `serde-1.0.104` Cargo extracts it to.
+pub fn crate_version(
+    name: &str,
+    src: &syntax_pos::FileName,
+    attrs: &clean::Attributes,
+) -> Option<String> {
+    let from_root_url = attrs.lists(sym::doc)
+        .filter(|a| a.check_name(sym::html_root_url))
+        .filter_map(|a| a.value_str())
+        .filter_map(|url| {
+            let url = url.as_str();
+            url.trim_end_matches('/').rsplit('/').next()
+                .filter(|version| is_version(version))
+                .map(|version| version.to_string())
+        })
+        .next();
+    if from_root_url.is_some() {
+        return from_root_url;
+    }
+
+    let path = match *src {
+        syntax_pos::FileName::Real(ref path) => path,
+        _ => return None,
+    };
+    // The name of a package may have `-` where the name of its crate has `_`.
+    path.ancestors()
+        .filter_map(|dir| dir.file_name()?.to_str())
+        .filter_map(|dir| {
+            dir.match_indices('-')
+                .map(|(i, _)| (&dir[..i], &dir[i + 1..]))
+                .find(|&(package, version)| {
+                    package.replace('-', "_") == name && is_version(version)
+                })
+                .map(|(_, version)| version.to_string())
+        })
+        .next()
+}
+
+/// Returns whether `s` is a semantic version, like `1.0.104` or `0.3.0-alpha.1`.
+fn is_version(s: &str) -> bool {
+    let release = s.split(|c| c == '-' || c == '+').next().unwrap_or("");
+    let parts = release.split('.').collect::<Vec<_>>();
+    parts.len() == 3 &&
+        parts.iter().all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
 pub fn external_generic_args(
-    cx: &DocContext<'_>,
+    cx: &mut DocContext<'_>,
     trait_did: Option<DefId>,
     has_self: bool,
     bindings: Vec<TypeBinding>,
@@ -161,13 +361,13 @@ pub fn external_generic_args(
 
 // trait_did should be set to a trait's DefId if called on a TraitRef, in order to sugar
 // from Fn<(A, B,), C> to Fn(A, B) -> C
-pub fn external_path(cx: &DocContext<'_>, name: Symbol, trait_did: Option<DefId>, has_self: bool,
-                     bindings: Vec<TypeBinding>, substs: SubstsRef<'_>) -> Path {
+pub fn external_path(cx: &mut DocContext<'_>, name: Symbol, trait_did: Option<DefId>,
+                     has_self: bool, bindings: Vec<TypeBinding>, substs: SubstsRef<'_>) -> Path {
     Path {
         global: false,
         res: Res::Err,
         segments: vec![PathSegment {
-            name: name.to_string(),
+            name,
             args: external_generic_args(cx, trait_did, has_self, bindings, substs)
         }],
     }
@@ -184,7 +384,6 @@ pub fn get_real_types(
     cx: &DocContext<'_>,
     recurse: i32,
 ) -> FxHashSet<Type> {
-    let arg_s = arg.print().to_string();
     let mut res = FxHashSet::default();
     if recurse >= 10 { // FIXME: remove this whole recurse thing when the recursion bug is fixed
         return res;
@@ -219,7 +418,7 @@ pub fn get_real_types(
             }
         }
         if let Some(bound) = generics.params.iter().find(|g| {
-            g.is_type() && g.name == arg_s
+            g.is_type() && Generic(g.name) == *arg
         }) {
             for bound in bound.get_bounds().unwrap_or_else(|| &[]) {
                 if let Some(ty) = bound.get_trait_type() {
@@ -327,6 +526,76 @@ pub fn strip_path(path: &Path) -> Path {
     }
 }
 
+/// Collects the traits that `bounds` refer to, along with the types and traits of their generic
+/// arguments.
+pub fn bound_referenced_dids(bounds: &[clean::GenericBound], dids: &mut FxHashSet<DefId>) {
+    for bound in bounds {
+        if let clean::GenericBound::TraitBound(ref poly, _) = *bound {
+            referenced_dids(&poly.trait_, dids);
+        }
+    }
+}
+
+/// Collects the types and traits that `ty` refers to, including through its generic arguments.
+pub fn referenced_dids(ty: &clean::Type, dids: &mut FxHashSet<DefId>) {
+    match *ty {
+        clean::ResolvedPath { ref path, did, is_generic, .. } => {
+            if !is_generic {
+                dids.insert(did);
+            }
+            for segment in &path.segments {
+                match segment.args {
+                    clean::GenericArgs::AngleBracketed { ref args, ref bindings } => {
+                        for arg in args {
+                            if let clean::GenericArg::Type(ref ty) = *arg {
+                                referenced_dids(ty, dids);
+                            }
+                        }
+                        for binding in bindings {
+                            match binding.kind {
+                                clean::TypeBindingKind::Equality { ref ty } => {
+                                    referenced_dids(ty, dids)
+                                }
+                                clean::TypeBindingKind::Constraint { bounds: ref b } => {
+                                    bound_referenced_dids(b, dids)
+                                }
+                            }
+                        }
+                    }
+                    clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+                        for ty in inputs.iter().chain(output) {
+                            referenced_dids(ty, dids);
+                        }
+                    }
+                }
+            }
+        }
+        clean::Tuple(ref tys) => {
+            for ty in tys {
+                referenced_dids(ty, dids);
+            }
+        }
+        clean::Slice(ref ty) |
+        clean::Array(ref ty, _) |
+        clean::RawPointer(_, ref ty) |
+        clean::BorrowedRef { type_: ref ty, .. } => referenced_dids(ty, dids),
+        clean::QPath { ref self_type, ref trait_, .. } => {
+            referenced_dids(self_type, dids);
+            referenced_dids(trait_, dids);
+        }
+        clean::ImplTrait(ref b) => bound_referenced_dids(b, dids),
+        clean::BareFunction(ref f) => {
+            for arg in &f.decl.inputs.values {
+                referenced_dids(&arg.type_, dids);
+            }
+            if let clean::FunctionRetTy::Return(ref ty) = f.decl.output {
+                referenced_dids(ty, dids);
+            }
+        }
+        clean::Generic(..) | clean::Primitive(..) | clean::Never | clean::Infer => {}
+    }
+}
+
 pub fn qpath_to_string(p: &hir::QPath) -> String {
     let segments = match *p {
         hir::QPath::Resolved(_, ref path) => &path.segments,
@@ -345,7 +614,7 @@ pub fn qpath_to_string(p: &hir::QPath) -> String {
     s
 }
 
-pub fn build_deref_target_impls(cx: &DocContext<'_>,
+pub fn build_deref_target_impls(cx: &mut DocContext<'_>,
                                 items: &[Item],
                                 ret: &mut Vec<Item>) {
     use self::PrimitiveType::*;
@@ -360,6 +629,7 @@ pub fn build_deref_target_impls(cx: &DocContext<'_>,
             ResolvedPath { did, .. } if did.is_local() => continue,
             ResolvedPath { did, .. } => {
                 ret.extend(inline::build_impls(cx, did, None));
+                inline::build_external_deref_impl(cx, did, ret);
                 continue
             }
             _ => match target.primitive_type() {
@@ -408,7 +678,7 @@ pub trait ToSource {
 
 impl ToSource for syntax_pos::Span {
     fn to_src(&self, cx: &DocContext<'_>) -> String {
-        debug!("converting span {:?} to snippet", self.clean(cx));
+        debug!("converting span {:?} to snippet", self);
         let sn = match cx.sess().source_map().span_to_snippet(*self) {
             Ok(x) => x,
             Err(_) => String::new()
@@ -418,6 +688,32 @@ impl ToSource for syntax_pos::Span {
     }
 }
 
+/// Returns the source shown for the macro `name` whose arms start with `matchers`, with the
+/// transcribers elided: a `macro_rules!` definition, or a `macro` item if the macro is not
+/// `legacy`.
+pub fn display_macro_source(
+    cx: &DocContext<'_>,
+    name: &str,
+    matchers: &[syntax_pos::Span],
+    legacy: bool,
+) -> String {
+    if legacy {
+        return format!("macro_rules! {} {{\n{}}}",
+                       name,
+                       matchers.iter().map(|span| {
+                           format!("    {} => {{ ... }};\n", span.to_src(cx))
+                       }).collect::<String>());
+    }
+    match *matchers {
+        [matcher] => format!("pub macro {}{} {{\n    ...\n}}", name, matcher.to_src(cx)),
+        _ => format!("pub macro {} {{\n{}}}",
+                     name,
+                     matchers.iter().map(|span| {
+                         format!("    {} => {{ ... }},\n", span.to_src(cx))
+                     }).collect::<String>()),
+    }
+}
+
 pub fn name_from_pat(p: &hir::Pat) -> String {
     use rustc::hir::*;
     debug!("trying to get a name from pattern: {:?}", p);
@@ -485,8 +781,101 @@ pub fn print_const_expr(cx: &DocContext<'_>, body: hir::BodyId) -> String {
     cx.tcx.hir().hir_to_pretty_string(body.hir_id)
 }
 
+/// Evaluates the constant or static `def_id` and prints its value if it's a number, a `bool` or a
+/// `char`.
+///
+/// Integers are printed in the radix of the first integer literal of `expr`, the expression the
+/// item is defined with, so that a mask written in hexadecimal or binary keeps reading as one.
+pub fn print_evaluated_const(cx: &mut DocContext<'_>, def_id: DefId, expr: &str) -> Option<String> {
+    let tcx = cx.tcx;
+    let ty = tcx.type_of(def_id);
+    match ty.kind {
+        ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) | ty::Float(_) => {}
+        _ => return None,
+    }
+
+    let param_env = tcx.param_env(def_id);
+    let cid = GlobalId {
+        instance: ty::Instance::new(def_id, InternalSubsts::identity_for_item(tcx, def_id)),
+        promoted: None,
+    };
+    let value = eval_const(cx, param_env, cid, "evaluate this constant",
+                           "its value is left out of the documentation")?;
+    let size = tcx.layout_of(param_env.and(ty)).ok()?.size;
+    let bits = match value.val {
+        // Statics are always evaluated to the memory they live in.
+        ty::ConstKind::Value(ConstValue::ByRef { alloc, offset }) => {
+            let start = offset.bytes() as usize;
+            let bytes = alloc.inspect_with_undef_and_ptr_outside_interpreter(
+                start..start + size.bytes() as usize,
+            );
+            read_target_uint(tcx.data_layout.endian, bytes).ok()?
+        }
+        _ => value.try_eval_bits(tcx, param_env, ty)?,
+    };
+
+    let (negative, magnitude) = match ty.kind {
+        ty::Bool => return Some((bits != 0).to_string()),
+        ty::Char => return std::char::from_u32(bits as u32).map(|c| format!("{:?}", c)),
+        ty::Float(ast::FloatTy::F32) => {
+            return Some(format!("{:?}", f32::from_bits(bits as u32)));
+        }
+        ty::Float(ast::FloatTy::F64) => {
+            return Some(format!("{:?}", f64::from_bits(bits as u64)));
+        }
+        ty::Int(_) => {
+            let n = sign_extend(bits, size) as i128;
+            // `wrapping_neg` leaves `i128::MIN` alone, which is still the right magnitude once
+            // reinterpreted as an `u128`.
+            (n < 0, if n < 0 { n.wrapping_neg() as u128 } else { n as u128 })
+        }
+        _ => (false, bits),
+    };
+
+    let sign = if negative { "-" } else { "" };
+    Some(match literal_radix(expr) {
+        16 => format!("{}0x{:x}", sign, magnitude),
+        8 => format!("{}0o{:o}", sign, magnitude),
+        2 => format!("{}0b{:b}", sign, magnitude),
+        _ => format!("{}{}", sign, magnitude),
+    })
+}
+
+/// The radix of the first integer literal of `expr`.
+fn literal_radix(expr: &str) -> u32 {
+    let literal = expr.split(|c: char| !c.is_alphanumeric() && c != '_')
+                      .find(|token| token.starts_with(|c: char| c.is_ascii_digit()));
+    match literal {
+        Some(literal) if literal.starts_with("0x") => 16,
+        Some(literal) if literal.starts_with("0o") => 8,
+        Some(literal) if literal.starts_with("0b") => 2,
+        _ => 10,
+    }
+}
+
+/// Returns the source code covered by `span`, with the indentation of its first line removed from
+/// all the following lines.
+pub fn span_to_dedented_snippet(cx: &DocContext<'_>, span: syntax_pos::Span) -> Option<String> {
+    let source_map = cx.sess().source_map();
+    let snippet = source_map.span_to_snippet(span).ok()?;
+    let indent = source_map.lookup_char_pos(span.lo()).col.to_usize();
+
+    let lines = snippet.lines().enumerate().map(|(i, line)| {
+        if i == 0 {
+            return line;
+        }
+        let start = line.char_indices()
+            .take(indent)
+            .take_while(|&(_, c)| c.is_whitespace())
+            .last()
+            .map_or(0, |(pos, c)| pos + c.len_utf8());
+        &line[start..]
+    });
+    Some(lines.collect::<Vec<_>>().join("\n"))
+}
+
 /// Given a type Path, resolve it to a Type using the TyCtxt
-pub fn resolve_type(cx: &DocContext<'_>,
+pub fn resolve_type(cx: &mut DocContext<'_>,
                     path: Path,
                     id: hir::HirId) -> Type {
     if id == hir::DUMMY_HIR_ID {
@@ -505,30 +894,31 @@ pub fn resolve_type(cx: &DocContext<'_>,
             hir::Float(float_ty) => return Primitive(float_ty.into()),
         },
         Res::SelfTy(..) if path.segments.len() == 1 => {
-            return Generic(kw::SelfUpper.to_string());
+            return Generic(kw::SelfUpper);
         }
         Res::Def(DefKind::TyParam, _) if path.segments.len() == 1 => {
-            return Generic(format!("{:#}", path.print()));
+            return Generic(path.segments[0].name);
         }
         Res::SelfTy(..)
         | Res::Def(DefKind::TyParam, _)
         | Res::Def(DefKind::AssocTy, _) => true,
         _ => false,
     };
-    let did = register_res(&*cx, path.res);
+    let did = register_res(cx, path.res);
     ResolvedPath { path, param_names: None, did, is_generic }
 }
 
 pub fn get_auto_trait_and_blanket_impls(
-    cx: &DocContext<'tcx>,
+    cx: &mut DocContext<'tcx>,
     ty: Ty<'tcx>,
     param_env_def_id: DefId,
 ) -> impl Iterator<Item = Item> {
-    AutoTraitFinder::new(cx).get_auto_trait_impls(ty, param_env_def_id).into_iter()
-        .chain(BlanketImplFinder::new(cx).get_blanket_impls(ty, param_env_def_id))
+    let auto_trait_impls = AutoTraitFinder::new(cx).get_auto_trait_impls(ty, param_env_def_id);
+    let blanket_impls = BlanketImplFinder::new(cx).get_blanket_impls(ty, param_env_def_id);
+    auto_trait_impls.into_iter().chain(blanket_impls)
 }
 
-pub fn register_res(cx: &DocContext<'_>, res: Res) -> DefId {
+pub fn register_res(cx: &mut DocContext<'_>, res: Res) -> DefId {
     debug!("register_res({:?})", res);
 
     let (did, kind) = match res {
@@ -562,7 +952,7 @@ pub fn register_res(cx: &DocContext<'_>, res: Res) -> DefId {
     did
 }
 
-pub fn resolve_use_source(cx: &DocContext<'_>, path: Path) -> ImportSource {
+pub fn resolve_use_source(cx: &mut DocContext<'_>, path: Path) -> ImportSource {
     ImportSource {
         did: if path.res.opt_def_id().is_none() {
             None
@@ -573,13 +963,122 @@ pub fn resolve_use_source(cx: &DocContext<'_>, path: Path) -> ImportSource {
     }
 }
 
-pub fn enter_impl_trait<F, R>(cx: &DocContext<'_>, f: F) -> R
+pub fn enter_impl_trait<'tcx, F, R>(cx: &mut DocContext<'tcx>, f: F) -> R
 where
-    F: FnOnce() -> R,
+    F: FnOnce(&mut DocContext<'tcx>) -> R,
 {
-    let old_bounds = mem::take(&mut *cx.impl_trait_bounds.borrow_mut());
-    let r = f();
-    assert!(cx.impl_trait_bounds.borrow().is_empty());
-    *cx.impl_trait_bounds.borrow_mut() = old_bounds;
+    let old_bounds = mem::take(&mut cx.impl_trait_bounds);
+    let r = f(cx);
+    assert!(cx.impl_trait_bounds.is_empty());
+    cx.impl_trait_bounds = old_bounds;
     r
 }
+
+/// Computes the layout of the struct, enum or union `did` if `--show-type-layout` was passed.
+pub fn type_layout(cx: &DocContext<'_>, did: DefId) -> Option<TypeLayout> {
+    if !cx.show_type_layout {
+        return None;
+    }
+
+    let tcx = cx.tcx;
+    let layout = match tcx.layout_of(tcx.param_env(did).and(tcx.type_of(did))) {
+        Ok(layout) => layout,
+        // Most likely the layout depends on a generic parameter.
+        Err(_) => return Some(TypeLayout::Unknown),
+    };
+
+    let adt = tcx.adt_def(did);
+    let (fields, variants) = if adt.is_enum() {
+        let variants = adt.variants.iter_enumerated().map(|(index, variant)| {
+            let size = match layout.variants {
+                Variants::Multiple { ref variants, .. } => variants[index].size.bytes(),
+                Variants::Single { index: only } if only == index => layout.size.bytes(),
+                // The other variants are uninhabited and take no space.
+                Variants::Single { .. } => 0,
+            };
+            (variant.ident.to_string(), size)
+        }).collect();
+        (vec![], variants)
+    } else {
+        let fields = adt.non_enum_variant().fields.iter().enumerate().map(|(i, field)| {
+            (field.ident.to_string(), layout.fields.offset(i).bytes())
+        }).collect();
+        (fields, vec![])
+    };
+
+    Some(TypeLayout::Known {
+        size: layout.size.bytes(),
+        align: layout.align.abi.bytes(),
+        fields,
+        variants,
+        niches: layout.largest_niche.as_ref().map_or(0, |niche| niche.available(&tcx)),
+    })
+}
+
+/// Returns whether the trait `did` is marked `#[doc(notable_trait)]`, or `#[doc(spotlight)]` like
+/// the traits of the standard library, and if so records it so that the functions returning an
+/// implementor of it show the impl in their "Important traits" popup.
+pub fn record_notable_trait(cx: &mut DocContext<'_>, did: DefId) -> bool {
+    let is_notable = cx.tcx.get_attrs(did).lists(sym::doc).any(|item| {
+        item.is_word() && (item.check_name(sym::notable_trait) || item.check_name(sym::spotlight))
+    });
+    if is_notable {
+        cx.renderinfo.notable_traits.insert(did);
+    }
+    is_notable
+}
+
+/// Reports a part of the crate rustdoc does not know how to document, like a type only the
+/// compiler uses internally, instead of aborting the documentation of the whole crate. The caller
+/// leaves it out, or shows it as `_`.
+pub fn report_uncleanable(cx: &DocContext<'_>, span: Option<syntax_pos::Span>, what: &str) {
+    warn_at(cx, span, &format!("rustdoc could not document {}", what),
+            "it is left out of the documentation, or shown as `_`");
+}
+
+/// Reports that whether `ty` implements `trait_did` could not be worked out, for a synthetic auto
+/// trait impl or a blanket impl, because of `why`. The impl is left out of the documentation.
+pub fn report_unsynthesizable(
+    cx: &DocContext<'_>,
+    span: Option<syntax_pos::Span>,
+    ty: Ty<'_>,
+    trait_did: DefId,
+    why: &str,
+) {
+    warn_at(cx, span,
+            &format!("rustdoc could not tell whether `{}` implements `{}`",
+                     ty, cx.tcx.def_path_str(trait_did)),
+            &format!("{}, so the impl is left out of the documentation", why));
+}
+
+fn warn_at(cx: &DocContext<'_>, span: Option<syntax_pos::Span>, msg: &str, note: &str) {
+    let mut diag = match span {
+        Some(span) if !span.is_dummy() => cx.sess().struct_span_warn(span, msg),
+        _ => cx.sess().struct_warn(msg),
+    };
+    diag.note(note).emit();
+}
+
+/// Evaluates the constant `cid`, for its value to be documented.
+///
+/// The constants depending on generic parameters cannot be evaluated, which is no error. The
+/// others can still fail to evaluate on code the compiler accepts because it never has to, like a
+/// constant overflowing which nothing uses: that is reported as a warning, with `note` telling
+/// what is documented instead.
+pub fn eval_const<'tcx>(
+    cx: &DocContext<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    cid: GlobalId<'tcx>,
+    what: &str,
+    note: &str,
+) -> Option<&'tcx ty::Const<'tcx>> {
+    match cx.tcx.const_eval(param_env.and(cid)) {
+        Ok(value) => Some(value),
+        Err(ErrorHandled::TooGeneric) => None,
+        Err(ErrorHandled::Reported) => {
+            let span = Some(cx.tcx.def_span(cid.instance.def_id()));
+            warn_at(cx, span, &format!("rustdoc could not {}", what), note);
+            None
+        }
+    }
+}
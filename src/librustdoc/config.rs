@@ -16,14 +16,49 @@ use rustc_driver;
 use rustc_target::spec::TargetTriple;
 use syntax::edition::{Edition, DEFAULT_EDITION};
 
+use crate::config_file::{self, ConfigFile};
 use crate::core::new_handler;
 use crate::externalfiles::ExternalHtml;
 use crate::html;
 use crate::html::{static_files};
+use crate::html::item_type::ItemType;
 use crate::html::markdown::{IdMap};
 use crate::opts;
-use crate::passes::{self, DefaultPassOption};
+use crate::passes::{self, DefaultPassOption, PrivatePaths};
 use crate::theme;
+use crate::workspace::{self, Member};
+
+/// The format rustdoc writes its output in, see `--output-format`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OutputFormat {
+    Html,
+    Json,
+}
+
+/// The artifacts rustdoc can produce, see `--emit`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EmitType {
+    /// The pages of the documentation, with their static files and the source pages.
+    Html,
+    /// The search index of the crate, added to the one of the output directory.
+    SearchIndex,
+    /// The documentation coverage, printed as with `--show-coverage`.
+    Coverage,
+    /// The URL of the documentation of every item of the crate, by path.
+    IdMap,
+}
+
+impl EmitType {
+    fn from_name(name: &str) -> Option<EmitType> {
+        match name {
+            "html" => Some(EmitType::Html),
+            "search-index" => Some(EmitType::SearchIndex),
+            "coverage" => Some(EmitType::Coverage),
+            "id-map" => Some(EmitType::IdMap),
+            _ => None,
+        }
+    }
+}
 
 /// Configuration options for rustdoc.
 #[derive(Clone)]
@@ -58,6 +93,9 @@ pub struct Options {
     pub debugging_options_strs: Vec<String>,
     /// The target used to compile the crate against.
     pub target: TargetTriple,
+    /// Additional targets to document the crate for. Their items are merged into the ones of
+    /// `target`, noting the targets that items are only available on.
+    pub merge_targets: Vec<TargetTriple>,
     /// Edition used when reading the crate. Defaults to "2015". Also used by default when
     /// compiling doctests from the crate.
     pub edition: Edition,
@@ -88,6 +126,12 @@ pub struct Options {
     /// For example, using ignore-foo to ignore running the doctest on any target that
     /// contains "foo" as a substring
     pub enable_per_target_ignores: bool,
+    /// Whether to compile the doctests which allow it into a single binary, instead of compiling
+    /// each of them separately.
+    pub merge_doctests: bool,
+    /// Whether to report the time spent compiling and running each doctest, and in total for
+    /// each module.
+    pub doctest_timings: bool,
 
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from $sysroot/bin/rustc.
@@ -109,6 +153,40 @@ pub struct Options {
     /// Whether to run the `calculate-doc-coverage` pass, which counts the number of public items
     /// with and without documentation.
     pub show_coverage: bool,
+    /// Whether to run the passes and the lints of the documentation without rendering it, with
+    /// `--check`. The documentation coverage is reported as well.
+    pub check: bool,
+    /// The format to write the output in. Only `--show-coverage`, `--check` and
+    /// `--doctest-timings` can write JSON.
+    pub output_format: OutputFormat,
+    /// The private modules to document the private items of, with
+    /// `--document-private-items=PATHS`.
+    pub document_private_paths: PrivatePaths,
+    /// Whether to inline the targets of public re-exports from other crates even if the targets
+    /// are `#[doc(hidden)]`, so that they get documented at the path of the re-export.
+    pub inline_hidden_reexports: bool,
+    /// Whether to document the `#[doc(hidden)]` items, so that they can be found with the search,
+    /// which tags them as hidden.
+    pub search_hidden: bool,
+    /// The kinds of public items the `missing_doc_code_examples` lint checks.
+    pub code_example_kinds: Vec<ItemType>,
+    /// Whether to compute the layout of structs, enums and unions and show it on their pages.
+    pub show_type_layout: bool,
+    /// Whether to record what the paths in the source of the crate resolve to, so that the
+    /// source pages can link them to their definitions.
+    pub generate_link_to_definition: bool,
+    /// The port to serve the documentation on with `--watch`, which documents the crate again
+    /// whenever its source files change.
+    pub watch: Option<u16>,
+    /// Whether `--verbose` was passed, to report the progress of long-running modes like
+    /// `--watch`.
+    pub verbose: bool,
+    /// The crates to document with `--workspace`, `input` being the first of them. This is empty
+    /// when a single crate is documented.
+    pub workspace: Vec<Member>,
+    /// The output directories to combine with `--merge-docs`, `input` being the first of them.
+    /// This is empty when a crate is documented.
+    pub merge_docs: Vec<PathBuf>,
 
     // Options that alter generated documentation pages
 
@@ -141,6 +219,7 @@ impl fmt::Debug for Options {
             .field("codegen_options", &"...")
             .field("debugging_options", &"...")
             .field("target", &self.target)
+            .field("merge_targets", &self.merge_targets)
             .field("edition", &self.edition)
             .field("maybe_sysroot", &self.maybe_sysroot)
             .field("lint_opts", &self.lint_opts)
@@ -153,11 +232,25 @@ impl fmt::Debug for Options {
             .field("manual_passes", &self.manual_passes)
             .field("display_warnings", &self.display_warnings)
             .field("show_coverage", &self.show_coverage)
+            .field("check", &self.check)
+            .field("output_format", &self.output_format)
+            .field("document_private_paths", &self.document_private_paths)
+            .field("inline_hidden_reexports", &self.inline_hidden_reexports)
+            .field("search_hidden", &self.search_hidden)
+            .field("code_example_kinds", &self.code_example_kinds)
+            .field("show_type_layout", &self.show_type_layout)
+            .field("generate_link_to_definition", &self.generate_link_to_definition)
+            .field("watch", &self.watch)
+            .field("verbose", &self.verbose)
+            .field("workspace", &self.workspace)
+            .field("merge_docs", &self.merge_docs)
             .field("crate_version", &self.crate_version)
             .field("render_options", &self.render_options)
             .field("runtool", &self.runtool)
             .field("runtool_args", &self.runtool_args)
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
+            .field("merge_doctests", &self.merge_doctests)
+            .field("doctest_timings", &self.doctest_timings)
             .finish()
     }
 }
@@ -222,9 +315,57 @@ pub struct RenderOptions {
     pub generate_search_filter: bool,
     /// Option (disabled by default) to generate files used by RLS and some other tools.
     pub generate_redirect_pages: bool,
+    /// Whether to compute documentation metrics for every item, show them on item pages and write
+    /// them to `item-metrics.json`.
+    pub item_metrics: bool,
+    /// Whether to write the documentation of every item of the crate to `print.html`.
+    pub generate_print_page: bool,
+    /// Whether to strip the insignificant whitespace and the comments from the generated HTML.
+    pub minify_output: bool,
+    /// Whether to write the impl blocks shown on several pages once, to files which the pages
+    /// load.
+    pub deduplicate_impls: bool,
+    /// Whether to write the static files under names containing the hash of their content.
+    pub hash_static_files: bool,
+    /// Whether to skip writing the pages which did not change since the previous run with the
+    /// same output directory.
+    pub incremental_docs: bool,
+    /// The number of items to list on each page of a module, if its listing is to be split into
+    /// several pages.
+    pub module_page_size: Option<usize>,
+    /// Whether to write gzip-compressed copies of the search index and of the other files shared
+    /// by the pages.
+    pub precompress: bool,
+    /// Whether to write the descriptions of the items to files loaded by the search when showing
+    /// results, instead of the search index.
+    pub shard_search_descriptions: bool,
+    /// The artifacts to produce, the pages and the search index by default.
+    pub emit: Vec<EmitType>,
+    /// The directory holding the documentation of every version, with `--versioned-output`. The
+    /// documentation is then written to the directory of its version in it.
+    pub versions_root: Option<PathBuf>,
 }
 
 impl Options {
+    /// Parses the options given as the arguments of the command-line, without the name of the
+    /// program. If an error message or other early-return has been printed, returns `Err` with
+    /// the exit code.
+    pub fn from_args(args: &[String]) -> Result<Options, i32> {
+        let mut options = getopts::Options::new();
+        for option in opts() {
+            (option.apply)(&mut options);
+        }
+        let matches = match options.parse(args) {
+            Ok(matches) => matches,
+            Err(err) => {
+                let diag = new_handler(ErrorOutputType::default(), None, None, false);
+                diag.struct_err(&err.to_string()).emit();
+                return Err(1);
+            }
+        };
+        Options::from_matches(&matches)
+    }
+
     /// Parses the given command-line for options. If an error message or other early-return has
     /// been printed, returns `Err` with the exit code.
     pub fn from_matches(matches: &getopts::Matches) -> Result<Options, i32> {
@@ -290,7 +431,11 @@ impl Options {
             println!("rustdoc: [check-theme] Starting tests! (Ignoring all other arguments)");
             for theme_file in to_check.iter() {
                 print!(" - Checking \"{}\"...", theme_file);
-                let (success, differences) = theme::test_theme_against(theme_file, &paths, &diag);
+                let (success, mut differences) =
+                    theme::test_theme_against(theme_file, &paths, &diag);
+                let (vars_success, undefined) = theme::test_theme_variables(theme_file, &diag);
+                let success = success && vars_success;
+                differences.extend(undefined);
                 if !differences.is_empty() || !success {
                     println!(" FAILED");
                     errors += 1;
@@ -311,23 +456,61 @@ impl Options {
             diag.struct_err("missing file operand").emit();
             return Err(1);
         }
-        if matches.free.len() > 1 {
+        let workspace = if matches.opt_present("workspace") {
+            match workspace::members(&matches.free) {
+                Ok(ref members) if members.is_empty() => {
+                    diag.struct_err("the workspace has no library to document").emit();
+                    return Err(1);
+                }
+                Ok(members) => members,
+                Err(err) => {
+                    diag.struct_err(&err).emit();
+                    return Err(1);
+                }
+            }
+        } else if matches.free.len() > 1 && !matches.opt_present("merge-docs") {
             diag.struct_err("too many file operands").emit();
             return Err(1);
-        }
-        let input = PathBuf::from(&matches.free[0]);
+        } else {
+            vec![]
+        };
+        let merge_docs = if matches.opt_present("merge-docs") {
+            matches.free.iter().map(PathBuf::from).collect()
+        } else {
+            vec![]
+        };
+        let input = match workspace.first() {
+            Some(member) => member.root.clone(),
+            None => PathBuf::from(&matches.free[0]),
+        };
+
+        // The options of the command line are layered over the ones of the configuration file.
+        let config_file = if nightly_options::is_unstable_enabled(matches) {
+            config_file::find(matches.opt_str("config"), &input)
+        } else {
+            None
+        };
+        let config_file = match config_file.map(|path| ConfigFile::load(&path)) {
+            Some(Ok(config_file)) => config_file,
+            Some(Err(err)) => {
+                diag.struct_err(&err).emit();
+                return Err(1);
+            }
+            None => ConfigFile::default(),
+        };
 
         let libs = matches.opt_strs("L").iter()
             .map(|s| SearchPath::from_cli_opt(s, error_format))
             .collect();
         let externs = parse_externs(&matches, &debugging_options, error_format);
-        let extern_html_root_urls = match parse_extern_html_roots(&matches) {
-            Ok(ex) => ex,
+        let mut extern_html_root_urls = config_file.extern_html_root_urls.clone();
+        match parse_extern_html_roots(&matches) {
+            Ok(ex) => extern_html_root_urls.extend(ex),
             Err(err) => {
                 diag.struct_err(err).emit();
                 return Err(1);
             }
-        };
+        }
 
         let test_args = matches.opt_strs("test-args");
         let test_args: Vec<String> = test_args.iter()
@@ -352,12 +535,15 @@ impl Options {
         }
 
         let mut themes = Vec::new();
-        if matches.opt_present("theme") {
+        let theme_args: Vec<String> = config_file.themes.iter()
+            .map(|theme| theme.display().to_string())
+            .chain(matches.opt_strs("theme"))
+            .collect();
+        if !theme_args.is_empty() {
             let paths = theme::load_css_paths(static_files::themes::LIGHT.as_bytes());
 
-            for (theme_file, theme_s) in matches.opt_strs("theme")
-                                                .iter()
-                                                .map(|s| (PathBuf::from(&s), s.to_owned())) {
+            for (theme_file, theme_s) in theme_args.iter()
+                                                   .map(|s| (PathBuf::from(&s), s.to_owned())) {
                 if !theme_file.is_file() {
                     diag.struct_err(&format!("invalid argument: \"{}\"", theme_s))
                         .help("arguments to --theme must be files")
@@ -369,6 +555,20 @@ impl Options {
                         .emit();
                     return Err(1);
                 }
+                let name = theme_file.file_stem().unwrap_or_default().to_string_lossy();
+                if theme::RESERVED_THEME_NAMES.contains(&&*name) {
+                    diag.struct_err(&format!("invalid argument: \"{}\"", theme_s))
+                        .note(&format!("rustdoc writes its own `{}.css` file", name))
+                        .help("rename the theme file")
+                        .emit();
+                    return Err(1);
+                }
+                if themes.iter().any(|t: &PathBuf| t.file_stem() == theme_file.file_stem()) {
+                    diag.struct_err(&format!("invalid argument: \"{}\"", theme_s))
+                        .note(&format!("another theme is already named `{}`", name))
+                        .emit();
+                    return Err(1);
+                }
                 let (success, ret) = theme::test_theme_against(&theme_file, &paths, &diag);
                 if !success {
                     diag.struct_err(&format!("error loading theme file: \"{}\"", theme_s)).emit();
@@ -381,6 +581,15 @@ impl Options {
                                         --check-theme \"{}\"`", theme_s))
                         .emit();
                 }
+                let (_, undefined) = theme::test_theme_variables(&theme_file, &diag);
+                if !undefined.is_empty() {
+                    diag.struct_warn(&format!("theme file \"{}\" uses CSS variables it doesn't \
+                                               define", theme_s))
+                        .warn("the rules using them are ignored when the theme is loaded")
+                        .help(&format!("to see which variables are undefined, call `rustdoc \
+                                        --check-theme \"{}\"`", theme_s))
+                        .emit();
+                }
                 themes.push(theme_file);
             }
         }
@@ -418,13 +627,54 @@ impl Options {
             }
         }
 
-        match matches.opt_str("w").as_ref().map(|s| &**s) {
-            Some("html") | None => {}
+        let show_coverage = matches.opt_present("show-coverage");
+        let check = matches.opt_present("check");
+        let doctest_timings = matches.opt_present("doctest-timings");
+        let verbose = matches.opt_present("verbose");
+        let watch = match matches.opt_default("watch", "8000") {
+            Some(port) => match port.parse() {
+                Ok(port) => Some(port),
+                Err(_) => {
+                    diag.struct_err(&format!("invalid port for `--watch`: {}", port)).emit();
+                    return Err(1);
+                }
+            },
+            None => None,
+        };
+        if watch.is_some() && (should_test || show_coverage) {
+            diag.struct_err("`--watch` cannot be used with `--test` or `--show-coverage`").emit();
+            return Err(1);
+        }
+        if check && (should_test || show_coverage || watch.is_some() || !merge_docs.is_empty()) {
+            diag.struct_err("`--check` cannot be used with `--test`, `--show-coverage`, `--watch` \
+                             or `--merge-docs`")
+                .emit();
+            return Err(1);
+        }
+        if !merge_docs.is_empty() && (should_test || !workspace.is_empty() || watch.is_some()) {
+            diag.struct_err("`--merge-docs` cannot be used with `--test`, `--workspace` or \
+                             `--watch`")
+                .emit();
+            return Err(1);
+        }
+        if !workspace.is_empty() && (should_test || matches.opt_present("crate-name")) {
+            diag.struct_err("`--workspace` cannot be used with `--test` or `--crate-name`").emit();
+            return Err(1);
+        }
+        let output_format = match matches.opt_str("w").as_ref().map(|s| &**s) {
+            Some("html") | None => OutputFormat::Html,
+            Some("json") if show_coverage || check || doctest_timings => OutputFormat::Json,
+            Some("json") => {
+                diag.struct_err("the json output format is only supported with `--show-coverage`, \
+                                 `--check` and `--doctest-timings`")
+                    .emit();
+                return Err(1);
+            }
             Some(s) => {
                 diag.struct_err(&format!("unknown output format: {}", s)).emit();
                 return Err(1);
             }
-        }
+        };
 
         let index_page = matches.opt_str("index-page").map(|s| PathBuf::from(&s));
         if let Some(ref index_page) = index_page {
@@ -434,18 +684,85 @@ impl Options {
             }
         }
 
-        let target = matches.opt_str("target").map_or(
-            TargetTriple::from_triple(host_triple()),
-            |target| {
+        let parse_target = |target: String| {
             if target.ends_with(".json") {
                 TargetTriple::TargetPath(PathBuf::from(target))
             } else {
                 TargetTriple::TargetTriple(target)
             }
-        });
+        };
+        let target = matches.opt_str("target")
+            .map_or(TargetTriple::from_triple(host_triple()), parse_target);
+        let merge_targets = matches.opt_strs("merge-target").into_iter()
+            .map(parse_target)
+            .filter(|merge_target| *merge_target != target)
+            .collect::<Vec<_>>();
 
-        let show_coverage = matches.opt_present("show-coverage");
-        let document_private = matches.opt_present("document-private-items");
+        // With a list of modules, the private items are only documented in these modules, the
+        // other private items being stripped as usual.
+        let document_private_paths = match matches.opt_str("document-private-items") {
+            Some(_) if !nightly_options::is_unstable_enabled(matches) => {
+                diag.struct_err("`--document-private-items=PATHS` is unstable")
+                    .note("use `-Z unstable-options` to enable it")
+                    .emit();
+                return Err(1);
+            }
+            Some(paths) => match PrivatePaths::parse(&paths) {
+                Ok(paths) => paths,
+                Err(err) => {
+                    diag.struct_err(&err).emit();
+                    return Err(1);
+                }
+            },
+            None => PrivatePaths::default(),
+        };
+        let document_private = matches.opt_present("document-private-items") &&
+            document_private_paths.is_empty();
+        let inline_hidden_reexports = matches.opt_present("inline-hidden-reexports");
+        let search_hidden = matches.opt_present("search-hidden");
+        let show_type_layout = matches.opt_present("show-type-layout");
+        let generate_link_to_definition = matches.opt_present("generate-link-to-definition");
+
+        let emit = match matches.opt_str("emit") {
+            Some(types) => {
+                let mut emit = vec![];
+                for name in types.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+                    match EmitType::from_name(name) {
+                        Some(ty) => emit.push(ty),
+                        None => {
+                            diag.struct_err(&format!("unknown emit type: {}", name))
+                                .note("the emit types are `html`, `search-index`, `coverage` \
+                                       and `id-map`")
+                                .emit();
+                            return Err(1);
+                        }
+                    }
+                }
+                emit
+            }
+            None => vec![EmitType::Html, EmitType::SearchIndex],
+        };
+        if matches.opt_present("emit") && (show_coverage || check) {
+            diag.struct_err("`--emit` cannot be used with `--show-coverage` or `--check`").emit();
+            return Err(1);
+        }
+
+        let code_example_kinds = match matches.opt_str("code-example-kinds") {
+            Some(kinds) => {
+                let mut code_example_kinds = vec![];
+                for kind in kinds.split(',').map(str::trim).filter(|kind| !kind.is_empty()) {
+                    match ItemType::from_name(kind) {
+                        Some(kind) => code_example_kinds.push(kind),
+                        None => {
+                            diag.struct_err(&format!("unknown item kind: {}", kind)).emit();
+                            return Err(1);
+                        }
+                    }
+                }
+                code_example_kinds
+            }
+            None => vec![ItemType::Function, ItemType::Method, ItemType::TyMethod, ItemType::Trait],
+        };
 
         let default_passes = if matches.opt_present("no-defaults") {
             passes::DefaultPassOption::None
@@ -458,7 +775,12 @@ impl Options {
         } else {
             passes::DefaultPassOption::Default
         };
-        let manual_passes = matches.opt_strs("passes");
+        let mut manual_passes = config_file.passes.clone();
+        manual_passes.extend(matches.opt_strs("passes"));
+        // The coverage is counted once the lints ran, over the items which would be documented.
+        if check || emit.contains(&EmitType::Coverage) {
+            manual_passes.push(passes::CALCULATE_DOC_COVERAGE.name.to_owned());
+        }
 
         let crate_types = match parse_crate_types_from_list(matches.opt_strs("crate-type")) {
             Ok(types) => types,
@@ -480,21 +802,89 @@ impl Options {
         let markdown_css = matches.opt_strs("markdown-css");
         let markdown_playground_url = matches.opt_str("markdown-playground-url");
         let crate_version = matches.opt_str("crate-version");
+        let (output, versions_root) = if matches.opt_present("versioned-output") {
+            match crate_version {
+                Some(ref version) => (output.join(version), Some(output)),
+                None => {
+                    diag.struct_err("`--versioned-output` requires `--crate-version`").emit();
+                    return Err(1);
+                }
+            }
+        } else {
+            (output, None)
+        };
         let enable_index_page = matches.opt_present("enable-index-page") || index_page.is_some();
         let static_root_path = matches.opt_str("static-root-path");
         let generate_search_filter = !matches.opt_present("disable-per-crate-search");
         let persist_doctests = matches.opt_str("persist-doctests").map(PathBuf::from);
         let generate_redirect_pages = matches.opt_present("generate-redirect-pages");
+        let item_metrics = matches.opt_present("item-metrics");
+        let generate_print_page = matches.opt_present("generate-print-page");
+        let minify_output = matches.opt_present("minify-output");
+        let deduplicate_impls = matches.opt_present("deduplicate-impls");
+        let hash_static_files = matches.opt_present("hash-static-files");
+        let incremental_docs = matches.opt_present("incremental-docs");
+        let precompress = matches.opt_present("precompress");
+        let shard_search_descriptions = matches.opt_present("shard-search-descriptions");
+        let module_page_size = match matches.opt_str("module-page-size") {
+            Some(size) => match size.parse() {
+                Ok(size) if size > 0 => Some(size),
+                _ => {
+                    diag.struct_err(&format!("invalid argument to `--module-page-size`: {}", size))
+                        .help("the page size must be a positive number of items")
+                        .emit();
+                    return Err(1);
+                }
+            },
+            None => None,
+        };
+        // These need every page to be rendered.
+        for &other in &["generate-print-page", "item-metrics", "deduplicate-impls"] {
+            if incremental_docs && matches.opt_present(other) {
+                diag.struct_err(&format!("the options `--incremental-docs` and `--{}` cannot be \
+                                          used together", other))
+                    .emit();
+                return Err(1);
+            }
+        }
         let test_builder = matches.opt_str("test-builder").map(PathBuf::from);
         let codegen_options_strs = matches.opt_strs("C");
         let debugging_options_strs = matches.opt_strs("Z");
         let lib_strs = matches.opt_strs("L");
         let extern_strs = matches.opt_strs("extern");
-        let runtool = matches.opt_str("runtool");
-        let runtool_args = matches.opt_strs("runtool-arg");
+        let mut runtool = matches.opt_str("runtool");
+        let mut runtool_args = matches.opt_strs("runtool-arg");
+        // `--runner` gives the runtool and its first arguments as a single command line.
+        if let Some(runner) = matches.opt_str("runner") {
+            if runtool.is_some() {
+                diag.struct_err("the options `--runner` and `--runtool` cannot be used together")
+                    .emit();
+                return Err(1);
+            }
+            let mut words = runner.split_whitespace().map(|word| word.to_string());
+            match words.next() {
+                Some(tool) => {
+                    runtool = Some(tool);
+                    runtool_args = words.chain(runtool_args).collect();
+                }
+                None => {
+                    diag.struct_err("option `--runner` requires a command").emit();
+                    return Err(1);
+                }
+            }
+        }
         let enable_per_target_ignores = matches.opt_present("enable-per-target-ignores");
+        let merge_doctests = matches.opt_present("merge-doctests");
 
-        let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+        let (cmd_lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
+        let mut lint_opts = match config_file.lint_opts() {
+            Ok(lint_opts) => lint_opts,
+            Err(err) => {
+                diag.struct_err(&err).emit();
+                return Err(1);
+            }
+        };
+        lint_opts.extend(cmd_lint_opts);
 
         Ok(Options {
             input,
@@ -511,6 +901,7 @@ impl Options {
             debugging_options,
             debugging_options_strs,
             target,
+            merge_targets,
             edition,
             maybe_sysroot,
             lint_opts,
@@ -522,11 +913,25 @@ impl Options {
             manual_passes,
             display_warnings,
             show_coverage,
+            check,
+            output_format,
+            document_private_paths,
+            inline_hidden_reexports,
+            search_hidden,
+            code_example_kinds,
+            show_type_layout,
+            generate_link_to_definition,
+            watch,
+            verbose,
+            workspace,
+            merge_docs,
             crate_version,
             persist_doctests,
             runtool,
             runtool_args,
             enable_per_target_ignores,
+            merge_doctests,
+            doctest_timings,
             test_builder,
             render_options: RenderOptions {
                 output,
@@ -547,6 +952,17 @@ impl Options {
                 markdown_playground_url,
                 generate_search_filter,
                 generate_redirect_pages,
+                item_metrics,
+                generate_print_page,
+                minify_output,
+                deduplicate_impls,
+                hash_static_files,
+                incremental_docs,
+                module_page_size,
+                precompress,
+                shard_search_descriptions,
+                emit,
+                versions_root,
             }
         })
     }
@@ -562,7 +978,6 @@ impl Options {
 fn check_deprecated_options(matches: &getopts::Matches, diag: &errors::Handler) {
     let deprecated_flags = [
        "input-format",
-       "output-format",
        "no-defaults",
        "passes",
     ];
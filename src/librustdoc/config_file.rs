@@ -0,0 +1,67 @@
+//! The `rustdoc.toml` configuration file.
+//!
+//! A crate can set the defaults of some options in a `rustdoc.toml` file next to its root, or in
+//! the file given with `--config`. The options given on the command line are layered over the
+//! ones of the file: the themes, passes and lint levels of both are used, those of the command
+//! line coming last so that they win, and the URLs given with `--extern-html-root-url` replace
+//! the ones of the file for the same crates. The file is only read with `-Z unstable-options`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustc::lint::Level;
+use serde::Deserialize;
+
+#[cfg(test)]
+mod tests;
+
+/// The name of the configuration file looked for next to the crate root.
+const CONFIG_FILE_NAME: &str = "rustdoc.toml";
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ConfigFile {
+    /// Additional themes to add to the documentation, relative to the directory of the file.
+    pub themes: Vec<PathBuf>,
+    /// The URLs of the documentation of the dependencies, by crate name.
+    pub extern_html_root_urls: BTreeMap<String, String>,
+    /// The level of the lints, by lint name.
+    pub lints: BTreeMap<String, String>,
+    /// The passes to run besides the default ones.
+    pub passes: Vec<String>,
+}
+
+impl ConfigFile {
+    /// Reads the configuration file at `path`, resolving its paths against its directory.
+    pub fn load(path: &Path) -> Result<ConfigFile, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+        let mut config: ConfigFile = toml::from_str(&contents)
+            .map_err(|e| format!("invalid configuration file {}: {}", path.display(), e))?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for theme in &mut config.themes {
+            *theme = dir.join(&*theme);
+        }
+        Ok(config)
+    }
+
+    /// Returns the lint levels of the file, in the form `get_cmd_lint_options` gives them.
+    pub fn lint_opts(&self) -> Result<Vec<(String, Level)>, String> {
+        self.lints.iter()
+            .map(|(name, level)| match Level::from_str(level) {
+                Some(level) => Ok((name.replace("-", "_"), level)),
+                None => Err(format!("unknown lint level for `{}`: `{}`", name, level)),
+            })
+            .collect()
+    }
+}
+
+/// Returns the configuration file to read: the one given with `--config`, or else the one next
+/// to `input` if there is one.
+pub fn find(config: Option<String>, input: &Path) -> Option<PathBuf> {
+    config.map(PathBuf::from).or_else(|| {
+        let candidate = input.with_file_name(CONFIG_FILE_NAME);
+        if candidate.is_file() { Some(candidate) } else { None }
+    })
+}
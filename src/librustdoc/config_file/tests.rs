@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn test_load() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(CONFIG_FILE_NAME);
+    fs::write(&path, "themes = [\"themes/dark.css\"]\n\
+                      passes = [\"collapse-docs\"]\n\
+                      [extern-html-root-urls]\n\
+                      serde = \"https://docs.rs/serde/1.0.0\"\n\
+                      [lints]\n\
+                      missing-docs = \"deny\"\n\
+                      intra_doc_link_resolution_failure = \"allow\"\n").unwrap();
+
+    let config = ConfigFile::load(&path).unwrap();
+    assert_eq!(config.themes, [dir.path().join("themes/dark.css")]);
+    assert_eq!(config.passes, ["collapse-docs"]);
+    assert_eq!(config.extern_html_root_urls["serde"], "https://docs.rs/serde/1.0.0");
+    assert_eq!(config.lint_opts().unwrap(),
+               [("intra_doc_link_resolution_failure".to_owned(), Level::Allow),
+                ("missing_docs".to_owned(), Level::Deny)]);
+
+    assert_eq!(find(None, &dir.path().join("lib.rs")), Some(path.clone()));
+    assert_eq!(find(Some("other.toml".to_owned()), &dir.path().join("lib.rs")),
+               Some(PathBuf::from("other.toml")));
+    assert_eq!(find(None, &dir.path().join("src/lib.rs")), None);
+}
+
+#[test]
+fn test_load_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(CONFIG_FILE_NAME);
+    fs::write(&path, "theme = \"dark.css\"\n").unwrap();
+    assert!(ConfigFile::load(&path).unwrap_err().contains("unknown field `theme`"));
+
+    fs::write(&path, "[lints]\nmissing-docs = \"loud\"\n").unwrap();
+    assert_eq!(ConfigFile::load(&path).unwrap().lint_opts().unwrap_err(),
+               "unknown lint level for `missing-docs`: `loud`");
+}
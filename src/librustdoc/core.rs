@@ -26,14 +26,17 @@ use errors::emitter::{Emitter, EmitterWriter};
 use std::cell::RefCell;
 use std::mem;
 use rustc_data_structures::sync::{self, Lrc};
+use rustc_metadata::creader::CStore;
 use std::rc::Rc;
 
-use crate::config::{Options as RustdocOptions, RenderOptions};
+use crate::config::{Options as RustdocOptions, OutputFormat, RenderOptions};
 use crate::clean;
 use crate::clean::{MAX_DEF_ID, AttributesExt};
+use crate::html::item_type::ItemType;
 use crate::html::render::RenderInfo;
 
-use crate::passes;
+use crate::passes::{self, PrivatePaths};
+use crate::span_map;
 
 pub use rustc::session::config::{Input, Options, CodegenOptions};
 pub use rustc::session::search_paths::SearchPath;
@@ -43,31 +46,53 @@ pub type ExternalPaths = FxHashMap<DefId, (Vec<String>, clean::TypeKind)>;
 pub struct DocContext<'tcx> {
 
     pub tcx: TyCtxt<'tcx>,
+    /// Shared with `rustc_interface`, which hands the resolver out as an `Rc<RefCell<_>>`.
     pub resolver: Rc<RefCell<interface::BoxedResolver>>,
     /// Later on moved into `html::render::CACHE_KEY`
-    pub renderinfo: RefCell<RenderInfo>,
-    /// Later on moved through `clean::Crate` into `html::render::CACHE_KEY`
-    pub external_traits: Rc<RefCell<FxHashMap<DefId, clean::Trait>>>,
-    /// Used while populating `external_traits` to ensure we don't process the same trait twice at
-    /// the same time.
-    pub active_extern_traits: RefCell<FxHashSet<DefId>>,
+    pub renderinfo: RenderInfo,
+    /// The traits recorded since the last pass, moved into `clean::Crate::external_traits` after
+    /// cleaning and after every pass, and later on into `html::render::CACHE_KEY`
+    pub external_traits: FxHashMap<DefId, clean::Trait>,
+    /// The traits recorded in `external_traits` so far or being built, to ensure we don't process
+    /// the same trait twice.
+    pub recorded_extern_traits: FxHashSet<DefId>,
     // The current set of type and lifetime substitutions,
     // for expanding type aliases at the HIR level:
 
     /// Table `DefId` of type parameter -> substituted type
-    pub ty_substs: RefCell<FxHashMap<DefId, clean::Type>>,
+    pub ty_substs: FxHashMap<DefId, clean::Type>,
     /// Table `DefId` of lifetime parameter -> substituted lifetime
-    pub lt_substs: RefCell<FxHashMap<DefId, clean::Lifetime>>,
+    pub lt_substs: FxHashMap<DefId, clean::Lifetime>,
     /// Table `DefId` of const parameter -> substituted const
-    pub ct_substs: RefCell<FxHashMap<DefId, clean::Constant>>,
+    pub ct_substs: FxHashMap<DefId, clean::Constant>,
     /// Table synthetic type parameter for `impl Trait` in argument position -> bounds
-    pub impl_trait_bounds: RefCell<FxHashMap<ImplTraitParam, Vec<clean::GenericBound>>>,
-    pub fake_def_ids: RefCell<FxHashMap<CrateNum, DefId>>,
-    pub all_fake_def_ids: RefCell<FxHashSet<DefId>>,
+    pub impl_trait_bounds: FxHashMap<ImplTraitParam, Vec<clean::GenericBound>>,
+    pub fake_def_ids: FxHashMap<CrateNum, DefId>,
+    pub all_fake_def_ids: FxHashSet<DefId>,
     /// Auto-trait or blanket impls processed so far, as `(self_ty, trait_def_id)`.
     // FIXME(eddyb) make this a `ty::TraitRef<'tcx>` set.
-    pub generated_synthetics: RefCell<FxHashSet<(Ty<'tcx>, DefId)>>,
+    pub generated_synthetics: FxHashSet<(Ty<'tcx>, DefId)>,
     pub auto_traits: Vec<DefId>,
+    /// Trait impls from other crates, indexed the first time they're needed so that they only get
+    /// inlined once the types or traits they mention are known to be documented.
+    pub external_impls: Option<clean::inline::ExternalImplIndex>,
+    /// Fully qualified names recorded by `clean::inline::record_extern_fqn`, computed at most once
+    /// per `DefId`. Moved into `renderinfo` once the passes have run.
+    pub extern_fqns: ExternalPaths,
+    /// Whether re-exports from other crates are inlined even if their targets are
+    /// `#[doc(hidden)]`.
+    pub inline_hidden_reexports: bool,
+    /// The format passes which print a report, like `calculate-doc-coverage`, write it in.
+    pub output_format: OutputFormat,
+    /// The kinds of public items the `missing_doc_code_examples` lint checks.
+    pub code_example_kinds: Vec<ItemType>,
+    /// Whether the layout of structs, enums and unions is computed for their pages.
+    pub show_type_layout: bool,
+    /// The private modules whose private items are documented.
+    pub document_private_paths: PrivatePaths,
+    /// The `#[doc(fragment)]`s of the crate, substituted for `{{include:name}}` when the
+    /// attributes are cleaned.
+    pub doc_fragments: clean::fragments::DocFragments,
 }
 
 impl<'tcx> DocContext<'tcx> {
@@ -80,23 +105,28 @@ impl<'tcx> DocContext<'tcx> {
         self.resolver.borrow_mut().access(f)
     }
 
+    /// The crate store, to load the metadata of other crates without borrowing the resolver.
+    pub fn cstore(&self) -> &'tcx CStore {
+        self.tcx.cstore_as_any().downcast_ref::<CStore>().expect("`tcx.cstore` is not a `CStore`")
+    }
+
     /// Call the closure with the given parameters set as
     /// the substitutions for a type alias' RHS.
-    pub fn enter_alias<F, R>(&self,
+    pub fn enter_alias<F, R>(&mut self,
                              ty_substs: FxHashMap<DefId, clean::Type>,
                              lt_substs: FxHashMap<DefId, clean::Lifetime>,
                              ct_substs: FxHashMap<DefId, clean::Constant>,
                              f: F) -> R
-    where F: FnOnce() -> R {
+    where F: FnOnce(&mut Self) -> R {
         let (old_tys, old_lts, old_cts) = (
-            mem::replace(&mut *self.ty_substs.borrow_mut(), ty_substs),
-            mem::replace(&mut *self.lt_substs.borrow_mut(), lt_substs),
-            mem::replace(&mut *self.ct_substs.borrow_mut(), ct_substs),
+            mem::replace(&mut self.ty_substs, ty_substs),
+            mem::replace(&mut self.lt_substs, lt_substs),
+            mem::replace(&mut self.ct_substs, ct_substs),
         );
-        let r = f();
-        *self.ty_substs.borrow_mut() = old_tys;
-        *self.lt_substs.borrow_mut() = old_lts;
-        *self.ct_substs.borrow_mut() = old_cts;
+        let r = f(self);
+        self.ty_substs = old_tys;
+        self.lt_substs = old_lts;
+        self.ct_substs = old_cts;
         r
     }
 
@@ -108,7 +138,7 @@ impl<'tcx> DocContext<'tcx> {
     // Instead, we construct 'fake' def ids, which start immediately after the last DefId.
     // In the Debug impl for clean::Item, we explicitly check for fake
     // def ids, as we'll end up with a panic if we use the DefId Debug impl for fake DefIds
-    pub fn next_def_id(&self, crate_num: CrateNum) -> DefId {
+    pub fn next_def_id(&mut self, crate_num: CrateNum) -> DefId {
         let start_def_id = {
             let next_id = if crate_num == LOCAL_CRATE {
                 self.tcx
@@ -117,7 +147,7 @@ impl<'tcx> DocContext<'tcx> {
                     .def_path_table()
                     .next_id()
             } else {
-                self.enter_resolver(|r| r.cstore().def_path_table(crate_num).next_id())
+                self.cstore().def_path_table(crate_num).next_id()
             };
 
             DefId {
@@ -126,10 +156,8 @@ impl<'tcx> DocContext<'tcx> {
             }
         };
 
-        let mut fake_ids = self.fake_def_ids.borrow_mut();
-
-        let def_id = fake_ids.entry(crate_num).or_insert(start_def_id).clone();
-        fake_ids.insert(
+        let def_id = self.fake_def_ids.entry(crate_num).or_insert(start_def_id).clone();
+        self.fake_def_ids.insert(
             crate_num,
             DefId {
                 krate: crate_num,
@@ -143,7 +171,7 @@ impl<'tcx> DocContext<'tcx> {
                 .or_insert(start_def_id);
         });
 
-        self.all_fake_def_ids.borrow_mut().insert(def_id);
+        self.all_fake_def_ids.insert(def_id);
 
         def_id.clone()
     }
@@ -151,7 +179,7 @@ impl<'tcx> DocContext<'tcx> {
     /// Like the function of the same name on the HIR map, but skips calling it on fake DefIds.
     /// (This avoids a slice-index-out-of-bounds panic.)
     pub fn as_local_hir_id(&self, def_id: DefId) -> Option<HirId> {
-        if self.all_fake_def_ids.borrow().contains(&def_id) {
+        if self.all_fake_def_ids.contains(&def_id) {
             None
         } else {
             self.tcx.hir().as_local_hir_id(def_id)
@@ -244,6 +272,13 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         mut default_passes,
         mut manual_passes,
         display_warnings,
+        document_private_paths,
+        inline_hidden_reexports,
+        search_hidden,
+        output_format,
+        code_example_kinds,
+        show_type_layout,
+        generate_link_to_definition,
         render_options,
         ..
     } = options;
@@ -258,20 +293,14 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
     let cpath = Some(input.clone());
     let input = Input::File(input);
 
-    let intra_link_resolution_failure_name = lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE.name;
     let warnings_lint_name = lint::builtin::WARNINGS.name;
     let missing_docs = rustc_lint::builtin::MISSING_DOCS.name;
-    let missing_doc_example = rustc_lint::builtin::MISSING_DOC_CODE_EXAMPLES.name;
-    let private_doc_tests = rustc_lint::builtin::PRIVATE_DOC_TESTS.name;
+    let rustdoc_lints = crate::lint::rustdoc_lints();
 
     // In addition to those specific lints, we also need to whitelist those given through
     // command line, otherwise they'll get ignored and we don't want that.
-    let mut whitelisted_lints = vec![warnings_lint_name.to_owned(),
-                                     intra_link_resolution_failure_name.to_owned(),
-                                     missing_docs.to_owned(),
-                                     missing_doc_example.to_owned(),
-                                     private_doc_tests.to_owned()];
-
+    let mut whitelisted_lints = vec![warnings_lint_name.to_owned(), missing_docs.to_owned()];
+    whitelisted_lints.extend(rustdoc_lints.iter().map(|(_, lint)| lint.name.to_owned()));
     whitelisted_lints.extend(lint_opts.iter().map(|(lint, _)| lint).cloned());
 
     let lints = || {
@@ -280,9 +309,11 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
             .chain(rustc_lint::SoftLints::get_lints().into_iter())
     };
 
+    // Rustdoc's own lints keep their default level, so that they can be controlled by the
+    // attributes of the crate.
     let lint_opts = lints().filter_map(|lint| {
         if lint.name == warnings_lint_name ||
-            lint.name == intra_link_resolution_failure_name {
+            rustdoc_lints.iter().any(|(_, rustdoc_lint)| lint.name == rustdoc_lint.name) {
             None
         } else {
             Some((lint.name_lower(), lint::Allow))
@@ -340,7 +371,7 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
         stderr: None,
         crate_name,
         lint_caps,
-        register_lints: None,
+        register_lints: Some(box crate::lint::register_lints),
         override_queries: None,
         registry: rustc_driver::diagnostics_registry(),
     };
@@ -395,13 +426,16 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
 
             let mut renderinfo = RenderInfo::default();
             renderinfo.access_levels = access_levels;
+            if generate_link_to_definition {
+                renderinfo.span_map = span_map::collect_spans(tcx);
+            }
 
             let mut ctxt = DocContext {
                 tcx,
                 resolver,
                 external_traits: Default::default(),
-                active_extern_traits: Default::default(),
-                renderinfo: RefCell::new(renderinfo),
+                recorded_extern_traits: Default::default(),
+                renderinfo,
                 ty_substs: Default::default(),
                 lt_substs: Default::default(),
                 ct_substs: Default::default(),
@@ -412,10 +446,21 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                 auto_traits: tcx.all_traits(LOCAL_CRATE).iter().cloned().filter(|trait_def_id| {
                     tcx.trait_is_auto(*trait_def_id)
                 }).collect(),
+                external_impls: Default::default(),
+                extern_fqns: Default::default(),
+                inline_hidden_reexports,
+                output_format,
+                code_example_kinds,
+                show_type_layout,
+                document_private_paths,
+                doc_fragments: clean::fragments::collect_fragments(tcx),
             };
             debug!("crate: {:?}", tcx.hir().krate());
 
             let mut krate = clean::krate(&mut ctxt);
+            if generate_link_to_definition {
+                span_map::record_extern_items(&mut ctxt);
+            }
 
             fn report_deprecated_attr(name: &str, diag: &errors::Handler) {
                 let mut msg = diag.struct_warn(&format!("the `#![doc({})]` attribute is \
@@ -468,8 +513,10 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
                 }
             }
 
-            let passes = passes::defaults(default_passes).iter().chain(manual_passes.into_iter()
-                .flat_map(|name| {
+            // With `--search-hidden`, the hidden items are documented and tagged in the search.
+            let passes = passes::defaults(default_passes).iter()
+                .filter(|pass| !search_hidden || pass.name != passes::STRIP_HIDDEN.name)
+                .chain(manual_passes.into_iter().flat_map(|name| {
                     if let Some(pass) = passes::find_pass(&name) {
                         Some(pass)
                     } else {
@@ -482,12 +529,23 @@ pub fn run_core(options: RustdocOptions) -> (clean::Crate, RenderInfo, RenderOpt
 
             for pass in passes {
                 debug!("running pass {}", pass.name);
-                krate = (pass.pass)(krate, &ctxt);
+                krate = (pass.pass)(krate, &mut ctxt);
+                krate.external_traits.extend(mem::take(&mut ctxt.external_traits));
             }
 
             ctxt.sess().abort_if_errors();
 
-            (krate, ctxt.renderinfo.into_inner(), render_options)
+            let mut renderinfo = ctxt.renderinfo;
+            for (did, (fqn, kind)) in ctxt.extern_fqns {
+                if did.is_local() {
+                    renderinfo.exact_paths.insert(did, fqn);
+                } else {
+                    renderinfo.external_paths.insert(did, (fqn, kind));
+                }
+            }
+            clean::record_canonical_paths(&mut krate, &renderinfo.exact_paths);
+
+            (krate, renderinfo, render_options)
         })
     }))
 }
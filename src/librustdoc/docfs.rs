@@ -10,9 +10,10 @@
 //! abstraction.
 
 use errors;
+use rustc_data_structures::sync::Lock;
 
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::mpsc::{channel, Receiver, Sender};
@@ -30,17 +31,19 @@ pub trait PathError {
     fn new<P: AsRef<Path>>(e: io::Error, path: P) -> Self;
 }
 
+/// The errors of the writes done in the background. Its channel is locked, as the pages may be
+/// written from the threads rendering them.
 pub struct ErrorStorage {
-    sender: Option<Sender<Option<String>>>,
-    receiver: Receiver<Option<String>>,
+    sender: Lock<Option<Sender<Option<String>>>>,
+    receiver: Lock<Receiver<Option<String>>>,
 }
 
 impl ErrorStorage {
     pub fn new() -> ErrorStorage {
         let (sender, receiver) = channel();
         ErrorStorage {
-            sender: Some(sender),
-            receiver,
+            sender: Lock::new(Some(sender)),
+            receiver: Lock::new(receiver),
         }
     }
 
@@ -48,9 +51,9 @@ impl ErrorStorage {
     pub fn write_errors(&mut self, diag: &errors::Handler) -> usize {
         let mut printed = 0;
         // In order to drop the sender part of the channel.
-        self.sender = None;
+        *self.sender.get_mut() = None;
 
-        for msg in self.receiver.iter() {
+        for msg in self.receiver.get_mut().iter() {
             if let Some(ref error) = msg {
                 diag.struct_err(&error).emit();
                 printed += 1;
@@ -95,7 +98,7 @@ impl DocFS {
             // be to create the file sync so errors are reported eagerly.
             let contents = contents.as_ref().to_vec();
             let path = path.as_ref().to_path_buf();
-            let sender = self.errors.sender.clone().unwrap();
+            let sender = self.errors.sender.lock().clone().unwrap();
             rayon::spawn(move || {
                 match fs::write(&path, &contents) {
                     Ok(_) => {
@@ -113,4 +116,23 @@ impl DocFS {
             Ok(try_err!(fs::write(&path, contents), path))
         }
     }
+
+    /// Writes the file at `path` with the contents that `write` streams into it, through a
+    /// buffer, so that they never need to be held in memory at once. When the write would be
+    /// done on another thread, the contents are collected first and handed to `write` above.
+    pub fn write_with<P, F, E>(&self, path: P, write: F) -> Result<(), E>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+        E: PathError,
+    {
+        if !self.sync_only && cfg!(windows) {
+            let mut contents = Vec::new();
+            try_err!(write(&mut contents), path);
+            return self.write(path, contents);
+        }
+        let mut file = io::BufWriter::new(try_err!(fs::File::create(&path), path));
+        try_err!(write(&mut file), path);
+        Ok(try_err!(file.flush(), path))
+    }
 }
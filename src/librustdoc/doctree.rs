@@ -8,6 +8,7 @@ use syntax_pos::hygiene::MacroKind;
 use syntax_pos::{self, Span};
 
 use rustc::hir;
+use rustc::hir::def::Res;
 use rustc::hir::def_id::CrateNum;
 use rustc::hir::ptr::P;
 
@@ -18,6 +19,7 @@ pub struct Module<'hir> {
     pub where_inner: Span,
     pub extern_crates: Vec<ExternCrate<'hir>>,
     pub imports: Vec<Import<'hir>>,
+    pub glob_reexports: Vec<GlobReexport>,
     pub structs: Vec<Struct<'hir>>,
     pub unions: Vec<Union<'hir>>,
     pub enums: Vec<Enum<'hir>>,
@@ -53,6 +55,7 @@ impl Module<'hir> {
             attrs,
             extern_crates: Vec::new(),
             imports    :   Vec::new(),
+            glob_reexports: Vec::new(),
             structs    :   Vec::new(),
             unions     :   Vec::new(),
             enums      :   Vec::new(),
@@ -232,6 +235,8 @@ pub struct Macro<'hir> {
     pub attrs: &'hir [ast::Attribute],
     pub whence: Span,
     pub matchers: hir::HirVec<Span>,
+    /// Whether the macro is defined with `macro_rules!`, rather than as a `macro` item.
+    pub legacy: bool,
     pub imported_from: Option<Name>,
 }
 
@@ -254,6 +259,13 @@ pub struct Import<'hir> {
     pub whence: Span,
 }
 
+/// An item reached through a glob import which asked not to be inlined with `#[doc(no_inline)]`.
+/// It has no `use` statement of its own, so it is documented as a re-export of its own path.
+pub struct GlobReexport {
+    pub name: Name,
+    pub res: Res,
+}
+
 pub struct ProcMacro<'hir> {
     pub name: Name,
     pub id: hir::HirId,
@@ -29,11 +29,11 @@ impl ExternalHtml {
         let bc = load_external_files(before_content, diag)?;
         let m_bc = load_external_files(md_before_content, diag)?;
         let bc = format!("{}{}", bc, Markdown(&m_bc, &[], id_map,
-                                    codes, edition, playground).to_string());
+                                    codes, edition, playground, None).to_string());
         let ac = load_external_files(after_content, diag)?;
         let m_ac = load_external_files(md_after_content, diag)?;
         let ac = format!("{}{}", ac, Markdown(&m_ac, &[], id_map,
-                                    codes, edition, playground).to_string());
+                                    codes, edition, playground, None).to_string());
         Some(ExternalHtml {
             in_header: ih,
             before_content: bc,
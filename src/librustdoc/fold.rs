@@ -83,6 +83,7 @@ pub trait DocFolder : Sized {
             inner,
             stability,
             deprecation,
+            canonical_path,
         } = item;
 
         let inner = match inner {
@@ -91,7 +92,7 @@ pub trait DocFolder : Sized {
         };
 
         Some(Item { attrs, name, source, inner, visibility,
-                    stability, deprecation, def_id })
+                    stability, deprecation, canonical_path, def_id })
     }
 
     fn fold_mod(&mut self, m: Module) -> Module {
@@ -104,14 +105,11 @@ pub trait DocFolder : Sized {
     fn fold_crate(&mut self, mut c: Crate) -> Crate {
         c.module = c.module.take().and_then(|module| self.fold_item(module));
 
-        {
-            let mut guard = c.external_traits.borrow_mut();
-            let external_traits = std::mem::replace(&mut *guard, Default::default());
-            *guard = external_traits.into_iter().map(|(k, mut v)| {
-                v.items = v.items.into_iter().filter_map(|i| self.fold_item(i)).collect();
-                (k, v)
-            }).collect();
-        }
+        let external_traits = std::mem::take(&mut c.external_traits);
+        c.external_traits = external_traits.into_iter().map(|(k, mut v)| {
+            v.items = v.items.into_iter().filter_map(|i| self.fold_item(i)).collect();
+            (k, v)
+        }).collect();
         c
     }
 }
@@ -8,11 +8,15 @@
 use std::borrow::Cow;
 use std::cell::Cell;
 use std::fmt;
+use std::hash::Hash;
 
 use rustc::hir::def_id::DefId;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::StableHasher;
 use rustc::util::nodemap::FxHashSet;
 use rustc_target::spec::abi::Abi;
 use rustc::hir;
+use syntax_pos::symbol::{SymbolStr, kw};
 
 use crate::clean::{self, PrimitiveType};
 use crate::html::item_type::ItemType;
@@ -176,7 +180,7 @@ impl clean::GenericParamDef {
             match self.kind {
                 clean::GenericParamDefKind::Lifetime => write!(f, "{}", self.name),
                 clean::GenericParamDefKind::Type { ref bounds, ref default, .. } => {
-                    f.write_str(&self.name)?;
+                    f.write_str(&self.name.as_str())?;
 
                     if !bounds.is_empty() {
                         if f.alternate() {
@@ -198,7 +202,7 @@ impl clean::GenericParamDef {
                 }
                 clean::GenericParamDefKind::Const { ref ty, .. } => {
                     f.write_str("const ")?;
-                    f.write_str(&self.name)?;
+                    f.write_str(&self.name.as_str())?;
 
                     if f.alternate() {
                         write!(f, ": {:#}", ty.print())
@@ -309,7 +313,7 @@ impl<'a> fmt::Display for WhereClause<'a> {
 }
 
 impl clean::Lifetime {
-    crate fn print(&self) -> &str {
+    crate fn print(&self) -> SymbolStr {
         self.get_ref()
     }
 }
@@ -437,7 +441,7 @@ impl clean::GenericArgs {
 impl clean::PathSegment {
     crate fn print(&self) -> impl fmt::Display + '_ {
         display_fn(move |f| {
-            f.write_str(&self.name)?;
+            f.write_str(&self.name.as_str())?;
             if f.alternate() {
                 write!(f, "{:#}", self.args.print())
             } else {
@@ -531,7 +535,7 @@ fn resolved_path(w: &mut fmt::Formatter<'_>, did: DefId, path: &clean::Path,
                 last.name.to_string()
             }
         } else {
-            anchor(did, &last.name).to_string()
+            anchor(did, &last.name.as_str()).to_string()
         };
         write!(w, "{}{}", path, last.args.print())?;
     }
@@ -611,8 +615,8 @@ pub fn anchor(did: DefId, text: &str) -> impl fmt::Display + '_ {
 
 fn fmt_type(t: &clean::Type, f: &mut fmt::Formatter<'_>, use_absolute: bool) -> fmt::Result {
     match *t {
-        clean::Generic(ref name) => {
-            f.write_str(name)
+        clean::Generic(name) => {
+            f.write_str(&name.as_str())
         }
         clean::ResolvedPath{ did, ref param_names, ref path, is_generic } => {
             if param_names.is_some() {
@@ -804,10 +808,34 @@ fn fmt_type(t: &clean::Type, f: &mut fmt::Formatter<'_>, use_absolute: bool) ->
     }
 }
 
+thread_local!(static IN_TYPE_FRAGMENT: Cell<bool> = Cell::new(false));
+
 impl clean::Type {
+    /// Formats the type, reusing the fragment rendered for it earlier at the same depth, if any.
+    ///
+    /// Only the outermost type is looked up: the types inside it are formatted as part of its
+    /// fragment, so they do not allocate fragments of their own.
     crate fn print(&self) -> impl fmt::Display + '_ {
         display_fn(move |f| {
-            fmt_type(self, f, false)
+            if IN_TYPE_FRAGMENT.with(|inside| inside.get()) {
+                return fmt_type(self, f, false);
+            }
+            let cache = cache();
+            let mut hasher = StableHasher::new();
+            self.hash(&mut hasher);
+            let fingerprint: Fingerprint = hasher.finish();
+            let key = (CURRENT_DEPTH.with(|s| s.get()), f.alternate(), fingerprint);
+            if let Some(fragment) = cache.type_fragments.lock().get(&key) {
+                return f.write_str(fragment);
+            }
+            // The lock is not held while formatting, as other threads may look types up.
+            IN_TYPE_FRAGMENT.with(|inside| inside.set(true));
+            let fragment = display_fn(|f| fmt_type(self, f, false));
+            let fragment = if key.1 { format!("{:#}", fragment) } else { fragment.to_string() };
+            IN_TYPE_FRAGMENT.with(|inside| inside.set(false));
+            f.write_str(&fragment)?;
+            cache.type_fragments.lock().insert(key, fragment);
+            Ok(())
         })
     }
 }
@@ -876,7 +904,7 @@ impl clean::Arguments {
     crate fn print(&self) -> impl fmt::Display + '_ {
         display_fn(move |f| {
             for (i, input) in self.values.iter().enumerate() {
-                if !input.name.is_empty() {
+                if input.name != kw::Invalid {
                     write!(f, "{}: ", input.name)?;
                 }
                 if f.alternate() {
@@ -970,7 +998,7 @@ impl Function<'_> {
                         args.push_str(" <br>");
                         args_plain.push_str(" ");
                     }
-                    if !input.name.is_empty() {
+                    if input.name != kw::Invalid {
                         args.push_str(&format!("{}: ", input.name));
                         args_plain.push_str(&format!("{}: ", input.name));
                     }
@@ -1039,7 +1067,8 @@ impl clean::Visibility {
                 clean::Visibility::Restricted(did, ref path) => {
                     f.write_str("pub(")?;
                     if path.segments.len() != 1
-                        || (path.segments[0].name != "self" && path.segments[0].name != "super")
+                        || (path.segments[0].name != kw::SelfLower
+                            && path.segments[0].name != kw::Super)
                     {
                         f.write_str("in ")?;
                     }
@@ -1087,7 +1116,7 @@ impl clean::Import {
         display_fn(move |f| {
             match *self {
                 clean::Import::Simple(ref name, ref src) => {
-                    if *name == src.path.last_name() {
+                    if src.path.last_name() == *name {
                         write!(f, "use {};", src.print())
                     } else {
                         write!(f, "use {} as {};", src.print(), *name)
@@ -1127,7 +1156,7 @@ impl clean::ImportSource {
 impl clean::TypeBinding {
     crate fn print(&self) -> impl fmt::Display + '_ {
         display_fn(move |f| {
-            f.write_str(&self.name)?;
+            f.write_str(&self.name.as_str())?;
             match self.kind {
                 clean::TypeBindingKind::Equality { ref ty } => {
                     if f.alternate() {
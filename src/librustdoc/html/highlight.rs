@@ -3,7 +3,8 @@
 //! This module uses libsyntax's lexer to provide token-based highlighting for
 //! the HTML documentation generated by rustdoc.
 //!
-//! Use the `render_with_highlighting` to highlight some rust code.
+//! Use the `render_with_highlighting` to highlight some rust code, or `render_with_links` to
+//! highlight a source file with links to the items its paths refer to.
 
 use crate::html::escape::Escape;
 
@@ -11,12 +12,13 @@ use std::fmt::Display;
 use std::io;
 use std::io::prelude::*;
 
+use rustc_data_structures::fx::FxHashMap;
 use rustc_parse::lexer;
 use syntax::token::{self, Token};
 use syntax::sess::ParseSess;
 use syntax::source_map::SourceMap;
 use syntax::symbol::{kw, sym};
-use syntax_pos::{Span, FileName};
+use syntax_pos::{BytePos, Span, FileName};
 
 /// Highlights `src`, returning the HTML output.
 pub fn render_with_highlighting(
@@ -24,6 +26,22 @@ pub fn render_with_highlighting(
     class: Option<&str>,
     extension: Option<&str>,
     tooltip: Option<(&str, &str)>,
+) -> String {
+    render(src, class, extension, tooltip, &FxHashMap::default())
+}
+
+/// Highlights the source file `src` like `render_with_highlighting`, also turning the
+/// identifiers found at the byte ranges of `links` into links to the paired URLs.
+pub fn render_with_links(src: &str, links: &FxHashMap<(u32, u32), String>) -> String {
+    render(src, None, None, None, links)
+}
+
+fn render(
+    src: &str,
+    class: Option<&str>,
+    extension: Option<&str>,
+    tooltip: Option<(&str, &str)>,
+    links: &FxHashMap<(u32, u32), String>,
 ) -> String {
     debug!("highlighting: ================\n{}\n==============", src);
     let mut out = Vec::new();
@@ -39,8 +57,9 @@ pub fn render_with_highlighting(
         src.to_owned(),
     );
     let highlight_result = {
+        let start_pos = fm.start_pos;
         let lexer = lexer::StringReader::new(&sess, fm, None);
-        let mut classifier = Classifier::new(lexer, sess.source_map());
+        let mut classifier = Classifier::new(lexer, sess.source_map(), start_pos, links);
 
         let mut highlighted_source = vec![];
         if classifier.write_source(&mut highlighted_source).is_err() {
@@ -76,11 +95,18 @@ struct Classifier<'a> {
     lexer: lexer::StringReader<'a>,
     peek_token: Option<Token>,
     source_map: &'a SourceMap,
+    /// Where the source being highlighted starts in `source_map`.
+    start_pos: BytePos,
+    /// The URLs the tokens at these byte ranges of the source link to.
+    links: &'a FxHashMap<(u32, u32), String>,
 
     // State of the classifier.
     in_attribute: bool,
     in_macro: bool,
     in_macro_nonterminal: bool,
+    /// Whether a `:` after a metavariable like `$x` was seen, so that the next identifier is its
+    /// fragment specifier, like the `expr` of `$x:expr`.
+    in_fragment_specifier: bool,
 }
 
 /// How a span of text is classified. Mostly corresponds to token kinds.
@@ -97,6 +123,7 @@ enum Class {
     Op,
     Macro,
     MacroNonTerminal,
+    MacroFragmentSpecifier,
     String,
     Number,
     Bool,
@@ -171,14 +198,22 @@ impl From<io::Error> for HighlightError {
 }
 
 impl<'a> Classifier<'a> {
-    fn new(lexer: lexer::StringReader<'a>, source_map: &'a SourceMap) -> Classifier<'a> {
+    fn new(
+        lexer: lexer::StringReader<'a>,
+        source_map: &'a SourceMap,
+        start_pos: BytePos,
+        links: &'a FxHashMap<(u32, u32), String>,
+    ) -> Classifier<'a> {
         Classifier {
             lexer,
             peek_token: None,
             source_map,
+            start_pos,
+            links,
             in_attribute: false,
             in_macro: false,
             in_macro_nonterminal: false,
+            in_fragment_specifier: false,
         }
     }
 
@@ -350,8 +385,12 @@ impl<'a> Classifier<'a> {
                     _ if token.is_reserved_ident() => Class::KeyWord,
 
                     _ => {
-                        if self.in_macro_nonterminal {
+                        if self.in_fragment_specifier {
+                            self.in_fragment_specifier = false;
+                            Class::MacroFragmentSpecifier
+                        } else if self.in_macro_nonterminal {
                             self.in_macro_nonterminal = false;
+                            self.in_fragment_specifier = self.peek()? == &token::Colon;
                             Class::MacroNonTerminal
                         } else if self.peek()? == &token::Not {
                             self.in_macro = true;
@@ -371,11 +410,27 @@ impl<'a> Classifier<'a> {
 
         // Anything that didn't return above is the simple case where we the
         // class just spans a single token, so we can use the `string` method.
-        out.string(Escape(&self.snip(token.span)), klass)?;
+        match self.link(token.span) {
+            Some(href) => {
+                let text = format!("<a href=\"{}\">{}</a>",
+                                   Escape(href), Escape(&self.snip(token.span)));
+                out.string(text, klass)?;
+            }
+            None => out.string(Escape(&self.snip(token.span)), klass)?,
+        }
 
         Ok(())
     }
 
+    // Helper function to get the URL the token at `sp` links to, if any.
+    fn link(&self, sp: Span) -> Option<&'a str> {
+        if self.links.is_empty() {
+            return None;
+        }
+        let range = ((sp.lo() - self.start_pos).0, (sp.hi() - self.start_pos).0);
+        self.links.get(&range).map(|href| &**href)
+    }
+
     // Helper function to get a snippet from the source_map.
     fn snip(&self, sp: Span) -> String {
         self.source_map.span_to_snippet(sp).unwrap()
@@ -396,6 +451,7 @@ impl Class {
             Class::Op => "op",
             Class::Macro => "macro",
             Class::MacroNonTerminal => "macro-nonterminal",
+            Class::MacroFragmentSpecifier => "macro-fragment",
             Class::String => "string",
             Class::Number => "number",
             Class::Bool => "bool-val",
@@ -155,6 +155,39 @@ impl ItemType {
         }
     }
 
+    /// Returns the item type which `as_str` gives `name` for.
+    pub fn from_name(name: &str) -> Option<ItemType> {
+        match name {
+            "mod"                => Some(ItemType::Module),
+            "externcrate"        => Some(ItemType::ExternCrate),
+            "import"             => Some(ItemType::Import),
+            "struct"             => Some(ItemType::Struct),
+            "union"              => Some(ItemType::Union),
+            "enum"               => Some(ItemType::Enum),
+            "fn"                 => Some(ItemType::Function),
+            "type"               => Some(ItemType::Typedef),
+            "static"             => Some(ItemType::Static),
+            "trait"              => Some(ItemType::Trait),
+            "impl"               => Some(ItemType::Impl),
+            "tymethod"           => Some(ItemType::TyMethod),
+            "method"             => Some(ItemType::Method),
+            "structfield"        => Some(ItemType::StructField),
+            "variant"            => Some(ItemType::Variant),
+            "macro"              => Some(ItemType::Macro),
+            "primitive"          => Some(ItemType::Primitive),
+            "associatedtype"     => Some(ItemType::AssocType),
+            "constant"           => Some(ItemType::Constant),
+            "associatedconstant" => Some(ItemType::AssocConst),
+            "foreigntype"        => Some(ItemType::ForeignType),
+            "keyword"            => Some(ItemType::Keyword),
+            "opaque"             => Some(ItemType::OpaqueTy),
+            "attr"               => Some(ItemType::ProcAttribute),
+            "derive"             => Some(ItemType::ProcDerive),
+            "traitalias"         => Some(ItemType::TraitAlias),
+            _ => None,
+        }
+    }
+
     pub fn name_space(&self) -> &'static str {
         match *self {
             ItemType::Struct |
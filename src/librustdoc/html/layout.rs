@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
+use std::io;
 use std::path::PathBuf;
 
+use serde::Serialize;
+
 use crate::externalfiles::ExternalHtml;
 use crate::html::escape::Escape;
 use crate::html::render::ensure_trailing_slash;
@@ -17,6 +21,38 @@ pub struct Layout {
     /// If false, the `select` element to have search filtering by crates on rendered docs
     /// won't be generated.
     pub generate_search_filter: bool,
+    /// The names the static files are written under.
+    pub static_files: StaticFileNames,
+}
+
+/// The names the static files are written under, by the names the pages know them by, with the
+/// resource suffix. They only differ with `--hash-static-files`, which puts the hash of the
+/// content of a file in its name.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct StaticFileNames(BTreeMap<String, String>);
+
+impl StaticFileNames {
+    pub fn insert(&mut self, file: String, written: String) {
+        self.0.insert(file, written);
+    }
+
+    /// Returns the name the static file `file` is written under.
+    pub fn get<'a>(&'a self, file: &'a str) -> &'a str {
+        self.0.get(file).map_or(file, |written| &**written)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.0.iter()
+    }
+}
+
+/// Returns the name of the static file `file` with the resource suffix `suffix`, which goes
+/// before all of its extensions: `style.min.css` becomes `style-suffix.min.css`.
+pub fn suffixed_file_name(file: &str, suffix: &str) -> String {
+    let mut iter = file.splitn(2, '.');
+    let base = iter.next().unwrap();
+    let ext = iter.next().unwrap();
+    format!("{}{}.{}", base, suffix, ext)
 }
 
 pub struct Page<'a> {
@@ -31,15 +67,22 @@ pub struct Page<'a> {
     pub static_extra_scripts: &'a [&'a str],
 }
 
+/// Writes `page` to `w` as it is rendered, so that only its sidebar and content are kept in
+/// memory at once.
 pub fn render<T: Print, S: Print>(
+    w: &mut dyn io::Write,
     layout: &Layout,
     page: &Page<'_>,
     sidebar: S,
     t: T,
     themes: &[PathBuf],
-) -> String {
+) -> io::Result<()> {
     let static_root_path = page.static_root_path.unwrap_or(page.root_path);
-    format!("<!DOCTYPE html>\
+    let static_file = |file: &str| {
+        let file = suffixed_file_name(file, page.resource_suffix);
+        format!("{}{}", static_root_path, layout.static_files.get(&file))
+    };
+    write!(w, "<!DOCTYPE html>\
 <html lang=\"en\">\
 <head>\
     <meta charset=\"utf-8\">\
@@ -48,20 +91,20 @@ pub fn render<T: Print, S: Print>(
     <meta name=\"description\" content=\"{description}\">\
     <meta name=\"keywords\" content=\"{keywords}\">\
     <title>{title}</title>\
-    <link rel=\"stylesheet\" type=\"text/css\" href=\"{static_root_path}normalize{suffix}.css\">\
-    <link rel=\"stylesheet\" type=\"text/css\" href=\"{static_root_path}rustdoc{suffix}.css\" \
+    <link rel=\"stylesheet\" type=\"text/css\" href=\"{normalize_css}\">\
+    <link rel=\"stylesheet\" type=\"text/css\" href=\"{rustdoc_css}\" \
           id=\"mainThemeStyle\">\
     {themes}\
-    <link rel=\"stylesheet\" type=\"text/css\" href=\"{static_root_path}dark{suffix}.css\">\
-    <link rel=\"stylesheet\" type=\"text/css\" href=\"{static_root_path}light{suffix}.css\" \
+    <link rel=\"stylesheet\" type=\"text/css\" href=\"{dark_css}\">\
+    <link rel=\"stylesheet\" type=\"text/css\" href=\"{light_css}\" \
           id=\"themeStyle\">\
-    <script src=\"{static_root_path}storage{suffix}.js\"></script>\
-    <noscript><link rel=\"stylesheet\" href=\"{static_root_path}noscript{suffix}.css\"></noscript>\
+    <script src=\"{storage_js}\"></script>\
+    <noscript><link rel=\"stylesheet\" href=\"{noscript_css}\"></noscript>\
     {css_extension}\
     {favicon}\
     {in_header}\
     <style type=\"text/css\">\
-    #crate-search{{background-image:url(\"{static_root_path}down-arrow{suffix}.svg\");}}\
+    #crate-search{{background-image:url(\"{down_arrow_svg}\");}}\
     </style>\
 </head>\
 <body class=\"rustdoc {css_class}\">\
@@ -79,13 +122,13 @@ pub fn render<T: Print, S: Print>(
     </nav>\
     <div class=\"theme-picker\">\
         <button id=\"theme-picker\" aria-label=\"Pick another theme!\">\
-            <img src=\"{static_root_path}brush{suffix}.svg\" \
+            <img src=\"{brush_svg}\" \
                  width=\"18\" \
                  alt=\"Pick another theme!\">\
         </button>\
         <div id=\"theme-choices\"></div>\
     </div>\
-    <script src=\"{static_root_path}theme{suffix}.js\"></script>\
+    <script src=\"{theme_js}\"></script>\
     <nav class=\"sub\">\
         <form class=\"search-form\">\
             <div class=\"search-container\">\
@@ -98,7 +141,7 @@ pub fn render<T: Print, S: Print>(
                            type=\"search\">\
                 </div>\
                 <a id=\"settings-menu\" href=\"{root_path}settings.html\">\
-                    <img src=\"{static_root_path}wheel{suffix}.svg\" \
+                    <img src=\"{wheel_svg}\" \
                          width=\"18\" \
                          alt=\"Change settings\">\
                 </a>\
@@ -114,7 +157,7 @@ pub fn render<T: Print, S: Print>(
         window.currentCrate = \"{krate}\";\
     </script>\
     <script src=\"{root_path}aliases{suffix}.js\"></script>\
-    <script src=\"{static_root_path}main{suffix}.js\"></script>\
+    <script src=\"{main_js}\"></script>\
     {static_extra_scripts}\
     {extra_scripts}\
     <script defer src=\"{root_path}search-index{suffix}.js\"></script>\
@@ -123,14 +166,23 @@ pub fn render<T: Print, S: Print>(
     css_extension = if layout.css_file_extension.is_some() {
         format!("<link rel=\"stylesheet\" \
                        type=\"text/css\" \
-                       href=\"{static_root_path}theme{suffix}.css\">",
-                static_root_path = static_root_path,
-                suffix=page.resource_suffix)
+                       href=\"{}\">",
+                static_file("theme.css"))
     } else {
         String::new()
     },
     content   = Buffer::html().to_display(t),
-    static_root_path = static_root_path,
+    normalize_css = static_file("normalize.css"),
+    rustdoc_css = static_file("rustdoc.css"),
+    dark_css = static_file("dark.css"),
+    light_css = static_file("light.css"),
+    storage_js = static_file("storage.js"),
+    noscript_css = static_file("noscript.css"),
+    down_arrow_svg = static_file("down-arrow.svg"),
+    brush_svg = static_file("brush.svg"),
+    theme_js = static_file("theme.js"),
+    wheel_svg = static_file("wheel.svg"),
+    main_js = static_file("main.js"),
     root_path = page.root_path,
     css_class = page.css_class,
     logo      = {
@@ -139,10 +191,9 @@ pub fn render<T: Print, S: Print>(
         if layout.logo.is_empty() {
             format!("<a href='{path}index.html'>\
                      <div class='logo-container'>\
-                     <img src='{static_root_path}rust-logo{suffix}.png' alt='logo'></div></a>",
+                     <img src='{logo}' alt='logo'></div></a>",
                     path=p,
-                    static_root_path=static_root_path,
-                    suffix=page.resource_suffix)
+                    logo=static_file("rust-logo.png"))
         } else {
             format!("<a href='{}index.html'>\
                      <div class='logo-container'><img src='{}' alt='logo'></div></a>",
@@ -154,9 +205,7 @@ pub fn render<T: Print, S: Print>(
     description = page.description,
     keywords = page.keywords,
     favicon   = if layout.favicon.is_empty() {
-        format!(r#"<link rel="shortcut icon" href="{static_root_path}favicon{suffix}.ico">"#,
-                static_root_path=static_root_path,
-                suffix=page.resource_suffix)
+        format!(r#"<link rel="shortcut icon" href="{}">"#, static_file("favicon.ico"))
     } else {
         format!(r#"<link rel="shortcut icon" href="{}">"#, layout.favicon)
     },
@@ -168,17 +217,14 @@ pub fn render<T: Print, S: Print>(
     themes = themes.iter()
                    .filter_map(|t| t.file_stem())
                    .filter_map(|t| t.to_str())
-                   .map(|t| format!(r#"<link rel="stylesheet" type="text/css" href="{}.css">"#,
-                                    Escape(&format!("{}{}{}",
-                                                    static_root_path,
-                                                    t,
-                                                    page.resource_suffix))))
+                   .map(|t| format!(r#"<link rel="stylesheet" type="text/css" href="{}">"#,
+                                    Escape(&static_file(&format!("{}.css", t)))))
                    .collect::<String>(),
     suffix=page.resource_suffix,
     static_extra_scripts=page.static_extra_scripts.iter().map(|e| {
-        format!("<script src=\"{static_root_path}{extra_script}.js\"></script>",
+        format!("<script src=\"{static_root_path}{extra_script}\"></script>",
                 static_root_path=static_root_path,
-                extra_script=e)
+                extra_script=layout.static_files.get(&format!("{}.js", e)))
     }).collect::<String>(),
     extra_scripts=page.extra_scripts.iter().map(|e| {
         format!("<script src=\"{root_path}{extra_script}.js\"></script>",
@@ -12,7 +12,7 @@
 //!
 //! let s = "My *markdown* _text_";
 //! let mut id_map = IdMap::new();
-//! let md = Markdown(s, &[], &mut id_map, ErrorCodes::Yes, Edition::Edition2015, &None);
+//! let md = Markdown(s, &[], &mut id_map, ErrorCodes::Yes, Edition::Edition2015, &None, None);
 //! let html = md.to_string();
 //! // ... something using html
 //! ```
@@ -33,7 +33,7 @@ use crate::html::toc::TocBuilder;
 use crate::html::highlight;
 use crate::test;
 
-use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+use pulldown_cmark::{html, CowStr, Event, LinkType, Options, Parser, Tag};
 
 #[cfg(test)]
 mod tests;
@@ -55,6 +55,9 @@ pub struct Markdown<'a>(
     /// Default edition to use when parsing doctests (to add a `fn main`).
     pub Edition,
     pub &'a Option<Playground>,
+    /// The prefix of the hidden lines of code blocks, given with
+    /// `#![doc(hidden_line_prefix = "...")]`, if it is not `#`.
+    pub Option<&'a str>,
 );
 /// A tuple struct like `Markdown` that renders the markdown with a table of contents.
 pub struct MarkdownWithToc<'a>(
@@ -126,14 +129,19 @@ impl<'a> Line<'a> {
 // is done in the single # case. This inconsistency seems okay, if non-ideal. In
 // order to fix it we'd have to iterate to find the first non-# character, and
 // then reallocate to remove it; which would make us return a String.
-fn map_line(s: &str) -> Line<'_> {
+//
+// The prefix of hidden lines is `#` unless another one is given, in which case it replaces `#`
+// in all of the above.
+fn map_line<'a>(s: &'a str, hidden_prefix: &str) -> Line<'a> {
     let trimmed = s.trim();
-    if trimmed.starts_with("##") {
-        Line::Shown(Cow::Owned(s.replacen("##", "#", 1)))
-    } else if trimmed.starts_with("# ") {
+    let escaped = format!("{0}{0}", hidden_prefix);
+    if trimmed.starts_with(&escaped) {
+        Line::Shown(Cow::Owned(s.replacen(&escaped, hidden_prefix, 1)))
+    } else if trimmed.starts_with(hidden_prefix) &&
+              trimmed[hidden_prefix.len()..].starts_with(' ') {
         // # text
-        Line::Hidden(&trimmed[2..])
-    } else if trimmed == "#" {
+        Line::Hidden(&trimmed[hidden_prefix.len() + 1..])
+    } else if trimmed == hidden_prefix {
         // We cannot handle '#text' because it could be #[attr].
         Line::Hidden("")
     } else {
@@ -141,9 +149,30 @@ fn map_line(s: &str) -> Line<'_> {
     }
 }
 
+/// Turns the hidden lines of a code block into the code they hide, like `map_line` does, but
+/// blanks out the markers instead of removing them so that every byte of code stays at the same
+/// offset.
+crate fn unhide_lines(code: &str, hidden_prefix: &str) -> String {
+    let escaped = format!("{0}{0}", hidden_prefix);
+    let blank = " ".repeat(hidden_prefix.len());
+    code.split('\n').map(|line| {
+        let indent = line.len() - line.trim_start().len();
+        let (prefix, rest) = line.split_at(indent);
+        if rest.starts_with(&escaped) || rest.trim_end() == hidden_prefix {
+            format!("{}{}{}", prefix, blank, &rest[hidden_prefix.len()..])
+        } else if rest.starts_with(hidden_prefix) && rest[hidden_prefix.len()..].starts_with(' ') {
+            format!("{}{} {}", prefix, blank, &rest[hidden_prefix.len() + 1..])
+        } else {
+            line.to_string()
+        }
+    }).collect::<Vec<_>>().join("\n")
+}
+
 /// Convert chars from a title for an id.
 ///
-/// "Hello, world!" -> "hello-world"
+/// Letters, digits, `-` and `_` are kept, with ASCII letters lowercased, ASCII whitespace becomes
+/// `-` and anything else is dropped: "Hello, world!" -> "hello-world". The ids are then made
+/// unique on the page by `IdMap::derive_heading`.
 fn slugify(c: char) -> Option<char> {
     if c.is_alphanumeric() || c == '-' || c == '_' {
         if c.is_ascii() {
@@ -172,6 +201,8 @@ struct CodeBlocks<'p, 'a, I: Iterator<Item = Event<'a>>> {
     // Information about the playground if a URL has been specified, containing an
     // optional crate name and the URL.
     playground: &'p Option<Playground>,
+    /// The prefix of hidden lines given by the crate, if it is not `#`.
+    hidden_prefix: Option<&'p str>,
 }
 
 impl<'p, 'a, I: Iterator<Item = Event<'a>>> CodeBlocks<'p, 'a, I> {
@@ -180,12 +211,14 @@ impl<'p, 'a, I: Iterator<Item = Event<'a>>> CodeBlocks<'p, 'a, I> {
         error_codes: ErrorCodes,
         edition: Edition,
         playground: &'p Option<Playground>,
+        hidden_prefix: Option<&'p str>,
     ) -> Self {
         CodeBlocks {
             inner: iter,
             check_error_codes: error_codes,
             edition,
             playground,
+            hidden_prefix,
         }
     }
 }
@@ -198,11 +231,13 @@ impl<'a, I: Iterator<Item = Event<'a>>> Iterator for CodeBlocks<'_, 'a, I> {
         let compile_fail;
         let ignore;
         let edition;
+        let hidden_prefix;
         if let Some(Event::Start(Tag::CodeBlock(lang))) = event {
             let parse_result = LangString::parse(&lang, self.check_error_codes, false);
             if !parse_result.rust {
                 return Some(Event::Start(Tag::CodeBlock(lang)));
             }
+            hidden_prefix = parse_result.hidden_prefix(self.hidden_prefix).to_owned();
             compile_fail = parse_result.compile_fail;
             ignore = parse_result.ignore;
             edition = parse_result.edition;
@@ -223,7 +258,7 @@ impl<'a, I: Iterator<Item = Event<'a>>> Iterator for CodeBlocks<'_, 'a, I> {
                 _ => {}
             }
         }
-        let lines = origtext.lines().filter_map(|l| map_line(l).for_html());
+        let lines = origtext.lines().filter_map(|l| map_line(l, &hidden_prefix).for_html());
         let text = lines.collect::<Vec<Cow<'_, str>>>().join("\n");
         // insert newline to clearly separate it from the
         // previous block so we can shorten the html output
@@ -235,7 +270,7 @@ impl<'a, I: Iterator<Item = Event<'a>>> Iterator for CodeBlocks<'_, 'a, I> {
                 return None;
             }
             let test = origtext.lines()
-                .map(|l| map_line(l).for_code())
+                .map(|l| map_line(l, &hidden_prefix).for_code())
                 .collect::<Vec<Cow<'_, str>>>().join("\n");
             let krate = krate.as_ref().map(|s| &**s);
             let (test, _) = test::make_test(&test, krate, false,
@@ -380,7 +415,7 @@ impl<'a, 'b, 'ids, I: Iterator<Item = Event<'a>>> Iterator for HeadingLinks<'a,
                 }
                 self.buf.push_back(event);
             }
-            let id = self.id_map.derive(id);
+            let (id, alias) = self.id_map.derive_heading(id);
 
             if let Some(ref mut builder) = self.toc {
                 let mut html_header = String::new();
@@ -391,9 +426,11 @@ impl<'a, 'b, 'ids, I: Iterator<Item = Event<'a>>> Iterator for HeadingLinks<'a,
 
             self.buf.push_back(Event::InlineHtml(format!("</a></h{}>", level).into()));
 
+            let alias = alias.map(|alias| format!(" id=\"{}\"", alias)).unwrap_or_default();
             let start_tags = format!("<h{level} id=\"{id}\" class=\"section-header\">\
-                                      <a href=\"#{id}\">",
+                                      <a{alias} href=\"#{id}\">",
                                      id = id,
+                                     alias = alias,
                                      level = level);
             return Some(Event::InlineHtml(start_tags.into()));
         }
@@ -576,9 +613,10 @@ pub fn find_testable_code<T: test::Tester>(doc: &str, tests: &mut T, error_codes
                     test_s.push_str(&s);
                 }
 
+                let hidden_prefix = block_info.hidden_prefix(tests.hidden_line_prefix());
                 let text = test_s
                     .lines()
-                    .map(|l| map_line(l).for_code())
+                    .map(|l| map_line(l, hidden_prefix).for_code())
                     .collect::<Vec<Cow<'_, str>>>()
                     .join("\n");
                 nb_lines += doc[prev_offset..offset].lines().count();
@@ -607,6 +645,8 @@ pub fn find_testable_code<T: test::Tester>(doc: &str, tests: &mut T, error_codes
 pub struct LangString {
     original: String,
     pub should_panic: bool,
+    /// The message given by `should_panic="..."`, which the test has to panic with.
+    pub expected_panic: Option<String>,
     pub no_run: bool,
     pub ignore: Ignore,
     pub rust: bool,
@@ -614,7 +654,11 @@ pub struct LangString {
     pub compile_fail: bool,
     pub error_codes: Vec<String>,
     pub allow_fail: bool,
-    pub edition: Option<Edition>
+    pub edition: Option<Edition>,
+    /// The edition asked for by an `editionXXXX` attribute, when it is not one the compiler knows.
+    pub unknown_edition: Option<String>,
+    /// The prefix of hidden lines given by `hidden_line_prefix="..."`, to use instead of `#`.
+    pub hidden_line_prefix: Option<String>,
 }
 
 #[derive(Eq, PartialEq, Clone, Debug)]
@@ -629,6 +673,7 @@ impl LangString {
         LangString {
             original: String::new(),
             should_panic: false,
+            expected_panic: None,
             no_run: false,
             ignore: Ignore::None,
             rust: true,  // NB This used to be `notrust = false`
@@ -637,10 +682,17 @@ impl LangString {
             error_codes: Vec::new(),
             allow_fail: false,
             edition: None,
+            unknown_edition: None,
+            hidden_line_prefix: None,
         }
     }
 
-    fn parse(
+    /// Returns the prefix of the hidden lines of the code block, given the one of the crate.
+    crate fn hidden_prefix<'a>(&'a self, crate_prefix: Option<&'a str>) -> &'a str {
+        self.hidden_line_prefix.as_ref().map(|prefix| &**prefix).or(crate_prefix).unwrap_or("#")
+    }
+
+    crate fn parse(
         string: &str,
         allow_error_code_check: ErrorCodes,
         enable_per_target_ignores: bool
@@ -652,6 +704,13 @@ impl LangString {
         let mut ignores = vec![];
 
         data.original = string.to_owned();
+        // The values of `should_panic="..."` and `hidden_line_prefix="..."` are taken out before
+        // splitting the tokens, since they may contain any character.
+        let mut string = string.to_owned();
+        data.expected_panic = take_quoted_value(&mut string, "should_panic");
+        data.hidden_line_prefix = take_quoted_value(&mut string, "hidden_line_prefix")
+            .map(|prefix| prefix.trim().to_owned())
+            .filter(|prefix| !prefix.is_empty());
         let tokens = string.split(|c: char|
             !(c == '_' || c == '-' || c.is_alphanumeric())
         );
@@ -670,6 +729,7 @@ impl LangString {
                     seen_rust_tags = !seen_other_tags;
                 }
                 "allow_fail" => { data.allow_fail = true; seen_rust_tags = !seen_other_tags; }
+                "hidden_line_prefix" => {}
                 "rust" => { data.rust = true; seen_rust_tags = true; }
                 "test_harness" => {
                     data.test_harness = true;
@@ -681,7 +741,10 @@ impl LangString {
                     data.no_run = true;
                 }
                 x if x.starts_with("edition") => {
-                    data.edition = x[7..].parse::<Edition>().ok();
+                    match x[7..].parse::<Edition>() {
+                        Ok(edition) => data.edition = Some(edition),
+                        Err(()) => data.unknown_edition = Some(x[7..].to_owned()),
+                    }
                 }
                 x if allow_error_code_check && x.starts_with("E") && x.len() == 5 => {
                     if x[1..].parse::<u32>().is_ok() {
@@ -705,9 +768,20 @@ impl LangString {
     }
 }
 
+/// Takes `key="value"` out of the lang string `string`, leaving `key` alone in its place, and
+/// returns the value.
+fn take_quoted_value(string: &mut String, key: &str) -> Option<String> {
+    let start = string.find(&format!("{}=\"", key))?;
+    let value_start = start + key.len() + 2;
+    let value_end = value_start + string[value_start..].find('"')?;
+    let value = string[value_start..value_end].to_owned();
+    string.replace_range(start..value_end + 1, key);
+    Some(value)
+}
+
 impl Markdown<'_> {
     pub fn to_string(self) -> String {
-        let Markdown(md, links, mut ids, codes, edition, playground) = self;
+        let Markdown(md, links, mut ids, codes, edition, playground, hidden_prefix) = self;
 
         // This is actually common enough to special-case
         if md.is_empty() { return String::new(); }
@@ -725,7 +799,7 @@ impl Markdown<'_> {
 
         let p = HeadingLinks::new(p, None, &mut ids);
         let p = LinkReplacer::new(p, links);
-        let p = CodeBlocks::new(p, codes, edition, playground);
+        let p = CodeBlocks::new(p, codes, edition, playground, hidden_prefix);
         let p = Footnotes::new(p);
         html::push_html(&mut s, p);
 
@@ -745,7 +819,7 @@ impl MarkdownWithToc<'_> {
 
         {
             let p = HeadingLinks::new(p, Some(&mut toc), &mut ids);
-            let p = CodeBlocks::new(p, codes, edition, playground);
+            let p = CodeBlocks::new(p, codes, edition, playground, None);
             let p = Footnotes::new(p);
             html::push_html(&mut s, p);
         }
@@ -771,7 +845,7 @@ impl MarkdownHtml<'_> {
         let mut s = String::with_capacity(md.len() * 3 / 2);
 
         let p = HeadingLinks::new(p, None, &mut ids);
-        let p = CodeBlocks::new(p, codes, edition, playground);
+        let p = CodeBlocks::new(p, codes, edition, playground, None);
         let p = Footnotes::new(p);
         html::push_html(&mut s, p);
 
@@ -903,6 +977,144 @@ pub fn markdown_links(md: &str) -> Vec<(String, Option<Range<usize>>)> {
     links
 }
 
+/// Returns the ranges of the plain text in the markdown, leaving out code, HTML and the text of
+/// links. Adjacent runs of text are merged into one range.
+crate fn plain_text_ranges(md: &str) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = vec![];
+
+    if md.is_empty() {
+        return ranges;
+    }
+
+    // Shortcut links like `[Vec]` are treated as links even when they are not defined, as they
+    // are resolved as intra-doc links.
+    let push = |_: &str, _: &str| Some((String::new(), String::new()));
+    let p = Parser::new_with_broken_link_callback(md, opts(), Some(&push));
+
+    let md_start = md.as_ptr() as usize;
+    let mut nesting = 0;
+    for event in p {
+        match event {
+            Event::Start(Tag::Link(..)) |
+            Event::Start(Tag::Image(..)) |
+            Event::Start(Tag::CodeBlock(..)) => nesting += 1,
+            Event::End(Tag::Link(..)) |
+            Event::End(Tag::Image(..)) |
+            Event::End(Tag::CodeBlock(..)) => nesting -= 1,
+            Event::Text(CowStr::Borrowed(s)) if nesting == 0 => {
+                let s_start = s.as_ptr() as usize;
+                if s_start < md_start || s_start + s.len() > md_start + md.len() {
+                    continue;
+                }
+                let start = s_start - md_start;
+                let end = start + s.len();
+                match ranges.last_mut() {
+                    Some(last) if last.end == start => last.end = end,
+                    _ => ranges.push(start..end),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    ranges
+}
+
+/// An inline link with a text made only of plain text and inline code, like
+/// ``[`Vec`](std::vec::Vec)``.
+#[derive(Debug, PartialEq)]
+crate struct InlineLink {
+    /// The text of the link, with the backticks around inline code left out.
+    crate text: String,
+    crate dest: String,
+    /// The range of the whole link in the markdown.
+    crate range: Range<usize>,
+    /// The range of the destination of the link in the markdown, along with the parentheses
+    /// around it. Removing it turns the link into a shortcut link.
+    crate dest_range: Range<usize>,
+}
+
+/// Returns the inline links of the markdown whose text is made only of plain text and inline
+/// code, and which have no title.
+crate fn inline_links(md: &str) -> Vec<InlineLink> {
+    let mut links = vec![];
+
+    if md.is_empty() {
+        return links;
+    }
+
+    let md_start = md.as_ptr() as usize;
+    let mut current: Option<(String, String, usize)> = None;
+    for event in Parser::new_ext(md, opts()) {
+        match event {
+            Event::Start(Tag::Link(LinkType::Inline, CowStr::Borrowed(dest), ref title))
+                if title.is_empty() =>
+            {
+                let dest_start = dest.as_ptr() as usize;
+                if dest_start < md_start || dest_start + dest.len() > md_start + md.len() {
+                    continue;
+                }
+                current = Some((String::new(), dest.to_owned(), dest_start - md_start));
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some((ref mut link_text, ..)) = current {
+                    link_text.push_str(&text);
+                }
+            }
+            Event::End(Tag::Link(..)) => {
+                let (text, dest, dest_start) = match current.take() {
+                    Some(link) => link,
+                    None => continue,
+                };
+                let open = md[..dest_start].rfind('(')
+                    .filter(|&open| md[..open].ends_with(']'));
+                let close = md[dest_start + dest.len()..].find(')')
+                    .map(|close| dest_start + dest.len() + close + 1);
+                if let (Some(open), Some(close)) = (open, close) {
+                    if let Some(start) = md[..open - 1].rfind('[') {
+                        links.push(InlineLink {
+                            text,
+                            dest,
+                            range: start..close,
+                            dest_range: open..close,
+                        });
+                    }
+                }
+            }
+            // Links with emphasis, images or anything else in their text are left out.
+            _ => current = None,
+        }
+    }
+
+    links
+}
+
+/// Returns the ids which the headings of the markdown get when it is rendered with `ids`, in the
+/// order they appear, each followed by its alias if it has one.
+crate fn heading_ids(md: &str, ids: &mut IdMap) -> Vec<String> {
+    let mut headings = vec![];
+    let mut current: Option<String> = None;
+    for event in Parser::new_ext(md, opts()) {
+        match event {
+            Event::Start(Tag::Header(_)) => current = Some(String::new()),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(ref mut id) = current {
+                    id.extend(text.chars().filter_map(slugify));
+                }
+            }
+            Event::End(Tag::Header(_)) => {
+                if let Some(slug) = current.take() {
+                    let (id, alias) = ids.derive_heading(slug);
+                    headings.push(id);
+                    headings.extend(alias);
+                }
+            }
+            _ => {}
+        }
+    }
+    headings
+}
+
 #[derive(Debug)]
 crate struct RustCodeBlock {
     /// The range in the markdown that the code block occupies. Note that this includes the fences
@@ -1015,6 +1227,8 @@ crate fn rust_code_blocks(md: &str) -> Vec<RustCodeBlock> {
 #[derive(Clone, Default, Debug)]
 pub struct IdMap {
     map: FxHashMap<String, usize>,
+    /// Prepended to the ids of the headings, see `set_heading_prefix`.
+    heading_prefix: Option<String>,
 }
 
 impl IdMap {
@@ -1030,6 +1244,7 @@ impl IdMap {
 
     pub fn reset(&mut self) {
         self.map = FxHashMap::default();
+        self.heading_prefix = None;
     }
 
     pub fn derive(&mut self, candidate: String) -> String {
@@ -1045,4 +1260,29 @@ impl IdMap {
         self.map.insert(id.clone(), 1);
         id
     }
+
+    /// Makes the ids of the headings rendered until the next call start with `prefix` and a dot,
+    /// so that the headings of the documentation of a member don't take the ids of the ones of
+    /// its parent or of the other members.
+    pub fn set_heading_prefix(&mut self, prefix: Option<String>) {
+        self.heading_prefix = prefix;
+    }
+
+    /// Returns the id of a heading whose text gives `slug`. Headings with the same text get `-1`,
+    /// `-2`, etc. appended in the order they are rendered, and so do the ones taking the id of a
+    /// section of the page.
+    ///
+    /// Headings with a prefix also get an alias: the id they had before the prefixes were added,
+    /// like `examples-1`, so that the links which were written to them keep working.
+    pub fn derive_heading(&mut self, slug: String) -> (String, Option<String>) {
+        let slug = if slug.is_empty() { "section".to_string() } else { slug };
+        match self.heading_prefix {
+            Some(ref prefix) => {
+                let candidate = format!("{}.{}", prefix, slug);
+                let id = self.derive(candidate);
+                (id, Some(self.derive(slug)))
+            }
+            None => (self.derive(slug), None),
+        }
+    }
 }
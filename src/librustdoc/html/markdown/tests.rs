@@ -1,5 +1,5 @@
-use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap, Ignore};
-use super::plain_summary_line;
+use super::{ErrorCodes, LangString, Markdown, MarkdownHtml, IdMap, Ignore, InlineLink};
+use super::{heading_ids, inline_links, plain_summary_line, plain_text_ranges, unhide_lines};
 use std::cell::RefCell;
 use syntax::edition::{Edition, DEFAULT_EDITION};
 
@@ -40,6 +40,9 @@ fn test_lang_string_parse() {
             original: s.to_owned(),
             allow_fail,
             edition,
+            unknown_edition: None,
+            expected_panic: None,
+            hidden_line_prefix: None,
         })
     }
     let ignore_foo = Ignore::Some(vec!("foo".to_string()));
@@ -69,6 +72,16 @@ fn test_lang_string_parse() {
     t("text,no_run",           false,         true,    Ignore::None,   false, false, false, false, v(), None);
     t("edition2015",           false,         false,   Ignore::None,   true,  false, false, false, v(), Some(Edition::Edition2015));
     t("edition2018",           false,         false,   Ignore::None,   true,  false, false, false, v(), Some(Edition::Edition2018));
+    t("rust,edition2015",      false,         false,   Ignore::None,   true,  false, false, false, v(), Some(Edition::Edition2015));
+
+    let parsed = LangString::parse("edition2021", ErrorCodes::Yes, true);
+    assert_eq!(parsed.edition, None);
+    assert_eq!(parsed.unknown_edition, Some("2021".to_owned()));
+
+    let parsed = LangString::parse("should_panic=\"out of bounds, sorry\",no_run", ErrorCodes::Yes,
+                                   true);
+    assert!(parsed.should_panic && parsed.no_run && parsed.rust);
+    assert_eq!(parsed.expected_panic, Some("out of bounds, sorry".to_owned()));
 }
 
 #[test]
@@ -76,7 +89,7 @@ fn test_header() {
     fn t(input: &str, expect: &str) {
         let mut map = IdMap::new();
         let output = Markdown(
-            input, &[], &mut map, ErrorCodes::Yes, DEFAULT_EDITION, &None).to_string();
+            input, &[], &mut map, ErrorCodes::Yes, DEFAULT_EDITION, &None, None).to_string();
         assert_eq!(output, expect, "original: {}", input);
     }
 
@@ -99,7 +112,7 @@ fn test_header_ids_multiple_blocks() {
     let mut map = IdMap::new();
     fn t(map: &mut IdMap, input: &str, expect: &str) {
         let output = Markdown(input, &[], map,
-                              ErrorCodes::Yes, DEFAULT_EDITION, &None).to_string();
+                              ErrorCodes::Yes, DEFAULT_EDITION, &None, None).to_string();
         assert_eq!(output, expect, "original: {}", input);
     }
 
@@ -145,3 +158,111 @@ fn test_markdown_html_escape() {
     t("Struct<'a, T>", "<p>Struct&lt;'a, T&gt;</p>\n");
     t("Struct<br>", "<p>Struct&lt;br&gt;</p>\n");
 }
+
+#[test]
+fn test_unhide_lines() {
+    fn t(input: &str, expect: &str) {
+        t_prefix(input, "#", expect);
+    }
+    fn t_prefix(input: &str, prefix: &str, expect: &str) {
+        let output = unhide_lines(input, prefix);
+        assert_eq!(output, expect, "original: {}", input);
+        assert_eq!(output.len(), input.len());
+    }
+
+    t("let x = 1;", "let x = 1;");
+    t("# fn main() {\nlet x = 1;\n# }", "  fn main() {\nlet x = 1;\n  }");
+    t("    # use std::mem;", "      use std::mem;");
+    t("#\n#[derive(Debug)]", " \n#[derive(Debug)]");
+    t("## not hidden", " # not hidden");
+    t_prefix("~ fn main() {\n# shown\n~ }", "~", "  fn main() {\n# shown\n  }");
+    t_prefix("~~ not hidden\n//~ fn", "~", " ~ not hidden\n//~ fn");
+}
+
+#[test]
+fn test_hidden_line_prefix() {
+    let input = "```hidden_line_prefix=\"~\"\n~ fn main() {\n// # shown\n~ }\n```";
+    let mut map = IdMap::new();
+    let output = Markdown(input, &[], &mut map, ErrorCodes::Yes, DEFAULT_EDITION, &None, None)
+        .to_string();
+    assert!(output.contains("// # shown") && !output.contains("main"), "output: {}", output);
+
+    let input = "```\n~ fn main() {\n// # shown\n~ }\n```";
+    let mut map = IdMap::new();
+    let output = Markdown(input, &[], &mut map, ErrorCodes::Yes, DEFAULT_EDITION, &None, Some("~"))
+        .to_string();
+    assert!(output.contains("// # shown") && !output.contains("main"), "output: {}", output);
+
+    let parsed = LangString::parse("rust,hidden_line_prefix=\" ~ \"", ErrorCodes::Yes, true);
+    assert!(parsed.rust);
+    assert_eq!(parsed.hidden_prefix(Some("%")), "~");
+    assert_eq!(LangString::parse("rust", ErrorCodes::Yes, true).hidden_prefix(Some("%")), "%");
+}
+
+#[test]
+fn test_plain_text_ranges() {
+    fn t(input: &str, expect: &[&str]) {
+        let output = plain_text_ranges(input).into_iter().map(|r| &input[r]).collect::<Vec<_>>();
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("", &[]);
+    t("see foo::bar", &["see foo::bar"]);
+    t("see `foo::bar` here", &["see ", " here"]);
+    t("see [foo::bar] and [baz](https://example.com)", &["see ", " and "]);
+    t("text\n\n```\nlet x = 1;\n```", &["text"]);
+}
+
+#[test]
+fn test_inline_links() {
+    fn t(input: &str, expect: &[(&str, &str, &str)]) {
+        let output = inline_links(input);
+        let expect = expect.iter().map(|&(text, dest, link)| {
+            let start = input.find(link).unwrap();
+            let end = start + link.len();
+            InlineLink {
+                text: text.to_owned(),
+                dest: dest.to_owned(),
+                range: start..end,
+                dest_range: start + link.find("](").unwrap() + 1..end,
+            }
+        }).collect::<Vec<_>>();
+        assert_eq!(output, expect, "original: {}", input);
+    }
+
+    t("", &[]);
+    t("see [`Vec`](std::vec::Vec)", &[("Vec", "std::vec::Vec", "[`Vec`](std::vec::Vec)")]);
+    t("see [the `Vec` type](Vec) here", &[("the Vec type", "Vec", "[the `Vec` type](Vec)")]);
+    t("see [Vec]", &[]);
+    t("see [Vec](Vec \"title\")", &[]);
+    t("see [*Vec*](Vec)", &[]);
+    t("see [Vec][v]\n\n[v]: std::vec::Vec", &[]);
+}
+
+#[test]
+fn test_heading_ids() {
+    fn t(input: &str, expect: &[&str]) {
+        assert_eq!(heading_ids(input, &mut IdMap::new()), expect, "original: {}", input);
+    }
+
+    t("", &[]);
+    t("# Examples", &["examples"]);
+    t("# Hello, world!\ntext\n## Panics", &["hello-world", "panics"]);
+    t("# The `Foo::bar` method", &["the-foobar-method"]);
+    t("# Examples\n# Examples", &["examples", "examples-1"]);
+    t("# Größe", &["größe"]);
+    t("# ?!\n# ...", &["section", "section-1"]);
+    t("```\n# not a heading\n```", &[]);
+}
+
+#[test]
+fn test_heading_ids_prefix() {
+    let mut map = IdMap::new();
+    map.populate(vec!["fields".to_string()]);
+    assert_eq!(heading_ids("# Fields\n# Examples", &mut map), ["fields-1", "examples"]);
+    map.set_heading_prefix(Some("method.push".to_string()));
+    assert_eq!(heading_ids("# Examples\n# Fields", &mut map),
+               ["method.push.examples", "examples-1", "method.push.fields", "fields-2"]);
+    map.set_heading_prefix(None);
+    assert_eq!(heading_ids("# Examples", &mut map), ["examples-2"]);
+}
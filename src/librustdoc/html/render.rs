@@ -27,29 +27,28 @@
 
 use std::borrow::Cow;
 use std::cell::{Cell, RefCell};
-use std::cmp::Ordering;
+use std::cmp::{self, Ordering};
 use std::collections::{BTreeMap, VecDeque};
 use std::default::Default;
 use std::error;
 
 use std::fmt::{self, Formatter, Write};
-use std::ffi::OsStr;
+use std::hash::Hasher;
 use std::fs::{self, File};
 use std::io::prelude::*;
 use std::io::{self, BufReader};
+use std::mem;
 use std::path::{PathBuf, Path, Component};
 use std::str;
 use std::sync::Arc;
-use std::rc::Rc;
 
 use errors;
 use serde::{Serialize, Serializer};
 use serde::ser::SerializeSeq;
 use syntax::ast;
 use syntax::edition::Edition;
-use syntax::print::pprust;
 use syntax::source_map::FileName;
-use syntax::symbol::{Symbol, sym};
+use syntax::symbol::{Symbol, kw, sym};
 use syntax_pos::hygiene::MacroKind;
 use rustc::hir::def_id::DefId;
 use rustc::middle::privacy::AccessLevels;
@@ -57,10 +56,13 @@ use rustc::middle::stability;
 use rustc::hir;
 use rustc::util::nodemap::{FxHashMap, FxHashSet};
 use rustc_data_structures::flock;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::sync::{Lock, ParallelIterator, par_iter};
 use rustc_feature::UnstableFeatures;
 
 use crate::clean::{self, AttributesExt, Deprecation, GetDefId, SelfTy, Mutability};
-use crate::config::RenderOptions;
+use crate::clean::cfg::{Cfg, CfgNames};
+use crate::config::{EmitType, RenderOptions};
 use crate::docfs::{DocFS, ErrorStorage, PathError};
 use crate::doctree;
 use crate::html::escape::Escape;
@@ -73,16 +75,41 @@ use crate::html::markdown::{self, Markdown, MarkdownHtml, MarkdownSummaryLine, E
 use crate::html::{highlight, layout, static_files};
 use crate::html::sources;
 
+use flate2::Compression;
+use flate2::write::GzEncoder;
 use minifier;
 
 #[cfg(test)]
 mod tests;
 
 mod cache;
-
-use cache::Cache;
+mod facets;
+mod fingerprint;
+mod fragments;
+mod index_entries;
+crate mod merge_docs;
+mod metrics;
+mod minify;
+mod name_table;
+mod print;
+mod ranking;
+mod shared_index;
+mod versions;
+
+use cache::{AliasItem, Cache, ImplementorKind, IndexVisibility, SearchIndex};
+use fingerprint::{FINGERPRINTS_FILE, PageFingerprints};
+use index_entries::{INDEX_ENTRIES_FILE, IndexEntries};
+use fragments::ImplFragments;
+use metrics::ItemMetrics;
+use minify::HtmlMinifier;
+use print::PrintPage;
+use shared_index::SharedSearchIndex;
 crate use cache::ExternalLocation::{self, *};
 
+/// The file the URLs of the items are written to with `--emit id-map`, in the directory of the
+/// crate.
+const ID_MAP_FILE: &str = "id-map.json";
+
 /// A pair of name and its optional document.
 pub type NameDoc = (String, Option<String>);
 
@@ -167,8 +194,15 @@ struct Context {
     /// real location of an item. This is used to allow external links to
     /// publicly reused items to redirect to the right location.
     pub render_redirect_pages: bool,
-    /// The map used to ensure all generated 'id=' attributes are unique.
-    id_map: Rc<RefCell<IdMap>>,
+    /// The map used to ensure all generated 'id=' attributes are unique on the page being
+    /// rendered. Each context has its own, as pages are rendered in parallel.
+    id_map: RefCell<IdMap>,
+    /// The position of the item being rendered in its module, and of the modules above it in
+    /// the ones containing them, to put it in reading order on the printable page.
+    print_position: Vec<usize>,
+    /// The page of the listing of the module being rendered, counting from 1 for `index.html`.
+    /// The listing is only split into several pages with `--module-page-size`.
+    module_page: usize,
     pub shared: Arc<SharedContext>,
     pub cache: Arc<Cache>,
 }
@@ -193,7 +227,7 @@ crate struct SharedContext {
     pub issue_tracker_base_url: Option<String>,
     /// The directories that have already been created in this doc run. Used to reduce the number
     /// of spurious `create_dir_all` calls.
-    pub created_dirs: RefCell<FxHashSet<PathBuf>>,
+    pub created_dirs: Lock<FxHashSet<PathBuf>>,
     /// This flag indicates whether listings of modules (in the side bar and documentation itself)
     /// should be ordered alphabetically or in order of appearance (in the source code).
     pub sort_modules_alphabetically: bool,
@@ -213,27 +247,32 @@ crate struct SharedContext {
     pub edition: Edition,
     pub codes: ErrorCodes,
     playground: Option<markdown::Playground>,
+    /// The prefix of hidden lines in code blocks, given with
+    /// `#![doc(hidden_line_prefix = "...")]`, if it is not `#`.
+    hidden_line_prefix: Option<String>,
+    /// Whether to show `ItemMetrics` on item pages and write them to `item-metrics.json`.
+    pub item_metrics: bool,
+    /// The metrics of every item page rendered so far, keyed by the item's path.
+    collected_metrics: Lock<BTreeMap<String, ItemMetrics>>,
+    /// The content of the item pages rendered so far, with `--generate-print-page`.
+    print_page: Option<PrintPage>,
+    /// The display names the crate gave to configurations, used in portability notes.
+    pub cfg_names: CfgNames,
+    /// Minifies the HTML pages before they are written, with `--minify-output`.
+    html_minifier: Option<HtmlMinifier>,
+    /// The impl blocks written to files shared by the pages, with `--deduplicate-impls`.
+    impl_fragments: Option<ImplFragments>,
+    /// The fingerprints of the pages, with `--incremental-docs`.
+    page_fingerprints: Option<PageFingerprints>,
+    /// The number of items listed on each page of a module, with `--module-page-size`.
+    module_page_size: Option<usize>,
+    /// Whether to write gzip-compressed copies of the files shared by the pages.
+    precompress: bool,
 }
 
-impl Context {
-    fn path(&self, filename: &str) -> PathBuf {
-        // We use splitn vs Path::extension here because we might get a filename
-        // like `style.min.css` and we want to process that into
-        // `style-suffix.min.css`.  Path::extension would just return `css`
-        // which would result in `style.min-suffix.css` which isn't what we
-        // want.
-        let mut iter = filename.splitn(2, '.');
-        let base = iter.next().unwrap();
-        let ext = iter.next().unwrap();
-        let filename = format!(
-            "{}{}.{}",
-            base,
-            self.shared.resource_suffix,
-            ext,
-        );
-        self.dst.join(&filename)
-    }
-}
+/// The extensions of the shared files getting a compressed copy with `--precompress`. The fonts
+/// and the images are compressed already.
+const COMPRESSIBLE_EXTENSIONS: &[&str] = &["js", "css", "svg"];
 
 impl SharedContext {
     crate fn ensure_dir(&self, dst: &Path) -> Result<(), Error> {
@@ -246,6 +285,49 @@ impl SharedContext {
         Ok(())
     }
 
+    /// Writes to `dst` the HTML page that `render` streams, minified with `--minify-output`. The
+    /// page is only collected in memory when it has to be minified.
+    crate fn write_page<P, F>(&self, dst: P, render: F) -> Result<(), Error>
+        where P: AsRef<Path>,
+              F: FnOnce(&mut dyn io::Write) -> io::Result<()>,
+    {
+        match self.html_minifier {
+            Some(ref minifier) => {
+                let mut page = Vec::new();
+                try_err!(render(&mut page), dst.as_ref());
+                let page = minifier.minify(&String::from_utf8_lossy(&page));
+                self.fs.write(dst, page.as_bytes())
+            }
+            None => self.fs.write_with(dst, render),
+        }
+    }
+
+    /// Writes `contents` to the file `dst` shared by the pages, along with a gzip-compressed copy
+    /// of it to `dst.gz` with `--precompress`, for the text formats compressing well.
+    crate fn write_shared_file<P, C>(&self, dst: P, contents: C) -> Result<(), Error>
+        where P: AsRef<Path>,
+              C: AsRef<[u8]>,
+    {
+        let dst = dst.as_ref();
+        if let Some(gz_dst) = compressed_copy(dst) {
+            if self.precompress {
+                let compressed = try_err!(gzip(contents.as_ref()), &gz_dst);
+                self.fs.write(&gz_dst, compressed)?;
+            } else if gz_dst.exists() {
+                // A web server would keep serving the outdated copy instead of the file.
+                try_err!(fs::remove_file(&gz_dst), &gz_dst);
+            }
+        }
+        self.fs.write(dst, contents)
+    }
+
+    /// Returns whether the file `dst` shared by the pages exists, along with a gzip-compressed
+    /// copy of it exactly if `write_shared_file` would write one.
+    crate fn is_shared_file_current(&self, dst: &Path) -> bool {
+        dst.exists() &&
+            compressed_copy(dst).map_or(true, |gz_dst| gz_dst.exists() == self.precompress)
+    }
+
     /// Based on whether the `collapse-docs` pass was run, return either the `doc_value` or the
     /// `collapsed_doc_value` of the given item.
     pub fn maybe_collapsed_doc_value<'a>(&self, item: &'a clean::Item) -> Option<Cow<'a, str>> {
@@ -257,6 +339,25 @@ impl SharedContext {
     }
 }
 
+/// Returns the path of the compressed copy of the shared file `dst`, if it gets one.
+fn compressed_copy(dst: &Path) -> Option<PathBuf> {
+    let compressible = dst.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| COMPRESSIBLE_EXTENSIONS.contains(&extension));
+    if !compressible {
+        return None;
+    }
+    let mut gz_dst = dst.as_os_str().to_owned();
+    gz_dst.push(".gz");
+    Some(PathBuf::from(gz_dst))
+}
+
+fn gzip(contents: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(contents)?;
+    encoder.finish()
+}
+
 /// Metadata about implementations for a type or trait.
 #[derive(Clone, Debug)]
 pub struct Impl {
@@ -287,6 +388,12 @@ pub struct RenderInfo {
     pub deref_trait_did: Option<DefId>,
     pub deref_mut_trait_did: Option<DefId>,
     pub owned_box_did: Option<DefId>,
+    /// The traits marked `#[doc(notable_trait)]` or `#[doc(spotlight)]` that an impl was
+    /// cleaned for.
+    pub notable_traits: FxHashSet<DefId>,
+    /// What the paths in the source files of the crate refer to, only collected with
+    /// `--generate-link-to-definition`.
+    pub span_map: crate::span_map::SpanMap,
 }
 
 // Helper structs for rendering items/sidebars and carrying along contextual
@@ -294,7 +401,7 @@ pub struct RenderInfo {
 
 /// Struct representing one entry in the JS search index. These are all emitted
 /// by hand to a large JS file at the end of cache-creation.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct IndexItem {
     ty: ItemType,
     name: String,
@@ -303,6 +410,16 @@ struct IndexItem {
     parent: Option<DefId>,
     parent_idx: Option<usize>,
     search_type: Option<IndexItemFunctionType>,
+    /// The id of the item on the page of its parent, when it is not derived from its name.
+    anchor: Option<String>,
+    /// Whether the item is deprecated, which ranks it lower.
+    deprecated: bool,
+    /// How the item is hidden from the users of the crate, if it is documented anyway.
+    visibility: Option<IndexVisibility>,
+    /// Whether the item is unstable, which the search can filter out.
+    unstable: bool,
+    /// The configuration the item is available on, which the search can filter by.
+    cfg: Option<Cfg>,
 }
 
 impl Serialize for IndexItem {
@@ -312,20 +429,33 @@ impl Serialize for IndexItem {
     {
         assert_eq!(self.parent.is_some(), self.parent_idx.is_some());
 
-        (
-            self.ty,
-            &self.name,
-            &self.path,
-            &self.desc,
-            self.parent_idx,
-            &self.search_type,
-        )
-            .serialize(serializer)
+        // The anchor is only written for the few items having one.
+        match self.anchor {
+            Some(ref anchor) => (
+                self.ty,
+                &self.name,
+                &self.path,
+                &self.desc,
+                self.parent_idx,
+                &self.search_type,
+                anchor,
+            )
+                .serialize(serializer),
+            None => (
+                self.ty,
+                &self.name,
+                &self.path,
+                &self.desc,
+                self.parent_idx,
+                &self.search_type,
+            )
+                .serialize(serializer),
+        }
     }
 }
 
 /// A type used for the search index.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Type {
     name: Option<String>,
     generics: Option<Vec<String>>,
@@ -350,7 +480,7 @@ impl Serialize for Type {
 }
 
 /// Full type of functions/methods in the search index.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct IndexItemFunctionType {
     inputs: Vec<Type>,
     output: Option<Vec<Type>>,
@@ -398,15 +528,75 @@ pub fn initial_ids() -> Vec<String> {
      "required-methods",
      "provided-methods",
      "implementors",
+     "generic-implementors",
      "synthetic-implementors",
      "implementors-list",
+     "generic-implementors-list",
      "synthetic-implementors-list",
      "methods",
      "deref-methods",
      "implementations",
+     "used-by",
     ].iter().map(|id| (String::from(*id))).collect()
 }
 
+/// The ids of the sections of item pages which are not in `initial_ids`.
+crate const ITEM_SECTION_IDS: &[&str] = &[
+    "fields",
+    "variants",
+    "implementations-list",
+    "synthetic-implementations",
+    "synthetic-implementations-list",
+    "blanket-implementations",
+    "blanket-implementations-list",
+    "foreign-impls",
+];
+
+/// Returns the id of the anchor of the member `name` on the page of its parent, like
+/// `method.push` or `variant.None`.
+crate fn item_anchor(ty: ItemType, name: &str) -> String {
+    format!("{}.{}", ty, name)
+}
+
+/// Returns the id identifying the member `name` in its namespace on the page of its parent,
+/// like `push.v` or `Item.t`.
+crate fn namespace_anchor(ty: ItemType, name: &str) -> String {
+    format!("{}.{}", name, ty.name_space())
+}
+
+/// Returns the id of the anchor of the field `field` of the struct-like variant `variant` on the
+/// page of its enum.
+crate fn variant_field_anchor(variant: &str, field: &str) -> String {
+    format!("{}.{}.field.{}", ItemType::Variant, variant, field)
+}
+
+/// Returns the prefix of the ids of the headings in the documentation of `item` if it is displayed
+/// on the page of its parent, which makes the headings of `Vec::push` get ids like
+/// `method.push.examples`.
+crate fn heading_prefix(item: &clean::Item) -> Option<String> {
+    let ty = match item.inner {
+        clean::TypedefItem(_, true) |
+        clean::OpaqueTyItem(_, true) => ItemType::AssocType,
+        clean::StructFieldItem(..) |
+        clean::VariantItem(..) |
+        clean::TyMethodItem(..) |
+        clean::MethodItem(..) |
+        clean::AssocConstItem(..) |
+        clean::AssocTypeItem(..) => item.type_(),
+        _ => return None,
+    };
+    item.name.map(|name| item_anchor(ty, &name.as_str()))
+}
+
+/// Returns the ids of an item page which are taken before its documentation is rendered, or
+/// which the sections rendered afterwards take without going through the `IdMap`.
+crate fn page_ids() -> IdMap {
+    let mut ids = IdMap::new();
+    ids.populate(initial_ids());
+    ids.populate(ITEM_SECTION_IDS.iter().map(|id| id.to_string()));
+    ids
+}
+
 /// Generates the documentation for `crate` into the directory `dst`
 pub fn run(mut krate: clean::Crate,
            options: RenderOptions,
@@ -428,8 +618,19 @@ pub fn run(mut krate: clean::Crate,
         static_root_path,
         generate_search_filter,
         generate_redirect_pages,
+        item_metrics,
+        generate_print_page,
+        minify_output,
+        deduplicate_impls,
+        incremental_docs,
+        module_page_size,
+        precompress,
+        shard_search_descriptions,
+        emit,
+        versions_root,
         ..
     } = options;
+    let emit_html = emit.contains(&EmitType::Html);
 
     let src_root = match krate.src {
         FileName::Real(ref p) => match p.parent() {
@@ -443,7 +644,7 @@ pub fn run(mut krate: clean::Crate,
     let mut playground = None;
     if let Some(url) = playground_url {
         playground = Some(markdown::Playground {
-            crate_name: Some(krate.name.clone()),
+            crate_name: Some(krate.name.to_string()),
             url,
         });
     }
@@ -451,11 +652,13 @@ pub fn run(mut krate: clean::Crate,
         logo: String::new(),
         favicon: String::new(),
         external_html,
-        krate: krate.name.clone(),
+        krate: krate.name.to_string(),
         css_file_extension: extension_css,
         generate_search_filter,
+        static_files: Default::default(),
     };
     let mut issue_tracker_base_url = None;
+    let mut hidden_line_prefix = None;
     let mut include_sources = true;
 
     // Crawl the crate attributes looking for attributes which control how we're
@@ -471,13 +674,16 @@ pub fn run(mut krate: clean::Crate,
                 }
                 (sym::html_playground_url, Some(s)) => {
                     playground = Some(markdown::Playground {
-                        crate_name: Some(krate.name.clone()),
+                        crate_name: Some(krate.name.to_string()),
                         url: s.to_string(),
                     });
                 }
                 (sym::issue_tracker_base_url, Some(s)) => {
                     issue_tracker_base_url = Some(s.to_string());
                 }
+                (sym::hidden_line_prefix, Some(s)) if !s.as_str().trim().is_empty() => {
+                    hidden_line_prefix = Some(s.as_str().trim().to_string());
+                }
                 (sym::html_no_source, None) if attr.is_word() => {
                     include_sources = false;
                 }
@@ -485,6 +691,19 @@ pub fn run(mut krate: clean::Crate,
             }
         }
     }
+    // The stylesheets which may be tiny enough to be inlined in the pages.
+    let html_minifier = if minify_output {
+        let mut stylesheets = vec![
+            (format!("noscript{}.css", resource_suffix), static_files::NOSCRIPT_CSS.to_string()),
+        ];
+        if let Some(ref css) = layout.css_file_extension {
+            let content = try_err!(fs::read_to_string(css), css);
+            stylesheets.push((format!("theme{}.css", resource_suffix), content));
+        }
+        Some(HtmlMinifier::new(stylesheets))
+    } else {
+        None
+    };
     let mut scx = SharedContext {
         collapsed: krate.collapsed,
         src_root,
@@ -502,105 +721,216 @@ pub fn run(mut krate: clean::Crate,
         edition,
         codes: ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build()),
         playground,
+        hidden_line_prefix,
+        item_metrics,
+        collected_metrics: Default::default(),
+        print_page: if generate_print_page { Some(PrintPage::default()) } else { None },
+        cfg_names: mem::take(&mut krate.cfg_names),
+        html_minifier,
+        impl_fragments: if deduplicate_impls { Some(ImplFragments::default()) } else { None },
+        page_fingerprints: None,
+        module_page_size,
+        precompress,
     };
 
     let dst = output;
     scx.ensure_dir(&dst)?;
-    krate = sources::render(&dst, &mut scx, krate)?;
-    let (new_crate, index, cache) = Cache::from_krate(
+    let entries_file = dst.join(&scx.layout.krate).join(INDEX_ENTRIES_FILE);
+    let index_entries = if incremental_docs {
+        IndexEntries::load(&entries_file)
+    } else {
+        let _ = fs::remove_file(&entries_file);
+        IndexEntries::default()
+    };
+    let (new_crate, index, mut cache) = Cache::from_krate(
         renderinfo,
         &extern_html_root_urls,
         &dst,
         krate,
+        shard_search_descriptions,
+        index_entries,
+        emit.contains(&EmitType::IdMap),
     );
-    krate = new_crate;
+    if incremental_docs {
+        let index_entries = mem::take(&mut cache.index_entries);
+        scx.ensure_dir(&dst.join(&scx.layout.krate))?;
+        scx.fs.write(&entries_file, index_entries.to_json().as_bytes())?;
+    }
+    if emit.contains(&EmitType::IdMap) {
+        let dir = dst.join(&scx.layout.krate);
+        scx.ensure_dir(&dir)?;
+        let item_urls = serde_json::to_string(&index.item_urls).unwrap();
+        scx.fs.write(&dir.join(ID_MAP_FILE), item_urls.as_bytes())?;
+    }
     let cache = Arc::new(cache);
+
+    // Freeze the cache now that the index has been built. Put an Arc into TLS,
+    // which the threads rendering the pages do as well. The source pages need it
+    // to link the paths in them.
+    CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
+    // The pages need the names the static files are written under.
+    let static_files = collect_static_files(&scx, &md_opts)?;
+    scx.layout.static_files = static_files.names.clone();
+    let fingerprints_file = dst.join(&scx.layout.krate).join(FINGERPRINTS_FILE);
+    if incremental_docs {
+        let global = fingerprint::global_fingerprint(&md_opts, &scx.layout.static_files, &cache);
+        scx.page_fingerprints = Some(PageFingerprints::load(&fingerprints_file, global));
+    } else {
+        // All the pages get written, so the saved fingerprints would no longer match them.
+        let _ = fs::remove_file(&fingerprints_file);
+    }
+    krate = if emit_html { sources::render(&dst, &mut scx, new_crate)? } else { new_crate };
+    CURRENT_DEPTH.with(|s| s.set(0));
     let mut cx = Context {
         current: Vec::new(),
         dst,
         render_redirect_pages: false,
-        id_map: Rc::new(RefCell::new(id_map)),
+        id_map: RefCell::new(id_map),
+        print_position: Vec::new(),
+        module_page: 1,
         shared: Arc::new(scx),
         cache: cache.clone(),
     };
 
-    // Freeze the cache now that the index has been built. Put an Arc into TLS
-    // for future parallelization opportunities
-    CACHE_KEY.with(|v| *v.borrow_mut() = cache.clone());
-    CURRENT_DEPTH.with(|s| s.set(0));
-
     // Write shared runs within a flock; disable thread dispatching of IO temporarily.
     Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(true);
-    write_shared(&cx, &krate, index, &md_opts, diag)?;
+    write_shared(&cx, &krate, index, static_files, &md_opts, diag)?;
     Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(false);
 
+    let krate_version = cx.cache.crate_version.clone();
+    let resource_suffix = cx.shared.resource_suffix.clone();
+
     // And finally render the whole crate's documentation
-    let ret = cx.krate(krate);
+    let ret = if emit_html { cx.krate(krate) } else { Ok(()) };
     let nb_errors = Arc::get_mut(&mut errors).map_or_else(|| 0, |errors| errors.write_errors(diag));
     if ret.is_err() {
         ret
     } else if nb_errors > 0 {
         Err(Error::new(io::Error::new(io::ErrorKind::Other, "I/O error"), ""))
+    } else if let (Some(root), Some(version)) = (versions_root, &krate_version) {
+        versions::update(&root, version, &resource_suffix)
     } else {
         Ok(())
     }
 }
 
-fn write_shared(
-    cx: &Context,
-    krate: &clean::Crate,
-    search_index: String,
-    options: &RenderOptions,
-    diag: &errors::Handler,
-) -> Result<(), Error> {
-    // Write out the shared files. Note that these are shared among all rustdoc
-    // docs placed in the output directory, so this needs to be a synchronized
-    // operation with respect to all other rustdocs running around.
-    let lock_file = cx.dst.join(".lock");
-    let _lock = try_err!(flock::Lock::new(&lock_file, true, true, true), &lock_file);
+/// The static files shared by the documentation of all the crates.
+struct StaticFiles {
+    /// Whether the files are written under names containing the hash of their content, with
+    /// `--hash-static-files`.
+    hash_names: bool,
+    names: layout::StaticFileNames,
+    /// The name and content of every file, in the order they are written in.
+    files: Vec<(String, Vec<u8>)>,
+}
 
-    // Add all the static files. These may already exist, but we just
-    // overwrite them anyway to make sure that they're fresh and up-to-date.
+impl StaticFiles {
+    /// Adds the static file `file`, named with the resource suffix. With `--hash-static-files`,
+    /// it is written under a name containing the hash of its content, and the references of a
+    /// stylesheet to the files added before it, like fonts, are made to their hashed names.
+    fn add(&mut self, file: String, contents: Vec<u8>) {
+        if !self.hash_names {
+            self.files.push((file, contents));
+            return;
+        }
+        let contents = if file.ends_with(".css") {
+            match String::from_utf8(contents) {
+                Ok(mut css) => {
+                    for (name, written) in self.names.iter() {
+                        css = css.replace(&format!("\"{}\"", name), &format!("\"{}\"", written));
+                    }
+                    css.into_bytes()
+                }
+                Err(e) => e.into_bytes(),
+            }
+        } else {
+            contents
+        };
+        let mut hasher = StableHasher::new();
+        hasher.write(&contents);
+        let hash: u64 = hasher.finish();
+        let mut iter = file.splitn(2, '.');
+        let written = format!("{}-{:016x}.{}", iter.next().unwrap(), hash, iter.next().unwrap());
+        self.names.insert(file, written.clone());
+        self.files.push((written, contents));
+    }
 
-    write_minify(&cx.shared.fs, cx.path("rustdoc.css"),
-                 static_files::RUSTDOC_CSS,
-                 options.enable_minification)?;
-    write_minify(&cx.shared.fs, cx.path("settings.css"),
-                 static_files::SETTINGS_CSS,
-                 options.enable_minification)?;
-    write_minify(&cx.shared.fs, cx.path("noscript.css"),
-                 static_files::NOSCRIPT_CSS,
-                 options.enable_minification)?;
+    /// Adds the static file `file`, which the pages do not refer to, under its own name.
+    fn add_unreferenced(&mut self, file: &str, contents: &[u8]) {
+        self.files.push((file.to_owned(), contents.to_vec()));
+    }
+}
+
+/// Collects the static files, minified with `--enable-minification`. They are collected before
+/// any page is rendered, since the pages need to know the names they are written under.
+fn collect_static_files(
+    scx: &SharedContext,
+    options: &RenderOptions,
+) -> Result<StaticFiles, Error> {
+    let mut files = StaticFiles {
+        hash_names: options.hash_static_files,
+        names: Default::default(),
+        files: Vec::new(),
+    };
+    let suffixed = |file| layout::suffixed_file_name(file, &scx.resource_suffix);
+    let minify = |file, contents| minify_static_file(file, contents, options.enable_minification);
+
+    // The fonts come first, so that the stylesheets refer to their hashed names.
+    files.add("FiraSans-Regular.woff".to_owned(), static_files::fira_sans::REGULAR.to_vec());
+    files.add("FiraSans-Medium.woff".to_owned(), static_files::fira_sans::MEDIUM.to_vec());
+    files.add_unreferenced("FiraSans-LICENSE.txt", static_files::fira_sans::LICENSE);
+    files.add("SourceSerifPro-Regular.ttf.woff".to_owned(),
+              static_files::source_serif_pro::REGULAR.to_vec());
+    files.add("SourceSerifPro-Bold.ttf.woff".to_owned(),
+              static_files::source_serif_pro::BOLD.to_vec());
+    files.add("SourceSerifPro-It.ttf.woff".to_owned(),
+              static_files::source_serif_pro::ITALIC.to_vec());
+    files.add_unreferenced("SourceSerifPro-LICENSE.md", static_files::source_serif_pro::LICENSE);
+    files.add("SourceCodePro-Regular.woff".to_owned(),
+              static_files::source_code_pro::REGULAR.to_vec());
+    files.add("SourceCodePro-Semibold.woff".to_owned(),
+              static_files::source_code_pro::SEMIBOLD.to_vec());
+    files.add_unreferenced("SourceCodePro-LICENSE.txt", static_files::source_code_pro::LICENSE);
+    files.add_unreferenced("LICENSE-MIT.txt", static_files::LICENSE_MIT);
+    files.add_unreferenced("LICENSE-APACHE.txt", static_files::LICENSE_APACHE);
+    files.add_unreferenced("COPYRIGHT.txt", static_files::COPYRIGHT);
+
+    files.add(suffixed("rustdoc.css"), minify("rustdoc.css", static_files::RUSTDOC_CSS)?);
+    files.add(suffixed("settings.css"), minify("settings.css", static_files::SETTINGS_CSS)?);
+    files.add(suffixed("noscript.css"), minify("noscript.css", static_files::NOSCRIPT_CSS)?);
 
     // To avoid "light.css" to be overwritten, we'll first run over the received themes and only
     // then we'll run over the "official" styles.
     let mut themes: FxHashSet<String> = FxHashSet::default();
 
-    for entry in &cx.shared.themes {
+    for entry in &scx.themes {
         let content = try_err!(fs::read(&entry), &entry);
         let theme = try_none!(try_none!(entry.file_stem(), &entry).to_str(), &entry);
         let extension = try_none!(try_none!(entry.extension(), &entry).to_str(), &entry);
-        cx.shared.fs.write(cx.path(&format!("{}.{}", theme, extension)), content.as_slice())?;
+        files.add(suffixed(&format!("{}.{}", theme, extension)), content);
         themes.insert(theme.to_owned());
     }
 
-    let write = |p, c| { cx.shared.fs.write(p, c) };
-    if (*cx.shared).layout.logo.is_empty() {
-        write(cx.path("rust-logo.png"), static_files::RUST_LOGO)?;
+    if scx.layout.logo.is_empty() {
+        files.add(suffixed("rust-logo.png"), static_files::RUST_LOGO.to_vec());
     }
-    if (*cx.shared).layout.favicon.is_empty() {
-        write(cx.path("favicon.ico"), static_files::RUST_FAVICON)?;
+    if scx.layout.favicon.is_empty() {
+        files.add(suffixed("favicon.ico"), static_files::RUST_FAVICON.to_vec());
     }
-    write(cx.path("brush.svg"), static_files::BRUSH_SVG)?;
-    write(cx.path("wheel.svg"), static_files::WHEEL_SVG)?;
-    write(cx.path("down-arrow.svg"), static_files::DOWN_ARROW_SVG)?;
-    write_minify(&cx.shared.fs,
-        cx.path("light.css"), static_files::themes::LIGHT, options.enable_minification)?;
+    files.add(suffixed("brush.svg"), static_files::BRUSH_SVG.to_vec());
+    files.add(suffixed("wheel.svg"), static_files::WHEEL_SVG.to_vec());
+    files.add(suffixed("down-arrow.svg"), static_files::DOWN_ARROW_SVG.to_vec());
+    files.add(suffixed("light.css"), minify("light.css", static_files::themes::LIGHT)?);
     themes.insert("light".to_owned());
-    write_minify(&cx.shared.fs,
-        cx.path("dark.css"), static_files::themes::DARK, options.enable_minification)?;
+    files.add(suffixed("dark.css"), minify("dark.css", static_files::themes::DARK)?);
     themes.insert("dark".to_owned());
 
+    if let Some(ref css) = scx.layout.css_file_extension {
+        let buffer = try_err!(fs::read_to_string(css), css);
+        files.add(suffixed("theme.css"), minify("theme.css", &buffer)?);
+    }
+    files.add(suffixed("normalize.css"), minify("normalize.css", static_files::NORMALIZE_CSS)?);
+
     let mut themes: Vec<&String> = themes.iter().collect();
     themes.sort();
     // To avoid theme switch latencies as much as possible, we put everything theme related
@@ -654,79 +984,55 @@ themePicker.onblur = handleThemeButtonsBlur;
     themes.appendChild(but);
 }});"#, serde_json::to_string(&themes).unwrap());
 
-    write_minify(&cx.shared.fs, cx.path("theme.js"),
-                 &theme_js,
-                 options.enable_minification)?;
-    write_minify(&cx.shared.fs, cx.path("main.js"),
-                 static_files::MAIN_JS,
-                 options.enable_minification)?;
-    write_minify(&cx.shared.fs, cx.path("settings.js"),
-                 static_files::SETTINGS_JS,
-                 options.enable_minification)?;
-    if cx.shared.include_sources {
-        write_minify(
-            &cx.shared.fs,
-            cx.path("source-script.js"),
-            static_files::sidebar::SOURCE_SCRIPT,
-            options.enable_minification)?;
+    files.add(suffixed("theme.js"), minify("theme.js", &theme_js)?);
+    files.add(suffixed("main.js"), minify("main.js", static_files::MAIN_JS)?);
+    files.add(suffixed("settings.js"), minify("settings.js", static_files::SETTINGS_JS)?);
+    if scx.include_sources {
+        files.add(suffixed("source-script.js"),
+                  minify("source-script.js", static_files::sidebar::SOURCE_SCRIPT)?);
     }
 
-    {
-        write_minify(
-            &cx.shared.fs,
-            cx.path("storage.js"),
-            &format!("var resourcesSuffix = \"{}\";{}",
-                     cx.shared.resource_suffix,
-                     static_files::STORAGE_JS),
-            options.enable_minification)?;
+    // The theme picker needs the names of the stylesheets, so this comes after all of them.
+    let storage_js = format!("var resourcesSuffix = \"{}\";var staticFileNames = {};{}",
+                             scx.resource_suffix,
+                             serde_json::to_string(&files.names).unwrap(),
+                             static_files::STORAGE_JS);
+    files.add(suffixed("storage.js"), minify("storage.js", &storage_js)?);
+
+    Ok(files)
+}
+
+fn write_shared(
+    cx: &Context,
+    krate: &clean::Crate,
+    search_index: SearchIndex,
+    static_files: StaticFiles,
+    options: &RenderOptions,
+    diag: &errors::Handler,
+) -> Result<(), Error> {
+    // Write out the shared files. Note that these are shared among all rustdoc
+    // docs placed in the output directory, so this needs to be a synchronized
+    // operation with respect to all other rustdocs running around.
+    let lock_file = cx.dst.join(".lock");
+    let _lock = try_err!(flock::Lock::new(&lock_file, true, true, true), &lock_file);
+
+    // With `--emit`, the files shared by the pages are only written along with them, and the
+    // search index only if it is asked for.
+    let emit_html = options.emit.contains(&EmitType::Html);
+    let emit_search_index = options.emit.contains(&EmitType::SearchIndex);
+    let krate_name = krate.name.to_string();
+
+    // Add all the static files. These may already exist, but we just
+    // overwrite them anyway to make sure that they're fresh and up-to-date.
+
+    if emit_html {
+        for (file, contents) in static_files.files {
+            cx.shared.write_shared_file(cx.dst.join(file), contents)?;
+        }
     }
 
-    if let Some(ref css) = cx.shared.layout.css_file_extension {
-        let out = cx.path("theme.css");
-        let buffer = try_err!(fs::read_to_string(css), css);
-        if !options.enable_minification {
-            cx.shared.fs.write(&out, &buffer)?;
-        } else {
-            write_minify(&cx.shared.fs, out, &buffer, options.enable_minification)?;
-        }
-    }
-    write_minify(&cx.shared.fs, cx.path("normalize.css"),
-                 static_files::NORMALIZE_CSS,
-                 options.enable_minification)?;
-    write(cx.dst.join("FiraSans-Regular.woff"),
-          static_files::fira_sans::REGULAR)?;
-    write(cx.dst.join("FiraSans-Medium.woff"),
-          static_files::fira_sans::MEDIUM)?;
-    write(cx.dst.join("FiraSans-LICENSE.txt"),
-          static_files::fira_sans::LICENSE)?;
-    write(cx.dst.join("SourceSerifPro-Regular.ttf.woff"),
-          static_files::source_serif_pro::REGULAR)?;
-    write(cx.dst.join("SourceSerifPro-Bold.ttf.woff"),
-          static_files::source_serif_pro::BOLD)?;
-    write(cx.dst.join("SourceSerifPro-It.ttf.woff"),
-          static_files::source_serif_pro::ITALIC)?;
-    write(cx.dst.join("SourceSerifPro-LICENSE.md"),
-          static_files::source_serif_pro::LICENSE)?;
-    write(cx.dst.join("SourceCodePro-Regular.woff"),
-          static_files::source_code_pro::REGULAR)?;
-    write(cx.dst.join("SourceCodePro-Semibold.woff"),
-          static_files::source_code_pro::SEMIBOLD)?;
-    write(cx.dst.join("SourceCodePro-LICENSE.txt"),
-          static_files::source_code_pro::LICENSE)?;
-    write(cx.dst.join("LICENSE-MIT.txt"),
-          static_files::LICENSE_MIT)?;
-    write(cx.dst.join("LICENSE-APACHE.txt"),
-          static_files::LICENSE_APACHE)?;
-    write(cx.dst.join("COPYRIGHT.txt"),
-          static_files::COPYRIGHT)?;
-
-    fn collect(
-        path: &Path,
-        krate: &str,
-        key: &str,
-    ) -> io::Result<(Vec<String>, Vec<String>)> {
+    fn collect(path: &Path, krate: &str, key: &str) -> io::Result<Vec<String>> {
         let mut ret = Vec::new();
-        let mut krates = Vec::new();
 
         if path.exists() {
             for line in BufReader::new(File::open(path)?).lines() {
@@ -738,28 +1044,28 @@ themePicker.onblur = handleThemeButtonsBlur;
                     continue;
                 }
                 ret.push(line.to_string());
-                krates.push(line[key.len() + 2..].split('"')
-                                                 .next()
-                                                 .map(|s| s.to_owned())
-                                                 .unwrap_or_else(|| String::new()));
             }
         }
-        Ok((ret, krates))
+        Ok(ret)
     }
 
-    fn show_item(item: &IndexItem, krate: &str) -> String {
-        format!("{{'crate':'{}','ty':{},'name':'{}','desc':'{}','p':'{}'{}}}",
+    // Alias entries are not part of the search index, so their parent is written out in full
+    // rather than as an index into its paths.
+    fn show_item(alias: &AliasItem, krate: &str, cache: &Cache) -> String {
+        let item = &alias.item;
+        format!("{{'crate':'{}','ty':{},'name':'{}','desc':'{}','p':'{}'{}{}}}",
                 krate, item.ty as usize, item.name, item.desc.replace("'", "\\'"), item.path,
-                if let Some(p) = item.parent_idx {
-                    format!(",'parent':{}", p)
-                } else {
-                    String::new()
-                })
+                match item.parent.and_then(|did| cache.paths.get(&did)) {
+                    Some(&(ref fqp, ty)) => format!(",'parent':{{'ty':{},'name':'{}'}}",
+                                                    ty as usize, fqp.last().unwrap()),
+                    None => String::new(),
+                },
+                if alias.low_priority { ",'low':true" } else { "" })
     }
 
     let dst = cx.dst.join(&format!("aliases{}.js", cx.shared.resource_suffix));
-    {
-        let (mut all_aliases, _) = try_err!(collect(&dst, &krate.name, "ALIASES"), &dst);
+    if emit_search_index {
+        let mut all_aliases = try_err!(collect(&dst, &krate_name, "ALIASES"), &dst);
         let mut output = String::with_capacity(100);
         for (alias, items) in &cx.cache.aliases {
             if items.is_empty() {
@@ -768,7 +1074,7 @@ themePicker.onblur = handleThemeButtonsBlur;
             output.push_str(&format!("\"{}\":[{}],",
                                      alias,
                                      items.iter()
-                                          .map(|v| show_item(v, &krate.name))
+                                          .map(|v| show_item(v, &krate_name, &cx.cache))
                                           .collect::<Vec<_>>()
                                           .join(",")));
         }
@@ -779,7 +1085,7 @@ themePicker.onblur = handleThemeButtonsBlur;
         for aliases in &all_aliases {
             writeln!(&mut v, "{}", aliases);
         }
-        cx.shared.fs.write(&dst, v.into_inner().into_bytes())?;
+        cx.shared.write_shared_file(&dst, v.into_inner().into_bytes())?;
     }
 
     use std::ffi::OsString;
@@ -828,7 +1134,7 @@ themePicker.onblur = handleThemeButtonsBlur;
         }
     }
 
-    if cx.shared.include_sources {
+    if emit_html && cx.shared.include_sources {
         let mut hierarchy = Hierarchy::new(OsString::new());
         for source in cx.shared.local_sources.iter()
                                              .filter_map(|p| p.0.strip_prefix(&cx.shared.src_root)
@@ -856,33 +1162,43 @@ themePicker.onblur = handleThemeButtonsBlur;
         }
 
         let dst = cx.dst.join(&format!("source-files{}.js", cx.shared.resource_suffix));
-        let (mut all_sources, _krates) = try_err!(collect(&dst, &krate.name, "sourcesIndex"), &dst);
+        let mut all_sources = try_err!(collect(&dst, &krate_name, "sourcesIndex"), &dst);
         all_sources.push(format!("sourcesIndex[\"{}\"] = {};",
-                                 &krate.name,
+                                 &krate_name,
                                  hierarchy.to_json_string()));
         all_sources.sort();
         let v = format!("var N = null;var sourcesIndex = {{}};\n{}\ncreateSourceSidebar();\n",
                           all_sources.join("\n"));
-        cx.shared.fs.write(&dst, v.as_bytes())?;
+        cx.shared.write_shared_file(&dst, v.as_bytes())?;
     }
 
-    // Update the search index
+    // Update the search index, keeping the entries of the other crates. The crates are sorted so
+    // the file will be generated identically even with rustdoc running in parallel.
     let dst = cx.dst.join(&format!("search-index{}.js", cx.shared.resource_suffix));
-    let (mut all_indexes, mut krates) = try_err!(collect(&dst, &krate.name, "searchIndex"), &dst);
-    all_indexes.push(search_index);
+    let json_dst = cx.dst.join(&format!("search-index{}.json", cx.shared.resource_suffix));
+    let mut shared_index = try_err!(SharedSearchIndex::load(&json_dst, &dst), &json_dst);
+    if emit_search_index {
+        let changed = shared_index.insert(&krate_name, search_index.index);
+        // With `--incremental-docs`, the index is left as it is when the entry of the crate did
+        // not change, so that the browsers do not load it again.
+        let incremental = cx.shared.page_fingerprints.is_some();
+        if changed || !incremental || !json_dst.exists() ||
+           !cx.shared.is_shared_file_current(&dst) {
+            try_err!(shared_index.save(&json_dst), &json_dst);
+            cx.shared.write_shared_file(&dst, shared_index.to_script())?;
+        }
+    }
 
-    // Sort the indexes by crate so the file will be generated identically even
-    // with rustdoc running in parallel.
-    all_indexes.sort();
-    {
-        let mut v = String::from("var searchIndex={};\n");
-        v.push_str(&all_indexes.join("\n"));
-        // "addSearchOptions" has to be called first so the crate filtering can be set before the
-        // search might start (if it's set into the URL for example).
-        v.push_str("\naddSearchOptions(searchIndex);initSearch(searchIndex);");
-        cx.shared.fs.write(&dst, &v)?;
-    }
-    if options.enable_index_page {
+    // The descriptions left out of the search index, with `--shard-search-descriptions`.
+    if emit_search_index && !search_index.desc_shards.is_empty() {
+        let dir = cx.dst.join("search-desc").join(&krate_name);
+        cx.shared.ensure_dir(&dir)?;
+        for (shard, descs) in search_index.desc_shards.iter().enumerate() {
+            let file = format!("{}{}.js", shard, cx.shared.resource_suffix);
+            cx.shared.write_shared_file(dir.join(file), descs)?;
+        }
+    }
+    if emit_html && options.enable_index_page {
         if let Some(index_page) = options.index_page.clone() {
             let mut md_opts = options.clone();
             md_opts.output = cx.dst.clone();
@@ -902,28 +1218,27 @@ themePicker.onblur = handleThemeButtonsBlur;
                 extra_scripts: &[],
                 static_extra_scripts: &[],
             };
-            krates.push(krate.name.clone());
-            krates.sort();
-            krates.dedup();
-
             let content = format!(
 "<h1 class='fqn'>\
      <span class='in-band'>List of all crates</span>\
 </h1><ul class='mod'>{}</ul>",
-                                  krates
-                                    .iter()
+                                  shared_index
+                                    .krates()
                                     .map(|s| {
                                         format!("<li><a href=\"{}index.html\">{}</li>",
                                                 ensure_trailing_slash(s), s)
                                     })
                                     .collect::<String>());
-            let v = layout::render(&cx.shared.layout,
-                           &page, "", content,
-                           &cx.shared.themes);
-            cx.shared.fs.write(&dst, v.as_bytes())?;
+            cx.shared.write_page(&dst, |w| {
+                layout::render(w, &cx.shared.layout, &page, "", content, &cx.shared.themes)
+            })?;
         }
     }
 
+    if !emit_html {
+        return Ok(());
+    }
+
     // Update the list of all implementors for traits
     let dst = cx.dst.join("implementors");
     for (&did, imps) in &cx.cache.implementors {
@@ -946,6 +1261,8 @@ themePicker.onblur = handleThemeButtonsBlur;
         struct Implementor {
             text: String,
             synthetic: bool,
+            /// The group the impl is listed in, see `ImplementorKind`.
+            kind: &'static str,
             types: Vec<String>,
         }
 
@@ -965,6 +1282,7 @@ themePicker.onblur = handleThemeButtonsBlur;
                     Some(Implementor {
                         text: imp.inner_impl().print().to_string(),
                         synthetic: imp.inner_impl().synthetic,
+                        kind: ImplementorKind::of(imp.inner_impl()).as_str(),
                         types: collect_paths_for_type(imp.inner_impl().for_.clone()),
                     })
                 }
@@ -993,8 +1311,7 @@ themePicker.onblur = handleThemeButtonsBlur;
                             remote_item_type,
                             remote_path[remote_path.len() - 1]));
 
-        let (mut all_implementors, _) = try_err!(collect(&mydst, &krate.name, "implementors"),
-                                                 &mydst);
+        let mut all_implementors = try_err!(collect(&mydst, &krate_name, "implementors"), &mydst);
         all_implementors.push(implementors);
         // Sort the implementors by crate so the file will be generated
         // identically even with rustdoc running in parallel.
@@ -1012,22 +1329,21 @@ themePicker.onblur = handleThemeButtonsBlur;
             }
         ");
         v.push_str("})()");
-        cx.shared.fs.write(&mydst, &v)?;
+        cx.shared.write_shared_file(&mydst, &v)?;
     }
     Ok(())
 }
 
-fn write_minify(fs:&DocFS, dst: PathBuf, contents: &str, enable_minification: bool
-                ) -> Result<(), Error> {
-    if enable_minification {
-        if dst.extension() == Some(&OsStr::new("css")) {
-            let res = try_none!(minifier::css::minify(contents).ok(), &dst);
-            fs.write(dst, res.as_bytes())
-        } else {
-            fs.write(dst, minifier::js::minify(contents).as_bytes())
-        }
+/// Returns `contents`, the content of the static file `file`, minified if `enable_minification`
+/// is set.
+fn minify_static_file(file: &str, contents: &str, enable_minification: bool
+                      ) -> Result<Vec<u8>, Error> {
+    if !enable_minification {
+        Ok(contents.as_bytes().to_vec())
+    } else if file.ends_with(".css") {
+        Ok(try_none!(minifier::css::minify(contents).ok(), file).into_bytes())
     } else {
-        fs.write(dst, contents.as_bytes())
+        Ok(minifier::js::minify(contents).into_bytes())
     }
 }
 
@@ -1179,6 +1495,53 @@ impl AllTypes {
     }
 }
 
+/// Prints the "Feature flags" page of the crate, listing the items gated on each cargo feature.
+fn print_features(cx: &Context, f: &mut Buffer) {
+    write!(f, "<h1 class='fqn'><span class='in-band'>Feature flags</span></h1>\
+               <div class='docblock'><p>The cargo features the items of this crate are gated on. \
+               Items are only listed under the features their module or type does not already \
+               require.</p></div>");
+    for (feature, items) in &cx.cache.features {
+        let mut entries = items.iter().filter_map(|item| {
+            let (fqp, ty) = cx.cache.paths.get(&item.parent.unwrap_or(item.did))?;
+            let mut url = String::new();
+            for component in &fqp[1..fqp.len() - 1] {
+                url.push_str(component);
+                url.push_str("/");
+            }
+            url.push_str(&item_path(*ty, fqp.last()?));
+            let mut path = fqp[1..].join("::");
+            if item.parent.is_some() {
+                url.push_str(&format!("#{}.{}", item.ty, item.name));
+                path.push_str("::");
+                path.push_str(&item.name);
+            }
+            // Only mention the configuration when the feature is not all it takes.
+            let cfg = if item.cfg == Cfg::Cfg(sym::feature, Some(Symbol::intern(feature))) {
+                String::new()
+            } else {
+                format!(" <span class='stab portability'>{}</span>",
+                        item.cfg.render_short_html(&cx.shared.cfg_names))
+            };
+            Some((path, url, item.ty, cfg))
+        }).collect::<Vec<_>>();
+        if entries.is_empty() {
+            continue;
+        }
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|a, b| a.0 == b.0);
+
+        write!(f, "<h3 id='feature.{name}' class='feature'>\
+                   <a href='#feature.{name}' class='anchor'></a><code>{name}</code></h3>\
+                   <ul class='features docblock'>",
+               name = Escape(feature));
+        for (path, url, ty, cfg) in entries {
+            write!(f, "<li><a class='{}' href='{}'>{}</a>{}</li>", ty, url, path, cfg);
+        }
+        write!(f, "</ul>");
+    }
+}
+
 #[derive(Debug)]
 enum Setting {
     Section {
@@ -1242,7 +1605,7 @@ impl<T: Into<Setting>> From<(&'static str, Vec<T>)> for Setting {
     }
 }
 
-fn settings(root_path: &str, suffix: &str) -> String {
+fn settings(root_path: &str, script: &str) -> String {
     // (id, explanation, default value)
     let settings: &[Setting] = &[
         ("Auto-hide item declarations", vec![
@@ -1266,10 +1629,10 @@ fn settings(root_path: &str, suffix: &str) -> String {
     <span class='in-band'>Rustdoc settings</span>\
 </h1>\
 <div class='settings'>{}</div>\
-<script src='{}settings{}.js'></script>",
+<script src='{}{}'></script>",
             settings.iter().map(|s| s.display()).collect::<String>(),
             root_path,
-            suffix)
+            script)
 }
 
 impl Context {
@@ -1286,31 +1649,35 @@ impl Context {
 
     /// Main method for rendering a crate.
     ///
-    /// This currently isn't parallelized, but it'd be pretty easy to add
-    /// parallelization to this function.
+    /// The crate is first walked to plan its pages, then the pages are rendered
+    /// on the thread pool, each in its own context.
     fn krate(self, mut krate: clean::Crate) -> Result<(), Error> {
         let mut item = match krate.module.take() {
             Some(i) => i,
             None => return Ok(()),
         };
-        let final_file = self.dst.join(&krate.name)
+        let crate_name = krate.name.to_string();
+        item.name = Some(krate.name);
+
+        let final_file = self.dst.join(&crate_name)
                                  .join("all.html");
         let settings_file = self.dst.join("settings.html");
 
-        let crate_name = krate.name.clone();
-        item.name = Some(krate.name);
-
         let mut all = AllTypes::new();
 
         {
-            // Render the crate documentation
-            let mut work = vec![(self.clone(), item)];
+            // Plan the crate documentation
+            let mut work = vec![(self.clone(), &item)];
+            let mut pages = vec![];
 
             while let Some((mut cx, item)) = work.pop() {
-                cx.item(item, &mut all, |cx, item| {
+                cx.item(item, &mut all, &mut pages, |cx, item| {
                     work.push((cx.clone(), item))
                 })?
             }
+
+            // Render it
+            par_iter(pages).try_for_each(|(cx, item)| cx.render_page(item))?;
         }
 
         let mut root_path = self.dst.to_str().expect("invalid path").to_owned();
@@ -1338,10 +1705,11 @@ impl Context {
         } else {
             String::new()
         };
-        let v = layout::render(&self.shared.layout,
-                       &page, sidebar, |buf: &mut Buffer| all.print(buf),
-                       &self.shared.themes);
-        self.shared.fs.write(&final_file, v.as_bytes())?;
+        self.shared.write_page(&final_file, |w| {
+            layout::render(w, &self.shared.layout, &page, sidebar,
+                           |buf: &mut Buffer| all.print(buf),
+                           &self.shared.themes)
+        })?;
 
         // Generating settings page.
         page.title = "Rustdoc settings";
@@ -1351,21 +1719,95 @@ impl Context {
         let mut themes = self.shared.themes.clone();
         let sidebar = "<p class='location'>Settings</p><div class='sidebar-elems'></div>";
         themes.push(PathBuf::from("settings.css"));
-        let v = layout::render(
-            &self.shared.layout,
-            &page, sidebar, settings(
-                self.shared.static_root_path.as_deref().unwrap_or("./"),
-                &self.shared.resource_suffix
-            ),
-            &themes);
-        self.shared.fs.write(&settings_file, v.as_bytes())?;
+        let settings_js = layout::suffixed_file_name("settings.js", &self.shared.resource_suffix);
+        self.shared.write_page(&settings_file, |w| {
+            layout::render(
+                w,
+                &self.shared.layout,
+                &page, sidebar, settings(
+                    self.shared.static_root_path.as_deref().unwrap_or("./"),
+                    self.shared.layout.static_files.get(&settings_js),
+                ),
+                &themes)
+        })?;
+
+        if let Some(ref print_page) = self.shared.print_page {
+            page.title = "All documentation";
+            page.css_class = "print";
+            page.root_path = "../";
+            page.description = "The documentation of every item in this crate";
+            let sidebar = format!("<p class='location'>Crate {}</p>\
+                                   <a id='all-types' href='index.html'><p>Back to index</p></a>",
+                                  crate_name);
+            let print_file = self.dst.join(&crate_name).join("print.html");
+            self.shared.write_page(&print_file, |w| {
+                layout::render(w, &self.shared.layout, &page, sidebar,
+                               |buf: &mut Buffer| print_page.print(buf),
+                               &self.shared.themes)
+            })?;
+        }
+
+        if !self.cache.features.is_empty() {
+            page.title = "Feature flags";
+            page.css_class = "mod";
+            page.root_path = "../";
+            page.description = "The cargo features of this crate and the items gated on them";
+            let sidebar = format!("<p class='location'>Crate {}</p>\
+                                   <a id='all-types' href='index.html'><p>Back to index</p></a>",
+                                  crate_name);
+            let features_file = self.dst.join(&crate_name).join("features.html");
+            self.shared.write_page(&features_file, |w| {
+                layout::render(w, &self.shared.layout, &page, sidebar,
+                               |buf: &mut Buffer| print_features(&self, buf),
+                               &self.shared.themes)
+            })?;
+        }
+
+        if let Some(ref impl_fragments) = self.shared.impl_fragments {
+            let impls_dir = self.dst.join(&crate_name).join("impls");
+            for (file_name, script) in impl_fragments.take_files() {
+                self.shared.ensure_dir(&impls_dir)?;
+                self.shared.fs.write(impls_dir.join(file_name), script.as_bytes())?;
+            }
+        }
+
+        if let Some(ref page_fingerprints) = self.shared.page_fingerprints {
+            let fingerprints_file = self.dst.join(&crate_name).join(FINGERPRINTS_FILE);
+            self.shared.fs.write(&fingerprints_file, page_fingerprints.to_json().as_bytes())?;
+        }
+
+        if self.shared.item_metrics {
+            let metrics_file = self.dst.join(&crate_name).join("item-metrics.json");
+            let metrics = serde_json::to_string(&*self.shared.collected_metrics.lock())
+                .expect("failed to serialize item metrics");
+            self.shared.fs.write(&metrics_file, metrics.as_bytes())?;
+        }
 
         Ok(())
     }
 
+    /// Whether `it` gets a page. The stripped items only do when they can redirect
+    /// to their page elsewhere.
+    fn has_page(&self, it: &clean::Item) -> bool {
+        !self.render_redirect_pages || self.cache.paths.contains_key(&it.def_id)
+    }
+
+    /// Whether the page `file_name` of `it` written by the previous run is up to date, with
+    /// `--incremental-docs`.
+    fn page_is_fresh(&self, it: &clean::Item, file_name: &str) -> bool {
+        let page_fingerprints = match self.shared.page_fingerprints {
+            Some(ref page_fingerprints) => page_fingerprints,
+            None => return false,
+        };
+        let page = format!("{}/{}", self.current.join("/"), file_name);
+        page_fingerprints.is_fresh(page, &self.current, self.render_redirect_pages, it, &self.cache)
+            && self.dst.join(file_name).exists()
+    }
+
     fn render_item(&self,
+                   w: &mut dyn io::Write,
                    it: &clean::Item,
-                   pushname: bool) -> String {
+                   pushname: bool) -> io::Result<()> {
         // A little unfortunate that this is done like this, but it sure
         // does make formatting *a lot* nicer.
         CURRENT_DEPTH.with(|slot| {
@@ -1382,7 +1824,10 @@ impl Context {
             if !title.is_empty() {
                 title.push_str("::");
             }
-            title.push_str(it.name.as_ref().unwrap());
+            title.push_str(&it.name.unwrap().as_str());
+        }
+        if self.module_page > 1 {
+            title.push_str(&format!(" (page {})", self.module_page));
         }
         title.push_str(" - Rust");
         let tyname = it.type_();
@@ -1406,38 +1851,64 @@ impl Context {
             static_extra_scripts: &[],
         };
 
-        {
-            self.id_map.borrow_mut().reset();
-            self.id_map.borrow_mut().populate(initial_ids());
-        }
+        *self.id_map.borrow_mut() = page_ids();
 
-        if !self.render_redirect_pages {
-            layout::render(&self.shared.layout, &page,
+        if let Some(canonical) = self.alias_of(it).filter(|_| !self.render_redirect_pages) {
+            layout::render(w, &self.shared.layout, &page,
                            |buf: &mut _| print_sidebar(self, it, buf),
-                           |buf: &mut _| print_item(self, it, buf),
+                           |buf: &mut _| print_alias(self, it, canonical, buf),
+                           &self.shared.themes)
+        } else if !self.render_redirect_pages {
+            let mut content = Buffer::html();
+            print_item(self, it, &mut content);
+            let content = content.into_inner();
+            // Only the first page of the listing of a module goes on the printable page, along
+            // with its documentation.
+            let print_page = self.shared.print_page.as_ref().filter(|_| self.module_page == 1);
+            if let Some(print_page) = print_page {
+                let file = if it.is_mod() {
+                    "index.html".to_string()
+                } else {
+                    item_path(it.type_(), &it.name.unwrap().as_str())
+                };
+                print_page.record(self.print_position.clone(), &self.current[1..], &file, &content);
+            }
+            layout::render(w, &self.shared.layout, &page,
+                           |buf: &mut _| print_sidebar(self, it, buf),
+                           |buf: &mut Buffer| buf.write_str(&content),
                            &self.shared.themes)
         } else {
             let mut url = self.root_path();
-            if let Some(&(ref names, ty)) = self.cache.paths.get(&it.def_id) {
-                for name in &names[..names.len() - 1] {
-                    url.push_str(name);
-                    url.push_str("/");
-                }
-                url.push_str(&item_path(ty, names.last().unwrap()));
-                layout::redirect(&url)
-            } else {
-                String::new()
+            let &(ref names, ty) = &self.cache.paths[&it.def_id];
+            for name in &names[..names.len() - 1] {
+                url.push_str(name);
+                url.push_str("/");
             }
+            url.push_str(&item_path(ty, names.last().unwrap()));
+            w.write_all(layout::redirect(&url).as_bytes())
         }
     }
 
-    /// Non-parallelized version of rendering an item. This will take the input
-    /// item, render its contents, and then invoke the specified closure with
-    /// all sub-items which need to be rendered.
+    /// Returns the canonical path of `it` if it is documented at several locations and the
+    /// current one is not the canonical one.
+    fn alias_of(&self, it: &clean::Item) -> Option<&[String]> {
+        let canonical = it.canonical_path.as_ref()?;
+        let name = it.name.map(|name| name.to_string());
+        let here = self.current.iter().chain(name.as_ref());
+        if canonical.iter().eq(here) { None } else { Some(canonical) }
+    }
+
+    /// Plans the rendering of an item. This will write the files shared by the
+    /// items of a module, queue the page of the input item in `pages`, and then
+    /// invoke the specified closure with all sub-items which need to be planned.
     ///
     /// The rendering driver uses this closure to queue up more work.
-    fn item<F>(&mut self, item: clean::Item, all: &mut AllTypes, mut f: F) -> Result<(), Error>
-        where F: FnMut(&mut Context, clean::Item),
+    fn item<'a, F>(&mut self,
+                   item: &'a clean::Item,
+                   all: &mut AllTypes,
+                   pages: &mut Vec<(Context, &'a clean::Item)>,
+                   mut f: F) -> Result<(), Error>
+        where F: FnMut(&mut Context, &'a clean::Item),
     {
         // Stripped modules survive the rustdoc passes (i.e., `strip-private`)
         // if they contain impls for public types. These modules can also
@@ -1464,30 +1935,36 @@ impl Context {
 
             info!("Recursing into {}", self.dst.display());
 
-            let buf = self.render_item(&item, false);
-            // buf will be empty if the module is stripped and there is no redirect for it
-            if !buf.is_empty() {
-                self.shared.ensure_dir(&self.dst)?;
-                let joint_dst = self.dst.join("index.html");
-                scx.fs.write(&joint_dst, buf.as_bytes())?;
-            }
+            pages.push((self.clone(), item));
 
             let m = match item.inner {
-                clean::StrippedItem(box clean::ModuleItem(m)) |
-                clean::ModuleItem(m) => m,
+                clean::StrippedItem(box clean::ModuleItem(ref m)) |
+                clean::ModuleItem(ref m) => m,
                 _ => unreachable!()
             };
 
+            // The other pages of the listing of the module, if it has to be split.
+            if !self.render_redirect_pages && self.alias_of(item).is_none() {
+                for page in 2..=module_pages(self, module_listing(self, &m.items).len()) {
+                    let mut cx = self.clone();
+                    cx.module_page = page;
+                    pages.push((cx, item));
+                }
+            }
+
             // Render sidebar-items.js used throughout this module.
             if !self.render_redirect_pages {
-                let items = self.build_sidebar_items(&m);
+                let items = self.build_sidebar_items(m);
                 let js_dst = self.dst.join("sidebar-items.js");
                 let v = format!("initSidebarItems({});", serde_json::to_string(&items).unwrap());
+                scx.ensure_dir(&self.dst)?;
                 scx.fs.write(&js_dst, &v)?;
             }
 
-            for item in m.items {
+            for (i, item) in m.items.iter().enumerate() {
+                self.print_position.push(i);
                 f(self, item);
+                self.print_position.pop();
             }
 
             info!("Recursed; leaving {}", self.dst.display());
@@ -1496,36 +1973,60 @@ impl Context {
             self.dst = prev;
             self.current.pop().unwrap();
         } else if item.name.is_some() {
-            let buf = self.render_item(&item, true);
-            // buf will be empty if the item is stripped and there is no redirect for it
-            if !buf.is_empty() {
-                let name = item.name.as_ref().unwrap();
-                let item_type = item.type_();
-                let file_name = &item_path(item_type, name);
-                self.shared.ensure_dir(&self.dst)?;
-                let joint_dst = self.dst.join(file_name);
-                self.shared.fs.write(&joint_dst, buf.as_bytes())?;
-
-                if !self.render_redirect_pages {
-                    all.append(full_path(self, &item), &item_type);
-                }
-                if self.shared.generate_redirect_pages {
-                    // Redirect from a sane URL using the namespace to Rustdoc's
-                    // URL for the page.
-                    let redir_name = format!("{}.{}.html", name, item_type.name_space());
-                    let redir_dst = self.dst.join(redir_name);
-                    let v = layout::redirect(file_name);
-                    self.shared.fs.write(&redir_dst, v.as_bytes())?;
-                }
-                // If the item is a macro, redirect from the old macro URL (with !)
-                // to the new one (without).
-                if item_type == ItemType::Macro {
-                    let redir_name = format!("{}.{}!.html", item_type, name);
-                    let redir_dst = self.dst.join(redir_name);
-                    let v = layout::redirect(file_name);
-                    self.shared.fs.write(&redir_dst, v.as_bytes())?;
-                }
+            if !self.render_redirect_pages && self.alias_of(item).is_none() {
+                all.append(full_path(self, item), &item.type_());
+            }
+            pages.push((self.clone(), item));
+        }
+        Ok(())
+    }
+
+    /// Renders the page of an item queued by `item` and writes it, along with
+    /// its redirect pages. This runs on the threads of the pool, so the state
+    /// shared with the other pages is either read-only or locked.
+    fn render_page(&self, item: &clean::Item) -> Result<(), Error> {
+        // The cache is frozen by now, but each thread needs its own handle to it.
+        CACHE_KEY.with(|v| *v.borrow_mut() = self.cache.clone());
+
+        // There is no page if the item is stripped and there is no redirect for it
+        if !self.has_page(item) {
+            return Ok(());
+        }
+
+        if item.is_mod() {
+            let file_name = &module_page_file(self.module_page);
+            if self.page_is_fresh(item, file_name) {
+                return Ok(());
             }
+            self.shared.ensure_dir(&self.dst)?;
+            let joint_dst = self.dst.join(file_name);
+            return self.shared.write_page(&joint_dst, |w| self.render_item(w, item, false));
+        }
+
+        let name = item.name.unwrap();
+        let item_type = item.type_();
+        let file_name = &item_path(item_type, &name.as_str());
+        if !self.page_is_fresh(item, file_name) {
+            self.shared.ensure_dir(&self.dst)?;
+            let joint_dst = self.dst.join(file_name);
+            self.shared.write_page(&joint_dst, |w| self.render_item(w, item, true))?;
+        }
+
+        if self.shared.generate_redirect_pages {
+            // Redirect from a sane URL using the namespace to Rustdoc's
+            // URL for the page.
+            let redir_name = format!("{}.{}.html", name, item_type.name_space());
+            let redir_dst = self.dst.join(redir_name);
+            let v = layout::redirect(file_name);
+            self.shared.fs.write(&redir_dst, v.as_bytes())?;
+        }
+        // If the item is a macro, redirect from the old macro URL (with !)
+        // to the new one (without).
+        if item_type == ItemType::Macro {
+            let redir_name = format!("{}.{}!.html", item_type, name);
+            let redir_dst = self.dst.join(redir_name);
+            let v = layout::redirect(file_name);
+            self.shared.fs.write(&redir_dst, v.as_bytes())?;
         }
         Ok(())
     }
@@ -1628,6 +2129,19 @@ fn print_item(cx: &Context, item: &clean::Item, buf: &mut Buffer) {
     debug_assert!(!item.is_stripped());
     // Write the breadcrumb trail header for the top
     write!(buf, "<h1 class='fqn'><span class='out-of-band'>");
+    if cx.shared.item_metrics {
+        let dox = cx.shared.maybe_collapsed_doc_value(item);
+        let metrics = ItemMetrics::for_item(item, dox.as_deref());
+        let reading_time = match metrics.reading_minutes() {
+            0 => "undocumented".to_owned(),
+            minutes => format!("{} min read", minutes),
+        };
+        write!(buf, "<span class='item-metrics' \
+                           title='{} words, {} examples, signature complexity {}'>{}</span>",
+               metrics.doc_words, metrics.examples, metrics.signature_complexity, reading_time);
+        let path = if item.is_mod() { cx.current.join("::") } else { full_path(cx, item) };
+        cx.shared.collected_metrics.borrow_mut().insert(path, metrics);
+    }
     if let Some(version) = item.stable_since() {
         write!(buf, "<span class='since' title='Stable since Rust version {0}'>{0}</span>",
                 version);
@@ -1725,9 +2239,69 @@ fn print_item(cx: &Context, item: &clean::Item, buf: &mut Buffer) {
             unreachable!();
         }
     }
+    render_used_by(buf, cx, item);
 }
 
-fn item_path(ty: ItemType, name: &str) -> String {
+/// Renders the collapsed list of the items whose signature mentions `it`.
+fn render_used_by(w: &mut Buffer, cx: &Context, it: &clean::Item) {
+    let references = match cx.cache.referenced_by.get(&it.def_id) {
+        Some(references) => references,
+        None => return,
+    };
+    let mut links = references.iter().filter_map(|r| {
+        let (url, _, fqp) = href(r.parent.unwrap_or(r.did))?;
+        Some(match r.parent {
+            Some(_) => (format!("{}::{}", fqp.join("::"), r.name),
+                        format!("{}#{}.{}", url, r.ty, r.name)),
+            None => (fqp.join("::"), url),
+        })
+    }).collect::<Vec<_>>();
+    links.sort();
+    links.dedup();
+    if links.is_empty() {
+        return;
+    }
+
+    write!(w, "<h2 id='used-by' class='small-section-header'>\
+                 Used by<a href='#used-by' class='anchor'></a>\
+               </h2>\
+               <details class='used-by'><summary>{} item{}</summary><ul>",
+           links.len(), if links.len() == 1 { "" } else { "s" });
+    for (path, url) in links {
+        write!(w, "<li><a href='{}'>{}</a></li>", url, path);
+    }
+    write!(w, "</ul></details>");
+}
+
+/// Prints the short page of an item documented at several locations, pointing to the location
+/// where its full documentation lives.
+fn print_alias(cx: &Context, item: &clean::Item, canonical: &[String], buf: &mut Buffer) {
+    let name = item.name.as_ref().unwrap();
+    let mut url = cx.root_path();
+    for component in &canonical[..canonical.len() - 1] {
+        url.push_str(component);
+        url.push_str("/");
+    }
+    url.push_str(&item_path(item.type_(), canonical.last().unwrap()));
+
+    write!(buf, "<h1 class='fqn'><span class='in-band'>Re-export ");
+    for (i, component) in cx.current.iter().enumerate() {
+        write!(buf, "<a href='{}index.html'>{}</a>::<wbr>",
+                "../".repeat(cx.current.len() - i - 1),
+                component);
+    }
+    write!(buf, "<a class=\"{}\" href=''>{}</a></span></h1>", item.type_(), name);
+
+    write!(buf, "<div class='docblock'>");
+    let summary = shorten(plain_summary_line(item.doc_value()));
+    if !summary.is_empty() {
+        write!(buf, "<p>{}</p>", Escape(&summary));
+    }
+    write!(buf, "<p>Documented at <a class='canonical' href='{}'><code>{}</code></a>.</p></div>",
+           url, canonical.join("::"));
+}
+
+crate fn item_path(ty: ItemType, name: &str) -> String {
     match ty {
         ItemType::Module => format!("{}index.html", ensure_trailing_slash(name)),
         _ => format!("{}.{}.html", ty, name),
@@ -1737,7 +2311,7 @@ fn item_path(ty: ItemType, name: &str) -> String {
 fn full_path(cx: &Context, item: &clean::Item) -> String {
     let mut s = cx.current.join("::");
     s.push_str("::");
-    s.push_str(item.name.as_ref().unwrap());
+    s.push_str(&item.name.unwrap().as_str());
     s
 }
 
@@ -1781,6 +2355,7 @@ fn document(w: &mut Buffer, cx: &Context, item: &clean::Item) {
         info!("Documenting {}", name);
     }
     document_stability(w, cx, item, false);
+    document_aliases(w, item, false);
     document_full(w, item, cx, "", false);
 }
 
@@ -1798,7 +2373,8 @@ fn render_markdown(
            if is_hidden { " hidden" } else { "" },
            prefix,
            Markdown(md_text, &links, &mut ids,
-           cx.shared.codes, cx.shared.edition, &cx.shared.playground).to_string())
+           cx.shared.codes, cx.shared.edition, &cx.shared.playground,
+           cx.shared.hidden_line_prefix.as_ref().map(|prefix| &**prefix)).to_string())
 }
 
 fn document_short(
@@ -1827,7 +2403,9 @@ fn document_short(
 fn document_full(w: &mut Buffer, item: &clean::Item, cx: &Context, prefix: &str, is_hidden: bool) {
     if let Some(s) = cx.shared.maybe_collapsed_doc_value(item) {
         debug!("Doc block: =====\n{}\n=====", s);
+        cx.id_map.borrow_mut().set_heading_prefix(heading_prefix(item));
         render_markdown(w, cx, &*s, item.links(), prefix, is_hidden);
+        cx.id_map.borrow_mut().set_heading_prefix(None);
     } else if !prefix.is_empty() {
         write!(w, "<div class='docblock{}'>{}</div>",
                if is_hidden { " hidden" } else { "" },
@@ -1835,6 +2413,12 @@ fn document_full(w: &mut Buffer, item: &clean::Item, cx: &Context, prefix: &str,
     }
 }
 
+/// Renders the source of a provided trait method's default implementation, collapsed by default.
+fn document_default_impl(w: &mut Buffer, src: &str) {
+    write!(w, "<details class='default-impl'><summary>Default implementation</summary>{}</details>",
+           highlight::render_with_highlighting(src, Some("rust default-impl"), None, None));
+}
+
 fn document_stability(w: &mut Buffer, cx: &Context, item: &clean::Item, is_hidden: bool) {
     let stabilities = short_stability(item, cx);
     if !stabilities.is_empty() {
@@ -1846,6 +2430,19 @@ fn document_stability(w: &mut Buffer, cx: &Context, item: &clean::Item, is_hidde
     }
 }
 
+fn document_aliases(w: &mut Buffer, item: &clean::Item, is_hidden: bool) {
+    if !item.attrs.aliases.is_empty() {
+        write!(w, "<div class='aliases{}'>Also known as: {}</div>",
+               if is_hidden { " hidden" } else { "" },
+               item.attrs.aliases.iter()
+                                 .map(|alias| {
+                                     format!("<code>{}</code>", Escape(&alias.name.as_str()))
+                                 })
+                                 .collect::<Vec<_>>()
+                                 .join(", "));
+    }
+}
+
 fn document_non_exhaustive_header(item: &clean::Item) -> &str {
     if item.is_non_exhaustive() { " (Non-exhaustive)" } else { "" }
 }
@@ -1908,10 +2505,14 @@ fn name_key(name: &str) -> (&str, u64, usize) {
     }
 }
 
-fn item_module(w: &mut Buffer, cx: &Context, item: &clean::Item, items: &[clean::Item]) {
-    document(w, cx, item);
-
-    let mut indices = (0..items.len()).filter(|i| !items[*i].is_stripped()).collect::<Vec<usize>>();
+/// Returns the indices of the items of a module listed on its page, in the order they are
+/// listed in.
+fn module_listing(cx: &Context, items: &[clean::Item]) -> Vec<usize> {
+    // The imports are the only items without a name to list.
+    let mut indices = (0..items.len()).filter(|i| {
+        let item = &items[*i];
+        !item.is_stripped() && (item.name.is_some() || item.is_import() || item.is_extern_crate())
+    }).collect::<Vec<usize>>();
 
     // the order of item types in the listing
     fn reorder(ty: ItemType) -> u8 {
@@ -1946,9 +2547,9 @@ fn item_module(w: &mut Buffer, cx: &Context, item: &clean::Item, items: &[clean:
             (Some(stability::Stable), Some(stability::Unstable)) => return Ordering::Less,
             _ => {}
         }
-        let lhs = i1.name.as_ref().map_or("", |s| &**s);
-        let rhs = i2.name.as_ref().map_or("", |s| &**s);
-        name_key(lhs).cmp(&name_key(rhs))
+        let lhs = i1.name.map(|s| s.as_str());
+        let rhs = i2.name.map(|s| s.as_str());
+        name_key(lhs.as_deref().unwrap_or("")).cmp(&name_key(rhs.as_deref().unwrap_or("")))
     }
 
     if cx.shared.sort_modules_alphabetically {
@@ -1987,12 +2588,41 @@ fn item_module(w: &mut Buffer, cx: &Context, item: &clean::Item, items: &[clean:
                               }));
 
     debug!("{:?}", indices);
+    indices
+}
+
+/// Returns the number of pages the listing of `listed` items of a module is split into, with
+/// `--module-page-size`.
+fn module_pages(cx: &Context, listed: usize) -> usize {
+    match cx.shared.module_page_size {
+        Some(size) => cmp::max(1, (listed + size - 1) / size),
+        None => 1,
+    }
+}
+
+/// Returns the name of the file holding the page `page` of the listing of a module.
+fn module_page_file(page: usize) -> String {
+    if page == 1 { "index.html".to_owned() } else { format!("index-{}.html", page) }
+}
+
+fn item_module(w: &mut Buffer, cx: &Context, item: &clean::Item, items: &[clean::Item]) {
+    if cx.module_page == 1 {
+        document(w, cx, item);
+    }
+
+    let indices = module_listing(cx, items);
+    let pages = module_pages(cx, indices.len());
+    let indices = match cx.shared.module_page_size {
+        Some(size) if pages > 1 => {
+            let start = (cx.module_page - 1) * size;
+            &indices[start..cmp::min(start + size, indices.len())]
+        }
+        _ => &indices[..],
+    };
+
     let mut curty = None;
-    for &idx in &indices {
+    for &idx in indices {
         let myitem = &items[idx];
-        if myitem.is_stripped() {
-            continue;
-        }
 
         let myty = Some(myitem.type_());
         if curty == Some(ItemType::ExternCrate) && myty == Some(ItemType::Import) {
@@ -2023,7 +2653,7 @@ fn item_module(w: &mut Buffer, cx: &Context, item: &clean::Item, items: &[clean:
                     None => {
                         write!(w, "<tr><td><code>{}extern crate {};",
                                myitem.visibility.print_with_space(),
-                               anchor(myitem.def_id, name))
+                               anchor(myitem.def_id, &name.as_str()))
                     }
                 }
                 write!(w, "</code></td></tr>");
@@ -2059,14 +2689,14 @@ fn item_module(w: &mut Buffer, cx: &Context, item: &clean::Item, items: &[clean:
                                   title='{title}'>{name}</a>{unsafety_flag}</td>\
                            <td class='docblock-short'>{stab_tags}{docs}</td>\
                        </tr>",
-                       name = *myitem.name.as_ref().unwrap(),
-                       stab_tags = stability_tags(myitem),
+                       name = myitem.name.unwrap(),
+                       stab_tags = stability_tags(myitem, cx),
                        docs = MarkdownSummaryLine(doc_value, &myitem.links()).to_string(),
                        class = myitem.type_(),
                        add = add,
                        stab = stab.unwrap_or_else(|| String::new()),
                        unsafety_flag = unsafety_flag,
-                       href = item_path(myitem.type_(), myitem.name.as_ref().unwrap()),
+                       href = item_path(myitem.type_(), &myitem.name.unwrap().as_str()),
                        title = [full_path(cx, myitem), myitem.type_().to_string()]
                                 .iter()
                                 .filter_map(|s| if !s.is_empty() {
@@ -2084,30 +2714,55 @@ fn item_module(w: &mut Buffer, cx: &Context, item: &clean::Item, items: &[clean:
     if curty.is_some() {
         write!(w, "</table>");
     }
+
+    if pages > 1 {
+        write!(w, "<nav class='module-pages'>Pages:");
+        for page in 1..=pages {
+            if page == cx.module_page {
+                write!(w, " <span class='current'>{}</span>", page);
+            } else {
+                write!(w, " <a href='{}'>{}</a>", module_page_file(page), page);
+            }
+        }
+        write!(w, "</nav>");
+    }
 }
 
 /// Render the stability and deprecation tags that are displayed in the item's summary at the
 /// module level.
-fn stability_tags(item: &clean::Item) -> String {
+fn stability_tags(item: &clean::Item, cx: &Context) -> String {
     let mut tags = String::new();
 
-    fn tag_html(class: &str, contents: &str) -> String {
-        format!(r#"<span class="stab {}">{}</span>"#, class, contents)
+    // The title is shown as a tooltip, for the details that don't fit in the tag.
+    fn tag_html(class: &str, title: &str, contents: &str) -> String {
+        if title.is_empty() {
+            format!(r#"<span class="stab {}">{}</span>"#, class, contents)
+        } else {
+            format!(r#"<span class="stab {}" title="{}">{}</span>"#, class, Escape(title), contents)
+        }
     }
 
     // The trailing space after each tag is to space it properly against the rest of the docs.
-    if item.deprecation().is_some() {
+    if let Some(Deprecation { note, since }) = item.deprecation() {
         let mut message = "Deprecated";
+        let mut title = match since {
+            Some(since) => format!("Deprecated since {}", since),
+            None => String::from("Deprecated"),
+        };
         if let Some(ref stab) = item.stability {
             if let Some(ref depr) = stab.deprecation {
                 if let Some(ref since) = depr.since {
                     if !stability::deprecation_in_effect(&since) {
                         message = "Deprecation planned";
+                        title = format!("Deprecating in {}", since);
                     }
                 }
             }
         }
-        tags += &tag_html("deprecated", message);
+        if let Some(note) = note {
+            title.push_str(&format!(": {}", note));
+        }
+        tags += &tag_html("deprecated", &title, message);
     }
 
     if let Some(stab) = item
@@ -2115,15 +2770,25 @@ fn stability_tags(item: &clean::Item) -> String {
         .as_ref()
         .filter(|s| s.level == stability::Unstable)
     {
-        if stab.feature.as_ref().map(|s| &**s) == Some("rustc_private") {
-            tags += &tag_html("internal", "Internal");
-        } else {
-            tags += &tag_html("unstable", "Experimental");
+        match stab.feature {
+            Some(ref feature) if feature == "rustc_private" => {
+                tags += &tag_html("internal", "", "Internal");
+            }
+            Some(ref feature) => {
+                let title = match stab.issue {
+                    Some(issue) => format!("Unstable feature {}, tracked in issue #{}",
+                                           feature, issue),
+                    None => format!("Unstable feature {}", feature),
+                };
+                tags += &tag_html("unstable", &title,
+                                  &format!("Experimental ({})", Escape(feature)));
+            }
+            None => tags += &tag_html("unstable", "", "Experimental"),
         }
     }
 
     if let Some(ref cfg) = item.attrs.cfg {
-        tags += &tag_html("portability", &cfg.render_short_html());
+        tags += &tag_html("portability", "", &cfg.render_short_html(&cx.shared.cfg_names));
     }
 
     tags
@@ -2189,6 +2854,10 @@ fn short_stability(item: &clean::Item, cx: &Context) -> Vec<String> {
             message.push_str(&format!(" ({})", feature));
         }
 
+        if let Some(trait_) = &stab.inherited_from {
+            message.push_str(&format!(" Unstable via trait <code>{}</code>.", Escape(trait_)));
+        }
+
         if let Some(unstable_reason) = &stab.unstable_reason {
             // Provide a more informative message than the compiler help.
             let unstable_reason = if is_rustc_private {
@@ -2226,7 +2895,7 @@ fn short_stability(item: &clean::Item, cx: &Context) -> Vec<String> {
     if let Some(ref cfg) = item.attrs.cfg {
         stability.push(format!(
             "<div class='stab portability'>{}</div>",
-            cfg.render_long_html()
+            cfg.render_long_html(&cx.shared.cfg_names)
         ));
     }
 
@@ -2237,10 +2906,14 @@ fn item_constant(w: &mut Buffer, cx: &Context, it: &clean::Item, c: &clean::Cons
     write!(w, "<pre class='rust const'>");
     render_attributes(w, it, false);
     write!(w, "{vis}const \
-               {name}: {typ}</pre>",
+               {name}: {typ}",
            vis = it.visibility.print_with_space(),
            name = it.name.as_ref().unwrap(),
            typ = c.type_.print());
+    if let Some(ref value) = c.value {
+        write!(w, " = {};", Escape(value));
+    }
+    write!(w, "</pre>");
     document(w, cx, it)
 }
 
@@ -2248,11 +2921,15 @@ fn item_static(w: &mut Buffer, cx: &Context, it: &clean::Item, s: &clean::Static
     write!(w, "<pre class='rust static'>");
     render_attributes(w, it, false);
     write!(w, "{vis}static {mutability}\
-               {name}: {typ}</pre>",
+               {name}: {typ}",
            vis = it.visibility.print_with_space(),
            mutability = s.mutability.print_with_space(),
            name = it.name.as_ref().unwrap(),
            typ = s.type_.print());
+    if let Some(ref value) = s.value {
+        write!(w, " = {};", Escape(value));
+    }
+    write!(w, "</pre>");
     document(w, cx, it)
 }
 
@@ -2290,7 +2967,7 @@ fn item_function(w: &mut Buffer, cx: &Context, it: &clean::Item, f: &clean::Func
 }
 
 fn render_implementor(cx: &Context, implementor: &Impl, w: &mut Buffer,
-                      implementor_dups: &FxHashMap<&str, (DefId, bool)>) {
+                      implementor_dups: &FxHashMap<Symbol, (DefId, bool)>) {
     // If there's already another implementor that has the same abbridged name, use the
     // full path, for example in `std::iter::ExactSizeIterator`
     let use_absolute = match implementor.inner_impl().for_ {
@@ -2298,7 +2975,7 @@ fn render_implementor(cx: &Context, implementor: &Impl, w: &mut Buffer,
         clean::BorrowedRef {
             type_: box clean::ResolvedPath { ref path, is_generic: false, .. },
             ..
-        } => implementor_dups[path.last_name()].1,
+        } => implementor_dups[&path.last_name()].1,
         _ => false,
     };
     render_impl(w, cx, implementor, AssocItemLink::Anchor(None), RenderMode::Normal,
@@ -2317,14 +2994,50 @@ fn render_impls(cx: &Context, w: &mut Buffer,
             } else {
                 Buffer::new()
             };
-            render_impl(&mut buffer, cx, i, assoc_link,
-                        RenderMode::Normal, containing_item.stable_since(),
-                        true, None, false, true);
-            buffer.into_inner()
+            let shared = render_shared_impl(&mut buffer, cx, |buffer, cx| {
+                render_impl(buffer, cx, i, assoc_link,
+                            RenderMode::Normal, containing_item.stable_since(),
+                            true, None, false, true)
+            });
+            let buffer = buffer.into_inner();
+            // The impls are sorted by their content, not by the script loading them.
+            (shared.unwrap_or_else(|| buffer.clone()), buffer)
         })
         .collect::<Vec<_>>();
     impls.sort();
-    w.write_str(&impls.join(""));
+    for (_, impl_) in impls {
+        w.write_str(&impl_);
+    }
+}
+
+/// Renders an impl block with `render`. With `--deduplicate-impls`, the block is rendered with
+/// links relative to the documentation root instead and recorded in the file shared by the pages
+/// showing it identically, and `w` gets the script loading it. The block is then returned.
+fn render_shared_impl<F>(w: &mut Buffer, cx: &Context, render: F) -> Option<String>
+    where F: FnOnce(&mut Buffer, &Context)
+{
+    let fragments = match cx.shared.impl_fragments {
+        Some(ref fragments) if w.is_for_html() => fragments,
+        _ => {
+            render(w, cx);
+            return None;
+        }
+    };
+
+    let mut root_cx = cx.clone();
+    root_cx.current.clear();
+    let depth = CURRENT_DEPTH.with(|slot| slot.replace(0));
+    let mut block = Buffer::html();
+    render(&mut block, &root_cx);
+    CURRENT_DEPTH.with(|slot| slot.set(depth));
+    // The ids of the block were derived from the ones of the page, which must not reuse them.
+    *cx.id_map.borrow_mut() = root_cx.id_map.into_inner();
+    let block = block.into_inner();
+
+    let file_name = fragments.record(block.clone());
+    write!(w, "<script src=\"{root}{krate}/impls/{file}\" data-root-path=\"{root}\"></script>",
+           root = cx.root_path(), krate = cx.shared.layout.krate, file = file_name);
+    Some(block)
 }
 
 fn bounds(t_bounds: &[clean::GenericBound], trait_alias: bool) -> String {
@@ -2445,15 +3158,38 @@ fn item_trait(
             </h2>{2}", id, title, extra_content)
     }
 
+    /// Writes the heading of the list of implementors of the group `kind` and opens the list.
+    /// The groups without implementors are hidden until the implementors from other crates are
+    /// added to them.
+    fn write_implementors_header(
+        w: &mut Buffer,
+        kind: ImplementorKind,
+        t: &clean::Trait,
+        count: usize,
+    ) {
+        let (id, title) = implementors_section(kind, t);
+        let hidden = if count == 0 && kind != ImplementorKind::Concrete &&
+                        !(t.auto && kind == ImplementorKind::Blanket) {
+            " hidden"
+        } else {
+            ""
+        };
+        write!(w, "
+            <h2 id='{id}' class='small-section-header{hidden}'>\
+              {title} <span class='count'>({count})</span><a href='#{id}' class='anchor'></a>\
+            </h2><div class='item-list{hidden}' id='{id}-list'>",
+               id = id, title = title, count = count, hidden = hidden);
+    }
+
     fn write_loading_content(w: &mut Buffer, extra_content: &str) {
         write!(w, "{}<span class='loading-content'>Loading content...</span>", extra_content)
     }
 
     fn trait_item(w: &mut Buffer, cx: &Context, m: &clean::Item, t: &clean::Item) {
-        let name = m.name.as_ref().unwrap();
+        let name = m.name.unwrap().as_str();
         let item_type = m.type_();
-        let id = cx.derive_id(format!("{}.{}", item_type, name));
-        let ns_id = cx.derive_id(format!("{}.{}", name, item_type.name_space()));
+        let id = cx.derive_id(item_anchor(item_type, &name));
+        let ns_id = cx.derive_id(namespace_anchor(item_type, &name));
         write!(w, "<h3 id='{id}' class='method'>{extra}<code id='{ns_id}'>",
                extra = render_spotlight_traits(m),
                id = id,
@@ -2463,6 +3199,9 @@ fn item_trait(
         render_stability_since(w, m, t);
         write!(w, "</h3>");
         document(w, cx, m);
+        if let clean::MethodItem(clean::Method { default_impl: Some(ref src), .. }) = m.inner {
+            document_default_impl(w, src);
+        }
     }
 
     if !types.is_empty() {
@@ -2509,7 +3248,7 @@ fn item_trait(
     if let Some(implementors) = cx.cache.implementors.get(&it.def_id) {
         // The DefId is for the first Type found with that name. The bool is
         // if any Types with the same name but different DefId have been found.
-        let mut implementor_dups: FxHashMap<&str, (DefId, bool)> = FxHashMap::default();
+        let mut implementor_dups: FxHashMap<Symbol, (DefId, bool)> = FxHashMap::default();
         for implementor in implementors {
             match implementor.inner_impl().for_ {
                 clean::ResolvedPath { ref path, did, is_generic: false, .. } |
@@ -2532,11 +3271,19 @@ fn item_trait(
                                          .map_or(true, |d| cx.cache.paths.contains_key(&d)));
 
 
-        let (mut synthetic, mut concrete): (Vec<&&Impl>, Vec<&&Impl>) = local.iter()
-            .partition(|i| i.inner_impl().synthetic);
-
-        synthetic.sort_by(compare_impl);
+        let mut concrete = vec![];
+        let mut generic = vec![];
+        let mut blanket = vec![];
+        for implementor in &local {
+            match ImplementorKind::of(implementor.inner_impl()) {
+                ImplementorKind::Concrete => concrete.push(implementor),
+                ImplementorKind::Generic => generic.push(implementor),
+                ImplementorKind::Blanket => blanket.push(implementor),
+            }
+        }
         concrete.sort_by(compare_impl);
+        generic.sort_by(compare_impl);
+        blanket.sort_by(compare_impl);
 
         if !foreign.is_empty() {
             write_small_section_header(w, "foreign-impls", "Implementations on Foreign Types", "");
@@ -2553,34 +3300,26 @@ fn item_trait(
             write_loading_content(w, "");
         }
 
-        write_small_section_header(w, "implementors", "Implementors",
-                                   "<div class='item-list' id='implementors-list'>");
-        for implementor in concrete {
-            render_implementor(cx, implementor, w, &implementor_dups);
-        }
-        write_loading_content(w, "</div>");
-
-        if t.auto {
-            write_small_section_header(w, "synthetic-implementors", "Auto implementors",
-                                       "<div class='item-list' id='synthetic-implementors-list'>");
-            for implementor in synthetic {
-                synthetic_types.extend(
-                    collect_paths_for_type(implementor.inner_impl().for_.clone())
-                );
+        for &(kind, ref implementors) in &[(ImplementorKind::Concrete, concrete),
+                                           (ImplementorKind::Generic, generic),
+                                           (ImplementorKind::Blanket, blanket)] {
+            write_implementors_header(w, kind, t, implementors.len());
+            for implementor in implementors {
+                if implementor.inner_impl().synthetic {
+                    synthetic_types.extend(
+                        collect_paths_for_type(implementor.inner_impl().for_.clone())
+                    );
+                }
                 render_implementor(cx, implementor, w, &implementor_dups);
             }
             write_loading_content(w, "</div>");
         }
     } else {
-        // even without any implementations to write in, we still want the heading and list, so the
-        // implementors javascript file pulled in below has somewhere to write the impls into
-        write_small_section_header(w, "implementors", "Implementors",
-                                   "<div class='item-list' id='implementors-list'>");
-        write_loading_content(w, "</div>");
-
-        if t.auto {
-            write_small_section_header(w, "synthetic-implementors", "Auto implementors",
-                                       "<div class='item-list' id='synthetic-implementors-list'>");
+        // even without any implementations to write in, we still want the headings and lists, so
+        // the implementors javascript file pulled in below has somewhere to write the impls into
+        let kinds = [ImplementorKind::Concrete, ImplementorKind::Generic, ImplementorKind::Blanket];
+        for &kind in &kinds {
+            write_implementors_header(w, kind, t, 0);
             write_loading_content(w, "</div>");
         }
     }
@@ -2604,6 +3343,19 @@ fn item_trait(
            name = *it.name.as_ref().unwrap());
 }
 
+/// Returns the id and the title of the section of a trait page listing the implementors of the
+/// group `kind`.
+fn implementors_section(kind: ImplementorKind, t: &clean::Trait) -> (&'static str, &'static str) {
+    match kind {
+        ImplementorKind::Concrete => ("implementors", "Implementors"),
+        ImplementorKind::Generic => ("generic-implementors", "Generic implementors"),
+        ImplementorKind::Blanket if t.auto => {
+            ("synthetic-implementors", "Auto and blanket implementors")
+        }
+        ImplementorKind::Blanket => ("synthetic-implementors", "Blanket implementors"),
+    }
+}
+
 fn naive_assoc_href(it: &clean::Item, link: AssocItemLink<'_>) -> String {
     use crate::html::item_type::ItemType::*;
 
@@ -2685,7 +3437,7 @@ fn render_assoc_item(w: &mut Buffer,
             AssocItemLink::GotoSource(did, provided_methods) => {
                 // We're creating a link from an impl-item to the corresponding
                 // trait-item and need to map the anchored type accordingly.
-                let ty = if provided_methods.contains(name) {
+                let ty = if provided_methods.contains(&*name.as_str()) {
                     ItemType::Method
                 } else {
                     ItemType::TyMethod
@@ -2784,12 +3536,10 @@ fn item_struct(w: &mut Buffer, cx: &Context, it: &clean::Item, s: &clean::Struct
                        document_non_exhaustive_header(it));
             document_non_exhaustive(w, it);
             for (field, ty) in fields {
-                let id = cx.derive_id(format!("{}.{}",
-                                           ItemType::StructField,
-                                           field.name.as_ref().unwrap()));
-                let ns_id = cx.derive_id(format!("{}.{}",
-                                              field.name.as_ref().unwrap(),
-                                              ItemType::StructField.name_space()));
+                let id = cx.derive_id(item_anchor(ItemType::StructField,
+                                                  &field.name.unwrap().as_str()));
+                let ns_id = cx.derive_id(namespace_anchor(ItemType::StructField,
+                                                          &field.name.unwrap().as_str()));
                 write!(w, "<span id=\"{id}\" class=\"{item_type} small-section-header\">\
                            <a href=\"#{id}\" class=\"anchor field\"></a>\
                            <code id=\"{ns_id}\">{name}: {ty}</code>\
@@ -2803,6 +3553,7 @@ fn item_struct(w: &mut Buffer, cx: &Context, it: &clean::Item, s: &clean::Struct
             }
         }
     }
+    document_type_layout(w, s.layout.as_ref());
     render_assoc_items(w, cx, it, it.def_id, AssocItemRender::All)
 }
 
@@ -2830,8 +3581,8 @@ fn item_union(w: &mut Buffer, cx: &Context, it: &clean::Item, s: &clean::Union)
         write!(w, "<h2 id='fields' class='fields small-section-header'>
                    Fields<a href='#fields' class='anchor'></a></h2>");
         for (field, ty) in fields {
-            let name = field.name.as_ref().expect("union field name");
-            let id = format!("{}.{}", ItemType::StructField, name);
+            let name = field.name.expect("union field name");
+            let id = item_anchor(ItemType::StructField, &name.as_str());
             write!(w, "<span id=\"{id}\" class=\"{shortty} small-section-header\">\
                            <a href=\"#{id}\" class=\"anchor field\"></a>\
                            <code>{name}: {ty}</code>\
@@ -2847,6 +3598,7 @@ fn item_union(w: &mut Buffer, cx: &Context, it: &clean::Item, s: &clean::Union)
             document(w, cx, field);
         }
     }
+    document_type_layout(w, s.layout.as_ref());
     render_assoc_items(w, cx, it, it.def_id, AssocItemRender::All)
 }
 
@@ -2911,12 +3663,10 @@ fn item_enum(w: &mut Buffer, cx: &Context, it: &clean::Item, e: &clean::Enum) {
                    document_non_exhaustive_header(it));
         document_non_exhaustive(w, it);
         for variant in &e.variants {
-            let id = cx.derive_id(format!("{}.{}",
-                                       ItemType::Variant,
-                                       variant.name.as_ref().unwrap()));
-            let ns_id = cx.derive_id(format!("{}.{}",
-                                          variant.name.as_ref().unwrap(),
-                                          ItemType::Variant.name_space()));
+            let id = cx.derive_id(item_anchor(ItemType::Variant,
+                                              &variant.name.unwrap().as_str()));
+            let ns_id = cx.derive_id(namespace_anchor(ItemType::Variant,
+                                                      &variant.name.unwrap().as_str()));
             write!(w, "<div id=\"{id}\" class=\"variant small-section-header\">\
                        <a href=\"#{id}\" class=\"anchor field\"></a>\
                        <code id='{ns_id}'>{name}",
@@ -2943,9 +3693,9 @@ fn item_enum(w: &mut Buffer, cx: &Context, it: &clean::Item, e: &clean::Enum) {
             if let clean::VariantItem(Variant {
                 kind: VariantKind::Struct(ref s)
             }) = variant.inner {
-                let variant_id = cx.derive_id(format!("{}.{}.fields",
-                                                   ItemType::Variant,
-                                                   variant.name.as_ref().unwrap()));
+                let variant_id = cx.derive_id(format!("{}.fields",
+                                                   item_anchor(ItemType::Variant,
+                                                               &variant.name.unwrap().as_str())));
                 write!(w, "<div class='autohide sub-variant' id='{id}'>",
                        id = variant_id);
                 write!(w, "<h3>Fields of <b>{name}</b></h3><div>",
@@ -2953,9 +3703,8 @@ fn item_enum(w: &mut Buffer, cx: &Context, it: &clean::Item, e: &clean::Enum) {
                 for field in &s.fields {
                     use crate::clean::StructFieldItem;
                     if let StructFieldItem(ref ty) = field.inner {
-                        let id = cx.derive_id(format!("variant.{}.field.{}",
-                                                   variant.name.as_ref().unwrap(),
-                                                   field.name.as_ref().unwrap()));
+                        let id = cx.derive_id(variant_field_anchor(&variant.name.unwrap().as_str(),
+                                                                   &field.name.unwrap().as_str()));
                         let ns_id = cx.derive_id(format!("{}.{}.{}.{}",
                                                       variant.name.as_ref().unwrap(),
                                                       ItemType::Variant.name_space(),
@@ -2977,41 +3726,59 @@ fn item_enum(w: &mut Buffer, cx: &Context, it: &clean::Item, e: &clean::Enum) {
             render_stability_since(w, variant, it);
         }
     }
+    document_type_layout(w, e.layout.as_ref());
     render_assoc_items(w, cx, it, it.def_id, AssocItemRender::All)
 }
 
-fn render_attribute(attr: &ast::MetaItem) -> Option<String> {
-    let path = pprust::path_to_string(&attr.path);
-
-    if attr.is_word() {
-        Some(path)
-    } else if let Some(v) = attr.value_str() {
-        Some(format!("{} = {:?}", path, v))
-    } else if let Some(values) = attr.meta_item_list() {
-        let display: Vec<_> = values.iter().filter_map(|attr| {
-            attr.meta_item().and_then(|mi| render_attribute(mi))
-        }).collect();
+/// Writes the "Layout" section computed with `--show-type-layout`.
+fn document_type_layout(w: &mut Buffer, layout: Option<&clean::TypeLayout>) {
+    fn bytes(n: u64) -> String {
+        if n == 1 { "1 byte".to_string() } else { format!("{} bytes", n) }
+    }
 
-        if display.len() > 0 {
-            Some(format!("{}({})", path, display.join(", ")))
-        } else {
-            None
+    let layout = match layout {
+        Some(layout) => layout,
+        None => return,
+    };
+    write!(w, "<h2 id='layout' class='layout small-section-header'>\
+               Layout<a href='#layout' class='anchor'></a></h2>");
+    write!(w, "<div class='docblock type-layout'>");
+    match *layout {
+        clean::TypeLayout::Known { size, align, ref fields, ref variants, niches } => {
+            write!(w, "<p><strong>Note:</strong> Most layout information is \
+                       <strong>completely unstable</strong> and may differ between compiler \
+                       versions and targets. The only exception is types with certain \
+                       <code>repr(...)</code> attributes.</p>");
+            write!(w, "<p><strong>Size:</strong> {}</p>", bytes(size));
+            write!(w, "<p><strong>Alignment:</strong> {}</p>", bytes(align));
+            if niches > 0 {
+                write!(w, "<p><strong>Niches:</strong> {} invalid value{} an enclosing enum \
+                           can use for its discriminant</p>",
+                       niches, if niches == 1 { "" } else { "s" });
+            }
+            if !fields.is_empty() {
+                write!(w, "<p><strong>Field offsets:</strong></p><ul>");
+                for (name, offset) in fields {
+                    write!(w, "<li><code>{}</code>: {}</li>", name, bytes(*offset));
+                }
+                write!(w, "</ul>");
+            }
+            if !variants.is_empty() {
+                write!(w, "<p><strong>Size of each variant:</strong></p><ul>");
+                for (name, size) in variants {
+                    write!(w, "<li><code>{}</code>: {}</li>", name, bytes(*size));
+                }
+                write!(w, "</ul>");
+            }
+        }
+        clean::TypeLayout::Unknown => {
+            write!(w, "<p><strong>Note:</strong> The layout of this type could not be computed, \
+                       most likely because it depends on its generic parameters.</p>");
         }
-    } else {
-        None
     }
+    write!(w, "</div>");
 }
 
-const ATTRIBUTE_WHITELIST: &'static [Symbol] = &[
-    sym::export_name,
-    sym::lang,
-    sym::link_section,
-    sym::must_use,
-    sym::no_mangle,
-    sym::repr,
-    sym::non_exhaustive
-];
-
 // The `top` parameter is used when generating the item declaration to ensure it doesn't have a
 // left padding. For example:
 //
@@ -3023,13 +3790,8 @@ const ATTRIBUTE_WHITELIST: &'static [Symbol] = &[
 fn render_attributes(w: &mut Buffer, it: &clean::Item, top: bool) {
     let mut attrs = String::new();
 
-    for attr in &it.attrs.other_attrs {
-        if !ATTRIBUTE_WHITELIST.contains(&attr.name_or_empty()) {
-            continue;
-        }
-        if let Some(s) = render_attribute(&attr.meta().unwrap()) {
-            attrs.push_str(&format!("#[{}]\n", s));
-        }
+    for attr in &it.attrs.displayed {
+        attrs.push_str(&format!("#[{}]\n", Escape(attr)));
     }
     if attrs.len() > 0 {
         write!(w, "<span class=\"docblock attributes{}\">{}</span>",
@@ -3160,7 +3922,8 @@ impl<'a> AssocItemLink<'a> {
 
 enum AssocItemRender<'a> {
     All,
-    DerefFor { trait_: &'a clean::Type, type_: &'a clean::Type, deref_mut_: bool }
+    DerefFor { trait_: &'a clean::Type, type_: &'a clean::Type, deref_mut_: bool,
+               via: Option<&'a clean::Type> }
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -3192,7 +3955,7 @@ fn render_assoc_items(w: &mut Buffer,
                 ");
                 RenderMode::Normal
             }
-            AssocItemRender::DerefFor { trait_, type_, deref_mut_ } => {
+            AssocItemRender::DerefFor { trait_, type_, deref_mut_, via: None } => {
                 write!(w, "\
                     <h2 id='deref-methods' class='small-section-header'>\
                       Methods from {}&lt;Target = {}&gt;\
@@ -3201,25 +3964,36 @@ fn render_assoc_items(w: &mut Buffer,
                 ", trait_.print(), type_.print());
                 RenderMode::ForDeref { mut_: deref_mut_ }
             }
+            AssocItemRender::DerefFor { trait_, type_, deref_mut_, via: Some(via) } => {
+                let id = deref_methods_id(type_, Some(via));
+                write!(w, "\
+                    <h2 id='{id}' class='small-section-header'>\
+                      Methods from {via}: {trait_}&lt;Target = {type_}&gt;\
+                      <a href='#{id}' class='anchor'></a>\
+                    </h2>\
+                ", id = id, via = via.print(), trait_ = trait_.print(), type_ = type_.print());
+                RenderMode::ForDeref { mut_: deref_mut_ }
+            }
         };
         for i in &non_trait {
-            render_impl(w, cx, i, AssocItemLink::Anchor(None), render_mode,
-                        containing_item.stable_since(), true, None, false, true);
+            let render = |w: &mut Buffer, cx: &Context| {
+                render_impl(w, cx, i, AssocItemLink::Anchor(None), render_mode,
+                            containing_item.stable_since(), true, None, false, true)
+            };
+            // The methods of a `Deref` target show up on the page of every type dereferencing
+            // to it.
+            if let RenderMode::ForDeref { .. } = render_mode {
+                render_shared_impl(w, cx, render);
+            } else {
+                render(w, cx);
+            }
         }
     }
     if let AssocItemRender::DerefFor { .. } = what {
         return;
     }
     if !traits.is_empty() {
-        let deref_impl = traits.iter().find(|t| {
-            t.inner_impl().trait_.def_id() == c.deref_trait_did
-        });
-        if let Some(impl_) = deref_impl {
-            let has_deref_mut = traits.iter().find(|t| {
-                t.inner_impl().trait_.def_id() == c.deref_mut_trait_did
-            }).is_some();
-            render_deref_methods(w, cx, impl_, containing_item, has_deref_mut);
-        }
+        render_deref_methods(w, cx, containing_item, it);
 
         let (synthetic, concrete): (Vec<&&Impl>, Vec<&&Impl>) = traits
             .iter()
@@ -3265,25 +4039,79 @@ fn render_assoc_items(w: &mut Buffer,
     }
 }
 
-fn render_deref_methods(w: &mut Buffer, cx: &Context, impl_: &Impl,
-                        container_item: &clean::Item, deref_mut: bool) {
-    let deref_type = impl_.inner_impl().trait_.as_ref().unwrap();
-    let target = impl_.inner_impl().items.iter().filter_map(|item| {
-        match item.inner {
-            clean::TypedefItem(ref t, true) => Some(&t.type_),
-            _ => None,
-        }
-    }).next().expect("Expected associated type binding");
-    let what = AssocItemRender::DerefFor { trait_: deref_type, type_: target,
-                                           deref_mut_: deref_mut };
-    if let Some(did) = target.def_id() {
-        render_assoc_items(w, cx, container_item, did, what)
-    } else {
-        if let Some(prim) = target.primitive_type() {
-            if let Some(&did) = cx.cache.primitive_locations.get(&prim) {
-                render_assoc_items(w, cx, container_item, did, what);
+/// Renders the methods reachable from the item `did` through its `Deref` impl, then through the
+/// `Deref` impl of the target, and so on, one section per step.
+fn render_deref_methods(w: &mut Buffer, cx: &Context, container_item: &clean::Item, did: DefId) {
+    for hop in deref_chain(&cx.cache, did) {
+        let what = AssocItemRender::DerefFor {
+            trait_: hop.impl_.inner_impl().trait_.as_ref().unwrap(),
+            type_: hop.target,
+            deref_mut_: hop.deref_mut,
+            via: hop.via,
+        };
+        render_assoc_items(w, cx, container_item, hop.target_did, what);
+    }
+}
+
+/// One step of the chain of `Deref` impls starting at a documented item.
+struct DerefHop<'a> {
+    /// The `Deref` impl.
+    impl_: &'a Impl,
+    target: &'a clean::Type,
+    /// The item the methods of `target` are cached under.
+    target_did: DefId,
+    /// Whether every impl of the chain up to this one comes with a `DerefMut` impl, so that the
+    /// `&mut self` methods of `target` can be called.
+    deref_mut: bool,
+    /// The type the impl is for, unless it's the documented item itself.
+    via: Option<&'a clean::Type>,
+}
+
+/// Follows the `Deref` impls starting at the item `did`, until a type without one. Stops at the
+/// first type seen twice too, since a `Deref` cycle would otherwise never end.
+fn deref_chain(cache: &Cache, did: DefId) -> Vec<DerefHop<'_>> {
+    let mut chain = Vec::new();
+    let mut visited = FxHashSet::default();
+    visited.insert(did);
+    let mut did = did;
+    let mut deref_mut = true;
+    let mut via = None;
+    while let Some(impls) = cache.impls.get(&did) {
+        let impl_ = match impls.iter().find(|i| {
+            i.inner_impl().trait_.def_id() == cache.deref_trait_did
+        }) {
+            Some(impl_) => impl_,
+            None => break,
+        };
+        deref_mut &= impls.iter().any(|i| {
+            i.inner_impl().trait_.def_id() == cache.deref_mut_trait_did
+        });
+        let target = impl_.inner_impl().items.iter().filter_map(|item| {
+            match item.inner {
+                clean::TypedefItem(ref t, true) => Some(&t.type_),
+                _ => None,
             }
-        }
+        }).next().expect("Expected associated type binding");
+        let target_did = target.def_id().or_else(|| {
+            target.primitive_type().and_then(|prim| cache.primitive_locations.get(&prim).cloned())
+        });
+        let target_did = match target_did {
+            Some(target_did) if visited.insert(target_did) => target_did,
+            _ => break,
+        };
+        chain.push(DerefHop { impl_, target, target_did, deref_mut, via });
+        did = target_did;
+        via = Some(target);
+    }
+    chain
+}
+
+/// The anchor of the section listing the methods of `target`, reached through the `Deref` impl
+/// of `via`, or of the documented item itself.
+fn deref_methods_id(target: &clean::Type, via: Option<&clean::Type>) -> String {
+    match via {
+        None => "deref-methods".to_string(),
+        Some(_) => format!("deref-methods-{}", small_url_encode(&format!("{:#}", target.print()))),
     }
 }
 
@@ -3334,7 +4162,7 @@ fn spotlight_decl(decl: &clean::FnDecl) -> String {
         if let Some(impls) = c.impls.get(&did) {
             for i in impls {
                 let impl_ = i.inner_impl();
-                if impl_.trait_.def_id().map_or(false, |d| c.traits[&d].is_spotlight) {
+                if impl_.trait_.def_id().map_or(false, |d| c.notable_traits.contains(&d)) {
                     if out.is_empty() {
                         out.push_str(
                             &format!("<h3 class=\"important\">Important traits for {}</h3>\
@@ -3418,7 +4246,9 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
             let mut ids = cx.id_map.borrow_mut();
             write!(w, "<div class='docblock'>{}</div>",
                    Markdown(&*dox, &i.impl_item.links(), &mut ids,
-                            cx.shared.codes, cx.shared.edition, &cx.shared.playground).to_string());
+                            cx.shared.codes, cx.shared.edition, &cx.shared.playground,
+                            cx.shared.hidden_line_prefix.as_ref().map(|prefix| &**prefix))
+                       .to_string());
         }
     }
 
@@ -3427,7 +4257,7 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
                      is_default_item: bool, outer_version: Option<&str>,
                      trait_: Option<&clean::Trait>, show_def_docs: bool) {
         let item_type = item.type_();
-        let name = item.name.as_ref().unwrap();
+        let name = item.name.unwrap().as_str();
 
         let render_method_item = match render_mode {
             RenderMode::Normal => true,
@@ -3447,9 +4277,8 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
             clean::TyMethodItem(clean::TyMethod { ref decl, .. }) => {
                 // Only render when the method is not static or we allow static methods
                 if render_method_item {
-                    let id = cx.derive_id(format!("{}.{}", item_type, name));
-                    let ns_id = cx.derive_id(format!("{}.{}",
-                            name, item_type.name_space()));
+                    let id = cx.derive_id(item_anchor(item_type, &name));
+                    let ns_id = cx.derive_id(namespace_anchor(item_type, &name));
                     write!(w, "<h4 id='{}' class=\"{}{}\">",
                         id, item_type, extra_class);
                     write!(w, "{}", spotlight_decl(decl));
@@ -3465,16 +4294,16 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
                 }
             }
             clean::TypedefItem(ref tydef, _) => {
-                let id = cx.derive_id(format!("{}.{}", ItemType::AssocType, name));
-                let ns_id = cx.derive_id(format!("{}.{}", name, item_type.name_space()));
+                let id = cx.derive_id(item_anchor(ItemType::AssocType, &name));
+                let ns_id = cx.derive_id(namespace_anchor(item_type, &name));
                 write!(w, "<h4 id='{}' class=\"{}{}\">", id, item_type, extra_class);
                 write!(w, "<code id='{}'>", ns_id);
                 assoc_type(w, item, &Vec::new(), Some(&tydef.type_), link.anchor(&id), "");
                 write!(w, "</code></h4>");
             }
             clean::AssocConstItem(ref ty, ref default) => {
-                let id = cx.derive_id(format!("{}.{}", item_type, name));
-                let ns_id = cx.derive_id(format!("{}.{}", name, item_type.name_space()));
+                let id = cx.derive_id(item_anchor(item_type, &name));
+                let ns_id = cx.derive_id(namespace_anchor(item_type, &name));
                 write!(w, "<h4 id='{}' class=\"{}{}\">", id, item_type, extra_class);
                 write!(w, "<code id='{}'>", ns_id);
                 assoc_const(w, item, ty, default.as_ref(), link.anchor(&id), "");
@@ -3487,8 +4316,8 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
                 write!(w, "</h4>");
             }
             clean::AssocTypeItem(ref bounds, ref default) => {
-                let id = cx.derive_id(format!("{}.{}", item_type, name));
-                let ns_id = cx.derive_id(format!("{}.{}", name, item_type.name_space()));
+                let id = cx.derive_id(item_anchor(item_type, &name));
+                let ns_id = cx.derive_id(namespace_anchor(item_type, &name));
                 write!(w, "<h4 id='{}' class=\"{}{}\">", id, item_type, extra_class);
                 write!(w, "<code id='{}'>", ns_id);
                 assoc_type(w, item, bounds, default.as_ref(), link.anchor(&id), "");
@@ -3502,11 +4331,21 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
             if !is_default_item {
                 if let Some(t) = trait_ {
                     // The trait item may have been stripped so we might not
-                    // find any documentation or stability for it.
+                    // find any documentation for it.
                     if let Some(it) = t.items.iter().find(|i| i.name == item.name) {
-                        // We need the stability of the item from the trait
-                        // because impls can't have a stability.
-                        document_stability(w, cx, it, is_hidden);
+                        // Impl items are given the stability of their trait item by the
+                        // `propagate-stability` pass, unless it did not run. They are available
+                        // wherever both the impl and the trait item are.
+                        let mut stab_item =
+                            (if item.stability.is_some() { item } else { it }).clone();
+                        stab_item.attrs.cfg = match (&item.attrs.cfg, &it.attrs.cfg) {
+                            (Some(cfg), Some(trait_cfg)) => {
+                                Some(Arc::new(Cfg::clone(cfg) & Cfg::clone(trait_cfg)))
+                            }
+                            (cfg, trait_cfg) => cfg.clone().or_else(|| trait_cfg.clone()),
+                        };
+                        document_stability(w, cx, &stab_item, is_hidden);
+                        document_aliases(w, item, is_hidden);
                         if item.doc_value().is_some() {
                             document_full(w, item, cx, "", is_hidden);
                         } else if show_def_docs {
@@ -3517,12 +4356,14 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
                     }
                 } else {
                     document_stability(w, cx, item, is_hidden);
+                    document_aliases(w, item, is_hidden);
                     if show_def_docs {
                         document_full(w, item, cx, "", is_hidden);
                     }
                 }
             } else {
                 document_stability(w, cx, item, is_hidden);
+                document_aliases(w, item, is_hidden);
                 if show_def_docs {
                     document_short(w, cx, item, link, "", is_hidden);
                 }
@@ -3547,7 +4388,7 @@ fn render_impl(w: &mut Buffer, cx: &Context, i: &Impl, link: AssocItemLink<'_>,
                             outer_version: Option<&str>,
                             show_def_docs: bool) {
         for trait_item in &t.items {
-            let n = trait_item.name.clone();
+            let n = trait_item.name;
             if i.items.iter().find(|m| m.name == n).is_some() {
                 continue;
             }
@@ -3684,7 +4525,14 @@ fn print_sidebar(cx: &Context, it: &clean::Item, buffer: &mut Buffer) {
     write!(buffer, "<div class=\"sidebar-elems\">");
     if it.is_crate() {
         write!(buffer, "<a id='all-types' href='all.html'><p>See all {}'s items</p></a>",
-                it.name.as_ref().expect("crates always have a name"));
+                it.name.expect("crates always have a name"));
+        if cx.shared.print_page.is_some() {
+            write!(buffer, "<a id='print-page' href='print.html'>\
+                            <p>Printable documentation</p></a>");
+        }
+        if !cx.cache.features.is_empty() {
+            write!(buffer, "<a id='feature-flags' href='features.html'><p>Feature flags</p></a>");
+        }
     }
     match it.inner {
         clean::StructItem(ref s) => sidebar_struct(buffer, it, s),
@@ -3725,7 +4573,7 @@ fn print_sidebar(cx: &Context, it: &clean::Item, buffer: &mut Buffer) {
                 ty: '{ty}', \
                 relpath: '{path}'\
             }};</script>",
-            name = it.name.as_ref().map(|x| &x[..]).unwrap_or(""),
+            name = it.name.unwrap_or(kw::Invalid),
             ty = it.type_(),
             path = relpath);
     if parentlen == 0 {
@@ -3758,7 +4606,7 @@ fn get_methods(
 ) -> Vec<String> {
     i.items.iter().filter_map(|item| {
         match item.name {
-            Some(ref name) if !name.is_empty() && item.is_method() => {
+            Some(name) if name != kw::Invalid && item.is_method() => {
                 if !for_deref || should_render_item(item, deref_mut) {
                     Some(format!("<a href=\"#{}\">{}</a>",
                                  get_next_url(used_links, format!("method.{}", name)),
@@ -3811,40 +4659,34 @@ fn sidebar_assoc_items(it: &clean::Item) -> String {
         }
 
         if v.iter().any(|i| i.inner_impl().trait_.is_some()) {
-            if let Some(impl_) = v.iter()
-                                  .filter(|i| i.inner_impl().trait_.is_some())
-                                  .find(|i| i.inner_impl().trait_.def_id() == c.deref_trait_did) {
-                if let Some(target) = impl_.inner_impl().items.iter().filter_map(|item| {
-                    match item.inner {
-                        clean::TypedefItem(ref t, true) => Some(&t.type_),
-                        _ => None,
-                    }
-                }).next() {
-                    let inner_impl = target.def_id().or(target.primitive_type().and_then(|prim| {
-                        c.primitive_locations.get(&prim).cloned()
-                    })).and_then(|did| c.impls.get(&did));
-                    if let Some(impls) = inner_impl {
-                        out.push_str("<a class=\"sidebar-title\" href=\"#deref-methods\">");
-                        out.push_str(&format!("Methods from {}&lt;Target={}&gt;",
-                            Escape(&format!(
-                                "{:#}", impl_.inner_impl().trait_.as_ref().unwrap().print()
-                            )),
-                            Escape(&format!("{:#}", target.print()))));
-                        out.push_str("</a>");
-                        let mut ret = impls.iter()
-                                           .filter(|i| i.inner_impl().trait_.is_none())
-                                           .flat_map(|i| get_methods(i.inner_impl(),
-                                                                     true,
-                                                                     &mut used_links,
-                                                                     true))
-                                           .collect::<Vec<_>>();
-                        // We want links' order to be reproducible so we don't use unstable sort.
-                        ret.sort();
-                        if !ret.is_empty() {
-                            out.push_str(&format!("<div class=\"sidebar-links\">{}</div>",
-                                                  ret.join("")));
-                        }
-                    }
+            for hop in deref_chain(&c, it.def_id) {
+                let impls = match c.impls.get(&hop.target_did) {
+                    Some(impls) => impls,
+                    None => continue,
+                };
+                out.push_str(&format!("<a class=\"sidebar-title\" href=\"#{}\">Methods from ",
+                                      deref_methods_id(hop.target, hop.via)));
+                if let Some(via) = hop.via {
+                    out.push_str(&format!("{}: ", Escape(&format!("{:#}", via.print()))));
+                }
+                out.push_str(&format!("{}&lt;Target={}&gt;",
+                    Escape(&format!(
+                        "{:#}", hop.impl_.inner_impl().trait_.as_ref().unwrap().print()
+                    )),
+                    Escape(&format!("{:#}", hop.target.print()))));
+                out.push_str("</a>");
+                let mut ret = impls.iter()
+                                   .filter(|i| i.inner_impl().trait_.is_none())
+                                   .flat_map(|i| get_methods(i.inner_impl(),
+                                                             true,
+                                                             &mut used_links,
+                                                             hop.deref_mut))
+                                   .collect::<Vec<_>>();
+                // We want links' order to be reproducible so we don't use unstable sort.
+                ret.sort();
+                if !ret.is_empty() {
+                    out.push_str(&format!("<div class=\"sidebar-links\">{}</div>",
+                                          ret.join("")));
                 }
             }
             let format_impls = |impls: Vec<&Impl>| {
@@ -4051,10 +4893,19 @@ fn sidebar_trait(buf: &mut Buffer, it: &clean::Item, t: &clean::Trait) {
         }
     }
 
-    sidebar.push_str("<a class=\"sidebar-title\" href=\"#implementors\">Implementors</a>");
-    if t.auto {
-        sidebar.push_str("<a class=\"sidebar-title\" \
-                          href=\"#synthetic-implementors\">Auto Implementors</a>");
+    // The groups without implementors in this crate are only linked to if they are shown anyway.
+    let kinds = c.implementors.get(&it.def_id).into_iter().flatten()
+        .filter(|i| i.inner_impl().for_.def_id().map_or(true, |d| c.paths.contains_key(&d)))
+        .map(|i| ImplementorKind::of(i.inner_impl()))
+        .collect::<FxHashSet<_>>();
+    for &kind in &[ImplementorKind::Concrete, ImplementorKind::Generic, ImplementorKind::Blanket] {
+        if kind == ImplementorKind::Concrete || kinds.contains(&kind) ||
+           (t.auto && kind == ImplementorKind::Blanket)
+        {
+            let (id, title) = implementors_section(kind, t);
+            sidebar.push_str(&format!("<a class=\"sidebar-title\" href=\"#{}\">{}</a>",
+                                      id, title));
+        }
     }
 
     sidebar.push_str(&sidebar_assoc_items(it));
@@ -4131,7 +4982,7 @@ fn sidebar_enum(buf: &mut Buffer, it: &clean::Item, e: &clean::Enum) {
     }
 }
 
-fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
+crate fn item_ty_to_strs(ty: &ItemType) -> (&'static str, &'static str) {
     match *ty {
         ItemType::ExternCrate |
         ItemType::Import          => ("reexports", "Re-exports"),
@@ -4211,7 +5062,7 @@ fn item_macro(w: &mut Buffer, cx: &Context, it: &clean::Item, t: &clean::Macro)
 }
 
 fn item_proc_macro(w: &mut Buffer, cx: &Context, it: &clean::Item, m: &clean::ProcMacro) {
-    let name = it.name.as_ref().expect("proc-macros always have names");
+    let name = it.name.expect("proc-macros always have names");
     match m.kind {
         MacroKind::Bang => {
             write!(w, "<pre class='rust macro'>");
@@ -4276,8 +5127,10 @@ fn collect_paths_for_type(first_ty: clean::Type) -> Vec<String> {
 
         match ty {
             clean::Type::ResolvedPath { did, .. } => {
-                let get_extern = || cache.external_paths.get(&did).map(|s| s.0.clone());
-                let fqp = cache.exact_paths.get(&did).cloned().or_else(get_extern);
+                // The public paths of the types are used, which are the same whichever crate
+                // the impl is documented in.
+                let fqp = cache.paths.get(&did).or_else(|| cache.external_paths.get(&did))
+                    .map(|s| s.0.clone());
 
                 match fqp {
                     Some(path) => {
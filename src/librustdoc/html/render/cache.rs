@@ -1,20 +1,48 @@
-use crate::clean::{self, GetDefId, AttributesExt};
+use crate::clean::{self, GetDefId, AttributesExt, NestedAttributesExt};
+use crate::clean::cfg::Cfg;
+use crate::clean::utils::referenced_dids;
 use crate::fold::DocFolder;
+use crate::span_map::SpanMap;
 use rustc::hir::def_id::{CrateNum, CRATE_DEF_INDEX, DefId};
 use rustc::middle::privacy::AccessLevels;
+use rustc_data_structures::fingerprint::Fingerprint;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_data_structures::sync::Lock;
 use std::mem;
 use std::path::{Path, PathBuf};
 use std::collections::BTreeMap;
 use syntax::source_map::FileName;
-use syntax::symbol::sym;
+use syntax::symbol::{Symbol, kw, sym};
 
 use serde::Serialize;
 
-use super::{ItemType, IndexItem, IndexItemFunctionType, Impl, shorten, plain_summary_line};
+use super::{ItemType, IndexItem, IndexItemFunctionType, Impl};
+use super::small_url_encode;
 use super::{Type, RenderInfo};
+use super::facets::{Facets, is_unstable, item_cfg};
+use super::index_entries::IndexEntries;
+use super::name_table::NameTable;
+use super::ranking::{is_deprecated, kind_weights};
+
+/// The number of items whose descriptions are in each of the files the descriptions are split
+/// into with `--shard-search-descriptions`.
+const DESC_SHARD_LEN: usize = 1000;
+
+/// The search index of a crate, see `build_index`.
+pub struct SearchIndex {
+    /// The entry of the crate in the search index of the output directory.
+    pub index: serde_json::Value,
+    /// The content of the files holding the descriptions of the items, in shards of
+    /// `DESC_SHARD_LEN` items, when they are left out of `index` with
+    /// `--shard-search-descriptions`.
+    pub desc_shards: Vec<String>,
+    /// The URL of the documentation of every item, relative to the output directory and by path
+    /// of the item, with `--emit id-map`.
+    pub item_urls: BTreeMap<String, String>,
+}
 
 /// Indicates where an external crate can be found.
+#[derive(Debug)]
 pub enum ExternalLocation {
     /// Remote URL root of the external crate
     Remote(String),
@@ -24,6 +52,102 @@ pub enum ExternalLocation {
     Unknown,
 }
 
+/// A documented item whose signature mentions a type or trait, see `Cache::referenced_by`.
+#[derive(Clone, Debug)]
+pub struct Reference {
+    pub did: DefId,
+    /// The type or trait whose page documents the item, for associated items.
+    pub parent: Option<DefId>,
+    pub name: String,
+    pub ty: ItemType,
+}
+
+/// A documented item gated on a cargo feature, see `Cache::features`.
+#[derive(Clone)]
+pub struct GatedItem {
+    pub did: DefId,
+    /// The type or trait whose page documents the item, for fields, variants and associated
+    /// items.
+    pub parent: Option<DefId>,
+    pub name: String,
+    pub ty: ItemType,
+    /// The whole configuration the item is available on.
+    pub cfg: Cfg,
+}
+
+/// An item found by one of its aliases, see `Cache::aliases`.
+#[derive(Clone)]
+pub(super) struct AliasItem {
+    pub(super) item: IndexItem,
+    /// Whether the alias ranks below the items found by their own name, as given with
+    /// `#[doc(alias("...", priority = "low"))]`.
+    pub(super) low_priority: bool,
+}
+
+/// The groups the implementors of a trait are listed in on its page.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ImplementorKind {
+    /// An impl for a type without parameters, like `impl Trait for Foo`.
+    Concrete,
+    /// An impl for a type built from parameters of the impl, like
+    /// `impl<T: Foo> Trait for Wrapper<T>`.
+    Generic,
+    /// An impl for any type meeting some bounds, like `impl<T: Foo> Trait for T`, or an impl of an
+    /// auto trait which the compiler derives.
+    Blanket,
+}
+
+impl ImplementorKind {
+    pub fn of(impl_: &clean::Impl) -> ImplementorKind {
+        if impl_.synthetic {
+            return ImplementorKind::Blanket;
+        }
+        let mut for_ = &impl_.for_;
+        loop {
+            match *for_ {
+                clean::BorrowedRef { ref type_, .. } |
+                clean::RawPointer(_, ref type_) => for_ = type_,
+                _ => break,
+            }
+        }
+        match *for_ {
+            clean::Generic(_) => ImplementorKind::Blanket,
+            _ if impl_.generics.params.iter()
+                .any(|param| param.kind != clean::GenericParamDefKind::Lifetime) => {
+                ImplementorKind::Generic
+            }
+            _ => ImplementorKind::Concrete,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            ImplementorKind::Concrete => "concrete",
+            ImplementorKind::Generic => "generic",
+            ImplementorKind::Blanket => "blanket",
+        }
+    }
+}
+
+/// How an item documented with `--document-private-items` or `--search-hidden` is hidden from
+/// the users of the crate, which the search tags it with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IndexVisibility {
+    /// The item is not reachable from outside the crate.
+    Private,
+    /// The item, or one of its parents, is `#[doc(hidden)]`.
+    Hidden,
+}
+
+impl IndexVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            IndexVisibility::Private => "private",
+            IndexVisibility::Hidden => "hidden",
+        }
+    }
+}
+
 /// This cache is used to store information about the `clean::Crate` being
 /// rendered in order to provide more useful documentation. This contains
 /// information like all implementors of a trait, all traits a type implements,
@@ -57,13 +181,6 @@ crate struct Cache {
     /// Maps local `DefId`s of exported types to fully qualified paths.
     /// Unlike 'paths', this mapping ignores any renames that occur
     /// due to 'use' statements.
-    ///
-    /// This map is used when writing out the special 'implementors'
-    /// javascript file. By using the exact path that the type
-    /// is declared with, we ensure that each path will be identical
-    /// to the path used if the corresponding type is inlined. By
-    /// doing this, we can detect duplicate impls on a trait page, and only display
-    /// the impl for the inlined type.
     pub exact_paths: FxHashMap<DefId, Vec<String>>,
 
     /// This map contains information about all known traits of this crate.
@@ -91,6 +208,21 @@ crate struct Cache {
     /// The version of the crate being documented, if given from the `--crate-version` flag.
     pub crate_version: Option<String>,
 
+    /// Maps types and traits to the documented items whose signature mentions them, listed in
+    /// the "Used by" section of their page.
+    pub referenced_by: FxHashMap<DefId, Vec<Reference>>,
+
+    /// Maps the cargo features mentioned in the configuration of the documented items to the
+    /// items gated on them, listed on the "Feature flags" page of the crate. An item is not
+    /// listed under the features which already gate an item it belongs to.
+    pub features: BTreeMap<String, Vec<GatedItem>>,
+
+    /// The types formatted so far, rendered once for every depth of the pages they appeared on
+    /// and for both HTML and plain text, since their links and escaping depend on those. They
+    /// are keyed by the fingerprint of the type rather than a copy of it. The same types appear
+    /// in the signatures of many items, see `clean::Type::print`.
+    pub type_fragments: Lock<FxHashMap<(usize, bool, Fingerprint), String>>,
+
     // Private fields only used when initially crawling a crate to build a cache
 
     stack: Vec<String>,
@@ -98,9 +230,22 @@ crate struct Cache {
     parent_is_trait_impl: bool,
     search_index: Vec<IndexItem>,
     stripped_mod: bool,
+    /// Whether the item being folded is a non-canonical copy of an item documented at several
+    /// locations, see `clean::Item::canonical_path`.
+    alias_copy: bool,
+    /// Whether the item being folded, or one of its parents, is `#[doc(hidden)]`.
+    hidden: bool,
+    /// The cargo features gating each of the items being folded, from the crate to the parent of
+    /// the current item.
+    feature_stack: Vec<Vec<Symbol>>,
     pub deref_trait_did: Option<DefId>,
     pub deref_mut_trait_did: Option<DefId>,
     pub owned_box_did: Option<DefId>,
+    /// The traits whose implementors get a "notable traits" popup on the functions returning
+    /// them, see `RenderInfo::notable_traits`.
+    pub notable_traits: FxHashSet<DefId>,
+    /// What the paths in the source files of the crate refer to, see `RenderInfo::span_map`.
+    pub span_map: SpanMap,
     masked_crates: FxHashSet<CrateNum>,
 
     // In rare case where a structure is defined in one module but implemented
@@ -108,7 +253,7 @@ crate struct Cache {
     // then the fully qualified name of the structure isn't presented in `paths`
     // yet when its implementation methods are being indexed. Caches such methods
     // and their parent id here and indexes them at the end of crate parsing.
-    orphan_impl_items: Vec<(DefId, clean::Item)>,
+    orphan_impl_items: Vec<(DefId, clean::Item, Option<IndexVisibility>)>,
 
     // Similarly to `orphan_impl_items`, sometimes trait impls are picked up
     // even though the trait itself is not exported. This can happen if a trait
@@ -119,9 +264,14 @@ crate struct Cache {
     // folding and add them to the cache later on if we find the trait.
     orphan_trait_impls: Vec<(DefId, FxHashSet<DefId>, Impl)>,
 
-    /// Aliases added through `#[doc(alias = "...")]`. Since a few items can have the same alias,
-    /// we need the alias element to have an array of items.
-    pub(super) aliases: FxHashMap<String, Vec<IndexItem>>,
+    /// Aliases added through `#[doc(alias = "...")]`, including those of methods and other
+    /// associated items. Since a few items can have the same alias, we need the alias element to
+    /// have an array of items.
+    pub(super) aliases: FxHashMap<String, Vec<AliasItem>>,
+
+    /// The descriptions of the entries of the search index, reused from the previous run with
+    /// `--incremental-docs`.
+    pub(super) index_entries: IndexEntries,
 }
 
 impl Cache {
@@ -130,7 +280,10 @@ impl Cache {
         extern_html_root_urls: &BTreeMap<String, String>,
         dst: &Path,
         mut krate: clean::Crate,
-    ) -> (clean::Crate, String, Cache) {
+        shard_descriptions: bool,
+        index_entries: IndexEntries,
+        item_urls: bool,
+    ) -> (clean::Crate, SearchIndex, Cache) {
         // Crawl the crate to build various caches used for the output
         let RenderInfo {
             inlined: _,
@@ -140,6 +293,8 @@ impl Cache {
             deref_trait_did,
             deref_mut_trait_did,
             owned_box_did,
+            notable_traits,
+            span_map,
         } = renderinfo;
 
         let external_paths = external_paths.into_iter()
@@ -159,16 +314,25 @@ impl Cache {
             extern_locations: Default::default(),
             primitive_locations: Default::default(),
             stripped_mod: false,
+            alias_copy: false,
+            hidden: false,
+            feature_stack: Vec::new(),
             access_levels,
             crate_version: krate.version.take(),
+            referenced_by: Default::default(),
+            features: Default::default(),
+            type_fragments: Default::default(),
             orphan_impl_items: Vec::new(),
             orphan_trait_impls: Vec::new(),
-            traits: krate.external_traits.replace(Default::default()),
+            traits: mem::take(&mut krate.external_traits),
             deref_trait_did,
             deref_mut_trait_did,
             owned_box_did,
+            notable_traits,
+            span_map,
             masked_crates: mem::take(&mut krate.masked_crates),
             aliases: Default::default(),
+            index_entries,
         };
 
         // Cache where all our extern crates are located
@@ -180,8 +344,8 @@ impl Cache {
                 },
                 _ => PathBuf::new(),
             };
-            let extern_url = extern_html_root_urls.get(&e.name).map(|u| &**u);
-            cache.extern_locations.insert(n, (e.name.clone(), src_root,
+            let extern_url = extern_html_root_urls.get(&*e.name.as_str()).map(|u| &**u);
+            cache.extern_locations.insert(n, (e.name.to_string(), src_root,
                                             extern_location(e, extern_url, &dst)));
 
             let did = DefId { krate: n, index: CRATE_DEF_INDEX };
@@ -201,7 +365,7 @@ impl Cache {
             cache.primitive_locations.insert(prim, def_id);
         }
 
-        cache.stack.push(krate.name.clone());
+        cache.stack.push(krate.name.to_string());
         krate = cache.fold_crate(krate);
 
         for (trait_did, dids, impl_) in cache.orphan_trait_impls.drain(..) {
@@ -213,7 +377,7 @@ impl Cache {
         }
 
         // Build our search index
-        let index = build_index(&krate, &mut cache);
+        let index = build_index(&krate, &mut cache, shard_descriptions, item_urls);
 
         (krate, index, cache)
     }
@@ -234,6 +398,41 @@ impl DocFolder for Cache {
             _ => self.stripped_mod,
         };
 
+        let orig_hidden = self.hidden;
+        if item.attrs.lists(sym::doc).has_word(sym::hidden) {
+            self.hidden = true;
+        }
+
+        // Items documented at several locations are only indexed at their canonical one. The
+        // other locations are recorded as search aliases of it.
+        let orig_alias_copy = self.alias_copy;
+        if let Some(ref canonical) = item.canonical_path {
+            let here = self.stack.iter().cloned()
+                .chain(item.name.map(|name| name.to_string()))
+                .collect::<Vec<_>>();
+            if *canonical != here && !self.stripped_mod {
+                self.alias_copy = true;
+                let name = canonical.last().unwrap().clone();
+                let path = canonical[..canonical.len() - 1].join("::");
+                let alias = IndexItem {
+                    ty: item.type_(),
+                    desc: self.index_entries.desc(&item, &path, &name),
+                    name,
+                    path,
+                    parent: None,
+                    parent_idx: None,
+                    search_type: get_index_search_type(&item),
+                    anchor: None,
+                    deprecated: is_deprecated(&item),
+                    visibility: self.index_visibility(&item),
+                    unstable: is_unstable(&item),
+                    cfg: item_cfg(&item),
+                };
+                self.aliases.entry(here.join("::").to_lowercase()).or_default()
+                    .push(AliasItem { item: alias, low_priority: false });
+            }
+        }
+
         // If the impl is from a masked crate or references something from a
         // masked crate then remove it completely.
         if let clean::ImplItem(ref i) = item.inner {
@@ -261,8 +460,21 @@ impl DocFolder for Cache {
             }
         }
 
-        // Index this method for searching later on.
-        if let Some(ref s) = item.name {
+        // Index this method for searching later on. Items inlined from a masked crate are still
+        // documented, but left out of the search index.
+        let masked = self.masked_crates.contains(&item.def_id.krate);
+        if !masked && !self.alias_copy && !self.stripped_mod && !self.parent_is_trait_impl {
+            self.add_references(&item);
+        }
+        let features = if !masked && !self.alias_copy && !self.stripped_mod &&
+                          !self.parent_is_trait_impl {
+            self.add_features(&item)
+        } else {
+            vec![]
+        };
+        self.feature_stack.push(features);
+        let name = item.name.map(|name| name.to_string());
+        if let Some(s) = name.as_ref().filter(|_| !masked && !self.alias_copy) {
             let (parent, is_inherent_impl_item) = match item.inner {
                 clean::StrippedItem(..) => ((None, None), false),
                 clean::AssocConstItem(..) |
@@ -311,21 +523,30 @@ impl DocFolder for Cache {
                     // which should not be indexed. The crate-item itself is
                     // inserted later on when serializing the search-index.
                     if item.def_id.index != CRATE_DEF_INDEX {
-                        self.search_index.push(IndexItem {
+                        let path = path.join("::");
+                        let index_item = IndexItem {
                             ty: item.type_(),
                             name: s.to_string(),
-                            path: path.join("::"),
-                            desc: shorten(plain_summary_line(item.doc_value())),
+                            desc: self.index_entries.desc(&item, &path, s),
+                            path,
                             parent,
                             parent_idx: None,
                             search_type: get_index_search_type(&item),
-                        });
+                            anchor: None,
+                            deprecated: is_deprecated(&item),
+                            visibility: self.index_visibility(&item),
+                            unstable: is_unstable(&item),
+                            cfg: item_cfg(&item),
+                        };
+                        add_aliases(&mut self.aliases, &item, &index_item);
+                        self.search_index.push(index_item);
                     }
                 }
                 (Some(parent), None) if is_inherent_impl_item => {
                     // We have a parent, but we don't know where they're
                     // defined yet. Wait for later to index this item.
-                    self.orphan_impl_items.push((parent, item.clone()));
+                    let visibility = self.index_visibility(&item);
+                    self.orphan_impl_items.push((parent, item.clone(), visibility));
                 }
                 _ => {}
             }
@@ -333,7 +554,7 @@ impl DocFolder for Cache {
 
         // Keep track of the fully qualified path for this item.
         let pushed = match item.name {
-            Some(ref n) if !n.is_empty() => {
+            Some(n) if n != kw::Invalid => {
                 self.stack.push(n.to_string());
                 true
             }
@@ -348,7 +569,7 @@ impl DocFolder for Cache {
             clean::ConstantItem(..) | clean::StaticItem(..) |
             clean::UnionItem(..) | clean::ForeignTypeItem |
             clean::MacroItem(..) | clean::ProcMacroItem(..)
-            if !self.stripped_mod => {
+            if !self.stripped_mod && !self.alias_copy => {
                 // Re-exported items mean that the same id can show up twice
                 // in the rustdoc ast that we're looking at. We know,
                 // however, that a re-exported item doesn't show up in the
@@ -361,18 +582,16 @@ impl DocFolder for Cache {
                     self.paths.insert(item.def_id,
                                       (self.stack.clone(), item.type_()));
                 }
-                self.add_aliases(&item);
             }
             // Link variants to their parent enum because pages aren't emitted
             // for each variant.
-            clean::VariantItem(..) if !self.stripped_mod => {
+            clean::VariantItem(..) if !self.stripped_mod && !self.alias_copy => {
                 let mut stack = self.stack.clone();
                 stack.pop();
                 self.paths.insert(item.def_id, (stack, ItemType::Enum));
             }
 
             clean::PrimitiveItem(..) => {
-                self.add_aliases(&item);
                 self.paths.insert(item.def_id, (self.stack.clone(),
                                                 item.type_()));
             }
@@ -469,40 +688,113 @@ impl DocFolder for Cache {
 
         if pushed { self.stack.pop().unwrap(); }
         if parent_pushed { self.parent_stack.pop().unwrap(); }
+        self.feature_stack.pop().unwrap();
         self.stripped_mod = orig_stripped_mod;
+        self.alias_copy = orig_alias_copy;
+        self.hidden = orig_hidden;
         self.parent_is_trait_impl = orig_parent_is_trait_impl;
         ret
     }
 }
 
 impl Cache {
-    fn add_aliases(&mut self, item: &clean::Item) {
-        if item.def_id.index == CRATE_DEF_INDEX {
-            return
-        }
-        if let Some(ref item_name) = item.name {
-            let path = self.paths.get(&item.def_id)
-                                 .map(|p| p.0[..p.0.len() - 1].join("::"))
-                                 .unwrap_or("std".to_owned());
-            for alias in item.attrs.lists(sym::doc)
-                                   .filter(|a| a.check_name(sym::alias))
-                                   .filter_map(|a| a.value_str()
-                                                    .map(|s| s.to_string().replace("\"", "")))
-                                   .filter(|v| !v.is_empty())
-                                   .collect::<FxHashSet<_>>()
-                                   .into_iter() {
-                self.aliases.entry(alias)
-                            .or_insert(Vec::with_capacity(1))
-                            .push(IndexItem {
-                                ty: item.type_(),
-                                name: item_name.to_string(),
-                                path: path.clone(),
-                                desc: shorten(plain_summary_line(item.doc_value())),
-                                parent: None,
-                                parent_idx: None,
-                                search_type: get_index_search_type(&item),
-                            });
+    /// Returns how `item` is hidden from the users of the crate, for the search to tag it with.
+    /// The pages of the primitives and keywords are documented on private modules, but are not
+    /// private themselves.
+    fn index_visibility(&self, item: &clean::Item) -> Option<IndexVisibility> {
+        match item.type_() {
+            ItemType::Primitive | ItemType::Keyword => None,
+            _ if item.def_id.is_local() && !self.access_levels.is_exported(item.def_id) => {
+                Some(IndexVisibility::Private)
+            }
+            _ if self.hidden => Some(IndexVisibility::Hidden),
+            _ => None,
+        }
+    }
+
+    /// Records `item` in `features` under the cargo features it is gated on, and returns them.
+    fn add_features(&mut self, item: &clean::Item) -> Vec<Symbol> {
+        let cfg = match item_cfg(item) {
+            Some(cfg) => cfg,
+            None => return vec![],
+        };
+        let features = cfg.features();
+        let name = match (&item.inner, item.name) {
+            (clean::ImplItem(..), _) | (clean::StrippedItem(..), _) => return features,
+            (_, Some(name)) if name != kw::Invalid => name.to_string(),
+            _ => return features,
+        };
+        let parent = match item.inner {
+            clean::StructFieldItem(..) | clean::VariantItem(..) |
+            clean::MethodItem(..) | clean::TyMethodItem(..) |
+            clean::AssocConstItem(..) | clean::AssocTypeItem(..) => {
+                match self.parent_stack.last() {
+                    Some(&parent) => Some(parent),
+                    None => return features,
+                }
+            }
+            _ => None,
+        };
+
+        for &feature in &features {
+            if self.feature_stack.iter().any(|gates| gates.contains(&feature)) {
+                continue;
             }
+            self.features.entry(feature.to_string()).or_default().push(GatedItem {
+                did: item.def_id,
+                parent,
+                name: name.clone(),
+                ty: item.type_(),
+                cfg: cfg.clone(),
+            });
+        }
+        features
+    }
+
+    /// Records the types and traits mentioned in the signature of `item` in `referenced_by`.
+    fn add_references(&mut self, item: &clean::Item) {
+        let name = match item.name {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let types: Vec<&clean::Type> = match item.inner {
+            clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) =>
+                f.all_types.iter().chain(&f.ret_types).collect(),
+            clean::MethodItem(ref m) => m.all_types.iter().chain(&m.ret_types).collect(),
+            clean::TyMethodItem(ref m) => m.all_types.iter().chain(&m.ret_types).collect(),
+            clean::ConstantItem(ref c) => vec![&c.type_],
+            clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => vec![&s.type_],
+            clean::AssocConstItem(ref ty, _) => vec![ty],
+            clean::TypedefItem(ref t, false) => vec![&t.type_],
+            _ => return,
+        };
+        let parent = match item.inner {
+            clean::MethodItem(..) | clean::TyMethodItem(..) | clean::AssocConstItem(..) => {
+                match self.parent_stack.last() {
+                    Some(&parent) => Some(parent),
+                    None => return,
+                }
+            }
+            _ => None,
+        };
+
+        let mut dids = FxHashSet::default();
+        for ty in types {
+            referenced_dids(ty, &mut dids);
+        }
+        // An item mentioning the type it belongs to, like a constructor, is already listed on
+        // the page of that type.
+        dids.remove(&item.def_id);
+        if let Some(parent) = parent {
+            dids.remove(&parent);
+        }
+        for did in dids {
+            self.referenced_by.entry(did).or_default().push(Reference {
+                did: item.def_id,
+                parent,
+                name: name.clone(),
+                ty: item.type_(),
+            });
         }
     }
 }
@@ -514,13 +806,22 @@ fn extern_location(e: &clean::ExternalCrate, extern_url: Option<&str>, dst: &Pat
 {
     use ExternalLocation::*;
     // See if there's documentation generated into the local directory
-    let local_location = dst.join(&e.name);
+    let local_location = dst.join(&*e.name.as_str());
     if local_location.is_dir() {
         return Local;
     }
 
-    if let Some(url) = extern_url {
-        let mut url = url.to_string();
+    // A URL naming the version of the crate is skipped if the version is unknown, rather than
+    // pointing to documentation which may not match.
+    let extern_url = extern_url.and_then(|url| {
+        let url = url.replace("{crate}", &e.name.as_str());
+        match e.version {
+            Some(ref version) => Some(url.replace("{version}", version)),
+            None if url.contains("{version}") => None,
+            None => Some(url),
+        }
+    });
+    if let Some(mut url) = extern_url {
         if !url.ends_with("/") {
             url.push('/');
         }
@@ -542,30 +843,75 @@ fn extern_location(e: &clean::ExternalCrate, extern_url: Option<&str>, dst: &Pat
 }
 
 /// Builds the search index from the collected metadata
-fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
+/// Records `index_item` under each of the `#[doc(alias = "...")]` names of `item`. Aliases are
+/// looked up without regard to case, so they are stored lowercased.
+fn add_aliases(
+    aliases: &mut FxHashMap<String, Vec<AliasItem>>,
+    item: &clean::Item,
+    index_item: &IndexItem,
+) {
+    for alias in &item.attrs.aliases {
+        aliases.entry(alias.name.as_str().to_lowercase()).or_default().push(AliasItem {
+            item: index_item.clone(),
+            low_priority: alias.low_priority,
+        });
+    }
+}
+
+/// Builds the search index of the crate. With `shard_descriptions`, the descriptions of the items
+/// are split out of it, and their entry in the index is only left empty for the items without
+/// one, which are ranked lower. With `item_urls`, the URLs of the items are collected as well.
+fn build_index(
+    krate: &clean::Crate,
+    cache: &mut Cache,
+    shard_descriptions: bool,
+    item_urls: bool,
+) -> SearchIndex {
     let mut nodeid_to_pathid = FxHashMap::default();
+    let mut urls = BTreeMap::new();
     let mut crate_items = Vec::with_capacity(cache.search_index.len());
     let mut crate_paths = vec![];
+    let mut descs = vec![];
+    let mut deprecated = vec![];
+    let mut visibility: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    let mut facets = Facets::default();
 
     let Cache { ref mut search_index,
+                ref mut aliases,
                 ref orphan_impl_items,
-                ref paths, .. } = *cache;
+                ref implementors,
+                ref impls,
+                ref traits,
+                ref paths,
+                ref external_paths,
+                ref mut index_entries, .. } = *cache;
 
     // Attach all orphan items to the type's definition if the type
     // has since been learned.
-    for &(did, ref item) in orphan_impl_items {
+    for &(did, ref item, visibility) in orphan_impl_items {
         if let Some(&(ref fqp, _)) = paths.get(&did) {
-            search_index.push(IndexItem {
+            let name = item.name.unwrap().to_string();
+            let path = fqp[..fqp.len() - 1].join("::");
+            let index_item = IndexItem {
                 ty: item.type_(),
-                name: item.name.clone().unwrap(),
-                path: fqp[..fqp.len() - 1].join("::"),
-                desc: shorten(plain_summary_line(item.doc_value())),
+                desc: index_entries.desc(item, &path, &name),
+                name,
+                path,
                 parent: Some(did),
                 parent_idx: None,
                 search_type: get_index_search_type(&item),
-            });
+                anchor: None,
+                deprecated: is_deprecated(item),
+                visibility,
+                unstable: is_unstable(item),
+                cfg: item_cfg(item),
+            };
+            add_aliases(aliases, item, &index_item);
+            search_index.push(index_item);
         }
     }
+    search_index.extend(implementor_items(implementors, paths, external_paths, index_entries));
+    search_index.extend(provided_method_items(impls, traits, paths, index_entries));
 
     // Reduce `NodeId` in paths into smaller sequential numbers,
     // and prune the paths that do not appear in the index.
@@ -587,6 +933,24 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
             }
         });
 
+        if shard_descriptions {
+            let marker = if item.desc.is_empty() { "" } else { "1" };
+            descs.push(mem::replace(&mut item.desc, marker.to_owned()));
+        }
+
+        if item.deprecated {
+            deprecated.push(crate_items.len());
+        }
+        if let Some(tag) = item.visibility {
+            visibility.entry(tag.as_str()).or_default().push(crate_items.len());
+        }
+        facets.add(crate_items.len(), item.unstable, item.cfg.as_ref());
+
+        if item_urls {
+            let (path, url) = item_url(item, paths);
+            urls.entry(path).or_insert(url);
+        }
+
         // Omit the parent path if it is same to that of the prior item.
         if lastpath == item.path {
             item.path.clear();
@@ -597,7 +961,7 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
     }
 
     let crate_doc = krate.module.as_ref().map(|module| {
-        shorten(plain_summary_line(module.doc_value()))
+        index_entries.desc(module, "", &krate.name.as_str())
     }).unwrap_or(String::new());
 
     #[derive(Serialize)]
@@ -607,19 +971,191 @@ fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
         items: Vec<&'a IndexItem>,
         #[serde(rename = "p")]
         paths: Vec<(ItemType, String)>,
+        /// The number of items whose descriptions are in each shard, if they are split out.
+        #[serde(rename = "d", skip_serializing_if = "Option::is_none")]
+        desc_shard_len: Option<usize>,
+        #[serde(rename = "f")]
+        names: NameTable,
+        /// The weight of every kind of item in the ranking of the results, by item type.
+        #[serde(rename = "k")]
+        kind_weights: Vec<usize>,
+        /// The positions of the deprecated items, which rank lower.
+        #[serde(rename = "x", skip_serializing_if = "Vec::is_empty")]
+        deprecated: Vec<usize>,
+        /// The positions of the private and hidden items, by the visibility they are tagged with.
+        #[serde(rename = "v", skip_serializing_if = "BTreeMap::is_empty")]
+        visibility: BTreeMap<&'static str, Vec<usize>>,
+        #[serde(flatten)]
+        facets: Facets,
     }
 
-    // Collect the index into a string
-    format!(
-        r#"searchIndex["{}"] = {};"#,
-        krate.name,
-        serde_json::to_string(&CrateData {
-            doc: crate_doc,
-            items: crate_items,
-            paths: crate_paths,
-        })
-        .unwrap()
-    )
+    let names = NameTable::new(crate_items.iter().map(|item| &*item.name));
+
+    let index = serde_json::to_value(&CrateData {
+        doc: crate_doc,
+        items: crate_items,
+        paths: crate_paths,
+        desc_shard_len: if shard_descriptions { Some(DESC_SHARD_LEN) } else { None },
+        names,
+        kind_weights: kind_weights(),
+        deprecated,
+        visibility,
+        facets,
+    }).unwrap();
+    let desc_shards = descs.chunks(DESC_SHARD_LEN).enumerate().map(|(shard, descs)| {
+        format!(r#"loadDescShard("{}",{},{});"#,
+                krate.name, shard, serde_json::to_string(descs).unwrap())
+    }).collect();
+    SearchIndex { index, desc_shards, item_urls: urls }
+}
+
+/// Returns the path of the item of the index and the URL of its documentation, as the search
+/// links to it.
+fn item_url(
+    item: &IndexItem,
+    paths: &FxHashMap<DefId, (Vec<String>, ItemType)>,
+) -> (String, String) {
+    let dir = item.path.replace("::", "/");
+    let parent = item.parent.and_then(|did| paths.get(&did));
+    match (item.ty, parent) {
+        (ItemType::Module, _) => {
+            (format!("{}::{}", item.path, item.name), format!("{}/{}/index.html", dir, item.name))
+        }
+        (ItemType::ExternCrate, _) => {
+            (format!("{}::{}", item.path, item.name), format!("{}/index.html", item.name))
+        }
+        (_, Some(&(ref fqp, parent_ty))) => {
+            let parent_name = fqp.last().unwrap();
+            let anchor = item.anchor.clone()
+                .unwrap_or_else(|| format!("{}.{}", item.ty, item.name));
+            (format!("{}::{}::{}", item.path, parent_name, item.name),
+             format!("{}/{}.{}.html#{}", dir, parent_ty, parent_name, anchor))
+        }
+        (_, None) => {
+            (format!("{}::{}", item.path, item.name),
+             format!("{}/{}.{}.html", dir, item.ty, item.name))
+        }
+    }
+}
+
+/// Returns the entries of the trait implementations, named after the trait and found under the
+/// implementing type, so that searching for a trait finds the types implementing it. They link
+/// to the implementation on the page of the type, and are only made for the types having a page.
+fn implementor_items(
+    implementors: &FxHashMap<DefId, Vec<Impl>>,
+    paths: &FxHashMap<DefId, (Vec<String>, ItemType)>,
+    external_paths: &FxHashMap<DefId, (Vec<String>, ItemType)>,
+    index_entries: &mut IndexEntries,
+) -> Vec<IndexItem> {
+    let mut items = vec![];
+    let mut seen = FxHashSet::default();
+    for (trait_did, impls) in implementors {
+        let trait_fqp = match paths.get(trait_did).or_else(|| external_paths.get(trait_did)) {
+            Some((fqp, _)) => fqp,
+            None => continue,
+        };
+        for imp in impls {
+            let inner = imp.inner_impl();
+            // The implementations of the auto traits are there for every type.
+            if inner.synthetic {
+                continue;
+            }
+            let for_did = match inner.for_.def_id() {
+                Some(did) => did,
+                None => continue,
+            };
+            let fqp = match paths.get(&for_did) {
+                Some((fqp, _)) => fqp,
+                None => continue,
+            };
+            // A type implementing a generic trait several times gets a single entry.
+            if !seen.insert((*trait_did, for_did)) {
+                continue;
+            }
+            let trait_ = inner.trait_.as_ref().unwrap();
+            let name = trait_fqp.last().unwrap().clone();
+            let path = fqp[..fqp.len() - 1].join("::");
+            items.push(((fqp, trait_fqp), IndexItem {
+                ty: ItemType::Impl,
+                desc: index_entries.desc(&imp.impl_item, &path, &name),
+                name,
+                path,
+                parent: Some(for_did),
+                parent_idx: None,
+                search_type: None,
+                anchor: Some(format!("impl-{}",
+                                     small_url_encode(&format!("{:#}", trait_.print())))),
+                deprecated: is_deprecated(&imp.impl_item),
+                visibility: None,
+                unstable: is_unstable(&imp.impl_item),
+                cfg: item_cfg(&imp.impl_item),
+            }));
+        }
+    }
+    // The implementors are kept in a hash map, but the index is written identically every time.
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    items.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Returns the entries of the provided methods which the trait implementations of the types
+/// do not override, found under the implementing type like the other methods, so that searching
+/// for `Type::method` finds them. The blanket implementations are left out, since they give
+/// the same methods to a great many types.
+fn provided_method_items(
+    impls: &FxHashMap<DefId, Vec<Impl>>,
+    traits: &FxHashMap<DefId, clean::Trait>,
+    paths: &FxHashMap<DefId, (Vec<String>, ItemType)>,
+    index_entries: &mut IndexEntries,
+) -> Vec<IndexItem> {
+    let mut items = vec![];
+    for (for_did, impls) in impls {
+        let fqp = match paths.get(for_did) {
+            Some((fqp, _)) => fqp,
+            None => continue,
+        };
+        for imp in impls {
+            let inner = imp.inner_impl();
+            if inner.synthetic || inner.blanket_impl.is_some() {
+                continue;
+            }
+            let trait_did = match inner.trait_.def_id() {
+                Some(did) => did,
+                None => continue,
+            };
+            let trait_ = match traits.get(&trait_did) {
+                Some(trait_) => trait_,
+                None => continue,
+            };
+            for method in &trait_.items {
+                let name = match method.name {
+                    Some(name) if inner.provided_trait_methods.contains(&*name.as_str()) => name,
+                    _ => continue,
+                };
+                if inner.items.iter().any(|item| item.name == Some(name)) {
+                    continue;
+                }
+                let name = name.to_string();
+                let path = fqp[..fqp.len() - 1].join("::");
+                items.push(((fqp, name.clone(), trait_did), IndexItem {
+                    ty: ItemType::Method,
+                    desc: index_entries.desc(method, &path, &name),
+                    name,
+                    path,
+                    parent: Some(*for_did),
+                    parent_idx: None,
+                    search_type: get_index_search_type(method),
+                    anchor: None,
+                    deprecated: is_deprecated(method),
+                    visibility: None,
+                    unstable: is_unstable(method),
+                    cfg: item_cfg(method),
+                }));
+            }
+        }
+    }
+    // The impls are kept in a hash map, but the index is written identically every time.
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+    items.into_iter().map(|(_, item)| item).collect()
 }
 
 fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
@@ -661,9 +1197,9 @@ fn get_index_type_name(clean_type: &clean::Type, accept_generic: bool) -> Option
                 "get_index_type_name(clean_type: {:?}, accept_generic: {:?}) had length zero path",
                 clean_type, accept_generic
             ));
-            Some(path_segment.name.clone())
+            Some(path_segment.name.to_string())
         }
-        clean::Generic(ref s) if accept_generic => Some(s.clone()),
+        clean::Generic(s) if accept_generic => Some(s.to_string()),
         clean::Primitive(ref p) => Some(format!("{:?}", p)),
         clean::BorrowedRef { ref type_, .. } => get_index_type_name(type_, accept_generic),
         // FIXME: add all from clean::Type.
@@ -0,0 +1,74 @@
+//! The facets the search results can be filtered by, besides the kind of item.
+//!
+//! The search index lists the unstable items, and the items available on some configurations
+//! only, grouped by configuration. The search then keeps the stable items for a query starting
+//! with `stable:`, and the items available on a platform for a query starting with `on:` and the
+//! platform, like `on:wasm32 alloc`. The configurations are written as JSON trees evaluated by the
+//! search: `true` and `false`, the options as `"unix"` or `"target_arch=wasm32"`, and the
+//! combinations of options as arrays starting with `"not"`, `"any"` or `"all"`.
+
+use rustc::middle::stability;
+use rustc_data_structures::fx::FxHashMap;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use crate::clean;
+use crate::clean::cfg::Cfg;
+
+#[derive(Default, Serialize)]
+crate struct Facets {
+    /// The positions of the unstable items.
+    #[serde(rename = "u", skip_serializing_if = "Vec::is_empty")]
+    unstable: Vec<usize>,
+    /// The configurations the items are available on, each with the positions of its items.
+    #[serde(rename = "c", skip_serializing_if = "Vec::is_empty")]
+    cfgs: Vec<(Value, Vec<usize>)>,
+    #[serde(skip)]
+    cfg_groups: FxHashMap<Cfg, usize>,
+}
+
+impl Facets {
+    /// Records the facets of the item at `pos` in the index.
+    crate fn add(&mut self, pos: usize, unstable: bool, cfg: Option<&Cfg>) {
+        if unstable {
+            self.unstable.push(pos);
+        }
+        if let Some(cfg) = cfg {
+            let cfgs = &mut self.cfgs;
+            let group = *self.cfg_groups.entry(cfg.clone()).or_insert_with(|| {
+                cfgs.push((cfg_to_json(cfg), vec![]));
+                cfgs.len() - 1
+            });
+            self.cfgs[group].1.push(pos);
+        }
+    }
+}
+
+/// Returns the whole configuration `item` is available on, if it is not available on all of them.
+crate fn item_cfg(item: &clean::Item) -> Option<Cfg> {
+    match (&item.attrs.cfg, &item.attrs.auto_cfg) {
+        (Some(cfg), Some(auto_cfg)) => Some(Cfg::clone(cfg) & Cfg::clone(auto_cfg)),
+        (Some(cfg), None) | (None, Some(cfg)) => Some(Cfg::clone(cfg)),
+        (None, None) => None,
+    }
+}
+
+crate fn is_unstable(item: &clean::Item) -> bool {
+    item.stability.as_ref().map_or(false, |stab| stab.level == stability::Unstable)
+}
+
+crate fn cfg_to_json(cfg: &Cfg) -> Value {
+    match *cfg {
+        Cfg::True => Value::Bool(true),
+        Cfg::False => Value::Bool(false),
+        Cfg::Cfg(name, None) => Value::String(name.to_string()),
+        Cfg::Cfg(name, Some(value)) => Value::String(format!("{}={}", name, value)),
+        Cfg::Not(ref cfg) => json!(["not", cfg_to_json(cfg)]),
+        Cfg::Any(ref cfgs) | Cfg::All(ref cfgs) => {
+            let op = if let Cfg::Any(..) = *cfg { "any" } else { "all" };
+            let mut tree = vec![Value::String(op.to_owned())];
+            tree.extend(cfgs.iter().map(cfg_to_json));
+            Value::Array(tree)
+        }
+    }
+}
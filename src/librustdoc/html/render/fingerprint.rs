@@ -0,0 +1,162 @@
+//! The fingerprints of the pages written with `--incremental-docs`.
+//!
+//! The fingerprint of a page hashes everything its content is rendered from: the cleaned item,
+//! the entries of the cache keyed by the item, like its impls and implementors, and the inputs
+//! shared by all the pages, like the options and the paths of the crate which links are made to.
+//! The fingerprints are saved next to the pages, and the next run with the same output directory
+//! skips writing the pages whose fingerprint did not change.
+//!
+//! The inputs are hashed through their `Debug` representation, which spells out every field of
+//! the clean IR. Hashing too much only makes a page rendered when it did not need to be.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Debug, Write};
+use std::fs;
+use std::hash::Hasher;
+use std::path::Path;
+
+use rustc::hir::def_id::DefId;
+use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::sync::Lock;
+use serde::{Deserialize, Serialize};
+
+use crate::clean::{self, GetDefId};
+use crate::config::RenderOptions;
+use crate::html::layout::StaticFileNames;
+
+use super::cache::Cache;
+use super::deref_chain;
+
+/// The file the fingerprints are saved to, in the directory of the crate.
+crate const FINGERPRINTS_FILE: &str = "page-fingerprints.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct SavedFingerprints {
+    /// The fingerprint of the inputs shared by all the pages.
+    global: u64,
+    /// The fingerprint of every page, by path relative to the output directory.
+    pages: BTreeMap<String, u64>,
+}
+
+crate struct PageFingerprints {
+    global: u64,
+    /// The fingerprints saved by the previous run, if it had the same shared inputs.
+    previous: BTreeMap<String, u64>,
+    /// The fingerprints of the pages of this run.
+    current: Lock<BTreeMap<String, u64>>,
+}
+
+impl PageFingerprints {
+    /// Loads the fingerprints saved to `path` by the previous run. They are all dropped if the
+    /// inputs shared by the pages changed since, in which case every page is rendered again.
+    crate fn load(path: &Path, global: u64) -> PageFingerprints {
+        let saved: SavedFingerprints = fs::read_to_string(path).ok()
+            .and_then(|saved| serde_json::from_str(&saved).ok())
+            .unwrap_or_default();
+        let previous = if saved.global == global { saved.pages } else { BTreeMap::new() };
+        PageFingerprints { global, previous, current: Default::default() }
+    }
+
+    /// Records the fingerprint of the page `page` of `item`, at the path `current` and rendered
+    /// as a redirect if `redirect` is set. Returns whether the page written by the previous run
+    /// had the same one, in which case it does not need to be written again.
+    crate fn is_fresh(
+        &self,
+        page: String,
+        current: &[String],
+        redirect: bool,
+        item: &clean::Item,
+        cache: &Cache,
+    ) -> bool {
+        let fingerprint = page_fingerprint(self.global, current, redirect, item, cache);
+        let fresh = self.previous.get(&page) == Some(&fingerprint);
+        self.current.lock().insert(page, fingerprint);
+        fresh
+    }
+
+    /// Returns the fingerprints to save for the next run.
+    crate fn to_json(&self) -> String {
+        let saved = SavedFingerprints { global: self.global, pages: self.current.lock().clone() };
+        serde_json::to_string(&saved).expect("failed to serialize page fingerprints")
+    }
+}
+
+/// Returns the fingerprint of the inputs shared by all the pages of the crate.
+crate fn global_fingerprint(
+    options: &RenderOptions,
+    static_files: &StaticFileNames,
+    cache: &Cache,
+) -> u64 {
+    let mut hasher = StableHasher::new();
+    hash_debug(&mut hasher, &option_env!("CFG_VERSION"));
+    hash_debug(&mut hasher, options);
+    hash_debug(&mut hasher, static_files);
+    hash_debug(&mut hasher, &cache.crate_version);
+    hash_debug(&mut hasher, &cache.features.is_empty());
+    hash_unordered(&mut hasher, &cache.paths);
+    hash_unordered(&mut hasher, &cache.external_paths);
+    hash_unordered(&mut hasher, &cache.extern_locations);
+    hash_unordered(&mut hasher, &cache.primitive_locations);
+    hasher.finish()
+}
+
+fn page_fingerprint(
+    global: u64,
+    current: &[String],
+    redirect: bool,
+    item: &clean::Item,
+    cache: &Cache,
+) -> u64 {
+    let mut hasher = StableHasher::new();
+    hasher.write_u64(global);
+    hash_debug(&mut hasher, &(current, redirect));
+    hash_debug(&mut hasher, item);
+
+    let did = item.def_id;
+    hash_impls(&mut hasher, did, cache);
+    for hop in deref_chain(cache, did) {
+        hash_impls(&mut hasher, hop.target_did, cache);
+    }
+    hash_debug(&mut hasher, &cache.implementors.get(&did));
+    hash_debug(&mut hasher, &cache.referenced_by.get(&did));
+    hasher.finish()
+}
+
+/// Hashes the impls cached under `did`, along with the traits they implement, whose default
+/// methods are shown with them.
+fn hash_impls(hasher: &mut StableHasher, did: DefId, cache: &Cache) {
+    let impls = cache.impls.get(&did);
+    hash_debug(hasher, &impls);
+    let traits: FxHashSet<_> = impls.into_iter().flatten()
+        .filter_map(|i| i.inner_impl().trait_.def_id())
+        .collect();
+    let mut traits: Vec<_> = traits.into_iter().collect();
+    traits.sort();
+    for trait_did in traits {
+        hash_debug(hasher, &cache.traits.get(&trait_did));
+    }
+}
+
+/// Hashes the entries of a map regardless of the order they are stored in.
+fn hash_unordered<'a, I, K, V>(hasher: &mut StableHasher, map: I)
+    where I: IntoIterator<Item = (&'a K, &'a V)>, K: Debug + 'a, V: Debug + 'a,
+{
+    let mut entries: Vec<_> = map.into_iter().map(|entry| format!("{:?}", entry)).collect();
+    entries.sort();
+    hash_debug(hasher, &entries);
+}
+
+fn hash_debug<T: Debug + ?Sized>(hasher: &mut StableHasher, value: &T) {
+    write!(HashWriter(hasher), "{:?}", value).expect("hashing cannot fail");
+}
+
+/// Feeds what is formatted to it to a hasher, without collecting it first.
+struct HashWriter<'a>(&'a mut StableHasher);
+
+impl fmt::Write for HashWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write(s.as_bytes());
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+//! The impl blocks shared by the pages, written with `--deduplicate-impls`.
+//!
+//! An impl block shown identically on several pages, like a blanket impl listed on the page of
+//! every type, is written once to `impls/<hash>.js` in the directory of the crate, and the pages
+//! load it from there instead of holding a copy of it. The links of the block are relative to the
+//! documentation root, like the ones of the implementors of a trait from other crates, and
+//! `storage.js` makes them relative to the page when inserting it.
+
+use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+use rustc_data_structures::sync::Lock;
+
+#[derive(Default)]
+crate struct ImplFragments {
+    /// The impl blocks rendered so far, by the name of the file holding them.
+    blocks: Lock<BTreeMap<String, String>>,
+}
+
+impl ImplFragments {
+    /// Records the impl block `html` and returns the name of the file holding it, named after
+    /// its content so that identical blocks share it.
+    crate fn record(&self, html: String) -> String {
+        let mut hasher = DefaultHasher::new();
+        html.hash(&mut hasher);
+        let file_name = format!("{:016x}.js", hasher.finish());
+        self.blocks.lock().entry(file_name.clone()).or_insert(html);
+        file_name
+    }
+
+    /// Returns the files to write, by name, with their content.
+    crate fn take_files(&self) -> Vec<(String, String)> {
+        mem::take(&mut *self.blocks.lock()).into_iter().map(|(file_name, html)| {
+            let script = format!("insertImplFragment({});", serde_json::to_string(&html).unwrap());
+            (file_name, script)
+        }).collect()
+    }
+}
@@ -0,0 +1,104 @@
+//! The entries of the search index reused with `--incremental-docs`.
+//!
+//! Building the entry of an item in the search index mostly goes to rendering the summary line of
+//! its documentation from Markdown, the other fields being copied from the cleaned item. With
+//! `--incremental-docs`, the summaries of a run are saved next to the pages, and the next run only
+//! renders those of the items whose documentation changed since.
+//!
+//! The summaries are saved by identity of their item: its kind and its path, which, unlike its
+//! `DefId`, stay the same when the items around it change. The items sharing an identity, like the
+//! methods of the same name of several types of a module, each keep their summary, told apart by
+//! the fingerprint of the documentation they are rendered from.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::hash::Hasher;
+use std::path::Path;
+
+use rustc_data_structures::stable_hasher::StableHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::clean;
+
+use super::{plain_summary_line, shorten};
+
+/// The file the summaries are saved to, in the directory of the crate.
+crate const INDEX_ENTRIES_FILE: &str = "search-entries.json";
+
+type Summaries = BTreeMap<String, BTreeMap<u64, String>>;
+
+#[derive(Default, Serialize, Deserialize)]
+struct SavedEntries {
+    /// The version of rustdoc which rendered the summaries.
+    version: Option<String>,
+    /// The summaries, by identity of the item, then by fingerprint of its documentation.
+    summaries: Summaries,
+}
+
+#[derive(Default)]
+crate struct IndexEntries {
+    /// Whether the summaries are reused and saved, which they are not by default.
+    incremental: bool,
+    /// The summaries saved by the previous run, if it was made by the same version of rustdoc.
+    previous: Summaries,
+    /// The summaries of this run.
+    current: Summaries,
+}
+
+impl IndexEntries {
+    /// Loads the summaries saved to `path` by the previous run.
+    crate fn load(path: &Path) -> IndexEntries {
+        let saved: SavedEntries = fs::read_to_string(path).ok()
+            .and_then(|saved| serde_json::from_str(&saved).ok())
+            .unwrap_or_default();
+        let previous = if saved.version.as_ref().map(|v| &**v) == option_env!("CFG_VERSION") {
+            saved.summaries
+        } else {
+            Summaries::new()
+        };
+        IndexEntries { incremental: true, previous, current: Summaries::new() }
+    }
+
+    /// Returns the description of the entry of `item` found as `name` in the module `path`,
+    /// reusing the one of the previous run if the documentation of the item did not change.
+    crate fn desc(&mut self, item: &clean::Item, path: &str, name: &str) -> String {
+        let doc = item.doc_value();
+        if !self.incremental {
+            return shorten(plain_summary_line(doc));
+        }
+        self.summary(format!("{}:{}::{}", item.type_(), path, name), doc)
+    }
+
+    /// Returns the summary of `doc`, the documentation of the item identified by `identity`.
+    crate fn summary(&mut self, identity: String, doc: Option<&str>) -> String {
+        let fingerprint = doc_fingerprint(doc);
+        let desc = match self.previous.get(&identity).and_then(|saved| saved.get(&fingerprint)) {
+            Some(desc) => desc.clone(),
+            None => shorten(plain_summary_line(doc)),
+        };
+        self.current.entry(identity).or_default().insert(fingerprint, desc.clone());
+        desc
+    }
+
+    /// Returns the summaries to save for the next run. The items gone since the previous run are
+    /// left out.
+    crate fn to_json(&self) -> String {
+        let saved = SavedEntries {
+            version: option_env!("CFG_VERSION").map(str::to_owned),
+            summaries: self.current.clone(),
+        };
+        serde_json::to_string(&saved).expect("failed to serialize search index entries")
+    }
+}
+
+fn doc_fingerprint(doc: Option<&str>) -> u64 {
+    let mut hasher = StableHasher::new();
+    match doc {
+        Some(doc) => {
+            hasher.write_u8(1);
+            hasher.write(doc.as_bytes());
+        }
+        None => hasher.write_u8(0),
+    }
+    hasher.finish()
+}
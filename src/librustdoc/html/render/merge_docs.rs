@@ -0,0 +1,260 @@
+//! Combining documentation generated separately, with `--merge-docs`.
+//!
+//! Every output directory given holds the documentation of some crates. Their pages and sources
+//! are copied to the output directory, and the files shared by the crates of a directory are
+//! merged: the search index, the aliases, the source files and the implementors keep one entry
+//! per crate, taken from the directory documenting it. A crate documented in several of the
+//! directories is taken from the first of them. The static files, like the scripts and the
+//! stylesheets, are copied from the first directory having them, the directories being expected
+//! to have been generated by the same version of rustdoc.
+//!
+//! The pages of a crate link to the crates documented elsewhere through the URLs given with
+//! `--extern-html-root-url`. Once these crates are in the bundle too, the links through the URLs
+//! given along with `--merge-docs` are rewritten to links within the bundle.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::config::RenderOptions;
+use crate::docfs::PathError;
+
+use super::{Error, SharedSearchIndex, ensure_trailing_slash};
+
+/// The shared files with one line per crate, and the prefix of these lines.
+const PER_CRATE_FILES: &[(&str, &str)] = &[
+    ("aliases", "ALIASES"),
+    ("source-files", "sourcesIndex"),
+];
+
+/// Merges the documentation of the directories `inputs` into `options.output`.
+crate fn run(inputs: &[PathBuf], options: &RenderOptions) -> Result<(), Error> {
+    let dst = &options.output;
+    let suffix = &options.resource_suffix;
+    fs::create_dir_all(dst).map_err(|e| Error::new(e, dst))?;
+
+    // The crates of every directory, each taken from the first directory documenting it. The
+    // crates already in the output directory are kept, unless one of the directories has them.
+    let mut index = load_index(dst, suffix)?;
+    let mut sources = BTreeMap::new();
+    for input in inputs {
+        let input_index = load_index(input, suffix)?;
+        for krate in input_index.krates() {
+            sources.entry(krate.to_owned()).or_insert_with(|| input.clone());
+        }
+    }
+    let krates = sources.keys().cloned().collect::<BTreeSet<_>>();
+    let links = rewritten_links(&krates, options);
+
+    for input in inputs {
+        let own = sources.iter()
+            .filter(|(_, source)| *source == input)
+            .map(|(krate, _)| krate.clone())
+            .collect::<BTreeSet<_>>();
+        let input_index = load_index(input, suffix)?;
+        for krate in &own {
+            if let Some(entry) = input_index.get(krate) {
+                index.insert(krate, entry.clone());
+            }
+            for dir in &[PathBuf::from(krate), Path::new("src").join(krate),
+                         Path::new("search-desc").join(krate)] {
+                let depth = dir.components().count();
+                copy_dir(&input.join(dir), &dst.join(dir), &links, depth)?;
+            }
+        }
+        for &(file, prefix) in PER_CRATE_FILES {
+            let file = format!("{}{}.js", file, suffix);
+            merge_lines(&input.join(&file), &dst.join(&file), prefix, &own)?;
+        }
+        merge_implementors(&input.join("implementors"), &dst.join("implementors"), &own, &links)?;
+        copy_static_files(input, dst)?;
+    }
+
+    let json = dst.join(format!("search-index{}.json", suffix));
+    index.save(&json).map_err(|e| Error::new(e, &json))?;
+    let js = dst.join(format!("search-index{}.js", suffix));
+    fs::write(&js, index.to_script()).map_err(|e| Error::new(e, &js))?;
+    write_crate_list(inputs, dst, &index)
+}
+
+fn load_index(dir: &Path, suffix: &str) -> Result<SharedSearchIndex, Error> {
+    let json = dir.join(format!("search-index{}.json", suffix));
+    let js = dir.join(format!("search-index{}.js", suffix));
+    SharedSearchIndex::load(&json, &js).map_err(|e| Error::new(e, &json))
+}
+
+/// Returns the prefixes of the links to the merged crates through their remote documentation,
+/// with the path of the crate they are rewritten to. The URLs naming the version of the crate are
+/// left out, the version of the merged documentation not being known.
+fn rewritten_links(krates: &BTreeSet<String>, options: &RenderOptions) -> Vec<(String, String)> {
+    krates.iter()
+        .filter_map(|krate| {
+            let url = options.extern_html_root_urls.get(krate)?.replace("{crate}", krate);
+            if url.contains("{version}") {
+                return None;
+            }
+            Some((format!("{}{}/", ensure_trailing_slash(&url), krate), format!("{}/", krate)))
+        })
+        .collect()
+}
+
+/// Copies the directory `src` to `dst`, `depth` directories below the root of the documentation,
+/// rewriting the links of the pages.
+fn copy_dir(src: &Path, dst: &Path, links: &[(String, String)], depth: usize)
+    -> Result<(), Error>
+{
+    if !src.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(dst).map_err(|e| Error::new(e, dst))?;
+    for entry in fs::read_dir(src).map_err(|e| Error::new(e, src))? {
+        let entry = entry.map_err(|e| Error::new(e, src))?;
+        let (from, to) = (entry.path(), dst.join(entry.file_name()));
+        if from.is_dir() {
+            copy_dir(&from, &to, links, depth + 1)?;
+        } else if from.extension().map_or(false, |ext| ext == "html") && !links.is_empty() {
+            let page = fs::read_to_string(&from).map_err(|e| Error::new(e, &from))?;
+            let page = rewrite_links(&page, links, &"../".repeat(depth));
+            fs::write(&to, page).map_err(|e| Error::new(e, &to))?;
+        } else {
+            fs::copy(&from, &to).map_err(|e| Error::new(e, &from))?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites the links of `contents` starting with one of the prefixes of `links` to the path
+/// relative to `root_path` they are given with.
+crate fn rewrite_links(contents: &str, links: &[(String, String)], root_path: &str) -> String {
+    links.iter().fold(contents.to_owned(), |contents, (url, path)| {
+        contents.replace(url, &format!("{}{}", root_path, path))
+    })
+}
+
+/// Adds the lines of `src` starting with `prefix` for one of the crates `own` to `dst`.
+crate fn merge_lines(src: &Path, dst: &Path, prefix: &str, own: &BTreeSet<String>)
+    -> Result<(), Error>
+{
+    merge_lines_with(src, dst, prefix, own, |line| line.to_owned())
+}
+
+fn merge_lines_with<F>(src: &Path, dst: &Path, prefix: &str, own: &BTreeSet<String>, map: F)
+    -> Result<(), Error>
+    where F: Fn(&str) -> String
+{
+    if !src.exists() {
+        return Ok(());
+    }
+    let contents = fs::read_to_string(src).map_err(|e| Error::new(e, src))?;
+    let existing = if dst.exists() {
+        Some(fs::read_to_string(dst).map_err(|e| Error::new(e, dst))?)
+    } else {
+        None
+    };
+    let added = contents.lines()
+        .filter(|line| entry_crate(line, prefix).map_or(false, |krate| own.contains(krate)))
+        .map(map);
+    let mut entries = existing.iter()
+        .flat_map(|existing| existing.lines())
+        .filter(|line| entry_crate(line, prefix).map_or(false, |krate| !own.contains(krate)))
+        .map(str::to_owned)
+        .chain(added)
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    // The lines around the entries, which set them up and use them, are kept as they are.
+    let mut merged = vec![];
+    let mut entries = Some(entries);
+    for line in existing.as_ref().unwrap_or(&contents).lines() {
+        if entry_crate(line, prefix).is_none() {
+            merged.push(line.to_owned());
+        } else if let Some(entries) = entries.take() {
+            merged.extend(entries);
+        }
+    }
+    if let Some(entries) = entries {
+        merged.extend(entries);
+    }
+    let mut merged = merged.join("\n");
+    merged.push('\n');
+    fs::write(dst, merged).map_err(|e| Error::new(e, dst))
+}
+
+/// Returns the crate of the entry `prefix["krate"] = ...;`, if `line` is one.
+fn entry_crate<'a>(line: &'a str, prefix: &str) -> Option<&'a str> {
+    if !line.starts_with(prefix) || !line[prefix.len()..].starts_with("[\"") {
+        return None;
+    }
+    let rest = &line[prefix.len() + 2..];
+    Some(&rest[..rest.find('"')?])
+}
+
+/// Merges the implementors of the traits listed by the crates `own` into `dst`.
+fn merge_implementors(src: &Path, dst: &Path, own: &BTreeSet<String>, links: &[(String, String)])
+    -> Result<(), Error>
+{
+    if !src.is_dir() {
+        return Ok(());
+    }
+    fs::create_dir_all(dst).map_err(|e| Error::new(e, dst))?;
+    for entry in fs::read_dir(src).map_err(|e| Error::new(e, src))? {
+        let entry = entry.map_err(|e| Error::new(e, src))?;
+        let (from, to) = (entry.path(), dst.join(entry.file_name()));
+        if from.is_dir() {
+            merge_implementors(&from, &to, own, links)?;
+        } else {
+            // The links of the implementors are relative to the root of the documentation.
+            merge_lines_with(&from, &to, "implementors", own, |line| {
+                rewrite_links(line, links, "")
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies the files at the root of `src` which are not in `dst` yet, the shared files with one
+/// entry per crate being merged instead.
+fn copy_static_files(src: &Path, dst: &Path) -> Result<(), Error> {
+    for entry in fs::read_dir(src).map_err(|e| Error::new(e, src))? {
+        let entry = entry.map_err(|e| Error::new(e, src))?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let shared = name.starts_with("search-index") || name == "index.html" ||
+            name.starts_with('.') ||
+            PER_CRATE_FILES.iter().any(|&(file, _)| name.starts_with(file));
+        let to = dst.join(&name);
+        if entry.path().is_file() && !shared && !to.exists() {
+            fs::copy(entry.path(), &to).map_err(|e| Error::new(e, entry.path()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the page listing the crates, from the one of the first directory having it.
+fn write_crate_list(inputs: &[PathBuf], dst: &Path, index: &SharedSearchIndex)
+    -> Result<(), Error>
+{
+    const LIST_START: &str = "<ul class='mod'>";
+    let page = dst.join("index.html");
+    let template = match inputs.iter().map(|input| input.join("index.html")).find(|p| p.exists()) {
+        Some(template) => template,
+        None => return Ok(()),
+    };
+    let contents = fs::read_to_string(&template).map_err(|e| Error::new(e, &template))?;
+    let (start, end) = match contents.find(LIST_START)
+        .and_then(|start| Some((start, start + contents[start..].find("</ul>")?)))
+    {
+        Some(range) => range,
+        None => {
+            let error = io::Error::new(io::ErrorKind::InvalidData, "no list of crates found");
+            return Err(Error::new(error, &template));
+        }
+    };
+    let list = index.krates()
+        .map(|krate| format!("<li><a href=\"{}index.html\">{}</li>",
+                             ensure_trailing_slash(krate), krate))
+        .collect::<String>();
+    let contents = format!("{}{}{}{}",
+                           &contents[..start], LIST_START, list, &contents[end..]);
+    fs::write(&page, contents).map_err(|e| Error::new(e, &page))
+}
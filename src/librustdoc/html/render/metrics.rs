@@ -0,0 +1,138 @@
+//! Rough per-item documentation metrics.
+//!
+//! These are emitted by `--item-metrics` so that docs health tooling can find the most complex
+//! APIs with the least documentation. None of the numbers are meant to be precise.
+
+use serde::Serialize;
+
+use crate::clean::{self, FunctionRetTy, GenericArg, GenericArgs, GenericBound};
+use crate::clean::{GenericParamDefKind, Type, WherePredicate};
+use crate::html::markdown;
+
+/// Average reading speed used to turn a word count into a reading time.
+const WORDS_PER_MINUTE: usize = 200;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+crate struct ItemMetrics {
+    /// Number of words in the item's documentation.
+    crate doc_words: usize,
+    /// Number of Rust code blocks in the item's documentation.
+    crate examples: usize,
+    /// How hard the item's signature is to read: one point for every type, generic parameter,
+    /// bound and where clause appearing in it.
+    crate signature_complexity: usize,
+}
+
+impl ItemMetrics {
+    crate fn for_item(item: &clean::Item, dox: Option<&str>) -> ItemMetrics {
+        let dox = dox.unwrap_or("");
+        ItemMetrics {
+            doc_words: dox.split_whitespace().count(),
+            examples: markdown::rust_code_blocks(dox).len(),
+            signature_complexity: signature_complexity(&item.inner),
+        }
+    }
+
+    /// The time needed to read the item's documentation, rounded up to the next minute.
+    crate fn reading_minutes(&self) -> usize {
+        (self.doc_words + WORDS_PER_MINUTE - 1) / WORDS_PER_MINUTE
+    }
+}
+
+fn signature_complexity(inner: &clean::ItemEnum) -> usize {
+    match *inner {
+        clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
+            decl_complexity(&f.decl) + generics_complexity(&f.generics)
+        }
+        clean::MethodItem(ref m) => decl_complexity(&m.decl) + generics_complexity(&m.generics),
+        clean::TyMethodItem(ref m) => decl_complexity(&m.decl) + generics_complexity(&m.generics),
+        clean::StructItem(clean::Struct { ref generics, .. }) |
+        clean::UnionItem(clean::Union { ref generics, .. }) |
+        clean::EnumItem(clean::Enum { ref generics, .. }) |
+        clean::TraitAliasItem(clean::TraitAlias { ref generics, .. }) => {
+            generics_complexity(generics)
+        }
+        clean::TraitItem(ref t) => {
+            generics_complexity(&t.generics) + bounds_complexity(&t.bounds)
+        }
+        clean::TypedefItem(ref t, _) => {
+            type_complexity(&t.type_) + generics_complexity(&t.generics)
+        }
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => type_complexity(&s.type_),
+        clean::ConstantItem(ref c) => type_complexity(&c.type_),
+        _ => 0,
+    }
+}
+
+fn decl_complexity(decl: &clean::FnDecl) -> usize {
+    let inputs: usize = decl.inputs.values.iter().map(|arg| type_complexity(&arg.type_)).sum();
+    let output = match decl.output {
+        FunctionRetTy::Return(ref ty) => type_complexity(ty),
+        FunctionRetTy::DefaultReturn => 0,
+    };
+    inputs + output
+}
+
+fn generics_complexity(generics: &clean::Generics) -> usize {
+    let params: usize = generics.params.iter().map(|param| {
+        1 + match param.kind {
+            GenericParamDefKind::Lifetime => 0,
+            GenericParamDefKind::Type { ref bounds, ref default, .. } => {
+                bounds_complexity(bounds) + default.as_ref().map_or(0, type_complexity)
+            }
+            GenericParamDefKind::Const { ref ty, .. } => type_complexity(ty),
+        }
+    }).sum();
+    let predicates: usize = generics.where_predicates.iter().map(|pred| {
+        1 + match *pred {
+            WherePredicate::BoundPredicate { ref ty, ref bounds } => {
+                type_complexity(ty) + bounds_complexity(bounds)
+            }
+            WherePredicate::RegionPredicate { ref bounds, .. } => bounds.len(),
+            WherePredicate::EqPredicate { ref lhs, ref rhs } => {
+                type_complexity(lhs) + type_complexity(rhs)
+            }
+        }
+    }).sum();
+    params + predicates
+}
+
+fn bounds_complexity(bounds: &[GenericBound]) -> usize {
+    bounds.iter().map(|bound| match *bound {
+        GenericBound::TraitBound(ref poly, _) => {
+            type_complexity(&poly.trait_) + poly.generic_params.len()
+        }
+        GenericBound::Outlives(_) => 1,
+    }).sum()
+}
+
+fn type_complexity(ty: &Type) -> usize {
+    1 + match *ty {
+        Type::ResolvedPath { ref path, .. } => {
+            path.segments.iter().map(|segment| match segment.args {
+                GenericArgs::AngleBracketed { ref args, ref bindings } => {
+                    let args: usize = args.iter().map(|arg| match *arg {
+                        GenericArg::Type(ref ty) => type_complexity(ty),
+                        GenericArg::Lifetime(_) | GenericArg::Const(_) => 1,
+                    }).sum();
+                    args + bindings.len()
+                }
+                GenericArgs::Parenthesized { ref inputs, ref output } => {
+                    inputs.iter().map(type_complexity).sum::<usize>() +
+                        output.as_ref().map_or(0, type_complexity)
+                }
+            }).sum()
+        }
+        Type::BareFunction(ref f) => decl_complexity(&f.decl) + f.generic_params.len(),
+        Type::Tuple(ref tys) => tys.iter().map(type_complexity).sum(),
+        Type::Slice(ref ty) |
+        Type::Array(ref ty, _) |
+        Type::RawPointer(_, ref ty) |
+        Type::BorrowedRef { type_: ref ty, .. } => type_complexity(ty),
+        Type::QPath { ref self_type, ref trait_, .. } => {
+            type_complexity(self_type) + type_complexity(trait_)
+        }
+        Type::ImplTrait(ref bounds) => bounds_complexity(bounds),
+        Type::Generic(_) | Type::Primitive(_) | Type::Never | Type::Infer => 0,
+    }
+}
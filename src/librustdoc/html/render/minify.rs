@@ -0,0 +1,184 @@
+//! The minification of the HTML pages written with `--minify-output`.
+//!
+//! Only what cannot change how a page is displayed or behaves is taken out: comments, other than
+//! conditional ones, and the whitespace which a browser collapses or ignores anyway. The content
+//! of the elements whose whitespace is kept, like `<pre>` and `<code>`, is left untouched, and so
+//! is the content of `<script>` and `<style>` elements.
+
+/// The stylesheets of at most this many bytes, once minified, get their content inlined in the
+/// pages linking to them.
+const TINY_CSS_LEN: usize = 512;
+
+/// The elements whose whitespace is displayed as written, either because of their default style
+/// or because of the one given to them by rustdoc.
+const WHITESPACE_KEPT: &[&str] = &["pre", "code", "textarea"];
+
+/// The elements whose content is not HTML.
+const RAW_TEXT: &[&str] = &["script", "style"];
+
+/// The elements between which whitespace is never displayed.
+const STRUCTURAL: &[&str] = &[
+    "html", "head", "body", "meta", "link", "title", "style", "script", "noscript", "section",
+    "nav", "ul", "ol", "li", "table", "thead", "tbody", "tfoot", "tr", "td", "th", "h1", "h2",
+    "h3", "h4", "h5", "h6", "blockquote", "br", "hr",
+];
+
+crate struct HtmlMinifier {
+    /// The minified content of the tiny stylesheets, by file name.
+    tiny_css: Vec<(String, String)>,
+}
+
+impl HtmlMinifier {
+    /// Creates a minifier inlining the stylesheets among `stylesheets`, given by file name, which
+    /// are tiny enough. Stylesheets referring to other files are not inlined, since the URLs
+    /// would then be relative to the page.
+    crate fn new(stylesheets: Vec<(String, String)>) -> HtmlMinifier {
+        let tiny_css = stylesheets.into_iter().filter_map(|(name, css)| {
+            let css = minifier::css::minify(&css).ok()?;
+            if css.len() <= TINY_CSS_LEN && !css.contains("url(") && !css.contains("</") {
+                Some((name, css))
+            } else {
+                None
+            }
+        }).collect();
+        HtmlMinifier { tiny_css }
+    }
+
+    crate fn minify(&self, html: &str) -> String {
+        let mut out = String::with_capacity(html.len());
+        // The tag written before the text being copied, if any.
+        let mut previous_tag = None;
+        // How many of the elements whose whitespace is kept are open.
+        let mut kept = 0usize;
+        let mut rest = html;
+        loop {
+            let (text, next) = rest.split_at(rest.find('<').unwrap_or(rest.len()));
+            if kept > 0 {
+                out.push_str(text);
+            } else if !is_whitespace(text) {
+                collapse_whitespace(text, &mut out);
+            } else if !text.is_empty() {
+                // Whitespace between two structural elements is dropped altogether.
+                let structural = |tag: Option<&str>| tag.map_or(false, |t| STRUCTURAL.contains(&t));
+                if !structural(previous_tag) || !structural(tag_name(next)) {
+                    out.push(' ');
+                }
+            }
+            rest = next;
+            if rest.is_empty() {
+                break;
+            }
+
+            if rest.starts_with("<!--") {
+                // Conditional comments are read by some browsers, so they are kept.
+                let end = if rest.starts_with("<!--[if") { "<![endif]-->" } else { "-->" };
+                let len = rest.find(end).map_or(rest.len(), |i| i + end.len());
+                if end != "-->" {
+                    out.push_str(&rest[..len]);
+                }
+                rest = &rest[len..];
+                continue;
+            }
+
+            let len = tag_len(rest);
+            let tag = &rest[..len];
+            rest = &rest[len..];
+            let name = tag_name(tag);
+            previous_tag = name;
+            let name = match name {
+                Some(name) => name,
+                None => {
+                    out.push_str(tag);
+                    continue;
+                }
+            };
+            let closing = tag.starts_with("</");
+
+            if !closing && RAW_TEXT.contains(&name) {
+                let end = format!("</{}", name);
+                let len = rest.find(&end).unwrap_or(rest.len());
+                out.push_str(tag);
+                out.push_str(&rest[..len]);
+                rest = &rest[len..];
+                continue;
+            }
+            if WHITESPACE_KEPT.contains(&name) && !tag.ends_with("/>") {
+                if closing {
+                    kept = kept.saturating_sub(1);
+                } else {
+                    kept += 1;
+                }
+            }
+            match self.inlined_stylesheet(name, tag) {
+                Some(css) => {
+                    out.push_str("<style type=\"text/css\">");
+                    out.push_str(css);
+                    out.push_str("</style>");
+                }
+                None => out.push_str(tag),
+            }
+        }
+        out
+    }
+
+    /// Returns the content to inline for `tag` if it links to one of the tiny stylesheets. The
+    /// stylesheets with an id are left alone, since the scripts of the page look them up.
+    fn inlined_stylesheet(&self, name: &str, tag: &str) -> Option<&str> {
+        if name != "link" || !tag.contains("rel=\"stylesheet\"") || tag.contains(" id=") {
+            return None;
+        }
+        let href = &tag[tag.find("href=\"")? + "href=\"".len()..];
+        let href = &href[..href.find('"')?];
+        let file = href.rsplit('/').next()?;
+        self.tiny_css.iter().find(|(name, _)| name == file).map(|(_, css)| &**css)
+    }
+}
+
+fn is_whitespace(text: &str) -> bool {
+    text.chars().all(|c| c.is_ascii_whitespace())
+}
+
+/// Writes `text` with its runs of whitespace replaced by a single space, as they are displayed.
+fn collapse_whitespace(text: &str, out: &mut String) {
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_ascii_whitespace() {
+            if !in_whitespace {
+                out.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            out.push(c);
+            in_whitespace = false;
+        }
+    }
+}
+
+/// Returns the length of the tag at the start of `html`, up to the `>` ending it outside of
+/// quoted attribute values.
+fn tag_len(html: &str) -> usize {
+    let mut quote = None;
+    for (i, c) in html.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return i + 1,
+            _ => {}
+        }
+    }
+    html.len()
+}
+
+/// Returns the name of the element of the opening or closing tag at the start of `html`, in
+/// lowercase as rustdoc writes them.
+fn tag_name(html: &str) -> Option<&str> {
+    let name = if html.starts_with("</") {
+        &html[2..]
+    } else if html.starts_with('<') {
+        &html[1..]
+    } else {
+        return None;
+    };
+    let len = name.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(name.len());
+    if len == 0 { None } else { Some(&name[..len]) }
+}
@@ -0,0 +1,68 @@
+//! The table of item names which the search uses for its fuzzy matching.
+//!
+//! The search ranks the items whose name is at most a few edits away from the query, which would
+//! otherwise mean computing an edit distance for every item of the index. The table groups the
+//! items by name, so that the distance is computed once per name, and sorts the groups by length,
+//! since the names much shorter or longer than the query are out of reach anyway. For the longer
+//! queries, the trigrams of the names narrow the candidates further: every edit changes at most
+//! three trigrams of a string, so a name `d` edits away from the query has all but `3 * d` of the
+//! trigrams of the query.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+#[derive(Serialize)]
+crate struct NameTable {
+    /// The positions in the index of the items sharing a name, for every name. The names are
+    /// sorted by the length of their lowercase form, as the search counts it.
+    #[serde(rename = "n")]
+    groups: Vec<Vec<usize>>,
+    /// The positions in `groups` of the names having each trigram, every position after the
+    /// first one given as the difference from the previous one. Only the trigrams of ASCII
+    /// characters are listed, and the search only uses them for ASCII queries.
+    #[serde(rename = "t")]
+    trigrams: BTreeMap<String, Vec<usize>>,
+}
+
+impl NameTable {
+    /// Builds the table of the names of the index, given in the order of its items.
+    crate fn new<'a>(names: impl IntoIterator<Item = &'a str>) -> NameTable {
+        let mut by_name: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (pos, name) in names.into_iter().enumerate() {
+            by_name.entry(name).or_default().push(pos);
+        }
+        // The search lowercases the names, and measures them in UTF-16 code units.
+        let mut names: Vec<(usize, String, Vec<usize>)> = by_name.into_iter()
+            .map(|(name, positions)| {
+                let lower = name.to_lowercase();
+                (lower.encode_utf16().count(), lower, positions)
+            })
+            .collect();
+        names.sort();
+
+        let mut groups = Vec::with_capacity(names.len());
+        let mut trigrams: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for (group, (_, lower, positions)) in names.into_iter().enumerate() {
+            for trigram in ascii_trigrams(&lower) {
+                trigrams.entry(trigram.to_owned()).or_default().push(group);
+            }
+            groups.push(positions);
+        }
+        for postings in trigrams.values_mut() {
+            for i in (1..postings.len()).rev() {
+                postings[i] -= postings[i - 1];
+            }
+        }
+        NameTable { groups, trigrams }
+    }
+}
+
+/// Returns the distinct trigrams of `name` made of ASCII characters.
+fn ascii_trigrams(name: &str) -> BTreeSet<&str> {
+    name.as_bytes().windows(3)
+        .enumerate()
+        .filter(|(_, window)| window.is_ascii())
+        .map(|(i, _)| &name[i..i + 3])
+        .collect()
+}
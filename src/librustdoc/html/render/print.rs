@@ -0,0 +1,99 @@
+//! The printable page of a crate, `print.html`, written with `--generate-print-page`.
+//!
+//! It holds the documentation of every item of the crate in reading order, each module followed
+//! by its items and the modules below it, so that the crate can be printed or saved as a PDF from
+//! a browser. The content of each item is the one rendered for its own page, whose relative URLs
+//! are made relative to the directory of the crate.
+
+use rustc_data_structures::sync::Lock;
+
+use crate::html::format::Buffer;
+
+#[derive(Default)]
+crate struct PrintPage {
+    /// The content of the page of each item, with the position of the item in its module and of
+    /// the modules above it in the one of the crate, in the order they were rendered in.
+    items: Lock<Vec<(Vec<usize>, String)>>,
+}
+
+impl PrintPage {
+    /// Records `content`, rendered for the page `page` in the directory `dir` of the crate, for
+    /// the item at `position`.
+    crate fn record(&self, position: Vec<usize>, dir: &[String], page: &str, content: &str) {
+        let mut dir = dir.join("/");
+        if !dir.is_empty() {
+            dir.push('/');
+        }
+        self.items.lock().push((position, relocate(content, &dir, page)));
+    }
+
+    crate fn print(&self, buf: &mut Buffer) {
+        let mut items = self.items.lock();
+        items.sort_by(|a, b| a.0.cmp(&b.0));
+        for (_, content) in items.iter() {
+            buf.write_str("<section class='print-item'>");
+            buf.write_str(content);
+            buf.write_str("</section>");
+        }
+    }
+}
+
+/// Makes the relative URLs of `html`, rendered for the page `page` in the directory `dir`,
+/// relative to the directory of the crate.
+///
+/// The links to anchors are made to point to `page`, since the ids of the sections of an item
+/// appear once for each item on the printable page. The scripts are left out, as they expect to
+/// run on the page of a single item.
+pub(super) fn relocate(html: &str, dir: &str, page: &str) -> String {
+    let html = strip_scripts(html);
+    let mut out = String::with_capacity(html.len());
+    let mut rest = &*html;
+    while let Some(eq) = rest.find('=') {
+        let is_url = rest[..eq].ends_with(" href") || rest[..eq].ends_with(" src");
+        let quote = rest[eq + 1..].chars().next().filter(|&c| c == '"' || c == '\'');
+        let (quote, start) = match quote {
+            Some(quote) if is_url => (quote, eq + 2),
+            _ => {
+                out.push_str(&rest[..eq + 1]);
+                rest = &rest[eq + 1..];
+                continue;
+            }
+        };
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find(quote).unwrap_or(rest.len());
+        let url = &rest[..end];
+        if url.starts_with('#') {
+            out.push_str(dir);
+            out.push_str(page);
+        } else if !url.is_empty() && !is_absolute(url) {
+            out.push_str(dir);
+        }
+        out.push_str(url);
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Returns whether `url` has a scheme, like `https:` or `javascript:`, or starts at the root of
+/// the host.
+fn is_absolute(url: &str) -> bool {
+    url.starts_with('/') ||
+        url.find(':').map_or(false, |colon| !url[..colon].contains(&['/', '?', '#'][..]))
+}
+
+fn strip_scripts(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find("<script") {
+        out.push_str(&rest[..start]);
+        rest = match rest[start..].find("</script>") {
+            Some(end) => &rest[start + end + "</script>".len()..],
+            None => "",
+        };
+    }
+    out.push_str(rest);
+    out
+}
+
@@ -0,0 +1,75 @@
+//! The inputs of the ranking of the search results which the search index provides.
+//!
+//! The search sorts the items matching a query by these criteria, each one only deciding
+//! between the items which the previous ones leave tied:
+//!
+//! 1. Whether the name is the query itself.
+//! 2. How many edits the name is away from the query, plus a fraction of an edit for every edit
+//!    the path is away from the path given in the query, if any.
+//! 3. Whether the item is deprecated: a deprecated item ranks after the items replacing it.
+//! 4. Whether the item is in the crate of the current page.
+//! 5. The length of the name, then the name itself.
+//! 6. Where the query appears in the name, the names not containing it coming last.
+//! 7. The weight of the kind of item, as given by `KIND_ORDER`.
+//! 8. Whether the item has a description.
+//! 9. How many modules deep the item is, then its path.
+//!
+//! The kind weights and the deprecated items are written to the index by rustdoc, so that the
+//! ranking does not depend on the script alone. The order of the results for a query is tested
+//! in `src/test/rustdoc-js`, and changing any of these criteria changes it.
+
+use rustc::middle::stability;
+
+use crate::clean;
+use crate::html::item_type::ItemType;
+
+/// The kinds of items from the first ranked to the last ranked. The pages of the primitives and
+/// keywords come first, since they document the language itself.
+const KIND_ORDER: &[ItemType] = &[
+    ItemType::Primitive,
+    ItemType::Keyword,
+    ItemType::Module,
+    ItemType::ExternCrate,
+    ItemType::Import,
+    ItemType::Struct,
+    ItemType::Enum,
+    ItemType::Function,
+    ItemType::Typedef,
+    ItemType::Static,
+    ItemType::Trait,
+    ItemType::Impl,
+    ItemType::TyMethod,
+    ItemType::Method,
+    ItemType::StructField,
+    ItemType::Variant,
+    ItemType::Macro,
+    ItemType::AssocType,
+    ItemType::Constant,
+    ItemType::AssocConst,
+    ItemType::Union,
+    ItemType::ForeignType,
+    ItemType::OpaqueTy,
+    ItemType::ProcAttribute,
+    ItemType::ProcDerive,
+    ItemType::TraitAlias,
+];
+
+/// Returns the weight of every kind of item, by item type: the kinds with a lower weight rank
+/// first.
+crate fn kind_weights() -> Vec<usize> {
+    let len = KIND_ORDER.iter().map(|&ty| ty as usize + 1).max().unwrap_or(0);
+    let mut weights = vec![KIND_ORDER.len(); len];
+    for (weight, &ty) in KIND_ORDER.iter().enumerate() {
+        weights[ty as usize] = weight;
+    }
+    weights
+}
+
+/// Returns whether `item` is deprecated already, rather than planned to be in a later version.
+crate fn is_deprecated(item: &clean::Item) -> bool {
+    item.deprecation().is_some() &&
+        item.stability.as_ref()
+            .and_then(|stab| stab.deprecation.as_ref())
+            .and_then(|depr| depr.since.as_ref())
+            .map_or(true, |since| stability::deprecation_in_effect(since))
+}
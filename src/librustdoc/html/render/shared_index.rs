@@ -0,0 +1,89 @@
+//! The search index shared by the crates documented to the same output directory.
+//!
+//! Every crate documented to an output directory adds its entry to the search index there, and
+//! keeps the entries of the other crates, so that the search finds the items of all of them. The
+//! entries are kept in `search-index.json`, as a JSON object with one entry per crate, and the
+//! `search-index.js` script loaded by the pages is generated from it. The output directories
+//! written before this file existed only have the script, whose entries are then read from it.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+crate struct SharedSearchIndex {
+    /// The entry of every crate, by name.
+    crates: BTreeMap<String, Value>,
+}
+
+impl SharedSearchIndex {
+    /// Loads the index saved to `json`, or the one of the script `js` if there is none.
+    crate fn load(json: &Path, js: &Path) -> io::Result<SharedSearchIndex> {
+        let crates = if json.exists() {
+            serde_json::from_slice(&fs::read(json)?).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData,
+                               format!("the search index is not valid: {}", e))
+            })?
+        } else if js.exists() {
+            parse_script(&fs::read_to_string(js)?)
+        } else {
+            BTreeMap::new()
+        };
+        Ok(SharedSearchIndex { crates })
+    }
+
+    /// Adds the entry of the crate `krate`, replacing the one of a previous run. Returns whether
+    /// the entry is a different one.
+    crate fn insert(&mut self, krate: &str, entry: Value) -> bool {
+        self.crates.insert(krate.to_owned(), entry.clone()) != Some(entry)
+    }
+
+    /// Returns the entry of the crate `krate`, if it is in the index.
+    crate fn get(&self, krate: &str) -> Option<&Value> {
+        self.crates.get(krate)
+    }
+
+    /// Returns the names of the crates in the index, sorted.
+    crate fn krates(&self) -> impl Iterator<Item = &str> {
+        self.crates.keys().map(|krate| &**krate)
+    }
+
+    /// Writes the index to `json`, through a temporary file renamed once written, so that an
+    /// interrupted run does not leave the entries of the other crates unreadable.
+    crate fn save(&self, json: &Path) -> io::Result<()> {
+        let mut tmp = json.as_os_str().to_owned();
+        tmp.push(".tmp");
+        fs::write(&tmp, serde_json::to_string(&self.crates)?)?;
+        fs::rename(&tmp, json)
+    }
+
+    /// Returns the `search-index.js` script loading the index, with one line per crate.
+    crate fn to_script(&self) -> String {
+        let mut script = String::from("var searchIndex={};\n");
+        for (krate, entry) in &self.crates {
+            script.push_str(&format!("searchIndex[{}] = {};\n",
+                                     serde_json::to_string(krate).unwrap(),
+                                     serde_json::to_string(entry).unwrap()));
+        }
+        // "addSearchOptions" has to be called first so the crate filtering can be set before the
+        // search might start (if it's set into the URL for example).
+        script.push_str("addSearchOptions(searchIndex);initSearch(searchIndex);");
+        script
+    }
+}
+
+/// Reads the entries of a `search-index.js` script. The lines which cannot be read are left out.
+fn parse_script(script: &str) -> BTreeMap<String, Value> {
+    script.lines()
+        .filter(|line| line.starts_with("searchIndex["))
+        .filter_map(|line| {
+            let (krate, entry) = line.split_at(line.find(" = ")?);
+            let krate = krate.get("searchIndex[".len()..krate.len() - 1)?;
+            let krate = serde_json::from_str(krate).ok()?;
+            let entry = entry[" = ".len()..].trim_end_matches(';');
+            Some((krate, serde_json::from_str(entry).ok()?))
+        })
+        .collect()
+}
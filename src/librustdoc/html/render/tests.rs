@@ -27,3 +27,238 @@ fn test_name_sorting() {
     sorted.sort_by_key(|&s| name_key(s));
     assert_eq!(names, sorted);
 }
+
+#[test]
+fn test_reading_minutes() {
+    let metrics = |doc_words| ItemMetrics { doc_words, ..ItemMetrics::default() };
+    assert_eq!(metrics(0).reading_minutes(), 0);
+    assert_eq!(metrics(1).reading_minutes(), 1);
+    assert_eq!(metrics(200).reading_minutes(), 1);
+    assert_eq!(metrics(201).reading_minutes(), 2);
+}
+
+#[test]
+fn test_relocate() {
+    fn t(html: &str, expected: &str) {
+        assert_eq!(print::relocate(html, "foo/bar/", "struct.Baz.html"), expected);
+    }
+
+    t("<a href='struct.Qux.html'>Qux</a>", "<a href='foo/bar/struct.Qux.html'>Qux</a>");
+    t("<a href=\"../index.html\">", "<a href=\"foo/bar/../index.html\">");
+    t("<a href='#method.new'>", "<a href='foo/bar/struct.Baz.html#method.new'>");
+    t("<a href='https://example.com/'>", "<a href='https://example.com/'>");
+    t("<a href=\"javascript:void(0)\">", "<a href=\"javascript:void(0)\">");
+    t("<img src='/logo.png'>", "<img src='/logo.png'>");
+    t("<code>a == b</code>", "<code>a == b</code>");
+    t("<p>x</p><script src='a.js'></script><p>y</p>", "<p>x</p><p>y</p>");
+}
+
+#[test]
+fn test_minify() {
+    let minifier = minify::HtmlMinifier::new(vec![
+        ("tiny.css".to_string(), ".a {\n    display: none;\n}\n".to_string()),
+        ("image.css".to_string(), ".a { background: url(a.png); }".to_string()),
+    ]);
+    let t = |html: &str, expected: &str| assert_eq!(minifier.minify(html), expected);
+
+    t("<p>a   lot\n  of\tspace</p>", "<p>a lot of space</p>");
+    t("<ul>\n  <li>a</li>\n  <li>b</li>\n</ul>", "<ul><li>a</li><li>b</li></ul>");
+    t("<a>x</a>\n<a>y</a>", "<a>x</a> <a>y</a>");
+    t("<p>a<!-- comment -->b</p>", "<p>ab</p>");
+    t("<!--[if lte IE 8]><div>old</div><![endif]-->",
+      "<!--[if lte IE 8]><div>old</div><![endif]-->");
+    t("<pre class='rust'>fn  main() {\n    <span>x</span>\n}</pre>  <p>a</p>",
+      "<pre class='rust'>fn  main() {\n    <span>x</span>\n}</pre> <p>a</p>");
+    t("<p><code>a  =  b</code>  c</p>", "<p><code>a  =  b</code> c</p>");
+    t("<script>if (a  <  b) {\n}</script>", "<script>if (a  <  b) {\n}</script>");
+    t("<a title='a > b'>  x</a>", "<a title='a > b'> x</a>");
+    let inlined = minifier.minify("<link rel=\"stylesheet\" href=\"../tiny.css\">");
+    assert!(inlined.starts_with("<style type=\"text/css\">.a"), "{}", inlined);
+    assert!(inlined.ends_with("</style>"), "{}", inlined);
+    t("<link rel=\"stylesheet\" href=\"../tiny.css\" id=\"themeStyle\">",
+      "<link rel=\"stylesheet\" href=\"../tiny.css\" id=\"themeStyle\">");
+    t("<link rel=\"stylesheet\" href=\"../image.css\">",
+      "<link rel=\"stylesheet\" href=\"../image.css\">");
+}
+
+#[test]
+fn test_impl_fragments() {
+    let fragments = ImplFragments::default();
+    let a = fragments.record("<h3 class='impl'>impl A</h3>".to_string());
+    let b = fragments.record("<h3 class='impl'>impl B</h3>".to_string());
+    assert_eq!(fragments.record("<h3 class='impl'>impl A</h3>".to_string()), a);
+    assert_ne!(a, b);
+    assert!(a.ends_with(".js"), "{}", a);
+
+    let files = fragments.take_files();
+    assert_eq!(files.len(), 2);
+    let (_, script) = files.iter().find(|(file_name, _)| *file_name == a).unwrap();
+    assert_eq!(script, "insertImplFragment(\"<h3 class='impl'>impl A</h3>\");");
+    assert!(fragments.take_files().is_empty());
+}
+
+#[test]
+fn test_static_files() {
+    let mut files = StaticFiles { hash_names: true, names: Default::default(), files: Vec::new() };
+    files.add("Font.woff".to_owned(), b"font".to_vec());
+    files.add("main.css".to_owned(), b"src: url(\"Font.woff\");".to_vec());
+    files.add_unreferenced("LICENSE.txt", b"license");
+    let font = files.names.get("Font.woff").to_owned();
+    assert!(font.starts_with("Font-") && font.ends_with(".woff"));
+    assert_eq!(font.len(), "Font-.woff".len() + 16);
+    assert_eq!(files.files[1].1, format!("src: url(\"{}\");", font).into_bytes());
+    assert_eq!(files.files[2].0, "LICENSE.txt");
+    assert_eq!(files.names.get("LICENSE.txt"), "LICENSE.txt");
+}
+
+#[test]
+fn test_gzip() {
+    use flate2::read::GzDecoder;
+
+    let index = "var searchIndex={};\n".repeat(100);
+    let compressed = gzip(index.as_bytes()).unwrap();
+    assert!(compressed.len() < index.len());
+    let mut decompressed = String::new();
+    GzDecoder::new(&compressed[..]).read_to_string(&mut decompressed).unwrap();
+    assert_eq!(decompressed, index);
+}
+
+#[test]
+fn test_name_table() {
+    let table = name_table::NameTable::new(vec!["push", "Vec", "push", "vec", "push_str"]);
+    assert_eq!(serde_json::to_string(&table).unwrap(),
+               "{\"n\":[[1],[3],[0,2],[4]],\
+                 \"t\":{\"_st\":[3],\"h_s\":[3],\"pus\":[2,1],\"sh_\":[3],\"str\":[3],\
+                        \"ush\":[2,1],\"vec\":[0,1]}}");
+}
+
+#[test]
+fn test_shared_search_index() {
+    let dir = tempfile::tempdir().unwrap();
+    let js = dir.path().join("search-index.js");
+    let json = dir.path().join("search-index.json");
+    fs::write(&js, "var searchIndex={};\n\
+                    searchIndex[\"foo\"] = {\"doc\":\"Foo.\"};\n\
+                    searchIndex[\"bar\"] = {\"doc\":\"Old.\"};\n\
+                    addSearchOptions(searchIndex);initSearch(searchIndex);").unwrap();
+
+    let mut index = shared_index::SharedSearchIndex::load(&json, &js).unwrap();
+    assert!(index.insert("bar", serde_json::json!({"doc": "Bar."})));
+    assert!(!index.insert("bar", serde_json::json!({"doc": "Bar."})));
+    assert_eq!(index.krates().collect::<Vec<_>>(), ["bar", "foo"]);
+    assert_eq!(index.to_script(),
+               "var searchIndex={};\n\
+                searchIndex[\"bar\"] = {\"doc\":\"Bar.\"};\n\
+                searchIndex[\"foo\"] = {\"doc\":\"Foo.\"};\n\
+                addSearchOptions(searchIndex);initSearch(searchIndex);");
+    index.save(&json).unwrap();
+
+    // Once saved, the index is loaded from its JSON file rather than from the script.
+    fs::remove_file(&js).unwrap();
+    let index = shared_index::SharedSearchIndex::load(&json, &js).unwrap();
+    assert_eq!(index.krates().collect::<Vec<_>>(), ["bar", "foo"]);
+    assert!(!dir.path().join("search-index.json.tmp").exists());
+}
+
+#[test]
+fn test_kind_weights() {
+    let weights = ranking::kind_weights();
+    assert_eq!(weights.len(), ItemType::TraitAlias as usize + 1);
+    let mut sorted = weights.clone();
+    sorted.sort();
+    assert_eq!(sorted, (0..weights.len()).collect::<Vec<_>>());
+    assert_eq!(weights[ItemType::Primitive as usize], 0);
+    assert!(weights[ItemType::Struct as usize] < weights[ItemType::Function as usize]);
+}
+
+#[test]
+fn test_index_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join(index_entries::INDEX_ENTRIES_FILE);
+    let mut entries = IndexEntries::load(&file);
+    assert_eq!(entries.summary("fn:foo::bar".to_owned(), Some("Does *this*.\n\nMore.")),
+               "Does this.");
+    assert_eq!(entries.summary("fn:foo::baz".to_owned(), None), "");
+    // Edit the saved summary, to tell it apart from one rendered again.
+    fs::write(&file, entries.to_json().replace("Does this.", "Saved.")).unwrap();
+
+    let mut entries = IndexEntries::load(&file);
+    assert_eq!(entries.summary("fn:foo::bar".to_owned(), Some("Does *this*.\n\nMore.")),
+               "Saved.");
+    assert_eq!(entries.summary("fn:foo::bar".to_owned(), Some("Does *that*.")), "Does that.");
+    assert_eq!(entries.summary("fn:foo::qux".to_owned(), Some("Does *this*.\n\nMore.")),
+               "Does this.");
+    // The summaries of the items gone since are not saved again.
+    assert!(!entries.to_json().contains("foo::baz"));
+}
+
+#[test]
+fn test_cfg_to_json() {
+    syntax::with_default_globals(|| {
+        let unix = Cfg::Cfg(Symbol::intern("unix"), None);
+        let wasm = Cfg::Cfg(Symbol::intern("target_arch"), Some(Symbol::intern("wasm32")));
+        let cfg = Cfg::Any(vec![unix, Cfg::Not(Box::new(wasm))]);
+        assert_eq!(facets::cfg_to_json(&cfg),
+                   serde_json::json!(["any", "unix", ["not", "target_arch=wasm32"]]));
+        assert_eq!(facets::cfg_to_json(&Cfg::True), serde_json::json!(true));
+    })
+}
+
+#[test]
+fn test_merge_docs_lines() {
+    let dir = tempfile::tempdir().unwrap();
+    let src = dir.path().join("src.js");
+    let dst = dir.path().join("dst.js");
+    fs::write(&src, "var ALIASES = {};\n\
+                     ALIASES[\"bar\"] = {\"new\":[]};\n\
+                     ALIASES[\"foo\"] = {\"dup\":[]};\n").unwrap();
+    fs::write(&dst, "var ALIASES = {};\n\
+                     ALIASES[\"bar\"] = {\"old\":[]};\n\
+                     ALIASES[\"baz\"] = {};\n").unwrap();
+
+    // Only the entries of the crates taken from the source replace the ones of the destination.
+    let own = ["bar".to_owned()].iter().cloned().collect();
+    merge_docs::merge_lines(&src, &dst, "ALIASES", &own).unwrap();
+    assert_eq!(fs::read_to_string(&dst).unwrap(),
+               "var ALIASES = {};\n\
+                ALIASES[\"bar\"] = {\"new\":[]};\n\
+                ALIASES[\"baz\"] = {};\n");
+}
+
+#[test]
+fn test_merge_docs_links() {
+    let links = [("https://docs.rs/foo/1.0/foo/".to_owned(), "foo/".to_owned())];
+    assert_eq!(merge_docs::rewrite_links("<a href=\"https://docs.rs/foo/1.0/foo/struct.Foo.html\">\
+                                          <a href=\"https://docs.rs/bar/1.0/bar/\">",
+                                         &links, "../../"),
+               "<a href=\"../../foo/struct.Foo.html\"><a href=\"https://docs.rs/bar/1.0/bar/\">");
+}
+
+#[test]
+fn test_versions_order() {
+    use std::cmp::Ordering;
+    assert_eq!(versions::compare_versions("1.10.0", "1.2.0"), Ordering::Greater);
+    assert_eq!(versions::compare_versions("1.2.0-beta.2", "1.2.0"), Ordering::Less);
+    assert_eq!(versions::compare_versions("1.2.0-beta.10", "1.2.0-beta.2"), Ordering::Greater);
+    assert_eq!(versions::compare_versions("1.2", "1.2.0"), Ordering::Less);
+    assert_eq!(versions::compare_versions("0.3.1", "0.3.1"), Ordering::Equal);
+}
+
+#[test]
+fn test_versions_update() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    for version in &["1.10.0", "1.9.0"] {
+        fs::create_dir_all(root.join(version)).unwrap();
+        fs::write(root.join(version).join("search-index.json"),
+                  format!("{{\"foo\":{{\"v\":\"{}\"}}}}", version)).unwrap();
+        versions::update(root, version, "").unwrap();
+    }
+
+    // The latest version is the greatest one, not the last one documented.
+    assert_eq!(fs::read_to_string(root.join(versions::VERSIONS_FILE)).unwrap(),
+               "{\"latest\":\"1.10.0\",\"versions\":[\"1.10.0\",\"1.9.0\"]}");
+    let redirect = fs::read_to_string(root.join("latest/foo/index.html")).unwrap();
+    assert!(redirect.contains("../../1.10.0/foo/index.html"));
+    assert!(!root.join("latest/index.html").exists());
+}
@@ -0,0 +1,114 @@
+//! The layout of the documentation of several versions, with `--versioned-output`.
+//!
+//! Every version of the documentation is written to the directory named after it in the output
+//! directory, which also holds `versions.json`, the manifest listing them from the latest to the
+//! oldest for a version switcher to read, and the `latest` directory, whose pages redirect to the
+//! ones of the crates of the latest version.
+
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+
+use rustc_data_structures::flock;
+use serde::{Deserialize, Serialize};
+
+use crate::docfs::PathError;
+use crate::html::layout;
+
+use super::{Error, SharedSearchIndex};
+
+/// The manifest of the versions, in the output directory.
+crate const VERSIONS_FILE: &str = "versions.json";
+
+/// The directory redirecting to the latest version.
+const LATEST_DIR: &str = "latest";
+
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    latest: String,
+    /// The versions, from the latest to the oldest.
+    versions: Vec<String>,
+}
+
+/// Adds `version` to the manifest of the output directory `root`, and points the redirect pages
+/// to the latest version.
+crate fn update(root: &Path, version: &str, resource_suffix: &str) -> Result<(), Error> {
+    let lock_file = root.join(".lock");
+    let _lock = flock::Lock::new(&lock_file, true, true, true)
+        .map_err(|e| Error::new(e, &lock_file))?;
+
+    let manifest_file = root.join(VERSIONS_FILE);
+    let mut manifest: Manifest = fs::read_to_string(&manifest_file).ok()
+        .and_then(|manifest| serde_json::from_str(&manifest).ok())
+        .unwrap_or_default();
+    if !manifest.versions.iter().any(|v| v == version) {
+        manifest.versions.push(version.to_owned());
+    }
+    manifest.versions.sort_by(|a, b| compare_versions(b, a));
+    manifest.latest = manifest.versions[0].clone();
+    let contents = serde_json::to_string(&manifest).unwrap();
+    fs::write(&manifest_file, contents).map_err(|e| Error::new(e, &manifest_file))?;
+
+    // The redirect pages are written again from scratch, the crates of the latest version not
+    // being the ones of the previous latest version if a crate was added or removed.
+    let latest = root.join(LATEST_DIR);
+    if latest.exists() {
+        fs::remove_dir_all(&latest).map_err(|e| Error::new(e, &latest))?;
+    }
+    let version_dir = root.join(&manifest.latest);
+    let index = SharedSearchIndex::load(
+        &version_dir.join(format!("search-index{}.json", resource_suffix)),
+        &version_dir.join(format!("search-index{}.js", resource_suffix)),
+    ).map_err(|e| Error::new(e, &version_dir))?;
+    for krate in index.krates() {
+        let dir = latest.join(krate);
+        fs::create_dir_all(&dir).map_err(|e| Error::new(e, &dir))?;
+        let page = dir.join("index.html");
+        let url = format!("../../{}/{}/index.html", manifest.latest, krate);
+        fs::write(&page, layout::redirect(&url)).map_err(|e| Error::new(e, &page))?;
+    }
+    if version_dir.join("index.html").exists() {
+        fs::create_dir_all(&latest).map_err(|e| Error::new(e, &latest))?;
+        let page = latest.join("index.html");
+        let url = format!("../{}/index.html", manifest.latest);
+        fs::write(&page, layout::redirect(&url)).map_err(|e| Error::new(e, &page))?;
+    }
+    Ok(())
+}
+
+/// Compares two versions like `1.10.0` and `1.2.0-beta.1`, as numbers when they are, a version
+/// with a pre-release coming before the same version without.
+crate fn compare_versions(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (&str, Option<&str>) {
+        match version.find('-') {
+            Some(pos) => (&version[..pos], Some(&version[pos + 1..])),
+            None => (version, None),
+        }
+    }
+    fn compare_parts(a: &str, b: &str) -> Ordering {
+        let mut a_parts = a.split('.');
+        let mut b_parts = b.split('.');
+        loop {
+            let ord = match (a_parts.next(), b_parts.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                    (Ok(a), Ok(b)) => a.cmp(&b),
+                    _ => a.cmp(b),
+                },
+            };
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+    }
+
+    let ((a, a_pre), (b, b_pre)) = (split(a), split(b));
+    compare_parts(a, b).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_parts(a, b),
+    })
+}
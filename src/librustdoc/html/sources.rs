@@ -2,24 +2,51 @@ use crate::clean;
 use crate::docfs::PathError;
 use crate::fold::DocFolder;
 use crate::html::layout;
-use crate::html::render::{Error, SharedContext, BASIC_KEYWORDS};
+use crate::html::render::{cache, Error, SharedContext, BASIC_KEYWORDS, CURRENT_DEPTH};
 use crate::html::highlight;
-use crate::html::format::Buffer;
+use crate::html::format::{href, Buffer};
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use std::ffi::OsStr;
 use std::fs;
+use std::mem;
 use std::path::{Component, Path, PathBuf};
 use syntax::source_map::FileName;
 
 crate fn render(dst: &Path, scx: &mut SharedContext,
                   krate: clean::Crate) -> Result<clean::Crate, Error> {
     info!("emitting source files");
-    let dst = dst.join("src").join(&krate.name);
+    let dst = dst.join("src").join(&*krate.name.as_str());
     scx.ensure_dir(&dst)?;
     let mut folder = SourceCollector {
         dst,
         scx,
+        krate_name: krate.name.to_string(),
+        files: Vec::new(),
+        seen_files: Default::default(),
     };
-    Ok(folder.fold_crate(krate))
+    let krate = folder.fold_crate(krate);
+
+    // The files are only emitted once they are all known, so that the paths in them can link to
+    // definitions in any of them.
+    for file in mem::take(&mut folder.files) {
+        if !folder.scx.include_sources {
+            break;
+        }
+
+        // If it turns out that we couldn't read this file, then we probably
+        // can't read any of the files (generating html output from json or
+        // something like that), so just don't include sources for the
+        // entire crate. The other option is maintaining this mapping on a
+        // per-file basis, but that's probably not worth it...
+        if let Err(e) = folder.emit_source(&file) {
+            println!("warning: source code was requested to be rendered, \
+                      but processing `{}` had an error: {}",
+                     file.display(), e);
+            println!("         skipping rendering of source code");
+            folder.scx.include_sources = false;
+        }
+    }
+    Ok(krate)
 }
 
 /// Helper struct to render all source code to HTML pages
@@ -28,6 +55,10 @@ struct SourceCollector<'a> {
 
     /// Root destination to place all HTML output into
     dst: PathBuf,
+    krate_name: String,
+    /// The source files to render, in the order their first item was found in.
+    files: Vec<PathBuf>,
+    seen_files: FxHashSet<PathBuf>,
 }
 
 impl<'a> DocFolder for SourceCollector<'a> {
@@ -35,27 +66,14 @@ impl<'a> DocFolder for SourceCollector<'a> {
         // If we're including source files, and we haven't seen this file yet,
         // then we need to render it out to the filesystem.
         if self.scx.include_sources
-            // skip all invalid or macro spans
-            && item.source.filename.is_real()
             // skip non-local items
             && item.def_id.is_local() {
-
-            // If it turns out that we couldn't read this file, then we probably
-            // can't read any of the files (generating html output from json or
-            // something like that), so just don't include sources for the
-            // entire crate. The other option is maintaining this mapping on a
-            // per-file basis, but that's probably not worth it...
-            self.scx
-                .include_sources = match self.emit_source(&item.source.filename) {
-                Ok(()) => true,
-                Err(e) => {
-                    println!("warning: source code was requested to be rendered, \
-                              but processing `{}` had an error: {}",
-                             item.source.filename, e);
-                    println!("         skipping rendering of source code");
-                    false
+            // skip all invalid or macro spans
+            if let FileName::Real(ref file) = item.source.filename {
+                if self.seen_files.insert(file.clone()) {
+                    self.files.push(file.clone());
                 }
-            };
+            }
         }
         self.fold_item_recur(item)
     }
@@ -63,12 +81,8 @@ impl<'a> DocFolder for SourceCollector<'a> {
 
 impl<'a> SourceCollector<'a> {
     /// Renders the given filename into its corresponding HTML source file.
-    fn emit_source(&mut self, filename: &FileName) -> Result<(), Error> {
-        let p = match *filename {
-            FileName::Real(ref file) => file,
-            _ => return Ok(()),
-        };
-        if self.scx.local_sources.contains_key(&**p) {
+    fn emit_source(&mut self, p: &Path) -> Result<(), Error> {
+        if self.scx.local_sources.contains_key(p) {
             // We've already emitted this source
             return Ok(());
         }
@@ -90,12 +104,9 @@ impl<'a> SourceCollector<'a> {
         // Create the intermediate directories
         let mut cur = self.dst.clone();
         let mut root_path = String::from("../../");
-        let mut href = String::new();
         clean_path(&self.scx.src_root, &p, false, |component| {
             cur.push(component);
             root_path.push_str("../");
-            href.push_str(&component.to_string_lossy());
-            href.push('/');
         });
         self.scx.ensure_dir(&cur)?;
         let mut fname = p.file_name()
@@ -103,11 +114,12 @@ impl<'a> SourceCollector<'a> {
                          .to_os_string();
         fname.push(".html");
         cur.push(&fname);
-        href.push_str(&fname.to_string_lossy());
+        let href = source_href(&self.scx.src_root, p);
 
         let title = format!("{} -- source", cur.file_name().expect("failed to get file name")
                                                .to_string_lossy());
-        let desc = format!("Source to the Rust file `{}`.", filename);
+        let desc = format!("Source to the Rust file `{}`.", p.display());
+        let links = self.links(p, &root_path);
         let page = layout::Page {
             title: &title,
             css_class: "source",
@@ -119,13 +131,58 @@ impl<'a> SourceCollector<'a> {
             extra_scripts: &[&format!("source-files{}", self.scx.resource_suffix)],
             static_extra_scripts: &[&format!("source-script{}", self.scx.resource_suffix)],
         };
-        let v = layout::render(&self.scx.layout,
-                       &page, "", |buf: &mut _| print_src(buf, &contents),
-                       &self.scx.themes);
-        self.scx.fs.write(&cur, v.as_bytes())?;
-        self.scx.local_sources.insert(p.clone(), href);
+        self.scx.write_page(&cur, |w| {
+            layout::render(w, &self.scx.layout,
+                           &page, "", |buf: &mut _| print_src(buf, &contents, &links),
+                           &self.scx.themes)
+        })?;
+        self.scx.local_sources.insert(p.to_path_buf(), href);
         Ok(())
     }
+
+    /// Computes the URLs the paths in the source file `p` link to, from its page at `root_path`.
+    /// Items are linked to their documentation page, or else to their definition if it is in one
+    /// of the rendered source files.
+    fn links(&self, p: &Path, root_path: &str) -> FxHashMap<(u32, u32), String> {
+        let cache = cache();
+        let spans = match cache.span_map.get(p) {
+            Some(spans) => spans,
+            None => return FxHashMap::default(),
+        };
+
+        // `href` builds URLs relative to the current page from its depth.
+        CURRENT_DEPTH.with(|depth| depth.set(root_path.matches("../").count()));
+        spans.iter().filter_map(|(&range, link)| {
+            let url = match href(link.def_id) {
+                Some((url, ..)) => match link.fragment {
+                    Some(ref fragment) => format!("{}#{}", url, fragment),
+                    None => url,
+                },
+                None => {
+                    let (file, line) = link.definition.as_ref()?;
+                    if !self.seen_files.contains(file) {
+                        return None;
+                    }
+                    format!("{}src/{}/{}#{}",
+                            root_path, self.krate_name, source_href(&self.scx.src_root, file),
+                            line)
+                }
+            };
+            Some((range, url))
+        }).collect()
+    }
+}
+
+/// The URL of the page of the source file `p`, relative to the source pages of its crate.
+fn source_href(src_root: &Path, p: &Path) -> String {
+    let mut href = String::new();
+    clean_path(src_root, p, false, |component| {
+        href.push_str(&component.to_string_lossy());
+        href.push('/');
+    });
+    href.push_str(&p.file_name().expect("source has no filename").to_string_lossy());
+    href.push_str(".html");
+    href
 }
 
 /// Takes a path to a source file and cleans the path to it. This canonicalizes
@@ -157,7 +214,7 @@ where
 
 /// Wrapper struct to render the source code of a file. This will do things like
 /// adding line numbers to the left-hand side.
-fn print_src(buf: &mut Buffer, s: &str) {
+fn print_src(buf: &mut Buffer, s: &str, links: &FxHashMap<(u32, u32), String>) {
     let lines = s.lines().count();
     let mut cols = 0;
     let mut tmp = lines;
@@ -171,5 +228,5 @@ fn print_src(buf: &mut Buffer, s: &str) {
     }
     write!(buf, "</pre>");
     write!(buf, "{}",
-            highlight::render_with_highlighting(s, None, None, None));
+            highlight::render_with_links(s, links));
 }
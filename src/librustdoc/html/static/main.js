@@ -1,5 +1,5 @@
 // From rust:
-/* global ALIASES, currentCrate, rootPath */
+/* global ALIASES, currentCrate, resourcesSuffix, rootPath */
 
 // Local js definitions:
 /* global addClass, getCurrentValue, hasClass */
@@ -138,10 +138,6 @@ function getSearchElement() {
         }
     }
 
-    // used for special search precedence
-    var TY_PRIMITIVE = itemTypes.indexOf("primitive");
-    var TY_KEYWORD = itemTypes.indexOf("keyword");
-
     function getQueryStringParams() {
         var params = {};
         window.location.search.substring(1).split("&").
@@ -512,7 +508,7 @@ function getSearchElement() {
     }
 
     function initSearch(rawSearchIndex) {
-        var currentResults, index, searchIndex;
+        var currentResults, index, searchIndex, nameTables;
         var MAX_LEV_DISTANCE = 3;
         var MAX_RESULTS = 200;
         var GENERICS_DATA = 1;
@@ -569,7 +565,7 @@ function getSearchElement() {
                     if (results[i].id > -1) {
                         var obj = searchIndex[results[i].id];
                         obj.lev = results[i].lev;
-                        if (isType !== true || obj.type) {
+                        if ((isType !== true || obj.type) && passesFacets(query, obj)) {
                             var res = buildHrefAndPath(obj);
                             obj.displayPath = pathSplitter(res[0]);
                             obj.fullPath = obj.displayPath + obj.name;
@@ -586,6 +582,65 @@ function getSearchElement() {
                 return out;
             }
 
+            /**
+             * Adds the items declaring `alias` with `#[doc(alias = "...")]` to `results`. They
+             * come right after the items named exactly `alias` and before the approximate
+             * matches, and the items of the current crate come first. The aliases declared with
+             * a low priority come after all the items found by name instead.
+             */
+            function addAliases(results, alias) {
+                var key = alias.toLowerCase();
+                var seen = {};
+                var firstInexact = 0;
+                var i;
+                for (i = 0; i < results.length; ++i) {
+                    seen[results[i].fullPath] = true;
+                    if (firstInexact === i && results[i].name.toLowerCase() === key) {
+                        firstInexact = i + 1;
+                    }
+                }
+
+                var found = [];
+                var foundLow = [];
+                for (var crate in ALIASES) {
+                    if (!ALIASES.hasOwnProperty(crate) || !ALIASES[crate].hasOwnProperty(key) ||
+                            (filterCrates !== undefined && crate !== filterCrates)) {
+                        continue;
+                    }
+                    var aliases = ALIASES[crate][key];
+                    for (i = 0; i < aliases.length; ++i) {
+                        var item = aliases[i];
+                        if (typePassesFilter(typeFilter, item.ty) === false) {
+                            continue;
+                        }
+                        item.is_alias = true;
+                        item.alias = alias;
+                        item.path = item.p;
+                        var res = buildHrefAndPath(item);
+                        item.displayPath = pathSplitter(res[0]);
+                        item.fullPath = item.displayPath + item.name + "|" + item.ty;
+                        item.href = res[1];
+                        if (seen[item.fullPath] !== true) {
+                            seen[item.fullPath] = true;
+                            (item.low === true ? foundLow : found).push(item);
+                        }
+                    }
+                }
+                var byCrate = function(aaa, bbb) {
+                    var a = (aaa.crate !== window.currentCrate);
+                    var b = (bbb.crate !== window.currentCrate);
+                    return a - b;
+                };
+                found.sort(byCrate);
+                foundLow.sort(byCrate);
+
+                Array.prototype.splice.apply(results, [firstInexact, 0].concat(found));
+                Array.prototype.push.apply(results, foundLow);
+                if (results.length > MAX_RESULTS) {
+                    results.length = MAX_RESULTS;
+                }
+            }
+
             function sortResults(results, isType) {
                 var ar = [];
                 for (var entry in results) {
@@ -605,6 +660,8 @@ function getSearchElement() {
                     return [];
                 }
 
+                // The ranking model is documented in `html/render/ranking.rs`, and the data it
+                // needs is written to the search index there.
                 results.sort(function(aaa, bbb) {
                     var a, b;
 
@@ -619,6 +676,11 @@ function getSearchElement() {
                     b = (bbb.lev);
                     if (a !== b) { return a - b; }
 
+                    // sort by deprecation (deprecated items go later)
+                    a = (aaa.item.deprecated === true);
+                    b = (bbb.item.deprecated === true);
+                    if (a !== b) { return a - b; }
+
                     // sort by crate (non-current crate goes later)
                     a = (aaa.item.crate !== window.currentCrate);
                     b = (bbb.item.crate !== window.currentCrate);
@@ -643,24 +705,19 @@ function getSearchElement() {
                     b = bbb.index;
                     if (a !== b) { return a - b; }
 
-                    // special precedence for primitive and keyword pages
-                    if ((aaa.item.ty === TY_PRIMITIVE && bbb.item.ty !== TY_KEYWORD) ||
-                        (aaa.item.ty === TY_KEYWORD && bbb.item.ty !== TY_PRIMITIVE)) {
-                        return -1;
-                    }
-                    if ((bbb.item.ty === TY_PRIMITIVE && aaa.item.ty !== TY_PRIMITIVE) ||
-                        (bbb.item.ty === TY_KEYWORD && aaa.item.ty !== TY_KEYWORD)) {
-                        return 1;
-                    }
+                    // sort by kind (heavier kinds go later)
+                    a = aaa.item.kindWeight;
+                    b = bbb.item.kindWeight;
+                    if (a !== b) { return a - b; }
 
                     // sort by description (no description goes later)
-                    a = (aaa.item.desc === "");
-                    b = (bbb.item.desc === "");
+                    a = (aaa.item.desc === "" && !aaa.item.hasDesc);
+                    b = (bbb.item.desc === "" && !bbb.item.hasDesc);
                     if (a !== b) { return a - b; }
 
-                    // sort by type (later occurrence in `itemTypes` goes later)
-                    a = aaa.item.ty;
-                    b = bbb.item.ty;
+                    // sort by path depth (deeper goes later)
+                    a = aaa.item.path.split("::").length;
+                    b = bbb.item.path.split("::").length;
                     if (a !== b) { return a - b; }
 
                     // sort by path (lexicographically larger goes later)
@@ -1077,6 +1134,7 @@ function getSearchElement() {
 
                 var lev;
                 var lev_distance;
+                var nameDistances = getNameDistances(val, searchWords);
                 for (j = 0; j < nSearchWords; ++j) {
                     ty = searchIndex[j];
                     if (!ty || (filterCrates !== undefined && ty.crate !== filterCrates)) {
@@ -1108,7 +1166,14 @@ function getSearchElement() {
                             index = searchWords[j].replace(/_/g, "").indexOf(val);
                         }
                     }
-                    if ((lev = levenshtein(searchWords[j], val)) <= MAX_LEV_DISTANCE) {
+                    lev = nameDistances[j];
+                    if (lev === undefined) {
+                        // The names left out by the name tables are too far from the query, but
+                        // the distance to the ones containing it still ranks them.
+                        lev = searchWords[j].replace(/_/g, "").indexOf(val) > -1 ?
+                              levenshtein(searchWords[j], val) : MAX_LEV_DISTANCE + 1;
+                    }
+                    if (lev <= MAX_LEV_DISTANCE) {
                         if (typePassesFilter(typeFilter, ty.ty) === false) {
                             lev = MAX_LEV_DISTANCE + 1;
                         } else {
@@ -1177,24 +1242,83 @@ function getSearchElement() {
                 "returned": sortResults(results_returned, true),
                 "others": sortResults(results),
             };
-            if (ALIASES && ALIASES[window.currentCrate] &&
-                    ALIASES[window.currentCrate][query.raw]) {
-                var aliases = ALIASES[window.currentCrate][query.raw];
-                for (i = 0; i < aliases.length; ++i) {
-                    aliases[i].is_alias = true;
-                    aliases[i].alias = query.raw;
-                    aliases[i].path = aliases[i].p;
-                    var res = buildHrefAndPath(aliases[i]);
-                    aliases[i].displayPath = pathSplitter(res[0]);
-                    aliases[i].fullPath = aliases[i].displayPath + aliases[i].name;
-                    aliases[i].href = res[1];
-                    ret.others.unshift(aliases[i]);
-                    if (ret.others.length > MAX_RESULTS) {
-                        ret.others.pop();
+            if (ALIASES) {
+                addAliases(ret.others, query.query);
+            }
+            return ret;
+        }
+
+        /**
+         * Returns the distances to the query of the names which may be at most MAX_LEV_DISTANCE
+         * edits away from it, by index in `searchWords`. Thanks to the name tables, the distance
+         * is only computed once for every name, and only for the names of a close enough length
+         * which have enough trigrams in common with the query.
+         *
+         * @param  {[string]} val         [The query, lowercased]
+         * @param  {[string]} searchWords [The lowercased names of the items]
+         * @return {[Object]}             [The distances, by index in `searchWords`]
+         */
+        function getNameDistances(val, searchWords) {
+            var distances = {};
+            // Every edit changes at most three trigrams, and the tables only list the ASCII ones.
+            var trigrams = /^[\x00-\x7f]*$/.test(val) ? getTrigrams(val) : [];
+            var minShared = trigrams.length - 3 * MAX_LEV_DISTANCE;
+            var minLength = val.length - MAX_LEV_DISTANCE;
+            var maxLength = val.length + MAX_LEV_DISTANCE;
+
+            nameTables.forEach(function(table) {
+                var groups = table.n;
+                var shared = null;
+                if (minShared > 0) {
+                    shared = {};
+                    trigrams.forEach(function(trigram) {
+                        var postings = table.t[trigram] || [];
+                        var group = 0;
+                        for (var i = 0; i < postings.length; ++i) {
+                            group += postings[i];
+                            shared[group] = (shared[group] || 0) + 1;
+                        }
+                    });
+                }
+                var nameAt = function(group) {
+                    return searchWords[table.start + groups[group][0]];
+                };
+                // The groups are sorted by the length of their name.
+                var low = 0, high = groups.length;
+                while (low < high) {
+                    var mid = (low + high) >> 1;
+                    if (nameAt(mid).length < minLength) {
+                        low = mid + 1;
+                    } else {
+                        high = mid;
+                    }
+                }
+                for (var group = low; group < groups.length; ++group) {
+                    var name = nameAt(group);
+                    if (name.length > maxLength) {
+                        break;
                     }
+                    if (shared !== null && (shared[group] || 0) < minShared) {
+                        continue;
+                    }
+                    var lev = levenshtein(name, val);
+                    for (var i = 0; i < groups[group].length; ++i) {
+                        distances[table.start + groups[group][i]] = lev;
+                    }
+                }
+            });
+            return distances;
+        }
+
+        function getTrigrams(s) {
+            var trigrams = [];
+            for (var i = 0; i + 3 <= s.length; ++i) {
+                var trigram = s.substr(i, 3);
+                if (trigrams.indexOf(trigram) === -1) {
+                    trigrams.push(trigram);
                 }
             }
-            return ret;
+            return trigrams;
         }
 
         /**
@@ -1231,21 +1355,91 @@ function getSearchElement() {
             return true;
         }
 
+        /**
+         * Returns whether an item available on the configuration `cfg` is available on
+         * `platform`. The options of the target match the platform if their value is the
+         * platform, as do `unix` and `windows` if they are the platform, and the other options
+         * of the target do not. The options not about the target, like the cargo features, are
+         * taken as enabled.
+         */
+        function isAvailableOn(cfg, platform) {
+            if (typeof cfg === "boolean") {
+                return cfg;
+            }
+            if (typeof cfg === "string") {
+                var eq = cfg.indexOf("=");
+                if (eq === -1) {
+                    return cfg === "unix" || cfg === "windows" ? cfg === platform : true;
+                }
+                if (cfg.substring(0, eq).indexOf("target_") !== 0) {
+                    return true;
+                }
+                return cfg.substring(eq + 1) === platform;
+            }
+            var i;
+            switch (cfg[0]) {
+            case "not":
+                return !isAvailableOn(cfg[1], platform);
+            case "any":
+                for (i = 1; i < cfg.length; ++i) {
+                    if (isAvailableOn(cfg[i], platform)) {
+                        return true;
+                    }
+                }
+                return false;
+            default:
+                for (i = 1; i < cfg.length; ++i) {
+                    if (!isAvailableOn(cfg[i], platform)) {
+                        return false;
+                    }
+                }
+                return true;
+            }
+        }
+
+        /**
+         * Returns whether `item` passes the facets of `query` other than the kind of item.
+         */
+        function passesFacets(query, item) {
+            return (query.stable !== true || item.unstable !== true) &&
+                (query.platform === undefined || item.cfg === undefined ||
+                 isAvailableOn(item.cfg, query.platform));
+        }
+
         function getQuery(raw) {
-            var matches, type, query;
+            var matches, type, query, stable = false, platform;
             query = raw;
 
-            matches = query.match(/^(fn|mod|struct|enum|trait|type|const|macro)\s*:\s*/i);
-            if (matches) {
-                type = matches[1].replace(/^const$/, "constant");
-                query = query.substring(matches[0].length);
+            // The facets come first, in any order.
+            for (;;) {
+                matches = query.match(/^(fn|mod|struct|enum|trait|type|const|macro)\s*:\s*/i);
+                if (matches) {
+                    type = matches[1].replace(/^const$/, "constant");
+                    query = query.substring(matches[0].length);
+                    continue;
+                }
+                matches = query.match(/^stable\s*:\s*/i);
+                if (matches) {
+                    stable = true;
+                    query = query.substring(matches[0].length);
+                    continue;
+                }
+                matches = query.match(/^on\s*:\s*([\w-]+)\s*/i);
+                if (matches) {
+                    platform = matches[1];
+                    query = query.substring(matches[0].length);
+                    continue;
+                }
+                break;
             }
 
             return {
                 raw: raw,
                 query: query,
                 type: type,
-                id: query + type
+                stable: stable,
+                platform: platform,
+                id: query + type + stable + platform
             };
         }
 
@@ -1367,7 +1561,7 @@ function getSearchElement() {
                 href = rootPath + name + "/index.html";
             } else if (item.parent !== undefined) {
                 var myparent = item.parent;
-                var anchor = "#" + type + "." + name;
+                var anchor = "#" + (item.anchor || type + "." + name);
                 var parentType = itemTypes[myparent.ty];
                 if (parentType === "primitive") {
                     displayPath = myparent.name + "::";
@@ -1413,7 +1607,7 @@ function getSearchElement() {
                 output = "<table class=\"search-results\"" + extraStyle + ">";
 
                 array.forEach(function(item) {
-                    var name, type;
+                    var name, type, descShard = "";
 
                     name = item.name;
                     type = itemTypes[item.ty];
@@ -1426,15 +1620,22 @@ function getSearchElement() {
                     }
                     length += 1;
 
+                    if (item.descShard && item.hasDesc) {
+                        descShard = " data-desc-shard=\"" + item.crate + "/" + item.descShard[0] +
+                                    "\" data-desc-index=\"" + item.descShard[1] + "\"";
+                    }
                     output += "<tr class=\"" + type + " result\"><td>" +
                               "<a href=\"" + item.href + "\">" +
                               (item.is_alias === true ?
                                ("<span class=\"alias\"><b>" + item.alias + " </b></span><span " +
                                   "class=\"grey\"><i>&nbsp;- see&nbsp;</i></span>") : "") +
                               item.displayPath + "<span class=\"" + type + "\">" +
-                              name + "</span></a></td><td>" +
+                              name + "</span>" +
+                              (item.visibility ? "<span class=\"grey\"><i>&nbsp;(" +
+                                  item.visibility + ")</i></span>" : "") +
+                              "</a></td><td>" +
                               "<a href=\"" + item.href + "\">" +
-                              "<span class=\"desc\">" + escape(item.desc) +
+                              "<span class=\"desc\"" + descShard + ">" + escape(item.desc) +
                               "&nbsp;</span></a></td></tr>";
                 });
                 output += "</table>";
@@ -1464,6 +1665,33 @@ function getSearchElement() {
             return "<div>" + text + " <div class=\"count\">(" + nbElems + ")</div></div>";
         }
 
+        // The shards of descriptions split out of the search index with
+        // `--shard-search-descriptions`, by crate and number. They are `null` while loading.
+        var descShards = {};
+
+        // Fills in the descriptions of the results shown in `elem` which are in shards, loading
+        // the shards which are not yet.
+        function loadDescriptions(elem) {
+            onEachLazy(elem.querySelectorAll("[data-desc-shard]"), function(e) {
+                var shard = e.getAttribute("data-desc-shard");
+                if (descShards[shard] === undefined) {
+                    descShards[shard] = null;
+                    var script = document.createElement("script");
+                    script.src = rootPath + "search-desc/" + shard + resourcesSuffix + ".js";
+                    document.head.appendChild(script);
+                } else if (descShards[shard] !== null) {
+                    var desc = descShards[shard][e.getAttribute("data-desc-index")];
+                    e.textContent = desc + "\u00a0";
+                    e.removeAttribute("data-desc-shard");
+                }
+            });
+        }
+
+        window.loadDescShard = function(crate, shard, descs) {
+            descShards[crate + "/" + shard] = descs;
+            loadDescriptions(document);
+        };
+
         function showResults(results) {
             if (results.others.length === 1 &&
                 getCurrentValue("rustdoc-go-to-only-result") === "true") {
@@ -1483,7 +1711,9 @@ function getSearchElement() {
             var ret_returned = addTab(results.returned, query, false);
 
             var output = "<h1>Results for " + escape(query.query) +
-                (query.type ? " (type: " + escape(query.type) + ")" : "") + "</h1>" +
+                (query.type ? " (type: " + escape(query.type) + ")" : "") +
+                (query.stable ? " (stable)" : "") +
+                (query.platform ? " (on: " + escape(query.platform) + ")" : "") + "</h1>" +
                 "<div id=\"titles\">" +
                 makeTabHeader(0, "In Names", ret_others[1]) +
                 makeTabHeader(1, "In Parameters", ret_in_args[1]) +
@@ -1495,6 +1725,7 @@ function getSearchElement() {
             var search = getSearchElement();
             removeClass(search, "hidden");
             search.innerHTML = output;
+            loadDescriptions(search);
             var tds = search.getElementsByTagName("td");
             var td_width = 0;
             if (tds.length > 0) {
@@ -1631,12 +1862,16 @@ function getSearchElement() {
 
         function buildIndex(rawSearchIndex) {
             searchIndex = [];
+            nameTables = [];
             var searchWords = [];
             var i;
 
             for (var crate in rawSearchIndex) {
                 if (!rawSearchIndex.hasOwnProperty(crate)) { continue; }
 
+                // The weight of every kind of item in the ranking of the results, by item type.
+                var kindWeights = rawSearchIndex[crate].k;
+
                 searchWords.push(crate);
                 searchIndex.push({
                     crate: crate,
@@ -1645,14 +1880,29 @@ function getSearchElement() {
                     path: "",
                     desc: rawSearchIndex[crate].doc,
                     type: null,
+                    kindWeight: kindWeights[1],
                 });
 
+                // The number of items whose descriptions are in each shard, if they are split
+                // out of the index with `--shard-search-descriptions`. The descriptions of the
+                // items are then only loaded when they are shown, and the index only tells which
+                // items have one.
+                var descShardLen = rawSearchIndex[crate].d;
+
+                // The names of the items grouped by length, and the trigrams they have, to only
+                // look at the names close to the query when matching it with typos.
+                var nameTable = rawSearchIndex[crate].f;
+                nameTable.start = searchIndex.length;
+                nameTables.push(nameTable);
+
                 // an array of [(Number) item type,
                 //              (String) name,
                 //              (String) full path or empty string for previous path,
-                //              (String) description,
+                //              (String) description, or "1" if it is in a shard,
                 //              (Number | null) the parent path index to `paths`]
-                //              (Object | null) the type of the function (if any)
+                //              (Object | null) the type of the function (if any),
+                //              (String | undefined) the id of the item on the page of its
+                //                                   parent, if not derived from its name
                 var items = rawSearchIndex[crate].i;
                 // an array of [(Number) item type,
                 //              (String) name]
@@ -1676,8 +1926,13 @@ function getSearchElement() {
                 for (i = 0; i < len; ++i) {
                     var rawRow = items[i];
                     var row = {crate: crate, ty: rawRow[0], name: rawRow[1],
-                               path: rawRow[2] || lastPath, desc: rawRow[3],
-                               parent: paths[rawRow[4]], type: rawRow[5]};
+                               path: rawRow[2] || lastPath,
+                               desc: descShardLen ? "" : rawRow[3], hasDesc: rawRow[3] !== "",
+                               descShard: descShardLen ?
+                                   [Math.floor(i / descShardLen), i % descShardLen] : null,
+                               parent: paths[rawRow[4]], type: rawRow[5], anchor: rawRow[6],
+                               kindWeight: kindWeights[rawRow[0]], deprecated: false,
+                               unstable: false, cfg: true};
                     searchIndex.push(row);
                     if (typeof row.name === "string") {
                         var word = row.name.toLowerCase();
@@ -1687,6 +1942,34 @@ function getSearchElement() {
                     }
                     lastPath = row.path;
                 }
+
+                // the positions of the deprecated items among `items`
+                var deprecated = rawSearchIndex[crate].x || [];
+                for (i = 0; i < deprecated.length; ++i) {
+                    searchIndex[nameTable.start + deprecated[i]].deprecated = true;
+                }
+                // the positions of the private and hidden items among `items`, by visibility
+                var visibilities = rawSearchIndex[crate].v || {};
+                for (var visibility in visibilities) {
+                    if (!visibilities.hasOwnProperty(visibility)) { continue; }
+                    var positions = visibilities[visibility];
+                    for (i = 0; i < positions.length; ++i) {
+                        searchIndex[nameTable.start + positions[i]].visibility = visibility;
+                    }
+                }
+                // the positions of the unstable items among `items`
+                var unstable = rawSearchIndex[crate].u || [];
+                for (i = 0; i < unstable.length; ++i) {
+                    searchIndex[nameTable.start + unstable[i]].unstable = true;
+                }
+                // the configurations the items are available on, each with the positions of
+                // its items among `items`
+                var cfgs = rawSearchIndex[crate].c || [];
+                for (i = 0; i < cfgs.length; ++i) {
+                    for (var j = 0; j < cfgs[i][1].length; ++j) {
+                        searchIndex[nameTable.start + cfgs[i][1][j]].cfg = cfgs[i][0];
+                    }
+                }
             }
             return searchWords;
         }
@@ -1892,8 +2175,11 @@ function getSearchElement() {
     window.initSidebarItems = initSidebarItems;
 
     window.register_implementors = function(imp) {
-        var implementors = document.getElementById("implementors-list");
-        var synthetic_implementors = document.getElementById("synthetic-implementors-list");
+        var lists = {
+            "concrete": "implementors",
+            "generic": "generic-implementors",
+            "blanket": "synthetic-implementors",
+        };
 
         var libs = Object.getOwnPropertyNames(imp);
         var llength = libs.length;
@@ -1906,7 +2192,10 @@ function getSearchElement() {
             for (var j = 0; j < slength; ++j) {
                 var struct = structs[j];
 
-                var list = struct.synthetic ? synthetic_implementors : implementors;
+                var kind = struct.kind || (struct.synthetic ? "blanket" : "concrete");
+                var header = document.getElementById(lists[kind]);
+                var list = document.getElementById(lists[kind] + "-list");
+                if (!header || !list) { continue; }
 
                 if (struct.synthetic) {
                     var stlength = struct.types.length;
@@ -1935,6 +2224,14 @@ function getSearchElement() {
                     "<tbody><tr><td><code>" + code.outerHTML + "</code></td><td></td></tr>" +
                     "</tbody></table></span>";
                 list.appendChild(display);
+
+                // Show the group if it had no implementors in this crate and update its count.
+                removeClass(header, "hidden");
+                removeClass(list, "hidden");
+                var count = header.getElementsByClassName("count")[0];
+                if (count) {
+                    count.textContent = "(" + list.getElementsByClassName("impl").length + ")";
+                }
             }
         }
     };
@@ -2664,6 +2961,9 @@ function getSearchElement() {
             "Accepted types are: <code>fn</code>, <code>mod</code>, <code>struct</code>, \
              <code>enum</code>, <code>trait</code>, <code>type</code>, <code>macro</code>, \
              and <code>const</code>.",
+            "Prefix searches with <code>stable:</code> to leave out the unstable items, and \
+             with <code>on:</code> followed by a platform (e.g., <code>on:wasm32</code>) to \
+             leave out the items not available on it.",
             "Search functions by type signature (e.g., <code>vec -> usize</code> or \
              <code>* -> vec</code>)",
             "Search multiple things at once by splitting your query with comma (e.g., \
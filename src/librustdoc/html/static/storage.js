@@ -1,5 +1,5 @@
 // From rust:
-/* global resourcesSuffix */
+/* global resourcesSuffix, staticFileNames */
 
 var currentTheme = document.getElementById("themeStyle");
 var mainTheme = document.getElementById("mainThemeStyle");
@@ -51,6 +51,24 @@ function onEachLazy(lazyArray, func, reversed) {
         reversed);
 }
 
+// Inserts an impl block shared by several pages, written with `--deduplicate-impls`, in place of
+// the script loading it. The links of the block are relative to the documentation root.
+function insertImplFragment(html) {
+    var script = document.currentScript;
+    var rootPath = script.getAttribute("data-root-path");
+    var block = document.createElement("div");
+    block.innerHTML = html;
+    onEachLazy(block.getElementsByTagName("a"), function(link) {
+        var href = link.getAttribute("href");
+        if (href && href.indexOf(":") === -1 && href.charAt(0) !== "#") {
+            link.setAttribute("href", rootPath + href);
+        }
+    });
+    while (block.firstChild) {
+        script.parentNode.insertBefore(block.firstChild, script);
+    }
+}
+
 function usableLocalStorage() {
     // Check if the browser supports localStorage at all:
     if (typeof Storage === "undefined") {
@@ -82,9 +100,15 @@ function getCurrentValue(name) {
     return null;
 }
 
+// Returns the name `file` is written under, which contains the hash of its content with
+// `--hash-static-files`.
+function staticFileName(file) {
+    return staticFileNames[file] || file;
+}
+
 function switchTheme(styleElem, mainStyleElem, newTheme, saveTheme) {
-    var fullBasicCss = "rustdoc" + resourcesSuffix + ".css";
-    var fullNewTheme = newTheme + resourcesSuffix + ".css";
+    var fullBasicCss = staticFileName("rustdoc" + resourcesSuffix + ".css");
+    var fullNewTheme = staticFileName(newTheme + resourcesSuffix + ".css");
     var newHref = mainStyleElem.href.replace(fullBasicCss, fullNewTheme);
 
     if (styleElem.href === newHref) {
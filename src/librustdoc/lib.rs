@@ -19,6 +19,7 @@
 
 #![recursion_limit="256"]
 
+extern crate arena;
 extern crate getopts;
 extern crate env_logger;
 extern crate rustc;
@@ -44,21 +45,26 @@ extern crate rustc_errors as errors;
 
 use std::default::Default;
 use std::env;
+use std::mem;
 use std::panic;
 use std::process;
 
-use rustc::session::{early_warn, early_error};
+use rustc::session::early_warn;
 use rustc::session::config::{ErrorOutputType, RustcOptGroup, make_crate_type_option};
+use rustc::util::common::ErrorReported;
+use syntax_pos::symbol::Symbol;
 
 #[macro_use]
 mod externalfiles;
 
 mod clean;
 mod config;
+mod config_file;
 mod core;
 mod docfs;
 mod doctree;
 mod fold;
+mod lint;
 pub mod html {
     crate mod highlight;
     crate mod escape;
@@ -73,10 +79,20 @@ pub mod html {
 }
 mod markdown;
 mod passes;
+pub mod serialized;
+mod span_map;
 mod visit_ast;
 mod visit_lib;
 mod test;
 mod theme;
+mod watch;
+mod workspace;
+
+// The types the entry point of the library needs. The types of the cleaned crate are only reached
+// through its fields; `serialized` mirrors them for the tools which have to name them.
+pub use crate::clean::{Crate, TypeKind};
+pub use crate::config::Options;
+pub use crate::core::ExternalPaths;
 
 struct Output {
     krate: clean::Crate,
@@ -129,7 +145,7 @@ fn opts() -> Vec<RustcOptGroup> {
                      "[rust]")
         }),
         stable("w", |o| {
-            o.optopt("w", "output-format", "the output type to write", "[html]")
+            o.optopt("w", "output-format", "the output type to write", "[html|json]")
         }),
         stable("o", |o| o.optopt("o", "output", "where to place the output", "PATH")),
         stable("crate-name", |o| {
@@ -169,7 +185,11 @@ fn opts() -> Vec<RustcOptGroup> {
             o.optflag("", "no-defaults", "don't run the default passes")
         }),
         stable("document-private-items", |o| {
-            o.optflag("", "document-private-items", "document private items")
+            o.optflagopt("",
+                         "document-private-items",
+                         "document private items, or only the ones of the comma-separated private \
+                          modules given, like `crate::internal,crate::ffi` (unstable)",
+                         "PATHS")
         }),
         stable("test", |o| o.optflag("", "test", "run code examples as tests")),
         stable("test-args", |o| {
@@ -357,6 +377,48 @@ fn opts() -> Vec<RustcOptGroup> {
                       "show-coverage",
                       "calculate percentage of public items with documentation")
         }),
+        unstable("emit", |o| {
+            o.optopt("",
+                     "emit",
+                     "comma-separated artifacts to produce: html, search-index, coverage, id-map \
+                      (default: html,search-index)",
+                     "TYPES")
+        }),
+        unstable("check", |o| {
+            o.optflag("",
+                      "check",
+                      "run the passes and lints of the documentation and report its coverage, \
+                       without generating it")
+        }),
+        unstable("code-example-kinds", |o| {
+            o.optopt("",
+                     "code-example-kinds",
+                     "comma-separated kinds of public items the `missing_doc_code_examples` \
+                      lint checks (default: fn,method,tymethod,trait)",
+                     "KINDS")
+        }),
+        unstable("inline-hidden-reexports", |o| {
+            o.optflag("",
+                      "inline-hidden-reexports",
+                      "inline re-exports from other crates even if their targets are hidden")
+        }),
+        unstable("show-type-layout", |o| {
+            o.optflag("",
+                      "show-type-layout",
+                      "show the size, alignment and field offsets of structs, enums and unions")
+        }),
+        unstable("generate-link-to-definition", |o| {
+            o.optflag("",
+                      "generate-link-to-definition",
+                      "make the paths in the source pages link to the items they refer to")
+        }),
+        unstable("merge-target", |o| {
+            o.optmulti("",
+                       "merge-target",
+                       "also document the crate for TRIPLE, and merge in the items which are \
+                        only available there",
+                       "TRIPLE")
+        }),
         unstable("enable-per-target-ignores", |o| {
             o.optflag("",
                       "enable-per-target-ignores",
@@ -374,11 +436,118 @@ fn opts() -> Vec<RustcOptGroup> {
                        "",
                        "One (of possibly many) arguments to pass to the runtool")
         }),
+        unstable("doctest-timings", |o| {
+            o.optflag("",
+                      "doctest-timings",
+                      "report the time spent compiling and running each doctest")
+        }),
+        unstable("runner", |o| {
+            o.optopt("",
+                     "runner",
+                     "The command to run doctest binaries through, like an emulator when \
+                      building for a different target than host",
+                     "COMMAND")
+        }),
+        unstable("merge-doctests", |o| {
+            o.optflag("",
+                      "merge-doctests",
+                      "compile the doctests which allow it into a single binary")
+        }),
         unstable("test-builder", |o| {
             o.optflag("",
                       "test-builder",
                       "specified the rustc-like binary to use as the test builder")
         }),
+        unstable("item-metrics", |o| {
+            o.optflag("",
+                      "item-metrics",
+                      "show documentation metrics on item pages and write them to \
+                       item-metrics.json")
+        }),
+        unstable("generate-print-page", |o| {
+            o.optflag("",
+                      "generate-print-page",
+                      "write the documentation of every item of the crate to print.html, to be \
+                       printed")
+        }),
+        unstable("minify-output", |o| {
+            o.optflag("",
+                      "minify-output",
+                      "strip insignificant whitespace and comments from the generated HTML")
+        }),
+        unstable("deduplicate-impls", |o| {
+            o.optflag("",
+                      "deduplicate-impls",
+                      "write the impl blocks shown identically on several pages once, to files \
+                       loaded by the pages")
+        }),
+        unstable("hash-static-files", |o| {
+            o.optflag("",
+                      "hash-static-files",
+                      "write the static files under names containing the hash of their content")
+        }),
+        unstable("incremental-docs", |o| {
+            o.optflag("",
+                      "incremental-docs",
+                      "skip writing the pages which did not change since the previous run")
+        }),
+        unstable("watch", |o| {
+            o.optflagopt("",
+                         "watch",
+                         "document the whole crate again whenever its source files change, and \
+                          serve the documentation on a local port, 8000 by default",
+                         "PORT")
+        }),
+        unstable("module-page-size", |o| {
+            o.optopt("",
+                     "module-page-size",
+                     "split the listing of the modules with more items than this into several \
+                      pages",
+                     "ITEMS")
+        }),
+        unstable("precompress", |o| {
+            o.optflag("",
+                      "precompress",
+                      "also write gzip-compressed copies of the search index and of the static \
+                       files")
+        }),
+        unstable("versioned-output", |o| {
+            o.optflag("",
+                      "versioned-output",
+                      "write the documentation to the directory of the `--crate-version` in the \
+                       output directory, listing the versions in `versions.json`")
+        }),
+        unstable("shard-search-descriptions", |o| {
+            o.optflag("",
+                      "shard-search-descriptions",
+                      "write the descriptions of the items to files loaded when showing search \
+                       results, to keep the search index small")
+        }),
+        unstable("search-hidden", |o| {
+            o.optflag("",
+                      "search-hidden",
+                      "document the items marked `#[doc(hidden)]`, tagging them as hidden in \
+                       the search")
+        }),
+        unstable("config", |o| {
+            o.optopt("",
+                     "config",
+                     "read the defaults of the options from this file instead of the \
+                      `rustdoc.toml` file next to the crate root",
+                     "PATH")
+        }),
+        unstable("workspace", |o| {
+            o.optflag("",
+                      "workspace",
+                      "document every crate root given, or every member of the workspace of the \
+                       `Cargo.toml` given, into the same output directory")
+        }),
+        unstable("merge-docs", |o| {
+            o.optflag("",
+                      "merge-docs",
+                      "combine the documentation of the output directories given into the \
+                       output directory, instead of documenting a crate")
+        }),
     ]
 }
 
@@ -391,17 +560,7 @@ fn usage(argv0: &str) {
 }
 
 fn main_args(args: &[String]) -> i32 {
-    let mut options = getopts::Options::new();
-    for option in opts() {
-        (option.apply)(&mut options);
-    }
-    let matches = match options.parse(&args[1..]) {
-        Ok(m) => m,
-        Err(err) => {
-            early_error(ErrorOutputType::default(), &err.to_string());
-        }
-    };
-    let options = match config::Options::from_matches(&matches) {
+    let options = match config::Options::from_args(&args[1..]) {
         Ok(opts) => opts,
         Err(code) => return code,
     };
@@ -416,14 +575,55 @@ fn main_options(options: config::Options) -> i32 {
                                  options.debugging_options.treat_err_as_bug,
                                  options.debugging_options.ui_testing);
 
+    if !options.merge_docs.is_empty() {
+        return match html::render::merge_docs::run(&options.merge_docs, &options.render_options) {
+            Ok(()) => rustc_driver::EXIT_SUCCESS,
+            Err(e) => {
+                diag.struct_err(&format!("couldn't merge documentation: {}", e.error))
+                    .note(&format!("failed to create or modify \"{}\"", e.file.display()))
+                    .emit();
+                rustc_driver::EXIT_FAILURE
+            }
+        };
+    }
+
     match (options.should_test, options.markdown_input()) {
         (true, true) => return markdown::test(options, &diag),
         (true, false) => return test::run(options),
-        (false, true) => return markdown::render(options.input,
-                                                 options.render_options,
-                                                 &diag,
-                                                 options.edition),
-        (false, false) => {}
+        (false, _) => {}
+    }
+
+    match options.watch {
+        Some(port) => watch::run(options, port, &diag, document),
+        None => document(options),
+    }
+}
+
+/// Documents the crate, or renders the Markdown file, given as input.
+fn document(mut options: config::Options) -> i32 {
+    if !options.workspace.is_empty() {
+        // Each crate is documented even if one before it failed, the crates which depend on it
+        // then linking to it as to any crate without documentation.
+        let workspace = mem::take(&mut options.workspace);
+        return workspace.into_iter().fold(rustc_driver::EXIT_SUCCESS, |code, member| {
+            let options = config::Options {
+                input: member.root,
+                crate_name: member.name,
+                ..options.clone()
+            };
+            match document(options) {
+                rustc_driver::EXIT_SUCCESS => code,
+                failure => failure,
+            }
+        });
+    }
+
+    if options.markdown_input() {
+        let diag = core::new_handler(options.error_format,
+                                     None,
+                                     options.debugging_options.treat_err_as_bug,
+                                     options.debugging_options.ui_testing);
+        return markdown::render(options.input, options.render_options, &diag, options.edition);
     }
 
     // need to move these items separately because we lose them by the time the closure is called,
@@ -433,12 +633,20 @@ fn main_options(options: config::Options) -> i32 {
                      options.debugging_options.ui_testing,
                      options.edition);
     let show_coverage = options.show_coverage;
+    // The coverage is printed by its pass, the other artifacts need the crate to be rendered.
+    let render = !options.check &&
+        options.render_options.emit.iter().any(|&ty| ty != config::EmitType::Coverage);
     rust_input(options, move |out| {
         if show_coverage {
             // if we ran coverage, bail early, we don't need to also generate docs at this point
             // (also we didn't load in any of the useful passes)
             return rustc_driver::EXIT_SUCCESS;
         }
+        if !render {
+            // The passes reported their errors before the crate got here, and would have failed
+            // the run then.
+            return rustc_driver::EXIT_SUCCESS;
+        }
 
         let Output { krate, renderinfo, renderopts } = out;
         info!("going to format");
@@ -462,6 +670,40 @@ fn main_options(options: config::Options) -> i32 {
     })
 }
 
+/// The documentation of a crate, once cleaned and through the passes, before it gets rendered.
+pub struct CleanedCrate {
+    pub krate: clean::Crate,
+    /// The paths of the items of the other crates the documentation refers to, by `DefId`.
+    pub external_paths: core::ExternalPaths,
+}
+
+/// Documents the crate of `options.input` up to the cleaned crate, which is given to `f`, for the
+/// tools which work on the documentation of a crate rather than on its pages.
+///
+/// The cleaned crate cannot be returned by value: its names are `Symbol`s and its items are
+/// identified by `DefId`s, which only mean something within the compiler session. The session
+/// runs on a thread of its own, whose interner and crate numbering are gone once `build` returns.
+/// So `f` runs within the session and only its result is returned, which may be the crate
+/// converted by `serialized::Converter::convert_crate`, as it owns its strings.
+///
+/// The errors are reported as the `rustdoc` command reports them, and return `Err` once reported.
+/// The options asking for tests, coverage or other crates to document are not looked at, the
+/// crate is neither tested nor rendered.
+pub fn build<R, F>(options: config::Options, f: F) -> Result<R, ErrorReported>
+where R: 'static + Send,
+      F: 'static + Send + FnOnce(CleanedCrate) -> R
+{
+    let edition = options.edition;
+    rustc_driver::catch_fatal_errors(move || {
+        rustc_interface::interface::default_thread_pool(edition, move || {
+            rust_input(options, move |out| {
+                let Output { krate, renderinfo, .. } = out;
+                f(CleanedCrate { krate, external_paths: renderinfo.external_paths })
+            })
+        })
+    })
+}
+
 /// Interprets the input file as a rust source file, passing it through the
 /// compiler all the way through the analysis passes. The rustdoc output is then
 /// generated from the cleaned AST of the crate.
@@ -477,12 +719,27 @@ where R: 'static + Send,
     let result = rustc_driver::catch_fatal_errors(move || {
         let crate_name = options.crate_name.clone();
         let crate_version = options.crate_version.clone();
-        let (mut krate, renderinfo, renderopts) = core::run_core(options);
+        let merge_targets = options.merge_targets.clone();
+        let other_options = merge_targets.iter().map(|target| {
+            config::Options { target: target.clone(), ..options.clone() }
+        }).collect::<Vec<_>>();
+        let mut targets = vec![options.target.clone()];
+        targets.extend(merge_targets);
+
+        let (mut krate, mut renderinfo, renderopts) = core::run_core(options);
+        if !other_options.is_empty() {
+            let others = other_options.into_iter().map(|options| {
+                let (krate, renderinfo, _) = core::run_core(options);
+                (krate, renderinfo)
+            }).collect();
+            let cfgs = clean::merge::target_cfgs(&targets);
+            krate = clean::merge::merge_targets(krate, &mut renderinfo, others, &cfgs);
+        }
 
         info!("finished with rustc");
 
         if let Some(name) = crate_name {
-            krate.name = name
+            krate.name = Symbol::intern(&name)
         }
 
         krate.version = crate_version;
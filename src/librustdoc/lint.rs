@@ -0,0 +1,60 @@
+//! The lints emitted by rustdoc.
+//!
+//! They are declared along with the compiler's builtin lints, so that attributes naming them
+//! are understood when the crate is compiled as well. When documenting, they can also be
+//! referred to with the `rustdoc::` tool prefix, both in attributes like
+//! `#![deny(rustdoc::bare_urls)]` and in `-W`/`-D` flags.
+//!
+//! They are grouped as well: `rustdoc::all` names all of them, `rustdoc::links` the ones about the
+//! links of the documentation and `rustdoc::examples` the ones about its code examples. The lints
+//! are only emitted for the documentation written in the crate, never for the one inlined from
+//! its dependencies, and `--cap-lints` caps their level like the one of the compiler's lints.
+
+use rustc::lint::{self, Lint, LintId, LintStore};
+use rustc::session::Session;
+
+/// The lints emitted by rustdoc, along with their names under the `rustdoc::` prefix.
+pub fn rustdoc_lints() -> Vec<(&'static str, &'static Lint)> {
+    vec![
+        ("broken_intra_doc_links", lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE),
+        ("missing_doc_code_examples", lint::builtin::MISSING_DOC_CODE_EXAMPLES),
+        ("private_doc_tests", lint::builtin::PRIVATE_DOC_TESTS),
+        ("hidden_types_in_signatures", lint::builtin::HIDDEN_TYPES_IN_SIGNATURES),
+        ("bare_urls", lint::builtin::BARE_URLS),
+        ("unlinked_paths", lint::builtin::UNLINKED_PATHS),
+        ("invalid_rust_codeblocks", lint::builtin::INVALID_RUST_CODEBLOCKS),
+        ("inconsistent_doc_cfg", lint::builtin::INCONSISTENT_DOC_CFG),
+        ("redundant_explicit_links", lint::builtin::REDUNDANT_EXPLICIT_LINKS),
+        ("broken_link_fragments", lint::builtin::BROKEN_LINK_FRAGMENTS),
+    ]
+}
+
+/// The groups of rustdoc's lints, along with their names under the `rustdoc::` prefix.
+pub fn rustdoc_lint_groups() -> Vec<(&'static str, Vec<&'static Lint>)> {
+    vec![
+        ("rustdoc::all", rustdoc_lints().into_iter().map(|(_, lint)| lint).collect()),
+        ("rustdoc::links", vec![
+            lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE,
+            lint::builtin::BARE_URLS,
+            lint::builtin::UNLINKED_PATHS,
+            lint::builtin::REDUNDANT_EXPLICIT_LINKS,
+            lint::builtin::BROKEN_LINK_FRAGMENTS,
+        ]),
+        ("rustdoc::examples", vec![
+            lint::builtin::MISSING_DOC_CODE_EXAMPLES,
+            lint::builtin::PRIVATE_DOC_TESTS,
+            lint::builtin::INVALID_RUST_CODEBLOCKS,
+        ]),
+    ]
+}
+
+/// Registers the `rustdoc::` names of rustdoc's lints and of their groups in the lint store of
+/// the session, so that the levels given to them are taken into account.
+pub fn register_lints(_sess: &Session, store: &mut LintStore) {
+    for (name, lint) in rustdoc_lints() {
+        store.register_tool_alias("rustdoc", name, lint);
+    }
+    for (name, lints) in rustdoc_lint_groups() {
+        store.register_group(false, name, None, lints.into_iter().map(LintId::of).collect());
+    }
+}
@@ -84,7 +84,7 @@ pub fn render(
     let text = if !options.markdown_no_toc {
         MarkdownWithToc(text, &mut ids, error_codes, edition, &playground).to_string()
     } else {
-        Markdown(text, &[], &mut ids, error_codes, edition, &playground).to_string()
+        Markdown(text, &[], &mut ids, error_codes, edition, &playground, None).to_string()
     };
 
     let err = write!(
@@ -149,9 +149,15 @@ pub fn test(mut options: Options, diag: &errors::Handler) -> i32 {
     let codes = ErrorCodes::from(UnstableFeatures::from_environment().is_nightly_build());
 
     find_testable_code(&input_str, &mut collector, codes, options.enable_per_target_ignores);
+    collector.write_persisted_manifest();
 
     options.test_args.insert(0, "rustdoctest".to_string());
-    testing::test_main(&options.test_args, collector.tests,
-                       Some(testing::Options::new().display_output(options.display_warnings)));
+    let timings_report = collector.timings_report();
+    testing::test_main_with_exit_callback(
+        &options.test_args,
+        collector.tests,
+        Some(testing::Options::new().display_output(options.display_warnings)),
+        timings_report,
+    );
     0
 }
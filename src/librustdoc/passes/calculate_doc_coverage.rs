@@ -1,10 +1,12 @@
 use crate::clean;
+use crate::config::OutputFormat;
 use crate::core::DocContext;
 use crate::fold::{self, DocFolder};
+use crate::html::markdown::{find_testable_code, ErrorCodes, LangString};
 use crate::passes::Pass;
 
+use serde::Serialize;
 use syntax::attr;
-use syntax_pos::FileName;
 use syntax::symbol::sym;
 
 use std::collections::BTreeMap;
@@ -16,28 +18,36 @@ pub const CALCULATE_DOC_COVERAGE: Pass = Pass {
     description: "counts the number of items with and without documentation",
 };
 
-fn calculate_doc_coverage(krate: clean::Crate, _: &DocContext<'_>) -> clean::Crate {
+fn calculate_doc_coverage(krate: clean::Crate, cx: &mut DocContext<'_>) -> clean::Crate {
     let mut calc = CoverageCalculator::default();
+    calc.modules.push(krate.name.to_string());
     let krate = calc.fold_crate(krate);
 
-    calc.print_results();
+    match cx.output_format {
+        OutputFormat::Json => calc.print_json(),
+        OutputFormat::Html => calc.print_results(),
+    }
 
     krate
 }
 
-#[derive(Default, Copy, Clone)]
+#[derive(Default, Copy, Clone, Serialize)]
 struct ItemCount {
     total: u64,
     with_docs: u64,
+    with_examples: u64,
 }
 
 impl ItemCount {
-    fn count_item(&mut self, has_docs: bool) {
+    fn count_item(&mut self, has_docs: bool, has_examples: bool) {
         self.total += 1;
 
         if has_docs {
             self.with_docs += 1;
         }
+        if has_examples {
+            self.with_examples += 1;
+        }
     }
 
     fn percentage(&self) -> Option<f64> {
@@ -49,73 +59,124 @@ impl ItemCount {
     }
 }
 
-impl ops::Sub for ItemCount {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self {
-        ItemCount {
-            total: self.total - rhs.total,
-            with_docs: self.with_docs - rhs.with_docs,
-        }
-    }
-}
-
 impl ops::AddAssign for ItemCount {
     fn add_assign(&mut self, rhs: Self) {
         self.total += rhs.total;
         self.with_docs += rhs.with_docs;
+        self.with_examples += rhs.with_examples;
     }
 }
 
+/// The counts of the whole crate, per module and per kind of item.
+#[derive(Serialize)]
+struct CoverageReport<'a> {
+    modules: &'a BTreeMap<String, ItemCount>,
+    kinds: &'a BTreeMap<&'static str, ItemCount>,
+    total: ItemCount,
+}
+
 #[derive(Default)]
 struct CoverageCalculator {
-    items: BTreeMap<FileName, ItemCount>,
+    /// The counts of each module, keyed by its path.
+    items: BTreeMap<String, ItemCount>,
+    /// The counts of each kind of item.
+    kinds: BTreeMap<&'static str, ItemCount>,
+    /// The path of the module being folded.
+    modules: Vec<String>,
 }
 
 impl CoverageCalculator {
-    fn print_results(&self) {
+    fn total(&self) -> ItemCount {
         let mut total = ItemCount::default();
+        for &count in self.items.values() {
+            total += count;
+        }
+        total
+    }
 
+    fn print_json(&self) {
+        let report = CoverageReport {
+            modules: &self.items,
+            kinds: &self.kinds,
+            total: self.total(),
+        };
+        println!("{}", serde_json::to_string(&report).expect("failed to serialize coverage"));
+    }
+
+    fn print_results(&self) {
         fn print_table_line() {
-            println!("+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+", "");
+            println!("+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+", "");
         }
 
         fn print_table_record(name: &str, count: ItemCount, percentage: f64) {
-            println!("| {:<35} | {:>10} | {:>10} | {:>9.1}% |",
-                     name, count.with_docs, count.total, percentage);
+            println!("| {:<35} | {:>10} | {:>10} | {:>9.1}% | {:>10} |",
+                     name, count.with_docs, count.total, percentage, count.with_examples);
         }
 
-        print_table_line();
-        println!("| {:<35} | {:>10} | {:>10} | {:>10} |",
-                 "File", "Documented", "Total", "Percentage");
-        print_table_line();
-
-        for (file, &count) in &self.items {
-            if let Some(percentage) = count.percentage() {
-                let mut name = file.to_string();
-                // if a filename is too long, shorten it so we don't blow out the table
-                // FIXME(misdreavus): this needs to count graphemes, and probably also track
-                // double-wide characters...
-                if name.len() > 35 {
-                    name = "...".to_string() + &name[name.len()-32..];
+        fn print_table<'a, K: AsRef<str> + 'a>(
+            title: &str,
+            counts: impl Iterator<Item = (&'a K, &'a ItemCount)>,
+            total: ItemCount,
+        ) {
+            print_table_line();
+            println!("| {:<35} | {:>10} | {:>10} | {:>10} | {:>10} |",
+                     title, "Documented", "Total", "Percentage", "Examples");
+            print_table_line();
+
+            for (name, &count) in counts {
+                if let Some(percentage) = count.percentage() {
+                    let mut name = name.as_ref().to_string();
+                    // if a name is too long, shorten it so we don't blow out the table
+                    // FIXME(misdreavus): this needs to count graphemes, and probably also track
+                    // double-wide characters...
+                    if name.len() > 35 {
+                        name = "...".to_string() + &name[name.len()-32..];
+                    }
+
+                    print_table_record(&name, count, percentage);
                 }
-
-                print_table_record(&name, count, percentage);
-
-                total += count;
             }
+
+            print_table_line();
+            print_table_record("Total", total, total.percentage().unwrap_or(0.0));
+            print_table_line();
         }
 
-        print_table_line();
-        print_table_record("Total", total, total.percentage().unwrap_or(0.0));
-        print_table_line();
+        let total = self.total();
+        print_table("Module", self.items.iter(), total);
+        println!();
+        print_table("Kind", self.kinds.iter(), total);
+    }
+
+    fn count_item(&mut self, module: String, i: &clean::Item) {
+        let docs = i.attrs.collapsed_doc_value();
+        let has_docs = docs.is_some();
+        let has_examples = docs.map_or(false, |docs| has_examples(&docs));
+
+        self.items.entry(module).or_default().count_item(has_docs, has_examples);
+        self.kinds.entry(i.type_().as_str()).or_default().count_item(has_docs, has_examples);
+    }
+}
+
+/// Returns whether the documentation contains a Rust code block.
+fn has_examples(doc: &str) -> bool {
+    struct Tests {
+        found_tests: usize,
     }
+
+    impl crate::test::Tester for Tests {
+        fn add_test(&mut self, _: String, _: LangString, _: usize) {
+            self.found_tests += 1;
+        }
+    }
+
+    let mut tests = Tests { found_tests: 0 };
+    find_testable_code(doc, &mut tests, ErrorCodes::No, false);
+    tests.found_tests > 0
 }
 
 impl fold::DocFolder for CoverageCalculator {
     fn fold_item(&mut self, i: clean::Item) -> Option<clean::Item> {
-        let has_docs = !i.attrs.doc_strings.is_empty();
-
         match i.inner {
             _ if !i.def_id.is_local() => {
                 // non-local items are skipped because they can be out of the users control,
@@ -157,12 +218,20 @@ impl fold::DocFolder for CoverageCalculator {
             }
             _ => {
                 debug!("counting {:?} {:?} in {}", i.type_(), i.name, i.source.filename);
-                self.items.entry(i.source.filename.clone())
-                          .or_default()
-                          .count_item(has_docs);
+                // the crate root is counted in itself, other modules in their parent
+                let module = self.modules.join("::");
+                self.count_item(module, &i);
             }
         }
 
-        self.fold_item_recur(i)
+        match i.inner {
+            clean::ModuleItem(ref m) if !m.is_crate => {
+                self.modules.push(i.name.map(|name| name.to_string()).unwrap_or_default());
+                let ret = self.fold_item_recur(i);
+                self.modules.pop();
+                ret
+            }
+            _ => self.fold_item_recur(i),
+        }
     }
 }
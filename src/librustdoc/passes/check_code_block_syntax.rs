@@ -1,14 +1,22 @@
-use errors::Applicability;
+use errors::{Applicability, Diagnostic, Handler};
+use errors::emitter::Emitter;
+use rustc::lint;
+use rustc_data_structures::sync::{Lock, Lrc};
 use rustc_parse::lexer::{StringReader as Lexer};
+use rustc_parse::maybe_new_parser_from_source_str;
 use syntax::token;
 use syntax::sess::ParseSess;
-use syntax::source_map::FilePathMapping;
+use syntax::source_map::{FilePathMapping, SourceMap};
+use syntax::symbol::sym;
+use syntax::with_globals;
 use syntax_pos::{InnerSpan, FileName};
 
-use crate::clean;
+use std::ops::Range;
+
+use crate::clean::{self, AttributesExt};
 use crate::core::DocContext;
 use crate::fold::DocFolder;
-use crate::html::markdown::{self, RustCodeBlock};
+use crate::html::markdown::{self, ErrorCodes, LangString, RustCodeBlock};
 use crate::passes::Pass;
 
 pub const CHECK_CODE_BLOCK_SYNTAX: Pass = Pass {
@@ -17,20 +25,34 @@ pub const CHECK_CODE_BLOCK_SYNTAX: Pass = Pass {
     description: "validates syntax inside Rust code blocks",
 };
 
-pub fn check_code_block_syntax(krate: clean::Crate, cx: &DocContext<'_>) -> clean::Crate {
-    SyntaxChecker { cx }.fold_crate(krate)
+pub fn check_code_block_syntax(krate: clean::Crate, cx: &mut DocContext<'_>) -> clean::Crate {
+    let hidden_line_prefix = krate.module.as_ref().and_then(|module| {
+        module.attrs.lists(sym::doc)
+            .filter(|attr| attr.check_name(sym::hidden_line_prefix))
+            .filter_map(|attr| attr.value_str())
+            .map(|prefix| prefix.as_str().trim().to_string())
+            .find(|prefix| !prefix.is_empty())
+    });
+    SyntaxChecker { cx, hidden_line_prefix }.fold_crate(krate)
 }
 
 struct SyntaxChecker<'a, 'tcx> {
     cx: &'a DocContext<'tcx>,
+    /// The prefix of hidden lines given by `#![doc(hidden_line_prefix = "...")]`.
+    hidden_line_prefix: Option<String>,
 }
 
 impl<'a, 'tcx> SyntaxChecker<'a, 'tcx> {
     fn check_rust_syntax(&self, item: &clean::Item, dox: &str, code_block: RustCodeBlock) {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            // The docs of items from other crates were checked when documenting those.
+            None => return,
+        };
         let sess = ParseSess::new(FilePathMapping::empty());
         let source_file = sess.source_map().new_source_file(
             FileName::Custom(String::from("doctest")),
-            dox[code_block.code].to_owned(),
+            dox[code_block.code.clone()].to_owned(),
         );
 
         let validation_status = {
@@ -52,7 +74,7 @@ impl<'a, 'tcx> SyntaxChecker<'a, 'tcx> {
             } else if only_whitespace {
                 Some(CodeBlockInvalid::Empty)
             } else {
-                None
+                self.parse_error(&dox[code_block.code.clone()], &code_block)
             }
         };
 
@@ -61,11 +83,36 @@ impl<'a, 'tcx> SyntaxChecker<'a, 'tcx> {
                 super::source_span_for_markdown_range(self.cx, &dox, &code_block.range, &item.attrs)
             {
                 let warning_message = match code_block_invalid {
-                    CodeBlockInvalid::SyntaxError => "could not parse code block as Rust code",
+                    CodeBlockInvalid::SyntaxError | CodeBlockInvalid::ParseError { .. } => {
+                        "could not parse code block as Rust code"
+                    }
                     CodeBlockInvalid::Empty => "Rust code block is empty",
                 };
 
-                let mut diag = self.cx.sess().struct_span_warn(sp, warning_message);
+                let mut diag = self.cx.tcx.struct_span_lint_hir(
+                    lint::builtin::INVALID_RUST_CODEBLOCKS,
+                    hir_id,
+                    sp,
+                    warning_message,
+                );
+
+                if let CodeBlockInvalid::ParseError { ref message, ref range } =
+                    code_block_invalid
+                {
+                    let error_sp = range.as_ref().and_then(|range| {
+                        let range = (code_block.code.start + range.start)..
+                            (code_block.code.start + range.end);
+                        super::source_span_for_markdown_range(self.cx, &dox, &range, &item.attrs)
+                    });
+                    match error_sp {
+                        Some(error_sp) => {
+                            diag.span_label(error_sp, message.as_str());
+                        }
+                        None => {
+                            diag.note(&format!("error from rustc: {}", message));
+                        }
+                    }
+                }
 
                 if code_block.syntax.is_none() && code_block.is_fenced {
                     let sp = sp.from_inner(InnerSpan::new(0, 3));
@@ -81,11 +128,17 @@ impl<'a, 'tcx> SyntaxChecker<'a, 'tcx> {
             } else {
                 // We couldn't calculate the span of the markdown block that had the error, so our
                 // diagnostics are going to be a bit lacking.
-                let mut diag = self.cx.sess().struct_span_warn(
+                let mut diag = self.cx.tcx.struct_span_lint_hir(
+                    lint::builtin::INVALID_RUST_CODEBLOCKS,
+                    hir_id,
                     super::span_of_attrs(&item.attrs).unwrap_or(item.source.span()),
                     "doc comment contains an invalid Rust code block",
                 );
 
+                if let CodeBlockInvalid::ParseError { ref message, .. } = code_block_invalid {
+                    diag.note(&format!("error from rustc: {}", message));
+                }
+
                 if code_block.syntax.is_none() && code_block.is_fenced {
                     diag.help("mark blocks that do not contain Rust code as text: ```text");
                 }
@@ -96,6 +149,89 @@ impl<'a, 'tcx> SyntaxChecker<'a, 'tcx> {
             diag.emit();
         }
     }
+
+    /// Parses `code` the way a doctest would be compiled, as items or as the body of the `main`
+    /// function rustdoc wraps it in, and returns the first error if it is neither.
+    ///
+    /// `compile_fail` blocks are allowed not to parse, and `ignore` blocks are never compiled, so
+    /// those are not checked.
+    fn parse_error(&self, code: &str, code_block: &RustCodeBlock) -> Option<CodeBlockInvalid> {
+        let syntax = code_block.syntax.as_ref().map_or("", |s| &s[..]);
+        let lang_string = LangString::parse(syntax, ErrorCodes::No, false);
+        if lang_string.compile_fail || lang_string.ignore != markdown::Ignore::None {
+            return None;
+        }
+
+        let edition = lang_string.edition.unwrap_or(self.cx.sess().edition());
+        let crate_prefix = self.hidden_line_prefix.as_ref().map(|prefix| &**prefix);
+        let code = markdown::unhide_lines(code, lang_string.hidden_prefix(crate_prefix));
+        with_globals(edition, || {
+            if first_parse_error(code.clone(), 0).is_none() {
+                return None;
+            }
+
+            const MAIN: &str = "fn main() {\n";
+            let (message, range) = first_parse_error(format!("{}{}\n}}", MAIN, code), MAIN.len())?;
+            // Errors found in the `main` wrapper itself, like at its closing brace when a
+            // delimiter is left open, have no position in the code block.
+            let range = range.map(|range| {
+                range.start.min(code.len())..range.end.min(code.len()).max(range.start)
+            }).filter(|range| range.start < range.end);
+            Some(CodeBlockInvalid::ParseError { message, range })
+        })
+    }
+}
+
+/// Parses `source` as the items of a crate and returns the first error, with its range in
+/// `source` after the first `offset` bytes.
+fn first_parse_error(source: String, offset: usize) -> Option<(String, Option<Range<usize>>)> {
+    let errors = Lrc::new(Lock::new(vec![]));
+    let emitter = BufferEmitter { errors: errors.clone() };
+    let handler = Handler::with_emitter(false, None, box emitter);
+    let sess = ParseSess::with_span_handler(
+        handler,
+        Lrc::new(SourceMap::new(FilePathMapping::empty())),
+    );
+
+    let filename = FileName::Custom(String::from("doctest"));
+    match maybe_new_parser_from_source_str(&sess, filename, source) {
+        Ok(mut parser) => {
+            if let Err(mut err) = parser.parse_crate_mod() {
+                err.emit();
+            }
+        }
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                sess.span_diagnostic.emit_diagnostic(&diagnostic);
+            }
+        }
+    }
+
+    let errors = errors.borrow();
+    let error = errors.first()?;
+    let range = error.span.primary_span().map(|sp| {
+        let lo = sess.source_map().lookup_byte_offset(sp.lo()).pos.0 as usize;
+        let hi = sess.source_map().lookup_byte_offset(sp.hi()).pos.0 as usize;
+        lo.saturating_sub(offset)..hi.saturating_sub(offset)
+    });
+    Some((error.message(), range))
+}
+
+/// Keeps the errors from parsing a code block instead of printing them.
+struct BufferEmitter {
+    errors: Lrc<Lock<Vec<Diagnostic>>>,
+}
+
+impl Emitter for BufferEmitter {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        if diag.is_error() {
+            self.errors.borrow_mut().push(diag.clone());
+        }
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        None
+    }
 }
 
 impl<'a, 'tcx> DocFolder for SyntaxChecker<'a, 'tcx> {
@@ -113,4 +249,6 @@ impl<'a, 'tcx> DocFolder for SyntaxChecker<'a, 'tcx> {
 enum CodeBlockInvalid {
     SyntaxError,
     Empty,
+    /// The code lexes, but does not parse. `range` is where the first error is in the code.
+    ParseError { message: String, range: Option<Range<usize>> },
 }
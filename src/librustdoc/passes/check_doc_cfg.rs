@@ -0,0 +1,142 @@
+use rustc::hir::HirId;
+use rustc::lint;
+use syntax::ast::Attribute;
+use syntax::symbol::sym;
+use syntax_pos::Span;
+
+use crate::clean::{Crate, Item};
+use crate::clean::cfg::Cfg;
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::passes::Pass;
+
+pub const CHECK_DOC_CFG: Pass = Pass {
+    name: "check-doc-cfg",
+    pass: check_doc_cfg,
+    description: "detects `doc(cfg)` annotations which contradict `#[cfg]` or are redundant",
+};
+
+pub fn check_doc_cfg(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
+    let auto_cfg = krate.module.as_ref().map_or(false, |m| m.attrs.has_doc_flag(sym::auto_cfg));
+    DocCfgLinter { cx, auto_cfg, parent_gates: vec![], parent_doc_cfgs: vec![] }.fold_crate(krate)
+}
+
+struct DocCfgLinter<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+    /// Whether plain `#[cfg(...)]` attributes are displayed like `#[doc(cfg(...))]`, which makes
+    /// a `doc(cfg)` repeating them redundant.
+    auto_cfg: bool,
+    /// The operands of the `#[cfg(...)]` attributes of the items being visited and their parents,
+    /// which all hold wherever the items are compiled.
+    parent_gates: Vec<(Cfg, Span)>,
+    /// The operands of the `#[doc(cfg(...))]` attributes of the parents of the items being
+    /// visited, which are displayed on the items already.
+    parent_doc_cfgs: Vec<(Cfg, Span)>,
+}
+
+impl<'a, 'tcx> DocCfgLinter<'a, 'tcx> {
+    /// Checks the operands of the `doc(cfg)` of an item against the `#[cfg]` of the item and its
+    /// parents, and against the `doc(cfg)` of its parents.
+    fn check_doc_cfgs(&self, hir_id: HirId, doc_cfgs: &[(Cfg, Span)]) {
+        let gates = &self.parent_gates;
+        for (doc_cfg, doc_span) in doc_cfgs {
+            let lint = lint::builtin::INCONSISTENT_DOC_CFG;
+            if let Some((_, gate_span)) = gates.iter().find(|(gate, _)| doc_cfg.excludes(gate)) {
+                let mut diag = self.cx.tcx.struct_span_lint_hir(
+                    lint,
+                    hir_id,
+                    *doc_span,
+                    "`doc(cfg)` contradicts the configuration this item is compiled in",
+                );
+                diag.span_label(*doc_span, "documented as only available here");
+                diag.span_label(*gate_span, "but only compiled where this holds");
+                diag.emit();
+            } else if let Some((_, span)) = self.parent_doc_cfgs.iter()
+                .find(|(parent_doc_cfg, _)| parent_doc_cfg == doc_cfg)
+            {
+                let mut diag = self.cx.tcx.struct_span_lint_hir(
+                    lint,
+                    hir_id,
+                    *doc_span,
+                    "`doc(cfg)` repeats the configuration of a parent item",
+                );
+                diag.span_label(*span, "already documented here");
+                diag.help("remove it, it is displayed on the items of the parent anyway");
+                diag.emit();
+            } else if let Some((_, span)) = gates.iter()
+                .find(|(gate, _)| self.auto_cfg && gate == doc_cfg)
+            {
+                let mut diag = self.cx.tcx.struct_span_lint_hir(
+                    lint,
+                    hir_id,
+                    *doc_span,
+                    "`doc(cfg)` repeats a `#[cfg]` of the item",
+                );
+                diag.span_label(*span, "already displayed because of `#![doc(auto_cfg)]`");
+                diag.emit();
+            }
+        }
+    }
+}
+
+/// Returns the operands of the `#[cfg(...)]` attributes in `attrs` which describe where the item
+/// is available, rather than how the crate is being built.
+fn gates(attrs: &[Attribute]) -> Vec<(Cfg, Span)> {
+    attrs.iter()
+        .filter(|attr| attr.check_name(sym::cfg))
+        .filter_map(|attr| {
+            let list = attr.meta_item_list().filter(|list| list.len() == 1)?;
+            let cfg = Cfg::parse(list[0].meta_item()?).ok()?;
+            if cfg.mentions_any(&[sym::test, sym::doc, sym::doctest]) {
+                None
+            } else {
+                Some((cfg, attr.span))
+            }
+        })
+        .flat_map(|(cfg, span)| {
+            cfg.conjuncts().into_iter().map(|cfg| (cfg.clone(), span)).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns the operands of the `#[doc(cfg(...))]` attributes in `attrs`. Invalid ones were
+/// reported when cleaning the item.
+fn doc_cfgs(attrs: &[Attribute]) -> Vec<(Cfg, Span)> {
+    attrs.iter()
+        .filter(|attr| attr.check_name(sym::doc))
+        .flat_map(|attr| attr.meta_item_list().unwrap_or_default())
+        .filter(|nested| nested.check_name(sym::cfg))
+        .filter_map(|nested| {
+            let list = nested.meta_item_list().filter(|list| list.len() == 1)?;
+            let cfg = Cfg::parse(list[0].meta_item()?).ok()?;
+            Some((cfg, nested.span()))
+        })
+        .flat_map(|(cfg, span)| {
+            cfg.conjuncts().into_iter().map(|cfg| (cfg.clone(), span)).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+impl<'a, 'tcx> DocFolder for DocCfgLinter<'a, 'tcx> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            None => return self.fold_item_recur(item),
+        };
+        let attrs = self.cx.tcx.hir().attrs(hir_id);
+
+        let gates = gates(attrs);
+        let doc_cfgs = doc_cfgs(attrs);
+        let old_gates_len = self.parent_gates.len();
+        let old_doc_cfgs_len = self.parent_doc_cfgs.len();
+        self.parent_gates.extend(gates);
+
+        self.check_doc_cfgs(hir_id, &doc_cfgs);
+
+        self.parent_doc_cfgs.extend(doc_cfgs);
+        let result = self.fold_item_recur(item);
+        self.parent_gates.truncate(old_gates_len);
+        self.parent_doc_cfgs.truncate(old_doc_cfgs_len);
+        result
+    }
+}
@@ -0,0 +1,47 @@
+use std::mem;
+
+use crate::clean::{self, Crate, Item};
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::html::item_type::ItemType;
+use crate::passes::{look_for_tests, Pass};
+
+pub const CHECK_DOC_CODE_EXAMPLES: Pass = Pass {
+    name: "check-doc-code-examples",
+    pass: check_doc_code_examples,
+    description: "detects public items of the kinds given by `--code-example-kinds` which have no \
+                  code example in their documentation",
+};
+
+pub fn check_doc_code_examples(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
+    CodeExampleLinter { cx, in_trait_impl: false }.fold_crate(krate)
+}
+
+struct CodeExampleLinter<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+    /// Whether the items being visited implement a trait, and so show the docs of the trait items
+    /// when they have none of their own.
+    in_trait_impl: bool,
+}
+
+impl<'a, 'tcx> DocFolder for CodeExampleLinter<'a, 'tcx> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        if let clean::ImplItem(ref impl_) = item.inner {
+            let old_in_trait_impl = mem::replace(&mut self.in_trait_impl, impl_.trait_.is_some());
+            let result = self.fold_item_recur(item);
+            self.in_trait_impl = old_in_trait_impl;
+            return result;
+        }
+
+        let cx = self.cx;
+        if !self.in_trait_impl
+            && cx.code_example_kinds.contains(&ItemType::from(&item))
+            && cx.renderinfo.access_levels.is_public(item.def_id)
+        {
+            let dox = item.attrs.collapsed_doc_value().unwrap_or_else(String::new);
+            look_for_tests(&cx, &dox, &item, true);
+        }
+
+        self.fold_item_recur(item)
+    }
+}
@@ -0,0 +1,201 @@
+use rustc::hir::def::DefKind;
+use rustc::hir::def_id::DefId;
+use rustc::lint;
+use rustc::util::nodemap::FxHashSet;
+use syntax::symbol::sym;
+
+use crate::clean::{self, AttributesExt, Crate, Item, NestedAttributesExt};
+use crate::clean::utils::{bound_referenced_dids, referenced_dids};
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::passes::Pass;
+
+pub const CHECK_HIDDEN_TYPES: Pass = Pass {
+    name: "check-hidden-types",
+    pass: check_hidden_types,
+    description: "detects documented signatures which refer to private or hidden types and traits",
+};
+
+pub fn check_hidden_types(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
+    HiddenTypeLinter { cx, in_generated_impl: false }.fold_crate(krate)
+}
+
+struct HiddenTypeLinter<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+    /// Whether the items being visited belong to an impl made up by rustdoc, whose signatures
+    /// cannot be changed by the user.
+    in_generated_impl: bool,
+}
+
+/// Why a type or trait referred to by a signature does not get documented.
+enum HiddenReason {
+    Private,
+    DocHidden,
+}
+
+impl<'a, 'tcx> HiddenTypeLinter<'a, 'tcx> {
+    fn hidden_reason(&self, did: DefId) -> Option<HiddenReason> {
+        if self.cx.tcx.get_attrs(did).lists(sym::doc).has_word(sym::hidden) {
+            Some(HiddenReason::DocHidden)
+        } else if did.is_local() && !self.cx.renderinfo.access_levels.is_exported(did) {
+            Some(HiddenReason::Private)
+        } else {
+            None
+        }
+    }
+
+    fn check_item(&self, item: &Item) {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            None => return,
+        };
+
+        let mut dids = FxHashSet::default();
+        collect_item_dids(item, &mut dids);
+        // An item referring to itself, like a method of a trait taking `Self`, is documented
+        // along with it.
+        dids.remove(&item.def_id);
+
+        let mut dids = dids.into_iter().collect::<Vec<_>>();
+        dids.sort();
+        for did in dids {
+            let reason = match self.hidden_reason(did) {
+                Some(reason) => reason,
+                None => continue,
+            };
+            let tcx = self.cx.tcx;
+            let kind = match tcx.def_kind(did) {
+                Some(DefKind::Trait) | Some(DefKind::TraitAlias) => "trait",
+                _ => "type",
+            };
+            let name = tcx.def_path_str(did);
+            let (what, note) = match reason {
+                HiddenReason::Private => (
+                    "private",
+                    format!("re-export `{}` publicly so that it gets documented, or hide this \
+                             item with `#[doc(hidden)]`", name),
+                ),
+                HiddenReason::DocHidden => (
+                    "hidden",
+                    format!("remove `#[doc(hidden)]` from `{}`, or hide this item with \
+                             `#[doc(hidden)]` as well", name),
+                ),
+            };
+            let mut diag = tcx.struct_span_lint_hir(
+                lint::builtin::HIDDEN_TYPES_IN_SIGNATURES,
+                hir_id,
+                tcx.def_span(item.def_id),
+                &format!("documented item refers to {} {} `{}`", what, kind, name),
+            );
+            diag.note("its name will not link to any documentation");
+            diag.help(&note);
+            diag.emit();
+        }
+    }
+}
+
+/// Collects the types and traits that the signature of `item` refers to.
+fn collect_item_dids(item: &Item, dids: &mut FxHashSet<DefId>) {
+    fn decl(decl: &clean::FnDecl, dids: &mut FxHashSet<DefId>) {
+        for arg in &decl.inputs.values {
+            referenced_dids(&arg.type_, dids);
+        }
+        if let clean::FunctionRetTy::Return(ref ty) = decl.output {
+            referenced_dids(ty, dids);
+        }
+    }
+
+    fn generics(generics: &clean::Generics, dids: &mut FxHashSet<DefId>) {
+        for param in &generics.params {
+            match param.kind {
+                clean::GenericParamDefKind::Type { ref bounds, ref default, .. } => {
+                    bound_referenced_dids(bounds, dids);
+                    if let Some(ref default) = *default {
+                        referenced_dids(default, dids);
+                    }
+                }
+                clean::GenericParamDefKind::Const { ref ty, .. } => referenced_dids(ty, dids),
+                clean::GenericParamDefKind::Lifetime => {}
+            }
+        }
+        for predicate in &generics.where_predicates {
+            match *predicate {
+                clean::WherePredicate::BoundPredicate { ref ty, ref bounds } => {
+                    referenced_dids(ty, dids);
+                    bound_referenced_dids(bounds, dids);
+                }
+                clean::WherePredicate::EqPredicate { ref lhs, ref rhs } => {
+                    referenced_dids(lhs, dids);
+                    referenced_dids(rhs, dids);
+                }
+                clean::WherePredicate::RegionPredicate { .. } => {}
+            }
+        }
+    }
+
+    match item.inner {
+        clean::FunctionItem(ref f) => {
+            decl(&f.decl, dids);
+            generics(&f.generics, dids);
+        }
+        clean::MethodItem(ref m) => {
+            decl(&m.decl, dids);
+            generics(&m.generics, dids);
+        }
+        clean::TyMethodItem(ref m) => {
+            decl(&m.decl, dids);
+            generics(&m.generics, dids);
+        }
+        clean::StructItem(ref s) => generics(&s.generics, dids),
+        clean::UnionItem(ref u) => generics(&u.generics, dids),
+        clean::EnumItem(ref e) => generics(&e.generics, dids),
+        clean::TraitItem(ref t) => {
+            generics(&t.generics, dids);
+            bound_referenced_dids(&t.bounds, dids);
+        }
+        clean::StructFieldItem(ref ty) |
+        clean::AssocConstItem(ref ty, _) => referenced_dids(ty, dids),
+        clean::VariantItem(clean::Variant { kind: clean::VariantKind::Tuple(ref tys) }) => {
+            for ty in tys {
+                referenced_dids(ty, dids);
+            }
+        }
+        clean::TypedefItem(ref t, _) => {
+            referenced_dids(&t.type_, dids);
+            generics(&t.generics, dids);
+        }
+        clean::ConstantItem(ref c) => referenced_dids(&c.type_, dids),
+        clean::StaticItem(ref s) | clean::ForeignStaticItem(ref s) => {
+            referenced_dids(&s.type_, dids)
+        }
+        clean::ForeignFunctionItem(ref f) => {
+            decl(&f.decl, dids);
+            generics(&f.generics, dids);
+        }
+        clean::AssocTypeItem(ref bounds, ref default) => {
+            bound_referenced_dids(bounds, dids);
+            if let Some(ref default) = *default {
+                referenced_dids(default, dids);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl<'a, 'tcx> DocFolder for HiddenTypeLinter<'a, 'tcx> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        if let clean::ImplItem(ref impl_) = item.inner {
+            let in_generated_impl = self.in_generated_impl;
+            self.in_generated_impl = impl_.synthetic || impl_.blanket_impl.is_some();
+            let result = self.fold_item_recur(item);
+            self.in_generated_impl = in_generated_impl;
+            return result;
+        }
+
+        if !self.in_generated_impl && !item.is_stripped() {
+            self.check_item(&item);
+        }
+
+        self.fold_item_recur(item)
+    }
+}
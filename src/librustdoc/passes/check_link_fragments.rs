@@ -0,0 +1,344 @@
+use rustc::hir::HirId;
+use rustc::hir::def_id::DefId;
+use rustc::lint;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+
+use std::mem;
+use std::ops::Range;
+
+use crate::clean::{self, Crate, GetDefId, Item};
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::html::item_type::ItemType;
+use crate::html::markdown::{heading_ids, markdown_links, IdMap};
+use crate::html::render::{heading_prefix, initial_ids, item_anchor, item_path, item_ty_to_strs};
+use crate::html::render::{namespace_anchor, page_ids, variant_field_anchor, ITEM_SECTION_IDS};
+use crate::passes::{source_span_for_markdown_range, span_of_attrs, Pass};
+
+pub const CHECK_LINK_FRAGMENTS: Pass = Pass {
+    name: "check-link-fragments",
+    pass: check_link_fragments,
+    description: "detects links to anchors which do not exist on the page they point to",
+};
+
+pub fn check_link_fragments(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
+    let mut collector = AnchorCollector {
+        deref_trait: cx.tcx.lang_items().deref_trait(),
+        pages: FxHashMap::default(),
+        ids: FxHashMap::default(),
+        files: FxHashMap::default(),
+        dirs: FxHashMap::default(),
+        deref_targets: FxHashMap::default(),
+        modules: vec![],
+        page: None,
+        variant: None,
+    };
+    let krate = collector.fold_crate(krate);
+
+    let mut section_ids = initial_ids().into_iter().collect::<FxHashSet<_>>();
+    section_ids.extend(ITEM_SECTION_IDS.iter().map(|id| id.to_string()));
+    FragmentChecker {
+        cx,
+        section_ids,
+        pages: collector.pages,
+        files: collector.files,
+        dirs: collector.dirs,
+        deref_targets: collector.deref_targets,
+        page: None,
+    }.fold_crate(krate)
+}
+
+/// The types of the items which have an anchor on the page of their parent.
+const MEMBER_TYPES: &[ItemType] = &[
+    ItemType::StructField,
+    ItemType::Variant,
+    ItemType::TyMethod,
+    ItemType::Method,
+    ItemType::AssocType,
+    ItemType::AssocConst,
+];
+
+/// Returns the item whose page displays the documentation of `item`, given the one of its
+/// parent.
+fn page_of(item: &Item, parent_page: Option<DefId>) -> Option<DefId> {
+    match item.inner {
+        clean::StrippedItem(..) |
+        clean::ImportItem(..) |
+        clean::ExternCrateItem(..) => None,
+        clean::ImplItem(ref impl_) => impl_.for_.def_id(),
+        clean::StructFieldItem(..) |
+        clean::VariantItem(..) |
+        clean::TyMethodItem(..) |
+        clean::MethodItem(..) |
+        clean::AssocConstItem(..) |
+        clean::AssocTypeItem(..) |
+        clean::TypedefItem(_, true) |
+        clean::OpaqueTyItem(_, true) => parent_page,
+        _ => Some(item.def_id),
+    }
+}
+
+struct AnchorCollector {
+    deref_trait: Option<DefId>,
+    /// The ids of the anchors on the page of each item, apart from the ones in `ITEM_SECTION_IDS`
+    /// and `initial_ids`, which may appear on any page.
+    pages: FxHashMap<DefId, FxHashSet<String>>,
+    /// The ids taken on the page of each item, to give the headings the ids they get when the
+    /// page is rendered.
+    ids: FxHashMap<DefId, IdMap>,
+    /// The items with a page, by the module whose directory holds the page and its file name.
+    files: FxHashMap<(DefId, String), DefId>,
+    /// The module whose directory holds the page of each item.
+    dirs: FxHashMap<DefId, DefId>,
+    /// The types that the types with a `Deref` impl dereference to, whose methods are displayed
+    /// on the page of the latter as well.
+    deref_targets: FxHashMap<DefId, DefId>,
+    modules: Vec<DefId>,
+    page: Option<DefId>,
+    /// The name of the variant whose fields are being visited.
+    variant: Option<String>,
+}
+
+impl AnchorCollector {
+    /// Returns the ids of the anchors which `item` gets on the page of its parent.
+    fn member_anchors(&self, item: &Item) -> Vec<String> {
+        let name = match item.name {
+            Some(name) => name.as_str(),
+            None => return vec![],
+        };
+        let ty = match item.inner {
+            clean::StructFieldItem(..) => match self.variant {
+                Some(ref variant) => return vec![variant_field_anchor(variant, &name)],
+                None => ItemType::StructField,
+            },
+            clean::TypedefItem(_, true) |
+            clean::OpaqueTyItem(_, true) => ItemType::AssocType,
+            clean::VariantItem(..) |
+            clean::TyMethodItem(..) |
+            clean::MethodItem(..) |
+            clean::AssocConstItem(..) |
+            clean::AssocTypeItem(..) => item.type_(),
+            _ => return vec![],
+        };
+
+        let mut anchors = vec![item_anchor(ty, &name), namespace_anchor(item.type_(), &name)];
+        if let clean::VariantItem(clean::Variant { kind: clean::VariantKind::Struct(_) }) =
+            item.inner
+        {
+            anchors.push(format!("{}.fields", item_anchor(ty, &name)));
+        }
+        anchors
+    }
+
+    /// Returns the ids of the anchors which the contents of `item` get on its page, besides
+    /// those of its members.
+    fn section_anchors(&mut self, item: &Item) -> Vec<String> {
+        match item.inner {
+            clean::ModuleItem(ref module) => {
+                module.items.iter()
+                    .filter(|item| !item.is_stripped())
+                    .map(|item| item_ty_to_strs(&item.type_()).0.to_string())
+                    .collect()
+            }
+            clean::ImplItem(ref impl_) => {
+                let is_deref = self.deref_trait.is_some() &&
+                               impl_.trait_.def_id() == self.deref_trait;
+                if let Some(for_) = impl_.for_.def_id().filter(|_| is_deref) {
+                    let target = impl_.items.iter().filter_map(|item| match item.inner {
+                        clean::TypedefItem(ref t, true) => t.type_.def_id(),
+                        _ => None,
+                    }).next();
+                    if let Some(target) = target {
+                        self.deref_targets.insert(for_, target);
+                    }
+                }
+                // The provided methods of the trait which the impl does not override are
+                // displayed along with the others.
+                impl_.provided_trait_methods.iter().flat_map(|name| {
+                    vec![item_anchor(ItemType::Method, name),
+                         namespace_anchor(ItemType::Method, name)]
+                }).collect()
+            }
+            _ => vec![],
+        }
+    }
+}
+
+impl DocFolder for AnchorCollector {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        let page = page_of(&item, self.page);
+        if let Some(page) = page {
+            let mut anchors = self.member_anchors(&item);
+            anchors.extend(self.section_anchors(&item));
+            if let Some(dox) = item.attrs.collapsed_doc_value() {
+                let ids = self.ids.entry(page).or_insert_with(page_ids);
+                ids.set_heading_prefix(heading_prefix(&item));
+                anchors.extend(heading_ids(&dox, ids));
+            }
+            self.pages.entry(page).or_default().extend(anchors);
+
+            if page == item.def_id {
+                let dir = if item.is_mod() { Some(&item.def_id) } else { self.modules.last() };
+                if let (Some(&dir), Some(name)) = (dir, item.name) {
+                    self.dirs.insert(page, dir);
+                    let file = if item.is_mod() {
+                        "index.html".to_string()
+                    } else {
+                        item_path(item.type_(), &name.as_str())
+                    };
+                    self.files.insert((dir, file), page);
+                }
+            }
+        }
+
+        let variant = match item.inner {
+            clean::VariantItem(..) => item.name.map(|name| name.to_string()),
+            _ => None,
+        };
+        let is_mod = item.is_mod();
+        if is_mod {
+            self.modules.push(item.def_id);
+        }
+        let old_page = mem::replace(&mut self.page, page);
+        let old_variant = mem::replace(&mut self.variant, variant);
+        let result = self.fold_item_recur(item);
+        self.page = old_page;
+        self.variant = old_variant;
+        if is_mod {
+            self.modules.pop();
+        }
+        result
+    }
+}
+
+struct FragmentChecker<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+    /// The ids of the sections which may appear on any page.
+    section_ids: FxHashSet<String>,
+    pages: FxHashMap<DefId, FxHashSet<String>>,
+    files: FxHashMap<(DefId, String), DefId>,
+    dirs: FxHashMap<DefId, DefId>,
+    deref_targets: FxHashMap<DefId, DefId>,
+    page: Option<DefId>,
+}
+
+impl<'a, 'tcx> FragmentChecker<'a, 'tcx> {
+    /// Returns the anchors on the page of `did`, including the ones of the methods displayed
+    /// there through `Deref`, or `None` if some of them are not known.
+    fn anchors_of(&self, did: DefId) -> Option<Vec<&FxHashSet<String>>> {
+        let mut anchors = vec![self.pages.get(&did)?];
+        let mut seen = FxHashSet::default();
+        let mut did = did;
+        while let Some(&target) = self.deref_targets.get(&did) {
+            if !seen.insert(target) {
+                break;
+            }
+            anchors.push(self.pages.get(&target)?);
+            did = target;
+        }
+        Some(anchors)
+    }
+
+    /// Returns the item whose page `dest`, the part of a link before its `#`, points to.
+    fn target_of(&self, item: &Item, link: &str, dest: &str) -> Option<DefId> {
+        if dest.is_empty() {
+            return self.page;
+        }
+        let dir = self.page.and_then(|page| self.dirs.get(&page));
+        if let Some(&dir) = dir {
+            let file = dest.trim_start_matches("./").to_string();
+            if let Some(&did) = self.files.get(&(dir, file)) {
+                return Some(did);
+            }
+        }
+        item.attrs.links.iter().find(|(ori_link, ..)| ori_link == link).and_then(|(_, did, _)| *did)
+    }
+
+    fn check_links(&self, item: &Item) {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            None => return,
+        };
+        let dox = item.attrs.collapsed_doc_value().unwrap_or_default();
+        for (link, range) in markdown_links(&dox) {
+            let (dest, fragment) = match link.find('#') {
+                Some(hash) => (&link[..hash], link[hash + 1..].trim_end_matches('`')),
+                None => continue,
+            };
+            if fragment.is_empty() {
+                continue;
+            }
+            let target = match self.target_of(item, &link, dest) {
+                Some(target) if target.is_local() => target,
+                _ => continue,
+            };
+            let anchors = match self.anchors_of(target) {
+                Some(anchors) => anchors,
+                None => continue,
+            };
+
+            // Ids appearing several times on a page get a number appended to them.
+            let base = match fragment.rfind('-') {
+                Some(i) if i + 1 < fragment.len() &&
+                           fragment[i + 1..].bytes().all(|b| b.is_ascii_digit()) => {
+                    &fragment[..i]
+                }
+                _ => fragment,
+            };
+            // The ids of impl blocks are derived from their trait path.
+            if base.starts_with("impl") || self.section_ids.contains(base) ||
+               anchors.iter().any(|anchors| anchors.contains(base))
+            {
+                continue;
+            }
+
+            // Links to members are often written with the name of the member alone.
+            let member = MEMBER_TYPES.iter()
+                .map(|&ty| item_anchor(ty, fragment))
+                .find(|anchor| anchors.iter().any(|anchors| anchors.contains(anchor)));
+            self.report(item, hir_id, &dox, range, &link, member);
+        }
+    }
+
+    fn report(
+        &self,
+        item: &Item,
+        hir_id: HirId,
+        dox: &str,
+        range: Option<Range<usize>>,
+        link: &str,
+        member: Option<String>,
+    ) {
+        let link = link.replace('`', "");
+        let fragment = link.splitn(2, '#').nth(1).unwrap_or("");
+        let sp = range.and_then(|range| {
+            source_span_for_markdown_range(self.cx, dox, &range, &item.attrs)
+        });
+        let mut diag = self.cx.tcx.struct_span_lint_hir(
+            lint::builtin::BROKEN_LINK_FRAGMENTS,
+            hir_id,
+            sp.or_else(|| span_of_attrs(&item.attrs)).unwrap_or(item.source.span()),
+            &format!("`{}` links to an anchor which does not exist", link),
+        );
+        if let Some(sp) = sp {
+            diag.span_label(sp, format!("there is no `#{}` on the linked page", fragment));
+        }
+        if let Some(member) = member {
+            diag.help(&format!("the anchor of the member `{}` is `#{}`", fragment, member));
+        }
+        diag.emit();
+    }
+}
+
+impl<'a, 'tcx> DocFolder for FragmentChecker<'a, 'tcx> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        let page = page_of(&item, self.page);
+        let old_page = mem::replace(&mut self.page, page);
+        if page.is_some() {
+            self.check_links(&item);
+        }
+        let result = self.fold_item_recur(item);
+        self.page = old_page;
+        result
+    }
+}
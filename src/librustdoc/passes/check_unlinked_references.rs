@@ -0,0 +1,195 @@
+use errors::Applicability;
+use rustc::lint;
+use rustc_feature::UnstableFeatures;
+
+use std::ops::Range;
+
+use crate::clean::{self, Crate, Item};
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::html::markdown::plain_text_ranges;
+use crate::passes::{source_span_for_markdown_range, span_of_attrs, Pass};
+
+#[cfg(test)]
+mod tests;
+
+pub const CHECK_UNLINKED_REFERENCES: Pass = Pass {
+    name: "check-unlinked-references",
+    pass: check_unlinked_references,
+    description: "detects bare URLs and item paths in documentation which are not links",
+};
+
+pub fn check_unlinked_references(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
+    UnlinkedReferenceLinter {
+        cx,
+        // Paths can only be turned into intra-doc links where those are resolved.
+        check_paths: UnstableFeatures::from_environment().is_nightly_build(),
+    }.fold_crate(krate)
+}
+
+struct UnlinkedReferenceLinter<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+    check_paths: bool,
+}
+
+/// The kinds of references which can be written without a link.
+#[derive(Clone, Copy)]
+enum Reference {
+    Url,
+    Path,
+}
+
+impl<'a, 'tcx> UnlinkedReferenceLinter<'a, 'tcx> {
+    fn report(&self, item: &Item, dox: &str, range: Range<usize>, reference: Reference) {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            None => return,
+        };
+
+        let text = &dox[range.clone()];
+        let (lint, msg, suggestion_msg, suggestion) = match reference {
+            Reference::Url => (
+                lint::builtin::BARE_URLS,
+                "this URL is not a hyperlink".to_string(),
+                "use an automatic link instead",
+                format!("<{}>", text),
+            ),
+            Reference::Path => (
+                lint::builtin::UNLINKED_PATHS,
+                format!("`{}` looks like an item path, but is not a link", text),
+                "link to the item instead",
+                format!("[`{}`]", text),
+            ),
+        };
+
+        let sp = source_span_for_markdown_range(self.cx, dox, &range, &item.attrs);
+        let mut diag = self.cx.tcx.struct_span_lint_hir(
+            lint,
+            hir_id,
+            sp.or_else(|| span_of_attrs(&item.attrs)).unwrap_or(item.source.span()),
+            &msg,
+        );
+        match sp {
+            Some(sp) => {
+                diag.span_suggestion(
+                    sp,
+                    suggestion_msg,
+                    suggestion,
+                    Applicability::MachineApplicable,
+                );
+            }
+            None => {
+                diag.help(&format!("{}: `{}`", suggestion_msg, suggestion));
+            }
+        }
+        diag.emit();
+    }
+}
+
+impl<'a, 'tcx> DocFolder for UnlinkedReferenceLinter<'a, 'tcx> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        // Docs of `extern crate` items are not displayed.
+        if let clean::ExternCrateItem(..) = item.inner {
+            return self.fold_item_recur(item);
+        }
+
+        let dox = item.attrs.collapsed_doc_value().unwrap_or_default();
+        for text_range in plain_text_ranges(&dox) {
+            let text = &dox[text_range.clone()];
+            let urls = bare_urls(text);
+            for url in &urls {
+                let range = (text_range.start + url.start)..(text_range.start + url.end);
+                self.report(&item, &dox, range, Reference::Url);
+            }
+
+            if !self.check_paths {
+                continue;
+            }
+            for path in unlinked_paths(text) {
+                if urls.iter().any(|url| url.start < path.end && path.start < url.end) {
+                    continue;
+                }
+                let range = (text_range.start + path.start)..(text_range.start + path.end);
+                self.report(&item, &dox, range, Reference::Path);
+            }
+        }
+
+        self.fold_item_recur(item)
+    }
+}
+
+/// Returns the ranges of the `http://` and `https://` URLs in `text`.
+fn bare_urls(text: &str) -> Vec<Range<usize>> {
+    let mut urls = vec![];
+    let mut pos = 0;
+    while let Some(found) = text[pos..].find("http") {
+        let start = pos + found;
+        let rest = &text[start..];
+        let preceded_by_word = text[..start].chars().next_back()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_');
+        if preceded_by_word || !(rest.starts_with("http://") || rest.starts_with("https://")) {
+            pos = start + "http".len();
+            continue;
+        }
+
+        let len = rest.find(|c: char| c.is_whitespace() || "<>\"'`".contains(c))
+            .unwrap_or(rest.len());
+        let mut url = &rest[..len];
+        // Leave out punctuation which ends the sentence rather than the URL, along with closing
+        // parentheses which were not opened in it.
+        loop {
+            let trimmed = url.trim_end_matches(|c: char| ".,;:!?".contains(c));
+            let trimmed = if trimmed.ends_with(')')
+                && trimmed.matches(')').count() > trimmed.matches('(').count()
+            {
+                &trimmed[..trimmed.len() - 1]
+            } else {
+                trimmed
+            };
+            if trimmed.len() == url.len() {
+                break;
+            }
+            url = trimmed;
+        }
+
+        let scheme_len = rest.find("://").unwrap() + "://".len();
+        if url.len() > scheme_len {
+            urls.push(start..start + url.len());
+        }
+        pos = start + len.max(1);
+    }
+    urls
+}
+
+/// Returns the ranges of the words in `text` which look like item paths, like `vec::Vec` or
+/// `Vec::new()`.
+fn unlinked_paths(text: &str) -> Vec<Range<usize>> {
+    fn is_ident(s: &str) -> bool {
+        let mut chars = s.chars();
+        match chars.next() {
+            Some(c) if c.is_alphabetic() || c == '_' => {}
+            _ => return false,
+        }
+        s != "_" && chars.all(|c| c.is_alphanumeric() || c == '_')
+    }
+
+    let is_path_char = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+    let mut paths = vec![];
+    let mut pos = 0;
+    while let Some(found) = text[pos..].find(is_path_char) {
+        let start = pos + found;
+        let len = text[start..].find(|c: char| !is_path_char(c)).unwrap_or(text.len() - start);
+        let mut end = start + len;
+        pos = end;
+
+        let word = &text[start..end];
+        if !word.contains("::") || !word.split("::").all(is_ident) {
+            continue;
+        }
+        if text[end..].starts_with("()") {
+            end += "()".len();
+        }
+        paths.push(start..end);
+    }
+    paths
+}
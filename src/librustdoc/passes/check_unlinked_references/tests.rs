@@ -0,0 +1,37 @@
+use super::*;
+
+fn urls(text: &str) -> Vec<&str> {
+    bare_urls(text).into_iter().map(|r| &text[r]).collect()
+}
+
+fn paths(text: &str) -> Vec<&str> {
+    unlinked_paths(text).into_iter().map(|r| &text[r]).collect()
+}
+
+#[test]
+fn finds_bare_urls() {
+    assert_eq!(urls("see https://example.com for more"), ["https://example.com"]);
+    assert_eq!(urls("http://a.org/x, then http://b.org."), ["http://a.org/x", "http://b.org"]);
+    assert_eq!(urls("(see https://en.wikipedia.org/wiki/Rust_(language))"),
+               ["https://en.wikipedia.org/wiki/Rust_(language)"]);
+}
+
+#[test]
+fn ignores_non_urls() {
+    assert!(urls("http is a protocol").is_empty());
+    assert!(urls("nothttps://example.com").is_empty());
+    assert!(urls("https:// alone").is_empty());
+}
+
+#[test]
+fn finds_unlinked_paths() {
+    assert_eq!(paths("use std::vec::Vec here"), ["std::vec::Vec"]);
+    assert_eq!(paths("call Vec::new() first"), ["Vec::new()"]);
+}
+
+#[test]
+fn ignores_non_paths() {
+    assert!(paths("at 12:30 or a:b").is_empty());
+    assert!(paths("::leading and trailing:: colons").is_empty());
+    assert!(paths("a::1 is not a path").is_empty());
+}
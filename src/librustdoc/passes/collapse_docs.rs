@@ -29,7 +29,7 @@ impl DocFragment {
     }
 }
 
-pub fn collapse_docs(krate: clean::Crate, _: &DocContext<'_>) -> clean::Crate {
+pub fn collapse_docs(krate: clean::Crate, _: &mut DocContext<'_>) -> clean::Crate {
     let mut krate = Collapser.fold_crate(krate);
     krate.collapsed = true;
     krate
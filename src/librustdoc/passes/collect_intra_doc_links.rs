@@ -1,6 +1,6 @@
-use errors::Applicability;
+use errors::{Applicability, DiagnosticBuilder};
 use rustc::hir::def::{Res, DefKind, Namespace::{self, *}, PerNS};
-use rustc::hir::def_id::DefId;
+use rustc::hir::def_id::{DefId, LOCAL_CRATE};
 use rustc::hir;
 use rustc::lint as lint;
 use rustc::ty;
@@ -10,15 +10,19 @@ use syntax;
 use syntax::ast::{self, Ident};
 use syntax_expand::base::SyntaxExtensionKind;
 use syntax::symbol::Symbol;
-use syntax_pos::DUMMY_SP;
+use syntax::util::lev_distance::find_best_match_for_name;
+use syntax_pos::{Span, DUMMY_SP};
 
+use std::mem;
 use std::ops::Range;
 
 use crate::core::DocContext;
 use crate::fold::DocFolder;
-use crate::html::markdown::markdown_links;
+use crate::html::item_type::ItemType;
+use crate::html::markdown::{inline_links, markdown_links};
+use crate::html::render::{item_anchor, namespace_anchor, variant_field_anchor};
 use crate::clean::*;
-use crate::passes::{look_for_tests, Pass};
+use crate::passes::Pass;
 
 use super::span_of_attrs;
 
@@ -28,41 +32,151 @@ pub const COLLECT_INTRA_DOC_LINKS: Pass = Pass {
     description: "reads a crate's documentation to resolve intra-doc-links",
 };
 
-pub fn collect_intra_doc_links(krate: Crate, cx: &DocContext<'_>) -> Crate {
+pub fn collect_intra_doc_links(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
     if !UnstableFeatures::from_environment().is_nightly_build() {
         krate
     } else {
-        let mut coll = LinkCollector::new(cx);
+        let mut indexer = ItemIndexer {
+            cx,
+            items: vec![],
+            path: vec![],
+            module: None,
+        };
+        let krate = indexer.fold_crate(krate);
+
+        let mut coll = LinkCollector::new(cx, indexer.items);
 
         coll.fold_crate(krate)
     }
 }
 
+/// The maximum number of items suggested for a link which cannot be resolved.
+const MAX_SIMILAR_ITEMS: usize = 3;
+
+/// An item of the crate, to which the links which cannot be resolved are compared in order to
+/// suggest the item they were meant to point to.
+struct IndexedItem {
+    name: Symbol,
+    res: Res,
+    /// The path of the parent of the item, starting from the crate root.
+    parent_path: Vec<String>,
+    /// The module the item is declared in, unless it is a member of another item.
+    module: Option<DefId>,
+}
+
+/// Lists the items of the crate which intra-doc links can point to.
+struct ItemIndexer<'a, 'tcx> {
+    cx: &'a DocContext<'tcx>,
+    items: Vec<IndexedItem>,
+    /// The path of the item whose members are being visited, starting from the crate root.
+    path: Vec<String>,
+    /// The module whose items are being visited, unless they are members of another item.
+    module: Option<DefId>,
+}
+
+impl<'a, 'tcx> DocFolder for ItemIndexer<'a, 'tcx> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        let (path, module) = match item.inner {
+            // Items are linked to with the path they are declared at, not through re-exports.
+            ImportItem(..) | ExternCrateItem(..) | StrippedItem(..) => return Some(item),
+            ModuleItem(Module { is_crate: true, .. }) => (vec![], Some(item.def_id)),
+            // The members of impls are linked to through the type they are implemented for.
+            ImplItem(Impl { ref for_, .. }) => match for_.def_id() {
+                Some(did) if did.is_local() => {
+                    let path = self.cx.tcx.def_path_str(did);
+                    (path.split("::").map(|segment| segment.to_string()).collect(), None)
+                }
+                _ => return Some(item),
+            },
+            _ => {
+                let name = match item.name {
+                    Some(name) if item.def_id.is_local() => name,
+                    _ => return Some(item),
+                };
+                // Fields have no `Res` to describe them with, so they are not suggested.
+                let kind = match item.inner {
+                    StructFieldItem(..) => None,
+                    _ => self.cx.tcx.def_kind(item.def_id),
+                };
+                if let Some(kind) = kind {
+                    self.items.push(IndexedItem {
+                        name,
+                        res: Res::Def(kind, item.def_id),
+                        parent_path: self.path.clone(),
+                        module: self.module,
+                    });
+                }
+                let mut path = self.path.clone();
+                path.push(name.to_string());
+                (path, if item.is_mod() { Some(item.def_id) } else { None })
+            }
+        };
+
+        let old_path = mem::replace(&mut self.path, path);
+        let old_module = mem::replace(&mut self.module, module);
+        let ret = self.fold_item_recur(item);
+        self.path = old_path;
+        self.module = old_module;
+        ret
+    }
+}
+
+/// The module whose scope the paths of a link are resolved in.
+#[derive(Clone, Copy)]
+enum Scope {
+    Local(ast::NodeId),
+    /// A module of the crate which the documentation was inlined from.
+    Foreign(DefId),
+}
+
 enum ErrorKind {
     ResolutionFailure,
     AnchorFailure(&'static str),
 }
 
 struct LinkCollector<'a, 'tcx> {
-    cx: &'a DocContext<'tcx>,
+    cx: &'a mut DocContext<'tcx>,
     mod_ids: Vec<hir::HirId>,
+    index: Vec<IndexedItem>,
+    /// The module which the documentation being visited was written in, when it was inlined
+    /// from another crate.
+    foreign_scope: Option<DefId>,
 }
 
 impl<'a, 'tcx> LinkCollector<'a, 'tcx> {
-    fn new(cx: &'a DocContext<'tcx>) -> Self {
+    fn new(cx: &'a mut DocContext<'tcx>, index: Vec<IndexedItem>) -> Self {
         LinkCollector {
             cx,
             mod_ids: Vec::new(),
+            index,
+            foreign_scope: None,
         }
     }
 
+    /// Resolves `path_str` with the resolver, in the scope of the module given by `scope`.
+    fn resolve_str_path(
+        &self,
+        path_str: &str,
+        ns: Namespace,
+        scope: Scope,
+    ) -> Result<(ast::Path, Res), ()> {
+        self.cx.enter_resolver(|resolver| match scope {
+            Scope::Local(module_id) => {
+                resolver.resolve_str_path_error(DUMMY_SP, path_str, ns, module_id)
+            }
+            Scope::Foreign(module_id) => {
+                resolver.resolve_str_path_in_module(DUMMY_SP, path_str, ns, module_id)
+            }
+        })
+    }
+
     fn variant_field(
         &self,
         path_str: &str,
         current_item: &Option<String>,
-        module_id: syntax::ast::NodeId,
+        scope: Scope,
     ) -> Result<(Res, Option<String>), ErrorKind> {
-        let cx = self.cx;
+        let cx = &*self.cx;
 
         let mut split = path_str.rsplitn(3, "::");
         let variant_field_name = split
@@ -81,9 +195,8 @@ impl<'a, 'tcx> LinkCollector<'a, 'tcx> {
             }
             f.to_owned()
         }).ok_or(ErrorKind::ResolutionFailure)?;
-        let (_, ty_res) = cx.enter_resolver(|resolver| {
-            resolver.resolve_str_path_error(DUMMY_SP, &path, TypeNS, module_id)
-        }).map_err(|_| ErrorKind::ResolutionFailure)?;
+        let (_, ty_res) = self.resolve_str_path(&path, TypeNS, scope)
+            .map_err(|_| ErrorKind::ResolutionFailure)?;
         if let Res::Err = ty_res {
             return Err(ErrorKind::ResolutionFailure);
         }
@@ -98,13 +211,16 @@ impl<'a, 'tcx> LinkCollector<'a, 'tcx> {
                 }
                 match cx.tcx.type_of(did).kind {
                     ty::Adt(def, _) if def.is_enum() => {
-                        if def.all_fields()
-                              .any(|item| item.ident.name == variant_field_name) {
-                            Ok((ty_res,
-                                Some(format!("variant.{}.field.{}",
-                                             variant_name, variant_field_name))))
-                        } else {
+                        let is_field = def.variants.iter()
+                            .filter(|variant| variant.ident.name == variant_name)
+                            .flat_map(|variant| variant.fields.iter())
+                            .any(|field| field.ident.name == variant_field_name);
+                        if !is_field {
                             Err(ErrorKind::ResolutionFailure)
+                        } else {
+                            Ok((ty_res,
+                                Some(variant_field_anchor(&variant_name.as_str(),
+                                                          &variant_field_name.as_str()))))
                         }
                     }
                     _ => Err(ErrorKind::ResolutionFailure),
@@ -124,205 +240,353 @@ impl<'a, 'tcx> LinkCollector<'a, 'tcx> {
         parent_id: Option<hir::HirId>,
         extra_fragment: &Option<String>,
     ) -> Result<(Res, Option<String>), ErrorKind> {
-        let cx = self.cx;
+        // The documentation of an inlined item is written in the scope of its original module,
+        // but the one of the re-export inlining it, which is merged into it, is not.
+        if let Some(module_id) = self.foreign_scope {
+            let scope = Scope::Foreign(module_id);
+            match self.resolve_in(path_str, ns, current_item, scope, extra_fragment) {
+                Err(ErrorKind::ResolutionFailure) => {}
+                result => return result,
+            }
+        }
 
         // In case we're in a module, try to resolve the relative path.
         if let Some(module_id) = parent_id.or(self.mod_ids.last().cloned()) {
-            let module_id = cx.tcx.hir().hir_to_node_id(module_id);
-            let result = cx.enter_resolver(|resolver| {
-                resolver.resolve_str_path_error(DUMMY_SP, &path_str, ns, module_id)
-            });
-            let result = match result {
-                Ok((_, Res::Err)) => Err(ErrorKind::ResolutionFailure),
-                _ => result.map_err(|_| ErrorKind::ResolutionFailure),
-            };
+            let scope = Scope::Local(self.cx.tcx.hir().hir_to_node_id(module_id));
+            self.resolve_in(path_str, ns, current_item, scope, extra_fragment)
+        } else {
+            debug!("attempting to resolve item without parent module: {}", path_str);
+            Err(ErrorKind::ResolutionFailure)
+        }
+    }
 
-            if let Ok((_, res)) = result {
-                let res = res.map_id(|_| panic!("unexpected node_id"));
-                // In case this is a trait item, skip the
-                // early return and try looking for the trait.
-                let value = match res {
-                    Res::Def(DefKind::Method, _) | Res::Def(DefKind::AssocConst, _) => true,
-                    Res::Def(DefKind::AssocTy, _) => false,
-                    Res::Def(DefKind::Variant, _) => {
-                        return handle_variant(cx, res, extra_fragment);
-                    }
-                    // Not a trait item; just return what we found.
-                    Res::PrimTy(..) => {
-                        if extra_fragment.is_some() {
-                            return Err(
-                                ErrorKind::AnchorFailure(
-                                    "primitive types cannot be followed by anchors"));
-                        }
-                        return Ok((res, Some(path_str.to_owned())));
-                    }
-                    _ => return Ok((res, extra_fragment.clone()))
-                };
+    /// Resolves a string as a path within a particular namespace, in the scope of a module.
+    fn resolve_in(
+        &self,
+        path_str: &str,
+        ns: Namespace,
+        current_item: &Option<String>,
+        scope: Scope,
+        extra_fragment: &Option<String>,
+    ) -> Result<(Res, Option<String>), ErrorKind> {
+        let cx = &*self.cx;
+
+        let result = self.resolve_str_path(path_str, ns, scope);
+        let result = match result {
+            Ok((_, Res::Err)) => Err(ErrorKind::ResolutionFailure),
+            _ => result.map_err(|_| ErrorKind::ResolutionFailure),
+        };
 
-                if value != (ns == ValueNS) {
-                    return Err(ErrorKind::ResolutionFailure)
+        if let Ok((_, res)) = result {
+            let res = res.map_id(|_| panic!("unexpected node_id"));
+            // In case this is a trait item, skip the
+            // early return and try looking for the trait.
+            let value = match res {
+                Res::Def(DefKind::Method, _) | Res::Def(DefKind::AssocConst, _) => true,
+                Res::Def(DefKind::AssocTy, _) => false,
+                Res::Def(DefKind::Variant, _) => {
+                    return handle_variant(cx, res, extra_fragment);
                 }
-            } else if let Some(prim) = is_primitive(path_str, ns) {
-                if extra_fragment.is_some() {
-                    return Err(
-                        ErrorKind::AnchorFailure("primitive types cannot be followed by anchors"));
+                // Not a trait item; just return what we found.
+                Res::PrimTy(..) => {
+                    if extra_fragment.is_some() {
+                        return Err(
+                            ErrorKind::AnchorFailure(
+                                "primitive types cannot be followed by anchors"));
+                    }
+                    return Ok((res, Some(path_str.to_owned())));
                 }
-                return Ok((prim, Some(path_str.to_owned())))
-            } else {
-                // If resolution failed, it may still be a method
-                // because methods are not handled by the resolver
-                // If so, bail when we're not looking for a value.
-                if ns != ValueNS {
-                    return Err(ErrorKind::ResolutionFailure)
+                _ => return Ok((res, extra_fragment.clone()))
+            };
+
+            if value != (ns == ValueNS) {
+                return Err(ErrorKind::ResolutionFailure)
+            }
+        } else if let Some(prim) = is_primitive(path_str, ns) {
+            if extra_fragment.is_some() {
+                return Err(
+                    ErrorKind::AnchorFailure("primitive types cannot be followed by anchors"));
+            }
+            return Ok((prim, Some(path_str.to_owned())))
+        } else {
+            // If resolution failed, it may still be a method
+            // because methods are not handled by the resolver
+            // If so, bail when we're not looking for a value.
+            if ns != ValueNS {
+                return Err(ErrorKind::ResolutionFailure)
+            }
+        }
+
+        // Try looking for methods and associated items.
+        let mut split = path_str.rsplitn(2, "::");
+        let item_name = split.next()
+            .map(|f| Symbol::intern(f))
+            .ok_or(ErrorKind::ResolutionFailure)?;
+        let path = split.next().map(|f| {
+            if f == "self" || f == "Self" {
+                if let Some(name) = current_item.as_ref() {
+                    return name.clone();
                 }
             }
+            f.to_owned()
+        }).ok_or(ErrorKind::ResolutionFailure)?;
 
-            // Try looking for methods and associated items.
-            let mut split = path_str.rsplitn(2, "::");
-            let item_name = split.next()
-                .map(|f| Symbol::intern(f))
-                .ok_or(ErrorKind::ResolutionFailure)?;
-            let path = split.next().map(|f| {
-                if f == "self" || f == "Self" {
-                    if let Some(name) = current_item.as_ref() {
-                        return name.clone();
+        if let Some(prim) = is_primitive(&path, TypeNS) {
+            let did = primitive_impl(cx, &path).ok_or(ErrorKind::ResolutionFailure)?;
+            return cx.tcx.associated_items(did)
+                .find(|item| item.ident.name == item_name)
+                .and_then(|item| match item.kind {
+                    ty::AssocKind::Method => Some("method"),
+                    _ => None,
+                })
+                .map(|out| (prim, Some(format!("{}#{}.{}", path, out, item_name))))
+                .ok_or(ErrorKind::ResolutionFailure);
+        }
+
+        let (_, ty_res) = self.resolve_str_path(&path, TypeNS, scope)
+            .map_err(|_| ErrorKind::ResolutionFailure)?;
+        if let Res::Err = ty_res {
+            return self.variant_field(path_str, current_item, scope);
+        }
+        let ty_res = ty_res.map_id(|_| panic!("unexpected node_id"));
+        match ty_res {
+            Res::Def(DefKind::Struct, did)
+            | Res::Def(DefKind::Union, did)
+            | Res::Def(DefKind::Enum, did)
+            | Res::Def(DefKind::TyAlias, did) => {
+                let item = cx.tcx.inherent_impls(did)
+                                 .iter()
+                                 .flat_map(|imp| cx.tcx.associated_items(*imp))
+                                 .find(|item| item.ident.name == item_name)
+                                 .or_else(|| trait_impl_item(cx, did, item_name));
+                if let Some(item) = item {
+                    let out = match item.kind {
+                        ty::AssocKind::Method if ns == ValueNS => "method",
+                        ty::AssocKind::Const if ns == ValueNS => "associatedconstant",
+                        _ => return self.variant_field(path_str, current_item, scope),
+                    };
+                    if extra_fragment.is_some() {
+                        Err(ErrorKind::AnchorFailure(
+                            if item.kind == ty::AssocKind::Method {
+                                "methods cannot be followed by anchors"
+                            } else {
+                                "associated constants cannot be followed by anchors"
+                            }))
+                    } else {
+                        Ok((ty_res, Some(format!("{}.{}", out, item_name))))
                     }
-                }
-                f.to_owned()
-            }).ok_or(ErrorKind::ResolutionFailure)?;
-
-            if let Some(prim) = is_primitive(&path, TypeNS) {
-                let did = primitive_impl(cx, &path).ok_or(ErrorKind::ResolutionFailure)?;
-                return cx.tcx.associated_items(did)
-                    .find(|item| item.ident.name == item_name)
-                    .and_then(|item| match item.kind {
-                        ty::AssocKind::Method => Some("method"),
-                        _ => None,
-                    })
-                    .map(|out| (prim, Some(format!("{}#{}.{}", path, out, item_name))))
-                    .ok_or(ErrorKind::ResolutionFailure);
-            }
-
-            let (_, ty_res) = cx.enter_resolver(|resolver| {
-                resolver.resolve_str_path_error(DUMMY_SP, &path, TypeNS, module_id)
-            }).map_err(|_| ErrorKind::ResolutionFailure)?;
-            if let Res::Err = ty_res {
-                return self.variant_field(path_str, current_item, module_id);
-            }
-            let ty_res = ty_res.map_id(|_| panic!("unexpected node_id"));
-            match ty_res {
-                Res::Def(DefKind::Struct, did)
-                | Res::Def(DefKind::Union, did)
-                | Res::Def(DefKind::Enum, did)
-                | Res::Def(DefKind::TyAlias, did) => {
-                    let item = cx.tcx.inherent_impls(did)
-                                     .iter()
-                                     .flat_map(|imp| cx.tcx.associated_items(*imp))
-                                     .find(|item| item.ident.name == item_name);
-                    if let Some(item) = item {
-                        let out = match item.kind {
-                            ty::AssocKind::Method if ns == ValueNS => "method",
-                            ty::AssocKind::Const if ns == ValueNS => "associatedconstant",
-                            _ => return self.variant_field(path_str, current_item, module_id),
-                        };
-                        if extra_fragment.is_some() {
-                            Err(ErrorKind::AnchorFailure(
-                                if item.kind == ty::AssocKind::Method {
-                                    "methods cannot be followed by anchors"
-                                } else {
-                                    "associated constants cannot be followed by anchors"
+                } else {
+                    match cx.tcx.type_of(did).kind {
+                        // Type aliases have no page for the fields and variants to be
+                        // displayed on, so links to them point to the aliased type.
+                        ty::Adt(def, _) => {
+                            let (kind, anchor) = if def.is_enum() {
+                                let variant = def.variants.iter()
+                                    .find(|variant| variant.ident.name == item_name);
+                                (DefKind::Enum, variant.map(|variant| {
+                                    namespace_anchor(ItemType::Variant,
+                                                     &variant.ident.as_str())
                                 }))
-                        } else {
-                            Ok((ty_res, Some(format!("{}.{}", out, item_name))))
-                        }
-                    } else {
-                        match cx.tcx.type_of(did).kind {
-                            ty::Adt(def, _) => {
-                                if let Some(item) = if def.is_enum() {
-                                    def.all_fields().find(|item| item.ident.name == item_name)
+                            } else {
+                                let field = def.non_enum_variant()
+                                    .fields
+                                    .iter()
+                                    .find(|field| field.ident.name == item_name);
+                                let kind = if def.is_union() {
+                                    DefKind::Union
                                 } else {
-                                    def.non_enum_variant()
-                                       .fields
-                                       .iter()
-                                       .find(|item| item.ident.name == item_name)
-                                } {
-                                    if extra_fragment.is_some() {
-                                        Err(ErrorKind::AnchorFailure(
-                                            if def.is_enum() {
-                                                "enum variants cannot be followed by anchors"
-                                            } else {
-                                                "struct fields cannot be followed by anchors"
-                                            }))
-                                    } else {
-                                        Ok((ty_res,
-                                            Some(format!("{}.{}",
-                                                         if def.is_enum() {
-                                                             "variant"
-                                                         } else {
-                                                             "structfield"
-                                                         },
-                                                         item.ident))))
-                                    }
-                                } else {
-                                    self.variant_field(path_str, current_item, module_id)
+                                    DefKind::Struct
+                                };
+                                (kind, field.map(|field| {
+                                    item_anchor(ItemType::StructField, &field.ident.as_str())
+                                }))
+                            };
+                            match anchor {
+                                Some(_) if extra_fragment.is_some() => {
+                                    Err(ErrorKind::AnchorFailure(
+                                        if def.is_enum() {
+                                            "enum variants cannot be followed by anchors"
+                                        } else {
+                                            "struct fields cannot be followed by anchors"
+                                        }))
                                 }
+                                Some(anchor) => Ok((Res::Def(kind, def.did), Some(anchor))),
+                                None => self.variant_field(path_str, current_item, scope),
                             }
-                            _ => self.variant_field(path_str, current_item, module_id),
                         }
+                        _ => self.variant_field(path_str, current_item, scope),
                     }
                 }
-                Res::Def(DefKind::Trait, did) => {
-                    let item = cx.tcx.associated_item_def_ids(did).iter()
-                                 .map(|item| cx.tcx.associated_item(*item))
-                                 .find(|item| item.ident.name == item_name);
-                    if let Some(item) = item {
-                        let kind = match item.kind {
-                            ty::AssocKind::Const if ns == ValueNS => "associatedconstant",
-                            ty::AssocKind::Type if ns == TypeNS => "associatedtype",
-                            ty::AssocKind::Method if ns == ValueNS => {
-                                if item.defaultness.has_value() {
-                                    "method"
-                                } else {
-                                    "tymethod"
-                                }
+            }
+            Res::Def(DefKind::Trait, did) => {
+                let item = cx.tcx.associated_item_def_ids(did).iter()
+                             .map(|item| cx.tcx.associated_item(*item))
+                             .find(|item| item.ident.name == item_name);
+                if let Some(item) = item {
+                    let kind = match item.kind {
+                        ty::AssocKind::Const if ns == ValueNS => "associatedconstant",
+                        ty::AssocKind::Type if ns == TypeNS => "associatedtype",
+                        ty::AssocKind::Method if ns == ValueNS => {
+                            if item.defaultness.has_value() {
+                                "method"
+                            } else {
+                                "tymethod"
                             }
-                            _ => return self.variant_field(path_str, current_item, module_id),
-                        };
-
-                        if extra_fragment.is_some() {
-                            Err(ErrorKind::AnchorFailure(
-                                if item.kind == ty::AssocKind::Const {
-                                    "associated constants cannot be followed by anchors"
-                                } else if item.kind == ty::AssocKind::Type {
-                                    "associated types cannot be followed by anchors"
-                                } else {
-                                    "methods cannot be followed by anchors"
-                                }))
-                        } else {
-                            Ok((ty_res, Some(format!("{}.{}", kind, item_name))))
                         }
+                        _ => return self.variant_field(path_str, current_item, scope),
+                    };
+
+                    if extra_fragment.is_some() {
+                        Err(ErrorKind::AnchorFailure(
+                            if item.kind == ty::AssocKind::Const {
+                                "associated constants cannot be followed by anchors"
+                            } else if item.kind == ty::AssocKind::Type {
+                                "associated types cannot be followed by anchors"
+                            } else {
+                                "methods cannot be followed by anchors"
+                            }))
                     } else {
-                        self.variant_field(path_str, current_item, module_id)
+                        Ok((ty_res, Some(format!("{}.{}", kind, item_name))))
                     }
+                } else {
+                    self.variant_field(path_str, current_item, scope)
                 }
-                _ => self.variant_field(path_str, current_item, module_id),
             }
+            _ => self.variant_field(path_str, current_item, scope),
+        }
+    }
+
+    /// Resolves `path_str` in every namespace except `ns`. Used to suggest another disambiguator
+    /// when a link does not resolve in the namespace it asked for.
+    fn resolve_elsewhere(
+        &self,
+        path_str: &str,
+        ns: Namespace,
+        current_item: &Option<String>,
+        parent_id: Option<hir::HirId>,
+        extra_fragment: &Option<String>,
+    ) -> PerNS<Option<Res>> {
+        let resolve = |other_ns: Namespace| if other_ns == ns {
+            None
         } else {
-            debug!("attempting to resolve item without parent module: {}", path_str);
-            Err(ErrorKind::ResolutionFailure)
+            self.resolve(path_str, other_ns, current_item, parent_id, extra_fragment)
+                .ok()
+                .map(|(res, _)| res)
+        };
+        PerNS {
+            type_ns: resolve(TypeNS),
+            // Constructors are picked up in the type namespace.
+            value_ns: resolve(ValueNS).filter(|res| match res {
+                Res::Def(DefKind::Ctor(..), _) | Res::SelfCtor(..) => false,
+                _ => true,
+            }),
+            macro_ns: if ns == MacroNS { None } else { macro_resolve(self.cx, path_str) },
+        }
+    }
+}
+
+impl<'a, 'tcx> LinkCollector<'a, 'tcx> {
+    /// Resolves `link` like the target of an intra-doc link, returning `None` if it does not
+    /// resolve to exactly one item.
+    fn resolve_link(
+        &self,
+        link: &str,
+        current_item: &Option<String>,
+        base_node: Option<hir::HirId>,
+    ) -> Option<(Res, Option<String>)> {
+        let (kind, path_str) = split_disambiguator(link);
+        if path_str.is_empty() || !is_path(path_str) {
+            return None;
+        }
+
+        match kind {
+            Some(MacroNS) => macro_resolve(self.cx, path_str).map(|res| (res, None)),
+            Some(ns) => self.resolve(path_str, ns, current_item, base_node, &None).ok(),
+            None => {
+                let candidates = PerNS {
+                    macro_ns: macro_resolve(self.cx, path_str).map(|res| (res, None)),
+                    type_ns: self.resolve(path_str, TypeNS, current_item, base_node, &None).ok(),
+                    value_ns: self.resolve(path_str, ValueNS, current_item, base_node, &None)
+                        .ok()
+                        // Constructors are picked up in the type namespace.
+                        .filter(|(res, _)| match res {
+                            Res::Def(DefKind::Ctor(..), _) | Res::SelfCtor(..) => false,
+                            _ => true,
+                        }),
+                };
+                let mut candidates = candidates.present_items();
+                match (candidates.next(), candidates.next()) {
+                    (Some(candidate), None) => Some(candidate),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Reports the inline links whose target resolves to the same item as their text would on
+    /// its own, like ``[`Vec`](std::vec::Vec)`` in a scope where `Vec` is.
+    fn check_redundant_links(
+        &self,
+        item: &Item,
+        dox: &str,
+        current_item: &Option<String>,
+        base_node: Option<hir::HirId>,
+    ) {
+        let hir_id = match self.cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            None => return,
+        };
+
+        for link in inline_links(dox) {
+            // Links with anchors may point to a part of the page that the text cannot name.
+            if link.dest.contains(|c| c == '/' || c == '#') || link.text.contains('#') {
+                continue;
+            }
+            let target = match self.resolve_link(&link.dest, current_item, base_node) {
+                Some(target) => target,
+                None => continue,
+            };
+            if self.resolve_link(&link.text, current_item, base_node).as_ref() != Some(&target) {
+                continue;
+            }
+
+            let shortcut = &dox[link.range.start..link.dest_range.start];
+            let sp = super::source_span_for_markdown_range(self.cx, dox, &link.range, &item.attrs);
+            let mut diag = self.cx.tcx.struct_span_lint_hir(
+                lint::builtin::REDUNDANT_EXPLICIT_LINKS,
+                hir_id,
+                sp.or_else(|| span_of_attrs(&item.attrs)).unwrap_or(item.source.span()),
+                "redundant explicit link target",
+            );
+            diag.note(&format!(
+                "`{}` resolves to the same item as the link text `{}`",
+                link.dest,
+                link.text,
+            ));
+            match sp {
+                Some(sp) => {
+                    diag.span_suggestion(
+                        sp,
+                        "remove the link target",
+                        shortcut.to_owned(),
+                        Applicability::MachineApplicable,
+                    );
+                }
+                None => {
+                    diag.help(&format!("remove the link target: `{}`", shortcut));
+                }
+            }
+            diag.emit();
         }
     }
 }
 
 impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
     fn fold_item(&mut self, mut item: Item) -> Option<Item> {
+        // Modules inlined from other crates have no node to resolve paths from, their items are
+        // resolved in the scope recorded on their documentation instead.
         let item_hir_id = if item.is_mod() {
-            if let Some(id) = self.cx.tcx.hir().as_local_hir_id(item.def_id) {
-                Some(id)
-            } else {
-                debug!("attempting to fold on a non-local item: {:?}", item);
-                return self.fold_item_recur(item);
-            }
+            self.cx.tcx.hir().as_local_hir_id(item.def_id)
         } else {
             None
         };
@@ -344,8 +608,8 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
         let current_item = match item.inner {
             ModuleItem(..) => {
                 if item.attrs.inner_docs {
-                    if item_hir_id.unwrap() != hir::CRATE_HIR_ID {
-                        item.name.clone()
+                    if item_hir_id != Some(hir::CRATE_HIR_ID) {
+                        item.name.map(|name| name.to_string())
                     } else {
                         None
                     }
@@ -364,19 +628,41 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
             }
             // we don't display docs on `extern crate` items anyway, so don't process them.
             ExternCrateItem(..) => return self.fold_item_recur(item),
-            ImportItem(Import::Simple(ref name, ..)) => Some(name.clone()),
+            ImportItem(Import::Simple(name, ..)) => Some(name.to_string()),
             MacroItem(..) => None,
-            _ => item.name.clone(),
+            _ => item.name.map(|name| name.to_string()),
         };
 
-        if item.is_mod() && item.attrs.inner_docs {
-            self.mod_ids.push(item_hir_id.unwrap());
+        if let Some(item_hir_id) = item_hir_id.filter(|_| item.attrs.inner_docs) {
+            self.mod_ids.push(item_hir_id);
         }
 
-        let cx = self.cx;
+        // The members of inlined items are written in the scope of the item itself.
+        let foreign_scope = if item.def_id.is_local() {
+            None
+        } else {
+            item.attrs.scope.or(self.foreign_scope)
+        };
+        let old_foreign_scope = mem::replace(&mut self.foreign_scope, foreign_scope);
+
         let dox = item.attrs.collapsed_doc_value().unwrap_or_else(String::new);
 
-        look_for_tests(&cx, &dox, &item, true);
+        // In order to correctly resolve intra-doc-links we need to
+        // pick a base AST node to work from.  If the documentation for
+        // this module came from an inner comment (//!) then we anchor
+        // our name resolution *inside* the module.  If, on the other
+        // hand it was an outer comment (///) then we anchor the name
+        // resolution in the parent module on the basis that the names
+        // used are more likely to be intended to be parent names.  For
+        // this, we set base_node to None for inner comments since
+        // we've already pushed this node onto the resolution stack but
+        // for outer comments we explicitly try and resolve against the
+        // parent_node first.
+        let base_node = if item.is_mod() && item.attrs.inner_docs {
+            None
+        } else {
+            parent_node
+        };
 
         for (ori_link, link_range) in markdown_links(&dox) {
             // Bail early for real links.
@@ -392,10 +678,10 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
             let link = ori_link.replace("`", "");
             let parts = link.split('#').collect::<Vec<_>>();
             let (link, extra_fragment) = if parts.len() > 2 {
-                build_diagnostic(cx, &item, &link, &dox, link_range,
-                                 "has an issue with the link anchor.",
-                                 "only one `#` is allowed in a link",
-                                 None);
+                self.report_link_error(&item, &dox, link_range, LinkError {
+                    link: &link,
+                    kind: LinkErrorKind::BadAnchor("only one `#` is allowed in a link"),
+                });
                 continue;
             } else if parts.len() == 2 {
                 if parts[0].trim().is_empty() {
@@ -407,69 +693,34 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
                 (parts[0].to_owned(), None)
             };
             let (res, fragment) = {
-                let mut kind = None;
-                let path_str = if let Some(prefix) =
-                    ["struct@", "enum@", "type@",
-                     "trait@", "union@"].iter()
-                                      .find(|p| link.starts_with(**p)) {
-                    kind = Some(TypeNS);
-                    link.trim_start_matches(prefix)
-                } else if let Some(prefix) =
-                    ["const@", "static@",
-                     "value@", "function@", "mod@",
-                     "fn@", "module@", "method@"]
-                        .iter().find(|p| link.starts_with(**p)) {
-                    kind = Some(ValueNS);
-                    link.trim_start_matches(prefix)
-                } else if link.ends_with("()") {
-                    kind = Some(ValueNS);
-                    link.trim_end_matches("()")
-                } else if link.starts_with("macro@") {
-                    kind = Some(MacroNS);
-                    link.trim_start_matches("macro@")
-                } else if link.ends_with('!') {
-                    kind = Some(MacroNS);
-                    link.trim_end_matches('!')
-                } else {
-                    &link[..]
-                }.trim();
+                let (kind, path_str) = split_disambiguator(&link);
 
-                if path_str.contains(|ch: char| !(ch.is_alphanumeric() ||
-                                                  ch == ':' || ch == '_')) {
+                if !is_path(path_str) {
                     continue;
                 }
 
-                // In order to correctly resolve intra-doc-links we need to
-                // pick a base AST node to work from.  If the documentation for
-                // this module came from an inner comment (//!) then we anchor
-                // our name resolution *inside* the module.  If, on the other
-                // hand it was an outer comment (///) then we anchor the name
-                // resolution in the parent module on the basis that the names
-                // used are more likely to be intended to be parent names.  For
-                // this, we set base_node to None for inner comments since
-                // we've already pushed this node onto the resolution stack but
-                // for outer comments we explicitly try and resolve against the
-                // parent_node first.
-                let base_node = if item.is_mod() && item.attrs.inner_docs {
-                    None
-                } else {
-                    parent_node
-                };
-
                 match kind {
                     Some(ns @ ValueNS) => {
                         match self.resolve(path_str, ns, &current_item, base_node,
                                            &extra_fragment) {
                             Ok(res) => res,
                             Err(ErrorKind::ResolutionFailure) => {
-                                resolution_failure(cx, &item, path_str, &dox, link_range);
+                                let candidates = self.resolve_elsewhere(
+                                    path_str, ns, &current_item, base_node, &extra_fragment);
+                                self.report_link_error(&item, &dox, link_range, LinkError {
+                                    link: path_str,
+                                    kind: LinkErrorKind::Unresolved(candidates),
+                                });
                                 // This could just be a normal link or a broken link
                                 // we could potentially check if something is
                                 // "intra-doc-link-like" and warn in that case.
                                 continue;
                             }
                             Err(ErrorKind::AnchorFailure(msg)) => {
-                                anchor_failure(cx, &item, &ori_link, &dox, link_range, msg);
+                                self.report_link_error(&item, &dox, link_range, LinkError {
+                                    link: &ori_link,
+                                    kind: LinkErrorKind::BadAnchor(msg),
+                                });
                                 continue
                             }
                         }
@@ -479,12 +730,20 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
                                            &extra_fragment) {
                             Ok(res) => res,
                             Err(ErrorKind::ResolutionFailure) => {
-                                resolution_failure(cx, &item, path_str, &dox, link_range);
+                                let candidates = self.resolve_elsewhere(
+                                    path_str, ns, &current_item, base_node, &extra_fragment);
+                                self.report_link_error(&item, &dox, link_range, LinkError {
+                                    link: path_str,
+                                    kind: LinkErrorKind::Unresolved(candidates),
+                                });
                                 // This could just be a normal link.
                                 continue;
                             }
                             Err(ErrorKind::AnchorFailure(msg)) => {
-                                anchor_failure(cx, &item, &ori_link, &dox, link_range, msg);
+                                self.report_link_error(&item, &dox, link_range, LinkError {
+                                    link: &ori_link,
+                                    kind: LinkErrorKind::BadAnchor(msg),
+                                });
                                 continue
                             }
                         }
@@ -492,12 +751,15 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
                     None => {
                         // Try everything!
                         let candidates = PerNS {
-                            macro_ns: macro_resolve(cx, path_str)
+                            macro_ns: macro_resolve(self.cx, path_str)
                                         .map(|res| (res, extra_fragment.clone())),
                             type_ns: match self.resolve(path_str, TypeNS, &current_item, base_node,
                                                         &extra_fragment) {
                                 Err(ErrorKind::AnchorFailure(msg)) => {
-                                    anchor_failure(cx, &item, &ori_link, &dox, link_range, msg);
+                                    self.report_link_error(&item, &dox, link_range, LinkError {
+                                        link: &ori_link,
+                                        kind: LinkErrorKind::BadAnchor(msg),
+                                    });
                                     continue;
                                 }
                                 x => x.ok(),
@@ -505,7 +767,10 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
                             value_ns: match self.resolve(path_str, ValueNS, &current_item,
                                                          base_node, &extra_fragment) {
                                 Err(ErrorKind::AnchorFailure(msg)) => {
-                                    anchor_failure(cx, &item, &ori_link, &dox, link_range, msg);
+                                    self.report_link_error(&item, &dox, link_range, LinkError {
+                                        link: &ori_link,
+                                        kind: LinkErrorKind::BadAnchor(msg),
+                                    });
                                     continue;
                                 }
                                 x => x.ok(),
@@ -528,7 +793,14 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
                         };
 
                         if candidates.is_empty() {
-                            resolution_failure(cx, &item, path_str, &dox, link_range);
+                            self.report_link_error(&item, &dox, link_range, LinkError {
+                                link: path_str,
+                                kind: LinkErrorKind::Unresolved(PerNS {
+                                    type_ns: None,
+                                    value_ns: None,
+                                    macro_ns: None,
+                                }),
+                            });
                             // this could just be a normal link
                             continue;
                         }
@@ -537,22 +809,25 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
                         if is_unambiguous {
                             candidates.present_items().next().unwrap()
                         } else {
-                            ambiguity_error(
-                                cx,
-                                &item,
-                                path_str,
-                                &dox,
-                                link_range,
-                                candidates.map(|candidate| candidate.map(|(res, _)| res)),
-                            );
+                            let candidates =
+                                candidates.map(|candidate| candidate.map(|(res, _)| res));
+                            self.report_link_error(&item, &dox, link_range, LinkError {
+                                link: path_str,
+                                kind: LinkErrorKind::Ambiguous(candidates),
+                            });
                             continue;
                         }
                     }
                     Some(MacroNS) => {
-                        if let Some(res) = macro_resolve(cx, path_str) {
+                        if let Some(res) = macro_resolve(self.cx, path_str) {
                             (res, extra_fragment)
                         } else {
-                            resolution_failure(cx, &item, path_str, &dox, link_range);
+                            let candidates = self.resolve_elsewhere(
+                                path_str, MacroNS, &current_item, base_node, &extra_fragment);
+                            self.report_link_error(&item, &dox, link_range, LinkError {
+                                link: path_str,
+                                kind: LinkErrorKind::Unresolved(candidates),
+                            });
                             continue
                         }
                     }
@@ -562,16 +837,18 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
             if let Res::PrimTy(_) = res {
                 item.attrs.links.push((ori_link, None, fragment));
             } else {
-                let id = register_res(cx, res);
+                let id = register_res(self.cx, res);
                 item.attrs.links.push((ori_link, Some(id), fragment));
             }
         }
 
-        if item.is_mod() && !item.attrs.inner_docs {
-            self.mod_ids.push(item_hir_id.unwrap());
+        self.check_redundant_links(&item, &dox, &current_item, base_node);
+
+        if let Some(item_hir_id) = item_hir_id.filter(|_| !item.attrs.inner_docs) {
+            self.mod_ids.push(item_hir_id);
         }
 
-        if item.is_mod() {
+        let ret = if item_hir_id.is_some() {
             let ret = self.fold_item_recur(item);
 
             self.mod_ids.pop();
@@ -579,7 +856,9 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
             ret
         } else {
             self.fold_item_recur(item)
-        }
+        };
+        self.foreign_scope = old_foreign_scope;
+        ret
     }
 
     // FIXME: if we can resolve intra-doc links from other crates, we can use the stock
@@ -593,6 +872,37 @@ impl<'a, 'tcx> DocFolder for LinkCollector<'a, 'tcx> {
 }
 
 /// Resolves a string as a macro.
+/// Splits the disambiguator off an intra-doc link, like the `struct@` of `struct@Vec` or the `()`
+/// of `foo()`, and returns the namespace it selects along with the path.
+fn split_disambiguator(link: &str) -> (Option<Namespace>, &str) {
+    let (kind, path_str) = if let Some(prefix) =
+        ["struct@", "enum@", "type@",
+         "trait@", "union@"].iter()
+                          .find(|p| link.starts_with(**p)) {
+        (Some(TypeNS), link.trim_start_matches(prefix))
+    } else if let Some(prefix) =
+        ["const@", "static@",
+         "value@", "function@", "mod@",
+         "fn@", "module@", "method@"]
+            .iter().find(|p| link.starts_with(**p)) {
+        (Some(ValueNS), link.trim_start_matches(prefix))
+    } else if link.ends_with("()") {
+        (Some(ValueNS), link.trim_end_matches("()"))
+    } else if link.starts_with("macro@") {
+        (Some(MacroNS), link.trim_start_matches("macro@"))
+    } else if link.ends_with('!') {
+        (Some(MacroNS), link.trim_end_matches('!'))
+    } else {
+        (None, link)
+    };
+    (kind, path_str.trim())
+}
+
+/// Whether `path_str` looks like a path, which may be resolved.
+fn is_path(path_str: &str) -> bool {
+    !path_str.contains(|ch: char| !(ch.is_alphanumeric() || ch == ':' || ch == '_'))
+}
+
 fn macro_resolve(cx: &DocContext<'_>, path_str: &str) -> Option<Res> {
     let path = ast::Path::from_ident(Ident::from_str(path_str));
     cx.enter_resolver(|resolver| {
@@ -610,117 +920,321 @@ fn macro_resolve(cx: &DocContext<'_>, path_str: &str) -> Option<Res> {
     })
 }
 
-fn build_diagnostic(
-    cx: &DocContext<'_>,
-    item: &Item,
-    path_str: &str,
-    dox: &str,
-    link_range: Option<Range<usize>>,
-    err_msg: &str,
-    short_err_msg: &str,
-    help_msg: Option<&str>,
-) {
-    let hir_id = match cx.as_local_hir_id(item.def_id) {
-        Some(hir_id) => hir_id,
-        None => {
-            // If non-local, no need to check anything.
-            return;
+/// An intra-doc link that could not be resolved.
+///
+/// Every failure is reported through `report_link_error`, so all of them produce diagnostics of
+/// the same shape: a message naming the link, a label on the link itself and, whenever the link
+/// can be fixed, suggestions carrying the corrected link text. Since suggestions are part of the
+/// JSON error format, editors and other tools can apply them without parsing the messages.
+struct LinkError<'a> {
+    /// The link as it is shown in the diagnostic message.
+    link: &'a str,
+    kind: LinkErrorKind,
+}
+
+enum LinkErrorKind {
+    /// The link resolved neither in the namespace selected by its disambiguator nor, if it had
+    /// none, in any namespace. Holds what the path resolves to in the namespaces that were not
+    /// searched.
+    Unresolved(PerNS<Option<Res>>),
+    /// The path resolved but its anchor cannot be used.
+    BadAnchor(&'static str),
+    /// The path resolved in several namespaces and the link has no disambiguator.
+    Ambiguous(PerNS<Option<Res>>),
+}
+
+impl LinkError<'_> {
+    fn message(&self) -> String {
+        match self.kind {
+            LinkErrorKind::Unresolved(_) => {
+                format!("`[{}]` cannot be resolved, ignoring it.", self.link)
+            }
+            LinkErrorKind::BadAnchor(_) => {
+                format!("`[{}]` has an issue with the link anchor.", self.link)
+            }
+            LinkErrorKind::Ambiguous(_) => ambiguity_message(self.link, &self.candidates()),
         }
-    };
-    let attrs = &item.attrs;
-    let sp = span_of_attrs(attrs).unwrap_or(item.source.span());
-
-    let mut diag = cx.tcx.struct_span_lint_hir(
-        lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE,
-        hir_id,
-        sp,
-        &format!("`[{}]` {}", path_str, err_msg),
-    );
-    if let Some(link_range) = link_range {
-        if let Some(sp) = super::source_span_for_markdown_range(cx, dox, &link_range, attrs) {
-            diag.set_span(sp);
-            diag.span_label(sp, short_err_msg);
-        } else {
-            // blah blah blah\nblah\nblah [blah] blah blah\nblah blah
-            //                       ^     ~~~~
-            //                       |     link_range
-            //                       last_new_line_offset
-            let last_new_line_offset = dox[..link_range.start].rfind('\n').map_or(0, |n| n + 1);
-            let line = dox[last_new_line_offset..].lines().next().unwrap_or("");
-
-            // Print the line containing the `link_range` and manually mark it with '^'s.
-            diag.note(&format!(
-                "the link appears in this line:\n\n{line}\n\
-                 {indicator: <before$}{indicator:^<found$}",
-                line=line,
-                indicator="",
-                before=link_range.start - last_new_line_offset,
-                found=link_range.len(),
-            ));
+    }
+
+    fn label(&self) -> &'static str {
+        match self.kind {
+            LinkErrorKind::Unresolved(_) => "cannot be resolved, ignoring",
+            LinkErrorKind::BadAnchor(msg) => msg,
+            LinkErrorKind::Ambiguous(_) => "ambiguous link",
+        }
+    }
+
+    /// The items the link may have been meant to point to, in the order they are suggested in.
+    fn candidates(&self) -> Vec<(Res, Namespace)> {
+        match self.kind {
+            LinkErrorKind::Unresolved(ref candidates) |
+            LinkErrorKind::Ambiguous(ref candidates) => {
+                [TypeNS, ValueNS, MacroNS].iter().filter_map(|&ns| {
+                    candidates[ns].map(|res| (res, ns))
+                }).collect()
+            }
+            LinkErrorKind::BadAnchor(_) => vec![],
         }
-    };
-    if let Some(help_msg) = help_msg {
-        diag.help(help_msg);
     }
-    diag.emit();
 }
 
-/// Reports a resolution failure diagnostic.
-///
-/// If we cannot find the exact source span of the resolution failure, we use the span of the
-/// documentation attributes themselves. This is a little heavy-handed, so we display the markdown
-/// line containing the failure as a note as well.
-fn resolution_failure(
-    cx: &DocContext<'_>,
-    item: &Item,
-    path_str: &str,
-    dox: &str,
-    link_range: Option<Range<usize>>,
-) {
-    build_diagnostic(cx, item, path_str, dox, link_range,
-         "cannot be resolved, ignoring it.",
-         "cannot be resolved, ignoring",
-         Some("to escape `[` and `]` characters, just add '\\' before them like `\\[` or `\\]`"));
+/// An item of the crate which a link that cannot be resolved may have been meant to point to.
+struct SimilarItem {
+    res: Res,
+    /// The path to link to the item with.
+    path: String,
+    /// Whether the item has the name the link was written with, in another scope.
+    same_name: bool,
 }
 
-fn anchor_failure(
-    cx: &DocContext<'_>,
-    item: &Item,
+impl<'a, 'tcx> LinkCollector<'a, 'tcx> {
+    /// Reports a link that could not be resolved.
+    ///
+    /// If we cannot find the exact source span of the link, we use the span of the documentation
+    /// attributes themselves. This is a little heavy-handed, so we display the markdown line
+    /// containing the link as a note as well.
+    fn report_link_error(
+        &self,
+        item: &Item,
+        dox: &str,
+        link_range: Option<Range<usize>>,
+        error: LinkError<'_>,
+    ) {
+        let cx = &*self.cx;
+        let hir_id = match cx.as_local_hir_id(item.def_id) {
+            Some(hir_id) => hir_id,
+            None => {
+                // If non-local, no need to check anything.
+                return;
+            }
+        };
+        let attrs = &item.attrs;
+        let sp = span_of_attrs(attrs).unwrap_or(item.source.span());
+
+        let mut diag = cx.tcx.struct_span_lint_hir(
+            lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE,
+            hir_id,
+            sp,
+            &error.message(),
+        );
+        let mut link_sp = None;
+        if let Some(ref link_range) = link_range {
+            if let Some(sp) = super::source_span_for_markdown_range(cx, dox, link_range, attrs) {
+                diag.set_span(sp);
+                diag.span_label(sp, error.label());
+                suggest_link_fixes(&mut diag, sp, &dox[link_range.clone()], &error);
+                link_sp = Some(sp);
+            } else {
+                // blah blah blah\nblah\nblah [blah] blah blah\nblah blah
+                //                       ^     ~~~~
+                //                       |     link_range
+                //                       last_new_line_offset
+                let last_new_line_offset =
+                    dox[..link_range.start].rfind('\n').map_or(0, |n| n + 1);
+                let line = dox[last_new_line_offset..].lines().next().unwrap_or("");
+
+                // Print the line containing the `link_range` and manually mark it with '^'s.
+                diag.note(&format!(
+                    "the link appears in this line:\n\n{line}\n\
+                     {indicator: <before$}{indicator:^<found$}",
+                    line=line,
+                    indicator="",
+                    before=link_range.start - last_new_line_offset,
+                    found=link_range.len(),
+                ));
+            }
+        }
+        if let LinkErrorKind::Unresolved(_) = error.kind {
+            if error.candidates().is_empty() {
+                let module = if item.is_mod() && item.attrs.inner_docs {
+                    item.def_id
+                } else {
+                    cx.tcx.hir().get_module_parent(hir_id)
+                };
+                let similar = self.similar_items(error.link, module);
+                let original = link_range.map_or(error.link, |link_range| &dox[link_range]);
+                suggest_similar_items(&mut diag, link_sp, original, error.link, &similar);
+            }
+            diag.help(
+                "to escape `[` and `]` characters, just add '\\' before them like `\\[` or `\\]`",
+            );
+        }
+        diag.emit();
+    }
+
+    /// Returns the items of the crate which `path_str`, a path which cannot be resolved from
+    /// `module`, may have been meant to point to: the ones with the same name in another scope or,
+    /// failing that, the ones with a similar name.
+    fn similar_items(&self, path_str: &str, module: DefId) -> Vec<SimilarItem> {
+        let mut prefix = path_str.split("::").collect::<Vec<_>>();
+        let name = prefix.pop().unwrap_or_default();
+        if name.is_empty() {
+            return vec![];
+        }
+        let full_path = |item: &IndexedItem| {
+            let mut path = vec!["crate".to_string()];
+            path.extend(item.parent_path.iter().cloned());
+            path.push(item.name.to_string());
+            path.join("::")
+        };
+
+        let same_name = self.index.iter()
+            .filter(|item| &*item.name.as_str() == name)
+            .take(MAX_SIMILAR_ITEMS)
+            .map(|item| SimilarItem { res: item.res, path: full_path(item), same_name: true })
+            .collect::<Vec<_>>();
+        if !same_name.is_empty() {
+            return same_name;
+        }
+
+        // Typos are only looked for in the name of the item, so the rest of the path has to be
+        // right. Without one, only the items of modules are considered, which are the ones most
+        // likely to be linked to with their name alone.
+        let candidates = self.index.iter().filter(|item| {
+            if prefix.is_empty() {
+                item.module.is_some()
+            } else {
+                item.parent_path.len() >= prefix.len() &&
+                    item.parent_path[item.parent_path.len() - prefix.len()..] == prefix[..]
+            }
+        }).collect::<Vec<_>>();
+        let best = match find_best_match_for_name(candidates.iter().map(|item| &item.name),
+                                                  name,
+                                                  None) {
+            Some(best) => best,
+            None => return vec![],
+        };
+        candidates.into_iter()
+            .filter(|item| item.name == best)
+            .take(MAX_SIMILAR_ITEMS)
+            .map(|item| {
+                let path = if !prefix.is_empty() {
+                    format!("{}::{}", prefix.join("::"), best)
+                } else if item.module == Some(module) {
+                    best.to_string()
+                } else {
+                    full_path(item)
+                };
+                SimilarItem { res: item.res, path, same_name: false }
+            })
+            .collect()
+    }
+}
+
+/// Suggests replacing `path_str` in `original`, the text of the link found at `sp`, with the
+/// paths of the items it may have been meant to point to.
+fn suggest_similar_items(
+    diag: &mut DiagnosticBuilder<'_>,
+    sp: Option<Span>,
+    original: &str,
     path_str: &str,
-    dox: &str,
-    link_range: Option<Range<usize>>,
-    msg: &str,
+    similar: &[SimilarItem],
 ) {
-    build_diagnostic(cx, item, path_str, dox, link_range,
-         "has an issue with the link anchor.",
-         msg,
-         None);
+    for item in similar {
+        let msg = if item.same_name {
+            format!("to link to the {} with this name, use its full path", item.res.descr())
+        } else {
+            format!("{} {} with a similar name exists", item.res.article(), item.res.descr())
+        };
+        // The path comes after the disambiguator, if any, and before the anchor.
+        let path_start = original.split('#').next().and_then(|link| link.rfind(path_str));
+        match (sp, path_start) {
+            (Some(sp), Some(start)) => {
+                let suggestion = format!("{}{}{}",
+                                         &original[..start],
+                                         item.path,
+                                         &original[start + path_str.len()..]);
+                diag.span_suggestion(sp, &msg, suggestion, Applicability::MaybeIncorrect);
+            }
+            _ => {
+                diag.help(&format!("{}: `{}`", msg, item.path));
+            }
+        }
+    }
 }
 
-fn ambiguity_error(
-    cx: &DocContext<'_>,
-    item: &Item,
-    path_str: &str,
-    dox: &str,
-    link_range: Option<Range<usize>>,
-    candidates: PerNS<Option<Res>>,
+/// Suggests replacements for `original`, the text of the link found at `sp`.
+fn suggest_link_fixes(
+    diag: &mut DiagnosticBuilder<'_>,
+    sp: Span,
+    original: &str,
+    error: &LinkError<'_>,
 ) {
-    let hir_id = match cx.as_local_hir_id(item.def_id) {
-        Some(hir_id) => hir_id,
-        None => {
-            // If non-local, no need to check anything.
-            return;
+    let in_backticks = original.starts_with('`');
+
+    if let LinkErrorKind::BadAnchor(_) = error.kind {
+        // This one is obvious from the label, only tools need to see it.
+        let mut suggestion = original.split('#').next().unwrap_or(original).to_owned();
+        if in_backticks {
+            suggestion.push('`');
         }
+        diag.tool_only_span_suggestion(
+            sp,
+            "remove the anchor",
+            suggestion,
+            Applicability::MaybeIncorrect,
+        );
+        return;
+    }
+
+    // Keep the anchor the link was written with.
+    let anchor = original.find('#').map_or("", |i| original[i..].trim_end_matches('`'));
+    let candidates = error.candidates();
+    let applicability = if candidates.len() == 1 {
+        Applicability::MachineApplicable
+    } else {
+        Applicability::MaybeIncorrect
     };
-    let attrs = &item.attrs;
-    let sp = span_of_attrs(attrs).unwrap_or(item.source.span());
+    for (res, ns) in candidates {
+        let (action, mut suggestion) = disambiguated_link(error.link, res, ns);
+        suggestion.push_str(anchor);
+        if in_backticks {
+            suggestion = format!("`{}`", suggestion);
+        }
 
+        diag.span_suggestion(
+            sp,
+            &format!("to link to the {}, {}", res.descr(), action),
+            suggestion,
+            applicability,
+        );
+    }
+}
+
+/// Returns a link to `res` that cannot be mistaken for a link to an item in another namespace,
+/// along with a description of the change.
+fn disambiguated_link(path_str: &str, res: Res, ns: Namespace) -> (&'static str, String) {
+    match res {
+        Res::Def(DefKind::Method, _) | Res::Def(DefKind::Fn, _) => {
+            ("add parentheses", format!("{}()", path_str))
+        }
+        Res::Def(DefKind::Macro(..), _) => {
+            ("add an exclamation mark", format!("{}!", path_str))
+        }
+        _ => {
+            let type_ = match (res, ns) {
+                (Res::Def(DefKind::Const, _), _) => "const",
+                (Res::Def(DefKind::Static, _), _) => "static",
+                (Res::Def(DefKind::Struct, _), _) => "struct",
+                (Res::Def(DefKind::Enum, _), _) => "enum",
+                (Res::Def(DefKind::Union, _), _) => "union",
+                (Res::Def(DefKind::Trait, _), _) => "trait",
+                (Res::Def(DefKind::Mod, _), _) => "module",
+                (_, TypeNS) => "type",
+                (_, ValueNS) => "value",
+                (_, MacroNS) => "macro",
+            };
+
+            // FIXME: if this is an implied shortcut link, it's bad style to suggest `@`
+            ("prefix with the item type", format!("{}@{}", type_, path_str))
+        }
+    }
+}
+
+fn ambiguity_message(path_str: &str, candidates: &[(Res, Namespace)]) -> String {
     let mut msg = format!("`{}` is ", path_str);
 
-    let candidates = [TypeNS, ValueNS, MacroNS].iter().filter_map(|&ns| {
-        candidates[ns].map(|res| (res, ns))
-    }).collect::<Vec<_>>();
-    match candidates.as_slice() {
+    match candidates {
         [(first_def, _), (second_def, _)] => {
             msg += &format!(
                 "both {} {} and {} {}",
@@ -742,77 +1256,7 @@ fn ambiguity_error(
         }
     }
 
-    let mut diag = cx.tcx.struct_span_lint_hir(
-        lint::builtin::INTRA_DOC_LINK_RESOLUTION_FAILURE,
-        hir_id,
-        sp,
-        &msg,
-    );
-
-    if let Some(link_range) = link_range {
-        if let Some(sp) = super::source_span_for_markdown_range(cx, dox, &link_range, attrs) {
-            diag.set_span(sp);
-            diag.span_label(sp, "ambiguous link");
-
-            for (res, ns) in candidates {
-                let (action, mut suggestion) = match res {
-                    Res::Def(DefKind::Method, _) | Res::Def(DefKind::Fn, _) => {
-                        ("add parentheses", format!("{}()", path_str))
-                    }
-                    Res::Def(DefKind::Macro(..), _) => {
-                        ("add an exclamation mark", format!("{}!", path_str))
-                    }
-                    _ => {
-                        let type_ = match (res, ns) {
-                            (Res::Def(DefKind::Const, _), _) => "const",
-                            (Res::Def(DefKind::Static, _), _) => "static",
-                            (Res::Def(DefKind::Struct, _), _) => "struct",
-                            (Res::Def(DefKind::Enum, _), _) => "enum",
-                            (Res::Def(DefKind::Union, _), _) => "union",
-                            (Res::Def(DefKind::Trait, _), _) => "trait",
-                            (Res::Def(DefKind::Mod, _), _) => "module",
-                            (_, TypeNS) => "type",
-                            (_, ValueNS) => "value",
-                            (_, MacroNS) => "macro",
-                        };
-
-                        // FIXME: if this is an implied shortcut link, it's bad style to suggest `@`
-                        ("prefix with the item type", format!("{}@{}", type_, path_str))
-                    }
-                };
-
-                if dox.bytes().nth(link_range.start) == Some(b'`') {
-                    suggestion = format!("`{}`", suggestion);
-                }
-
-                diag.span_suggestion(
-                    sp,
-                    &format!("to link to the {}, {}", res.descr(), action),
-                    suggestion,
-                    Applicability::MaybeIncorrect,
-                );
-            }
-        } else {
-            // blah blah blah\nblah\nblah [blah] blah blah\nblah blah
-            //                       ^     ~~~~
-            //                       |     link_range
-            //                       last_new_line_offset
-            let last_new_line_offset = dox[..link_range.start].rfind('\n').map_or(0, |n| n + 1);
-            let line = dox[last_new_line_offset..].lines().next().unwrap_or("");
-
-            // Print the line containing the `link_range` and manually mark it with '^'s.
-            diag.note(&format!(
-                "the link appears in this line:\n\n{line}\n\
-                 {indicator: <before$}{indicator:^<found$}",
-                line=line,
-                indicator="",
-                before=link_range.start - last_new_line_offset,
-                found=link_range.len(),
-            ));
-        }
-    }
-
-    diag.emit();
+    msg
 }
 
 /// Given an enum variant's res, return the res of its enum and the associated fragment.
@@ -833,7 +1277,7 @@ fn handle_variant(
     };
     let parent_def = Res::Def(DefKind::Enum, parent);
     let variant = cx.tcx.expect_variant_res(res);
-    Ok((parent_def, Some(format!("{}.v", variant.ident.name))))
+    Ok((parent_def, Some(namespace_anchor(ItemType::Variant, &variant.ident.as_str()))))
 }
 
 const PRIMITIVES: &[(&str, Res)] = &[
@@ -864,6 +1308,27 @@ fn is_primitive(path_str: &str, ns: Namespace) -> Option<Res> {
     }
 }
 
+/// Looks for an associated item named `item_name` in the traits which the type `did` implements,
+/// whose impls display it on the page of the type. Blanket impls are not taken into account, and
+/// `None` is returned when several traits have an item with that name.
+fn trait_impl_item(cx: &DocContext<'_>, did: DefId, item_name: Symbol) -> Option<ty::AssocItem> {
+    let ty = cx.tcx.type_of(did);
+    let adt = ty.ty_adt_def()?.did;
+    let mut items = cx.tcx.all_traits(LOCAL_CRATE).iter().filter_map(|&trait_did| {
+        let item = cx.tcx.associated_items(trait_did).find(|item| item.ident.name == item_name)?;
+        let mut implemented = false;
+        cx.tcx.for_each_relevant_impl(trait_did, ty, |impl_did| {
+            let self_ty = cx.tcx.type_of(impl_did);
+            implemented |= self_ty.ty_adt_def().map(|def| def.did) == Some(adt);
+        });
+        if implemented { Some(item) } else { None }
+    });
+    match (items.next(), items.next()) {
+        (Some(item), None) => Some(item),
+        _ => None,
+    }
+}
+
 fn primitive_impl(cx: &DocContext<'_>, path_str: &str) -> Option<DefId> {
     let tcx = cx.tcx;
     match path_str {
@@ -13,9 +13,10 @@ pub const COLLECT_TRAIT_IMPLS: Pass = Pass {
     description: "retrieves trait impls for items in the crate",
 };
 
-pub fn collect_trait_impls(krate: Crate, cx: &DocContext<'_>) -> Crate {
+pub fn collect_trait_impls(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
     let mut synth = SyntheticImplCollector::new(cx);
     let mut krate = synth.fold_crate(krate);
+    let synth_impls = synth.impls;
 
     let prims: FxHashSet<PrimitiveType> =
         krate.primitives.iter().map(|p| p.1).collect();
@@ -28,10 +29,11 @@ pub fn collect_trait_impls(krate: Crate, cx: &DocContext<'_>) -> Crate {
 
     let mut new_items = Vec::new();
 
-    for &cnum in cx.tcx.crates().iter() {
-        for &did in cx.tcx.all_trait_implementations(cnum).iter() {
-            inline::build_impl(cx, did, None, &mut new_items);
-        }
+    // Only the impls mentioning a documented item can survive the `BadImplStripper` below, so
+    // there's no point in inlining the impls of every dependency up front.
+    inline::build_unkeyed_external_impls(cx, &mut new_items);
+    for &did in &crate_items {
+        inline::build_external_impls_for(cx, did, &mut new_items);
     }
 
     // Also try to inline primitive impls from other crates.
@@ -73,9 +75,8 @@ pub fn collect_trait_impls(krate: Crate, cx: &DocContext<'_>) -> Crate {
             if !cx.tcx.get_attrs(def_id).lists(sym::doc).has_word(sym::hidden) {
                 let self_ty = cx.tcx.type_of(def_id);
                 let impls = get_auto_trait_and_blanket_impls(cx, self_ty, def_id);
-                let mut renderinfo = cx.renderinfo.borrow_mut();
 
-                new_items.extend(impls.filter(|i| renderinfo.inlined.insert(i.def_id)));
+                new_items.extend(impls.filter(|i| cx.renderinfo.inlined.insert(i.def_id)));
             }
         }
     }
@@ -85,21 +86,34 @@ pub fn collect_trait_impls(krate: Crate, cx: &DocContext<'_>) -> Crate {
         items: crate_items,
     };
 
-    // scan through included items ahead of time to splice in Deref targets to the "valid" sets
-    for it in &new_items {
-        if let ImplItem(Impl { ref for_, ref trait_, ref items, .. }) = it.inner {
-            if cleaner.keep_item(for_) && trait_.def_id() == cx.tcx.lang_items().deref_trait() {
-                let target = items.iter().filter_map(|item| {
-                    match item.inner {
-                        TypedefItem(ref t, true) => Some(&t.type_),
-                        _ => None,
-                    }
-                }).next().expect("Deref impl without Target type");
-
-                if let Some(prim) = target.primitive_type() {
-                    cleaner.prims.insert(prim);
-                } else if let Some(did) = target.def_id() {
-                    cleaner.items.insert(did);
+    // scan through included items ahead of time to splice in Deref targets to the "valid" sets,
+    // inlining the impls of the newly valid items as we go since they may contain more Deref impls
+    let mut scanned = 0;
+    while scanned < new_items.len() {
+        let mut targets = Vec::new();
+        for it in &new_items[scanned..] {
+            if let ImplItem(Impl { ref for_, ref trait_, ref items, .. }) = it.inner {
+                if cleaner.keep_item(for_) &&
+                    trait_.def_id() == cx.tcx.lang_items().deref_trait()
+                {
+                    let target = items.iter().filter_map(|item| {
+                        match item.inner {
+                            TypedefItem(ref t, true) => Some(&t.type_),
+                            _ => None,
+                        }
+                    }).next().expect("Deref impl without Target type");
+                    targets.push(target.clone());
+                }
+            }
+        }
+        scanned = new_items.len();
+
+        for target in targets {
+            if let Some(prim) = target.primitive_type() {
+                cleaner.prims.insert(prim);
+            } else if let Some(did) = target.def_id() {
+                if cleaner.items.insert(did) {
+                    inline::build_external_impls_for(cx, did, &mut new_items);
                 }
             }
         }
@@ -126,7 +140,7 @@ pub fn collect_trait_impls(krate: Crate, cx: &DocContext<'_>) -> Crate {
 
     if let Some(ref mut it) = krate.module {
         if let ModuleItem(Module { ref mut items, .. }) = it.inner {
-            items.extend(synth.impls);
+            items.extend(synth_impls);
             items.extend(new_items);
         } else {
             panic!("collect-trait-impls can't run");
@@ -139,12 +153,12 @@ pub fn collect_trait_impls(krate: Crate, cx: &DocContext<'_>) -> Crate {
 }
 
 struct SyntheticImplCollector<'a, 'tcx> {
-    cx: &'a DocContext<'tcx>,
+    cx: &'a mut DocContext<'tcx>,
     impls: Vec<Item>,
 }
 
 impl<'a, 'tcx> SyntheticImplCollector<'a, 'tcx> {
-    fn new(cx: &'a DocContext<'tcx>) -> Self {
+    fn new(cx: &'a mut DocContext<'tcx>) -> Self {
         SyntheticImplCollector {
             cx,
             impls: Vec::new(),
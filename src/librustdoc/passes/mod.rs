@@ -21,17 +21,23 @@ mod strip_hidden;
 pub use self::strip_hidden::STRIP_HIDDEN;
 
 mod strip_private;
-pub use self::strip_private::STRIP_PRIVATE;
+pub use self::strip_private::{PrivatePaths, STRIP_PRIVATE};
 
 mod strip_priv_imports;
 pub use self::strip_priv_imports::STRIP_PRIV_IMPORTS;
 
+mod strip_masked_impls;
+pub use self::strip_masked_impls::STRIP_MASKED_IMPLS;
+
 mod unindent_comments;
 pub use self::unindent_comments::UNINDENT_COMMENTS;
 
 mod propagate_doc_cfg;
 pub use self::propagate_doc_cfg::PROPAGATE_DOC_CFG;
 
+mod propagate_stability;
+pub use self::propagate_stability::PROPAGATE_STABILITY;
+
 mod collect_intra_doc_links;
 pub use self::collect_intra_doc_links::COLLECT_INTRA_DOC_LINKS;
 
@@ -44,6 +50,21 @@ pub use self::collect_trait_impls::COLLECT_TRAIT_IMPLS;
 mod check_code_block_syntax;
 pub use self::check_code_block_syntax::CHECK_CODE_BLOCK_SYNTAX;
 
+mod check_doc_code_examples;
+pub use self::check_doc_code_examples::CHECK_DOC_CODE_EXAMPLES;
+
+mod check_hidden_types;
+pub use self::check_hidden_types::CHECK_HIDDEN_TYPES;
+
+mod check_unlinked_references;
+pub use self::check_unlinked_references::CHECK_UNLINKED_REFERENCES;
+
+mod check_doc_cfg;
+pub use self::check_doc_cfg::CHECK_DOC_CFG;
+
+mod check_link_fragments;
+pub use self::check_link_fragments::CHECK_LINK_FRAGMENTS;
+
 mod calculate_doc_coverage;
 pub use self::calculate_doc_coverage::CALCULATE_DOC_COVERAGE;
 
@@ -53,7 +74,7 @@ pub use self::calculate_doc_coverage::CALCULATE_DOC_COVERAGE;
 #[derive(Copy, Clone)]
 pub struct Pass {
     pub name: &'static str,
-    pub pass: fn(clean::Crate, &DocContext<'_>) -> clean::Crate,
+    pub pass: fn(clean::Crate, &mut DocContext<'_>) -> clean::Crate,
     pub description: &'static str,
 }
 
@@ -67,15 +88,23 @@ pub const PASSES: &[Pass] = &[
     STRIP_PRIVATE,
     STRIP_PRIV_IMPORTS,
     PROPAGATE_DOC_CFG,
+    PROPAGATE_STABILITY,
     COLLECT_INTRA_DOC_LINKS,
     CHECK_CODE_BLOCK_SYNTAX,
+    CHECK_DOC_CODE_EXAMPLES,
+    CHECK_HIDDEN_TYPES,
+    CHECK_UNLINKED_REFERENCES,
+    CHECK_DOC_CFG,
+    CHECK_LINK_FRAGMENTS,
     COLLECT_TRAIT_IMPLS,
+    STRIP_MASKED_IMPLS,
     CALCULATE_DOC_COVERAGE,
 ];
 
 /// The list of passes run by default.
 pub const DEFAULT_PASSES: &[Pass] = &[
     COLLECT_TRAIT_IMPLS,
+    STRIP_MASKED_IMPLS,
     COLLAPSE_DOCS,
     UNINDENT_COMMENTS,
     CHECK_PRIVATE_ITEMS_DOC_TESTS,
@@ -83,19 +112,31 @@ pub const DEFAULT_PASSES: &[Pass] = &[
     STRIP_PRIVATE,
     COLLECT_INTRA_DOC_LINKS,
     CHECK_CODE_BLOCK_SYNTAX,
+    CHECK_DOC_CODE_EXAMPLES,
+    CHECK_HIDDEN_TYPES,
+    CHECK_UNLINKED_REFERENCES,
+    CHECK_DOC_CFG,
+    CHECK_LINK_FRAGMENTS,
     PROPAGATE_DOC_CFG,
+    PROPAGATE_STABILITY,
 ];
 
 /// The list of default passes run with `--document-private-items` is passed to rustdoc.
 pub const DEFAULT_PRIVATE_PASSES: &[Pass] = &[
     COLLECT_TRAIT_IMPLS,
+    STRIP_MASKED_IMPLS,
     COLLAPSE_DOCS,
     UNINDENT_COMMENTS,
     CHECK_PRIVATE_ITEMS_DOC_TESTS,
     STRIP_PRIV_IMPORTS,
     COLLECT_INTRA_DOC_LINKS,
     CHECK_CODE_BLOCK_SYNTAX,
+    CHECK_DOC_CODE_EXAMPLES,
+    CHECK_UNLINKED_REFERENCES,
+    CHECK_DOC_CFG,
+    CHECK_LINK_FRAGMENTS,
     PROPAGATE_DOC_CFG,
+    PROPAGATE_STABILITY,
 ];
 
 /// The list of default passes run when `--doc-coverage` is passed to rustdoc.
@@ -144,10 +185,36 @@ struct Stripper<'a> {
     retained: &'a mut DefIdSet,
     access_levels: &'a AccessLevels<DefId>,
     update_retained: bool,
+    /// The private modules whose items are kept, with `--document-private-items=PATHS`.
+    private_paths: &'a PrivatePaths,
+    /// The path of the module being folded, from the crate root.
+    path: Vec<String>,
+}
+
+impl<'a> Stripper<'a> {
+    /// Returns whether the items of the module being folded are kept even if private. The path
+    /// starts with the crate root, which the private paths leave out.
+    fn in_private_paths(&self) -> bool {
+        self.path.len() > 1 && self.private_paths.contains(&self.path[1..])
+    }
 }
 
 impl<'a> DocFolder for Stripper<'a> {
     fn fold_item(&mut self, i: Item) -> Option<Item> {
+        // The path is kept up to date on the way down, for the items to be matched against the
+        // private paths.
+        if !i.is_mod() {
+            return self.strip_item(i);
+        }
+        self.path.push(i.name.map(|name| name.to_string()).unwrap_or_default());
+        let ret = self.strip_item(i);
+        self.path.pop();
+        ret
+    }
+}
+
+impl<'a> Stripper<'a> {
+    fn strip_item(&mut self, i: Item) -> Option<Item> {
         match i.inner {
             clean::StrippedItem(..) => {
                 // We need to recurse into stripped modules to strip things
@@ -177,7 +244,7 @@ impl<'a> DocFolder for Stripper<'a> {
             | clean::TraitAliasItem(..)
             | clean::ForeignTypeItem => {
                 if i.def_id.is_local() {
-                    if !self.access_levels.is_exported(i.def_id) {
+                    if !self.access_levels.is_exported(i.def_id) && !self.in_private_paths() {
                         debug!("Stripper: stripping {:?} {:?}", i.type_(), i.name);
                         return None;
                     }
@@ -185,13 +252,14 @@ impl<'a> DocFolder for Stripper<'a> {
             }
 
             clean::StructFieldItem(..) => {
-                if i.visibility != clean::Public {
+                if i.visibility != clean::Public && !self.in_private_paths() {
                     return StripItem(i).strip();
                 }
             }
 
             clean::ModuleItem(..) => {
-                if i.def_id.is_local() && i.visibility != clean::Public {
+                let kept = self.private_paths.leads_to(&self.path[1..]);
+                if i.def_id.is_local() && i.visibility != clean::Public && !kept {
                     debug!("Stripper: stripping module {:?}", i.name);
                     let old = mem::replace(&mut self.update_retained, false);
                     let ret = StripItem(self.fold_item_recur(i).unwrap()).strip();
@@ -348,7 +416,7 @@ pub fn look_for_tests<'tcx>(
         diag.emit();
     } else if check_missing_code == false &&
               tests.found_tests > 0 &&
-              !cx.renderinfo.borrow().access_levels.is_public(item.def_id) {
+              !cx.renderinfo.access_levels.is_public(item.def_id) {
         let mut diag = cx.tcx.struct_span_lint_hir(
             lint::builtin::PRIVATE_DOC_TESTS,
             hir_id,
@@ -21,7 +21,7 @@ impl<'a, 'tcx> PrivateItemDocTestLinter<'a, 'tcx> {
     }
 }
 
-pub fn check_private_items_doc_tests(krate: Crate, cx: &DocContext<'_>) -> Crate {
+pub fn check_private_items_doc_tests(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
     let mut coll = PrivateItemDocTestLinter::new(cx);
 
     coll.fold_crate(krate)
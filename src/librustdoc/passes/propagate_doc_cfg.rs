@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
-use crate::clean::{Crate, Item};
+use rustc_data_structures::fx::FxHashSet;
+use syntax::symbol::sym;
+
+use crate::clean::{AttributesExt, Crate, Item};
 use crate::clean::cfg::Cfg;
 use crate::core::DocContext;
 use crate::fold::DocFolder;
@@ -12,18 +15,49 @@ pub const PROPAGATE_DOC_CFG: Pass = Pass {
     description: "propagates `#[doc(cfg(...))]` to child items",
 };
 
-pub fn propagate_doc_cfg(cr: Crate, _: &DocContext<'_>) -> Crate {
-    CfgPropagator { parent_cfg: None }.fold_crate(cr)
+pub fn propagate_doc_cfg(cr: Crate, cx: &mut DocContext<'_>) -> Crate {
+    let auto_cfg = cr.module.as_ref().map_or(false, |m| m.attrs.has_doc_flag(sym::auto_cfg));
+
+    let mut hidden_cfg = FxHashSet::default();
+    if let Some(ref m) = cr.module {
+        for attr in m.attrs.lists(sym::doc).filter(|a| a.check_name(sym::cfg_hide)) {
+            for nested in attr.meta_item_list().unwrap_or(&[]) {
+                match Cfg::parse_nested(nested) {
+                    Ok(cfg) => { hidden_cfg.insert(cfg); }
+                    Err(e) => cx.sess().span_err(e.span, e.msg),
+                }
+            }
+        }
+    }
+
+    CfgPropagator { parent_cfg: None, auto_cfg, hidden_cfg }.fold_crate(cr)
 }
 
 struct CfgPropagator {
     parent_cfg: Option<Arc<Cfg>>,
+    /// Whether plain `#[cfg(...)]` attributes are treated like `#[doc(cfg(...))]`.
+    auto_cfg: bool,
+    /// The predicates listed in `#![doc(cfg_hide(...))]`, which are left out of the displayed
+    /// configuration of every item.
+    hidden_cfg: FxHashSet<Cfg>,
 }
 
 impl DocFolder for CfgPropagator {
     fn fold_item(&mut self, mut item: Item) -> Option<Item> {
         let old_parent_cfg = self.parent_cfg.clone();
 
+        if self.auto_cfg {
+            if let Some(auto_cfg) = item.attrs.auto_cfg.clone() {
+                item.attrs.cfg = Some(match item.attrs.cfg.take() {
+                    None => auto_cfg,
+                    Some(mut cfg) => {
+                        *Arc::make_mut(&mut cfg) &= Cfg::clone(&auto_cfg);
+                        cfg
+                    }
+                });
+            }
+        }
+
         let new_cfg = match (self.parent_cfg.take(), item.attrs.cfg.take()) {
             (None, None) => None,
             (Some(rc), None) | (None, Some(rc)) => Some(rc),
@@ -34,7 +68,12 @@ impl DocFolder for CfgPropagator {
             }
         };
         self.parent_cfg = new_cfg.clone();
-        item.attrs.cfg = new_cfg;
+        item.attrs.cfg = match new_cfg {
+            Some(cfg) if !self.hidden_cfg.is_empty() => {
+                cfg.strip_hidden(&self.hidden_cfg).map(Arc::new)
+            }
+            cfg => cfg,
+        };
 
         let result = self.fold_item_recur(item);
         self.parent_cfg = old_parent_cfg;
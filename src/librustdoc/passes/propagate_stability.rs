@@ -0,0 +1,107 @@
+use std::mem;
+
+use rustc::hir::def_id::DefId;
+use rustc::middle::stability;
+use rustc::ty;
+
+use crate::clean::{self, Clean, Crate, GetDefId, Item, Stability};
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::passes::Pass;
+
+pub const PROPAGATE_STABILITY: Pass = Pass {
+    name: "propagate-stability",
+    pass: propagate_stability,
+    description: "gives trait items and the items of trait impls the stability of their trait",
+};
+
+pub fn propagate_stability(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
+    StabilityPropagator { cx, parent: None }.fold_crate(krate)
+}
+
+/// The trait whose items are being visited.
+#[derive(Clone, Copy)]
+struct ParentTrait {
+    did: DefId,
+    /// Whether the items belong to an impl of the trait rather than to the trait itself.
+    in_impl: bool,
+}
+
+struct StabilityPropagator<'a, 'tcx> {
+    cx: &'a mut DocContext<'tcx>,
+    parent: Option<ParentTrait>,
+}
+
+impl<'a, 'tcx> StabilityPropagator<'a, 'tcx> {
+    /// Returns the stability of the item of the trait `trait_did` which `item` is or implements,
+    /// falling back to the stability of the trait itself.
+    fn trait_item_stability(&mut self, trait_did: DefId, item: &Item) -> Option<Stability> {
+        let tcx = self.cx.tcx;
+        let name = item.name?;
+        let is_type = match item.inner {
+            clean::TypedefItem(..) | clean::AssocTypeItem(..) => true,
+            _ => false,
+        };
+        let trait_item = tcx.associated_items(trait_did).find(|assoc| {
+            assoc.ident.name == name && (assoc.kind == ty::AssocKind::Type) == is_type
+        });
+        let stab = trait_item
+            .and_then(|assoc| tcx.lookup_stability(assoc.def_id))
+            .or_else(|| tcx.lookup_stability(trait_did))?;
+
+        let mut stab = stab.clean(self.cx);
+        stab.inherited_from = Some(tcx.item_name(trait_did).to_string());
+        Some(stab)
+    }
+
+    /// Gives `item` the stability of its trait item if it has none of its own. The items of an
+    /// impl cannot be more stable than the trait items they implement either.
+    fn inherit(&mut self, parent: ParentTrait, item: &mut Item) {
+        let overridable = match item.stability {
+            None => true,
+            Some(ref own) => parent.in_impl && own.level == stability::Stable,
+        };
+        if !overridable {
+            return;
+        }
+        if let Some(stab) = self.trait_item_stability(parent.did, item) {
+            if item.stability.is_none() || stab.level == stability::Unstable {
+                item.stability = Some(stab);
+            }
+        }
+    }
+}
+
+impl<'a, 'tcx> DocFolder for StabilityPropagator<'a, 'tcx> {
+    fn fold_item(&mut self, mut item: Item) -> Option<Item> {
+        let parent = match item.inner {
+            clean::TraitItem(..) => Some(ParentTrait { did: item.def_id, in_impl: false }),
+            clean::ImplItem(ref impl_) => impl_.trait_.as_ref()
+                .and_then(|t| t.def_id())
+                .map(|did| ParentTrait { did, in_impl: true }),
+            _ => {
+                if let Some(parent) = self.parent {
+                    self.inherit(parent, &mut item);
+                }
+                return self.fold_item_recur(item);
+            }
+        };
+
+        let old_parent = mem::replace(&mut self.parent, parent);
+        let result = self.fold_item_recur(item);
+        self.parent = old_parent;
+        result
+    }
+
+    fn fold_crate(&mut self, mut krate: Crate) -> Crate {
+        krate.module = krate.module.take().and_then(|module| self.fold_item(module));
+
+        for (&did, trait_) in krate.external_traits.iter_mut() {
+            let parent = ParentTrait { did, in_impl: false };
+            for item in &mut trait_.items {
+                self.inherit(parent, item);
+            }
+        }
+        krate
+    }
+}
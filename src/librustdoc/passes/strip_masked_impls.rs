@@ -0,0 +1,90 @@
+use rustc::hir::def_id::CrateNum;
+use rustc::util::nodemap::{FxHashMap, FxHashSet};
+
+use crate::clean::{self, Crate, GetDefId, Item};
+use crate::core::DocContext;
+use crate::fold::DocFolder;
+use crate::passes::Pass;
+
+pub const STRIP_MASKED_IMPLS: Pass = Pass {
+    name: "strip-masked-impls",
+    pass: strip_masked_impls,
+    description: "strips impls involving the crates masked with `#[doc(masked)]`",
+};
+
+pub fn strip_masked_impls(krate: Crate, cx: &mut DocContext<'_>) -> Crate {
+    let masked_crates = krate.masked_crates.clone();
+    let mut stripper = MaskedImplStripper {
+        masked_crates: &masked_crates,
+        counts: Default::default(),
+    };
+    let krate = stripper.fold_crate(krate);
+
+    let mut reported = krate.reported_masked_crates.iter().collect::<Vec<_>>();
+    reported.sort();
+    for cnum in reported {
+        let counts = stripper.counts.get(cnum).cloned().unwrap_or_default();
+        cx.sess().note_without_error(&format!(
+            "masked {} impl{} involving crate `{}`: {} defined in it, {} of its traits, \
+             {} for its types",
+            counts.total(),
+            if counts.total() == 1 { "" } else { "s" },
+            cx.tcx.crate_name(*cnum),
+            counts.defined,
+            counts.traits,
+            counts.types,
+        ));
+    }
+
+    krate
+}
+
+/// How many impls were masked for each of the reasons an impl can involve a crate.
+#[derive(Clone, Copy, Default)]
+struct MaskedCounts {
+    /// Impls defined in the masked crate.
+    defined: usize,
+    /// Impls of a trait of the masked crate.
+    traits: usize,
+    /// Impls for a type of the masked crate.
+    types: usize,
+}
+
+impl MaskedCounts {
+    fn total(&self) -> usize {
+        self.defined + self.traits + self.types
+    }
+}
+
+struct MaskedImplStripper<'a> {
+    masked_crates: &'a FxHashSet<CrateNum>,
+    counts: FxHashMap<CrateNum, MaskedCounts>,
+}
+
+impl<'a> DocFolder for MaskedImplStripper<'a> {
+    fn fold_item(&mut self, item: Item) -> Option<Item> {
+        if let clean::ImplItem(ref i) = item.inner {
+            let masked_crates = self.masked_crates;
+            let masked = |krate: Option<CrateNum>| {
+                krate.filter(|krate| masked_crates.contains(krate))
+            };
+            // An impl involving several masked crates is counted once, for the first reason
+            // which applies.
+            let reason = if let Some(krate) = masked(Some(item.def_id.krate)) {
+                Some((krate, &mut self.counts.entry(krate).or_default().defined))
+            } else if let Some(krate) = masked(i.trait_.def_id().map(|did| did.krate)) {
+                Some((krate, &mut self.counts.entry(krate).or_default().traits))
+            } else if let Some(krate) = masked(i.for_.def_id().map(|did| did.krate)) {
+                Some((krate, &mut self.counts.entry(krate).or_default().types))
+            } else {
+                None
+            };
+            if let Some((krate, count)) = reason {
+                debug!("strip-masked-impls: masking impl {:?} involving {:?}", item.def_id, krate);
+                *count += 1;
+                return None;
+            }
+        }
+        self.fold_item_recur(item)
+    }
+}
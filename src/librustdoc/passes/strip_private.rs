@@ -5,6 +5,9 @@ use crate::fold::{DocFolder};
 use crate::core::DocContext;
 use crate::passes::{ImplStripper, ImportStripper, Stripper, Pass};
 
+#[cfg(test)]
+mod tests;
+
 pub const STRIP_PRIVATE: Pass = Pass {
     name: "strip-private",
     pass: strip_private,
@@ -14,10 +17,10 @@ pub const STRIP_PRIVATE: Pass = Pass {
 
 /// Strip private items from the point of view of a crate or externally from a
 /// crate, specified by the `xcrate` flag.
-pub fn strip_private(mut krate: clean::Crate, cx: &DocContext<'_>) -> clean::Crate {
+pub fn strip_private(mut krate: clean::Crate, cx: &mut DocContext<'_>) -> clean::Crate {
     // This stripper collects all *retained* nodes.
     let mut retained = DefIdSet::default();
-    let access_levels = cx.renderinfo.borrow().access_levels.clone();
+    let access_levels = cx.renderinfo.access_levels.clone();
 
     // strip all private items
     {
@@ -25,6 +28,8 @@ pub fn strip_private(mut krate: clean::Crate, cx: &DocContext<'_>) -> clean::Cra
             retained: &mut retained,
             access_levels: &access_levels,
             update_retained: true,
+            private_paths: &cx.document_private_paths,
+            path: vec![],
         };
         krate = ImportStripper.fold_crate(stripper.fold_crate(krate));
     }
@@ -33,3 +38,45 @@ pub fn strip_private(mut krate: clean::Crate, cx: &DocContext<'_>) -> clean::Cra
     let mut stripper = ImplStripper { retained: &retained };
     stripper.fold_crate(krate)
 }
+
+/// The private modules documented with `--document-private-items=PATHS`, whose items are kept
+/// when the other private items are stripped.
+#[derive(Clone, Debug, Default)]
+pub struct PrivatePaths {
+    /// The paths of the modules from the crate root.
+    paths: Vec<Vec<String>>,
+}
+
+impl PrivatePaths {
+    /// Parses a comma-separated list of paths from the crate root, like `crate::internal,ffi`.
+    pub fn parse(list: &str) -> Result<PrivatePaths, String> {
+        let mut paths = vec![];
+        for path in list.split(',').map(str::trim).filter(|path| !path.is_empty()) {
+            let mut segments = path.split("::").map(str::trim).collect::<Vec<_>>();
+            if segments.first() == Some(&"crate") {
+                segments.remove(0);
+            }
+            if segments.is_empty() || segments.iter().any(|segment| segment.is_empty()) {
+                return Err(format!("invalid module path: `{}`", path));
+            }
+            paths.push(segments.into_iter().map(str::to_owned).collect());
+        }
+        Ok(PrivatePaths { paths })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Returns whether the module at `path` from the crate root is one of the documented modules
+    /// or is in one of them.
+    pub fn contains(&self, path: &[String]) -> bool {
+        self.paths.iter().any(|documented| path.starts_with(documented))
+    }
+
+    /// Returns whether the module at `path` is kept to document the modules: whether it is one of
+    /// them, in one of them, or contains one of them.
+    pub fn leads_to(&self, path: &[String]) -> bool {
+        self.contains(path) || self.paths.iter().any(|documented| documented.starts_with(path))
+    }
+}
@@ -0,0 +1,23 @@
+use super::*;
+
+fn path(path: &str) -> Vec<String> {
+    path.split("::").map(str::to_owned).collect()
+}
+
+#[test]
+fn test_private_paths() {
+    let paths = PrivatePaths::parse("crate::internal, ffi::sys").unwrap();
+    assert!(paths.contains(&path("internal")));
+    assert!(paths.contains(&path("internal::detail")));
+    assert!(!paths.contains(&path("ffi")));
+    assert!(paths.contains(&path("ffi::sys")));
+    assert!(!paths.contains(&path("internals")));
+
+    assert!(paths.leads_to(&path("ffi")));
+    assert!(paths.leads_to(&path("internal::detail")));
+    assert!(!paths.leads_to(&path("other")));
+
+    assert!(PrivatePaths::parse("").unwrap().is_empty());
+    assert_eq!(PrivatePaths::parse("crate").unwrap_err(), "invalid module path: `crate`");
+    assert_eq!(PrivatePaths::parse("a::::b").unwrap_err(), "invalid module path: `a::::b`");
+}
@@ -0,0 +1,1079 @@
+//! A serializable representation of the cleaned crate, for the tools reading the documentation of
+//! a crate rather than its pages.
+//!
+//! The types mirror the ones of `clean::types`, without the parts only meaningful within the
+//! compiler session: the `DefId`s become `Id`s naming the item by its crate and index, and the
+//! strings are interned into the table of the crate, which the `Str`s index. Its layout is
+//! versioned by `FORMAT_VERSION`, to be bumped whenever a change would make the crates written
+//! before unreadable.
+//!
+//! The conversions are the methods of a `Converter`, which owns the table the strings are interned
+//! into. `Converter::convert_crate` starts from an empty table and hands it over as the `strings`
+//! of the crate. Other parts of the crate can be converted alone with a converter of their own,
+//! whose table `Converter::into_strings` then gives.
+
+use rustc::hir;
+use rustc::hir::def_id::DefId;
+use rustc::middle::stability;
+use rustc::util::nodemap::FxHashMap;
+use serde::{Deserialize, Serialize};
+use syntax_pos::hygiene;
+use syntax_pos::symbol::Symbol;
+
+use crate::clean;
+use crate::doctree;
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the layout of the serialized crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// A string, as its index in the table of the crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Str(pub u32);
+
+/// Converts the cleaned crate, or parts of it, interning their strings into its table.
+#[derive(Default)]
+pub struct Converter {
+    strings: Vec<String>,
+    ids: FxHashMap<String, Str>,
+}
+
+impl Converter {
+    pub fn new() -> Converter {
+        Converter::default()
+    }
+
+    /// Takes the strings interned by the conversions so far, in the order of their `Str`s.
+    pub fn into_strings(self) -> Vec<String> {
+        self.strings
+    }
+
+    fn intern(&mut self, s: &str) -> Str {
+        if let Some(&id) = self.ids.get(s) {
+            return id;
+        }
+        let id = Str(self.strings.len() as u32);
+        self.strings.push(s.to_owned());
+        self.ids.insert(s.to_owned(), id);
+        id
+    }
+
+    fn intern_symbol(&mut self, symbol: Symbol) -> Str {
+        self.intern(&symbol.as_str())
+    }
+
+    fn intern_opt(&mut self, s: &Option<String>) -> Option<Str> {
+        s.as_ref().map(|s| self.intern(s))
+    }
+
+    fn convert_all<T, U>(&mut self, items: &[T], convert: fn(&mut Self, &T) -> U) -> Vec<U> {
+        items.iter().map(|item| convert(self, item)).collect()
+    }
+}
+
+/// An item, of this crate or of another one, as `<crate>:<index>`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Id(pub String);
+
+impl From<DefId> for Id {
+    fn from(did: DefId) -> Id {
+        Id(format!("{}:{}", did.krate.as_u32(), did.index.as_u32()))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Crate {
+    pub format_version: u32,
+    pub name: Str,
+    pub version: Option<Str>,
+    pub module: Option<Item>,
+    pub external_crates: Vec<ExternalCrate>,
+    /// The strings the `Str`s of the crate index.
+    pub strings: Vec<String>,
+}
+
+impl Converter {
+    /// Converts the whole crate, with the table of its strings.
+    pub fn convert_crate(krate: &clean::Crate) -> Crate {
+        let mut cx = Converter::new();
+        let name = cx.intern_symbol(krate.name);
+        let version = cx.intern_opt(&krate.version);
+        let module = krate.module.as_ref().map(|module| cx.convert_item(module));
+        let external_crates = krate.externs.iter().map(|&(cnum, ref external)| {
+            ExternalCrate {
+                id: cnum.as_u32(),
+                name: cx.intern_symbol(external.name),
+                version: cx.intern_opt(&external.version),
+            }
+        }).collect();
+        Crate {
+            format_version: FORMAT_VERSION,
+            name,
+            version,
+            module,
+            external_crates,
+            strings: cx.into_strings(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExternalCrate {
+    /// The crate number the `Id`s of its items start with.
+    pub id: u32,
+    pub name: Str,
+    pub version: Option<Str>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Item {
+    pub id: Id,
+    pub name: Option<Str>,
+    pub span: Span,
+    pub visibility: Visibility,
+    /// The documentation of the item, its doc comments joined.
+    pub docs: Option<Str>,
+    /// The links of the documentation resolved to an item, and the section of its page.
+    pub links: Vec<(Str, Option<Id>, Option<Str>)>,
+    /// The attributes shown in the declaration of the item, like `repr(C)`.
+    pub attrs: Vec<Str>,
+    pub stability: Option<Stability>,
+    pub deprecation: Option<Deprecation>,
+    /// The public path the item is documented at, for the items with a page of their own.
+    pub path: Option<Vec<Str>>,
+    pub inner: ItemEnum,
+}
+
+impl Converter {
+    pub fn convert_item(&mut self, item: &clean::Item) -> Item {
+        Item {
+            id: item.def_id.into(),
+            name: item.name.map(|name| self.intern_symbol(name)),
+            span: self.convert_span(&item.source),
+            visibility: self.convert_visibility(&item.visibility),
+            docs: item.collapsed_doc_value().map(|docs| self.intern(&docs)),
+            links: item.attrs.links.iter().map(|(text, did, fragment)| {
+                (self.intern(text), did.map(Id::from), self.intern_opt(fragment))
+            }).collect(),
+            attrs: item.attrs.displayed.iter().map(|attr| self.intern(attr)).collect(),
+            stability: item.stability.as_ref().map(|stability| self.convert_stability(stability)),
+            deprecation: item.deprecation.as_ref()
+                .map(|deprecation| self.convert_deprecation(deprecation)),
+            path: item.canonical_path.as_ref()
+                .map(|path| path.iter().map(|name| self.intern(name)).collect()),
+            inner: self.convert_item_enum(&item.inner),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Span {
+    pub filename: Str,
+    /// The line and column the span starts at.
+    pub begin: (usize, usize),
+    /// The line and column the span ends at.
+    pub end: (usize, usize),
+}
+
+impl Converter {
+    pub fn convert_span(&mut self, span: &clean::Span) -> Span {
+        Span {
+            filename: self.intern(&span.filename.to_string()),
+            begin: (span.loline, span.locol),
+            end: (span.hiline, span.hicol),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    Public,
+    Inherited,
+    Crate,
+    Restricted { parent: Id, path: Path },
+}
+
+impl Converter {
+    pub fn convert_visibility(&mut self, visibility: &clean::Visibility) -> Visibility {
+        match *visibility {
+            clean::Public => Visibility::Public,
+            clean::Inherited => Visibility::Inherited,
+            clean::Visibility::Crate => Visibility::Crate,
+            clean::Visibility::Restricted(did, ref path) => {
+                Visibility::Restricted { parent: did.into(), path: self.convert_path(path) }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemEnum {
+    ExternCrate { name: Str, rename: Option<Str> },
+    Import(Import),
+    Struct(Struct),
+    Union(Union),
+    Enum(Enum),
+    Function(Function),
+    Module(Module),
+    Typedef { typedef: Typedef, is_associated: bool },
+    OpaqueTy { opaque_ty: OpaqueTy, is_associated: bool },
+    Static(Static),
+    Constant(Constant),
+    Trait(Trait),
+    TraitAlias(TraitAlias),
+    Impl(Impl),
+    /// A required method of a trait.
+    TyMethod(Function),
+    Method(Method),
+    StructField(Type),
+    Variant(Variant),
+    ForeignFunction(Function),
+    ForeignStatic(Static),
+    ForeignType,
+    Macro(Macro),
+    ProcMacro(ProcMacro),
+    Primitive(Str),
+    AssocConst { type_: Type, default: Option<Str> },
+    AssocType { bounds: Vec<GenericBound>, default: Option<Type> },
+    /// An item stripped by a pass, which is kept for its children.
+    Stripped(Box<ItemEnum>),
+    Keyword(Str),
+}
+
+impl Converter {
+    pub fn convert_item_enum(&mut self, inner: &clean::ItemEnum) -> ItemEnum {
+        use crate::clean::ItemEnum as I;
+        match *inner {
+            I::ExternCrateItem(name, ref rename) => ItemEnum::ExternCrate {
+                name: self.intern_symbol(name),
+                rename: self.intern_opt(rename),
+            },
+            I::ImportItem(ref import) => ItemEnum::Import(self.convert_import(import)),
+            I::StructItem(ref struct_) => ItemEnum::Struct(self.convert_struct(struct_)),
+            I::UnionItem(ref union) => ItemEnum::Union(self.convert_union(union)),
+            I::EnumItem(ref enum_) => ItemEnum::Enum(self.convert_enum(enum_)),
+            I::FunctionItem(ref function) => ItemEnum::Function(self.convert_function(function)),
+            I::ModuleItem(ref module) => ItemEnum::Module(self.convert_module(module)),
+            I::TypedefItem(ref typedef, is_associated) => ItemEnum::Typedef {
+                typedef: self.convert_typedef(typedef),
+                is_associated,
+            },
+            I::OpaqueTyItem(ref opaque_ty, is_associated) => ItemEnum::OpaqueTy {
+                opaque_ty: self.convert_opaque_ty(opaque_ty),
+                is_associated,
+            },
+            I::StaticItem(ref static_) => ItemEnum::Static(self.convert_static(static_)),
+            I::ConstantItem(ref constant) => ItemEnum::Constant(self.convert_constant(constant)),
+            I::TraitItem(ref trait_) => ItemEnum::Trait(self.convert_trait(trait_)),
+            I::TraitAliasItem(ref alias) => ItemEnum::TraitAlias(self.convert_trait_alias(alias)),
+            I::ImplItem(ref impl_) => ItemEnum::Impl(self.convert_impl(impl_)),
+            I::TyMethodItem(ref method) => ItemEnum::TyMethod(Function {
+                decl: self.convert_fn_decl(&method.decl),
+                generics: self.convert_generics(&method.generics),
+                header: self.convert_fn_header(method.header),
+            }),
+            I::MethodItem(ref method) => ItemEnum::Method(self.convert_method(method)),
+            I::StructFieldItem(ref ty) => ItemEnum::StructField(self.convert_type(ty)),
+            I::VariantItem(ref variant) => ItemEnum::Variant(self.convert_variant(&variant.kind)),
+            I::ForeignFunctionItem(ref function) => {
+                ItemEnum::ForeignFunction(self.convert_function(function))
+            }
+            I::ForeignStaticItem(ref static_) => {
+                ItemEnum::ForeignStatic(self.convert_static(static_))
+            }
+            I::ForeignTypeItem => ItemEnum::ForeignType,
+            I::MacroItem(ref macro_) => ItemEnum::Macro(self.convert_macro(macro_)),
+            I::ProcMacroItem(ref macro_) => ItemEnum::ProcMacro(self.convert_proc_macro(macro_)),
+            I::PrimitiveItem(prim) => ItemEnum::Primitive(self.intern(prim.as_str())),
+            I::AssocConstItem(ref ty, ref default) => ItemEnum::AssocConst {
+                type_: self.convert_type(ty),
+                default: self.intern_opt(default),
+            },
+            I::AssocTypeItem(ref bounds, ref default) => ItemEnum::AssocType {
+                bounds: self.convert_all(bounds, Converter::convert_generic_bound),
+                default: default.as_ref().map(|default| self.convert_type(default)),
+            },
+            I::StrippedItem(ref inner) => {
+                ItemEnum::Stripped(Box::new(self.convert_item_enum(inner)))
+            }
+            I::KeywordItem(keyword) => ItemEnum::Keyword(self.intern_symbol(keyword)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Module {
+    pub items: Vec<Item>,
+    pub is_crate: bool,
+}
+
+impl Converter {
+    pub fn convert_module(&mut self, module: &clean::Module) -> Module {
+        Module {
+            items: self.convert_all(&module.items, Converter::convert_item),
+            is_crate: module.is_crate,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StructType {
+    Plain,
+    Tuple,
+    Unit,
+}
+
+impl From<doctree::StructType> for StructType {
+    fn from(struct_type: doctree::StructType) -> StructType {
+        match struct_type {
+            doctree::StructType::Plain => StructType::Plain,
+            doctree::StructType::Tuple => StructType::Tuple,
+            doctree::StructType::Unit => StructType::Unit,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Struct {
+    pub struct_type: StructType,
+    pub generics: Generics,
+    pub fields: Vec<Item>,
+    pub fields_stripped: bool,
+}
+
+impl Converter {
+    pub fn convert_struct(&mut self, struct_: &clean::Struct) -> Struct {
+        Struct {
+            struct_type: struct_.struct_type.into(),
+            generics: self.convert_generics(&struct_.generics),
+            fields: self.convert_all(&struct_.fields, Converter::convert_item),
+            fields_stripped: struct_.fields_stripped,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Union {
+    pub generics: Generics,
+    pub fields: Vec<Item>,
+    pub fields_stripped: bool,
+}
+
+impl Converter {
+    pub fn convert_union(&mut self, union: &clean::Union) -> Union {
+        Union {
+            generics: self.convert_generics(&union.generics),
+            fields: self.convert_all(&union.fields, Converter::convert_item),
+            fields_stripped: union.fields_stripped,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Enum {
+    pub generics: Generics,
+    pub variants: Vec<Item>,
+    pub variants_stripped: bool,
+}
+
+impl Converter {
+    pub fn convert_enum(&mut self, enum_: &clean::Enum) -> Enum {
+        Enum {
+            generics: self.convert_generics(&enum_.generics),
+            variants: enum_.variants.iter().map(|variant| self.convert_item(variant)).collect(),
+            variants_stripped: enum_.variants_stripped,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Variant {
+    CLike,
+    Tuple(Vec<Type>),
+    Struct { struct_type: StructType, fields: Vec<Item>, fields_stripped: bool },
+}
+
+impl Converter {
+    pub fn convert_variant(&mut self, kind: &clean::VariantKind) -> Variant {
+        match *kind {
+            clean::VariantKind::CLike => Variant::CLike,
+            clean::VariantKind::Tuple(ref tys) => {
+                Variant::Tuple(self.convert_all(tys, Converter::convert_type))
+            }
+            clean::VariantKind::Struct(ref struct_) => Variant::Struct {
+                struct_type: struct_.struct_type.into(),
+                fields: self.convert_all(&struct_.fields, Converter::convert_item),
+                fields_stripped: struct_.fields_stripped,
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FnHeader {
+    pub is_unsafe: bool,
+    pub is_const: bool,
+    pub is_async: bool,
+    pub abi: Str,
+}
+
+impl Converter {
+    pub fn convert_fn_header(&mut self, header: hir::FnHeader) -> FnHeader {
+        FnHeader {
+            is_unsafe: header.unsafety == hir::Unsafety::Unsafe,
+            is_const: header.constness == hir::Constness::Const,
+            is_async: header.asyncness == hir::IsAsync::Async,
+            abi: self.intern(header.abi.name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Function {
+    pub decl: FnDecl,
+    pub generics: Generics,
+    pub header: FnHeader,
+}
+
+impl Converter {
+    pub fn convert_function(&mut self, function: &clean::Function) -> Function {
+        Function {
+            decl: self.convert_fn_decl(&function.decl),
+            generics: self.convert_generics(&function.generics),
+            header: self.convert_fn_header(function.header),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Method {
+    pub decl: FnDecl,
+    pub generics: Generics,
+    pub header: FnHeader,
+    /// Whether the method of a trait impl can be overridden by a more specialized impl.
+    pub is_default: bool,
+    /// For the provided methods of a trait, the source of their default implementation.
+    pub default_impl: Option<Str>,
+}
+
+impl Converter {
+    pub fn convert_method(&mut self, method: &clean::Method) -> Method {
+        Method {
+            decl: self.convert_fn_decl(&method.decl),
+            generics: self.convert_generics(&method.generics),
+            header: self.convert_fn_header(method.header),
+            is_default: method.defaultness.map_or(false, |defaultness| {
+                defaultness.has_value() && !defaultness.is_final()
+            }),
+            default_impl: self.intern_opt(&method.default_impl),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FnDecl {
+    /// The name and type of each argument.
+    pub inputs: Vec<(Str, Type)>,
+    pub output: Option<Type>,
+    pub c_variadic: bool,
+}
+
+impl Converter {
+    pub fn convert_fn_decl(&mut self, decl: &clean::FnDecl) -> FnDecl {
+        FnDecl {
+            inputs: decl.inputs.values.iter()
+                .map(|arg| (self.intern_symbol(arg.name), self.convert_type(&arg.type_)))
+                .collect(),
+            output: match decl.output {
+                clean::Return(ref ty) => Some(self.convert_type(ty)),
+                clean::DefaultReturn => None,
+            },
+            c_variadic: decl.c_variadic,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Generics {
+    pub params: Vec<GenericParamDef>,
+    pub where_predicates: Vec<WherePredicate>,
+}
+
+impl Converter {
+    pub fn convert_generics(&mut self, generics: &clean::Generics) -> Generics {
+        Generics {
+            params: self.convert_all(&generics.params, Converter::convert_generic_param_def),
+            where_predicates: self.convert_all(&generics.where_predicates,
+                                               Converter::convert_where_predicate),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GenericParamDef {
+    pub name: Str,
+    pub kind: GenericParamDefKind,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenericParamDefKind {
+    Lifetime,
+    Type {
+        bounds: Vec<GenericBound>,
+        default: Option<Type>,
+        /// Whether the parameter stands for an `impl Trait` argument.
+        synthetic: bool,
+    },
+    Const { ty: Type },
+}
+
+impl Converter {
+    pub fn convert_generic_param_def(&mut self, param: &clean::GenericParamDef) -> GenericParamDef {
+        let kind = match param.kind {
+            clean::GenericParamDefKind::Lifetime => GenericParamDefKind::Lifetime,
+            clean::GenericParamDefKind::Type { ref bounds, ref default, synthetic, .. } => {
+                GenericParamDefKind::Type {
+                    bounds: self.convert_all(bounds, Converter::convert_generic_bound),
+                    default: default.as_ref().map(|default| self.convert_type(default)),
+                    synthetic: synthetic.is_some(),
+                }
+            }
+            clean::GenericParamDefKind::Const { ref ty, .. } => {
+                GenericParamDefKind::Const { ty: self.convert_type(ty) }
+            }
+        };
+        GenericParamDef { name: self.intern_symbol(param.name), kind }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WherePredicate {
+    BoundPredicate { ty: Type, bounds: Vec<GenericBound> },
+    RegionPredicate { lifetime: Str, bounds: Vec<GenericBound> },
+    EqPredicate { lhs: Type, rhs: Type },
+}
+
+impl Converter {
+    pub fn convert_where_predicate(&mut self, predicate: &clean::WherePredicate) -> WherePredicate {
+        match *predicate {
+            clean::WherePredicate::BoundPredicate { ref ty, ref bounds } => {
+                WherePredicate::BoundPredicate {
+                    ty: self.convert_type(ty),
+                    bounds: self.convert_all(bounds, Converter::convert_generic_bound),
+                }
+            }
+            clean::WherePredicate::RegionPredicate { ref lifetime, ref bounds } => {
+                WherePredicate::RegionPredicate {
+                    lifetime: self.intern_symbol(lifetime.0),
+                    bounds: self.convert_all(bounds, Converter::convert_generic_bound),
+                }
+            }
+            clean::WherePredicate::EqPredicate { ref lhs, ref rhs } => {
+                WherePredicate::EqPredicate {
+                    lhs: self.convert_type(lhs),
+                    rhs: self.convert_type(rhs),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenericBound {
+    TraitBound {
+        trait_: Type,
+        /// The higher-ranked lifetimes of the bound, like `'a` in `for<'a> Fn(&'a u8)`.
+        generic_params: Vec<GenericParamDef>,
+        /// Whether the bound is `?Trait`.
+        maybe: bool,
+    },
+    Outlives(Str),
+}
+
+impl Converter {
+    pub fn convert_generic_bound(&mut self, bound: &clean::GenericBound) -> GenericBound {
+        match *bound {
+            clean::GenericBound::TraitBound(ref poly_trait, modifier) => {
+                GenericBound::TraitBound {
+                    trait_: self.convert_type(&poly_trait.trait_),
+                    generic_params: self.convert_all(&poly_trait.generic_params,
+                                                     Converter::convert_generic_param_def),
+                    maybe: modifier == hir::TraitBoundModifier::Maybe,
+                }
+            }
+            clean::GenericBound::Outlives(ref lifetime) => {
+                GenericBound::Outlives(self.intern_symbol(lifetime.0))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Type {
+    ResolvedPath {
+        path: Path,
+        /// The bounds of a trait object, like `Send` in `dyn Any + Send`.
+        param_names: Option<Vec<GenericBound>>,
+        id: Id,
+        /// Whether the path is a `T::Name` path for an associated type.
+        is_generic: bool,
+    },
+    Generic(Str),
+    Primitive(Str),
+    BareFunction(Box<BareFunctionDecl>),
+    Tuple(Vec<Type>),
+    Slice(Box<Type>),
+    Array { type_: Box<Type>, len: Str },
+    Never,
+    RawPointer { mutable: bool, type_: Box<Type> },
+    BorrowedRef { lifetime: Option<Str>, mutable: bool, type_: Box<Type> },
+    /// `<Type as Trait>::Name`
+    QPath { name: Str, self_type: Box<Type>, trait_: Box<Type> },
+    Infer,
+    ImplTrait(Vec<GenericBound>),
+}
+
+impl Converter {
+    pub fn convert_type(&mut self, ty: &clean::Type) -> Type {
+        match *ty {
+            clean::ResolvedPath { ref path, ref param_names, did, is_generic } => {
+                Type::ResolvedPath {
+                    path: self.convert_path(path),
+                    param_names: param_names.as_ref().map(|bounds| {
+                        self.convert_all(bounds, Converter::convert_generic_bound)
+                    }),
+                    id: did.into(),
+                    is_generic,
+                }
+            }
+            clean::Generic(name) => Type::Generic(self.intern_symbol(name)),
+            clean::Primitive(prim) => Type::Primitive(self.intern(prim.as_str())),
+            clean::BareFunction(ref decl) => {
+                Type::BareFunction(Box::new(self.convert_bare_function_decl(decl)))
+            }
+            clean::Tuple(ref tys) => Type::Tuple(self.convert_all(tys, Converter::convert_type)),
+            clean::Slice(ref ty) => Type::Slice(Box::new(self.convert_type(ty))),
+            clean::Array(ref ty, ref len) => Type::Array {
+                type_: Box::new(self.convert_type(ty)),
+                len: self.intern(len),
+            },
+            clean::Never => Type::Never,
+            clean::RawPointer(mutability, ref ty) => Type::RawPointer {
+                mutable: mutability == clean::Mutable,
+                type_: Box::new(self.convert_type(ty)),
+            },
+            clean::BorrowedRef { ref lifetime, mutability, ref type_ } => Type::BorrowedRef {
+                lifetime: lifetime.as_ref().map(|lifetime| self.intern_symbol(lifetime.0)),
+                mutable: mutability == clean::Mutable,
+                type_: Box::new(self.convert_type(type_)),
+            },
+            clean::QPath { name, ref self_type, ref trait_ } => Type::QPath {
+                name: self.intern_symbol(name),
+                self_type: Box::new(self.convert_type(self_type)),
+                trait_: Box::new(self.convert_type(trait_)),
+            },
+            clean::Infer => Type::Infer,
+            clean::ImplTrait(ref bounds) => {
+                Type::ImplTrait(self.convert_all(bounds, Converter::convert_generic_bound))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BareFunctionDecl {
+    pub is_unsafe: bool,
+    pub generic_params: Vec<GenericParamDef>,
+    pub decl: FnDecl,
+    pub abi: Str,
+}
+
+impl Converter {
+    pub fn convert_bare_function_decl(
+        &mut self,
+        decl: &clean::BareFunctionDecl,
+    ) -> BareFunctionDecl {
+        BareFunctionDecl {
+            is_unsafe: decl.unsafety == hir::Unsafety::Unsafe,
+            generic_params: self.convert_all(&decl.generic_params,
+                                             Converter::convert_generic_param_def),
+            decl: self.convert_fn_decl(&decl.decl),
+            abi: self.intern(decl.abi.name()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Path {
+    pub global: bool,
+    pub segments: Vec<PathSegment>,
+}
+
+impl Converter {
+    pub fn convert_path(&mut self, path: &clean::Path) -> Path {
+        Path {
+            global: path.global,
+            segments: self.convert_all(&path.segments, Converter::convert_path_segment),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PathSegment {
+    pub name: Str,
+    pub args: GenericArgs,
+}
+
+impl Converter {
+    pub fn convert_path_segment(&mut self, segment: &clean::PathSegment) -> PathSegment {
+        PathSegment {
+            name: self.intern_symbol(segment.name),
+            args: self.convert_generic_args(&segment.args),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenericArgs {
+    AngleBracketed { args: Vec<GenericArg>, bindings: Vec<TypeBinding> },
+    Parenthesized { inputs: Vec<Type>, output: Option<Type> },
+}
+
+impl Converter {
+    pub fn convert_generic_args(&mut self, args: &clean::GenericArgs) -> GenericArgs {
+        match *args {
+            clean::GenericArgs::AngleBracketed { ref args, ref bindings } => {
+                GenericArgs::AngleBracketed {
+                    args: self.convert_all(args, Converter::convert_generic_arg),
+                    bindings: self.convert_all(bindings, Converter::convert_type_binding),
+                }
+            }
+            clean::GenericArgs::Parenthesized { ref inputs, ref output } => {
+                GenericArgs::Parenthesized {
+                    inputs: self.convert_all(inputs, Converter::convert_type),
+                    output: output.as_ref().map(|output| self.convert_type(output)),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenericArg {
+    Lifetime(Str),
+    Type(Type),
+    Const(Constant),
+}
+
+impl Converter {
+    pub fn convert_generic_arg(&mut self, arg: &clean::GenericArg) -> GenericArg {
+        match *arg {
+            clean::GenericArg::Lifetime(ref lifetime) => {
+                GenericArg::Lifetime(self.intern_symbol(lifetime.0))
+            }
+            clean::GenericArg::Type(ref ty) => GenericArg::Type(self.convert_type(ty)),
+            clean::GenericArg::Const(ref constant) => {
+                GenericArg::Const(self.convert_constant(constant))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TypeBinding {
+    pub name: Str,
+    pub kind: TypeBindingKind,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypeBindingKind {
+    Equality { ty: Type },
+    Constraint { bounds: Vec<GenericBound> },
+}
+
+impl Converter {
+    pub fn convert_type_binding(&mut self, binding: &clean::TypeBinding) -> TypeBinding {
+        let kind = match binding.kind {
+            clean::TypeBindingKind::Equality { ref ty } => {
+                TypeBindingKind::Equality { ty: self.convert_type(ty) }
+            }
+            clean::TypeBindingKind::Constraint { ref bounds } => TypeBindingKind::Constraint {
+                bounds: self.convert_all(bounds, Converter::convert_generic_bound),
+            },
+        };
+        TypeBinding { name: self.intern_symbol(binding.name), kind }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Trait {
+    pub is_auto: bool,
+    pub is_unsafe: bool,
+    pub items: Vec<Item>,
+    pub generics: Generics,
+    pub bounds: Vec<GenericBound>,
+    /// Whether the trait is marked `#[doc(notable_trait)]`.
+    pub is_notable: bool,
+}
+
+impl Converter {
+    pub fn convert_trait(&mut self, trait_: &clean::Trait) -> Trait {
+        Trait {
+            is_auto: trait_.auto,
+            is_unsafe: trait_.unsafety == hir::Unsafety::Unsafe,
+            items: self.convert_all(&trait_.items, Converter::convert_item),
+            generics: self.convert_generics(&trait_.generics),
+            bounds: self.convert_all(&trait_.bounds, Converter::convert_generic_bound),
+            is_notable: trait_.is_notable,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraitAlias {
+    pub generics: Generics,
+    pub bounds: Vec<GenericBound>,
+}
+
+impl Converter {
+    pub fn convert_trait_alias(&mut self, alias: &clean::TraitAlias) -> TraitAlias {
+        TraitAlias {
+            generics: self.convert_generics(&alias.generics),
+            bounds: self.convert_all(&alias.bounds, Converter::convert_generic_bound),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Impl {
+    pub is_unsafe: bool,
+    pub generics: Generics,
+    /// The names of the methods of the trait the impl does not override, sorted.
+    pub provided_trait_methods: Vec<Str>,
+    pub trait_: Option<Type>,
+    pub for_: Type,
+    pub items: Vec<Item>,
+    /// Whether the impl is a negative one, like `impl !Send for T`.
+    pub negative: bool,
+    /// Whether the impl was derived by rustdoc, like the impls of the auto traits.
+    pub synthetic: bool,
+    /// The blanket impl the impl was derived from, if it was.
+    pub blanket_impl: Option<Type>,
+}
+
+impl Converter {
+    pub fn convert_impl(&mut self, impl_: &clean::Impl) -> Impl {
+        let mut provided_trait_methods = impl_.provided_trait_methods.iter().collect::<Vec<_>>();
+        provided_trait_methods.sort();
+        Impl {
+            is_unsafe: impl_.unsafety == hir::Unsafety::Unsafe,
+            generics: self.convert_generics(&impl_.generics),
+            provided_trait_methods: provided_trait_methods.into_iter()
+                .map(|name| self.intern(name))
+                .collect(),
+            trait_: impl_.trait_.as_ref().map(|trait_| self.convert_type(trait_)),
+            for_: self.convert_type(&impl_.for_),
+            items: self.convert_all(&impl_.items, Converter::convert_item),
+            negative: impl_.polarity == Some(clean::ImplPolarity::Negative),
+            synthetic: impl_.synthetic,
+            blanket_impl: impl_.blanket_impl.as_ref().map(|ty| self.convert_type(ty)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Typedef {
+    pub type_: Type,
+    pub generics: Generics,
+}
+
+impl Converter {
+    pub fn convert_typedef(&mut self, typedef: &clean::Typedef) -> Typedef {
+        Typedef {
+            type_: self.convert_type(&typedef.type_),
+            generics: self.convert_generics(&typedef.generics),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpaqueTy {
+    pub bounds: Vec<GenericBound>,
+    pub generics: Generics,
+}
+
+impl Converter {
+    pub fn convert_opaque_ty(&mut self, opaque_ty: &clean::OpaqueTy) -> OpaqueTy {
+        OpaqueTy {
+            bounds: self.convert_all(&opaque_ty.bounds, Converter::convert_generic_bound),
+            generics: self.convert_generics(&opaque_ty.generics),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Static {
+    pub type_: Type,
+    pub mutable: bool,
+    pub expr: Str,
+    /// The value `expr` evaluates to, when it is a primitive worth showing.
+    pub value: Option<Str>,
+}
+
+impl Converter {
+    pub fn convert_static(&mut self, static_: &clean::Static) -> Static {
+        Static {
+            type_: self.convert_type(&static_.type_),
+            mutable: static_.mutability == clean::Mutable,
+            expr: self.intern(&static_.expr),
+            value: self.intern_opt(&static_.value),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Constant {
+    pub type_: Type,
+    pub expr: Str,
+    /// The value `expr` evaluates to, when it is a primitive worth showing.
+    pub value: Option<Str>,
+}
+
+impl Converter {
+    pub fn convert_constant(&mut self, constant: &clean::Constant) -> Constant {
+        Constant {
+            type_: self.convert_type(&constant.type_),
+            expr: self.intern(&constant.expr),
+            value: self.intern_opt(&constant.value),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Import {
+    /// `use source as name;`
+    Simple { name: Str, source: ImportSource },
+    /// `use source::*;`
+    Glob { source: ImportSource },
+}
+
+impl Converter {
+    pub fn convert_import(&mut self, import: &clean::Import) -> Import {
+        match *import {
+            clean::Import::Simple(name, ref source) => Import::Simple {
+                name: self.intern_symbol(name),
+                source: self.convert_import_source(source),
+            },
+            clean::Import::Glob(ref source) => {
+                Import::Glob { source: self.convert_import_source(source) }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImportSource {
+    pub path: Path,
+    pub id: Option<Id>,
+}
+
+impl Converter {
+    pub fn convert_import_source(&mut self, source: &clean::ImportSource) -> ImportSource {
+        ImportSource { path: self.convert_path(&source.path), id: source.did.map(Id::from) }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Macro {
+    pub source: Str,
+    pub imported_from: Option<Str>,
+}
+
+impl Converter {
+    pub fn convert_macro(&mut self, macro_: &clean::Macro) -> Macro {
+        Macro {
+            source: self.intern(&macro_.source),
+            imported_from: macro_.imported_from.map(|krate| self.intern_symbol(krate)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroKind {
+    Bang,
+    Attr,
+    Derive,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ProcMacro {
+    pub kind: MacroKind,
+    pub helpers: Vec<Str>,
+}
+
+impl Converter {
+    pub fn convert_proc_macro(&mut self, macro_: &clean::ProcMacro) -> ProcMacro {
+        ProcMacro {
+            kind: match macro_.kind {
+                hygiene::MacroKind::Bang => MacroKind::Bang,
+                hygiene::MacroKind::Attr => MacroKind::Attr,
+                hygiene::MacroKind::Derive => MacroKind::Derive,
+            },
+            helpers: macro_.helpers.iter().map(|&helper| self.intern_symbol(helper)).collect(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Stability {
+    pub is_unstable: bool,
+    pub feature: Option<Str>,
+    pub since: Str,
+    pub deprecation: Option<Deprecation>,
+    pub unstable_reason: Option<Str>,
+    pub issue: Option<u32>,
+}
+
+impl Converter {
+    pub fn convert_stability(&mut self, stability: &clean::Stability) -> Stability {
+        Stability {
+            is_unstable: stability.level == stability::Unstable,
+            feature: self.intern_opt(&stability.feature),
+            since: self.intern(&stability.since),
+            deprecation: stability.deprecation.as_ref()
+                .map(|deprecation| self.convert_deprecation(deprecation)),
+            unstable_reason: self.intern_opt(&stability.unstable_reason),
+            issue: stability.issue.map(|issue| issue.get()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Deprecation {
+    pub since: Option<Str>,
+    pub note: Option<Str>,
+}
+
+impl Converter {
+    pub fn convert_deprecation(&mut self, deprecation: &clean::Deprecation) -> Deprecation {
+        Deprecation {
+            since: self.intern_opt(&deprecation.since),
+            note: self.intern_opt(&deprecation.note),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use super::*;
+
+use rustc::hir::def_id::{DefIndex, LOCAL_CRATE};
+use rustc_target::spec::abi::Abi;
+use syntax::with_default_globals;
+
+fn function(name: &str, arg: &str) -> clean::Item {
+    let decl = clean::FnDecl {
+        inputs: clean::Arguments {
+            values: vec![clean::Argument {
+                type_: clean::Primitive(clean::PrimitiveType::U8),
+                name: Symbol::intern(arg),
+            }],
+        },
+        output: clean::Return(clean::Generic(Symbol::intern("T"))),
+        c_variadic: false,
+        attrs: Default::default(),
+    };
+    clean::Item {
+        source: clean::Span::empty(),
+        name: Some(Symbol::intern(name)),
+        attrs: Default::default(),
+        inner: clean::FunctionItem(clean::Function {
+            decl,
+            generics: Default::default(),
+            header: hir::FnHeader {
+                unsafety: hir::Unsafety::Normal,
+                constness: hir::Constness::NotConst,
+                asyncness: hir::IsAsync::NotAsync,
+                abi: Abi::Rust,
+            },
+            all_types: vec![],
+            ret_types: vec![],
+        }),
+        visibility: clean::Public,
+        def_id: DefId { krate: LOCAL_CRATE, index: DefIndex::from_u32(3) },
+        stability: None,
+        deprecation: None,
+        canonical_path: None,
+    }
+}
+
+#[test]
+fn test_strings_interned() {
+    with_default_globals(|| {
+        let mut converter = Converter::new();
+        let item = converter.convert_item(&function("len", "len"));
+        let strings = converter.into_strings();
+
+        // The name of the function and of its argument are the same string.
+        assert_eq!(item.name, Some(Str(0)));
+        match item.inner {
+            ItemEnum::Function(ref function) => assert_eq!(function.decl.inputs[0].0, Str(0)),
+            _ => panic!("expected a function"),
+        }
+        assert_eq!(strings[0], "len");
+        assert_eq!(strings.iter().filter(|s| *s == "len").count(), 1);
+    })
+}
+
+#[test]
+fn test_converters_independent() {
+    with_default_globals(|| {
+        let mut first = Converter::new();
+        first.convert_item(&function("len", "value"));
+
+        // A second conversion starts from an empty table, whatever the first one interned.
+        let mut second = Converter::new();
+        let item = second.convert_item(&function("new", "value"));
+        assert_eq!(item.name, Some(Str(0)));
+        assert_eq!(second.into_strings()[0], "new");
+        assert_eq!(first.into_strings()[0], "len");
+    })
+}
+
+#[test]
+fn test_item_json() {
+    with_default_globals(|| {
+        let item = Converter::new().convert_item(&function("new", "value"));
+
+        assert_eq!(item.id, Id("0:3".to_owned()));
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["visibility"], "public");
+        assert_eq!(json["inner"]["function"]["decl"]["output"]["generic"], 4);
+        assert_eq!(json["inner"]["function"]["header"]["is_unsafe"], false);
+
+        // The item reads back as it was written.
+        let read: Item = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&read).unwrap(), json);
+    })
+}
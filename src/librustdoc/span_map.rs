@@ -0,0 +1,188 @@
+//! Collects what the paths in the source of the crate resolve to, for the jump-to-definition
+//! links of the source pages, with `--generate-link-to-definition`.
+//!
+//! The resolutions are only known while the HIR is around, but the source pages are rendered
+//! after the compiler is done, so they are recorded by position in the source files beforehand.
+//!
+//! The items of the dependencies are linked to their documentation, where `--extern-html-root-url`
+//! or the output directory says it is. The associated items and variants, which have no page of
+//! their own, link to their section of the page of the type or trait they belong to.
+
+use rustc::hir::{self, intravisit};
+use rustc::hir::def::{CtorOf, DefKind, Res};
+use rustc::hir::def_id::DefId;
+use rustc::ty::{self, TyCtxt};
+use rustc::util::nodemap::FxHashMap;
+use syntax::source_map::FileName;
+use syntax_pos::Span;
+
+use crate::clean::{self, inline};
+use crate::core::DocContext;
+
+use std::mem;
+use std::path::PathBuf;
+
+/// For each source file, the items that the identifiers in it refer to, keyed by the byte range
+/// of the identifier from the start of the file.
+pub type SpanMap = FxHashMap<PathBuf, FxHashMap<(u32, u32), SourceLink>>;
+
+/// The item an identifier in the source refers to.
+#[derive(Clone, Debug)]
+pub struct SourceLink {
+    /// The item whose page is linked to.
+    pub def_id: DefId,
+    /// The section of the page of `def_id` to link to, for the associated items and variants of
+    /// other crates.
+    pub fragment: Option<String>,
+    /// The file and line the item is defined at, if it is defined in this crate. Used when the
+    /// item has no page of its own.
+    pub definition: Option<(PathBuf, usize)>,
+}
+
+crate fn collect_spans(tcx: TyCtxt<'_>) -> SpanMap {
+    let mut collector = SpanMapCollector { tcx, tables: None, map: Default::default() };
+    intravisit::walk_crate(&mut collector, tcx.hir().krate());
+    collector.map
+}
+
+/// Records the paths of the items of other crates linked to from the source pages, so that their
+/// documentation is found when rendering.
+crate fn record_extern_items(cx: &mut DocContext<'_>) {
+    let dids = cx.renderinfo.span_map.values()
+        .flat_map(|links| links.values())
+        .map(|link| link.def_id)
+        .filter(|did| !did.is_local())
+        .collect::<Vec<_>>();
+    for did in dids {
+        let kind = match cx.tcx.def_kind(did) {
+            Some(DefKind::Fn) => clean::TypeKind::Function,
+            Some(DefKind::TyAlias) => clean::TypeKind::Typedef,
+            Some(DefKind::Enum) => clean::TypeKind::Enum,
+            Some(DefKind::Trait) => clean::TypeKind::Trait,
+            Some(DefKind::Struct) => clean::TypeKind::Struct,
+            Some(DefKind::Union) => clean::TypeKind::Union,
+            Some(DefKind::Mod) => clean::TypeKind::Module,
+            Some(DefKind::ForeignTy) => clean::TypeKind::Foreign,
+            Some(DefKind::Const) => clean::TypeKind::Const,
+            Some(DefKind::Static) => clean::TypeKind::Static,
+            Some(DefKind::TraitAlias) => clean::TypeKind::TraitAlias,
+            _ => continue,
+        };
+        inline::record_extern_fqn(cx, did, kind);
+    }
+}
+
+struct SpanMapCollector<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    /// The type-check results of the body being visited, to resolve method calls.
+    tables: Option<&'tcx ty::TypeckTables<'tcx>>,
+    map: SpanMap,
+}
+
+impl<'tcx> SpanMapCollector<'tcx> {
+    fn record(&mut self, span: Span, def_id: DefId) {
+        // Spans coming from macro expansions don't match what is written in the file.
+        if span.is_dummy() || span.from_expansion() {
+            return;
+        }
+        let source_map = self.tcx.sess.source_map();
+        let file = source_map.lookup_char_pos(span.lo()).file;
+        let path = match file.name {
+            FileName::Real(ref path) => path.clone(),
+            _ => return,
+        };
+        let (def_id, fragment) = if def_id.is_local() {
+            (def_id, None)
+        } else {
+            match self.extern_page(def_id) {
+                Some(page) => page,
+                None => return,
+            }
+        };
+        let definition = if def_id.is_local() {
+            let loc = source_map.lookup_char_pos(self.tcx.def_span(def_id).lo());
+            match loc.file.name {
+                FileName::Real(ref path) => Some((path.clone(), loc.line)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        let range = ((span.lo() - file.start_pos).0, (span.hi() - file.start_pos).0);
+        let link = SourceLink { def_id, fragment, definition };
+        self.map.entry(path).or_default().insert(range, link);
+    }
+
+    /// Returns the item of another crate whose page documents `def_id`, and the section of the
+    /// page it is documented in.
+    fn extern_page(&self, def_id: DefId) -> Option<(DefId, Option<String>)> {
+        let tcx = self.tcx;
+        let section = |kind: &str, did: DefId| Some(format!("{}.{}", kind, tcx.item_name(did)));
+        match tcx.def_kind(def_id)? {
+            DefKind::Method | DefKind::AssocConst | DefKind::AssocTy => {
+                let item = tcx.associated_item(def_id);
+                let (page, provided) = match item.container {
+                    ty::TraitContainer(trait_did) => (trait_did, item.defaultness.has_value()),
+                    // The inherent and trait impls are documented on the page of their type.
+                    ty::ImplContainer(impl_did) => {
+                        (tcx.type_of(impl_did).ty_adt_def()?.did, true)
+                    }
+                };
+                let kind = match item.kind {
+                    ty::AssocKind::Method if provided => "method",
+                    ty::AssocKind::Method => "tymethod",
+                    ty::AssocKind::Const => "associatedconstant",
+                    ty::AssocKind::Type | ty::AssocKind::OpaqueTy => "associatedtype",
+                };
+                Some((page, section(kind, def_id)))
+            }
+            DefKind::Variant => Some((tcx.parent(def_id)?, section("variant", def_id))),
+            DefKind::Ctor(CtorOf::Variant, _) => {
+                let variant = tcx.parent(def_id)?;
+                Some((tcx.parent(variant)?, section("variant", variant)))
+            }
+            DefKind::Ctor(CtorOf::Struct, _) => Some((tcx.parent(def_id)?, None)),
+            _ => Some((def_id, None)),
+        }
+    }
+}
+
+impl<'tcx> intravisit::Visitor<'tcx> for SpanMapCollector<'tcx> {
+    fn nested_visit_map<'this>(&'this mut self) -> intravisit::NestedVisitorMap<'this, 'tcx> {
+        intravisit::NestedVisitorMap::All(&self.tcx.hir())
+    }
+
+    fn visit_nested_body(&mut self, id: hir::BodyId) {
+        let tables = mem::replace(&mut self.tables, Some(self.tcx.body_tables(id)));
+        self.visit_body(self.tcx.hir().body(id));
+        self.tables = tables;
+    }
+
+    fn visit_path(&mut self, path: &'tcx hir::Path, _id: hir::HirId) {
+        // Only the last segment is sure to have its resolution recorded, the others have one
+        // when it was known while lowering.
+        let last = path.segments.len().saturating_sub(1);
+        for (i, segment) in path.segments.iter().enumerate() {
+            let res = if i == last { Some(path.res) } else { segment.res };
+            if let Some(Res::Def(_, def_id)) = res {
+                self.record(segment.ident.span, def_id);
+            }
+        }
+        intravisit::walk_path(self, path);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr) {
+        // Method calls and paths like `Type::method` are only resolved while type-checking.
+        let segment = match expr.kind {
+            hir::ExprKind::MethodCall(ref segment, ..) |
+            hir::ExprKind::Path(hir::QPath::TypeRelative(_, ref segment)) => Some(segment),
+            _ => None,
+        };
+        if let Some(segment) = segment {
+            if let Some(def_id) = self.tables.and_then(|t| t.type_dependent_def_id(expr.hir_id)) {
+                self.record(segment.ident.span, def_id);
+            }
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
@@ -6,25 +6,37 @@ use rustc::hir;
 use rustc::hir::intravisit;
 use rustc::session::{self, config, DiagnosticOutput};
 use rustc::util::common::ErrorReported;
+use serde::{Deserialize, Serialize};
 use syntax::ast;
 use syntax::with_globals;
-use syntax::source_map::SourceMap;
-use syntax::edition::Edition;
+use syntax::source_map::{SourceFile, SourceMap};
+use syntax::edition::{Edition, EDITION_NAME_LIST};
+use std::collections::hash_map::DefaultHasher;
 use std::env;
+use std::fs;
+use std::hash::{Hash, Hasher};
 use std::io::{self, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use syntax::symbol::sym;
 use syntax_pos::{BytePos, DUMMY_SP, Pos, Span, FileName};
 use tempfile::Builder as TempFileBuilder;
 use testing;
 
-use crate::clean::Attributes;
+use crate::clean::{Attributes, DocFragment};
 use crate::config::Options;
 use crate::html::markdown::{self, ErrorCodes, LangString, Ignore};
 
+use self::bundle::Bundle;
+use self::timings::{TestTimes, Timings};
+
+mod bundle;
+mod timings;
+
 #[derive(Clone, Default)]
 pub struct TestOptions {
     /// Whether to disable the default `extern crate my_crate;` when creating doctests.
@@ -34,6 +46,9 @@ pub struct TestOptions {
     pub display_warnings: bool,
     /// Additional crate-level attributes to add to doctests.
     pub attrs: Vec<String>,
+    /// The prefix of hidden lines given by `#![doc(hidden_line_prefix = "...")]`, to use instead
+    /// of `#`.
+    pub hidden_line_prefix: Option<String>,
 }
 
 pub fn run(options: Options) -> i32 {
@@ -85,7 +100,7 @@ pub fn run(options: Options) -> i32 {
     let mut test_args = options.test_args.clone();
     let display_warnings = options.display_warnings;
 
-    let tests = interface::run_compiler(config, |compiler| compiler.enter(|queries| {
+    let (tests, timings_report) = interface::run_compiler(config, |compiler| compiler.enter(|queries| {
         let lower_to_hir = queries.lower_to_hir()?;
 
         let mut opts = scrape_test_config(lower_to_hir.peek().0.krate());
@@ -117,16 +132,19 @@ pub fn run(options: Options) -> i32 {
             });
         });
 
-        let ret : Result<_, ErrorReported> = Ok(collector.tests);
+        collector.write_persisted_manifest();
+        let report = collector.timings_report();
+        let ret : Result<_, ErrorReported> = Ok((collector.tests, report));
         ret
     })).expect("compiler aborted in rustdoc!");
 
     test_args.insert(0, "rustdoctest".to_string());
 
-    testing::test_main(
+    testing::test_main_with_exit_callback(
         &test_args,
         tests,
-        Some(testing::Options::new().display_output(display_warnings))
+        Some(testing::Options::new().display_output(display_warnings)),
+        timings_report,
     );
 
     0
@@ -140,11 +158,19 @@ fn scrape_test_config(krate: &::rustc::hir::Crate) -> TestOptions {
         no_crate_inject: false,
         display_warnings: false,
         attrs: Vec::new(),
+        hidden_line_prefix: None,
     };
 
-    let test_attrs: Vec<_> = krate.attrs.iter()
+    let doc_attrs: Vec<_> = krate.attrs.iter()
         .filter(|a| a.check_name(sym::doc))
         .flat_map(|a| a.meta_item_list().unwrap_or_else(Vec::new))
+        .collect();
+    opts.hidden_line_prefix = doc_attrs.iter()
+        .filter(|a| a.check_name(sym::hidden_line_prefix))
+        .filter_map(|a| a.value_str())
+        .map(|prefix| prefix.as_str().trim().to_owned())
+        .find(|prefix| !prefix.is_empty());
+    let test_attrs: Vec<_> = doc_attrs.into_iter()
         .filter(|a| a.check_name(sym::test))
         .collect();
     let attrs = test_attrs.iter().flat_map(|a| a.meta_item_list().unwrap_or(&[]));
@@ -182,6 +208,26 @@ enum TestFailure {
     ExecutionFailure(process::Output),
     /// The test is marked `should_panic` but the test binary executed successfully.
     UnexpectedRunPass,
+    /// The test binary panicked, but its output does not contain the message given with
+    /// `should_panic="..."`.
+    UnexpectedPanicMessage(String, process::Output),
+    /// The test asks for an edition which the compiler does not know.
+    UnknownEdition(String),
+}
+
+/// The directory doctest binaries are compiled into.
+enum DirState {
+    Temp(tempfile::TempDir),
+    Perm(PathBuf),
+}
+
+impl DirState {
+    fn path(&self) -> &std::path::Path {
+        match self {
+            DirState::Temp(t) => t.path(),
+            DirState::Perm(p) => p.as_path(),
+        }
+    }
 }
 
 fn run_test(
@@ -191,6 +237,7 @@ fn run_test(
     line: usize,
     options: Options,
     should_panic: bool,
+    expected_panic: Option<&str>,
     no_run: bool,
     as_test_harness: bool,
     runtool: Option<String>,
@@ -200,15 +247,17 @@ fn run_test(
     mut error_codes: Vec<String>,
     opts: &TestOptions,
     edition: Edition,
+    times: &mut TestTimes,
 ) -> Result<(), TestFailure> {
-    let (test, line_offset) = match panic::catch_unwind(|| {
-        make_test(test, Some(cratename), as_test_harness, opts, edition)
+    let (test, line_offset, checks_panic) = match panic::catch_unwind(|| {
+        make_test_checking_panic(test, Some(cratename), as_test_harness, opts, edition,
+                                 expected_panic)
     }) {
-        Ok((test, line_offset)) => (test, line_offset),
+        Ok(made) => made,
         Err(cause) if cause.is::<errors::FatalErrorMarker>() => {
             // If the parser used by `make_test` panicked due to a fatal error, pass the test code
             // through unchanged. The error will be reported during compilation.
-            (test.to_owned(), 0)
+            (test.to_owned(), 0, false)
         },
         Err(cause) => panic::resume_unwind(cause),
     };
@@ -220,30 +269,8 @@ fn run_test(
         _ => PathBuf::from(r"doctest.rs"),
     };
 
-    enum DirState {
-        Temp(tempfile::TempDir),
-        Perm(PathBuf),
-    }
-
-    impl DirState {
-        fn path(&self) -> &std::path::Path {
-            match self {
-                DirState::Temp(t) => t.path(),
-                DirState::Perm(p) => p.as_path(),
-            }
-        }
-    }
-
-    let outdir = if let Some(mut path) = options.persist_doctests {
-        path.push(format!("{}_{}",
-            filename
-                .to_string()
-                .rsplit('/')
-                .next()
-                .unwrap()
-                .replace(".", "_"),
-                line)
-        );
+    let outdir = if let Some(ref dir) = options.persist_doctests {
+        let path = dir.join(persisted_dir_name(filename, line, test));
         std::fs::create_dir_all(&path)
             .expect("Couldn't create directory for doctest executables");
 
@@ -256,53 +283,35 @@ fn run_test(
     };
     let output_file = outdir.path().join("rust_out");
 
-    let rustc_binary = options.test_builder.as_ref().map(|v| &**v).unwrap_or_else(|| {
-        rustc_interface::util::rustc_path().expect("found rustc")
-    });
-    let mut compiler = Command::new(&rustc_binary);
-    compiler.arg("--crate-type").arg("bin");
-    for cfg in &options.cfgs {
-        compiler.arg("--cfg").arg(&cfg);
-    }
-    if let Some(sysroot) = options.maybe_sysroot {
-        compiler.arg("--sysroot").arg(sysroot);
-    }
-    compiler.arg("--edition").arg(&edition.to_string());
+    let mut compiler = rustc_command(&options, &target, edition, &output_file);
     compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
     compiler.env("UNSTABLE_RUSTDOC_TEST_LINE",
                  format!("{}", line as isize - line_offset as isize));
-    compiler.arg("-o").arg(&output_file);
     if as_test_harness {
         compiler.arg("--test");
     }
-    for lib_str in &options.lib_strs {
-        compiler.arg("-L").arg(&lib_str);
-    }
-    for extern_str in &options.extern_strs {
-        compiler.arg("--extern").arg(&extern_str);
-    }
-    compiler.arg("-Ccodegen-units=1");
-    for codegen_options_str in &options.codegen_options_strs {
-        compiler.arg("-C").arg(&codegen_options_str);
-    }
-    for debugging_option_str in &options.debugging_options_strs {
-        compiler.arg("-Z").arg(&debugging_option_str);
-    }
     if no_run {
         compiler.arg("--emit=metadata");
     }
-    compiler.arg("--target").arg(target.to_string());
+    // The error codes are read from the diagnostics, rather than looked for in their text, which
+    // may mention other error codes.
+    let check_error_codes = compile_fail && !error_codes.is_empty();
+    if check_error_codes {
+        compiler.arg("--error-format=json");
+    }
 
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
     compiler.stderr(Stdio::piped());
 
+    let start = Instant::now();
     let mut child = compiler.spawn().expect("Failed to spawn rustc process");
     {
         let stdin = child.stdin.as_mut().expect("Failed to open stdin");
         stdin.write_all(test.as_bytes()).expect("could write out test sources");
     }
     let output = child.wait_with_output().expect("Failed to read stdout");
+    times.compile = Some(start.elapsed());
 
     struct Bomb<'a>(&'a str);
     impl Drop for Bomb<'_> {
@@ -312,6 +321,11 @@ fn run_test(
     }
 
     let out = str::from_utf8(&output.stderr).unwrap();
+    let (out, emitted_codes) = if check_error_codes {
+        parse_json_diagnostics(out)
+    } else {
+        (out.to_owned(), vec![])
+    };
     let _bomb = Bomb(&out);
     match (output.status.success(), compile_fail) {
         (true, true) => {
@@ -320,7 +334,7 @@ fn run_test(
         (true, false) => {}
         (false, true) => {
             if !error_codes.is_empty() {
-                error_codes.retain(|err| !out.contains(err));
+                error_codes.retain(|err| !emitted_codes.contains(err));
 
                 if !error_codes.is_empty() {
                     return Err(TestFailure::MissingErrorCodes(error_codes));
@@ -337,6 +351,154 @@ fn run_test(
     }
 
     // Run the code!
+    let start = Instant::now();
+    let result = if checks_panic {
+        // The binary exits successfully once it panicked with the expected message.
+        run_binary(&output_file, &[], runtool, runtool_args, false, None)
+    } else {
+        run_binary(&output_file, &[], runtool, runtool_args, should_panic, expected_panic)
+    };
+    times.run = Some(start.elapsed());
+    result
+}
+
+/// Returns the name of the directory a doctest binary is persisted in with `--persist-doctests`.
+/// It is made of the path of the file and the line of the doctest, and a hash of its code, so
+/// that it stays the same from one run to the next unless the doctest changes.
+fn persisted_dir_name(filename: &FileName, line: usize, test: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    test.hash(&mut hasher);
+    let file = filename.to_string()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+        .collect::<String>();
+    format!("{}_{}_{:016x}", file.trim_start_matches('_'), line, hasher.finish())
+}
+
+/// An entry of the manifest written with `--persist-doctests`, mapping the binary of a doctest
+/// back to the documentation it comes from.
+#[derive(Serialize)]
+struct PersistedDoctest {
+    /// The name of the test, as displayed by the test harness.
+    name: String,
+    file: String,
+    line: usize,
+    /// The directory the binary of the doctest is compiled into, relative to the one given to
+    /// `--persist-doctests`.
+    directory: String,
+    /// The argument to give to the binary in `merged_doctests` to run the doctest, if it was
+    /// compiled along with the others with `--merge-doctests`. The doctest is only compiled into
+    /// its own directory if the merged binary does not compile.
+    merged_index: Option<usize>,
+}
+
+/// The name of the manifest written with `--persist-doctests`.
+const PERSISTED_MANIFEST: &str = "doctests.json";
+
+/// Runs a doctest from the binary of the bundle it was added to, or returns `None` if the bundle
+/// does not compile, in which case the doctest has to be compiled separately.
+fn run_bundled_test(
+    bundle: &Mutex<Bundle>,
+    index: usize,
+    options: &Options,
+    target: &TargetTriple,
+    config: &LangString,
+    runtool: &Option<String>,
+    runtool_args: &[String],
+    times: &mut TestTimes,
+) -> Option<Result<(), TestFailure>> {
+    let binary = bundle.lock().unwrap().binary(options, target)?;
+    if config.no_run {
+        return Some(Ok(()));
+    }
+    let start = Instant::now();
+    // The doctests of a bundle expecting a panic message check it themselves, see `main_wrapper`.
+    let result = run_binary(&binary, &[index.to_string()], runtool.clone(), runtool_args.to_vec(),
+                            config.should_panic && config.expected_panic.is_none(), None);
+    times.run = Some(start.elapsed());
+    Some(result)
+}
+
+/// The parts of the diagnostics emitted by rustc with `--error-format=json` which doctests use.
+#[derive(Deserialize)]
+struct JsonDiagnostic {
+    code: Option<JsonDiagnosticCode>,
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct JsonDiagnosticCode {
+    code: String,
+}
+
+/// Returns the rendered text of the diagnostics emitted by rustc with `--error-format=json`, along
+/// with their error codes. The lines of `output` which are not diagnostics are kept as they are.
+fn parse_json_diagnostics(output: &str) -> (String, Vec<String>) {
+    let mut rendered = String::new();
+    let mut codes = vec![];
+    for line in output.lines() {
+        match serde_json::from_str::<JsonDiagnostic>(line) {
+            Ok(diagnostic) => {
+                codes.extend(diagnostic.code.map(|code| code.code));
+                rendered.push_str(&diagnostic.rendered.unwrap_or_default());
+            }
+            Err(_) => {
+                rendered.push_str(line);
+                rendered.push('\n');
+            }
+        }
+    }
+    (rendered, codes)
+}
+
+/// Returns the command compiling a doctest binary to `output_file`, with the options of the
+/// crate being tested.
+fn rustc_command(
+    options: &Options,
+    target: &TargetTriple,
+    edition: Edition,
+    output_file: &Path,
+) -> Command {
+    let rustc_binary = options.test_builder.as_ref().map(|v| &**v).unwrap_or_else(|| {
+        rustc_interface::util::rustc_path().expect("found rustc")
+    });
+    let mut compiler = Command::new(&rustc_binary);
+    compiler.arg("--crate-type").arg("bin");
+    for cfg in &options.cfgs {
+        compiler.arg("--cfg").arg(&cfg);
+    }
+    if let Some(ref sysroot) = options.maybe_sysroot {
+        compiler.arg("--sysroot").arg(sysroot);
+    }
+    compiler.arg("--edition").arg(&edition.to_string());
+    compiler.arg("-o").arg(output_file);
+    for lib_str in &options.lib_strs {
+        compiler.arg("-L").arg(&lib_str);
+    }
+    for extern_str in &options.extern_strs {
+        compiler.arg("--extern").arg(&extern_str);
+    }
+    compiler.arg("-Ccodegen-units=1");
+    for codegen_options_str in &options.codegen_options_strs {
+        compiler.arg("-C").arg(&codegen_options_str);
+    }
+    for debugging_option_str in &options.debugging_options_strs {
+        compiler.arg("-Z").arg(&debugging_option_str);
+    }
+    compiler.arg("--target").arg(target.to_string());
+    compiler
+}
+
+/// Runs a compiled doctest binary with `args`, through the runtool if there is one. If it should
+/// panic with `expected_panic`, the message is looked for in its standard error.
+fn run_binary(
+    output_file: &Path,
+    args: &[String],
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    should_panic: bool,
+    expected_panic: Option<&str>,
+) -> Result<(), TestFailure> {
     let mut cmd;
 
     if let Some(tool) = runtool {
@@ -346,6 +508,7 @@ fn run_test(
     } else {
         cmd = Command::new(output_file);
     }
+    cmd.args(args);
 
     match cmd.output() {
         Err(e) => return Err(TestFailure::ExecutionError(e)),
@@ -354,6 +517,10 @@ fn run_test(
                 return Err(TestFailure::UnexpectedRunPass);
             } else if !should_panic && !out.status.success() {
                 return Err(TestFailure::ExecutionFailure(out));
+            } else if let Some(expected) = expected_panic.filter(|_| should_panic) {
+                if !String::from_utf8_lossy(&out.stderr).contains(expected) {
+                    return Err(TestFailure::UnexpectedPanicMessage(expected.to_owned(), out));
+                }
             }
         }
     }
@@ -374,6 +541,22 @@ pub fn make_test(s: &str,
                  opts: &TestOptions,
                  edition: Edition)
                  -> (String, usize) {
+    let (prog, line_offset, _) =
+        make_test_checking_panic(s, cratename, dont_insert_main, opts, edition, None);
+    (prog, line_offset)
+}
+
+/// Like `make_test`, but for a test which should panic with `expected_panic`. The `main` function
+/// inserted around the test then checks the message of the panic, and exits successfully if it
+/// contains the expected one, in which case `true` is returned as well. Doctests with a `main`
+/// function of their own are left as they are.
+fn make_test_checking_panic(s: &str,
+                            cratename: Option<&str>,
+                            dont_insert_main: bool,
+                            opts: &TestOptions,
+                            edition: Edition,
+                            expected_panic: Option<&str>)
+                            -> (String, usize, bool) {
     let (crate_attrs, everything_else, crates) = partition_source(s);
     let everything_else = everything_else.trim();
     let mut line_offset = 0;
@@ -399,6 +582,94 @@ pub fn make_test(s: &str,
     prog.push_str(&crate_attrs);
     prog.push_str(&crates);
 
+    let (already_has_main, already_has_extern_crate) =
+        scan_items(s, crates, everything_else, cratename, edition);
+
+    // Don't inject `extern crate std` because it's already injected by the
+    // compiler.
+    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
+        if let Some(cratename) = cratename {
+            // Make sure its actually used if not included.
+            if s.contains(cratename) {
+                prog.push_str(&format!("extern crate {};\n", cratename));
+                line_offset += 1;
+            }
+        }
+    }
+
+    // FIXME: This code cannot yet handle no_std test cases yet
+    let checks_panic = if dont_insert_main || already_has_main || prog.contains("![no_std]") {
+        prog.push_str(everything_else);
+        false
+    } else {
+        let (main_pre, main_post) = main_wrapper(everything_else, expected_panic);
+        prog.extend([&*main_pre, everything_else, &*main_post].iter().cloned());
+        line_offset += 1;
+        expected_panic.is_some()
+    };
+
+    debug!("final doctest:\n{}", prog);
+
+    (prog, line_offset, checks_panic)
+}
+
+/// Returns the code to insert before and after the code of a doctest without a `main` function to
+/// wrap it into one. With `expected_panic`, the code is run under `catch_unwind` and the function
+/// checks that it panics with a message containing the expected one, like the test harness does
+/// for `#[should_panic(expected = "...")]`.
+fn main_wrapper(everything_else: &str, expected_panic: Option<&str>) -> (String, String) {
+    let returns_result = everything_else.trim_end().ends_with("(())");
+    let expected = match expected_panic {
+        Some(expected) => expected,
+        None if returns_result => {
+            return ("fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> {".to_owned(),
+                    "}\n_inner().unwrap() }".to_owned());
+        }
+        None => return ("fn main() {\n".to_owned(), "\n}".to_owned()),
+    };
+    let (body_pre, body_post) = if returns_result {
+        ("fn _inner() -> Result<(), impl core::fmt::Debug> {", "}\n_inner().unwrap()")
+    } else {
+        ("\n", "\n")
+    };
+    (format!("fn main() {{ let result = std::panic::catch_unwind(|| {{{}", body_pre),
+     format!("{}}});\nlet expected = {:?};\n{}}}", body_post, expected, PANIC_CHECK))
+}
+
+/// Checks the `result` of running a doctest which should panic with a message containing
+/// `expected`, and reports the failures the way the test harness does.
+const PANIC_CHECK: &str = "\
+match result {
+    Ok(()) => {
+        eprintln!(\"note: test did not panic as expected\");
+        std::process::exit(1);
+    }
+    Err(payload) => {
+        let message = payload.downcast_ref::<&str>().map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        if !message.contains(expected) {
+            eprintln!(\"panic did not contain expected string\\n      panic message: `{:?}`,\\n \\
+                       expected substring: `{:?}`\", message, expected);
+            std::process::exit(1);
+        }
+    }
+}
+";
+
+/// Parses the items of a doctest, split by `partition_source`, to find whether it has a `main`
+/// function and whether it has an `extern crate` of `cratename` already.
+///
+/// # Panics
+///
+/// Like `make_test`, this function panics if the parser encounters a fatal error.
+fn scan_items(
+    s: &str,
+    crates: String,
+    everything_else: &str,
+    cratename: Option<&str>,
+    edition: Edition,
+) -> (bool, bool) {
     // Uses libsyntax to parse the doctest and find if there's a main fn and the extern
     // crate already is included.
     let (already_has_main, already_has_extern_crate, found_macro) = with_globals(edition, || {
@@ -408,7 +679,7 @@ pub fn make_test(s: &str,
         use errors::Handler;
 
         let filename = FileName::anon_source_code(s);
-        let source = crates + &everything_else;
+        let source = crates + everything_else;
 
         // Any errors in parsing should also appear when the doctest is compiled for real, so just
         // send all the errors that libsyntax emits directly into a `Sink` instead of stderr.
@@ -497,36 +768,7 @@ pub fn make_test(s: &str,
         already_has_main
     };
 
-    // Don't inject `extern crate std` because it's already injected by the
-    // compiler.
-    if !already_has_extern_crate && !opts.no_crate_inject && cratename != Some("std") {
-        if let Some(cratename) = cratename {
-            // Make sure its actually used if not included.
-            if s.contains(cratename) {
-                prog.push_str(&format!("extern crate {};\n", cratename));
-                line_offset += 1;
-            }
-        }
-    }
-
-    // FIXME: This code cannot yet handle no_std test cases yet
-    if dont_insert_main || already_has_main || prog.contains("![no_std]") {
-        prog.push_str(everything_else);
-    } else {
-        let returns_result = everything_else.trim_end().ends_with("(())");
-        let (main_pre, main_post) = if returns_result {
-            ("fn main() { fn _inner() -> Result<(), impl core::fmt::Debug> {",
-             "}\n_inner().unwrap() }")
-        } else {
-            ("fn main() {\n", "\n}")
-        };
-        prog.extend([main_pre, everything_else, main_post].iter().cloned());
-        line_offset += 1;
-    }
-
-    debug!("final doctest:\n{}", prog);
-
-    (prog, line_offset)
+    (already_has_main, already_has_extern_crate)
 }
 
 // FIXME(aburka): use a real parser to deal with multiline attributes
@@ -605,6 +847,10 @@ pub trait Tester {
         0
     }
     fn register_header(&mut self, _name: &str, _level: u32) {}
+    /// The prefix of hidden lines in the code blocks which do not give their own.
+    fn hidden_line_prefix(&self) -> Option<&str> {
+        None
+    }
 }
 
 pub struct Collector {
@@ -640,12 +886,32 @@ pub struct Collector {
     position: Span,
     source_map: Option<Lrc<SourceMap>>,
     filename: Option<PathBuf>,
+    /// The doctests compiled into a single binary, with `--merge-doctests`.
+    bundle: Option<Arc<Mutex<Bundle>>>,
+    /// The doctests whose binaries are kept, with `--persist-doctests`.
+    persisted: Vec<PersistedDoctest>,
+    /// The number of leading `names` which are modules, for the per module totals of
+    /// `--doctest-timings`.
+    module_len: usize,
+    /// The time spent on each doctest, with `--doctest-timings`.
+    timings: Option<Arc<Mutex<Timings>>>,
 }
 
 impl Collector {
     pub fn new(cratename: String, options: Options, use_headers: bool, opts: TestOptions,
                source_map: Option<Lrc<SourceMap>>, filename: Option<PathBuf>,
                enable_per_target_ignores: bool) -> Collector {
+        let bundle = if options.merge_doctests {
+            let bundle = Bundle::new(cratename.clone(), opts.clone(), options.edition);
+            Some(Arc::new(Mutex::new(bundle)))
+        } else {
+            None
+        };
+        let timings = if options.doctest_timings {
+            Some(Arc::new(Mutex::new(Timings::default())))
+        } else {
+            None
+        };
         Collector {
             tests: Vec::new(),
             names: Vec::new(),
@@ -657,6 +923,34 @@ impl Collector {
             position: DUMMY_SP,
             source_map,
             filename,
+            bundle,
+            persisted: Vec::new(),
+            module_len: 0,
+            timings,
+        }
+    }
+
+    /// Returns the function printing the report of `--doctest-timings` once the doctests have
+    /// run, to give to the test harness.
+    pub fn timings_report(&self) -> impl FnOnce() {
+        let timings = self.timings.clone();
+        let format = self.options.output_format;
+        move || {
+            if let Some(timings) = timings {
+                timings.lock().unwrap().report(format);
+            }
+        }
+    }
+
+    /// Writes the manifest of the doctest binaries kept with `--persist-doctests`, once all the
+    /// doctests are collected.
+    pub fn write_persisted_manifest(&self) {
+        if let Some(ref dir) = self.options.persist_doctests {
+            fs::create_dir_all(dir).expect("Couldn't create directory for doctest executables");
+            let manifest = serde_json::to_string_pretty(&self.persisted)
+                .expect("Couldn't serialize the doctest manifest");
+            fs::write(dir.join(PERSISTED_MANIFEST), manifest)
+                .expect("Couldn't write the doctest manifest");
         }
     }
 
@@ -699,31 +993,66 @@ impl Tester for Collector {
         let runtool_args = self.options.runtool_args.clone();
         let target = self.options.target.clone();
         let target_str = target.to_string();
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => {
+                ignores.iter().any(|s| target_str.contains(s))
+            },
+        };
+        // Ignored doctests may not even compile, so they are kept out of the bundle.
+        let bundled = match self.bundle {
+            Some(ref bundle) if !ignore => {
+                let index = bundle.lock().unwrap().add(&test, &config, edition);
+                index.map(|index| (bundle.clone(), index))
+            }
+            _ => None,
+        };
+        if self.options.persist_doctests.is_some() {
+            self.persisted.push(PersistedDoctest {
+                name: name.clone(),
+                file: filename.to_string(),
+                line,
+                directory: persisted_dir_name(&filename, line, &test),
+                merged_index: bundled.as_ref().map(|&(_, index)| index),
+            });
+        }
+
+        let module = std::iter::once(&self.cratename)
+            .chain(&self.names[..self.module_len.min(self.names.len())])
+            .map(|name| &**name)
+            .collect::<Vec<_>>()
+            .join("::");
+        let timings = self.timings.clone();
 
         debug!("creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name.clone()),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => {
-                        ignores.iter().any(|s| target_str.contains(s))
-                    },
-                },
+                ignore,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
                 test_type: testing::TestType::DocTest,
             },
             testfn: testing::DynTestFn(box move || {
-                let res = run_test(
+                let mut times = TestTimes::default();
+                let res = if let Some(ref edition) = config.unknown_edition {
+                    Some(Err(TestFailure::UnknownEdition(edition.clone())))
+                } else {
+                    bundled.and_then(|(bundle, index)| {
+                        run_bundled_test(&bundle, index, &options, &target, &config, &runtool,
+                                         &runtool_args, &mut times)
+                    })
+                };
+                let res = res.unwrap_or_else(|| run_test(
                     &test,
                     &cratename,
                     &filename,
                     line,
                     options,
                     config.should_panic,
+                    config.expected_panic.as_ref().map(|expected| &**expected),
                     config.no_run,
                     config.test_harness,
                     runtool,
@@ -733,7 +1062,11 @@ impl Tester for Collector {
                     config.error_codes,
                     &opts,
                     edition,
-                );
+                    &mut times,
+                ));
+                if let Some(timings) = timings {
+                    timings.lock().unwrap().record(name, module, times);
+                }
 
                 if let Err(err) = res {
                     match err {
@@ -746,9 +1079,21 @@ impl Tester for Collector {
                         TestFailure::UnexpectedRunPass => {
                             eprint!("Test executable succeeded, but it's marked `should_panic`.");
                         }
+                        TestFailure::UnexpectedPanicMessage(expected, out) => {
+                            eprintln!("Test executable panicked, but not with the expected \
+                                       message `{}`.", expected);
+                            let stderr = str::from_utf8(&out.stderr).unwrap_or_default();
+                            if !stderr.is_empty() {
+                                eprintln!("\nstderr:\n{}", stderr);
+                            }
+                        }
                         TestFailure::MissingErrorCodes(codes) => {
                             eprint!("Some expected error codes were not found: {:?}", codes);
                         }
+                        TestFailure::UnknownEdition(edition) => {
+                            eprint!("Unknown edition `{}`, the available editions are {}.",
+                                    edition, EDITION_NAME_LIST);
+                        }
                         TestFailure::ExecutionError(err) => {
                             eprint!("Couldn't run the test: {}", err);
                             if err.kind() == io::ErrorKind::PermissionDenied {
@@ -806,6 +1151,10 @@ impl Tester for Collector {
         }
     }
 
+    fn hidden_line_prefix(&self) -> Option<&str> {
+        self.opts.hidden_line_prefix.as_ref().map(|prefix| &**prefix)
+    }
+
     fn register_header(&mut self, name: &str, level: u32) {
         if self.use_headers {
             // We use these headings as test names, so it's good if
@@ -853,6 +1202,20 @@ struct HirCollector<'a, 'hir> {
 }
 
 impl<'a, 'hir> HirCollector<'a, 'hir> {
+    /// Returns the file included with `#[doc(include = "filename")]` by the attribute at `span`,
+    /// which the compiler loaded into the source map, resolving `filename` the same way.
+    fn included_file(&self, span: Span, filename: &str) -> Option<Lrc<SourceFile>> {
+        let source_map = self.sess.source_map();
+        let mut path = PathBuf::from(filename);
+        if !path.is_absolute() {
+            path = match source_map.span_to_unmapped_path(span.source_callsite()) {
+                FileName::Real(base) | FileName::DocTest(base, _) => base.with_file_name(path),
+                _ => return None,
+            };
+        }
+        source_map.get_source_file(&FileName::Real(path))
+    }
+
     fn visit_testable<F: FnOnce(&mut Self)>(&mut self,
                                             name: String,
                                             attrs: &[ast::Attribute],
@@ -871,15 +1234,36 @@ impl<'a, 'hir> HirCollector<'a, 'hir> {
 
         attrs.collapse_doc_comments();
         attrs.unindent_doc_comments();
+        // The doctests of the files included with `#[doc(include = "...")]` are collected on
+        // their own, so that they are reported at their line in the included file.
+        let (included, doc_strings): (Vec<_>, Vec<_>) = attrs.doc_strings.iter()
+            .map(|fragment| match *fragment {
+                DocFragment::Include(_, span, ref filename, ref contents) => {
+                    match self.included_file(span, filename) {
+                        Some(file) => Ok((file, contents)),
+                        None => Err(fragment),
+                    }
+                }
+                _ => Err(fragment),
+            })
+            .partition(|fragment| fragment.is_ok());
         // The collapse-docs pass won't combine sugared/raw doc attributes, or included files with
         // anything else, this will combine them for us.
-        if let Some(doc) = attrs.collapsed_doc_value() {
+        let doc = doc_strings.into_iter().filter_map(|fragment| fragment.err()).collect::<String>();
+        if !doc.is_empty() {
             self.collector.set_position(attrs.span.unwrap_or(DUMMY_SP));
             markdown::find_testable_code(&doc,
                                          self.collector,
                                          self.codes,
                                          self.collector.enable_per_target_ignores);
         }
+        for (file, contents) in included.into_iter().filter_map(|fragment| fragment.ok()) {
+            self.collector.set_position(Span::with_root_ctxt(file.start_pos, file.start_pos));
+            markdown::find_testable_code(contents,
+                                         self.collector,
+                                         self.codes,
+                                         self.collector.enable_per_target_ignores);
+        }
 
         nested(self);
 
@@ -901,9 +1285,14 @@ impl<'a, 'hir> intravisit::Visitor<'hir> for HirCollector<'a, 'hir> {
             item.ident.to_string()
         };
 
+        let module_len = self.collector.module_len;
+        if let hir::ItemKind::Mod(..) = item.kind {
+            self.collector.module_len = self.collector.names.len() + 1;
+        }
         self.visit_testable(name, &item.attrs, |this| {
             intravisit::walk_item(this, item);
         });
+        self.collector.module_len = module_len;
     }
 
     fn visit_trait_item(&mut self, item: &'hir hir::TraitItem) {
@@ -0,0 +1,172 @@
+//! Merged compilation of doctests.
+//!
+//! With `--merge-doctests`, the doctests of a crate which allow it are wrapped into a module each
+//! and compiled together, into a single binary running the doctest whose index it is given as
+//! argument. This saves invoking rustc once per doctest. The doctests which need a crate of their
+//! own, like the ones with crate attributes or expected to fail to compile, are still compiled
+//! separately, and so are all the doctests of a bundle which does not compile, so that the
+//! errors are reported for the doctests they come from.
+
+use rustc_target::spec::TargetTriple;
+use syntax::edition::Edition;
+use std::fs;
+use std::io::Write;
+use std::panic;
+use std::path::PathBuf;
+use std::process::Stdio;
+use tempfile::Builder as TempFileBuilder;
+
+use crate::config::Options;
+use crate::html::markdown::LangString;
+
+use super::{main_wrapper, partition_source, rustc_command, scan_items, DirState, TestOptions};
+
+/// The doctests compiled into a single binary.
+pub struct Bundle {
+    cratename: String,
+    opts: TestOptions,
+    /// The edition the binary is compiled with, which is the one of the crate.
+    edition: Edition,
+    /// The source of the module wrapping each doctest of the bundle.
+    modules: Vec<String>,
+    /// Whether a doctest refers to the crate being tested without an `extern crate` of its own.
+    uses_crate: bool,
+    /// The directory holding the binary once the first doctest of the bundle runs, or `None` if
+    /// the bundle failed to compile.
+    compiled: Option<Option<DirState>>,
+}
+
+impl Bundle {
+    crate fn new(cratename: String, opts: TestOptions, edition: Edition) -> Bundle {
+        Bundle {
+            cratename,
+            opts,
+            edition,
+            modules: Vec::new(),
+            uses_crate: false,
+            compiled: None,
+        }
+    }
+
+    /// Adds a doctest to the bundle if it can be compiled along with the others, and returns its
+    /// index in the bundle.
+    crate fn add(&mut self, test: &str, config: &LangString, edition: Edition) -> Option<usize> {
+        // Paths in the modules of 2015 edition crates are relative to the module rather than to
+        // the crate root, which breaks the paths to the crate being tested.
+        if config.compile_fail || config.test_harness || config.unknown_edition.is_some() ||
+           edition != self.edition || edition == Edition::Edition2015
+        {
+            return None;
+        }
+        let (crate_attrs, everything_else, crates) = partition_source(test);
+        // Crate attributes and `#[macro_use] extern crate` only work at the crate root.
+        if crate_attrs.lines().any(|line| line.trim_start().starts_with("#![")) ||
+           crates.contains("#[macro_use]")
+        {
+            return None;
+        }
+        let everything_else = everything_else.trim();
+
+        let cratename = Some(&*self.cratename);
+        let scanned = panic::catch_unwind(|| {
+            scan_items(test, crates.clone(), everything_else, cratename, edition)
+        });
+        let (has_main, has_extern_crate) = match scanned {
+            Ok(scanned) => scanned,
+            // The doctest does not parse, so let it report its errors on its own.
+            Err(cause) if cause.is::<errors::FatalErrorMarker>() => return None,
+            Err(cause) => panic::resume_unwind(cause),
+        };
+        // The `main` function of a doctest may return a `Result` which the one of the bundle
+        // would have to report like the standard library does.
+        if has_main {
+            return None;
+        }
+        if !has_extern_crate && !self.opts.no_crate_inject && self.cratename != "std" &&
+           test.contains(&self.cratename)
+        {
+            self.uses_crate = true;
+        }
+
+        let index = self.modules.len();
+        let expected_panic = config.expected_panic.as_ref().map(|expected| &**expected);
+        let (main_pre, main_post) = main_wrapper(everything_else, expected_panic);
+        self.modules.push(format!("mod doctest_{} {{\n{}pub {}{}{}\n}}\n",
+                                  index, crates, main_pre, everything_else, main_post));
+        Some(index)
+    }
+
+    fn source(&self) -> String {
+        let mut prog = String::new();
+        // Like for the doctests compiled separately, see `make_test`.
+        if self.opts.attrs.is_empty() && !self.opts.display_warnings {
+            prog.push_str("#![allow(unused)]\n");
+        }
+        for attr in &self.opts.attrs {
+            prog.push_str(&format!("#![{}]\n", attr));
+        }
+        if self.uses_crate {
+            prog.push_str(&format!("extern crate {};\n", self.cratename));
+        }
+        for module in &self.modules {
+            prog.push_str(module);
+        }
+
+        prog.push_str("fn main() {\n");
+        prog.push_str("    match std::env::args().nth(1).map(|arg| arg.parse::<usize>()) {\n");
+        for index in 0..self.modules.len() {
+            prog.push_str(&format!("        Some(Ok({0})) => doctest_{0}::main(),\n", index));
+        }
+        prog.push_str("        _ => std::process::exit(2),\n");
+        prog.push_str("    }\n");
+        prog.push_str("}\n");
+        debug!("merged doctests:\n{}", prog);
+        prog
+    }
+
+    /// Compiles the bundle the first time one of its doctests runs, and returns the path of the
+    /// binary, or `None` if the bundle does not compile.
+    crate fn binary(&mut self, options: &Options, target: &TargetTriple) -> Option<PathBuf> {
+        if self.compiled.is_none() {
+            self.compiled = Some(self.compile(options, target));
+        }
+        self.compiled.as_ref().and_then(|dir| dir.as_ref()).map(|dir| dir.path().join("rust_out"))
+    }
+
+    fn compile(&self, options: &Options, target: &TargetTriple) -> Option<DirState> {
+        let outdir = if let Some(ref path) = options.persist_doctests {
+            let path = path.join("merged_doctests");
+            fs::create_dir_all(&path)
+                .expect("Couldn't create directory for doctest executables");
+            DirState::Perm(path)
+        } else {
+            DirState::Temp(TempFileBuilder::new()
+                            .prefix("rustdoctest")
+                            .tempdir()
+                            .expect("rustdoc needs a tempdir"))
+        };
+        let output_file = outdir.path().join("rust_out");
+
+        let mut compiler = rustc_command(options, target, self.edition, &output_file);
+        compiler.arg("-");
+        compiler.stdin(Stdio::piped());
+        // The errors are reported by the doctests once they are compiled separately.
+        compiler.stdout(Stdio::null());
+        compiler.stderr(Stdio::null());
+
+        let mut child = compiler.spawn().expect("Failed to spawn rustc process");
+        {
+            let stdin = child.stdin.as_mut().expect("Failed to open stdin");
+            stdin.write_all(self.source().as_bytes()).expect("could write out test sources");
+        }
+        let status = child.wait().expect("Failed to wait for rustc");
+        if status.success() {
+            Some(outdir)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
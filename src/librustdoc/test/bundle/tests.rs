@@ -0,0 +1,56 @@
+use super::Bundle;
+use crate::html::markdown::{ErrorCodes, LangString};
+use crate::test::TestOptions;
+use syntax::edition::Edition;
+
+fn bundle() -> Bundle {
+    Bundle::new("asdf".to_string(), TestOptions::default(), Edition::Edition2018)
+}
+
+fn lang_string(s: &str) -> LangString {
+    LangString::parse(s, ErrorCodes::Yes, false)
+}
+
+#[test]
+fn bundle_wraps_doctests_into_modules() {
+    let mut bundle = bundle();
+    let rust = lang_string("rust");
+    assert_eq!(bundle.add("assert_eq!(2+2, 4);", &rust, Edition::Edition2018), Some(0));
+    assert_eq!(bundle.add("use asdf::qwop;\nqwop();", &rust, Edition::Edition2018), Some(1));
+    let expected =
+"#![allow(unused)]
+extern crate asdf;
+mod doctest_0 {
+pub fn main() {
+assert_eq!(2+2, 4);
+}
+}
+mod doctest_1 {
+pub fn main() {
+use asdf::qwop;
+qwop();
+}
+}
+fn main() {
+    match std::env::args().nth(1).map(|arg| arg.parse::<usize>()) {
+        Some(Ok(0)) => doctest_0::main(),
+        Some(Ok(1)) => doctest_1::main(),
+        _ => std::process::exit(2),
+    }
+}
+";
+    assert_eq!(bundle.source(), expected);
+}
+
+#[test]
+fn bundle_keeps_out_doctests_needing_their_own_crate() {
+    let mut bundle = bundle();
+    let rust = lang_string("rust");
+    let edition = Edition::Edition2018;
+    assert_eq!(bundle.add("#![no_std]\nlet x = 1;", &rust, edition), None);
+    assert_eq!(bundle.add("#[macro_use] extern crate asdf;\nqwop!();", &rust, edition), None);
+    assert_eq!(bundle.add("fn main() {}", &rust, edition), None);
+    assert_eq!(bundle.add("let x: u8 = \"\";", &lang_string("compile_fail"), edition), None);
+    assert_eq!(bundle.add("let x = 1;", &lang_string("rust"), Edition::Edition2015), None);
+    assert_eq!(bundle.add("let x = 1;", &lang_string("should_panic"), edition), Some(0));
+}
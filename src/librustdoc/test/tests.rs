@@ -1,4 +1,7 @@
-use super::{TestOptions, make_test};
+use super::{TestOptions, make_test, make_test_checking_panic, parse_json_diagnostics};
+use super::persisted_dir_name;
+use std::path::PathBuf;
+use syntax_pos::FileName;
 use syntax::edition::DEFAULT_EDITION;
 
 #[test]
@@ -59,6 +62,7 @@ fn make_test_no_crate_inject() {
         no_crate_inject: true,
         display_warnings: false,
         attrs: vec![],
+        hidden_line_prefix: None,
     };
     let input =
 "use asdf::qwop;
@@ -297,3 +301,53 @@ test_wrapper! {
     let output = make_test(input, Some("my_crate"), false, &opts, DEFAULT_EDITION);
     assert_eq!(output, (expected, 1));
 }
+
+#[test]
+fn make_test_expected_panic() {
+    let opts = TestOptions::default();
+    let input =
+"let v: Vec<u8> = vec![];
+v[0];";
+
+    let (output, line_offset, checks_panic) = make_test_checking_panic(
+        input, None, false, &opts, DEFAULT_EDITION, Some("out of bounds"));
+    assert!(checks_panic);
+    assert_eq!(line_offset, 2);
+    assert!(output.starts_with("#![allow(unused)]
+fn main() { let result = std::panic::catch_unwind(|| {
+let v: Vec<u8> = vec![];
+v[0];
+});
+let expected = \"out of bounds\";
+match result {"));
+
+    // The message cannot be checked without inserting the `main` function.
+    let input = "fn main() { panic!(\"out of bounds\") }";
+    let (output, _, checks_panic) = make_test_checking_panic(
+        input, None, false, &opts, DEFAULT_EDITION, Some("out of bounds"));
+    assert!(!checks_panic);
+    assert_eq!(output, format!("#![allow(unused)]\n{}", input));
+}
+
+#[test]
+fn parse_json_diagnostics_codes() {
+    let output = r#"{"code":{"code":"E0308"},"rendered":"error[E0308]: mismatched types\n\n"}
+{"code":null,"rendered":"error: aborting due to previous error\n\n"}
+thread 'rustc' panicked
+"#;
+    let (rendered, codes) = parse_json_diagnostics(output);
+    assert_eq!(codes, vec!["E0308".to_string()]);
+    assert_eq!(rendered, "error[E0308]: mismatched types\n\n\
+                          error: aborting due to previous error\n\n\
+                          thread 'rustc' panicked\n");
+}
+
+#[test]
+fn persisted_dir_names() {
+    let file = FileName::Real(PathBuf::from("src/lib.rs"));
+    let name = persisted_dir_name(&file, 12, "assert!(true);");
+    assert!(name.starts_with("src_lib_rs_12_"));
+    assert_eq!(name.len(), "src_lib_rs_12_".len() + 16);
+    assert_eq!(name, persisted_dir_name(&file, 12, "assert!(true);"));
+    assert_ne!(name, persisted_dir_name(&file, 12, "assert!(false);"));
+}
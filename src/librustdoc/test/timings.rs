@@ -0,0 +1,107 @@
+//! The time spent compiling and running each doctest, reported with `--doctest-timings`.
+
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::config::OutputFormat;
+
+/// The time spent on a doctest. Either part is missing if the doctest did not get that far, and
+/// so is the compile time of the doctests compiled along with the others with `--merge-doctests`.
+#[derive(Default, Clone, Copy)]
+crate struct TestTimes {
+    crate compile: Option<Duration>,
+    crate run: Option<Duration>,
+}
+
+#[derive(Serialize)]
+struct TestTiming {
+    name: String,
+    module: String,
+    compile_secs: Option<f64>,
+    run_secs: Option<f64>,
+}
+
+impl TestTiming {
+    fn total_secs(&self) -> f64 {
+        self.compile_secs.unwrap_or(0.0) + self.run_secs.unwrap_or(0.0)
+    }
+}
+
+#[derive(Default, Clone, Copy, Serialize)]
+struct ModuleTiming {
+    tests: usize,
+    compile_secs: f64,
+    run_secs: f64,
+}
+
+/// The timings of all the doctests and their totals per module.
+#[derive(Serialize)]
+struct TimingsReport<'a> {
+    tests: &'a [TestTiming],
+    modules: &'a BTreeMap<String, ModuleTiming>,
+}
+
+#[derive(Default)]
+crate struct Timings {
+    tests: Vec<TestTiming>,
+}
+
+impl Timings {
+    /// Records the times of the doctest `name`, found in the documentation of `module`.
+    crate fn record(&mut self, name: String, module: String, times: TestTimes) {
+        self.tests.push(TestTiming {
+            name,
+            module,
+            compile_secs: times.compile.map(|time| time.as_secs_f64()),
+            run_secs: times.run.map(|time| time.as_secs_f64()),
+        });
+    }
+
+    crate fn report(&mut self, format: OutputFormat) {
+        // The slowest doctests come first.
+        self.tests.sort_by(|a, b| {
+            b.total_secs().partial_cmp(&a.total_secs()).unwrap().then_with(|| a.name.cmp(&b.name))
+        });
+        let mut modules = BTreeMap::<String, ModuleTiming>::new();
+        for test in &self.tests {
+            let module = modules.entry(test.module.clone()).or_default();
+            module.tests += 1;
+            module.compile_secs += test.compile_secs.unwrap_or(0.0);
+            module.run_secs += test.run_secs.unwrap_or(0.0);
+        }
+
+        match format {
+            OutputFormat::Json => self.print_json(&modules),
+            OutputFormat::Html => self.print_results(&modules),
+        }
+    }
+
+    fn print_json(&self, modules: &BTreeMap<String, ModuleTiming>) {
+        let report = TimingsReport { tests: &self.tests, modules };
+        println!("{}", serde_json::to_string(&report).expect("failed to serialize timings"));
+    }
+
+    fn print_results(&self, modules: &BTreeMap<String, ModuleTiming>) {
+        fn secs(secs: Option<f64>) -> String {
+            secs.map_or_else(|| "-".to_string(), |secs| format!("{:.3}s", secs))
+        }
+
+        println!();
+        println!("doctest timings, slowest first:");
+        println!("{:>10} {:>10}  {}", "compile", "run", "test");
+        for test in &self.tests {
+            println!("{:>10} {:>10}  {}", secs(test.compile_secs), secs(test.run_secs), test.name);
+        }
+
+        println!();
+        println!("doctest timings per module:");
+        println!("{:>10} {:>10} {:>6}  {}", "compile", "run", "tests", "module");
+        for (name, module) in modules {
+            println!("{:>10} {:>10} {:>6}  {}",
+                     secs(Some(module.compile_secs)), secs(Some(module.run_secs)), module.tests,
+                     name);
+        }
+    }
+}
@@ -279,3 +279,90 @@ pub fn test_theme_against<P: AsRef<Path>>(
     get_differences(against, &paths, &mut ret);
     (true, ret)
 }
+
+/// The stylesheets rustdoc writes itself. A theme is written next to them as `<name>.css`, so it
+/// can't be named like one of them.
+pub const RESERVED_THEME_NAMES: &[&str] = &[
+    "dark", "light", "noscript", "normalize", "rustdoc", "settings", "theme",
+];
+
+pub fn test_theme_variables<P: AsRef<Path>>(f: &P, diag: &Handler) -> (bool, Vec<String>) {
+    let data = try_something!(fs::read(f), diag, (false, vec![]));
+
+    let undefined = get_undefined_variables(&data)
+        .into_iter()
+        .map(|name| format!("  Undefined \"{}\" variable", name))
+        .collect();
+    (true, undefined)
+}
+
+/// Removes the comments of `v`, both the `/* */` ones and the `//` ones this module accepts.
+fn strip_comments(v: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(v.len());
+    let mut pos = 0;
+    while pos < v.len() {
+        if v[pos..].starts_with(b"/*") {
+            pos = v[pos + 2..].windows(2)
+                              .position(|w| w == b"*/")
+                              .map_or(v.len(), |p| pos + p + 4);
+        } else if v[pos..].starts_with(b"//") {
+            pos = v[pos..].iter().position(|&c| c == b'\n').map_or(v.len(), |p| pos + p);
+        } else {
+            out.push(v[pos]);
+            pos += 1;
+        }
+    }
+    out
+}
+
+fn trim_end(v: &[u8]) -> &[u8] {
+    let len = v.iter().rposition(|c| !c.is_ascii_whitespace()).map_or(0, |p| p + 1);
+    &v[..len]
+}
+
+fn is_ident_byte(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c >= 0x80
+}
+
+/// Returns the CSS variables (custom properties, like `--main-color`) that the stylesheet `v`
+/// uses with `var()` and no fallback value, but never defines. The declarations using them are
+/// ignored by browsers, so the theme doesn't apply there.
+pub fn get_undefined_variables(v: &[u8]) -> Vec<String> {
+    let v = strip_comments(v);
+    let mut defined = FxHashSet::default();
+    let mut used = Vec::new();
+
+    let mut pos = 0;
+    while pos + 1 < v.len() {
+        if &v[pos..pos + 2] != b"--" || (pos > 0 && is_ident_byte(v[pos - 1])) {
+            pos += 1;
+            continue;
+        }
+        let end = v[pos..].iter().position(|&c| !is_ident_byte(c)).map_or(v.len(), |p| pos + p);
+        let name = String::from_utf8_lossy(&v[pos..end]).into_owned();
+        let before = trim_end(&v[..pos]);
+        let after = v[end..].iter().find(|c| !c.is_ascii_whitespace());
+        match (before.last(), after) {
+            // `--name: value;`
+            (Some(b'{'), Some(b':')) | (Some(b';'), Some(b':')) => {
+                defined.insert(name);
+            }
+            // `var(--name)`, a use without fallback.
+            (Some(b'('), Some(b')'))
+                if before.len() >= 4 && before[before.len() - 4..].eq_ignore_ascii_case(b"var(") =>
+            {
+                used.push(name);
+            }
+            _ => {}
+        }
+        pos = end;
+    }
+
+    let mut undefined = used.into_iter()
+                            .filter(|name| !defined.contains(name))
+                            .collect::<Vec<_>>();
+    undefined.sort();
+    undefined.dedup();
+    undefined
+}
+
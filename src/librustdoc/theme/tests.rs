@@ -100,3 +100,23 @@ fn check_invalid_css() {
     let events = load_css_events(b"*");
     assert_eq!(events.len(), 0);
 }
+
+#[test]
+fn check_undefined_variables() {
+    let text = br#"
+:root {
+    --main-color: #000;
+    --background: white; --unused: 0;
+}
+
+/* color: var(--in-comment); */
+a { color: var(--main-color); background: var( --background ); }
+b { color: var(--with-fallback, black); }
+c { color: var(--undefined); border-color: VAR(--other-undefined); }
+d { color: var(--undefined); }
+e--f { color: red; }
+"#;
+
+    assert_eq!(get_undefined_variables(text),
+               vec!["--other-undefined".to_owned(), "--undefined".to_owned()]);
+}
@@ -84,7 +84,7 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
         );
         module.is_crate = true;
 
-        self.cx.renderinfo.get_mut().exact_paths = self.exact_paths;
+        self.cx.renderinfo.exact_paths = self.exact_paths;
 
         module
     }
@@ -283,7 +283,8 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
         // (this is done here because we need to know this upfront).
         if !res_did.is_local() && !is_no_inline {
             let attrs = clean::inline::load_attrs(self.cx, res_did);
-            let self_is_hidden = attrs.lists(sym::doc).has_word(sym::hidden);
+            let self_is_hidden = !self.cx.inline_hidden_reexports &&
+                                 attrs.lists(sym::doc).has_word(sym::hidden);
             match res {
                 Res::Def(DefKind::Trait, did) |
                 Res::Def(DefKind::Struct, did) |
@@ -292,7 +293,6 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
                 Res::Def(DefKind::ForeignTy, did) |
                 Res::Def(DefKind::TyAlias, did) if !self_is_hidden => {
                     self.cx.renderinfo
-                        .get_mut()
                         .access_levels.map
                         .insert(did, AccessLevel::Public);
                 },
@@ -309,7 +309,7 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
             Some(n) => n, None => return false
         };
 
-        let is_private = !self.cx.renderinfo.borrow().access_levels.is_public(res_did);
+        let is_private = !self.cx.renderinfo.access_levels.is_public(res_did);
         let is_hidden = inherits_doc_hidden(self.cx, res_hir_id);
 
         // Only inline if requested or if the item would otherwise be stripped.
@@ -324,6 +324,20 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
                 let prev = mem::replace(&mut self.inlining, true);
                 for i in &m.item_ids {
                     let i = self.cx.tcx.hir().expect_item(i.id);
+                    // Items reached through a glob can opt out of being inlined on their own.
+                    if i.attrs.lists(sym::doc).has_word(sym::hidden) {
+                        continue;
+                    }
+                    if i.attrs.lists(sym::doc).has_word(sym::no_inline) {
+                        let did = tcx.hir().local_def_id(i.hir_id);
+                        if let Some(kind) = tcx.def_kind(did) {
+                            om.glob_reexports.push(GlobReexport {
+                                name: i.ident.name,
+                                res: Res::Def(kind, did),
+                            });
+                            continue;
+                        }
+                    }
                     self.visit_item(i, None, om);
                 }
                 self.inlining = prev;
@@ -586,6 +600,7 @@ impl<'a, 'tcx> RustdocVisitor<'a, 'tcx> {
             name: renamed.unwrap_or(def.name),
             whence: def.span,
             matchers,
+            legacy: def.legacy,
             imported_from: None,
         }
     }
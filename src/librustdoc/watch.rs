@@ -0,0 +1,249 @@
+//! The watch mode of `--watch`.
+//!
+//! The input is documented again whenever one of the source files next to it changes, and the
+//! output directory is served over HTTP on a local port to preview the documentation in a
+//! browser.
+//!
+//! Every run documents the whole crate from scratch: the compiler session, and the `TyCtxt` the
+//! cleaned crate borrows from, do not outlive a run, so there is nothing to re-clean only the
+//! modules whose files changed against. Only the writing is incremental: the pages whose content
+//! did not change are skipped, as with `--incremental-docs`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use errors;
+
+use crate::config::Options;
+
+#[cfg(test)]
+mod tests;
+
+/// How often the source files are checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The extensions of the files whose changes make the input documented again.
+const WATCHED_EXTENSIONS: &[&str] = &["rs", "md", "markdown", "css", "html"];
+
+/// Documents the input with `document`, then again every time the source files change. This
+/// only returns if the documentation cannot be served.
+crate fn run(
+    mut options: Options,
+    port: u16,
+    diag: &errors::Handler,
+    document: fn(Options) -> i32,
+) -> i32 {
+    let render_options = &mut options.render_options;
+    // The options needing every page to be rendered do not allow skipping any.
+    render_options.incremental_docs = !render_options.generate_print_page &&
+                                      !render_options.item_metrics &&
+                                      !render_options.deduplicate_impls;
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            diag.struct_err(&format!("couldn't serve the documentation on port {}: {}", port, e))
+                .emit();
+            return 1;
+        }
+    };
+    let root = options.render_options.output.clone();
+    thread::spawn(move || serve(listener, &root));
+    diag.note_without_error(&format!("serving the documentation on http://127.0.0.1:{}/", port));
+
+    let sources = match options.input.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    // The output directory may be among the watched ones, but writing to it is not a change.
+    let output = &options.render_options.output;
+    let output = fs::create_dir_all(output).and_then(|_| fs::canonicalize(output)).ok();
+    let verbose = options.verbose;
+    loop {
+        // Changes made while documenting make the input documented once more.
+        let before = snapshot(&sources, output.as_deref());
+        // The compiler unwinds with a fatal error when the crate has errors, which is no reason to
+        // stop watching. Any other panic is a bug of the compiler and is not caught.
+        let code = rustc_driver::catch_fatal_errors(|| document(options.clone()))
+            .unwrap_or(rustc_driver::EXIT_FAILURE);
+        // The errors of a failed run are already reported by the compiler.
+        if verbose {
+            let status = if code == rustc_driver::EXIT_SUCCESS { "updated" } else { "not updated" };
+            diag.note_without_error(&format!("documentation {}, watching {} for changes",
+                                             status, sources.display()));
+        }
+        while before == snapshot(&sources, output.as_deref()) {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+/// Returns the modification time of every watched file in `dir` and its subdirectories, except
+/// in the hidden ones and in `output`.
+fn snapshot(dir: &Path, output: Option<&Path>) -> BTreeMap<PathBuf, SystemTime> {
+    let mut files = BTreeMap::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            let hidden = entry.file_name().to_str().map_or(false, |name| name.starts_with('.'));
+            let metadata = match entry.metadata() {
+                Ok(metadata) => metadata,
+                Err(_) => continue,
+            };
+            if metadata.is_dir() {
+                if !hidden && fs::canonicalize(&path).ok().as_deref() != output {
+                    dirs.push(path);
+                }
+            } else if is_watched(&path) {
+                if let Ok(modified) = metadata.modified() {
+                    files.insert(path, modified);
+                }
+            }
+        }
+    }
+    files
+}
+
+fn is_watched(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map_or(false, |extension| WATCHED_EXTENSIONS.contains(&extension))
+}
+
+/// Serves the files of `root` to the connections made to `listener`.
+fn serve(listener: TcpListener, root: &Path) {
+    for stream in listener.incoming() {
+        if let Ok(stream) = stream {
+            // A failed response only matters to the browser which asked for it.
+            let _ = respond(stream, root);
+        }
+    }
+}
+
+fn respond(mut stream: TcpStream, root: &Path) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let mut words = request_line.split_whitespace();
+    let target = match (words.next(), words.next()) {
+        (Some("GET"), Some(target)) => target,
+        _ => return write_response(&mut stream, "405 Method Not Allowed", "text/plain", b""),
+    };
+    let file = match resolve(root, target) {
+        Some(file) => file,
+        None => return write_response(&mut stream, "404 Not Found", "text/plain", b"not found"),
+    };
+    if file.is_dir() {
+        let listing = crate_listing(&file);
+        return write_response(&mut stream, "200 OK", "text/html", listing.as_bytes());
+    }
+    match fs::read(&file) {
+        Ok(body) => write_response(&mut stream, "200 OK", content_type(&file), &body),
+        Err(_) => write_response(&mut stream, "404 Not Found", "text/plain", b"not found"),
+    }
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> io::Result<()> {
+    write!(stream,
+           "HTTP/1.1 {}\r\n\
+            Content-Type: {}\r\n\
+            Content-Length: {}\r\n\
+            Cache-Control: no-cache\r\n\
+            Connection: close\r\n\r\n",
+           status, content_type, body.len())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+/// Returns the file of `root` which the request target `target` asks for: the `index.html` file
+/// for a directory having one, or the directory itself. The targets going out of `root` are
+/// refused.
+fn resolve(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(|c| c == '?' || c == '#').next().unwrap();
+    let mut file = root.to_path_buf();
+    for component in percent_decode(path).split('/') {
+        match component {
+            "" | "." => {}
+            ".." => return None,
+            _ if component.contains(|c| c == '\\' || c == ':') => return None,
+            _ => file.push(component),
+        }
+    }
+    if file.is_dir() {
+        let index = file.join("index.html");
+        if index.is_file() {
+            return Some(index);
+        }
+    }
+    if file.exists() { Some(file) } else { None }
+}
+
+/// Decodes the `%XX` escapes of an URL path.
+fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = if bytes[i] == b'%' {
+            path.get(i + 1..i + 3).and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Returns a page linking to the documentation of the crates in `dir`, for the output directory
+/// which does not have an index page of its own.
+fn crate_listing(dir: &Path) -> String {
+    let mut crates: Vec<String> = fs::read_dir(dir).into_iter().flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("index.html").is_file())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    crates.sort();
+    let links: String = crates.iter()
+        .map(|name| format!("<li><a href=\"{0}/index.html\">{0}</a></li>", name))
+        .collect();
+    format!("<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Crates</title></head>\
+             <body><h1>Crates</h1><ul>{}</ul></body></html>", links)
+}
+
+fn content_type(file: &Path) -> &'static str {
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("html") => "text/html; charset=utf-8",
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("svg") => "image/svg+xml",
+        Some("png") => "image/png",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("txt") | Some("md") => "text/plain; charset=utf-8",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,27 @@
+use super::*;
+
+#[test]
+fn test_percent_decode() {
+    assert_eq!(percent_decode("foo/struct.Bar.html"), "foo/struct.Bar.html");
+    assert_eq!(percent_decode("a%20b"), "a b");
+    assert_eq!(percent_decode("%E2%80%94"), "\u{2014}");
+    assert_eq!(percent_decode("100%"), "100%");
+    assert_eq!(percent_decode("%zz"), "%zz");
+}
+
+#[test]
+fn test_resolve() {
+    let root = tempfile::tempdir().unwrap();
+    let krate = root.path().join("foo");
+    fs::create_dir(&krate).unwrap();
+    fs::write(krate.join("index.html"), "").unwrap();
+    fs::write(krate.join("struct.Bar.html"), "").unwrap();
+
+    assert_eq!(resolve(root.path(), "/"), Some(root.path().to_path_buf()));
+    assert_eq!(resolve(root.path(), "/foo/"), Some(krate.join("index.html")));
+    assert_eq!(resolve(root.path(), "/foo/struct.Bar.html?search=x#method.baz"),
+               Some(krate.join("struct.Bar.html")));
+    assert_eq!(resolve(root.path(), "/foo/missing.html"), None);
+    assert_eq!(resolve(root.path(), "/foo/../../etc/passwd"), None);
+    assert_eq!(resolve(root.path(), "/foo/%2E%2E/%2E%2E/etc/passwd"), None);
+}
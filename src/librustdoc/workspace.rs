@@ -0,0 +1,172 @@
+//! Documenting several crates in one invocation, with `--workspace`.
+//!
+//! The crates are given either as several crate roots, or as the `Cargo.toml` manifest of a
+//! workspace, whose members are then read from it. They are documented one after the other into
+//! the same output directory, so that each crate finds the documentation of the crates documented
+//! before it there and links to it locally, and they all share the search index and the files of
+//! the output directory. The members of a workspace are documented after the members they depend
+//! on through a `path` dependency, the crate roots in the order they are given.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+
+#[cfg(test)]
+mod tests;
+
+/// A crate documented with `--workspace`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Member {
+    /// The root of the crate.
+    pub root: PathBuf,
+    /// The name of the crate, if it is known from its manifest.
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Option<Package>,
+    lib: Option<Target>,
+    workspace: Option<Workspace>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Target {
+    name: Option<String>,
+    path: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+struct Workspace {
+    #[serde(default)]
+    members: Vec<String>,
+    #[serde(default)]
+    exclude: Vec<String>,
+}
+
+/// Returns the crates to document for the inputs given on the command line.
+pub fn members(inputs: &[String]) -> Result<Vec<Member>, String> {
+    match inputs {
+        [input] if Path::new(input).file_name().map_or(false, |name| name == "Cargo.toml") => {
+            manifest_members(Path::new(input))
+        }
+        _ => Ok(inputs.iter().map(|input| Member { root: input.into(), name: None }).collect()),
+    }
+}
+
+fn load(path: &Path) -> Result<Manifest, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("couldn't read {}: {}", path.display(), e))?;
+    toml::from_str(&contents).map_err(|e| format!("invalid manifest {}: {}", path.display(), e))
+}
+
+/// Returns the library crates of the members of the workspace of `path`, each after the members
+/// it depends on.
+fn manifest_members(path: &Path) -> Result<Vec<Member>, String> {
+    let root = path.parent().unwrap_or_else(|| Path::new(""));
+    let workspace = load(path)?.workspace
+        .ok_or_else(|| format!("{} is not the manifest of a workspace", path.display()))?;
+    let excluded: Vec<_> = workspace.exclude.iter()
+        .map(|dir| normalize(&root.join(dir)))
+        .collect();
+    let mut dirs = vec![];
+    for member in &workspace.members {
+        for dir in expand(root, member)? {
+            if !excluded.contains(&dir) && !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    // The members without a library have nothing to document, but still order the others.
+    let mut crates = BTreeMap::new();
+    for dir in &dirs {
+        let manifest = load(&dir.join("Cargo.toml"))?;
+        let deps = manifest.dependencies.values()
+            .filter_map(|dep| dep.get("path").and_then(|path| path.as_str()))
+            .map(|path| normalize(&dir.join(path)))
+            .collect::<Vec<_>>();
+        let (name, path) = manifest.lib.map_or((None, None), |lib| (lib.name, lib.path));
+        let path = dir.join(path.unwrap_or_else(|| "src/lib.rs".into()));
+        let name = name.or_else(|| manifest.package.map(|package| package.name));
+        let member = if path.is_file() {
+            Some(Member { root: path, name: name.map(|name| name.replace("-", "_")) })
+        } else {
+            None
+        };
+        crates.insert(dir.clone(), (member, deps));
+    }
+
+    let mut members = vec![];
+    let mut visiting = vec![];
+    for dir in &dirs {
+        visit(dir, &mut crates, &mut visiting, &mut members)?;
+    }
+    Ok(members)
+}
+
+/// Adds the member of `dir` to `members` after its dependencies, if it was not added yet.
+fn visit(
+    dir: &PathBuf,
+    crates: &mut BTreeMap<PathBuf, (Option<Member>, Vec<PathBuf>)>,
+    visiting: &mut Vec<PathBuf>,
+    members: &mut Vec<Member>,
+) -> Result<(), String> {
+    if visiting.contains(dir) {
+        return Err(format!("cyclic dependency on the workspace member {}", dir.display()));
+    }
+    let (member, deps) = match crates.remove(dir) {
+        Some(entry) => entry,
+        // Either already added, or not a member of the workspace.
+        None => return Ok(()),
+    };
+    visiting.push(dir.clone());
+    for dep in &deps {
+        visit(dep, crates, visiting, members)?;
+    }
+    visiting.pop();
+    members.extend(member);
+    Ok(())
+}
+
+/// Returns the directories named by the `member` pattern of the workspace at `root`, which can
+/// end with a `*` component matching every directory with a manifest.
+fn expand(root: &Path, member: &str) -> Result<Vec<PathBuf>, String> {
+    if !member.ends_with("/*") {
+        return Ok(vec![normalize(&root.join(member))]);
+    }
+    let parent = root.join(&member[..member.len() - 2]);
+    let entries = fs::read_dir(&parent)
+        .map_err(|e| format!("couldn't read {}: {}", parent.display(), e))?;
+    let mut dirs = entries
+        .filter_map(|entry| entry.ok().map(|entry| normalize(&entry.path())))
+        .filter(|dir| dir.join("Cargo.toml").is_file())
+        .collect::<Vec<_>>();
+    dirs.sort();
+    Ok(dirs)
+}
+
+/// Removes the `.` and `..` components of `path`, so that the paths to the same member compare
+/// equal however they are written.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
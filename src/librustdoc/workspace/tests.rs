@@ -0,0 +1,54 @@
+use super::*;
+
+fn write(path: PathBuf, contents: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn test_manifest_members() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"app\", \"crates/*\"]\n\
+                                    exclude = [\"crates/scratch\"]\n");
+    write(root.join("app/Cargo.toml"), "[package]\nname = \"app\"\n[dependencies]\n\
+                                        core-utils = { path = \"../crates/core\" }\n\
+                                        serde = \"1.0\"\n");
+    write(root.join("app/src/main.rs"), "");
+    write(root.join("crates/core/Cargo.toml"), "[package]\nname = \"core-utils\"\n");
+    write(root.join("crates/core/src/lib.rs"), "");
+    write(root.join("crates/macros/Cargo.toml"), "[package]\nname = \"macros\"\n\
+                                                  [lib]\npath = \"macros.rs\"\n\
+                                                  [dependencies.core-utils]\npath = \"../core\"\n");
+    write(root.join("crates/macros/macros.rs"), "");
+    write(root.join("crates/scratch/Cargo.toml"), "[package]\nname = \"scratch\"\n");
+    write(root.join("crates/scratch/src/lib.rs"), "");
+
+    let manifest = root.join("Cargo.toml").to_str().unwrap().to_owned();
+    assert_eq!(members(&[manifest]).unwrap(), [
+        Member { root: root.join("crates/core/src/lib.rs"), name: Some("core_utils".to_owned()) },
+        Member { root: root.join("crates/macros/macros.rs"), name: Some("macros".to_owned()) },
+    ]);
+}
+
+#[test]
+fn test_roots() {
+    assert_eq!(members(&["a/lib.rs".to_owned(), "b/lib.rs".to_owned()]).unwrap(), [
+        Member { root: PathBuf::from("a/lib.rs"), name: None },
+        Member { root: PathBuf::from("b/lib.rs"), name: None },
+    ]);
+}
+
+#[test]
+fn test_cyclic_dependency() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"a\", \"b\"]\n");
+    write(root.join("a/Cargo.toml"), "[package]\nname = \"a\"\n\
+                                      [dependencies]\nb = { path = \"../b\" }\n");
+    write(root.join("b/Cargo.toml"), "[package]\nname = \"b\"\n\
+                                      [dependencies]\na = { path = \"../a\" }\n");
+
+    let manifest = root.join("Cargo.toml").to_str().unwrap().to_owned();
+    assert!(members(&[manifest]).unwrap_err().starts_with("cyclic dependency"));
+}
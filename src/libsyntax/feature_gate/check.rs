@@ -314,10 +314,14 @@ impl<'a> Visitor<'a> for PostExpansionVisitor<'a> {
                 gate_doc!(
                     include => external_doc
                     cfg => doc_cfg
+                    auto_cfg => doc_cfg
+                    cfg_hide => doc_cfg
+                    cfg_name => doc_cfg
                     masked => doc_masked
                     spotlight => doc_spotlight
                     alias => doc_alias
                     keyword => doc_keyword
+                    fragment => doc_fragment
                 );
             }
         }
@@ -158,6 +158,7 @@ symbols! {
         attributes,
         attr_literals,
         augmented_assignments,
+        auto_cfg,
         automatically_derived,
         avx512_target_feature,
         await_macro,
@@ -181,6 +182,8 @@ symbols! {
         cfg_attr,
         cfg_attr_multi,
         cfg_doctest,
+        cfg_hide,
+        cfg_name,
         cfg_sanitize,
         cfg_target_feature,
         cfg_target_has_atomic,
@@ -262,6 +265,7 @@ symbols! {
         doc,
         doc_alias,
         doc_cfg,
+        doc_fragment,
         doc_keyword,
         doc_masked,
         doc_spotlight,
@@ -321,6 +325,7 @@ symbols! {
         forbid,
         format_args,
         format_args_nl,
+        fragment,
         from,
         From,
         from_desugaring,
@@ -347,6 +352,7 @@ symbols! {
         HashMap,
         hexagon_target_feature,
         hidden,
+        hidden_line_prefix,
         homogeneous_aggregate,
         html_favicon_url,
         html_logo_url,
@@ -486,6 +492,7 @@ symbols! {
         no_start,
         no_std,
         not,
+        notable_trait,
         note,
         object_safe_for_dispatch,
         Ok,
@@ -538,6 +545,7 @@ symbols! {
         prelude,
         prelude_import,
         primitive,
+        priority,
         proc_dash_macro: "proc-macro",
         proc_macro,
         proc_macro_attribute,
@@ -650,6 +658,7 @@ symbols! {
         rustc_test_marker,
         rustc_then_this_would_need,
         rustc_variance,
+        rustdoc,
         rustfmt,
         rust_eh_personality,
         rust_eh_unwind_resume,
@@ -0,0 +1,12 @@
+// exact-check
+
+// The low priority alias comes after the approximate matches of the names.
+const QUERY = 'length';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'doc_alias_priority', 'name': 'length' },
+        { 'path': 'doc_alias_priority', 'name': 'lengths' },
+        { 'path': 'doc_alias_priority::Counter', 'name': 'count' },
+    ],
+};
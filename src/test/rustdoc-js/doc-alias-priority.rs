@@ -0,0 +1,15 @@
+#![feature(doc_alias)]
+
+pub struct Counter;
+
+impl Counter {
+    /// Returns the number of items counted so far.
+    #[doc(alias("length", priority = "low"))]
+    pub fn count(&self) -> usize { 0 }
+}
+
+/// Returns the length.
+pub fn length() -> usize { 0 }
+
+/// Returns the lengths.
+pub fn lengths() {}
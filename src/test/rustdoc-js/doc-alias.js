@@ -0,0 +1,9 @@
+const QUERY = 'len';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'doc_alias', 'name': 'len' },
+        { 'path': 'doc_alias::Counter', 'name': 'count' },
+        { 'path': 'doc_alias', 'name': 'lens' },
+    ],
+};
@@ -0,0 +1,16 @@
+#![feature(doc_alias)]
+
+pub struct Counter;
+
+impl Counter {
+    /// Returns the number of items counted so far.
+    #[doc(alias = "len")]
+    #[doc(alias = "Size")]
+    pub fn count(&self) -> usize { 0 }
+}
+
+/// Returns the length.
+pub fn len() -> usize { 0 }
+
+/// Not related to lengths.
+pub fn lens() {}
@@ -0,0 +1,10 @@
+// exact-check
+
+// The provided method is found under the type even though its impl does not override it.
+const QUERY = 'Person::greet';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'provided_methods::Person', 'name': 'greet' },
+    ],
+};
@@ -0,0 +1,21 @@
+pub trait Greet {
+    fn name(&self) -> String;
+
+    /// Greets.
+    fn greet(&self) {}
+}
+
+pub struct Person;
+
+impl Greet for Person {
+    fn name(&self) -> String { String::new() }
+}
+
+pub struct Robot;
+
+impl Greet for Robot {
+    fn name(&self) -> String { String::new() }
+
+    /// Beeps.
+    fn greet(&self) {}
+}
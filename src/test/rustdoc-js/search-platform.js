@@ -0,0 +1,12 @@
+// exact-check
+// ignore-order
+
+const QUERY = 'on:wasm32 Socket';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'search_platform::everywhere', 'name': 'Socket' },
+        { 'path': 'search_platform::not_windows', 'name': 'Socket' },
+        { 'path': 'search_platform::wasm', 'name': 'Socket' },
+    ],
+};
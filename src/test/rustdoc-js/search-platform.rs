@@ -0,0 +1,24 @@
+#![feature(doc_cfg)]
+
+pub mod wasm {
+    /// A socket.
+    #[doc(cfg(target_arch = "wasm32"))]
+    pub struct Socket;
+}
+
+pub mod unix {
+    /// A socket.
+    #[doc(cfg(unix))]
+    pub struct Socket;
+}
+
+pub mod not_windows {
+    /// A socket.
+    #[doc(cfg(not(windows)))]
+    pub struct Socket;
+}
+
+pub mod everywhere {
+    /// A socket.
+    pub struct Socket;
+}
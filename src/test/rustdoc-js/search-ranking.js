@@ -0,0 +1,14 @@
+// exact-check
+
+// The order of these results follows the ranking model documented in
+// `src/librustdoc/html/render/ranking.rs`.
+const QUERY = 'Parser';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'search_ranking::v2', 'name': 'Parser' },
+        { 'path': 'search_ranking::nested::deeper', 'name': 'Parser' },
+        { 'path': 'search_ranking', 'name': 'parser' },
+        { 'path': 'search_ranking::v1', 'name': 'Parser' },
+    ],
+};
@@ -0,0 +1,20 @@
+pub mod v1 {
+    /// Parses the input.
+    #[deprecated(note = "use `v2::Parser` instead")]
+    pub struct Parser;
+}
+
+pub mod v2 {
+    /// Parses the input.
+    pub struct Parser;
+}
+
+pub mod nested {
+    pub mod deeper {
+        /// Parses the input.
+        pub struct Parser;
+    }
+}
+
+/// Parses the input.
+pub fn parser() {}
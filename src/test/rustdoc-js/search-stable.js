@@ -0,0 +1,9 @@
+// exact-check
+
+const QUERY = 'stable:Socket';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'search_stable::net', 'name': 'Socket' },
+    ],
+};
@@ -0,0 +1,16 @@
+#![feature(staged_api)]
+#![stable(feature = "rust1", since = "1.0.0")]
+
+#[stable(feature = "rust1", since = "1.0.0")]
+pub mod net {
+    /// A socket.
+    #[stable(feature = "rust1", since = "1.0.0")]
+    pub struct Socket;
+}
+
+#[unstable(feature = "experimental", issue = "0")]
+pub mod experimental {
+    /// A socket.
+    #[unstable(feature = "experimental", issue = "0")]
+    pub struct Socket;
+}
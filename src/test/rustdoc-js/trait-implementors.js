@@ -0,0 +1,9 @@
+const QUERY = 'Shape';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'trait_implementors', 'name': 'Shape' },
+        { 'path': 'trait_implementors::Circle', 'name': 'Shape' },
+        { 'path': 'trait_implementors::Square', 'name': 'Shape' },
+    ],
+};
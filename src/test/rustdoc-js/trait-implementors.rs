@@ -0,0 +1,12 @@
+/// A shape.
+pub trait Shape {}
+
+pub struct Circle;
+
+impl Shape for Circle {}
+
+pub struct Square;
+
+impl Shape for Square {}
+
+pub struct Unrelated;
@@ -0,0 +1,7 @@
+const QUERY = 'TokenizerBulider';
+
+const EXPECTED = {
+    'others': [
+        { 'path': 'typo_tolerance', 'name': 'TokenizerBuilder' },
+    ],
+};
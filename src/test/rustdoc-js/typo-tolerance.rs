@@ -0,0 +1,5 @@
+/// Builds tokenizers.
+pub struct TokenizerBuilder;
+
+/// Splits text into tokens.
+pub struct Tokenizer;
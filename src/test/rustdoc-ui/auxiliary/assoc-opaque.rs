@@ -0,0 +1,19 @@
+#![feature(type_alias_impl_trait)]
+
+use std::fmt::Debug;
+
+pub trait Get {
+    type Output;
+
+    fn get(&self) -> Self::Output;
+}
+
+pub struct Value;
+
+impl Get for Value {
+    type Output = impl Debug;
+
+    fn get(&self) -> Self::Output {
+        0u8
+    }
+}
@@ -0,0 +1,11 @@
+// build-pass
+
+// Whether the blanket impl applies to `S` cannot be told, as evaluating its bound needs
+// `Box<S>: Tr`, then `Box<Box<S>>: Tr` and so on until the recursion limit. It is reported and
+// left out of the documentation of `S` rather than failing the documentation of the crate.
+
+pub trait Tr {}
+
+impl<T> Tr for T where Box<T>: Tr {}
+
+pub struct S;
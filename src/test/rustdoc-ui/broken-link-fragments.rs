@@ -0,0 +1,34 @@
+#![deny(rustdoc::broken_link_fragments)]
+
+/// A struct.
+///
+/// # Examples
+///
+/// See [the examples](#examples), [the method](#method.bar) and [`Foo::bar`].
+pub struct Foo {
+    /// A field.
+    pub field: u8,
+}
+
+impl Foo {
+    /// A method.
+    ///
+    /// See [the field](#structfield.field) and [the examples](#examples).
+    pub fn bar() {}
+}
+
+/// See [the examples](Foo#examples), [the method](struct.Foo.html#method.bar) and
+/// [the implementations](Foo#implementations).
+pub fn good() {}
+
+/// See [the panics](Foo#panics).
+pub fn missing_heading() {}
+//~^^ ERROR `Foo#panics` links to an anchor which does not exist
+
+/// See [the method](struct.Foo.html#bar).
+pub fn missing_prefix() {}
+//~^^ ERROR `struct.Foo.html#bar` links to an anchor which does not exist
+
+/// See [the section](#usage).
+pub fn missing_local() {}
+//~^^ ERROR `#usage` links to an anchor which does not exist
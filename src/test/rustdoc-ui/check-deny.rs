@@ -0,0 +1,6 @@
+// compile-flags:-Z unstable-options --check
+
+#![deny(intra_doc_link_resolution_failure)]
+
+/// [v2] //~ ERROR
+pub fn foo() {}
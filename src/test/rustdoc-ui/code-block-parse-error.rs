@@ -0,0 +1,21 @@
+// build-pass
+
+/// ```
+/// let x = ;
+/// ```
+pub fn missing_expr() {}
+//~^^^^ WARNING could not parse code block as Rust code
+
+/// Hidden lines are parsed along with the rest of the code, and `compile_fail` blocks are
+/// allowed not to parse.
+///
+/// ```
+/// # fn main() {
+/// let x = 1;
+/// # }
+/// ```
+///
+/// ```compile_fail
+/// let x = ;
+/// ```
+pub fn not_checked() {}
@@ -0,0 +1,9 @@
+// compile-flags: -Z unstable-options --code-example-kinds struct,enum
+
+#![deny(missing_doc_code_examples)]
+
+/// Functions are not checked with these kinds.
+pub fn function() {}
+
+/// doc
+pub struct Struct; //~^ ERROR missing code example
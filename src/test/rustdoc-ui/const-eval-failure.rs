@@ -0,0 +1,6 @@
+// build-pass
+
+// The constant overflows, which the compiler only reports as a lint, allowed by rustdoc. It is
+// reported rather than failing the documentation of the crate, and documented without its value.
+
+pub const OVERFLOW: u8 = 255 + 1;
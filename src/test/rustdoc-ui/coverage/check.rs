@@ -0,0 +1,9 @@
+// compile-flags:-Z unstable-options --check
+// check-pass
+
+//! The coverage is reported by `--check` as well.
+
+/// Documented.
+pub fn foo() {}
+
+pub struct Bar;
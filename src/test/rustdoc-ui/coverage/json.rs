@@ -0,0 +1,18 @@
+// compile-flags:-Z unstable-options --output-format json --show-coverage
+// build-pass (FIXME(62277): could be check-pass?)
+
+//! a crate with a submodule, to check the per-module counts
+
+pub mod inner {
+    /// a function with an example
+    ///
+    /// ```
+    /// json::inner::with_example();
+    /// ```
+    pub fn with_example() {}
+
+    pub fn undocumented() {}
+}
+
+/// a struct with docs but no example
+pub struct Documented;
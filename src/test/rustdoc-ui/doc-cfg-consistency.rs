@@ -0,0 +1,17 @@
+#![feature(doc_cfg)]
+#![deny(rustdoc::inconsistent_doc_cfg)]
+
+#[cfg(not(feature = "serde"))]
+#[doc(cfg(feature = "serde"))]
+pub fn contradictory() {}
+//~^^ ERROR `doc(cfg)` contradicts the configuration this item is compiled in
+
+#[doc(cfg(feature = "json"))]
+pub mod json {
+    #[doc(cfg(feature = "json"))]
+    pub fn redundant() {}
+    //~^^ ERROR `doc(cfg)` repeats the configuration of a parent item
+
+    #[doc(cfg(feature = "pretty"))]
+    pub fn consistent() {}
+}
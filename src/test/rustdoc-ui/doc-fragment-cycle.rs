@@ -0,0 +1,12 @@
+#![feature(doc_fragment)]
+
+/// {{include:second}}
+#[doc(fragment = "first")]
+pub struct First;
+
+/// {{include:first}}
+#[doc(fragment = "second")]
+pub struct Second;
+
+/// {{include:missing}}
+pub fn missing() {}
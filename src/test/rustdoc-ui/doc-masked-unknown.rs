@@ -0,0 +1,6 @@
+#![feature(doc_masked)]
+#![doc(masked(not_a_crate, "std"))]
+//~^ ERROR unknown crate `not_a_crate`
+//~| ERROR expected a crate name
+
+pub struct Foo;
@@ -0,0 +1,13 @@
+// compile-flags:--test --edition 2018 --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+
+/// ```edition2015
+/// let async = 1;
+/// assert_eq!(async, 1);
+/// ```
+///
+/// ```rust,edition2021
+/// let x = 1;
+/// ```
+pub struct Foo;
@@ -0,0 +1,15 @@
+// compile-flags:--test --test-args --test-threads=1
+// rustc-env:RUST_BACKTRACE=0
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+
+/// ```should_panic="out of bounds"
+/// let v: Vec<u8> = vec![];
+/// v[0];
+/// ```
+///
+/// ```should_panic="out of bounds"
+/// let v: Vec<u8> = vec![1];
+/// v[0];
+/// ```
+pub struct Foo;
@@ -0,0 +1,11 @@
+// compile-flags:--test --test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// failure-status: 101
+
+#![feature(external_doc)]
+
+#[doc(include = "auxiliary/doctest-include.md")]
+/// ```
+/// assert!(true);
+/// ```
+pub struct Foo;
@@ -0,0 +1,21 @@
+#![deny(hidden_types_in_signatures)]
+
+mod private {
+    pub struct Private;
+}
+
+#[doc(hidden)]
+pub struct Hidden;
+
+pub fn takes_private(_: private::Private) {}
+//~^ ERROR documented item refers to private type `private::Private`
+
+pub fn returns_hidden() -> Hidden { Hidden }
+//~^ ERROR documented item refers to hidden type `Hidden`
+
+#[doc(hidden)]
+pub fn hidden_fn(_: private::Private) {}
+
+pub struct Public;
+
+pub fn takes_public(_: Public) {}
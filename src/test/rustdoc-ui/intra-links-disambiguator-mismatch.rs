@@ -0,0 +1,11 @@
+#![deny(intra_doc_link_resolution_failure)]
+#![allow(non_camel_case_types)]
+
+pub fn ambiguous() {}
+
+pub struct ambiguous {}
+
+/// [macro@ambiguous] has the wrong disambiguator. //~ERROR `[ambiguous]`
+///
+/// [`macro@ambiguous#fields`] keeps its anchor. //~ERROR `[ambiguous]`
+pub struct Docs {}
@@ -0,0 +1,17 @@
+// compile-flags: --error-format json
+// The fixes of intra-doc link errors are meant to be applied by tools, so check the spans,
+// labels and suggestions they carry in the JSON error format.
+
+#![deny(intra_doc_link_resolution_failure)]
+#![allow(non_camel_case_types)]
+
+pub fn ambiguous() {}
+
+pub struct ambiguous {}
+
+/// [macro@ambiguous] has the wrong disambiguator.
+//~^ ERROR `[ambiguous]`
+///
+/// [ambiguous#a#b] has too many anchors.
+//~^ ERROR `[ambiguous#a#b]`
+pub struct Docs {}
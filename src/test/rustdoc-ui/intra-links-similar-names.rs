@@ -0,0 +1,27 @@
+#![deny(intra_doc_link_resolution_failure)]
+
+pub mod inner {
+    pub struct Parser;
+
+    impl Parser {
+        pub fn parse() {}
+    }
+}
+
+pub struct Tokenizer;
+
+/// See [Tokeniser].
+pub fn typo() {}
+//~^^ ERROR `[Tokeniser]` cannot be resolved
+
+/// See [`tokenizer`].
+pub fn case() {}
+//~^^ ERROR `[tokenizer]` cannot be resolved
+
+/// See [Parser].
+pub fn missing_prefix() {}
+//~^^ ERROR `[Parser]` cannot be resolved
+
+/// See [inner::Parser::pars()].
+pub fn member_typo() {}
+//~^^ ERROR `[inner::Parser::pars]` cannot be resolved
@@ -3,7 +3,7 @@
 
 //! crate level doc
 //! ```
-//! println!("hello"):
+//! println!("hello");
 //! ```
 
 
@@ -16,7 +16,7 @@ fn test() {
 }
 
 #[allow(missing_docs)]
-mod module1 { //~ ERROR
+mod module1 {
 }
 
 #[allow(missing_doc_code_examples)]
@@ -38,3 +38,18 @@ pub mod module3 {
   //~^ ERROR
   pub fn test() {}
 }
+
+/// Structs are not checked by default.
+pub struct Struct;
+
+/// doc
+//~^ ERROR
+pub trait Trait {
+    /// doc
+    //~^ ERROR
+    fn method();
+}
+
+impl Trait for Struct {
+    fn method() {}
+}
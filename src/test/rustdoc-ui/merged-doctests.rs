@@ -0,0 +1,38 @@
+// build-pass (FIXME(62277): could be check-pass?)
+// compile-flags:--test -Z unstable-options --merge-doctests --edition 2018
+// compile-flags:--test-args --test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+
+// Doctests compiled into a single binary still run on their own, and the ones which need a crate
+// of their own are still compiled separately.
+
+/// ```
+/// struct Same;
+/// assert_eq!(1 + 1, 2);
+/// ```
+///
+/// ```
+/// struct Same;
+/// let v: Vec<u8> = Vec::new();
+/// assert!(v.is_empty());
+/// ```
+pub struct Merged;
+
+/// ```should_panic
+/// panic!("expected");
+/// ```
+///
+/// ```no_run
+/// loop {}
+/// ```
+pub struct Attributes;
+
+/// ```
+/// #![allow(dead_code)]
+/// fn main() {}
+/// ```
+///
+/// ```compile_fail
+/// let x: u8 = "not a number";
+/// ```
+pub struct Standalone;
@@ -0,0 +1,14 @@
+#![deny(rustdoc::redundant_explicit_links)]
+
+pub mod inner {
+    pub struct Foo;
+}
+
+pub use inner::Foo;
+
+/// See [`Foo`](inner::Foo).
+pub fn redundant() {}
+//~^^ ERROR redundant explicit link target
+
+/// See [the type](Foo) and [`Bar`](inner::Foo).
+pub fn not_redundant() {}
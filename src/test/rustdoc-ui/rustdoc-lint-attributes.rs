@@ -0,0 +1,18 @@
+#![deny(rustdoc::bare_urls)]
+#![deny(rustdoc::invalid_rust_codeblocks)]
+
+/// Read https://example.com for more.
+pub fn bare_url() {}
+//~^^ ERROR this URL is not a hyperlink
+
+/// ```
+/// let x = ;
+/// ```
+pub fn invalid_code_block() {}
+//~^^^^ ERROR could not parse code block as Rust code
+
+/// ```
+/// let x = ;
+/// ```
+#[allow(rustdoc::invalid_rust_codeblocks)]
+pub fn allowed() {}
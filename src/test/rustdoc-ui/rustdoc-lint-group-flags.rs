@@ -0,0 +1,10 @@
+// compile-flags: -D rustdoc::all -A rustdoc::examples
+
+/// Read https://example.com for more.
+pub fn bare_url() {}
+//~^^ ERROR this URL is not a hyperlink
+
+/// ```
+/// let x = ;
+/// ```
+pub fn invalid_code_block() {}
@@ -0,0 +1,9 @@
+// aux-build:assoc-opaque.rs
+// build-pass
+
+// The bounds of the associated opaque types of external impls cannot be documented, which is
+// reported rather than failing the documentation of the crate.
+
+extern crate assoc_opaque;
+
+pub use assoc_opaque::Value;
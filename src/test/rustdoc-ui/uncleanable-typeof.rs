@@ -0,0 +1,4 @@
+// `typeof` types never get to be documented: the compiler rejects them while collecting the
+// types of the items, before rustdoc cleans them, so the signature is not shown as `_`.
+
+pub fn reserved() -> typeof(0u8) {} //~ ERROR `typeof` is a reserved keyword but unimplemented
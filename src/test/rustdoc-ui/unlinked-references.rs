@@ -0,0 +1,13 @@
+#![deny(bare_urls, unlinked_paths)]
+
+/// Read https://example.com for more.
+pub fn bare_url() {}
+//~^^ ERROR this URL is not a hyperlink
+
+/// Call Vec::new() to start.
+pub fn unlinked_path() {}
+//~^^ ERROR `Vec::new()` looks like an item path, but is not a link
+
+/// Already linked: <https://example.com> and [the site](https://example.com/a::b), or written as
+/// code: `Vec::new()`.
+pub fn linked() {}
@@ -15,3 +15,17 @@ pub extern "C" fn g() {}
 pub enum Foo {
     Bar,
 }
+
+// @has foo/static.BAZ.html '//*[@class="docblock attributes"]' '#[link_section = ".bss"]'
+#[link_section = ".bss"]
+pub static BAZ: u32 = 0;
+
+// @has foo/struct.Qux.html '//*[@class="docblock attributes top-attr"]' '#[non_exhaustive]'
+// @has foo/struct.Qux.html '//*[@class="docblock attributes top-attr"]' '#[repr(C)]'
+// @!has foo/struct.Qux.html '//*[@class="docblock attributes top-attr"]' 'allow'
+#[repr(C)]
+#[non_exhaustive]
+#[allow(dead_code)]
+pub struct Qux {
+    pub x: u32,
+}
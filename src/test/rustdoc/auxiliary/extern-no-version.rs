@@ -0,0 +1,3 @@
+#![doc(html_root_url = "https://example.com/extern_no_version/")]
+
+pub struct Unpinned;
@@ -0,0 +1,3 @@
+#![doc(html_root_url = "https://example.com/extern_version/1.2.3")]
+
+pub struct Pinned;
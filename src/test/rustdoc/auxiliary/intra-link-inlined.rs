@@ -0,0 +1,19 @@
+#![crate_name = "inner"]
+
+pub mod sub {
+    /// Built with a [`Builder`], or with [`super::top_level`] from a [`crate::Root`].
+    pub struct Widget;
+
+    impl Widget {
+        /// Turns it into a [`Gadget`].
+        pub fn into_gadget(self) {}
+    }
+
+    pub struct Builder;
+
+    pub struct Gadget;
+}
+
+pub struct Root;
+
+pub fn top_level() {}
@@ -0,0 +1,11 @@
+pub struct Bar;
+
+impl Bar {
+    pub fn new() -> Bar { Bar }
+}
+
+pub enum Kind {
+    First,
+}
+
+pub fn make() -> Bar { Bar }
@@ -0,0 +1,12 @@
+mod imp {
+    pub struct Token;
+
+    pub trait Encode {}
+
+    pub mod nested {
+        pub struct Inner;
+    }
+}
+
+pub use imp::{Encode, Token};
+pub use imp::nested::Inner as Renamed;
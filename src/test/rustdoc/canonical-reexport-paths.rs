@@ -0,0 +1,35 @@
+// aux-build:private-reexports.rs
+// build-aux-docs
+// ignore-cross-compile
+
+#![crate_name = "foo"]
+
+extern crate private_reexports;
+
+use private_reexports::{Encode, Renamed, Token};
+
+// Items of other crates defined in a private module are linked to where they are re-exported.
+// @has foo/fn.decode.html '//a[@href="../private_reexports/struct.Token.html"]' 'Token'
+// @has - '//a[@href="../private_reexports/struct.Renamed.html"]' 'Renamed'
+pub fn decode(_: Token) -> Renamed {
+    loop {}
+}
+
+// @has implementors/private_reexports/trait.Encode.js
+// @!has implementors/private_reexports/imp/trait.Encode.js
+pub struct Message;
+
+impl Encode for Message {}
+
+mod private {
+    pub struct Local;
+}
+
+// @has foo/struct.Local.html '//h1[@class="fqn"]' 'Struct foo::Local'
+// @!has foo/private/struct.Local.html
+// @has foo/fn.local.html '//a[@href="../foo/struct.Local.html"]' 'Local'
+pub use private::Local;
+
+pub fn local() -> Local {
+    Local
+}
@@ -0,0 +1,8 @@
+#![crate_name = "foo"]
+
+// @has foo/constant.OVERFLOW.html '//pre[@class="rust const"]' 'pub const OVERFLOW: u8'
+// @!has - '//pre[@class="rust const"]' '='
+pub const OVERFLOW: u8 = 255 + 1;
+
+// @has foo/constant.FINE.html '//pre[@class="rust const"]' 'pub const FINE: u8 = 255;'
+pub const FINE: u8 = 254 + 1;
@@ -0,0 +1,29 @@
+// The value of constants and statics of primitive types is shown, but not the expression they
+// are defined with.
+
+// @has const_value/constant.MASK.html '//pre[@class="rust const"]' 'pub const MASK: u32 = 15;'
+// @!has - '//pre[@class="rust const"]' '1 << 4'
+pub const MASK: u32 = (1 << 4) - 1;
+
+// @has const_value/constant.HEX_MASK.html '//pre[@class="rust const"]' '= 0xff00;'
+pub const HEX_MASK: u16 = 0xff << 8;
+
+// @has const_value/constant.NEGATIVE.html '//pre[@class="rust const"]' '= -128;'
+pub const NEGATIVE: i8 = i8::min_value();
+
+// @has const_value/constant.ENABLED.html '//pre[@class="rust const"]' '= true;'
+pub const ENABLED: bool = MASK > 10;
+
+// @has const_value/constant.LETTER.html '//pre[@class="rust const"]' "= 'b';"
+pub const LETTER: char = ('a' as u8 + 1) as char;
+
+// @has const_value/static.LIMIT.html '//pre[@class="rust static"]' '= 4096;'
+pub static LIMIT: usize = 4 * 1024;
+
+// @has const_value/static.COUNTER.html '//pre[@class="rust static"]' 'static mut COUNTER'
+// @!has - '//pre[@class="rust static"]' '='
+pub static mut COUNTER: usize = 0;
+
+// @has const_value/constant.NAME.html '//pre[@class="rust const"]' 'pub const NAME'
+// @!has - '//pre[@class="rust const"]' 'crate'
+pub const NAME: &str = "crate";
@@ -0,0 +1,44 @@
+// compile-flags: -Z unstable-options --deduplicate-impls
+
+#![crate_name = "foo"]
+
+use std::ops::Deref;
+
+pub trait Everywhere {
+    fn everywhere(&self) {}
+}
+
+impl<T> Everywhere for T {}
+
+pub struct Target;
+
+impl Target {
+    pub fn target_method(&self) {}
+}
+
+// @has foo/struct.Foo.html
+// @has - '//div[@id="blanket-implementations-list"]/script[@data-root-path="../"]' ''
+// @!has - '//div[@id="blanket-implementations-list"]//h3'
+// @has - '//script[@data-root-path="../"]/@src' '../foo/impls/'
+// @has - '//h4[@id="method.new"]' 'fn new'
+pub struct Foo;
+
+impl Foo {
+    pub fn new() -> Foo {
+        Foo
+    }
+}
+
+impl Deref for Foo {
+    type Target = Target;
+
+    fn deref(&self) -> &Target {
+        &Target
+    }
+}
+
+pub mod inner {
+    // @has foo/inner/struct.Bar.html
+    // @has - '//div[@id="blanket-implementations-list"]/script[@data-root-path="../../"]' ''
+    pub struct Bar;
+}
@@ -0,0 +1,69 @@
+// Methods reachable through several `Deref` impls are listed too, one section per step.
+
+use std::ops::Deref;
+
+// @has deref_recursive/struct.Guard.html
+// @has - '//*[@id="deref-methods"]' 'Methods from Deref<Target = Inner>'
+// @has - '//*[@class="impl-items"]//code' 'pub fn inner_method(&self)'
+// @has - '//*[@id="deref-methods-%5Bu8%5D"]' 'Methods from Inner: Deref<Target = [u8]>'
+// @has - '//*[@class="impl-items"]//code' 'fn len(&self)'
+// @has - '//*[@href="#deref-methods-%5Bu8%5D"]' 'Methods from Inner: Deref<Target=[u8]>'
+pub struct Guard {
+    inner: Inner,
+}
+
+pub struct Inner {
+    data: Vec<u8>,
+}
+
+impl Inner {
+    pub fn inner_method(&self) {}
+}
+
+impl Deref for Guard {
+    type Target = Inner;
+
+    fn deref(&self) -> &Inner {
+        &self.inner
+    }
+}
+
+impl Deref for Inner {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+// @has deref_recursive/struct.A.html
+// @has - '//*[@id="deref-methods"]' 'Methods from Deref<Target = B>'
+// @has - '//*[@id="method.b_method"]' 'pub fn b_method(&self)'
+// @!has - '//h2' 'Methods from B'
+pub struct A;
+
+pub struct B;
+
+impl A {
+    pub fn a_method(&self) {}
+}
+
+impl B {
+    pub fn b_method(&self) {}
+}
+
+impl Deref for A {
+    type Target = B;
+
+    fn deref(&self) -> &B {
+        &B
+    }
+}
+
+impl Deref for B {
+    type Target = A;
+
+    fn deref(&self) -> &A {
+        &A
+    }
+}
@@ -0,0 +1,35 @@
+#![feature(doc_alias)]
+
+#![crate_name = "foo"]
+
+// @has foo/struct.Counter.html
+// @has - '//div[@class="aliases"]' 'Also known as: Tally'
+#[doc(alias = "Tally")]
+pub struct Counter;
+
+impl Counter {
+    // @has - '//div[@class="aliases"]/code' 'len'
+    // @has - '//div[@class="aliases"]' 'Also known as: len, size'
+    /// Returns the number of items counted so far.
+    #[doc(alias = "len")]
+    #[doc(alias = "size")]
+    #[doc(alias = "len")]
+    pub fn count(&self) -> usize { 0 }
+}
+
+// @has foo/fn.reset.html
+// @!has - '//div[@class="aliases"]'
+pub fn reset() {}
+
+// @has aliases.js 'ALIASES["foo"]'
+// @has - '"tally":[{'
+// @has - "'name':'count','desc':'Returns the number of items counted so far.','p':'foo',\
+//      'parent':{'ty':3,'name':'Counter'}"
+
+// @has foo/fn.tally.html
+// @has - '//div[@class="aliases"]' 'Also known as: total'
+#[doc(alias("total", priority = "low"))]
+pub fn tally() {}
+
+// @has aliases.js '"total":[{'
+// @has - "'name':'tally','desc':'','p':'foo','low':true"
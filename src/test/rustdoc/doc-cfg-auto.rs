@@ -0,0 +1,34 @@
+// compile-flags: --cfg sandbox --cfg tracing
+
+#![feature(doc_cfg)]
+#![doc(auto_cfg)]
+
+// @has doc_cfg_auto/struct.Sandboxed.html
+// @has - '//*[@class="stab portability"]' 'This is supported on sandbox only.'
+#[cfg(sandbox)]
+pub struct Sandboxed;
+
+// @has doc_cfg_auto/struct.Documented.html
+// @count - '//*[@class="stab portability"]' 0
+#[cfg(any(sandbox, doc))]
+pub struct Documented;
+
+// @has doc_cfg_auto/struct.Unconditional.html
+// @count - '//*[@class="stab portability"]' 0
+pub struct Unconditional;
+
+// @has doc_cfg_auto/sandbox/index.html
+// @has - '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' \
+//  'This is supported on sandbox only.'
+#[cfg(sandbox)]
+pub mod sandbox {
+    // @has doc_cfg_auto/sandbox/fn.traced.html
+    // @has - '//*[@class="stab portability"]' 'This is supported on sandbox and tracing only.'
+    #[cfg(tracing)]
+    pub fn traced() {}
+
+    // @has doc_cfg_auto/sandbox/fn.on_unix.html
+    // @has - '//*[@class="stab portability"]' 'This is supported on sandbox and Unix only.'
+    #[doc(cfg(unix))]
+    pub fn on_unix() {}
+}
@@ -0,0 +1,25 @@
+// compile-flags: --cfg feature="std" --cfg sandbox
+
+#![feature(doc_cfg)]
+#![doc(auto_cfg)]
+#![doc(cfg_hide(feature = "std"))]
+
+// @has doc_cfg_hide/struct.Std.html
+// @count - '//*[@class="stab portability"]' 0
+#[cfg(feature = "std")]
+pub struct Std;
+
+// @has doc_cfg_hide/struct.Sandboxed.html
+// @has - '//*[@class="stab portability"]' 'This is supported on sandbox only.'
+#[cfg(all(feature = "std", sandbox))]
+pub struct Sandboxed;
+
+// @has doc_cfg_hide/std/index.html
+// @count - '//*[@id="main"]/*[@class="stability"]/*[@class="stab portability"]' 0
+#[cfg(feature = "std")]
+pub mod std {
+    // @has doc_cfg_hide/std/fn.on_unix.html
+    // @has - '//*[@class="stab portability"]' 'This is supported on Unix only.'
+    #[doc(cfg(unix))]
+    pub fn on_unix() {}
+}
@@ -0,0 +1,40 @@
+// ignore-tidy-linelength
+
+#![crate_name = "foo"]
+#![feature(doc_cfg)]
+
+pub trait Trait {
+    fn trait_method(&self);
+}
+
+// @has foo/struct.Foo.html
+// @has - '//*[@id="method.inherent_method"]/following-sibling::div[1]/*[@class="stab portability"]' \
+//        'This is supported on Windows only.'
+// @has - '//*[@id="method.trait_method"]/following-sibling::div[1]/*[@class="stab portability"]' \
+//        'This is supported on Unix only.'
+pub struct Foo;
+
+#[doc(cfg(windows))]
+impl Foo {
+    pub fn inherent_method(&self) {}
+}
+
+#[doc(cfg(unix))]
+impl Trait for Foo {
+    fn trait_method(&self) {}
+}
+
+// @has foo/struct.Bar.html
+// @has - '//*[@id="method.trait_method"]/following-sibling::div[1]/*[@class="stab portability"]' \
+//        'This is supported on Unix and x86-64 only.'
+pub struct Bar;
+
+#[doc(cfg(all(unix, target_arch = "x86_64")))]
+pub trait PortableTrait {
+    fn trait_method(&self);
+}
+
+#[doc(cfg(unix))]
+impl PortableTrait for Bar {
+    fn trait_method(&self) {}
+}
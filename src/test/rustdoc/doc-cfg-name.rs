@@ -0,0 +1,22 @@
+#![feature(doc_cfg)]
+#![doc(cfg_name(feature = "tls", "TLS support"))]
+#![doc(cfg_name(any(unix, windows), "desktop platforms"))]
+
+// @has doc_cfg_name/index.html
+// @has - '//*[@class="stab portability"]' 'TLS support'
+// @has doc_cfg_name/struct.Tls.html
+// @has - '//*[@class="stab portability"]' 'This is supported on TLS support only.'
+#[doc(cfg(feature = "tls"))]
+pub struct Tls;
+
+// @has doc_cfg_name/struct.Desktop.html
+// @has - '//*[@class="stab portability"]' \
+//     'This is supported on desktop platforms only.'
+#[doc(cfg(any(unix, windows)))]
+pub struct Desktop;
+
+// @has doc_cfg_name/struct.Both.html
+// @has - '//*[@class="stab portability"]' \
+//     'This is supported on Unix and TLS support only.'
+#[doc(cfg(all(unix, feature = "tls")))]
+pub struct Both;
@@ -0,0 +1,20 @@
+// compile-flags: --no-defaults
+#![feature(doc_fragment)]
+#![crate_name = "foo"]
+
+// The references are resolved while cleaning, not by a pass.
+
+// @has foo/fn.read.html
+// @has - '//*[@class="docblock"]' 'The pointer must be non-null.'
+// @has - '//*[@class="doc-fragment-source"]/code' 'NonNull'
+// @!has - '//*[@class="docblock"]' '{{include:'
+/// # Safety
+///
+/// {{include:non-null}}
+pub unsafe fn read(ptr: *const u8) -> u8 {
+    *ptr
+}
+
+/// The pointer must be non-null.
+#[doc(fragment = "non-null")]
+pub struct NonNull;
@@ -0,0 +1,31 @@
+#![feature(doc_fragment)]
+#![crate_name = "foo"]
+
+/// The pointer must be non-null and properly aligned.
+#[doc(fragment = "aligned")]
+#[doc(hidden)]
+pub struct Aligned;
+
+pub mod safety {
+    /// The pointer must be valid for reads.
+    ///
+    /// {{include:aligned}}
+    #[doc(fragment = "readable")]
+    pub struct Readable;
+}
+
+// @has foo/fn.read.html
+// @has - '//*[@class="docblock"]' 'The pointer must be valid for reads.'
+// @has - '//*[@class="docblock"]' 'The pointer must be non-null and properly aligned.'
+// @has - '//*[@class="doc-fragment-source"]/code' 'readable'
+// @has - '//*[@class="doc-fragment-source"]/code' 'safety::Readable'
+// @has - '//*[@class="doc-fragment-source"]/code' 'Aligned'
+// @!has - '//*[@class="docblock"]' '{{include:'
+/// Reads the value behind `ptr`.
+///
+/// # Safety
+///
+/// {{include:readable}}
+pub unsafe fn read(ptr: *const u8) -> u8 {
+    *ptr
+}
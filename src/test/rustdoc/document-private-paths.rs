@@ -0,0 +1,33 @@
+// compile-flags: -Z unstable-options --document-private-items=crate::internal,outer::inner
+
+#![crate_name = "foo"]
+
+// @has foo/internal/index.html
+// @has foo/internal/fn.helper.html
+// @has foo/internal/struct.Secret.html '//*[@id="structfield.value"]' 'value'
+mod internal {
+    fn helper() {}
+
+    struct Secret {
+        value: u32,
+    }
+}
+
+// @!has foo/other/index.html
+mod other {
+    fn hidden() {}
+}
+
+// @has foo/outer/index.html
+// @!has foo/outer/fn.skipped.html
+// @has foo/outer/inner/fn.shown.html
+mod outer {
+    fn skipped() {}
+
+    mod inner {
+        fn shown() {}
+    }
+}
+
+// @!has foo/fn.private_root.html
+fn private_root() {}
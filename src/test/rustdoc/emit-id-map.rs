@@ -0,0 +1,19 @@
+// compile-flags: -Z unstable-options --emit id-map
+
+#![crate_name = "foo"]
+
+// @!has foo/index.html
+// @!has search-index.js
+// @has foo/id-map.json '"foo::Bar":"foo/struct.Bar.html"'
+pub struct Bar;
+
+impl Bar {
+    // @has foo/id-map.json '"foo::Bar::new":"foo/struct.Bar.html#method.new"'
+    pub fn new() -> Bar { Bar }
+}
+
+pub mod baz {
+    // @has foo/id-map.json '"foo::baz":"foo/baz/index.html"'
+    // @has foo/id-map.json '"foo::baz::qux":"foo/baz/fn.qux.html"'
+    pub fn qux() {}
+}
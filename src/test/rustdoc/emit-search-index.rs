@@ -0,0 +1,10 @@
+// compile-flags: -Z unstable-options --emit search-index
+
+#![crate_name = "foo"]
+
+// @has search-index.js
+// @has search-index.js 'Bar'
+// @!has foo/index.html
+// @!has foo/struct.Bar.html
+// @!has main.js
+pub struct Bar;
@@ -0,0 +1,19 @@
+// aux-build:extern-version.rs
+// aux-build:extern-no-version.rs
+// compile-flags:-Z unstable-options
+// compile-flags:--extern-html-root-url extern_version=https://docs.rs/{crate}/{version}
+// compile-flags:--extern-html-root-url extern_no_version=https://docs.rs/{crate}/{version}
+
+#![crate_name = "foo"]
+
+extern crate extern_version;
+extern crate extern_no_version;
+
+// @has foo/fn.pinned.html
+// @has - '//a/@href' 'https://docs.rs/extern_version/1.2.3/extern_version/struct.Pinned.html'
+pub fn pinned(_: extern_version::Pinned) {}
+
+// The version of `extern_no_version` is unknown, so its `html_root_url` is used.
+// @has foo/fn.unpinned.html
+// @has - '//a/@href' 'https://example.com/extern_no_version/extern_no_version/struct.Unpinned.html'
+pub fn unpinned(_: extern_no_version::Unpinned) {}
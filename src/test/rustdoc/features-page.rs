@@ -0,0 +1,44 @@
+#![crate_name = "foo"]
+#![feature(doc_cfg)]
+
+// @has foo/index.html '//a[@id="feature-flags"]/@href' 'features.html'
+// @has foo/features.html '//h3[@id="feature.serde"]' 'serde'
+// @has foo/features.html '//h3[@id="feature.std"]' 'std'
+
+// @has foo/features.html '//a[@href="struct.Value.html"]' 'Value'
+// @!has - '//li[a[@href="struct.Value.html"]]/span'
+#[doc(cfg(feature = "serde"))]
+pub struct Value;
+
+pub struct Plain;
+
+impl Plain {
+    // @has foo/features.html '//a[@href="struct.Plain.html#method.to_value"]' 'Plain::to_value'
+    #[doc(cfg(feature = "serde"))]
+    pub fn to_value(&self) -> Value {
+        Value
+    }
+
+    pub fn new() -> Plain {
+        Plain
+    }
+}
+
+// @has foo/features.html '//a[@href="fn.read_file.html"]' 'read_file'
+// @has - '//li[a[@href="fn.read_file.html"]]/span[@class="stab portability"]' 'Unix'
+#[doc(cfg(all(feature = "std", unix)))]
+pub fn read_file() {}
+
+// @has foo/features.html '//a[@href="io/index.html"]' 'io'
+// @!has - '//a[@href="io/fn.write.html"]'
+// @has - '//a[@href="io/fn.serialize.html"]' 'io::serialize'
+#[doc(cfg(feature = "std"))]
+pub mod io {
+    pub fn write() {}
+
+    #[doc(cfg(feature = "serde"))]
+    pub fn serialize() {}
+}
+
+// @!has foo/features.html '//a[@href="fn.always.html"]'
+pub fn always() {}
@@ -0,0 +1,18 @@
+#![crate_name = "foo"]
+
+mod inner {
+    pub struct Inlined;
+
+    #[doc(no_inline)]
+    pub struct NotInlined;
+
+    #[doc(hidden)]
+    pub struct Hidden;
+}
+
+// @has foo/struct.Inlined.html
+// @!has foo/struct.NotInlined.html
+// @has foo/index.html '//code' 'pub use foo::inner::NotInlined;'
+// @!has foo/struct.Hidden.html
+// @!has foo/index.html 'Hidden'
+pub use inner::*;
@@ -0,0 +1,11 @@
+// compile-flags:-Z unstable-options --hash-static-files
+
+// @has hash_static_files/struct.SomeStruct.html
+// @matches - '"\.\./main-[0-9a-f]{16}\.js"'
+// @!matches - '"\.\./main\.js"'
+// @matches - '//link[@id="mainThemeStyle"]/@href' 'rustdoc-[0-9a-f]{16}\.css'
+// @matches - '"\.\./search-index\.js"'
+pub struct SomeStruct;
+
+// @has src/hash_static_files/hash-static-files.rs.html
+// @matches - '"\.\./\.\./source-script-[0-9a-f]{16}\.js"'
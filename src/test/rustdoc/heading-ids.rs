@@ -0,0 +1,37 @@
+#![crate_name = "foo"]
+
+// @has foo/struct.Foo.html
+// @has - '//h1[@id="examples"]/a[@href="#examples"]' 'Examples'
+// @has - '//h1[@id="examples-1"]/a[@href="#examples-1"]' 'Examples'
+// @has - '//h1[@id="fields-1"]/a[@href="#fields-1"]' 'Fields'
+// @has - '//h1[@id="section"]/a[@href="#section"]' '?!'
+// @has - '//*[@id="fields"]' 'Fields'
+/// # Examples
+/// # Examples
+/// # Fields
+/// # ?!
+pub struct Foo {
+    // @has - '//h1[@id="structfield.bar.examples"]' 'Examples'
+    // @has - '//h1[@id="structfield.bar.examples"]/a[@id="examples-2"]' 'Examples'
+    /// # Examples
+    pub bar: u8,
+}
+
+impl Foo {
+    // @has - '//h1[@id="method.new.examples"]' 'Examples'
+    // @has - '//h1[@id="method.new.examples-1"]' 'Examples'
+    // @has - '//h1[@id="method.new.examples"]/a[@id="examples-3"]' 'Examples'
+    // @has - '//h1[@id="method.new.examples-1"]/a[@id="examples-4"]' 'Examples'
+    /// # Examples
+    /// # Examples
+    pub fn new() -> Foo {
+        Foo { bar: 0 }
+    }
+}
+
+// @has foo/fn.foo.html '//h1[@id="examples"]' 'Examples'
+/// See [the examples](Foo#examples-1) and [the method's](Foo#method.new.examples), also known
+/// as [`examples-3`](Foo#examples-3).
+///
+/// # Examples
+pub fn foo() {}
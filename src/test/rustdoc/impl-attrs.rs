@@ -0,0 +1,24 @@
+#![feature(doc_cfg)]
+
+pub struct Foo;
+
+// @has impl_attrs/struct.Foo.html
+// @has - '//*[@id="method.old"]' 'pub fn old(&self)'
+// @has - '//*[@class="stab deprecated"]' 'Deprecated since 1.0.0: use new instead'
+#[deprecated(since = "1.0.0", note = "use new instead")]
+impl Foo {
+    pub fn old(&self) {}
+}
+
+// @has - '//*[@id="method.unix_only"]' 'pub fn unix_only(&self)'
+// @has - '//*[@class="stab portability"]' 'This is supported on Unix only.'
+#[doc(cfg(unix))]
+impl Foo {
+    pub fn unix_only(&self) {}
+}
+
+// @!has - '//*[@id="method.hidden"]' 'pub fn hidden(&self)'
+#[doc(hidden)]
+impl Foo {
+    pub fn hidden(&self) {}
+}
@@ -0,0 +1,33 @@
+#![crate_name = "foo"]
+
+use std::fmt::Debug;
+
+pub trait Trait {}
+
+pub struct Foo;
+pub struct Wrapper<T>(T);
+
+// @has foo/trait.Trait.html
+// @has - '//h2[@id="implementors"]/span[@class="count"]' '(2)'
+// @has - '//*[@id="implementors-list"]//code' 'impl Trait for Foo'
+// @has - '//*[@id="implementors-list"]//code' "impl<'a> Trait for &'a str"
+impl Trait for Foo {}
+impl<'a> Trait for &'a str {}
+
+// @has - '//h2[@id="generic-implementors"]' 'Generic implementors'
+// @has - '//h2[@id="generic-implementors"]/span[@class="count"]' '(1)'
+// @has - '//*[@id="generic-implementors-list"]//code' 'impl<T: Debug> Trait for Wrapper<T>'
+impl<T: Debug> Trait for Wrapper<T> {}
+
+// @has - '//h2[@id="synthetic-implementors"][@class="small-section-header hidden"]'
+impl Trait for Vec<Foo> {}
+
+pub trait Other {}
+
+// @has foo/trait.Other.html
+// @has - '//h2[@id="implementors"]/span[@class="count"]' '(0)'
+// @has - '//h2[@id="generic-implementors"][@class="small-section-header hidden"]'
+// @has - '//h2[@id="synthetic-implementors"]' 'Blanket implementors'
+// @has - '//h2[@id="synthetic-implementors"]/span[@class="count"]' '(1)'
+// @has - '//*[@id="synthetic-implementors-list"]//code' 'impl<T: Debug> Other for T'
+impl<T: Debug> Other for T {}
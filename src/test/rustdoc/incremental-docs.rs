@@ -0,0 +1,7 @@
+// compile-flags:-Z unstable-options --incremental-docs
+
+// @has incremental_docs/page-fingerprints.json
+// @has - '"incremental_docs/index.html"'
+// @has - '"incremental_docs/struct.SomeStruct.html"'
+// @has incremental_docs/struct.SomeStruct.html '//h1' 'Struct incremental_docs::SomeStruct'
+pub struct SomeStruct;
@@ -0,0 +1,15 @@
+pub trait Greet {
+    fn name(&self) -> String;
+
+    fn greet(&self) -> String {
+        format!("Hello, {}!", self.name())
+    }
+}
+
+pub struct World;
+
+impl Greet for World {
+    fn name(&self) -> String {
+        "world".to_string()
+    }
+}
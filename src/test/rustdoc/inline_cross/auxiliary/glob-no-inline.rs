@@ -0,0 +1,9 @@
+#![crate_name = "glob_source"]
+
+pub struct Inlined;
+
+#[doc(no_inline)]
+pub struct NotInlined;
+
+#[doc(hidden)]
+pub struct Hidden;
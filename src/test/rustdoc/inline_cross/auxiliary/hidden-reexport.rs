@@ -0,0 +1,8 @@
+#![crate_name = "hidden_source"]
+
+/// Only meant to be used through a facade.
+#[doc(hidden)]
+pub struct Internal;
+
+#[doc(hidden)]
+pub struct StaysHidden;
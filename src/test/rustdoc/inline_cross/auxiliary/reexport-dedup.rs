@@ -0,0 +1,6 @@
+/// The thing.
+pub struct Thing;
+
+impl Thing {
+    pub fn frobnicate(&self) {}
+}
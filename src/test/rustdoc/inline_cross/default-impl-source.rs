@@ -0,0 +1,15 @@
+// aux-build:default-impl-source.rs
+// build-aux-docs
+// ignore-cross-compile
+
+extern crate default_impl_source;
+
+// @has default_impl_source/trait.Greet.html
+// @has - '//details[@class="default-impl"]/pre' 'format!("Hello, {}!", self.name())'
+// @count - '//details[@class="default-impl"]' 1
+pub use default_impl_source::Greet;
+
+// The trait of this impl is not inlined, so its default implementations are not shown.
+// @has default_impl_source/struct.World.html
+// @!has - '//details[@class="default-impl"]'
+pub use default_impl_source::World;
@@ -0,0 +1,13 @@
+// aux-build:glob-no-inline.rs
+// build-aux-docs
+// ignore-cross-compile
+
+extern crate glob_source;
+
+// @has glob_no_inline/struct.Inlined.html
+// @!has glob_no_inline/struct.NotInlined.html
+// @has glob_no_inline/index.html '//code' 'pub use glob_source::NotInlined;'
+// @!has glob_no_inline/struct.Hidden.html
+// @!has glob_no_inline/index.html 'Hidden'
+#[doc(inline)]
+pub use glob_source::*;
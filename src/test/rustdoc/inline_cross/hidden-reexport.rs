@@ -0,0 +1,15 @@
+// aux-build:hidden-reexport.rs
+// build-aux-docs
+// ignore-cross-compile
+// compile-flags: -Z unstable-options --inline-hidden-reexports
+
+extern crate hidden_source;
+
+// @has hidden_reexport/struct.Internal.html
+// @has - '//*[@class="docblock"]' 'Only meant to be used through a facade.'
+pub use hidden_source::Internal;
+
+// @!has hidden_reexport/struct.StaysHidden.html
+// @!has hidden_reexport/index.html 'StaysHidden'
+#[doc(hidden)]
+pub use hidden_source::StaysHidden;
@@ -0,0 +1,23 @@
+// aux-build:reexport-dedup.rs
+// ignore-cross-compile
+
+#![crate_name = "foo"]
+
+extern crate reexport_dedup;
+
+// @has foo/struct.Thing.html
+// @has - '//*[@class="docblock"]' 'The thing.'
+// @has - '//*[@id="method.frobnicate"]' 'pub fn frobnicate(&self)'
+pub use reexport_dedup::Thing;
+
+pub mod prelude {
+    // @has foo/prelude/struct.Thing.html
+    // @has - '//h1' 'Re-export foo::prelude::Thing'
+    // @has - '//a[@class="canonical"]/@href' '../../foo/struct.Thing.html'
+    // @!has - '//*[@id="method.frobnicate"]' 'pub fn frobnicate(&self)'
+    pub use reexport_dedup::Thing;
+}
+
+// @count foo/all.html '//a[@href="struct.Thing.html"]' 1
+// @!has foo/all.html 'prelude/struct.Thing.html'
+// @has aliases.js '"foo::prelude::Thing"'
@@ -0,0 +1,42 @@
+// ignore-tidy-linelength
+
+#![crate_name = "foo"]
+
+// @has foo/struct.Point.html '//*[@id="structfield.x"]' 'x: i32'
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+// @has foo/enum.Shape.html '//*[@id="Square.v"]' 'Square'
+// @has foo/enum.Shape.html '//*[@id="variant.Circle.field.radius"]' 'radius'
+pub enum Shape {
+    Circle { radius: f64 },
+    Square(f64),
+}
+
+// @has foo/union.Bits.html '//*[@id="structfield.float"]' 'float: f32'
+pub union Bits {
+    pub int: u32,
+    pub float: f32,
+}
+
+pub type Alias = Shape;
+
+// @has foo/fn.links.html
+// @has - '//a/@href' '../foo/struct.Point.html#structfield.x'
+// @has - '//a/@href' '../foo/enum.Shape.html#Square.v'
+// @has - '//a/@href' '../foo/enum.Shape.html#variant.Circle.field.radius'
+// @has - '//a/@href' '../foo/union.Bits.html#structfield.float'
+// @has - '//a/@href' '../foo/enum.Shape.html#Circle.v'
+/// See [Point::x], [Shape::Square], [Shape::Circle::radius], [Bits::float] and
+/// [Alias::Circle].
+pub fn links() {}
+
+impl Point {
+    // @has foo/struct.Point.html '//a/@href' '../foo/struct.Point.html#structfield.y'
+    /// Returns [Self::y].
+    pub fn get_y(&self) -> i32 {
+        self.y
+    }
+}
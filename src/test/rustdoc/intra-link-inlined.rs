@@ -0,0 +1,22 @@
+// aux-build:intra-link-inlined.rs
+// build-aux-docs
+// ignore-cross-compile
+
+// The links in documentation inlined from another crate are resolved in the scope of the module
+// they were written in, while the ones of the re-export are resolved in the local one.
+
+#![crate_name = "outer"]
+#![deny(intra_doc_link_resolution_failure)]
+
+extern crate inner;
+
+// @has outer/struct.Widget.html
+// @has - '//a[@href="../inner/sub/struct.Builder.html"]' 'Builder'
+// @has - '//a[@href="../inner/fn.top_level.html"]' 'super::top_level'
+// @has - '//a[@href="../inner/struct.Root.html"]' 'crate::Root'
+// @has - '//a[@href="../inner/sub/struct.Gadget.html"]' 'Gadget'
+// @has - '//a[@href="../outer/struct.Local.html"]' 'Local'
+/// Also see [`Local`].
+pub use inner::sub::Widget;
+
+pub struct Local;
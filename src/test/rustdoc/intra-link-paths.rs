@@ -0,0 +1,14 @@
+#![crate_name = "foo"]
+#![deny(intra_doc_link_resolution_failure)]
+
+// @has foo/index.html
+// @has - '//a/@href' '../foo/struct.Foo.html'
+// @has - '//a/@href' 'struct.Vec.html#method.push'
+// @has - '//a/@href' '../foo/module/struct.Item.html'
+//! Links to [Foo], [`Vec::push`] and [crate::module::Item].
+
+pub struct Foo;
+
+pub mod module {
+    pub struct Item;
+}
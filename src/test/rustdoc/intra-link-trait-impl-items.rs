@@ -0,0 +1,31 @@
+#![crate_name = "foo"]
+#![deny(intra_doc_link_resolution_failure)]
+
+pub trait Shape {
+    const SIDES: usize;
+
+    fn area(&self) -> f64;
+
+    fn describe(&self) -> String {
+        String::new()
+    }
+}
+
+#[derive(Clone)]
+pub struct Square;
+
+impl Shape for Square {
+    const SIDES: usize = 4;
+
+    fn area(&self) -> f64 {
+        1.0
+    }
+}
+
+// @has foo/fn.links.html
+// @has - '//a/@href' '../foo/struct.Square.html#method.area'
+// @has - '//a/@href' '../foo/struct.Square.html#method.describe'
+// @has - '//a/@href' '../foo/struct.Square.html#associatedconstant.SIDES'
+// @has - '//a/@href' '../foo/struct.Square.html#method.clone'
+/// Links to [`Square::area`], [`Square::describe`], [`Square::SIDES`] and [`Square::clone`].
+pub fn links() {}
@@ -2,18 +2,23 @@
 pub struct Foo;
 
 impl Foo {
-    // @has - '//*[@id="examples"]//a' 'Examples'
-    // @has - '//*[@id="panics"]//a' 'Panics'
+    // @has - '//*[@id="method.bar.examples"]//a' 'Examples'
+    // @has - '//*[@id="method.bar.examples"]/a[@id="examples"]' 'Examples'
+    // @has - '//*[@id="method.bar.panics"]//a' 'Panics'
+    // @has - '//*[@id="method.bar.panics"]/a[@id="panics"]' 'Panics'
     /// # Examples
     /// # Panics
     pub fn bar() {}
 
-    // @has - '//*[@id="examples-1"]//a' 'Examples'
+    // @has - '//*[@id="method.bar_1.examples"]//a' 'Examples'
+    // @has - '//*[@id="method.bar_1.examples"]/a[@id="examples-1"]' 'Examples'
     /// # Examples
     pub fn bar_1() {}
 
-    // @has - '//*[@id="examples-2"]//a' 'Examples'
-    // @has - '//*[@id="panics-1"]//a' 'Panics'
+    // @has - '//*[@id="method.bar_2.examples"]//a' 'Examples'
+    // @has - '//*[@id="method.bar_2.examples"]/a[@id="examples-2"]' 'Examples'
+    // @has - '//*[@id="method.bar_2.panics"]//a' 'Panics'
+    // @has - '//*[@id="method.bar_2.panics"]/a[@id="panics-1"]' 'Panics'
     /// # Examples
     /// # Panics
     pub fn bar_2() {}
@@ -0,0 +1,19 @@
+// compile-flags: -Z unstable-options --item-metrics
+
+#![crate_name = "foo"]
+
+// @has foo/fn.documented.html '//*[@class="item-metrics"]' '1 min read'
+// @has - '//*[@class="item-metrics"]/@title' '7 words, 1 examples, signature complexity 3'
+/// Adds two numbers.
+///
+/// ```
+/// foo::documented(1, 2);
+/// ```
+pub fn documented(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+// @has foo/struct.Undocumented.html '//*[@class="item-metrics"]' 'undocumented'
+pub struct Undocumented;
+
+// @has foo/item-metrics.json
@@ -0,0 +1,23 @@
+// aux-build:jump-to-def-dep.rs
+// compile-flags: -Z unstable-options --generate-link-to-definition
+// compile-flags: --extern-html-root-url jump_to_def_dep=https://example.com/dep/0.1.0
+
+#![crate_name = "foo"]
+
+extern crate jump_to_def_dep;
+
+// @has src/foo/jump-to-def-extern.rs.html
+// @has - '//a[@href="https://example.com/dep/0.1.0/jump_to_def_dep/fn.make.html"]' 'make'
+// @has - '//a[@href="https://example.com/dep/0.1.0/jump_to_def_dep/struct.Bar.html"]' 'Bar'
+// @has - '//a[@href="https://example.com/dep/0.1.0/jump_to_def_dep/struct.Bar.html#method.new"]' \
+//   'new'
+// @has - '//a[@href="https://example.com/dep/0.1.0/jump_to_def_dep/enum.Kind.html#variant.First"]' \
+//   'First'
+
+use jump_to_def_dep::{Bar, Kind};
+
+pub fn use_them() {
+    let _ = jump_to_def_dep::make();
+    let _ = Bar::new();
+    let _ = Kind::First;
+}
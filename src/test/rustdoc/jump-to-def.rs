@@ -0,0 +1,29 @@
+// compile-flags: -Z unstable-options --generate-link-to-definition
+
+#![crate_name = "foo"]
+
+// @has src/foo/jump-to-def.rs.html
+// @has - '//a[@href="../../foo/struct.Foo.html"]' 'Foo'
+// @has - '//a[@href="../../foo/fn.use_them.html"]' 'use_them'
+// @has - '//a[@href="../../src/foo/jump-to-def.rs.html#16"]' 'new'
+// @has - '//a[@href="../../src/foo/jump-to-def.rs.html#17"]' 'bar'
+// @has - '//a[@href="../../src/foo/jump-to-def.rs.html#20"]' 'private'
+
+pub struct Foo;
+
+impl Foo {
+    pub fn new() -> Foo { Foo }
+    pub fn bar(&self) {}
+}
+
+fn private() {}
+
+pub fn use_them() {
+    let foo = Foo::new();
+    foo.bar();
+    private();
+}
+
+pub fn call() {
+    use_them();
+}
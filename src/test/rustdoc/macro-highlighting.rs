@@ -0,0 +1,27 @@
+#![feature(decl_macro)]
+
+#![crate_name = "foo"]
+
+// @has foo/macro.rules.html
+// @has - '//pre[@class="rust macro"]//span[@class="macro-nonterminal"]' '$e'
+// @has - '//pre[@class="rust macro"]//span[@class="macro-fragment"]' 'expr'
+// @has - '//pre[@class="rust macro"]//span[@class="macro-fragment"]' 'ident'
+#[macro_export]
+macro_rules! rules {
+    ($e:expr) => {};
+    ($($name:ident),*) => {};
+}
+
+// @has foo/macro.single.html '//pre[@class="rust macro"]' 'pub macro single($t:ty) {'
+// @has - '//pre[@class="rust macro"]//span[@class="macro-fragment"]' 'ty'
+pub macro single($t:ty) {
+    let _: $t;
+}
+
+// @has foo/macro.multi.html '//pre[@class="rust macro"]' 'pub macro multi {'
+// @has - '//pre[@class="rust macro"]' '() => { ... },'
+// @has - '//pre[@class="rust macro"]' '($p:pat) => { ... },'
+pub macro multi {
+    () => {},
+    ($p:pat) => {},
+}
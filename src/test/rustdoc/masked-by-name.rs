@@ -0,0 +1,21 @@
+// aux-build:masked.rs
+
+#![feature(doc_masked)]
+#![doc(masked(masked))]
+
+#![crate_name = "foo"]
+
+extern crate masked;
+
+// @!has 'foo/struct.MyStruct.html' 'MaskedTrait'
+// @!has 'foo/struct.MyStruct.html' 'masked_method'
+pub struct MyStruct;
+
+impl masked::MaskedTrait for MyStruct {
+    fn masked_method() {}
+}
+
+// @!has 'foo/trait.MyTrait.html' 'MaskedStruct'
+pub trait MyTrait {}
+
+impl MyTrait for masked::MaskedStruct {}
@@ -0,0 +1,19 @@
+// aux-build:masked.rs
+
+#![feature(doc_masked)]
+
+#![crate_name = "foo"]
+
+extern crate masked;
+
+// @has 'foo/index.html' '//code' 'pub use masked as shims;'
+#[doc(masked)]
+pub use masked as shims;
+
+// @!has 'foo/struct.MyStruct.html' 'MaskedTrait'
+// @!has 'foo/struct.MyStruct.html' 'masked_method'
+pub struct MyStruct;
+
+impl masked::MaskedTrait for MyStruct {
+    fn masked_method() {}
+}
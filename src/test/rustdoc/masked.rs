@@ -27,4 +27,8 @@ impl masked::MaskedTrait for MyStruct {
 // @!has 'foo/trait.MyTrait.html' 'MaskedStruct'
 pub trait MyTrait {}
 
+// @has 'foo/struct.MaskedStruct.html'
+// @!has 'search-index.js' 'MaskedStruct'
+pub use masked::MaskedStruct;
+
 impl MyTrait for masked::MaskedStruct {}
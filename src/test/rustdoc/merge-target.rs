@@ -0,0 +1,27 @@
+// compile-flags: -Z unstable-options --target x86_64-unknown-linux-gnu
+// compile-flags: --merge-target x86_64-pc-windows-msvc
+// only-x86_64
+
+#![feature(no_core)]
+#![no_core]
+
+// @has merge_target/struct.Everywhere.html
+// @count - '//*[@class="stab portability"]' 0
+pub struct Everywhere;
+
+// @has merge_target/struct.WindowsOnly.html
+// @has - '//*[@class="stab portability"]' 'This is supported on Windows only.'
+#[cfg(windows)]
+pub struct WindowsOnly;
+
+// @has merge_target/linux/index.html
+// @has - '//*[@class="stab portability"]' 'This is supported on Linux only.'
+// @has merge_target/linux/fn.epoll.html
+// @has - '//*[@class="stab portability"]' 'This is supported on Linux only.'
+#[cfg(target_os = "linux")]
+pub mod linux {
+    pub fn epoll() {}
+}
+
+// @has merge_target/index.html
+// @has - '//a[@href="struct.WindowsOnly.html"]' 'WindowsOnly'
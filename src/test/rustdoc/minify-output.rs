@@ -0,0 +1,21 @@
+// compile-flags: -Z unstable-options --minify-output
+
+#![crate_name = "foo"]
+
+// @has foo/fn.add.html '//noscript/style' '#main'
+// @!has - '//link[@href="../noscript.css"]'
+// @has - '//link[@id="themeStyle"]/@href' '../light.css'
+// @has - '//div[@class="docblock"]//code' 'a + b'
+// @has - '//div[@class="docblock"]//pre' 'let x = add(1, 2);'
+// @!has - 'comment'
+
+/// Returns `a + b`.
+///
+/// <!-- comment -->
+///
+/// ```
+/// let x = add(1, 2);
+/// ```
+pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
@@ -0,0 +1,23 @@
+#![crate_name = "foo"]
+#![feature(staged_api)]
+#![feature(doc_cfg)]
+#![stable(feature = "rust1", since = "1.0.0")]
+
+// @has foo/index.html
+// @has - '//*[@class="module-item"]//*[@class="stab unstable"]' 'Experimental (frobnicate)'
+// @has - '//*[@class="module-item"]//*[@class="stab unstable"]/@title' \
+//      'Unstable feature frobnicate, tracked in issue #1234'
+#[unstable(feature = "frobnicate", issue = "1234")]
+pub fn frobnicate() {}
+
+// @has - '//*[@class="module-item"]//*[@class="stab deprecated"]' 'Deprecated'
+// @has - '//*[@class="module-item"]//*[@class="stab deprecated"]/@title' \
+//      'Deprecated since 1.2.0: use `frobnicate` instead'
+#[stable(feature = "rust1", since = "1.0.0")]
+#[rustc_deprecated(since = "1.2.0", reason = "use `frobnicate` instead")]
+pub fn old_frobnicate() {}
+
+// @has - '//*[@class="module-item"]//*[@class="stab portability"]' 'Unix'
+#[stable(feature = "rust1", since = "1.0.0")]
+#[doc(cfg(unix))]
+pub fn unix_only() {}
@@ -0,0 +1,36 @@
+// compile-flags:-Z unstable-options --module-page-size 2
+
+#![crate_name = "foo"]
+
+//! The crate documentation.
+
+// @has foo/index.html '//div[@class="docblock"]' 'The crate documentation.'
+// @has - '//a[@href="fn.a.html"]' 'a'
+// @has - '//a[@href="fn.b.html"]' 'b'
+// @!has - '//a[@href="fn.c.html"]' 'c'
+// @has - '//nav[@class="module-pages"]/span[@class="current"]' '1'
+// @has - '//nav[@class="module-pages"]/a[@href="index-2.html"]' '2'
+// @has - '//nav[@class="module-pages"]/a[@href="index-3.html"]' '3'
+
+// @has foo/index-2.html '//title' 'foo (page 2) - Rust'
+// @!has - '//div[@class="docblock"]' 'The crate documentation.'
+// @!has - '//a[@href="fn.b.html"]' 'b'
+// @has - '//a[@href="fn.c.html"]' 'c'
+// @has - '//a[@href="fn.d.html"]' 'd'
+// @has - '//nav[@class="module-pages"]/a[@href="index.html"]' '1'
+
+// @has foo/index-3.html '//a[@href="fn.e.html"]' 'e'
+// @!has foo/index-4.html
+
+pub fn a() {}
+pub fn b() {}
+pub fn c() {}
+pub fn d() {}
+pub fn e() {}
+
+// @has foo/small/index.html '//a[@href="fn.f.html"]' 'f'
+// @!has - '//nav[@class="module-pages"]'
+// @!has foo/small/index-2.html
+pub mod small {
+    pub fn f() {}
+}
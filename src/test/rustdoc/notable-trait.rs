@@ -0,0 +1,31 @@
+#[doc(notable_trait)]
+pub trait Stream {
+    type Item;
+}
+
+pub struct Numbers;
+
+impl Stream for Numbers {
+    type Item = u32;
+}
+
+// @has notable_trait/fn.numbers.html
+// @has - '//code[@class="content"]' 'impl Stream for Numbers'
+// @has - '//code[@class="content"]' 'type Item = u32;'
+pub fn numbers() -> Numbers {
+    Numbers
+}
+
+// @has notable_trait/fn.numbers_mut.html
+// @has - '//code[@class="content"]' 'impl Stream for Numbers'
+pub fn numbers_mut(numbers: &mut Numbers) -> &mut Numbers {
+    numbers
+}
+
+pub struct NotAStream;
+
+// @has notable_trait/fn.not_a_stream.html
+// @!has - '//code[@class="content"]' 'impl'
+pub fn not_a_stream() -> NotAStream {
+    NotAStream
+}
@@ -0,0 +1,32 @@
+// ignore-tidy-linelength
+// compile-flags:-Z unstable-options --generate-print-page
+
+#![crate_name = "foo"]
+
+//! The crate.
+
+// @has foo/index.html '//a[@id="print-page"]/@href' 'print.html'
+// @has foo/print.html
+// @has - '//section[@class="print-item"][1]//h1[@class="fqn"]' 'Crate foo'
+// @has - '//section[@class="print-item"][2]//h1[@class="fqn"]' 'Function foo::after'
+// @has - '//section[@class="print-item"][2]//a[@href="bar/struct.Inner.html"]' 'Inner'
+// @has - '//section[@class="print-item"][3]//h1[@class="fqn"]' 'Module foo::bar'
+// @has - '//section[@class="print-item"][4]//h1[@class="fqn"]' 'Struct foo::bar::Inner'
+// @has - '//section[@class="print-item"][4]//a[@href="bar/struct.Inner.html#method.new"]' 'new'
+// @!has - '//script[@src="../implementors/foo/trait.Trait.js"]'
+
+/// A module.
+pub mod bar {
+    /// A struct.
+    pub struct Inner;
+
+    impl Inner {
+        /// See [`new`](#method.new).
+        pub fn new() -> Inner {
+            Inner
+        }
+    }
+}
+
+/// Takes an [`Inner`](bar::Inner).
+pub fn after(_: bar::Inner) {}
@@ -0,0 +1,14 @@
+// compile-flags: -Z unstable-options --search-hidden
+
+#![crate_name = "foo"]
+
+// @has foo/struct.Secret.html
+// @has search-index.js 'Secret'
+// @has - '"v":{"hidden":['
+#[doc(hidden)]
+pub struct Secret {
+    pub field: u8,
+}
+
+// @has search-index.js 'Visible'
+pub struct Visible;
@@ -0,0 +1,27 @@
+#![crate_name = "foo"]
+
+pub trait Shape {}
+
+// @has search-index.js '"impl-Shape"'
+// The auto traits implemented by every type are left out.
+// @!has - '"impl-Send"'
+pub struct Circle;
+
+impl Shape for Circle {}
+
+// A type implementing a generic trait several times is only listed once under it.
+// @has search-index.js '"impl-From%3Cu8%3E"'
+// @!has - '"impl-From%3Cu16%3E"'
+pub struct Meters(pub u32);
+
+impl From<u8> for Meters {
+    fn from(m: u8) -> Meters {
+        Meters(m.into())
+    }
+}
+
+impl From<u16> for Meters {
+    fn from(m: u16) -> Meters {
+        Meters(m.into())
+    }
+}
@@ -0,0 +1,11 @@
+// compile-flags: --document-private-items
+
+#![crate_name = "foo"]
+
+// @has search-index.js 'Internal'
+// @has - '"v":{"private":['
+mod inner {
+    pub struct Internal;
+}
+
+pub struct Exported;
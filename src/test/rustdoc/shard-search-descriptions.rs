@@ -0,0 +1,20 @@
+// compile-flags:-Z unstable-options --shard-search-descriptions
+
+#![crate_name = "foo"]
+
+//! The crate description.
+
+// @has search-index.js '"doc":"The crate description."'
+// @has - '"d":1000'
+// @!has - 'The struct description.'
+// @has search-desc/foo/0.js 'loadDescShard("foo",0,['
+// @has - '"The struct description."'
+// @has - '"The function description."'
+
+/// The struct description.
+pub struct Described;
+
+pub struct Undescribed;
+
+/// The function description.
+pub fn described() {}
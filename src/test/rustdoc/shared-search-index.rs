@@ -0,0 +1,13 @@
+// aux-build:all-item-types.rs
+// build-aux-docs
+// ignore-cross-compile
+
+#![crate_name = "foo"]
+
+// @has search-index.json '"all_item_types":{'
+// @has - '"foo":{'
+// @has search-index.js 'searchIndex["all_item_types"] = {'
+// @has - 'searchIndex["foo"] = {'
+// @has - 'FooStruct'
+// @has - 'SharedIndex'
+pub struct SharedIndex;
@@ -0,0 +1,29 @@
+#![crate_name = "foo"]
+
+#![feature(staged_api)]
+
+#![stable(feature = "rust1", since = "1.0.0")]
+
+#[stable(feature = "rust1", since = "1.0.0")]
+pub trait Tr {
+    #[stable(feature = "rust1", since = "1.0.0")]
+    fn old(&self);
+
+    #[unstable(feature = "tr_new", issue = "0")]
+    fn new(&self) {}
+}
+
+// @has foo/trait.Tr.html '//*[@class="stab unstable"]' 'tr_new'
+// @!has - '//*[@class="stab unstable"]' 'via trait'
+
+#[stable(feature = "rust1", since = "1.0.0")]
+pub struct S;
+
+// @count foo/struct.S.html '//*[@class="stab unstable"]' 1
+// @has - '//*[@class="stab unstable"]' 'tr_new'
+// @has - '//*[@class="stab unstable"]' 'Unstable via trait Tr.'
+#[stable(feature = "rust1", since = "1.0.0")]
+impl Tr for S {
+    fn old(&self) {}
+    fn new(&self) {}
+}
@@ -0,0 +1,14 @@
+#![crate_name = "foo"]
+
+// @has foo/trait.Greet.html
+// @has - '//details[@class="default-impl"]/summary' 'Default implementation'
+// @has - '//details[@class="default-impl"]/pre' 'fn greet(&self) -> String {'
+// @has - '//details[@class="default-impl"]/pre' 'format!("Hello, {}!", self.name())'
+// @count - '//details[@class="default-impl"]' 1
+pub trait Greet {
+    fn name(&self) -> String;
+
+    fn greet(&self) -> String {
+        format!("Hello, {}!", self.name())
+    }
+}
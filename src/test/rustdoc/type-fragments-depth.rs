@@ -0,0 +1,19 @@
+#![crate_name = "foo"]
+
+// The same type is rendered with links relative to the page it appears on.
+
+pub struct Wrapper<T>(pub T);
+
+// @has foo/fn.top.html '//a[@href="../foo/struct.Wrapper.html"]' 'Wrapper'
+pub fn top(_: Wrapper<u8>) -> Wrapper<u8> {
+    Wrapper(0)
+}
+
+pub mod inner {
+    use super::Wrapper;
+
+    // @has foo/inner/fn.nested.html '//a[@href="../../foo/struct.Wrapper.html"]' 'Wrapper'
+    pub fn nested(_: Wrapper<u8>) -> Wrapper<u8> {
+        Wrapper(0)
+    }
+}
@@ -0,0 +1,33 @@
+// compile-flags: -Z unstable-options --show-type-layout
+
+// @has type_layout/struct.Foo.html 'Layout'
+// @has - '//*[@class="docblock type-layout"]' 'completely unstable'
+// @has - '//*[@class="docblock type-layout"]' 'Size: 8 bytes'
+// @has - '//*[@class="docblock type-layout"]' 'Alignment: 4 bytes'
+// @has - '//*[@class="docblock type-layout"]/ul/li' 'b: 4 bytes'
+#[repr(C)]
+pub struct Foo {
+    pub a: u8,
+    pub b: u32,
+}
+
+// @has type_layout/struct.Generic.html 'Layout'
+// @has - '//*[@class="docblock type-layout"]' 'depends on its generic parameters'
+// @!has - '//*[@class="docblock type-layout"]' 'Size:'
+pub struct Generic<T>(pub T);
+
+// @has type_layout/enum.Bar.html 'Layout'
+// @has - '//*[@class="docblock type-layout"]' 'Size: 1 byte'
+// @has - '//*[@class="docblock type-layout"]' 'Niches: 254 invalid values'
+// @has - '//*[@class="docblock type-layout"]/ul/li' 'B: 1 byte'
+pub enum Bar {
+    A,
+    B,
+}
+
+// @has type_layout/union.Baz.html 'Layout'
+// @has - '//*[@class="docblock type-layout"]/ul/li' 'y: 0 bytes'
+pub union Baz {
+    pub x: u32,
+    pub y: u8,
+}
@@ -0,0 +1,9 @@
+// aux-build:assoc-opaque.rs
+// build-aux-docs
+// ignore-cross-compile
+
+extern crate assoc_opaque;
+
+// @has uncleanable_assoc_opaque/struct.Value.html
+// @has - '//h4[@id="associatedtype.Output"]/code' 'type Output = _'
+pub use assoc_opaque::Value;
@@ -0,0 +1,40 @@
+#![crate_name = "foo"]
+
+pub mod a {
+    pub struct Config;
+
+    impl Config {
+        // Mentions its own type, so it is not listed.
+        pub fn new() -> Config { Config }
+    }
+}
+
+pub trait Load {
+    fn load(config: &a::Config) -> Self;
+}
+
+pub struct Loader;
+
+impl Loader {
+    pub fn with(&self, _: Option<a::Config>) {}
+}
+
+impl Load for Loader {
+    // Trait impl items are not listed.
+    fn load(_: &a::Config) -> Self { Loader }
+}
+
+pub fn parse(_: &str) -> Vec<a::Config> { vec![] }
+
+pub fn unrelated() {}
+
+// @has foo/a/struct.Config.html '//h2[@id="used-by"]' 'Used by'
+// @has - '//details[@class="used-by"]/summary' '3 items'
+// @has - '//details[@class="used-by"]//a[@href="../../foo/fn.parse.html"]' 'foo::parse'
+// @has - '//details[@class="used-by"]//a[@href="../../foo/trait.Load.html#tymethod.load"]' \
+//     'foo::Load::load'
+// @has - '//details[@class="used-by"]//a[@href="../../foo/struct.Loader.html#method.with"]' \
+//     'foo::Loader::with'
+// @!has - '//details[@class="used-by"]' 'new'
+
+// @!has foo/struct.Loader.html '//h2[@id="used-by"]' 'Used by'
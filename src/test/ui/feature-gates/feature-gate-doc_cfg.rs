@@ -1,2 +1,6 @@
+#![doc(auto_cfg)] //~ ERROR: `#[doc(auto_cfg)]` is experimental
+#![doc(cfg_hide(test))] //~ ERROR: `#[doc(cfg_hide)]` is experimental
+#![doc(cfg_name(unix, "Unix-like"))] //~ ERROR: `#[doc(cfg_name)]` is experimental
+
 #[doc(cfg(unix))] //~ ERROR: `#[doc(cfg)]` is experimental
 fn main() {}
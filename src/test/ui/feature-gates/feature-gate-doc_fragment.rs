@@ -0,0 +1,5 @@
+#[doc(fragment = "safety")] //~ ERROR: `#[doc(fragment)]` is experimental
+/// The pointer must be valid for reads.
+pub struct Safety;
+
+fn main() {}